@@ -0,0 +1,188 @@
+// LRU cache for identical generation requests
+
+use crate::ai::{
+    config::ProviderType,
+    models::{GenerationRequest, GenerationResponse},
+};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+struct CacheEntry {
+    response: GenerationResponse,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+/// Bounded cache of generation responses, evicting the least recently used entry
+pub struct ResponseCache {
+    entries: HashMap<u64, CacheEntry>,
+    capacity: usize,
+    ttl: Duration,
+    clock: u64,
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            ttl,
+            clock: 0,
+        }
+    }
+
+    /// Cache key over provider, model, message content, generation params and tools.
+    /// Message timestamps and request metadata are deliberately excluded.
+    pub fn key(provider: &ProviderType, request: &GenerationRequest) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        provider.hash(&mut hasher);
+        request.model.hash(&mut hasher);
+        for message in &request.messages {
+            message.role.to_string().hash(&mut hasher);
+            message.content.hash(&mut hasher);
+            message.name.hash(&mut hasher);
+        }
+        // Params contain floats, so hash their serialized form
+        serde_json::to_string(&request.params)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        serde_json::to_string(&request.tools)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Look up a fresh entry, marking it as recently used
+    pub fn get(&mut self, key: u64) -> Option<GenerationResponse> {
+        self.clock += 1;
+        let clock = self.clock;
+        let ttl = self.ttl;
+
+        match self.entries.get_mut(&key) {
+            Some(entry) if entry.inserted_at.elapsed() <= ttl => {
+                entry.last_used = clock;
+                Some(entry.response.clone())
+            }
+            Some(_) => {
+                self.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, response: GenerationResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.clock += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                response,
+                inserted_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    /// Apply new size and TTL settings, dropping entries that no longer fit
+    pub fn configure(&mut self, capacity: usize, ttl: Duration) {
+        self.capacity = capacity;
+        self.ttl = ttl;
+        while self.entries.len() > self.capacity {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::{ChatMessage, GenerationParams};
+
+    fn request(content: &str) -> GenerationRequest {
+        GenerationRequest::new(vec![ChatMessage::user(content)], "model")
+    }
+
+    #[test]
+    fn test_key_ignores_message_timestamps() {
+        let first = request("Hello");
+        let mut second = request("Hello");
+        second.messages[0].timestamp = None;
+        assert_eq!(
+            ResponseCache::key(&ProviderType::Ollama, &first),
+            ResponseCache::key(&ProviderType::Ollama, &second)
+        );
+        assert_ne!(
+            ResponseCache::key(&ProviderType::Ollama, &first),
+            ResponseCache::key(&ProviderType::OpenAI, &first)
+        );
+    }
+
+    #[test]
+    fn test_key_differs_on_params() {
+        let first = request("Hello");
+        let second = request("Hello").with_params(GenerationParams {
+            temperature: Some(0.1),
+            ..GenerationParams::default()
+        });
+        assert_ne!(
+            ResponseCache::key(&ProviderType::Ollama, &first),
+            ResponseCache::key(&ProviderType::Ollama, &second)
+        );
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = ResponseCache::new(2, Duration::from_secs(60));
+        cache.insert(1, GenerationResponse::new("one", "model"));
+        cache.insert(2, GenerationResponse::new("two", "model"));
+        assert!(cache.get(1).is_some());
+
+        cache.insert(3, GenerationResponse::new("three", "model"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(1).is_some());
+        assert!(cache.get(2).is_none());
+        assert!(cache.get(3).is_some());
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let mut cache = ResponseCache::new(2, Duration::from_secs(0));
+        cache.insert(1, GenerationResponse::new("one", "model"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.len(), 0);
+    }
+}
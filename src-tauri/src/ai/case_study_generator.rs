@@ -1,9 +1,25 @@
 // Case study generation workflow with parameter validation and error handling
 
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::ai::{AIManager, GenerationRequest, models::ChatMessage};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use crate::ai::{AIManager, GenerationRequest, GenerationRequestBuilder, GenerationStyle, ModelUseCase};
+use crate::ai::models::{ChatMessage, GenerationParams};
 use crate::ai::errors::{AIError, Result};
+use crate::ai::prompts::{validate_system_prompt_override, RenderedPrompt};
+use crate::ai::sanitize::{sanitize_markdown, Sanitizer};
+use crate::case_study::export::{ANALYSIS_FRAMEWORK_FIELD, PROBLEM_STATEMENT_FIELD, SAMPLE_SOLUTION_FIELD};
+use crate::case_study::{CaseStudy, CaseStudyError, CaseStudyManager, UpdateCaseStudy};
+
+/// Event emitted to the frontend as each item of a batch generation finishes
+pub const BATCH_PROGRESS_EVENT: &str = "ai://case-study-batch/progress";
+
+/// Provider requests made per case study: content, summary and analysis framework
+const REQUESTS_PER_CASE_STUDY: u32 = 3;
 
 /// Parameters for case study generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +34,18 @@ pub struct CaseStudyGenerationParams {
     pub geographical_context: Option<String>,
     pub time_period: Option<String>,
     pub specific_focus_areas: Vec<String>,
+    /// Ask the model once to expand or condense content that misses `target_length`
+    #[serde(default)]
+    pub enforce_length: bool,
+    /// Replaces the template's system prompt (the author persona) for this call
+    #[serde(default)]
+    pub system_prompt_override: Option<String>,
+    /// Sampling preset for the main generation call
+    #[serde(default)]
+    pub style: GenerationStyle,
+    /// Explicit sampling parameters; any that are set take precedence over `style`
+    #[serde(default)]
+    pub generation_params: Option<GenerationParams>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +75,78 @@ pub struct GeneratedCaseStudy {
     pub key_learning_points: Vec<String>,
     pub suggested_analysis_framework: Option<String>,
     pub metadata: CaseStudyMetadata,
+    /// Whether the fields came from a structured JSON response or were parsed from text
+    #[serde(default)]
+    pub generation_path: GenerationPath,
+    /// Final word count compared against the requested target length
+    #[serde(default)]
+    pub length_check: LengthCheck,
+}
+
+impl GeneratedCaseStudy {
+    /// Strip disallowed HTML from every generated text field
+    pub fn sanitize(&mut self, sanitizer: &Sanitizer) {
+        sanitizer.clean_in_place(&mut self.title);
+        sanitizer.clean_in_place(&mut self.content);
+        sanitizer.clean_in_place(&mut self.summary);
+        sanitizer.clean_all(&mut self.key_learning_points);
+        sanitizer.clean_option(&mut self.suggested_analysis_framework);
+        sanitizer.clean_all(&mut self.metadata.decision_points);
+    }
+}
+
+/// Accepted deviation from `target_length`, as a fraction of the target
+const LENGTH_TOLERANCE: f64 = 0.2;
+
+/// Follow-up generation that rewrote content missing its target length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthAdjustment {
+    Expand,
+    Condense,
+}
+
+impl LengthAdjustment {
+    /// Adjustment needed to bring `word_count` within tolerance of `target_length`, if any
+    pub fn needed(word_count: u32, target_length: u32) -> Option<Self> {
+        let word_count = word_count as f64;
+        let target_length = target_length as f64;
+        if word_count < target_length * (1.0 - LENGTH_TOLERANCE) {
+            Some(LengthAdjustment::Expand)
+        } else if word_count > target_length * (1.0 + LENGTH_TOLERANCE) {
+            Some(LengthAdjustment::Condense)
+        } else {
+            None
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            LengthAdjustment::Expand => "Expand",
+            LengthAdjustment::Condense => "Condense",
+        }
+    }
+}
+
+/// How a generated case study's length compares to the requested target
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LengthCheck {
+    pub target_length: u32,
+    pub word_count: u32,
+    pub within_tolerance: bool,
+    /// Set when a follow-up generation rewrote the content
+    pub adjustment: Option<LengthAdjustment>,
+}
+
+/// How a generated case study's fields were produced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationPath {
+    /// The model returned a JSON object matching the case study schema
+    Structured,
+    /// Fields were extracted from free-form text
+    #[default]
+    TextParsing,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +159,153 @@ pub struct CaseStudyMetadata {
     pub decision_points: Vec<String>,
 }
 
+/// Section of a stored case study that can be regenerated on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStudySection {
+    Background,
+    ProblemStatement,
+    AnalysisFramework,
+    SampleSolution,
+    Content,
+}
+
+impl CaseStudySection {
+    /// Human-readable name used in prompts and version summaries
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseStudySection::Background => "background",
+            CaseStudySection::ProblemStatement => "problem statement",
+            CaseStudySection::AnalysisFramework => "analysis framework",
+            CaseStudySection::SampleSolution => "sample solution",
+            CaseStudySection::Content => "case narrative",
+        }
+    }
+
+    fn instructions(self) -> &'static str {
+        match self {
+            CaseStudySection::Background => "Write 2-3 paragraphs of company and industry background that set up the case without revealing its resolution.",
+            CaseStudySection::ProblemStatement => "Write a concise problem statement framing the central decision students must make.",
+            CaseStudySection::AnalysisFramework => "Recommend an analysis framework for this case and explain step by step how students should apply it.",
+            CaseStudySection::SampleSolution => "Write a model answer that analyses the case, weighs the options and justifies a recommendation.",
+            CaseStudySection::Content => "Rewrite the full case narrative in markdown, keeping the same company, industry and central decision.",
+        }
+    }
+
+    /// Custom metadata field holding the section, for sections stored there
+    fn custom_field(self) -> Option<&'static str> {
+        match self {
+            CaseStudySection::ProblemStatement => Some(PROBLEM_STATEMENT_FIELD),
+            CaseStudySection::AnalysisFramework => Some(ANALYSIS_FRAMEWORK_FIELD),
+            CaseStudySection::SampleSolution => Some(SAMPLE_SOLUTION_FIELD),
+            CaseStudySection::Background | CaseStudySection::Content => None,
+        }
+    }
+
+    /// Current text of the section, if any. Background is stored as the summary.
+    pub fn current_text(self, case_study: &CaseStudy) -> Option<String> {
+        match self {
+            CaseStudySection::Background => case_study.summary.clone(),
+            CaseStudySection::Content => Some(case_study.content.clone()),
+            _ => self.custom_field()
+                .and_then(|field| case_study.metadata.get_custom_field(field))
+                .and_then(|value| value.as_str())
+                .map(String::from),
+        }
+    }
+
+    /// Update that replaces only this section with `text`
+    fn update(self, case_study: &CaseStudy, text: String) -> UpdateCaseStudy {
+        match self {
+            CaseStudySection::Background => UpdateCaseStudy { summary: Some(text), ..Default::default() },
+            CaseStudySection::Content => UpdateCaseStudy { content: Some(text), ..Default::default() },
+            _ => {
+                let mut metadata = case_study.metadata.clone();
+                if let Some(field) = self.custom_field() {
+                    metadata.add_custom_field(field.to_string(), serde_json::json!(text));
+                }
+                UpdateCaseStudy { metadata: Some(metadata), ..Default::default() }
+            }
+        }
+    }
+}
+
+/// Progress payload sent as each batch item completes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub index: usize,
+    pub completed: usize,
+    pub total: usize,
+    pub succeeded: bool,
+    pub error: Option<String>,
+}
+
+/// Receiver for batch generation progress updates
+pub trait BatchProgressSink: Send + Sync {
+    fn report(&self, progress: &BatchProgress);
+}
+
+impl BatchProgressSink for AppHandle {
+    fn report(&self, progress: &BatchProgress) {
+        if let Err(e) = self.emit(BATCH_PROGRESS_EVENT, progress) {
+            tracing::warn!("Failed to emit batch progress: {}", e);
+        }
+    }
+}
+
+/// Sink that discards batch progress updates
+pub struct NoopBatchProgress;
+
+impl BatchProgressSink for NoopBatchProgress {
+    fn report(&self, _progress: &BatchProgress) {}
+}
+
+/// Outcome of one batch item, in a shape the frontend can consume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub case_study: Option<GeneratedCaseStudy>,
+    pub error: Option<String>,
+}
+
+impl BatchItemResult {
+    pub fn new(index: usize, result: Result<GeneratedCaseStudy>) -> Self {
+        match result {
+            Ok(case_study) => Self { index, case_study: Some(case_study), error: None },
+            Err(e) => Self { index, case_study: None, error: Some(e.to_string()) },
+        }
+    }
+}
+
+/// Spaces out the start of generations so a batch stays under the provider's request rate
+struct RateGate {
+    interval: Option<Duration>,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateGate {
+    fn new(interval: Option<Duration>) -> Self {
+        Self { interval, next_slot: Mutex::new(None) }
+    }
+
+    async fn wait(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = match *next_slot {
+                Some(next) if next > now => next,
+                _ => now,
+            };
+            *next_slot = Some(slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
 /// Case study generation service
 pub struct CaseStudyGenerator {
     ai_manager: AIManager,
@@ -69,22 +316,85 @@ impl CaseStudyGenerator {
         Self { ai_manager }
     }
 
-    /// Generate a complete case study with validation
+    /// Generate a complete case study with validation.
+    /// Models that support JSON output are asked for a structured response; others, and
+    /// structured responses that turn out not to be JSON at all, go through text parsing.
+    /// Disallowed HTML is stripped from the result before it is returned.
     pub async fn generate_case_study(&self, params: CaseStudyGenerationParams) -> Result<GeneratedCaseStudy> {
+        let mut case_study = self.generate_unsanitized(params).await?;
+        let config = self.ai_manager.get_config().await.sanitization;
+        case_study.sanitize(&Sanitizer::new(&config));
+        Ok(case_study)
+    }
+
+    async fn generate_unsanitized(&self, params: CaseStudyGenerationParams) -> Result<GeneratedCaseStudy> {
         // Validate parameters
         self.validate_parameters(&params)?;
-        
+
+        let model = self.default_model().await?;
+        if self.ai_manager.model_supports_format(&model, "json").await {
+            let text = self.generate_structured_content(&params, &model).await?;
+            match parse_structured_case_study(&text)? {
+                Some(mut structured) => {
+                    let (content, length_check) = self.enforce_length(structured.content, &params).await?;
+                    structured.content = content;
+                    let mut case_study = self.assemble_structured(structured, &params);
+                    case_study.length_check = length_check;
+                    return Ok(case_study);
+                }
+                None => {
+                    tracing::warn!("Model {} did not return JSON, falling back to text parsing", model);
+                    return self.assemble_from_text(text, &params).await;
+                }
+            }
+        }
+
         // Generate the main case study content
         let content = self.generate_content(&params).await?;
-        
+        self.assemble_from_text(content, &params).await
+    }
+
+    /// Regenerate one section of a stored case study, using the rest of the case study as
+    /// context. Only that section changes, and the update is recorded as a new version.
+    pub async fn regenerate_section(
+        &self,
+        case_studies: &CaseStudyManager,
+        case_study_id: &str,
+        section: CaseStudySection,
+    ) -> Result<CaseStudy> {
+        let case_study = case_studies.get_case_study(case_study_id).await?
+            .ok_or_else(|| CaseStudyError::NotFound(case_study_id.to_string()))?;
+
+        let messages = vec![
+            ChatMessage::system("You are an expert business case study author revising one section of an existing case study. Reply with the new section text only."),
+            ChatMessage::user(section_prompt(&case_study, section)),
+        ];
+        let request = GenerationRequest::new(messages, self.default_model().await?);
+        let text = self.ai_manager.generate(request).await?.content.trim().to_string();
+        let config = self.ai_manager.get_config().await.sanitization;
+        let text = sanitize_markdown(&text, &config);
+        if text.trim().is_empty() {
+            return Err(AIError::ParsingError(format!("Model returned an empty {}", section.label())));
+        }
+
+        let update = section.update(&case_study, text);
+        let summary = format!("Regenerated {}", section.label());
+        let updated = case_studies.update_case_study_with_summary(case_study_id, update, &summary).await?;
+        updated.ok_or_else(|| CaseStudyError::NotFound(case_study_id.to_string()).into())
+    }
+
+    /// Build a case study from free-form content, asking the model for the summary and framework
+    async fn assemble_from_text(&self, content: String, params: &CaseStudyGenerationParams) -> Result<GeneratedCaseStudy> {
+        let (content, length_check) = self.enforce_length(content, params).await?;
+
         // Extract metadata from the generated content
-        let metadata = self.extract_metadata(&content, &params);
+        let metadata = self.extract_metadata(&content, params);
         
         // Generate additional components
         let title = self.extract_title(&content);
         let summary = self.generate_summary(&content).await?;
-        let learning_points = self.extract_learning_points(&content, &params);
-        let analysis_framework = self.suggest_analysis_framework(&params).await?;
+        let learning_points = self.extract_learning_points(&content, params);
+        let analysis_framework = self.suggest_analysis_framework(params).await?;
         
         Ok(GeneratedCaseStudy {
             title,
@@ -93,61 +403,170 @@ impl CaseStudyGenerator {
             key_learning_points: learning_points,
             suggested_analysis_framework: Some(analysis_framework),
             metadata,
+            generation_path: GenerationPath::TextParsing,
+            length_check,
         })
     }
 
-    /// Validate generation parameters
-    fn validate_parameters(&self, params: &CaseStudyGenerationParams) -> Result<()> {
-        // Industry validation
-        if params.industry.trim().is_empty() {
-            return Err(AIError::ValidationError("Industry cannot be empty".to_string()));
-        }
-        if params.industry.len() > 100 {
-            return Err(AIError::ValidationError("Industry name too long (max 100 characters)".to_string()));
+    /// Build a case study from a validated structured response. Counts are recomputed
+    /// from the content rather than trusted from the model.
+    fn assemble_structured(&self, structured: StructuredCaseStudy, params: &CaseStudyGenerationParams) -> GeneratedCaseStudy {
+        let mut metadata = self.extract_metadata(&structured.content, params);
+        metadata.primary_business_functions = structured.metadata.primary_business_functions;
+        metadata.key_stakeholders = structured.metadata.key_stakeholders;
+        metadata.decision_points = structured.metadata.decision_points;
+        if let Some(score) = structured.metadata.complexity_score {
+            metadata.complexity_score = score;
         }
 
-        // Duration validation
-        if params.duration_minutes < 5 {
-            return Err(AIError::ValidationError("Duration must be at least 5 minutes".to_string()));
-        }
-        if params.duration_minutes > 480 {
-            return Err(AIError::ValidationError("Duration cannot exceed 8 hours".to_string()));
+        GeneratedCaseStudy {
+            title: structured.title,
+            content: structured.content,
+            summary: structured.summary,
+            key_learning_points: structured.key_learning_points,
+            suggested_analysis_framework: structured.suggested_analysis_framework,
+            metadata,
+            generation_path: GenerationPath::Structured,
+            length_check: LengthCheck::default(),
         }
+    }
 
-        // Learning objectives validation
-        if params.learning_objectives.is_empty() {
-            return Err(AIError::ValidationError("At least one learning objective is required".to_string()));
-        }
-        if params.learning_objectives.len() > 10 {
-            return Err(AIError::ValidationError("Too many learning objectives (max 10)".to_string()));
-        }
-        for objective in &params.learning_objectives {
-            if objective.trim().is_empty() {
-                return Err(AIError::ValidationError("Learning objectives cannot be empty".to_string()));
+    /// Compare the content's word count with `target_length`. When it is out of band and
+    /// `enforce_length` is set, ask the model once to expand or condense it.
+    async fn enforce_length(&self, content: String, params: &CaseStudyGenerationParams) -> Result<(String, LengthCheck)> {
+        let needed = LengthAdjustment::needed(count_words(&content), params.target_length);
+        let (content, adjustment) = match needed {
+            Some(adjustment) if params.enforce_length => {
+                let revised = self.adjust_length(&content, params.target_length, adjustment).await?;
+                (revised, Some(adjustment))
             }
-            if objective.len() > 200 {
-                return Err(AIError::ValidationError("Learning objective too long (max 200 characters)".to_string()));
-            }
-        }
+            _ => (content, None),
+        };
 
-        // Target length validation
-        if params.target_length < 200 {
-            return Err(AIError::ValidationError("Target length must be at least 200 words".to_string()));
-        }
-        if params.target_length > 5000 {
-            return Err(AIError::ValidationError("Target length cannot exceed 5000 words".to_string()));
+        let word_count = count_words(&content);
+        Ok((content, LengthCheck {
+            target_length: params.target_length,
+            word_count,
+            within_tolerance: LengthAdjustment::needed(word_count, params.target_length).is_none(),
+            adjustment,
+        }))
+    }
+
+    /// Rewrite the content towards `target_length` words
+    async fn adjust_length(&self, content: &str, target_length: u32, adjustment: LengthAdjustment) -> Result<String> {
+        let messages = vec![
+            ChatMessage::system("You are an expert business case study author revising the length of a case study. Reply with the revised case study only, in markdown."),
+            ChatMessage::user(format!(
+                "{} this case study to about {} words; it is currently {} words. Keep the company, facts, characters and decision point unchanged.\n\n{}",
+                adjustment.verb(),
+                target_length,
+                count_words(content),
+                content
+            )),
+        ];
+        let request = GenerationRequest::new(messages, self.default_model().await?);
+        let revised = self.ai_manager.generate(request).await?.content.trim().to_string();
+        if revised.is_empty() {
+            return Err(AIError::ParsingError("Model returned an empty case study when adjusting its length".to_string()));
         }
+        Ok(revised)
+    }
+
+    /// Generate several case studies with at most `concurrency` in flight at once.
+    /// Each item gets its own result, in input order, so one failure doesn't sink the batch.
+    /// Starts are spaced to respect the active provider's requests-per-minute limit.
+    pub async fn generate_case_studies_batch(
+        &self,
+        params: Vec<CaseStudyGenerationParams>,
+        concurrency: usize,
+        progress: &dyn BatchProgressSink,
+    ) -> Vec<Result<GeneratedCaseStudy>> {
+        let total = params.len();
+        let gate = RateGate::new(
+            self.ai_manager.request_interval().await.map(|i| i * REQUESTS_PER_CASE_STUDY),
+        );
+        let gate = &gate;
+
+        let mut completed = 0;
+        let mut results: Vec<(usize, Result<GeneratedCaseStudy>)> = stream::iter(params.into_iter().enumerate())
+            .map(|(index, params)| async move {
+                gate.wait().await;
+                (index, self.generate_case_study(params).await)
+            })
+            .buffer_unordered(concurrency.max(1))
+            .inspect(|(index, result)| {
+                completed += 1;
+                progress.report(&BatchProgress {
+                    index: *index,
+                    completed,
+                    total,
+                    succeeded: result.is_ok(),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                });
+            })
+            .collect()
+            .await;
 
-        // Focus areas validation
-        if params.specific_focus_areas.len() > 5 {
-            return Err(AIError::ValidationError("Too many focus areas (max 5)".to_string()));
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Validate generation parameters
+    fn validate_parameters(&self, params: &CaseStudyGenerationParams) -> Result<()> {
+        if let Some(error) = case_study_param_errors(params).into_iter().next() {
+            return Err(AIError::ValidationError(error));
         }
 
+        validate_system_prompt_override(params.system_prompt_override.as_deref())?;
+
         Ok(())
     }
 
     /// Generate the main case study content
     async fn generate_content(&self, params: &CaseStudyGenerationParams) -> Result<String> {
+        let messages = self.content_messages(params)?;
+        let request = self.ai_manager.build_request(content_request(messages, params)).await?;
+
+        let response = self.ai_manager.generate(request).await?;
+        Ok(response.content)
+    }
+
+    /// Generate the whole case study as a JSON object matching `structured_output_schema`
+    async fn generate_structured_content(&self, params: &CaseStudyGenerationParams, model: &str) -> Result<String> {
+        let mut messages = self.content_messages(params)?;
+        messages.push(ChatMessage::user(format!(
+            "Return the case study as a single JSON object, with no surrounding text, matching this JSON schema. \
+             Put the full case study text in \"content\" as markdown.\n\n{}",
+            structured_output_schema()
+        )));
+
+        let request = self.ai_manager.build_request(content_request(messages, params).model(model).json_output()).await?;
+        let response = self.ai_manager.generate(request).await?;
+        Ok(response.content)
+    }
+
+    /// Default model of the configured default provider
+    async fn default_model(&self) -> Result<String> {
+        let config = self.ai_manager.get_config().await;
+        config.providers.get(&config.default_provider)
+            .map(|provider_config| provider_config.default_model.clone())
+            .ok_or_else(|| AIError::ConfigurationError("Default provider not configured".to_string()))
+    }
+
+    /// Render the case study prompt for the given parameters
+    fn content_messages(&self, params: &CaseStudyGenerationParams) -> Result<Vec<ChatMessage>> {
+        let rendered = self.render_content_prompt(params)?;
+
+        let mut messages = Vec::new();
+        if let Some(system) = rendered.system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(rendered.user_prompt));
+        Ok(messages)
+    }
+
+    /// Render the case study template, applying any system prompt override
+    fn render_content_prompt(&self, params: &CaseStudyGenerationParams) -> Result<RenderedPrompt> {
         let prompt_manager = self.ai_manager.get_prompt_manager();
         
         // Prepare template variables
@@ -175,25 +594,8 @@ impl CaseStudyGenerator {
             variables.insert("focus_areas".to_string(), serde_json::json!(params.specific_focus_areas.join(", ")));
         }
 
-        // Render the template
         let rendered = prompt_manager.render_template("case_study_generation", &variables)?;
-
-        // Create generation request
-        let mut messages = Vec::new();
-        if let Some(system) = rendered.system_prompt {
-            messages.push(ChatMessage::system(system));
-        }
-        messages.push(ChatMessage::user(rendered.user_prompt));
-
-        let config = self.ai_manager.get_config().await;
-        let default_provider = &config.default_provider;
-        let provider_config = config.providers.get(default_provider)
-            .ok_or_else(|| AIError::ConfigurationError("Default provider not configured".to_string()))?;
-        
-        let request = GenerationRequest::new(messages, provider_config.default_model.clone());
-
-        let response = self.ai_manager.generate(request).await?;
-        Ok(response.content)
+        Ok(rendered.with_system_prompt_override(params.system_prompt_override.as_deref()))
     }
 
     /// Generate a summary of the case study
@@ -377,10 +779,252 @@ impl Default for CaseStudyGenerationParams {
             geographical_context: None,
             time_period: None,
             specific_focus_areas: vec![],
+            enforce_length: false,
+            system_prompt_override: None,
+            style: GenerationStyle::default(),
+            generation_params: None,
         }
     }
 }
 
+/// Problems with case study generation parameters, in the order they're checked
+pub fn case_study_param_errors(params: &CaseStudyGenerationParams) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    // Industry validation
+    if params.industry.trim().is_empty() {
+        errors.push("Industry cannot be empty".to_string());
+    }
+    if params.industry.len() > 100 {
+        errors.push("Industry name too long (max 100 characters)".to_string());
+    }
+
+    // Duration validation
+    if params.duration_minutes < 5 {
+        errors.push("Duration must be at least 5 minutes".to_string());
+    }
+    if params.duration_minutes > 480 {
+        errors.push("Duration cannot exceed 8 hours".to_string());
+    }
+
+    // Learning objectives validation
+    if params.learning_objectives.is_empty() {
+        errors.push("At least one learning objective is required".to_string());
+    }
+    if params.learning_objectives.len() > 10 {
+        errors.push("Too many learning objectives (max 10)".to_string());
+    }
+    for objective in &params.learning_objectives {
+        if objective.trim().is_empty() {
+            errors.push("Learning objectives cannot be empty".to_string());
+            break;
+        }
+        if objective.len() > 200 {
+            errors.push("Learning objective too long (max 200 characters)".to_string());
+            break;
+        }
+    }
+
+    // Target length validation
+    if params.target_length < 200 {
+        errors.push("Target length must be at least 200 words".to_string());
+    }
+    if params.target_length > 5000 {
+        errors.push("Target length cannot exceed 5000 words".to_string());
+    }
+
+    // Focus areas validation
+    if params.specific_focus_areas.len() > 5 {
+        errors.push("Too many focus areas (max 5)".to_string());
+    }
+
+    errors
+}
+
+/// Request builder for the main content call, applying the params' style and explicit overrides
+fn content_request(messages: Vec<ChatMessage>, params: &CaseStudyGenerationParams) -> GenerationRequestBuilder {
+    let builder = GenerationRequestBuilder::new(messages)
+        .use_case(ModelUseCase::CaseStudyGeneration)
+        .style(params.style);
+    match &params.generation_params {
+        Some(overrides) => builder.params(overrides.clone()),
+        None => builder,
+    }
+}
+
+fn count_words(text: &str) -> u32 {
+    text.split_whitespace().count() as u32
+}
+
+/// Prompt asking for a replacement `section`, with the other sections as context
+fn section_prompt(case_study: &CaseStudy, section: CaseStudySection) -> String {
+    let mut prompt = format!(
+        "Case study: {}\nIndustry: {}\nDifficulty: {}\nLearning objectives: {}\n",
+        case_study.title,
+        case_study.industry,
+        case_study.difficulty_level,
+        case_study.learning_objectives.join(", ")
+    );
+
+    let context_sections = [
+        CaseStudySection::Background,
+        CaseStudySection::ProblemStatement,
+        CaseStudySection::Content,
+        CaseStudySection::AnalysisFramework,
+        CaseStudySection::SampleSolution,
+    ];
+    for other in context_sections.into_iter().filter(|s| *s != section) {
+        if let Some(text) = other.current_text(case_study).filter(|t| !t.trim().is_empty()) {
+            prompt.push_str(&format!("\n## Current {}\n{}\n", other.label(), text.trim()));
+        }
+    }
+    if let Some(previous) = section.current_text(case_study).filter(|t| !t.trim().is_empty()) {
+        prompt.push_str(&format!("\n## {} to replace\n{}\n", section.label(), previous.trim()));
+    }
+
+    prompt.push_str(&format!("\nWrite a new {}. {}", section.label(), section.instructions()));
+    prompt
+}
+
+/// Shape of a JSON value expected in a structured case study response
+#[derive(Debug, Clone, Copy)]
+enum FieldKind {
+    Text,
+    TextList,
+    OptionalText,
+    OptionalNumber,
+}
+
+impl FieldKind {
+    fn schema(self) -> serde_json::Value {
+        match self {
+            FieldKind::Text => serde_json::json!({ "type": "string", "minLength": 1 }),
+            FieldKind::TextList => serde_json::json!({ "type": "array", "items": { "type": "string" } }),
+            FieldKind::OptionalText => serde_json::json!({ "type": ["string", "null"] }),
+            FieldKind::OptionalNumber => serde_json::json!({ "type": ["number", "null"] }),
+        }
+    }
+
+    fn is_required(self) -> bool {
+        matches!(self, FieldKind::Text | FieldKind::TextList)
+    }
+
+    fn matches(self, value: Option<&serde_json::Value>) -> bool {
+        use serde_json::Value;
+        match (self, value) {
+            (FieldKind::Text, Some(Value::String(text))) => !text.trim().is_empty(),
+            (FieldKind::TextList, Some(Value::Array(items))) => items.iter().all(Value::is_string),
+            (FieldKind::OptionalText, None | Some(Value::Null | Value::String(_))) => true,
+            (FieldKind::OptionalNumber, None | Some(Value::Null | Value::Number(_))) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Top-level fields of a structured case study response
+const STRUCTURED_FIELDS: &[(&str, FieldKind)] = &[
+    ("title", FieldKind::Text),
+    ("content", FieldKind::Text),
+    ("summary", FieldKind::Text),
+    ("key_learning_points", FieldKind::TextList),
+    ("suggested_analysis_framework", FieldKind::OptionalText),
+];
+
+/// Fields of the `metadata` object; counts are computed locally so aren't requested
+const STRUCTURED_METADATA_FIELDS: &[(&str, FieldKind)] = &[
+    ("primary_business_functions", FieldKind::TextList),
+    ("key_stakeholders", FieldKind::TextList),
+    ("decision_points", FieldKind::TextList),
+    ("complexity_score", FieldKind::OptionalNumber),
+];
+
+/// Case study as returned by a model in structured output mode
+#[derive(Debug, Deserialize)]
+struct StructuredCaseStudy {
+    title: String,
+    content: String,
+    summary: String,
+    key_learning_points: Vec<String>,
+    suggested_analysis_framework: Option<String>,
+    metadata: StructuredMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct StructuredMetadata {
+    primary_business_functions: Vec<String>,
+    key_stakeholders: Vec<String>,
+    decision_points: Vec<String>,
+    complexity_score: Option<f32>,
+}
+
+fn object_schema(fields: &[(&str, FieldKind)]) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = fields.iter()
+        .map(|(name, kind)| (name.to_string(), kind.schema()))
+        .collect();
+    let required: Vec<&str> = fields.iter()
+        .filter(|(_, kind)| kind.is_required())
+        .map(|(name, _)| *name)
+        .collect();
+    serde_json::json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// JSON schema a structured case study response must match
+pub fn structured_output_schema() -> serde_json::Value {
+    let mut schema = object_schema(STRUCTURED_FIELDS);
+    schema["properties"]["metadata"] = object_schema(STRUCTURED_METADATA_FIELDS);
+    if let Some(required) = schema["required"].as_array_mut() {
+        required.push(serde_json::json!("metadata"));
+    }
+    schema
+}
+
+/// Names of fields in `object` that are missing or don't match their expected shape
+fn invalid_fields(object: &serde_json::Map<String, serde_json::Value>, fields: &[(&str, FieldKind)], prefix: &str) -> Vec<String> {
+    fields.iter()
+        .filter(|(name, kind)| !kind.matches(object.get(*name)))
+        .map(|(name, _)| format!("{}{}", prefix, name))
+        .collect()
+}
+
+/// Parse a structured response. Returns `Ok(None)` when the text isn't JSON at all, so the
+/// caller can fall back to text parsing, and an error when it is JSON but incomplete or
+/// doesn't match `structured_output_schema`.
+fn parse_structured_case_study(text: &str) -> Result<Option<StructuredCaseStudy>> {
+    let trimmed = text.trim();
+    // Models sometimes wrap JSON in a markdown code fence despite instructions
+    let json = trimmed.strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|inner| inner.trim_end().strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+
+    let value: serde_json::Value = match serde_json::from_str(json) {
+        Ok(value) => value,
+        // A truncated object means the model tried and failed, which text parsing can't fix
+        Err(e) if e.is_eof() && json.starts_with('{') => {
+            return Err(AIError::ParsingError(format!("Structured case study response is incomplete JSON: {}", e)));
+        }
+        Err(_) => return Ok(None),
+    };
+    let Some(object) = value.as_object() else {
+        return Ok(None);
+    };
+
+    let mut invalid = invalid_fields(object, STRUCTURED_FIELDS, "");
+    match object.get("metadata").and_then(|m| m.as_object()) {
+        Some(metadata) => invalid.extend(invalid_fields(metadata, STRUCTURED_METADATA_FIELDS, "metadata.")),
+        None => invalid.push("metadata".to_string()),
+    }
+    if !invalid.is_empty() {
+        return Err(AIError::ParsingError(format!(
+            "Structured case study response is missing or has invalid fields: {}",
+            invalid.join(", ")
+        )));
+    }
+
+    Ok(Some(serde_json::from_value(value)?))
+}
+
 impl std::fmt::Display for DifficultyLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -401,4 +1045,374 @@ impl std::fmt::Display for CompanySize {
             CompanySize::Enterprise => write!(f, "enterprise"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ProviderType;
+    use crate::ai::providers::mock::MockProvider;
+    use crate::database::DatabaseManager;
+    use std::sync::atomic::Ordering;
+
+    async fn test_generator(provider: MockProvider) -> CaseStudyGenerator {
+        let database = DatabaseManager::in_memory().await.unwrap();
+        let manager = AIManager::detached(database.pool().clone());
+        manager.set_active_provider(Box::new(provider)).await;
+        manager.create_case_study_generator()
+    }
+
+    fn params(industry: &str) -> CaseStudyGenerationParams {
+        CaseStudyGenerationParams {
+            industry: industry.to_string(),
+            difficulty_level: DifficultyLevel::Intermediate,
+            duration_minutes: 60,
+            learning_objectives: vec!["Strategic analysis".to_string()],
+            company_size: CompanySize::Medium,
+            target_length: 800,
+            additional_requirements: None,
+            geographical_context: None,
+            time_period: None,
+            specific_focus_areas: vec![],
+            enforce_length: false,
+            system_prompt_override: None,
+            style: GenerationStyle::default(),
+            generation_params: None,
+        }
+    }
+
+    /// Register the default provider's model as supporting JSON output
+    async fn enable_json_mode(generator: &CaseStudyGenerator) {
+        let model_id = generator.default_model().await.unwrap();
+        let registry = generator.ai_manager.get_model_config_manager().await;
+        let mut registry = registry.write().await;
+        let mut model = registry.get_model_config("gpt-4-turbo").unwrap().clone();
+        assert!(model.capabilities.supported_formats.contains(&"json".to_string()));
+        model.id = model_id;
+        registry.add_model(model);
+    }
+
+    fn structured_response() -> serde_json::Value {
+        serde_json::json!({
+            "title": "Solis Energy Enters Brazil",
+            "content": "# Solis Energy Enters Brazil\n\nThe CEO must decide how to enter the Brazilian solar market.",
+            "summary": "A solar manufacturer weighs a joint venture against a greenfield entry.",
+            "key_learning_points": ["Market entry modes", "Risk assessment"],
+            "suggested_analysis_framework": "Porter's Five Forces",
+            "metadata": {
+                "primary_business_functions": ["Strategy"],
+                "key_stakeholders": ["CEO", "Board"],
+                "decision_points": ["Joint venture or greenfield"],
+                "complexity_score": 2.5
+            }
+        })
+    }
+
+    struct RecordingProgress(std::sync::Mutex<Vec<BatchProgress>>);
+
+    impl BatchProgressSink for RecordingProgress {
+        fn report(&self, progress: &BatchProgress) {
+            self.0.lock().unwrap().push(progress.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_honors_concurrency_cap() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_delay(Duration::from_millis(20));
+        let peak = provider.peak_concurrency();
+        let generator = test_generator(provider).await;
+        let progress = RecordingProgress(std::sync::Mutex::new(Vec::new()));
+
+        let batch = (0..6).map(|i| params(&format!("Industry {}", i))).collect();
+        let results = generator.generate_case_studies_batch(batch, 2, &progress).await;
+
+        assert_eq!(results.len(), 6);
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+        let events = progress.0.lock().unwrap();
+        assert_eq!(events.len(), 6);
+        assert_eq!(events.last().unwrap().completed, 6);
+        assert!(events.iter().all(|e| e.total == 6 && e.succeeded));
+    }
+
+    #[tokio::test]
+    async fn test_batch_failure_does_not_abort_other_items() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .fail_with(AIError::AuthenticationError("bad key".to_string()));
+        let generator = test_generator(provider).await;
+        let progress = RecordingProgress(std::sync::Mutex::new(Vec::new()));
+
+        let batch = vec![params("Retail"), params(""), params("Finance"), params("Energy")];
+        let results = generator.generate_case_studies_batch(batch, 3, &progress).await;
+
+        // The invalid item fails validation; exactly one other item hits the provider error
+        assert!(matches!(results[1], Err(AIError::ValidationError(_))));
+        let failures = results.iter().filter(|r| r.is_err()).count();
+        assert_eq!(failures, 2);
+
+        let events = progress.0.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events.iter().filter(|e| !e.succeeded).count(), 2);
+        assert!(events.iter().any(|e| e.index == 1 && e.error.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_structured_json_response_is_used_directly() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .respond_with(format!("```json\n{}\n```", structured_response()));
+        let calls = provider.call_counter();
+        let generator = test_generator(provider).await;
+        enable_json_mode(&generator).await;
+
+        let case_study = generator.generate_case_study(params("Energy")).await.unwrap();
+
+        assert_eq!(case_study.generation_path, GenerationPath::Structured);
+        assert_eq!(case_study.title, "Solis Energy Enters Brazil");
+        assert_eq!(case_study.key_learning_points, vec!["Market entry modes", "Risk assessment"]);
+        assert_eq!(case_study.suggested_analysis_framework.as_deref(), Some("Porter's Five Forces"));
+        assert_eq!(case_study.metadata.key_stakeholders, vec!["CEO", "Board"]);
+        assert_eq!(case_study.metadata.complexity_score, 2.5);
+        // Counts come from the content, not the model
+        assert_eq!(case_study.metadata.word_count, case_study.content.split_whitespace().count() as u32);
+        // Summary and framework came from the same response
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generated_html_is_sanitized() {
+        let mut response = structured_response();
+        response["content"] = serde_json::json!(
+            "# Solis Energy\n\n<script>steal()</script>- **Market** entry\n- *Risk* review\n\n<p onclick=\"steal()\">The CEO must decide.</p>"
+        );
+        response["summary"] = serde_json::json!("<img src=\"x\" onerror=\"steal()\">A solar manufacturer expands.");
+        let generator = test_generator(MockProvider::new(ProviderType::Ollama).respond_with(response.to_string())).await;
+        enable_json_mode(&generator).await;
+
+        let case_study = generator.generate_case_study(params("Energy")).await.unwrap();
+
+        assert_eq!(
+            case_study.content,
+            "# Solis Energy\n\n- **Market** entry\n- *Risk* review\n\n<p>The CEO must decide.</p>"
+        );
+        assert_eq!(case_study.summary, "<img src=\"x\">A solar manufacturer expands.");
+    }
+
+    #[tokio::test]
+    async fn test_non_json_response_falls_back_to_text_parsing() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .respond_with("# Harbor Bank Turnaround\n\nThe new CEO must rebuild trust with regional managers.")
+            .respond_with("A bank's new leader faces a trust deficit.")
+            .respond_with("Use stakeholder analysis.");
+        let calls = provider.call_counter();
+        let generator = test_generator(provider).await;
+        enable_json_mode(&generator).await;
+
+        let case_study = generator.generate_case_study(params("Banking")).await.unwrap();
+
+        assert_eq!(case_study.generation_path, GenerationPath::TextParsing);
+        assert_eq!(case_study.title, "Harbor Bank Turnaround");
+        assert_eq!(case_study.summary, "A bank's new leader faces a trust deficit.");
+        assert!(case_study.metadata.key_stakeholders.contains(&"CEO".to_string()));
+        // The text response is reused rather than generating the content again
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_partial_json_response_is_a_clear_error() {
+        let mut partial = structured_response();
+        partial.as_object_mut().unwrap().remove("summary");
+        partial["metadata"].as_object_mut().unwrap().remove("decision_points");
+        let provider = MockProvider::new(ProviderType::Ollama).respond_with(partial.to_string());
+        let generator = test_generator(provider).await;
+        enable_json_mode(&generator).await;
+
+        let error = generator.generate_case_study(params("Energy")).await.unwrap_err();
+        assert!(matches!(error, AIError::ParsingError(_)));
+        let message = error.to_string();
+        assert!(message.contains("summary"), "{}", message);
+        assert!(message.contains("metadata.decision_points"), "{}", message);
+
+        let truncated = structured_response().to_string();
+        let provider = MockProvider::new(ProviderType::Ollama).respond_with(&truncated[..truncated.len() / 2]);
+        let generator = test_generator(provider).await;
+        enable_json_mode(&generator).await;
+
+        let error = generator.generate_case_study(params("Energy")).await.unwrap_err();
+        assert!(error.to_string().contains("incomplete JSON"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_short_content_is_expanded_once() {
+        let short = "# Harbor Bank\n\nThe CEO must act.";
+        let corrected = format!("# Harbor Bank\n\n{}", "The regional manager weighs a difficult choice. ".repeat(30));
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .respond_with(short)
+            .respond_with(corrected.clone())
+            .respond_with("A bank faces a turnaround.")
+            .respond_with("Use stakeholder analysis.");
+        let calls = provider.call_counter();
+        let generator = test_generator(provider).await;
+
+        let mut params = params("Banking");
+        params.target_length = 200;
+        params.enforce_length = true;
+        let case_study = generator.generate_case_study(params).await.unwrap();
+
+        assert_eq!(case_study.content, corrected.trim());
+        assert_eq!(case_study.length_check, LengthCheck {
+            target_length: 200,
+            word_count: 213,
+            within_tolerance: true,
+            adjustment: Some(LengthAdjustment::Expand),
+        });
+        assert_eq!(case_study.metadata.word_count, 213);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_length_is_reported_without_enforcement() {
+        let provider = MockProvider::new(ProviderType::Ollama).respond_with("# Harbor Bank\n\nThe CEO must act.");
+        let calls = provider.call_counter();
+        let generator = test_generator(provider).await;
+
+        let mut params = params("Banking");
+        params.target_length = 200;
+        let case_study = generator.generate_case_study(params).await.unwrap();
+
+        assert_eq!(case_study.length_check.word_count, 7);
+        assert!(!case_study.length_check.within_tolerance);
+        assert_eq!(case_study.length_check.adjustment, None);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_length_tolerance_band() {
+        assert_eq!(LengthAdjustment::needed(159, 200), Some(LengthAdjustment::Expand));
+        assert_eq!(LengthAdjustment::needed(160, 200), None);
+        assert_eq!(LengthAdjustment::needed(240, 200), None);
+        assert_eq!(LengthAdjustment::needed(241, 200), Some(LengthAdjustment::Condense));
+    }
+
+    #[test]
+    fn test_schema_lists_required_fields() {
+        let schema = structured_output_schema();
+        let required: Vec<&str> = schema["required"].as_array().unwrap().iter().filter_map(|v| v.as_str()).collect();
+        assert_eq!(required, vec!["title", "content", "summary", "key_learning_points", "metadata"]);
+        assert!(schema["properties"]["metadata"]["properties"]["decision_points"].is_object());
+    }
+
+    async fn stored_case_study(provider: MockProvider) -> (CaseStudyGenerator, CaseStudyManager, CaseStudy) {
+        let database = DatabaseManager::in_memory().await.unwrap();
+        let manager = AIManager::detached(database.pool().clone());
+        manager.set_active_provider(Box::new(provider)).await;
+        let case_studies = CaseStudyManager::new(database);
+
+        let mut metadata = crate::case_study::CaseStudyMetadata::default();
+        metadata.add_custom_field(PROBLEM_STATEMENT_FIELD.to_string(), serde_json::json!("Should Solis enter Brazil?"));
+        metadata.add_custom_field(SAMPLE_SOLUTION_FIELD.to_string(), serde_json::json!("Enter through a joint venture."));
+        let case_study = case_studies.create_case_study(crate::case_study::NewCaseStudy {
+            summary: Some("Founded in Lisbon in 2009.".to_string()),
+            industry: "Energy".to_string(),
+            duration_minutes: 60,
+            learning_objectives: vec!["Market analysis".to_string()],
+            metadata,
+            ..crate::case_study::NewCaseStudy::for_test("Solis Energy", "Solis Energy makes residential solar panels and is weighing expansion into Brazil.")
+        }).await.unwrap();
+
+        (manager.create_case_study_generator(), case_studies, case_study)
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_section_changes_only_that_field() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .respond_with("  Acquire a local distributor and license its brand.  ");
+        let (generator, case_studies, original) = stored_case_study(provider).await;
+
+        let updated = generator
+            .regenerate_section(&case_studies, &original.id, CaseStudySection::SampleSolution)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            CaseStudySection::SampleSolution.current_text(&updated).as_deref(),
+            Some("Acquire a local distributor and license its brand.")
+        );
+        for section in [CaseStudySection::Background, CaseStudySection::ProblemStatement, CaseStudySection::Content, CaseStudySection::AnalysisFramework] {
+            assert_eq!(section.current_text(&updated), section.current_text(&original), "{:?}", section);
+        }
+        assert_eq!(updated.title, original.title);
+        assert_eq!(updated.version, original.version + 1);
+
+        let versions = case_studies.get_case_study_versions(&original.id).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions.iter().any(|v| v.changes_summary.as_deref() == Some("Regenerated sample solution")));
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_background_and_content() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .respond_with("Solis Energy was founded by two engineers in Lisbon.")
+            .respond_with("# Solis Energy\n\nThe board must choose between Brazil and Mexico for its first overseas plant.");
+        let (generator, case_studies, original) = stored_case_study(provider).await;
+
+        let updated = generator
+            .regenerate_section(&case_studies, &original.id, CaseStudySection::Background)
+            .await
+            .unwrap();
+        assert_eq!(updated.summary.as_deref(), Some("Solis Energy was founded by two engineers in Lisbon."));
+        assert_eq!(updated.content, original.content);
+        assert_eq!(updated.metadata.custom_fields, original.metadata.custom_fields);
+
+        let updated = generator
+            .regenerate_section(&case_studies, &original.id, CaseStudySection::Content)
+            .await
+            .unwrap();
+        assert!(updated.content.contains("Brazil and Mexico"));
+        assert_eq!(updated.word_count, updated.content.split_whitespace().count() as i32);
+        assert_eq!(updated.summary.as_deref(), Some("Solis Energy was founded by two engineers in Lisbon."));
+        assert_eq!(case_studies.get_case_study_versions(&original.id).await.unwrap().len(), 3);
+
+        let missing = generator
+            .regenerate_section(&case_studies, "missing-id", CaseStudySection::Content)
+            .await
+            .unwrap_err();
+        assert!(matches!(missing, AIError::CaseStudyError(CaseStudyError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rate_gate_spaces_starts() {
+        let gate = RateGate::new(Some(Duration::from_millis(20)));
+        let started = Instant::now();
+        for _ in 0..3 {
+            gate.wait().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        let unlimited = RateGate::new(None);
+        let started = Instant::now();
+        unlimited.wait().await;
+        assert!(started.elapsed() < Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_override_replaces_template_persona() {
+        let generator = test_generator(MockProvider::new(ProviderType::Ollama)).await;
+
+        let default = generator.render_content_prompt(&params("Retail")).unwrap();
+        assert!(default.system_prompt.as_deref().unwrap().starts_with("You are an expert business case study author"));
+
+        let overridden = generator
+            .render_content_prompt(&CaseStudyGenerationParams {
+                system_prompt_override: Some("You write terse case studies for first-year students.".to_string()),
+                ..params("Retail")
+            })
+            .unwrap();
+        assert_eq!(overridden.system_prompt.as_deref(), Some("You write terse case studies for first-year students."));
+        assert_eq!(overridden.user_prompt, default.user_prompt);
+
+        for bad in ["   ".to_string(), "x".repeat(crate::ai::prompts::MAX_SYSTEM_PROMPT_OVERRIDE_CHARS + 1)] {
+            let params = CaseStudyGenerationParams { system_prompt_override: Some(bad), ..params("Retail") };
+            assert!(matches!(generator.validate_parameters(&params), Err(AIError::ValidationError(_))));
+        }
+    }
+}
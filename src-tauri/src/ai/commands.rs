@@ -1,21 +1,25 @@
 // Tauri commands for AI provider abstraction layer
 
 use crate::ai::{
-    AIManager, AIConfig, ProviderConfig, ProviderType,
-    GenerationRequest, GenerationResponse, GenerationStats,
-    ModelInfo, PromptTemplate, RenderedPrompt,
-    ModelConfig, ModelSelectionCriteria, ModelPerformancePriority, ModelUseCase,
-    CaseStudyGenerationParams, GeneratedCaseStudy, DifficultyLevel, CompanySize,
+    AIManager, AIConfig, AIStatus, ProviderConfig, ProviderType,
+    GenerationRequest, GenerationRequestBuilder, GenerationResponse, GenerationStats, StreamEvent, TauriStreamSink, TokenCount,
+    ModelInfo, ProviderCapabilities, ProviderHealth, PromptTemplate, RenderedPrompt, TemplateImportReport,
+    ModelConfig, ModelSelectionCriteria, ModelAvailabilitySync, ModelPerformancePriority, ModelUseCase, GenerationStyle, GenerationStylePreset,
+    CaseStudyGenerationParams, GeneratedCaseStudy, DifficultyLevel, CompanySize, BatchItemResult, CaseStudySection, EmbeddingBackfillReport,
+    case_study_param_errors,
     QuestionGenerationParams, GeneratedAssessment, QuestionType, QuestionDifficulty,
     providers::{get_supported_providers, is_provider_supported},
 };
+use crate::case_study::{commands::CaseStudyManagerState, CaseStudy, SemanticSearchHit};
 use crate::database::{
-    PromptTemplateRepository, 
-    models::{NewPromptTemplate, UpdatePromptTemplate, NewTemplateUsage}
+    AssessmentQuestionRepository, DatabaseManager, GenerationPreset, GenerationPresetRepository, PromptTemplateRepository,
+    UpdateGenerationPreset,
+    models::{NewPromptTemplate, UpdatePromptTemplate, NewTemplateUsage, GenerationHistory, GenerationCostSummary, AnalyticsPeriod, TemplateAnalytics}
 };
+use crate::encryption::commands::EncryptionState;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Manager, State};
 use tokio::sync::RwLock;
 
 type AIManagerState = Arc<RwLock<Option<AIManager>>>;
@@ -23,15 +27,17 @@ type TemplateRepositoryState = Arc<RwLock<Option<PromptTemplateRepository>>>;
 
 /// Initialize AI manager state
 pub fn setup_ai_state(app_handle: AppHandle, pool: sqlx::SqlitePool) -> AIManagerState {
-    let manager = AIManager::new(app_handle, pool);
+    // Encryption state is managed at startup; API keys are encrypted with it
+    let encryption = app_handle.try_state::<EncryptionState>().map(|state| state.inner().clone());
+    let manager = AIManager::new(app_handle, pool, encryption);
     
     // Initialize from database in the background
     let manager_clone = manager.clone();
     tauri::async_runtime::spawn(async move {
         if let Err(e) = manager_clone.initialize_from_database().await {
-            eprintln!("Warning: Failed to initialize AI manager from database: {}", e);
+            tracing::warn!("Failed to initialize AI manager from database: {}", e);
         } else {
-            println!("AI manager initialized with saved configuration");
+            tracing::info!("AI manager initialized with saved configuration");
         }
     });
     
@@ -104,28 +110,128 @@ pub async fn switch_ai_provider(
     }
 }
 
-/// Generate content using AI
+/// Generate content using AI. Passing a `request_id` makes the generation
+/// cancellable with `cancel_generation`.
 #[tauri::command]
 pub async fn generate_content(
     request: GenerationRequest,
+    request_id: Option<String>,
     ai_manager_state: State<'_, AIManagerState>,
 ) -> Result<GenerationResponse, String> {
+    // Release the state lock before generating so a cancel isn't blocked behind it
+    let manager = ai_manager_state.read().await.clone();
+    let Some(manager) = manager else {
+        return Err("AI manager not initialized".to_string());
+    };
+    let result = match request_id {
+        Some(request_id) => manager.generate_cancellable(request, &request_id).await,
+        None => manager.generate(request).await,
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Cancel a running generation or stream by its request id.
+/// Returns false if the request already finished or was never started.
+#[tauri::command]
+pub async fn cancel_generation(
+    request_id: String,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<bool, String> {
     let manager_lock = ai_manager_state.read().await;
     if let Some(manager) = manager_lock.as_ref() {
-        manager.generate(request).await.map_err(|e| e.to_string())
+        Ok(manager.cancel_generation(&request_id).await)
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Generate content and emit each chunk as an `ai://stream/{stream_id}` event,
+/// followed by a `done` event with usage stats or an `error` event. Events are emitted
+/// whether or not anything listens, so a frontend that stops listening before `done`
+/// must call `cancel_content_stream` or the generation runs to completion.
+#[tauri::command]
+pub async fn generate_content_stream(
+    request: GenerationRequest,
+    stream_id: String,
+    app_handle: AppHandle,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<StreamEvent, String> {
+    // Release the state lock before streaming so other commands aren't blocked
+    let manager = ai_manager_state.read().await.clone();
+    if let Some(manager) = manager {
+        let sink = TauriStreamSink::new(app_handle, &stream_id);
+        manager.stream_to_sink(request, &stream_id, &sink).await.map_err(|e| e.to_string())
     } else {
         Err("AI manager not initialized".to_string())
     }
 }
 
-/// Get available models for current provider
+/// Cancel a running content stream
+#[tauri::command]
+pub async fn cancel_content_stream(
+    stream_id: String,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<bool, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        Ok(manager.cancel_stream(&stream_id).await)
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Record usage for a generation produced outside `generate_content`, e.g. a completed stream
+#[tauri::command]
+pub async fn record_generation(
+    response: GenerationResponse,
+    generation_type: Option<String>,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<GenerationHistory, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let generation_type = generation_type.unwrap_or_else(|| "case_study".to_string());
+        manager.record_generation(&response, &generation_type).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Get total generation cost grouped by provider and by day
+#[tauri::command]
+pub async fn get_generation_cost_summary(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<GenerationCostSummary, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.get_generation_cost_summary().await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Clear cached generation responses
+#[tauri::command]
+pub async fn clear_ai_cache(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<bool, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.clear_cache().await;
+        Ok(true)
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Get available models for current provider, from the cache unless `force_refresh` is set
 #[tauri::command]
 pub async fn get_available_models(
+    force_refresh: Option<bool>,
     ai_manager_state: State<'_, AIManagerState>,
 ) -> Result<Vec<ModelInfo>, String> {
     let manager_lock = ai_manager_state.read().await;
     if let Some(manager) = manager_lock.as_ref() {
-        manager.get_available_models().await.map_err(|e| e.to_string())
+        manager.get_available_models(force_refresh.unwrap_or(false)).await.map_err(|e| e.to_string())
     } else {
         Err("AI manager not initialized".to_string())
     }
@@ -148,6 +254,46 @@ pub async fn validate_ai_provider(
     }
 }
 
+/// Health-check every configured provider concurrently
+#[tauri::command]
+pub async fn check_all_providers(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<HashMap<ProviderType, ProviderHealth>, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        Ok(manager.check_all_providers().await)
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// What a configured provider supports (streaming, functions, vision, ...)
+#[tauri::command]
+pub async fn get_provider_capabilities(
+    provider_type: ProviderType,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<ProviderCapabilities, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.get_provider_capabilities(&provider_type).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// What the active provider supports
+#[tauri::command]
+pub async fn get_active_provider_capabilities(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<ProviderCapabilities, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.get_active_provider_capabilities().await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
 /// Get AI generation statistics
 #[tauri::command]
 pub async fn get_ai_stats(
@@ -161,6 +307,18 @@ pub async fn get_ai_stats(
     }
 }
 
+/// Get generation stats together with the active provider's health and model count
+#[tauri::command]
+pub async fn get_ai_status(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<AIStatus, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.get_status().await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
 
 /// Get list of supported AI providers
 #[tauri::command]
@@ -306,6 +464,62 @@ pub async fn export_prompt_templates(
     }
 }
 
+/// Import prompt templates from JSON, reporting templates skipped by validation
+#[tauri::command]
+pub async fn import_prompt_templates(
+    json: String,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<TemplateImportReport, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.import_prompt_templates(&json).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Create a user prompt template, persisting it across restarts
+#[tauri::command]
+pub async fn create_prompt_template(
+    template: PromptTemplate,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<PromptTemplate, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.create_prompt_template(template).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Update a persisted user prompt template
+#[tauri::command]
+pub async fn update_prompt_template(
+    template: PromptTemplate,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<PromptTemplate, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.update_prompt_template(template).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Delete a user prompt template, restoring any built-in it overrode
+#[tauri::command]
+pub async fn delete_prompt_template(
+    template_id: String,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<bool, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.delete_prompt_template(&template_id).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
 /// Load default prompt templates
 #[tauri::command]
 pub async fn load_default_prompt_templates(
@@ -338,6 +552,83 @@ pub async fn generate_case_study_enhanced(
     }
 }
 
+/// Generate several case studies concurrently, emitting `ai://case-study-batch/progress`
+/// events as items finish. Failed items are reported individually.
+#[tauri::command]
+pub async fn generate_case_studies_batch(
+    params: Vec<CaseStudyGenerationParams>,
+    concurrency: Option<usize>,
+    app_handle: AppHandle,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<Vec<BatchItemResult>, String> {
+    // Release the lock so configuration changes aren't blocked for the whole batch
+    let manager = ai_manager_state.read().await.clone()
+        .ok_or_else(|| "AI manager not initialized".to_string())?;
+
+    let results = manager
+        .generate_case_studies_batch(params, concurrency.unwrap_or(3), &app_handle)
+        .await;
+    Ok(results.into_iter()
+        .enumerate()
+        .map(|(index, result)| BatchItemResult::new(index, result))
+        .collect())
+}
+
+/// Regenerate one section of a saved case study, recording the change as a new version
+#[tauri::command]
+pub async fn regenerate_case_study_section(
+    case_study_id: String,
+    section: CaseStudySection,
+    ai_manager_state: State<'_, AIManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+) -> Result<CaseStudy, String> {
+    let manager_lock = ai_manager_state.read().await;
+    let manager = manager_lock.as_ref().ok_or_else(|| "AI manager not initialized".to_string())?;
+    let case_study_lock = case_study_state.read().await;
+    let case_studies = case_study_lock.as_ref().ok_or_else(|| "Case study manager not initialized".to_string())?;
+
+    manager.create_case_study_generator()
+        .regenerate_section(case_studies, &case_study_id, section)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Find the case studies most similar in meaning to `query`
+#[tauri::command]
+pub async fn semantic_search_case_studies(
+    query: String,
+    k: Option<usize>,
+    ai_manager_state: State<'_, AIManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    let manager_lock = ai_manager_state.read().await;
+    let manager = manager_lock.as_ref().ok_or_else(|| "AI manager not initialized".to_string())?;
+    let case_study_lock = case_study_state.read().await;
+    let case_studies = case_study_lock.as_ref().ok_or_else(|| "Case study manager not initialized".to_string())?;
+
+    manager
+        .semantic_search_case_studies(case_studies, &query, k.unwrap_or(10))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Embed existing case studies that have no current embedding from the active provider
+#[tauri::command]
+pub async fn backfill_case_study_embeddings(
+    ai_manager_state: State<'_, AIManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+) -> Result<EmbeddingBackfillReport, String> {
+    let manager_lock = ai_manager_state.read().await;
+    let manager = manager_lock.as_ref().ok_or_else(|| "AI manager not initialized".to_string())?;
+    let case_study_lock = case_study_state.read().await;
+    let case_studies = case_study_lock.as_ref().ok_or_else(|| "Case study manager not initialized".to_string())?;
+
+    manager
+        .backfill_case_study_embeddings(case_studies)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Generate a case study using AI (legacy command for backward compatibility)
 #[tauri::command]
 pub async fn generate_case_study(
@@ -381,6 +672,10 @@ pub async fn generate_case_study(
         geographical_context: None,
         time_period: None,
         specific_focus_areas: vec![],
+        enforce_length: false,
+        system_prompt_override: None,
+        style: GenerationStyle::default(),
+        generation_params: None,
     };
     
     let manager_lock = ai_manager_state.read().await;
@@ -403,56 +698,86 @@ pub async fn generate_case_study(
 pub async fn validate_case_study_params(
     params: CaseStudyGenerationParams,
 ) -> Result<Vec<String>, String> {
-    let mut validation_errors = Vec::new();
-    
-    // Industry validation
-    if params.industry.trim().is_empty() {
-        validation_errors.push("Industry cannot be empty".to_string());
-    }
-    if params.industry.len() > 100 {
-        validation_errors.push("Industry name too long (max 100 characters)".to_string());
-    }
+    Ok(case_study_param_errors(&params))
+}
 
-    // Duration validation
-    if params.duration_minutes < 5 {
-        validation_errors.push("Duration must be at least 5 minutes".to_string());
-    }
-    if params.duration_minutes > 480 {
-        validation_errors.push("Duration cannot exceed 8 hours".to_string());
-    }
+fn generation_preset_repository(database_manager: &DatabaseManager) -> GenerationPresetRepository {
+    GenerationPresetRepository::new(database_manager.pool().clone())
+}
 
-    // Learning objectives validation
-    if params.learning_objectives.is_empty() {
-        validation_errors.push("At least one learning objective is required".to_string());
-    }
-    if params.learning_objectives.len() > 10 {
-        validation_errors.push("Too many learning objectives (max 10)".to_string());
-    }
-    for objective in &params.learning_objectives {
-        if objective.trim().is_empty() {
-            validation_errors.push("Learning objectives cannot be empty".to_string());
-            break;
-        }
-        if objective.len() > 200 {
-            validation_errors.push("Learning objective too long (max 200 characters)".to_string());
-            break;
-        }
-    }
+/// Save named case study generation parameters for a user; invalid params are rejected
+#[tauri::command]
+pub async fn save_generation_preset(
+    user_id: String,
+    name: String,
+    params: CaseStudyGenerationParams,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<GenerationPreset, String> {
+    generation_preset_repository(&database_manager)
+        .save(&user_id, &name, &params)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Target length validation
-    if params.target_length < 200 {
-        validation_errors.push("Target length must be at least 200 words".to_string());
-    }
-    if params.target_length > 5000 {
-        validation_errors.push("Target length cannot exceed 5000 words".to_string());
-    }
+/// A user's saved generation presets, by name
+#[tauri::command]
+pub async fn list_generation_presets(
+    user_id: String,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<GenerationPreset>, String> {
+    generation_preset_repository(&database_manager)
+        .list_for_user(&user_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Rename a generation preset and/or replace its params
+#[tauri::command]
+pub async fn update_generation_preset(
+    preset_id: i64,
+    update: UpdateGenerationPreset,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<GenerationPreset, String> {
+    generation_preset_repository(&database_manager)
+        .update(preset_id, &update)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    // Focus areas validation
-    if params.specific_focus_areas.len() > 5 {
-        validation_errors.push("Too many focus areas (max 5)".to_string());
+/// Delete a generation preset; returns false if it didn't exist
+#[tauri::command]
+pub async fn delete_generation_preset(
+    preset_id: i64,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<bool, String> {
+    generation_preset_repository(&database_manager)
+        .delete(preset_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Generate a case study from a saved preset, with `overrides` (a partial params object)
+/// taking precedence over the preset's fields
+#[tauri::command]
+pub async fn generate_case_study_from_preset(
+    preset_id: i64,
+    overrides: Option<serde_json::Value>,
+    ai_manager_state: State<'_, AIManagerState>,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<GeneratedCaseStudy, String> {
+    let preset = generation_preset_repository(&database_manager)
+        .get(preset_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let params = preset.params_with_overrides(overrides.as_ref()).map_err(|e| e.to_string())?;
+
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let generator = manager.create_case_study_generator();
+        generator.generate_case_study(params).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
     }
-    
-    Ok(validation_errors)
 }
 
 /// Generate assessment questions using AI
@@ -492,8 +817,10 @@ pub async fn generate_assessment_questions(
         }
         messages.push(crate::ai::models::ChatMessage::user(rendered.user_prompt));
 
-        let config = manager.get_config().await;
-        let request = GenerationRequest::new(messages, config.providers.get(&config.default_provider).unwrap().default_model.clone());
+        let request = manager
+            .build_request(GenerationRequestBuilder::new(messages).use_case(ModelUseCase::QuestionGeneration))
+            .await
+            .map_err(|e| e.to_string())?;
 
         manager.generate(request).await.map_err(|e| e.to_string())
     } else {
@@ -515,13 +842,12 @@ pub async fn test_ai_connection(
             crate::ai::models::ChatMessage::user("Say 'Hello, World!' and nothing else.")
         ];
         
-        let config = manager.get_config().await;
-        let default_provider = &config.default_provider;
-        let default_model = &config.providers.get(default_provider).unwrap().default_model;
-        
-        let request = GenerationRequest::new(test_messages, default_model.clone());
-        
-        match manager.generate(request).await {
+        let generation = match manager.build_request(GenerationRequestBuilder::new(test_messages).max_tokens(32)).await {
+            Ok(request) => manager.generate(request).await,
+            Err(e) => Err(e),
+        };
+
+        match generation {
             Ok(response) => {
                 results.insert("generation_test".to_string(), serde_json::json!({
                     "success": true,
@@ -539,7 +865,7 @@ pub async fn test_ai_connection(
         }
 
         // Test model listing
-        match manager.get_available_models().await {
+        match manager.get_available_models(true).await {
             Ok(models) => {
                 results.insert("models_test".to_string(), serde_json::json!({
                     "success": true,
@@ -738,6 +1064,22 @@ pub async fn get_template_usage_stats(
     }
 }
 
+/// Get usage across all templates for a period: top templates and uses over time
+#[tauri::command]
+pub async fn get_template_analytics(
+    period: Option<AnalyticsPeriod>,
+    template_repo_state: State<'_, TemplateRepositoryState>,
+) -> Result<TemplateAnalytics, String> {
+    let repo_lock = template_repo_state.read().await;
+    if let Some(repo) = repo_lock.as_ref() {
+        repo.get_template_analytics(period.unwrap_or(AnalyticsPeriod::Month))
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Template repository not initialized".to_string())
+    }
+}
+
 /// Record template usage
 #[tauri::command]
 pub async fn record_template_usage(
@@ -916,6 +1258,47 @@ pub async fn estimate_generation_cost(
     }
 }
 
+/// Estimate prompt tokens for a generation request
+#[tauri::command]
+pub async fn count_tokens(
+    request: GenerationRequest,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<TokenCount, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.count_tokens(&request).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Warn when a generation request would not fit the model's context window
+#[tauri::command]
+pub async fn check_context_window(
+    request: GenerationRequest,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<Vec<String>, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.check_context_window(&request).await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
+/// Sync Ollama model availability with the models installed on the local server
+#[tauri::command]
+pub async fn refresh_ollama_availability(
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<ModelAvailabilitySync, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.refresh_ollama_availability().await.map_err(|e| e.to_string())
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
 /// Update model availability
 #[tauri::command]
 pub async fn update_model_availability(
@@ -1008,6 +1391,22 @@ pub async fn get_model_parameter_constraints(
     }
 }
 
+/// List the generation style presets with the values each would use for a model
+#[tauri::command]
+pub async fn list_generation_style_presets(
+    model_id: String,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<Vec<GenerationStylePreset>, String> {
+    let manager_lock = ai_manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let config_manager = manager.get_model_config_manager().await;
+        let config_manager = config_manager.read().await;
+        Ok(config_manager.style_presets(&model_id))
+    } else {
+        Err("AI manager not initialized".to_string())
+    }
+}
+
 // Question Generation Commands
 
 /// Generate assessment questions for a case study
@@ -1025,6 +1424,27 @@ pub async fn generate_assessment_questions_enhanced(
     }
 }
 
+/// Generate questions for a saved case study and store them, returning the new question ids
+#[tauri::command]
+pub async fn generate_and_save_questions(
+    case_study_id: String,
+    params: QuestionGenerationParams,
+    ai_manager_state: State<'_, AIManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+    database_manager: State<'_, Arc<DatabaseManager>>,
+) -> Result<Vec<i64>, String> {
+    let manager_lock = ai_manager_state.read().await;
+    let manager = manager_lock.as_ref().ok_or_else(|| "AI manager not initialized".to_string())?;
+    let case_study_lock = case_study_state.read().await;
+    let case_studies = case_study_lock.as_ref().ok_or_else(|| "Case study manager not initialized".to_string())?;
+    let repository = AssessmentQuestionRepository::new(database_manager.pool().clone());
+
+    manager.create_question_generator()
+        .generate_and_save_questions(case_studies, &repository, &case_study_id, params)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Validate question generation parameters
 #[tauri::command]
 pub async fn validate_question_generation_params(
@@ -1160,6 +1580,9 @@ pub async fn generate_questions_from_case_study(
         include_rubric: false,
         target_duration_minutes: None,
         focus_areas: vec![],
+        system_prompt_override: None,
+        style: GenerationStyle::default(),
+        generation_params: None,
     };
 
     let manager_lock = ai_manager_state.read().await;
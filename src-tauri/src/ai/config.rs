@@ -69,6 +69,21 @@ impl ModelConfig {
     }
 }
 
+/// What `AIManager` does with a request that exceeds the provider's rate limit
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitMode {
+    /// Hold the request until the limiter has capacity
+    #[default]
+    Wait,
+    /// Fail immediately with `AIError::RateLimited`
+    Reject,
+}
+
+fn default_request_timeout_secs() -> u64 {
+    120
+}
+
 /// Configuration for an AI provider
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
@@ -81,9 +96,17 @@ pub struct ProviderConfig {
     pub default_model: String,
     pub models: Vec<ModelConfig>,
     pub timeout_seconds: u64,
+    /// Upper bound on a single generation or health check, enforced by `AIManager`
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
     pub max_retries: u32,
     pub rate_limit_requests_per_minute: Option<u32>,
     pub rate_limit_tokens_per_minute: Option<u32>,
+    /// Requests allowed back-to-back before `rate_limit_requests_per_minute` spacing applies
+    #[serde(default)]
+    pub rate_limit_burst: Option<u32>,
+    #[serde(default)]
+    pub rate_limit_mode: RateLimitMode,
     pub custom_headers: HashMap<String, String>,
     pub proxy_url: Option<String>,
     pub verify_ssl: bool,
@@ -123,9 +146,12 @@ impl ProviderConfig {
                     .with_cost(0.0010, 0.0020),
             ],
             timeout_seconds: 60,
+            request_timeout_secs: default_request_timeout_secs(),
             max_retries: 3,
             rate_limit_requests_per_minute: Some(10000),
             rate_limit_tokens_per_minute: Some(2000000),
+            rate_limit_burst: None,
+            rate_limit_mode: RateLimitMode::default(),
             custom_headers: HashMap::new(),
             proxy_url: None,
             verify_ssl: true,
@@ -157,9 +183,12 @@ impl ProviderConfig {
                     .with_cost(0.015, 0.075),
             ],
             timeout_seconds: 60,
+            request_timeout_secs: default_request_timeout_secs(),
             max_retries: 3,
             rate_limit_requests_per_minute: Some(4000),
             rate_limit_tokens_per_minute: Some(400000),
+            rate_limit_burst: None,
+            rate_limit_mode: RateLimitMode::default(),
             custom_headers: HashMap::new(),
             proxy_url: None,
             verify_ssl: true,
@@ -191,9 +220,12 @@ impl ProviderConfig {
                     .with_streaming(true),
             ],
             timeout_seconds: 300, // Ollama can be slower
+            request_timeout_secs: 300,
             max_retries: 2,
             rate_limit_requests_per_minute: None, // No limits for local Ollama
             rate_limit_tokens_per_minute: None,
+            rate_limit_burst: None,
+            rate_limit_mode: RateLimitMode::default(),
             custom_headers: HashMap::new(),
             proxy_url: None,
             verify_ssl: false, // Often used locally with self-signed certs
@@ -226,6 +258,11 @@ impl ProviderConfig {
         Ok(())
     }
 
+    /// Timeout applied to each provider call
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.request_timeout_secs)
+    }
+
     /// Get enabled models
     pub fn get_enabled_models(&self) -> Vec<&ModelConfig> {
         self.models.iter().filter(|m| m.enabled).collect()
@@ -265,6 +302,73 @@ impl ModelConfig {
     }
 }
 
+/// Retry policy for transient provider failures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            jitter: true,
+        }
+    }
+}
+
+/// Allowlist for HTML embedded in AI-generated markdown. Anything not listed is stripped;
+/// `<script>`/`<style>` and `on*` event handler attributes are always removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SanitizationConfig {
+    pub enabled: bool,
+    pub allowed_tags: Vec<String>,
+    pub allowed_attributes: Vec<String>,
+    pub allowed_url_schemes: Vec<String>,
+}
+
+impl Default for SanitizationConfig {
+    fn default() -> Self {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        Self {
+            enabled: true,
+            allowed_tags: strings(&[
+                "a", "b", "blockquote", "br", "code", "del", "em", "h1", "h2", "h3", "h4", "h5", "h6",
+                "hr", "i", "img", "kbd", "li", "ol", "p", "pre", "s", "strong", "sub", "sup",
+                "table", "tbody", "td", "th", "thead", "tr", "u", "ul",
+            ]),
+            allowed_attributes: strings(&["alt", "href", "src", "title"]),
+            allowed_url_schemes: strings(&["http", "https", "mailto"]),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Backoff delay before the given retry (1-based), doubling each time up to `max_delay_ms`
+    pub fn delay_for_attempt(&self, attempt: u32) -> std::time::Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay_ms
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_delay_ms);
+
+        let delay = if self.jitter && delay > 0 {
+            // Jitter within the upper half of the window so some backoff is always kept
+            use rand::Rng;
+            rand::thread_rng().gen_range(delay / 2..=delay)
+        } else {
+            delay
+        };
+
+        std::time::Duration::from_millis(delay)
+    }
+}
+
 /// Main AI configuration containing all providers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIConfig {
@@ -275,6 +379,27 @@ pub struct AIConfig {
     pub log_level: String,
     pub cache_responses: bool,
     pub cache_ttl_seconds: u64,
+    #[serde(default = "default_cache_size")]
+    pub cache_size: usize,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Providers tried in order when the default provider is unavailable
+    #[serde(default)]
+    pub fallback_providers: Vec<ProviderType>,
+    /// HTML allowed to survive in generated content
+    #[serde(default)]
+    pub sanitization: SanitizationConfig,
+    /// How long each provider's model list is reused before it is fetched again
+    #[serde(default = "default_model_list_ttl_seconds")]
+    pub model_list_ttl_seconds: u64,
+}
+
+fn default_cache_size() -> usize {
+    100
+}
+
+fn default_model_list_ttl_seconds() -> u64 {
+    600
 }
 
 impl Default for AIConfig {
@@ -295,6 +420,11 @@ impl Default for AIConfig {
             log_level: "info".to_string(),
             cache_responses: false,
             cache_ttl_seconds: 3600,
+            cache_size: default_cache_size(),
+            retry: RetryConfig::default(),
+            fallback_providers: Vec::new(),
+            sanitization: SanitizationConfig::default(),
+            model_list_ttl_seconds: default_model_list_ttl_seconds(),
         }
     }
 }
@@ -352,6 +482,10 @@ impl AIConfig {
             provider.validate()?;
         }
 
+        if let Some(missing) = self.fallback_providers.iter().find(|p| !self.providers.contains_key(p)) {
+            return Err(format!("Fallback provider {} is not configured", missing));
+        }
+
         Ok(())
     }
 }
@@ -419,6 +553,31 @@ mod tests {
         assert!(config.providers.contains_key(&ProviderType::Ollama));
     }
 
+    #[test]
+    fn test_retry_delay_backoff() {
+        let retry = RetryConfig {
+            max_attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 350,
+            jitter: false,
+        };
+        assert_eq!(retry.delay_for_attempt(1).as_millis(), 100);
+        assert_eq!(retry.delay_for_attempt(2).as_millis(), 200);
+        assert_eq!(retry.delay_for_attempt(3).as_millis(), 350);
+
+        let jittered = RetryConfig { jitter: true, ..retry };
+        let delay = jittered.delay_for_attempt(2).as_millis();
+        assert!((100..=200).contains(&delay));
+    }
+
+    #[test]
+    fn test_ai_config_without_retry_deserializes() {
+        let mut value = serde_json::to_value(AIConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("retry");
+        let config: AIConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.retry.max_attempts, RetryConfig::default().max_attempts);
+    }
+
     #[test]
     fn test_ai_config_provider_management() {
         let mut config = AIConfig::default();
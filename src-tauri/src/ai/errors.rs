@@ -23,6 +23,9 @@ pub enum AIError {
     #[error("Rate limit exceeded: {0}")]
     RateLimitError(String),
 
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: std::time::Duration },
+
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
@@ -38,12 +41,21 @@ pub enum AIError {
     #[error("Model not found: {0}")]
     ModelNotFound(String),
 
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Quota exceeded: {0}")]
     QuotaExceeded(String),
 
     #[error("Timeout error: {0}")]
     TimeoutError(String),
 
+    #[error("Request timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Request cancelled")]
+    Cancelled,
+
     #[error("Streaming error: {0}")]
     StreamingError(String),
 
@@ -56,20 +68,27 @@ pub enum AIError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("Case study error: {0}")]
+    CaseStudyError(#[from] crate::case_study::CaseStudyError),
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
 impl AIError {
-    /// Check if the error is retryable
+    /// Check if the error is transient and the request may succeed if retried
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
+        match self {
             AIError::NetworkError(_) |
             AIError::RateLimitError(_) |
+            AIError::ServiceUnavailable(_) |
             AIError::TimeoutError(_) |
-            AIError::HttpError(_)
-        )
+            AIError::Timeout(_) => true,
+            AIError::HttpError(e) => {
+                e.is_timeout() || e.is_connect() || e.status().map_or(false, |s| s.is_server_error())
+            }
+            _ => false,
+        }
     }
 
     /// Get error category for logging/metrics
@@ -81,17 +100,22 @@ impl AIError {
             AIError::NetworkError(_) => "network",
             AIError::AuthenticationError(_) => "authentication",
             AIError::RateLimitError(_) => "rate_limit",
+            AIError::RateLimited { .. } => "rate_limit",
             AIError::InvalidRequest(_) => "validation",
             AIError::ValidationError(_) => "validation",
             AIError::ParsingError(_) => "parsing",
             AIError::TemplateError(_) => "template",
             AIError::ModelNotFound(_) => "model",
+            AIError::ServiceUnavailable(_) => "service_unavailable",
             AIError::QuotaExceeded(_) => "quota",
             AIError::TimeoutError(_) => "timeout",
+            AIError::Timeout(_) => "timeout",
+            AIError::Cancelled => "cancelled",
             AIError::StreamingError(_) => "streaming",
             AIError::SerializationError(_) => "serialization",
             AIError::HttpError(_) => "http",
             AIError::IoError(_) => "io",
+            AIError::CaseStudyError(_) => "case_study",
             AIError::Unknown(_) => "unknown",
         }
     }
@@ -103,10 +127,13 @@ impl AIError {
             AIError::ConfigurationError(_) => "AI configuration is invalid. Please verify your provider settings.".to_string(),
             AIError::AuthenticationError(_) => "AI provider authentication failed. Please check your API key.".to_string(),
             AIError::RateLimitError(_) => "AI provider rate limit exceeded. Please try again later.".to_string(),
+            AIError::RateLimited { retry_after } => format!("Too many AI requests. Please try again in {} seconds.", retry_after.as_secs().max(1)),
             AIError::NetworkError(_) => "Network connection failed. Please check your internet connection.".to_string(),
+            AIError::ServiceUnavailable(_) => "AI provider is temporarily unavailable. Please try again later.".to_string(),
             AIError::QuotaExceeded(_) => "AI provider quota exceeded. Please check your usage limits.".to_string(),
             AIError::ModelNotFound(_) => "The requested AI model is not available. Please try a different model.".to_string(),
-            AIError::TimeoutError(_) => "AI request timed out. Please try again.".to_string(),
+            AIError::TimeoutError(_) | AIError::Timeout(_) => "AI request timed out. Please try again.".to_string(),
+            AIError::Cancelled => "The AI request was cancelled.".to_string(),
             AIError::ValidationError(msg) => format!("Validation failed: {}", msg),
             _ => "An unexpected error occurred while processing your AI request.".to_string(),
         }
@@ -124,6 +151,10 @@ mod tests {
     fn test_error_retryable() {
         assert!(AIError::NetworkError("test".to_string()).is_retryable());
         assert!(AIError::RateLimitError("test".to_string()).is_retryable());
+        assert!(AIError::ServiceUnavailable("test".to_string()).is_retryable());
+        assert!(AIError::TimeoutError("test".to_string()).is_retryable());
+        assert!(!AIError::ValidationError("test".to_string()).is_retryable());
+        assert!(!AIError::InvalidRequest("test".to_string()).is_retryable());
         assert!(!AIError::AuthenticationError("test".to_string()).is_retryable());
         assert!(!AIError::ConfigurationError("test".to_string()).is_retryable());
     }
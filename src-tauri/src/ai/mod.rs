@@ -10,61 +10,151 @@ pub mod errors;
 pub mod model_config;
 pub mod case_study_generator;
 pub mod question_generator;
+pub mod streaming;
+pub mod retry;
+pub mod cache;
+pub mod tokens;
+pub mod truncation;
+pub mod rate_limit;
+pub mod semantic_search;
+pub mod sanitize;
+pub mod request_builder;
 
-pub use config::{AIConfig, ProviderConfig, ProviderType};
+pub use config::{AIConfig, ProviderConfig, ProviderType, RateLimitMode, RetryConfig, SanitizationConfig};
 pub use errors::{AIError, Result};
 pub use models::{
     GenerationRequest, GenerationResponse, GenerationStats, 
-    StreamResponse, ModelInfo, ProviderCapabilities
+    StreamResponse, ModelInfo, ProviderCapabilities, ProviderHealth, ToolCall, ToolDefinition, AIStatus
 };
 pub use providers::AIProvider;
-pub use prompts::{PromptTemplate, PromptManager, RenderedPrompt};
+pub use streaming::{StreamEvent, StreamEventSink, TauriStreamSink};
+pub use cache::ResponseCache;
+pub use rate_limit::RateLimiter;
+pub use semantic_search::EmbeddingBackfillReport;
+pub use sanitize::{sanitize_markdown, Sanitizer};
+pub use request_builder::GenerationRequestBuilder;
+pub use tokens::{TokenCount, TokenCountMethod};
+pub use truncation::{TruncationReport, TruncationStrategy};
+pub use prompts::{PromptTemplate, PromptManager, RenderedPrompt, SkippedTemplate, TemplateImportReport};
 pub use model_config::{
     ModelConfig, ModelConfigManager, ModelSelectionCriteria, 
     ModelPerformancePriority, ModelUseCase, ModelCapabilities,
-    ParameterConstraints, ParameterRange
+    ParameterConstraints, ParameterRange, ModelAvailabilitySync,
+    GenerationStyle, GenerationStylePreset
 };
 pub use case_study_generator::{
     CaseStudyGenerator, CaseStudyGenerationParams, GeneratedCaseStudy,
-    DifficultyLevel, CompanySize, CaseStudyMetadata
+    DifficultyLevel, CompanySize, CaseStudyMetadata, CaseStudySection, GenerationPath,
+    LengthAdjustment, LengthCheck, BatchItemResult, BatchProgress, BatchProgressSink, NoopBatchProgress,
+    case_study_param_errors
 };
 pub use question_generator::{
     QuestionGenerator, QuestionGenerationParams, GeneratedAssessment,
     AssessmentQuestion, QuestionType, QuestionDifficulty, QuestionOption, AssessmentMetadata
 };
 
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 use tauri::AppHandle;
-use crate::database::AIConfigRepository;
+use crate::database::{AIConfigRepository, GenerationCostSummary, GenerationHistory, GenerationHistoryRepository, NewGenerationHistory, PromptTemplateRepository};
 use sqlx::SqlitePool;
+use crate::encryption::commands::EncryptionState;
+
+/// Fail with `AIError::Timeout` if `future` does not complete within `timeout`
+pub async fn with_timeout<T>(timeout: Duration, future: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(timeout, future)
+        .await
+        .map_err(|_| AIError::Timeout(timeout))?
+}
+
+/// Provider instance shared by the requests using it. Requests hold their own `Arc`
+/// rather than a lock guard, so switching providers never waits on or changes them.
+type SharedProvider = Arc<dyn AIProvider + Send + Sync>;
+
+/// How long the active provider's health check and model count are reused by `get_status`
+const HEALTH_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Health probe of the active provider kept for `HEALTH_CACHE_TTL`
+struct CachedHealth {
+    provider_type: ProviderType,
+    health: ProviderHealth,
+    model_count: Option<usize>,
+    checked_at: chrono::DateTime<chrono::Utc>,
+    probed: std::time::Instant,
+}
+
+/// A provider's model list, kept for `AIConfig::model_list_ttl_seconds`
+struct CachedModelList {
+    models: Vec<ModelInfo>,
+    fetched: std::time::Instant,
+}
 
 /// Main AI manager that coordinates between different providers
 #[derive(Clone)]
 pub struct AIManager {
     config: Arc<RwLock<AIConfig>>,
-    active_provider: Arc<RwLock<Option<Box<dyn AIProvider + Send + Sync>>>>,
-    prompt_manager: Arc<PromptManager>,
+    active_provider: Arc<RwLock<Option<SharedProvider>>>,
+    prompt_manager: Arc<std::sync::RwLock<Arc<PromptManager>>>,
+    template_repository: Arc<PromptTemplateRepository>,
     model_config_manager: Arc<RwLock<ModelConfigManager>>,
     config_repository: Arc<AIConfigRepository>,
-    app_handle: AppHandle,
+    history_repository: Arc<GenerationHistoryRepository>,
+    active_requests: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    response_cache: Arc<Mutex<ResponseCache>>,
+    fallback_instances: Arc<RwLock<HashMap<ProviderType, SharedProvider>>>,
+    rate_limiter: Arc<RateLimiter>,
+    health_cache: Arc<Mutex<Option<CachedHealth>>>,
+    model_list_cache: Arc<Mutex<HashMap<ProviderType, CachedModelList>>>,
+    app_handle: Option<AppHandle>,
 }
 
 impl AIManager {
     /// Create a new AI manager instance
-    pub fn new(app_handle: AppHandle, pool: SqlitePool) -> Self {
+    pub fn new(app_handle: AppHandle, pool: SqlitePool, encryption: Option<EncryptionState>) -> Self {
+        Self::with_app_handle(Some(app_handle), pool, encryption)
+    }
+
+    /// Create a manager that is not attached to a running Tauri app
+    #[cfg(test)]
+    pub(crate) fn detached(pool: SqlitePool) -> Self {
+        Self::with_app_handle(None, pool, None)
+    }
+
+    fn with_app_handle(app_handle: Option<AppHandle>, pool: SqlitePool, encryption: Option<EncryptionState>) -> Self {
+        let defaults = AIConfig::default();
+        let mut prompt_manager = PromptManager::new();
+        prompt_manager.load_default_templates();
         Self {
-            config: Arc::new(RwLock::new(AIConfig::default())),
+            config: Arc::new(RwLock::new(defaults.clone())),
             active_provider: Arc::new(RwLock::new(None)),
-            prompt_manager: Arc::new(PromptManager::new()),
+            prompt_manager: Arc::new(std::sync::RwLock::new(Arc::new(prompt_manager))),
+            template_repository: Arc::new(PromptTemplateRepository::new(pool.clone())),
             model_config_manager: Arc::new(RwLock::new(ModelConfigManager::new())),
-            config_repository: Arc::new(AIConfigRepository::new(pool)),
+            config_repository: Arc::new(AIConfigRepository::with_encryption(pool.clone(), encryption)),
+            history_repository: Arc::new(GenerationHistoryRepository::new(pool)),
+            active_requests: Arc::new(Mutex::new(HashMap::new())),
+            response_cache: Arc::new(Mutex::new(ResponseCache::new(
+                defaults.cache_size,
+                Duration::from_secs(defaults.cache_ttl_seconds),
+            ))),
+            fallback_instances: Arc::new(RwLock::new(HashMap::new())),
+            rate_limiter: Arc::new(RateLimiter::new()),
+            health_cache: Arc::new(Mutex::new(None)),
+            model_list_cache: Arc::new(Mutex::new(HashMap::new())),
             app_handle,
         }
     }
 
-    /// Initialize the AI manager and load configuration from database
+    /// Initialize the AI manager and load configuration and user prompt templates from database
     pub async fn initialize_from_database(&self) -> Result<()> {
+        if let Err(e) = self.load_persisted_templates().await {
+            tracing::warn!("Could not load prompt templates from database: {}", e);
+        }
+
         // Try to load existing configuration from database
         match self.config_repository.load_config().await {
             Ok(Some(saved_config)) => {
@@ -72,23 +162,23 @@ impl AIManager {
                 // Try to switch to the default provider
                 if let Err(e) = self.switch_provider(&saved_config.default_provider).await {
                     // If switching fails, just log it - we'll use default config
-                    eprintln!("Warning: Could not switch to saved provider: {}", e);
+                    tracing::warn!("Could not switch to saved provider: {}", e);
                 }
             },
             Ok(None) => {
                 // No saved config, use default and save it
                 let default_config = AIConfig::default();
                 if let Err(e) = self.config_repository.save_config(&default_config).await {
-                    eprintln!("Warning: Could not save default config: {}", e);
+                    tracing::warn!("Could not save default config: {}", e);
                 }
                 *self.config.write().await = default_config.clone();
                 // Try to switch to default provider
                 if let Err(e) = self.switch_provider(&default_config.default_provider).await {
-                    eprintln!("Warning: Could not switch to default provider: {}", e);
+                    tracing::warn!("Could not switch to default provider: {}", e);
                 }
             },
             Err(e) => {
-                eprintln!("Warning: Could not load config from database: {}", e);
+                tracing::warn!("Could not load config from database: {}", e);
                 // Fall back to default config
                 let default_config = AIConfig::default();
                 *self.config.write().await = default_config.clone();
@@ -104,42 +194,454 @@ impl AIManager {
         Ok(())
     }
 
-    /// Switch to a different AI provider
+    /// Switch to a different AI provider. Requests already in flight finish on the
+    /// provider they started with.
     pub async fn switch_provider(&self, provider_type: &ProviderType) -> Result<()> {
-        let config = self.config.read().await;
-        let provider_config = config.get_provider_config(provider_type)
+        self.install_provider(provider_type).await.map(|_| ())
+    }
+
+    /// Create a provider from its configuration and make it active in a single swap
+    async fn install_provider(&self, provider_type: &ProviderType) -> Result<SharedProvider> {
+        // Clone the config so no lock is held while the provider is created
+        let provider_config = self.config.read().await
+            .get_provider_config(provider_type)
+            .cloned()
             .ok_or_else(|| AIError::ConfigurationError(format!("Provider {} not configured", provider_type)))?;
 
-        let provider = providers::create_provider(provider_type.clone(), provider_config.clone()).await?;
-        *self.active_provider.write().await = Some(provider);
-        Ok(())
+        let provider: SharedProvider = Arc::from(providers::create_provider(provider_type.clone(), provider_config).await?);
+        *self.active_provider.write().await = Some(provider.clone());
+        // The provider's endpoint or key may have changed, so its models are listed afresh
+        self.model_list_cache.lock().await.remove(provider_type);
+        Ok(provider)
     }
 
-    /// Generate content using the active provider
+    /// Install a provider directly, bypassing configuration
+    #[cfg(test)]
+    pub(crate) async fn set_active_provider(&self, provider: Box<dyn AIProvider + Send + Sync>) {
+        *self.active_provider.write().await = Some(Arc::from(provider));
+    }
+
+    /// Snapshot of the active provider. The lock is released before returning, so callers
+    /// can await on the provider without blocking a switch.
+    async fn current_provider(&self) -> Result<SharedProvider> {
+        self.active_provider.read().await.clone().ok_or(AIError::ProviderNotInitialized)
+    }
+
+    /// Type of the currently active provider
+    async fn active_provider_type(&self) -> Result<ProviderType> {
+        Ok(self.current_provider().await?.get_provider_type())
+    }
+
+    /// Generate content using the active provider, retrying transient failures.
+    /// Identical non-streaming requests are served from the response cache when enabled.
     pub async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse> {
-        let provider = self.active_provider.read().await;
-        let provider = provider.as_ref()
-            .ok_or_else(|| AIError::ProviderNotInitialized)?;
+        let provider = self.current_provider().await?;
+        self.generate_with_provider(provider, request).await
+    }
+
+    /// Generate on `provider` for the whole request, including retries, so a concurrent
+    /// switch can't split it across providers. Runs in an `ai.generate` span with a fresh
+    /// `request_id` so provider calls and the history write can be followed back to the request.
+    #[tracing::instrument(name = "ai.generate", skip_all, fields(request_id = %uuid::Uuid::new_v4(), model = %request.model))]
+    async fn generate_with_provider(&self, provider: SharedProvider, mut request: GenerationRequest) -> Result<GenerationResponse> {
+        let provider_type = provider.get_provider_type();
+        let truncation = self.apply_truncation(&provider, &mut request).await?;
+
+        let (retry_config, cache_settings) = {
+            let config = self.config.read().await;
+            let cache_settings = (config.cache_responses && !request.stream)
+                .then(|| (config.cache_size, Duration::from_secs(config.cache_ttl_seconds)));
+            (config.retry.clone(), cache_settings)
+        };
+
+        let cache_key = match cache_settings {
+            Some((size, ttl)) => {
+                let key = ResponseCache::key(&provider_type, &request);
+                let mut cache = self.response_cache.lock().await;
+                cache.configure(size, ttl);
+                if let Some(mut cached) = cache.get(key) {
+                    cached.cached = true;
+                    tracing::debug!("Served generation from the response cache");
+                    return Ok(cached);
+                }
+                Some(key)
+            }
+            None => None,
+        };
+
+        let generation_type = request.metadata.get("generation_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("case_study")
+            .to_string();
+
+        let mut response = match retry::with_retry(&retry_config, |_| self.generate_once(&provider, request.clone())).await {
+            Ok(response) => response,
+            Err(e) if e.is_retryable() => self.generate_with_fallback(&provider_type, &request, &retry_config, e).await?,
+            Err(e) => {
+                tracing::warn!(error = %e, "Generation failed");
+                return Err(e);
+            }
+        };
+        response.truncation = truncation;
+        tracing::info!(
+            provider = ?response.provider,
+            prompt_tokens = response.usage.as_ref().map(|u| u.prompt_tokens),
+            completion_tokens = response.usage.as_ref().map(|u| u.completion_tokens),
+            "Generation finished"
+        );
+
+        // Usage tracking must never fail an otherwise successful generation
+        if let Err(e) = self.record_generation(&response, &generation_type).await {
+            tracing::warn!("Could not record generation usage: {}", e);
+        }
 
-        provider.generate(request).await
+        // Fallback responses are keyed under the primary provider, so don't cache them
+        if let Some(key) = cache_key.filter(|_| !response.metadata.contains_key("fallback_from")) {
+            self.response_cache.lock().await.insert(key, response.clone());
+        }
+        Ok(response)
+    }
+
+    /// Try each configured fallback provider in order after the `primary` provider failed
+    async fn generate_with_fallback(
+        &self,
+        primary: &ProviderType,
+        request: &GenerationRequest,
+        retry_config: &RetryConfig,
+        primary_error: AIError,
+    ) -> Result<GenerationResponse> {
+        let fallbacks = self.config.read().await.fallback_providers.clone();
+        let mut last_error = primary_error;
+
+        for provider_type in fallbacks {
+            if *primary == provider_type {
+                continue;
+            }
+
+            match self.generate_on_fallback(&provider_type, request, retry_config).await {
+                Ok(mut response) => {
+                    response.metadata.insert("fallback_from".to_string(), serde_json::json!(primary.to_string()));
+                    return Ok(response);
+                }
+                Err(e) => {
+                    tracing::warn!("Fallback provider {} failed: {}", provider_type, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Generate on a fallback provider once it passes a health check
+    async fn generate_on_fallback(
+        &self,
+        provider_type: &ProviderType,
+        request: &GenerationRequest,
+        retry_config: &RetryConfig,
+    ) -> Result<GenerationResponse> {
+        let provider = self.fallback_provider(provider_type).await?;
+        providers::ensure_tool_support(&*provider, request)?;
+        let timeout = self.request_timeout(provider_type).await;
+        if !with_timeout(timeout, provider.health_check()).await? {
+            return Err(AIError::ServiceUnavailable(format!("Provider {} failed health check", provider_type)));
+        }
+
+        // The requested model usually belongs to the primary provider
+        let mut request = request.clone();
+        if provider.validate_model(&request.model).is_err() {
+            request.model = provider.get_default_model().to_string();
+        }
+
+        let (request, provider) = (&request, &provider);
+        let mut response = retry::with_retry(retry_config, |_| async move {
+            self.acquire_rate_limit(provider_type).await?;
+            let span = tracing::info_span!("ai.provider_call", provider = %provider_type, fallback = true);
+            with_timeout(timeout, provider.generate(request.clone())).instrument(span).await
+        }).await?;
+        response.provider = Some(provider.get_provider_type());
+        Ok(response)
+    }
+
+    /// Get or create the provider instance used for fallback
+    async fn fallback_provider(&self, provider_type: &ProviderType) -> Result<SharedProvider> {
+        if let Some(provider) = self.fallback_instances.read().await.get(provider_type) {
+            return Ok(provider.clone());
+        }
+
+        let provider_config = {
+            let config = self.config.read().await;
+            config.get_provider_config(provider_type)
+                .filter(|c| c.enabled)
+                .cloned()
+                .ok_or_else(|| AIError::ConfigurationError(format!("Provider {} not configured", provider_type)))?
+        };
+
+        let provider: SharedProvider = Arc::from(providers::create_provider(provider_type.clone(), provider_config).await?);
+        self.fallback_instances.write().await.insert(provider_type.clone(), provider.clone());
+        Ok(provider)
+    }
+
+    /// Install a fallback provider instance directly, bypassing configuration
+    #[cfg(test)]
+    pub(crate) async fn set_fallback_provider(&self, provider: Box<dyn AIProvider + Send + Sync>) {
+        let provider_type = provider.get_provider_type();
+        self.fallback_instances.write().await.insert(provider_type, Arc::from(provider));
+    }
+
+    /// Shrink the request to fit the model's context window minus `max_tokens`,
+    /// using the request's truncation strategy. Summaries are written by `provider`.
+    async fn apply_truncation(&self, provider: &SharedProvider, request: &mut GenerationRequest) -> Result<Option<TruncationReport>> {
+        if request.truncation == TruncationStrategy::None {
+            return Ok(None);
+        }
+        let Some(context_length) = self.context_length(&request.model).await else {
+            return Ok(None);
+        };
+        let budget = context_length.saturating_sub(request.params.max_tokens.unwrap_or(0));
+        let provider_type = provider.get_provider_type();
+
+        if request.truncation == TruncationStrategy::SummarizeThenTruncate {
+            return self.summarize_then_truncate(provider, request, budget).await;
+        }
+        truncation::truncate_request(&provider_type, request, budget, request.truncation)
+    }
+
+    /// Replace the oldest messages that don't fit with a model-written summary,
+    /// then truncate whatever still exceeds the budget
+    async fn summarize_then_truncate(
+        &self,
+        provider: &SharedProvider,
+        request: &mut GenerationRequest,
+        budget: u32,
+    ) -> Result<Option<TruncationReport>> {
+        let provider_type = &provider.get_provider_type();
+        let original_tokens = tokens::count_request_tokens(provider_type, request).prompt_tokens;
+        if original_tokens <= budget {
+            return Ok(None);
+        }
+
+        let dropped = truncation::drop_oldest(provider_type, request, budget);
+        if !dropped.is_empty() {
+            let transcript = dropped.iter()
+                .map(|m| format!("{}: {}", m.role, m.content))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            // Keep the summarization prompt itself well inside the window
+            let mut summary_request = GenerationRequest::new(
+                vec![
+                    models::ChatMessage::system("Summarize the following conversation excerpt concisely, keeping facts needed to continue the task."),
+                    models::ChatMessage::user(transcript),
+                ],
+                request.model.clone(),
+            );
+            truncation::truncate_request(provider_type, &mut summary_request, budget / 2, TruncationStrategy::TruncateOldest)?;
+
+            match self.generate_once(provider, summary_request).await {
+                Ok(summary) => {
+                    let insert_at = request.messages.iter()
+                        .position(|m| m.role != models::MessageRole::System)
+                        .unwrap_or(request.messages.len());
+                    request.messages.insert(
+                        insert_at,
+                        models::ChatMessage::user(format!("Summary of earlier context: {}", summary.content)),
+                    );
+                }
+                Err(e) => tracing::warn!("Could not summarize truncated context: {}", e),
+            }
+        }
+
+        truncation::truncate_request(provider_type, request, budget, TruncationStrategy::TruncateOldest)?;
+        let final_tokens = tokens::count_request_tokens(provider_type, request).prompt_tokens;
+        Ok(Some(TruncationReport {
+            strategy: TruncationStrategy::SummarizeThenTruncate,
+            original_tokens,
+            final_tokens,
+            trimmed_tokens: original_tokens.saturating_sub(final_tokens),
+        }))
+    }
+
+    /// Drop all cached generation responses
+    pub async fn clear_cache(&self) {
+        self.response_cache.lock().await.clear();
+    }
+
+    /// Single generation attempt against `provider`
+    async fn generate_once(&self, provider: &SharedProvider, request: GenerationRequest) -> Result<GenerationResponse> {
+        let provider_type = provider.get_provider_type();
+        self.acquire_rate_limit(&provider_type).await?;
+
+        providers::ensure_tool_support(&**provider, &request)?;
+        let timeout = self.request_timeout(&provider_type).await;
+        let span = tracing::info_span!("ai.provider_call", provider = %provider_type, fallback = false);
+        let mut response = with_timeout(timeout, provider.generate(request)).instrument(span).await?;
+        response.provider = Some(provider_type);
+        Ok(response)
+    }
+
+    /// Persist provider, token usage, cost and timing of a generation to history
+    pub async fn record_generation(&self, response: &GenerationResponse, generation_type: &str) -> Result<GenerationHistory> {
+        let provider = match &response.provider {
+            Some(provider) => provider.to_string(),
+            None => self.config.read().await.default_provider.to_string(),
+        };
+
+        let (prompt_tokens, completion_tokens) = response.usage.as_ref()
+            .map(|u| (u.prompt_tokens, u.completion_tokens))
+            .unwrap_or((0, 0));
+
+        let estimated_cost = {
+            let manager = self.model_config_manager.read().await;
+            manager.get_model_config(&response.model)
+                .map(|model| manager.estimate_model_cost(model, prompt_tokens, completion_tokens))
+        };
+
+        self.history_repository.create(NewGenerationHistory {
+            case_study_id: None,
+            generation_type: generation_type.to_string(),
+            prompt_template: None,
+            user_input: None,
+            ai_provider: Some(provider),
+            model_name: Some(response.model.clone()),
+            prompt_tokens: Some(prompt_tokens as i64),
+            completion_tokens: Some(completion_tokens as i64),
+            generation_time_ms: Some(response.response_time_ms as i64),
+            success: Some(true),
+            error_message: None,
+            created_by: None,
+            estimated_cost,
+        })
+        .await
+        .map_err(|e| AIError::Unknown(format!("Failed to record generation: {}", e)))
+    }
+
+    /// Get total generation cost grouped by provider and by day
+    pub async fn get_generation_cost_summary(&self) -> Result<GenerationCostSummary> {
+        self.history_repository.cost_summary().await
+            .map_err(|e| AIError::Unknown(format!("Failed to load generation costs: {}", e)))
     }
 
     /// Generate content with streaming response
     pub async fn generate_stream(&self, request: GenerationRequest) -> Result<Box<dyn futures::Stream<Item = Result<StreamResponse>> + Unpin + Send>> {
-        let provider = self.active_provider.read().await;
-        let provider = provider.as_ref()
-            .ok_or_else(|| AIError::ProviderNotInitialized)?;
+        if !request.tools.is_empty() {
+            return Err(AIError::InvalidRequest("Tool calls are not supported for streaming requests".to_string()));
+        }
+        let provider = self.current_provider().await?;
+        let provider_type = provider.get_provider_type();
+        self.acquire_rate_limit(&provider_type).await?;
 
-        provider.generate_stream(request).await
+        let timeout = self.request_timeout(&provider_type).await;
+        with_timeout(timeout, provider.generate_stream(request)).await
     }
 
-    /// Get available models for the active provider
-    pub async fn get_available_models(&self) -> Result<Vec<ModelInfo>> {
-        let provider = self.active_provider.read().await;
-        let provider = provider.as_ref()
-            .ok_or_else(|| AIError::ProviderNotInitialized)?;
+    /// Take a slot from the provider's rate limiter, waiting or failing with
+    /// `AIError::RateLimited` according to its `rate_limit_mode`
+    async fn acquire_rate_limit(&self, provider_type: &ProviderType) -> Result<()> {
+        let provider_config = self.config.read().await.get_provider_config(provider_type).cloned();
+        match provider_config {
+            Some(provider_config) => self.rate_limiter.acquire(&provider_config).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Configured per-request timeout for a provider
+    async fn request_timeout(&self, provider_type: &ProviderType) -> Duration {
+        let config = self.config.read().await;
+        config.get_provider_config(provider_type)
+            .map(|c| c.request_timeout())
+            .unwrap_or_else(|| Duration::from_secs(config.global_timeout_seconds))
+    }
+
+    /// Minimum spacing between requests to the active provider, from its configured
+    /// requests-per-minute limit. `None` when the provider is unlimited.
+    pub async fn request_interval(&self) -> Option<Duration> {
+        let provider_type = self.active_provider_type().await.ok()?;
+        let config = self.config.read().await;
+        config.get_provider_config(&provider_type)
+            .and_then(|c| c.rate_limit_requests_per_minute)
+            .filter(|&rpm| rpm > 0)
+            .map(|rpm| Duration::from_secs(60) / rpm)
+    }
+
+    /// Generate like `generate`, but cancellable by `request_id` via `cancel_generation`.
+    /// Cancelling drops the in-flight provider request and returns `AIError::Cancelled`.
+    pub async fn generate_cancellable(&self, request: GenerationRequest, request_id: &str) -> Result<GenerationResponse> {
+        let cancelled = self.register_request(request_id).await;
+        let result = tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => Err(AIError::Cancelled),
+            result = self.generate(request) => result,
+        };
+        self.active_requests.lock().await.remove(request_id);
+        result
+    }
+
+    /// Stream content into `sink`, cancellable by id via `cancel_generation`.
+    /// Streaming always bypasses the response cache.
+    pub async fn stream_to_sink(&self, request: GenerationRequest, stream_id: &str, sink: &dyn StreamEventSink) -> Result<StreamEvent> {
+        let cancelled = self.register_request(stream_id).await;
+
+        let opened = tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => Err(AIError::Cancelled),
+            stream = self.generate_stream(request) => stream,
+        };
+        let result = match opened {
+            Ok(stream) => streaming::forward_stream(stream, sink, &cancelled).await,
+            Err(e) => {
+                sink.send(&StreamEvent::Error { message: e.to_string() });
+                Err(e)
+            }
+        };
+
+        self.active_requests.lock().await.remove(stream_id);
+        result
+    }
+
+    /// Track a new cancellable request under `request_id`
+    async fn register_request(&self, request_id: &str) -> CancellationToken {
+        let cancelled = CancellationToken::new();
+        self.active_requests.lock().await.insert(request_id.to_string(), cancelled.clone());
+        cancelled
+    }
+
+    /// Cancel an in-flight generation or stream; returns false if no such request is running
+    pub async fn cancel_generation(&self, request_id: &str) -> bool {
+        match self.active_requests.lock().await.get(request_id) {
+            Some(cancelled) => {
+                cancelled.cancel();
+                true
+            }
+            None => false,
+        }
+    }
 
-        provider.get_models().await
+    /// Cancel an in-flight stream; returns false if no such stream is running
+    pub async fn cancel_stream(&self, stream_id: &str) -> bool {
+        self.cancel_generation(stream_id).await
+    }
+
+    /// Get available models for the active provider. Lists are cached per provider for
+    /// `model_list_ttl_seconds`; `force_refresh` fetches a new list regardless.
+    pub async fn get_available_models(&self, force_refresh: bool) -> Result<Vec<ModelInfo>> {
+        let ttl = Duration::from_secs(self.config.read().await.model_list_ttl_seconds);
+        let provider = self.current_provider().await?;
+        let provider_type = provider.get_provider_type();
+
+        if !force_refresh {
+            let cache = self.model_list_cache.lock().await;
+            if let Some(cached) = cache.get(&provider_type).filter(|cached| cached.fetched.elapsed() < ttl) {
+                return Ok(cached.models.clone());
+            }
+        }
+
+        // The fetch is a network call, so the cache is not held across it
+        let models = provider.get_models().await?;
+        self.model_list_cache.lock().await.insert(provider_type, CachedModelList {
+            models: models.clone(),
+            fetched: std::time::Instant::now(),
+        });
+        Ok(models)
     }
 
     /// Get current configuration
@@ -153,14 +655,143 @@ impl AIManager {
         self.config_repository.save_config(&config).await
             .map_err(|e| AIError::ConfigurationError(format!("Failed to save config: {}", e)))?;
         
-        // Update in-memory config
+        // Update in-memory config; fallback providers and model lists are rebuilt from it on demand
         *self.config.write().await = config;
+        self.fallback_instances.write().await.clear();
+        self.model_list_cache.lock().await.clear();
         Ok(())
     }
 
-    /// Get prompt manager for template operations
+    /// Reload the saved configuration and reconnect its default provider, e.g. once
+    /// encryption is unlocked and the stored API keys can be decrypted
+    pub async fn reload_config(&self) -> Result<()> {
+        let config = self.config_repository.load_config().await
+            .map_err(|e| AIError::ConfigurationError(format!("Failed to load config: {}", e)))?;
+        let Some(config) = config else {
+            return Ok(());
+        };
+
+        let default_provider = config.default_provider.clone();
+        *self.config.write().await = config;
+        self.fallback_instances.write().await.clear();
+        self.model_list_cache.lock().await.clear();
+        self.switch_provider(&default_provider).await
+    }
+
+    /// Get a snapshot of the prompt manager for template operations
     pub fn get_prompt_manager(&self) -> Arc<PromptManager> {
-        self.prompt_manager.clone()
+        self.prompt_manager.read().unwrap().clone()
+    }
+
+    /// Apply a change to the prompt manager, publishing a new snapshot
+    fn modify_prompt_manager<T>(&self, change: impl FnOnce(&mut PromptManager) -> T) -> T {
+        let mut current = self.prompt_manager.write().unwrap();
+        let mut next = PromptManager::clone(&current);
+        let result = change(&mut next);
+        *current = Arc::new(next);
+        result
+    }
+
+    /// Load active user templates from the database over the built-in defaults
+    pub async fn load_persisted_templates(&self) -> Result<usize> {
+        let records = self.template_repository.list_active().await
+            .map_err(|e| AIError::TemplateError(format!("Failed to load templates: {}", e)))?;
+
+        let mut templates = Vec::new();
+        for record in records.iter().filter(|r| !r.is_system_template) {
+            match PromptTemplate::from_record(record) {
+                Ok(template) => templates.push(template),
+                Err(e) => tracing::warn!("Skipping unreadable template '{}': {}", record.template_id, e),
+            }
+        }
+
+        let count = templates.len();
+        self.modify_prompt_manager(|manager| {
+            for template in templates {
+                manager.add_template(template);
+            }
+        });
+        Ok(count)
+    }
+
+    /// Persist a new user template. A template with a built-in's id overrides that built-in.
+    pub async fn create_prompt_template(&self, template: PromptTemplate) -> Result<PromptTemplate> {
+        if self.template_repository.find_by_template_id(&template.id).await
+            .map_err(|e| AIError::TemplateError(format!("Failed to look up template: {}", e)))?
+            .is_some()
+        {
+            return Err(AIError::TemplateError(format!("Template '{}' already exists", template.id)));
+        }
+
+        let record = self.template_repository.create(template.to_new_record()?).await
+            .map_err(|e| AIError::TemplateError(format!("Failed to save template: {}", e)))?;
+        let saved = PromptTemplate::from_record(&record)?;
+
+        self.modify_prompt_manager(|manager| manager.add_template(saved.clone()));
+        Ok(saved)
+    }
+
+    /// Overwrite a persisted user template
+    pub async fn update_prompt_template(&self, template: PromptTemplate) -> Result<PromptTemplate> {
+        let record = self.template_repository.update(&template.id, template.to_update_record()?).await
+            .map_err(|e| AIError::TemplateError(format!("Failed to update template: {}", e)))?
+            .ok_or_else(|| AIError::TemplateError(format!("User template '{}' not found", template.id)))?;
+        let saved = PromptTemplate::from_record(&record)?;
+
+        self.modify_prompt_manager(|manager| manager.add_template(saved.clone()));
+        Ok(saved)
+    }
+
+    /// Validate and import templates from JSON, persisting each valid one as a user template.
+    /// Templates whose id is already persisted are overwritten.
+    pub async fn import_prompt_templates(&self, json: &str) -> Result<TemplateImportReport> {
+        let (templates, mut skipped) = PromptManager::validate_import(json)?;
+
+        let mut imported = Vec::new();
+        for (index, template) in templates {
+            let exists = self.template_repository.find_by_template_id(&template.id).await
+                .map_err(|e| AIError::TemplateError(format!("Failed to look up template: {}", e)))?
+                .is_some();
+            let saved = if exists {
+                self.template_repository.update(&template.id, template.to_update_record()?).await
+                    .and_then(|record| record.ok_or(sqlx::Error::RowNotFound))
+            } else {
+                self.template_repository.create(template.to_new_record()?).await
+            };
+
+            match saved.map_err(|e| e.to_string()).and_then(|r| PromptTemplate::from_record(&r).map_err(|e| e.to_string())) {
+                Ok(saved) => imported.push(saved),
+                Err(e) => skipped.push(SkippedTemplate {
+                    index,
+                    template_id: Some(template.id),
+                    errors: vec![format!("Failed to save template: {}", e)],
+                }),
+            }
+        }
+
+        let imported_ids: Vec<String> = imported.iter().map(|t| t.id.clone()).collect();
+        self.modify_prompt_manager(|manager| {
+            for template in imported {
+                manager.add_template(template);
+            }
+        });
+
+        Ok(TemplateImportReport {
+            imported: imported_ids.len(),
+            imported_ids,
+            skipped,
+        })
+    }
+
+    /// Delete a user template; a built-in with the same id becomes active again
+    pub async fn delete_prompt_template(&self, template_id: &str) -> Result<bool> {
+        let deleted = self.template_repository.hard_delete(template_id).await
+            .map_err(|e| AIError::TemplateError(format!("Failed to delete template: {}", e)))?;
+
+        if deleted {
+            self.modify_prompt_manager(|manager| manager.remove_user_template(template_id));
+        }
+        Ok(deleted)
     }
 
     /// Validate provider configuration
@@ -169,27 +800,139 @@ impl AIManager {
         let provider_config = config.get_provider_config(provider_type)
             .ok_or_else(|| AIError::ConfigurationError(format!("Provider {} not configured", provider_type)))?;
 
+        let timeout = provider_config.request_timeout();
         let provider = providers::create_provider(provider_type.clone(), provider_config.clone()).await?;
-        provider.health_check().await
+        drop(config);
+        with_timeout(timeout, provider.health_check()).await
+    }
+
+    /// Health-check every configured provider concurrently. Each check is bounded by that
+    /// provider's request timeout, so a slow provider only delays its own entry.
+    pub async fn check_all_providers(&self) -> HashMap<ProviderType, ProviderHealth> {
+        let provider_types: Vec<ProviderType> = self.config.read().await.providers.keys().cloned().collect();
+        let checks = provider_types.into_iter().map(|provider_type| async move {
+            let health = self.timed_health_check(&provider_type).await;
+            (provider_type, health)
+        });
+        futures::future::join_all(checks).await.into_iter().collect()
+    }
+
+    /// Health-check one provider, recording how long the check took
+    async fn timed_health_check(&self, provider_type: &ProviderType) -> ProviderHealth {
+        let started = std::time::Instant::now();
+        let result = self.health_check_provider(provider_type).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(healthy) => ProviderHealth {
+                healthy,
+                latency_ms,
+                error: (!healthy).then(|| format!("Provider {} failed health check", provider_type)),
+            },
+            Err(e) => ProviderHealth { healthy: false, latency_ms, error: Some(e.to_string()) },
+        }
     }
 
-    /// Get provider capabilities
+    /// Health-check one provider, reusing the active or cached fallback instance when there is one
+    async fn health_check_provider(&self, provider_type: &ProviderType) -> Result<bool> {
+        let enabled = self.config.read().await
+            .get_provider_config(provider_type)
+            .map(|c| c.enabled)
+            .unwrap_or(false);
+        if !enabled {
+            return Err(AIError::ConfigurationError(format!("Provider {} is disabled", provider_type)));
+        }
+
+        let timeout = self.request_timeout(provider_type).await;
+        if let Some(active) = self.current_provider().await.ok().filter(|p| p.get_provider_type() == *provider_type) {
+            return with_timeout(timeout, active.health_check()).await;
+        }
+
+        let provider = self.fallback_provider(provider_type).await?;
+        with_timeout(timeout, provider.health_check()).await
+    }
+
+    /// Get a configured provider's capabilities. Doesn't contact the provider, so it
+    /// also works while the provider is unreachable.
     pub async fn get_provider_capabilities(&self, provider_type: &ProviderType) -> Result<ProviderCapabilities> {
-        let config = self.config.read().await;
-        let provider_config = config.get_provider_config(provider_type)
-            .ok_or_else(|| AIError::ConfigurationError(format!("Provider {} not configured", provider_type)))?;
+        if self.config.read().await.get_provider_config(provider_type).is_none() {
+            return Err(AIError::ConfigurationError(format!(
+                "Provider {} is not configured; add it in the AI settings first", provider_type
+            )));
+        }
+        Ok(providers::capabilities_for(provider_type))
+    }
 
-        let provider = providers::create_provider(provider_type.clone(), provider_config.clone()).await?;
-        Ok(provider.get_capabilities())
+    /// Get the active provider's capabilities
+    pub async fn get_active_provider_capabilities(&self) -> Result<ProviderCapabilities> {
+        Ok(self.current_provider().await?.get_capabilities())
     }
 
     /// Get generation statistics
     pub async fn get_stats(&self) -> Result<GenerationStats> {
-        let provider = self.active_provider.read().await;
-        let provider = provider.as_ref()
-            .ok_or_else(|| AIError::ProviderNotInitialized)?;
+        self.current_provider().await?.get_stats().await
+    }
+
+    /// Generation stats with the active provider's health and model count, and which
+    /// provider is configured versus active. The health check and model count are
+    /// reused for `HEALTH_CACHE_TTL` so polling doesn't probe the provider every call.
+    pub async fn get_status(&self) -> Result<AIStatus> {
+        let configured_provider = self.config.read().await.default_provider.clone();
+        let Ok(provider) = self.current_provider().await else {
+            return Ok(AIStatus {
+                stats: GenerationStats::default(),
+                configured_provider,
+                active_provider: None,
+                health: None,
+                health_checked_at: None,
+                model_count: None,
+            });
+        };
+        let provider_type = provider.get_provider_type();
+        let stats = provider.get_stats().await?;
+
+        let cached = self.health_cache.lock().await.as_ref()
+            .filter(|cached| cached.provider_type == provider_type && cached.probed.elapsed() < HEALTH_CACHE_TTL)
+            .map(|cached| (cached.health.clone(), cached.checked_at, cached.model_count));
+
+        // The probes are network calls, so the cache is not held across them
+        let (health, checked_at, model_count) = match cached {
+            Some(cached) => cached,
+            None => {
+                let health = self.timed_health_check(&provider_type).await;
+                let model_count = self.active_model_count(&provider).await;
+                let checked_at = chrono::Utc::now();
+                *self.health_cache.lock().await = Some(CachedHealth {
+                    provider_type: provider_type.clone(),
+                    health: health.clone(),
+                    model_count,
+                    checked_at,
+                    probed: std::time::Instant::now(),
+                });
+                (health, checked_at, model_count)
+            }
+        };
+
+        Ok(AIStatus {
+            stats,
+            configured_provider,
+            active_provider: Some(provider_type),
+            health: Some(health),
+            health_checked_at: Some(checked_at),
+            model_count,
+        })
+    }
+
+    /// Number of models the active provider lists, or `None` if listing fails
+    async fn active_model_count(&self, provider: &SharedProvider) -> Option<usize> {
+        let timeout = self.request_timeout(&provider.get_provider_type()).await;
+        with_timeout(timeout, provider.get_models()).await.ok().map(|models| models.len())
+    }
 
-        provider.get_stats().await
+    /// Resolve a request builder against the current configuration and model registry
+    pub async fn build_request(&self, builder: GenerationRequestBuilder) -> Result<GenerationRequest> {
+        let config = self.config.read().await;
+        let models = self.model_config_manager.read().await;
+        builder.build(&config, &models)
     }
 
     /// Get model configuration manager
@@ -221,6 +964,11 @@ impl AIManager {
         manager.get_recommended_models(use_case).into_iter().cloned().collect()
     }
 
+    /// Whether the registry lists `format` among the model's supported output formats
+    pub async fn model_supports_format(&self, model_id: &str, format: &str) -> bool {
+        self.model_config_manager.read().await.supports_format(model_id, format)
+    }
+
     /// Validate generation parameters for a specific model
     pub async fn validate_model_parameters(&self, model_id: &str, params: &models::GenerationParams) -> Result<()> {
         let manager = self.model_config_manager.read().await;
@@ -233,6 +981,50 @@ impl AIManager {
         manager.adjust_parameters(model_id, params)
     }
 
+    /// Estimate the prompt tokens a request will use on the active provider
+    pub async fn count_tokens(&self, request: &GenerationRequest) -> Result<TokenCount> {
+        let provider_type = match self.active_provider_type().await {
+            Ok(provider_type) => provider_type,
+            Err(_) => self.config.read().await.default_provider.clone(),
+        };
+        Ok(tokens::count_request_tokens(&provider_type, request))
+    }
+
+    /// Context window of a model, from the model registry or provider configuration
+    pub async fn context_length(&self, model_id: &str) -> Option<u32> {
+        if let Some(length) = self.model_config_manager.read().await
+            .get_model_config(model_id)
+            .and_then(|m| m.context_length)
+        {
+            return Some(length);
+        }
+
+        let config = self.config.read().await;
+        config.providers.values()
+            .find_map(|p| p.get_model(model_id))
+            .and_then(|m| m.context_length)
+    }
+
+    /// Warnings for a request whose prompt plus `max_tokens` would exceed the model's context window
+    pub async fn check_context_window(&self, request: &GenerationRequest) -> Result<Vec<String>> {
+        let mut warnings = Vec::new();
+        let count = self.count_tokens(request).await?;
+        let max_tokens = request.params.max_tokens.unwrap_or(0);
+
+        match self.context_length(&request.model).await {
+            Some(context_length) if count.prompt_tokens + max_tokens > context_length => {
+                warnings.push(format!(
+                    "Prompt uses ~{} tokens plus {} reserved for the response, exceeding the {} token context window of '{}'",
+                    count.prompt_tokens, max_tokens, context_length, request.model
+                ));
+            }
+            Some(_) => {}
+            None => warnings.push(format!("Context window for model '{}' is unknown", request.model)),
+        }
+
+        Ok(warnings)
+    }
+
     /// Estimate cost for a generation request
     pub async fn estimate_generation_cost(&self, model_id: &str, input_tokens: u32, estimated_output_tokens: u32) -> Result<f64> {
         let manager = self.model_config_manager.read().await;
@@ -248,6 +1040,17 @@ impl AIManager {
         manager.update_model_availability(model_id, available);
     }
 
+    /// Query the Ollama server for installed models and sync registry availability with it
+    pub async fn refresh_ollama_availability(&self) -> Result<ModelAvailabilitySync> {
+        let installed = match self.current_provider().await {
+            Ok(provider) if provider.get_provider_type() == ProviderType::Ollama => provider.get_models().await?,
+            _ => self.fallback_provider(&ProviderType::Ollama).await?.get_models().await?,
+        };
+
+        let mut manager = self.model_config_manager.write().await;
+        Ok(manager.sync_installed_models(&ProviderType::Ollama, &installed))
+    }
+
     /// Generate content with dynamic model selection
     pub async fn generate_with_auto_model(&self, mut request: GenerationRequest, criteria: ModelSelectionCriteria) -> Result<GenerationResponse> {
         // Select the best model based on criteria
@@ -259,15 +1062,15 @@ impl AIManager {
         // Adjust parameters to fit model constraints
         request.params = self.adjust_model_parameters(&model_config.id, &request.params).await?;
         
-        // Switch to the model's provider if needed
-        let current_config = self.config.read().await;
-        if current_config.default_provider != model_config.provider {
-            drop(current_config);
-            self.switch_provider(&model_config.provider).await?;
-        }
-        
+        // Use the active provider if it serves the model, switching to the model's provider otherwise.
+        // The request stays on this instance even if another switch happens meanwhile.
+        let provider = match self.current_provider().await {
+            Ok(provider) if provider.get_provider_type() == model_config.provider => provider,
+            _ => self.install_provider(&model_config.provider).await?,
+        };
+
         // Generate with the selected model
-        self.generate(request).await
+        self.generate_with_provider(provider, request).await
     }
 
     /// Create a case study generator instance
@@ -275,6 +1078,18 @@ impl AIManager {
         case_study_generator::CaseStudyGenerator::new(self.clone())
     }
 
+    /// Generate several case studies concurrently; see `CaseStudyGenerator::generate_case_studies_batch`
+    pub async fn generate_case_studies_batch(
+        &self,
+        params: Vec<case_study_generator::CaseStudyGenerationParams>,
+        concurrency: usize,
+        progress: &dyn case_study_generator::BatchProgressSink,
+    ) -> Vec<Result<case_study_generator::GeneratedCaseStudy>> {
+        self.create_case_study_generator()
+            .generate_case_studies_batch(params, concurrency, progress)
+            .await
+    }
+
     /// Create a question generator instance
     pub fn create_question_generator(&self) -> question_generator::QuestionGenerator {
         question_generator::QuestionGenerator::new(self.clone())
@@ -285,15 +1100,659 @@ impl AIManager {
 mod tests {
     use super::*;
 
+    use crate::ai::models::{ChatMessage, GenerationParams};
+    use crate::ai::providers::mock::MockProvider;
+    use std::sync::atomic::Ordering;
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(include_str!("../../schema.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to create schema");
+        sqlx::query(include_str!("../database/migrations/013_generation_cost_tracking.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to add cost tracking");
+        sqlx::query(include_str!("../database/migrations/009_create_prompt_templates.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to create prompt templates");
+        pool
+    }
+
+    async fn test_manager(provider: MockProvider) -> AIManager {
+        let manager = AIManager::detached(create_test_pool().await);
+        manager.set_active_provider(Box::new(provider)).await;
+        manager
+    }
+
+    fn request(content: &str) -> GenerationRequest {
+        GenerationRequest::new(vec![ChatMessage::user(content)], "mock-model")
+    }
+
     #[test]
     fn test_ai_manager_creation() {
         // Mock app handle would be needed for real testing
         // This is a placeholder for when we implement proper testing
     }
 
+    #[tokio::test]
+    async fn test_identical_request_hits_cache() {
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let calls = provider.call_counter();
+        let manager = test_manager(provider).await;
+        manager.config.write().await.cache_responses = true;
+
+        let first = manager.generate(request("Write a case study")).await.unwrap();
+        let second = manager.generate(request("Write a case study")).await.unwrap();
+
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(first.content, second.content);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_require_function_support() {
+        let weather = ToolDefinition::new("get_weather", "Current weather for a city", serde_json::json!({ "type": "object" }));
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({ "city": "Perth" }),
+        };
+
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let calls = provider.call_counter();
+        let manager = test_manager(provider).await;
+        let error = manager.generate(request("Weather?").with_tools(vec![weather.clone()])).await.unwrap_err();
+        assert!(matches!(error, AIError::InvalidRequest(ref message) if message.contains("tool calling")));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let manager = test_manager(
+            MockProvider::new(ProviderType::OpenAI).with_functions().respond_with_tool_calls(vec![call.clone()]),
+        )
+        .await;
+        let response = manager.generate(request("Weather?").with_tools(vec![weather])).await.unwrap();
+        assert_eq!(response.tool_calls, vec![call]);
+    }
+
+    #[tokio::test]
+    async fn test_differing_params_miss_cache() {
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let calls = provider.call_counter();
+        let manager = test_manager(provider).await;
+        manager.config.write().await.cache_responses = true;
+
+        manager.generate(request("Write a case study")).await.unwrap();
+        let varied = request("Write a case study").with_params(GenerationParams {
+            temperature: Some(0.2),
+            ..GenerationParams::default()
+        });
+        let response = manager.generate(varied).await.unwrap();
+
+        assert!(!response.cached);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_disabled_and_cleared() {
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let calls = provider.call_counter();
+        let manager = test_manager(provider).await;
+
+        manager.generate(request("Hello")).await.unwrap();
+        manager.generate(request("Hello")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        manager.config.write().await.cache_responses = true;
+        manager.generate(request("Hello")).await.unwrap();
+        manager.clear_cache().await;
+        let response = manager.generate(request("Hello")).await.unwrap();
+        assert!(!response.cached);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_healthy_secondary() {
+        let primary = MockProvider::new(ProviderType::Ollama)
+            .fail_with(AIError::NetworkError("connection refused".to_string()));
+        let secondary = MockProvider::new(ProviderType::OpenAI).respond_with("from secondary");
+        let manager = test_manager(primary).await;
+        manager.set_fallback_provider(Box::new(secondary)).await;
+        {
+            let mut config = manager.config.write().await;
+            config.retry.max_attempts = 1;
+            config.fallback_providers = vec![ProviderType::OpenAI];
+        }
+
+        let response = manager.generate(request("Hello")).await.unwrap();
+
+        assert_eq!(response.content, "from secondary");
+        assert_eq!(response.provider, Some(ProviderType::OpenAI));
+        assert_eq!(response.metadata.get("fallback_from"), Some(&serde_json::json!("ollama")));
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_fallback_is_skipped() {
+        let primary = MockProvider::new(ProviderType::Ollama)
+            .fail_with(AIError::NetworkError("connection refused".to_string()));
+        let secondary = MockProvider::new(ProviderType::OpenAI).with_health(false);
+        let secondary_calls = secondary.call_counter();
+        let manager = test_manager(primary).await;
+        manager.set_fallback_provider(Box::new(secondary)).await;
+        {
+            let mut config = manager.config.write().await;
+            config.retry.max_attempts = 1;
+            config.fallback_providers = vec![ProviderType::OpenAI];
+        }
+
+        let result = manager.generate(request("Hello")).await;
+
+        assert!(matches!(result, Err(AIError::ServiceUnavailable(_))));
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_auth_error_does_not_fall_back() {
+        let primary = MockProvider::new(ProviderType::OpenAI)
+            .fail_with(AIError::AuthenticationError("bad key".to_string()));
+        let secondary = MockProvider::new(ProviderType::Ollama);
+        let secondary_calls = secondary.call_counter();
+        let manager = test_manager(primary).await;
+        manager.set_fallback_provider(Box::new(secondary)).await;
+        manager.config.write().await.fallback_providers = vec![ProviderType::Ollama];
+
+        let result = manager.generate(request("Hello")).await;
+
+        assert!(matches!(result, Err(AIError::AuthenticationError(_))));
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_with_timeout_returns_promptly() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_delay(Duration::from_secs(10));
+        let started = std::time::Instant::now();
+
+        let result = with_timeout(Duration::from_millis(50), provider.generate(request("Hello"))).await;
+
+        assert!(matches!(result, Err(AIError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_generate_times_out_on_slow_provider() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_delay(Duration::from_secs(30));
+        let manager = test_manager(provider).await;
+        {
+            let mut config = manager.config.write().await;
+            config.retry.max_attempts = 1;
+            config.providers.get_mut(&ProviderType::Ollama).unwrap().request_timeout_secs = 1;
+        }
+        let started = std::time::Instant::now();
+
+        let result = manager.generate(request("Hello")).await;
+
+        assert!(matches!(result, Err(AIError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_health_check_times_out() {
+        let primary = MockProvider::new(ProviderType::Ollama)
+            .fail_with(AIError::NetworkError("connection refused".to_string()));
+        let secondary = MockProvider::new(ProviderType::OpenAI).with_delay(Duration::from_secs(30));
+        let manager = test_manager(primary).await;
+        manager.set_fallback_provider(Box::new(secondary)).await;
+        {
+            let mut config = manager.config.write().await;
+            config.retry.max_attempts = 1;
+            config.fallback_providers = vec![ProviderType::OpenAI];
+            config.global_timeout_seconds = 1;
+        }
+
+        let result = manager.generate(request("Hello")).await;
+
+        assert!(matches!(result, Err(AIError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_switch_does_not_wait_for_or_tear_in_flight_generation() {
+        let slow = MockProvider::new(ProviderType::Ollama).with_delay(Duration::from_millis(300));
+        let manager = test_manager(slow).await;
+
+        let in_flight = {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.generate(request("Hello")).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        tokio::time::timeout(
+            Duration::from_millis(100),
+            manager.set_active_provider(Box::new(MockProvider::new(ProviderType::OpenAI))),
+        )
+        .await
+        .expect("switch waited for the in-flight generation");
+        assert_eq!(manager.active_provider_type().await.unwrap(), ProviderType::OpenAI);
+
+        // The in-flight request finishes on the provider it started with
+        let response = in_flight.await.unwrap().unwrap();
+        assert_eq!(response.provider, Some(ProviderType::Ollama));
+        let response = manager.generate(request("Hello again")).await.unwrap();
+        assert_eq!(response.provider, Some(ProviderType::OpenAI));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_interleaved_generate_and_switch() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+
+        let tasks: Vec<_> = (0..40).map(|i| {
+            let manager = manager.clone();
+            tokio::spawn(async move {
+                if i % 4 == 0 {
+                    let provider_type = if i % 8 == 0 { ProviderType::OpenAI } else { ProviderType::Ollama };
+                    let provider = MockProvider::new(provider_type).with_delay(Duration::from_millis(5));
+                    manager.set_active_provider(Box::new(provider)).await;
+                    None
+                } else {
+                    Some(manager.generate(request(&format!("Request {}", i))).await)
+                }
+            })
+        }).collect();
+
+        let results = tokio::time::timeout(Duration::from_secs(10), futures::future::join_all(tasks))
+            .await
+            .expect("generate and switch deadlocked");
+        for result in results {
+            if let Some(response) = result.expect("task panicked") {
+                assert!(response.unwrap().provider.is_some());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_respects_provider_rate_limit() {
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let calls = provider.call_counter();
+        let manager = test_manager(provider).await;
+        {
+            let mut config = manager.config.write().await;
+            let ollama = config.providers.get_mut(&ProviderType::Ollama).unwrap();
+            ollama.rate_limit_requests_per_minute = Some(600);
+            ollama.rate_limit_burst = Some(2);
+            ollama.rate_limit_mode = RateLimitMode::Reject;
+        }
+
+        // Clones share one limiter, so concurrent callers can't sidestep it
+        let other = manager.clone();
+        manager.generate(request("one")).await.unwrap();
+        other.generate(request("two")).await.unwrap();
+        let error = manager.generate(request("three")).await.unwrap_err();
+        assert!(matches!(error, AIError::RateLimited { .. }));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        manager.generate(request("four")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_slow_generation() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_delay(Duration::from_secs(30));
+        let manager = test_manager(provider).await;
+
+        let canceller = manager.clone();
+        tokio::spawn(async move {
+            // Wait until the request is registered before cancelling it
+            while !canceller.cancel_generation("slow").await {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+
+        let started = std::time::Instant::now();
+        let error = manager.generate_cancellable(request("Hi"), "slow").await.unwrap_err();
+        assert!(matches!(error, AIError::Cancelled));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!manager.cancel_generation("slow").await);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_after_completion_is_noop() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama).respond_with("done")).await;
+
+        let response = manager.generate_cancellable(request("Hi"), "quick").await.unwrap();
+        assert_eq!(response.content, "done");
+        assert!(!manager.cancel_generation("quick").await);
+        assert!(!manager.cancel_generation("never-started").await);
+    }
+
+    #[tokio::test]
+    async fn test_check_all_providers_reports_each_provider() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+        manager.set_fallback_provider(Box::new(MockProvider::new(ProviderType::OpenAI).with_health(false))).await;
+        manager.set_fallback_provider(Box::new(
+            MockProvider::new(ProviderType::Anthropic).with_delay(Duration::from_secs(30)),
+        )).await;
+        {
+            let mut config = manager.config.write().await;
+            config.providers.insert(ProviderType::OpenAI, ProviderConfig::openai("sk-test"));
+            let mut anthropic = ProviderConfig::anthropic("sk-ant-test");
+            anthropic.request_timeout_secs = 1;
+            config.providers.insert(ProviderType::Anthropic, anthropic);
+        }
+
+        let started = std::time::Instant::now();
+        let report = manager.check_all_providers().await;
+
+        assert_eq!(report.len(), 3);
+        let ollama = &report[&ProviderType::Ollama];
+        assert!(ollama.healthy);
+        assert!(ollama.error.is_none());
+
+        let openai = &report[&ProviderType::OpenAI];
+        assert!(!openai.healthy);
+        assert!(openai.error.as_deref().unwrap().contains("failed health check"));
+        // The slow provider doesn't hold up the others
+        assert!(openai.latency_ms < 1000);
+
+        let anthropic = &report[&ProviderType::Anthropic];
+        assert!(!anthropic.healthy);
+        assert!(anthropic.error.as_deref().unwrap().contains("timed out"));
+        assert!(anthropic.latency_ms >= 1000);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_status_combines_stats_health_and_models() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .with_models(&["llama3", "mistral", "phi3"])
+            .with_delay(Duration::from_millis(20));
+        let manager = test_manager(provider).await;
+        manager.config.write().await.default_provider = ProviderType::OpenAI;
+
+        let status = manager.get_status().await.unwrap();
+        assert_eq!(status.configured_provider, ProviderType::OpenAI);
+        assert_eq!(status.active_provider, Some(ProviderType::Ollama));
+        assert_eq!(status.model_count, Some(3));
+        let health = status.health.unwrap();
+        assert!(health.healthy);
+        assert!(health.latency_ms >= 20);
+
+        // The cached probe is reused rather than checking the provider again
+        let again = manager.get_status().await.unwrap();
+        assert_eq!(again.health_checked_at, status.health_checked_at);
+        assert_eq!(again.health.unwrap().latency_ms, health.latency_ms);
+
+        let idle = AIManager::detached(create_test_pool().await).get_status().await.unwrap();
+        assert_eq!(idle.active_provider, None);
+        assert!(idle.health.is_none() && idle.model_count.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_all_providers_skips_disabled_provider() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+        manager.set_fallback_provider(Box::new(MockProvider::new(ProviderType::OpenAI))).await;
+        let mut openai = ProviderConfig::openai("sk-test");
+        openai.enabled = false;
+        manager.config.write().await.providers.insert(ProviderType::OpenAI, openai);
+
+        let report = manager.check_all_providers().await;
+
+        let openai = &report[&ProviderType::OpenAI];
+        assert!(!openai.healthy);
+        assert!(openai.error.as_deref().unwrap().contains("disabled"));
+        assert!(report[&ProviderType::Ollama].healthy);
+    }
+
+    #[tokio::test]
+    async fn test_context_window_warning() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+
+        let small = GenerationRequest::new(vec![ChatMessage::user("Summarise this case")], "llama3.2");
+        assert!(manager.check_context_window(&small).await.unwrap().is_empty());
+
+        let oversized = GenerationRequest::new(vec![ChatMessage::user("market ".repeat(10_000))], "llama3.2");
+        let count = manager.count_tokens(&oversized).await.unwrap();
+        assert!(count.prompt_tokens > 8192);
+        assert_eq!(manager.check_context_window(&oversized).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_truncates_oversized_request() {
+        let provider = MockProvider::new(ProviderType::Ollama);
+        let manager = test_manager(provider).await;
+
+        let oversized = GenerationRequest::new(
+            vec![
+                ChatMessage::system("You write assessment questions."),
+                ChatMessage::user("market ".repeat(10_000)),
+            ],
+            "llama3.2",
+        )
+        .with_truncation(TruncationStrategy::TruncateOldest);
+
+        let response = manager.generate(oversized).await.unwrap();
+        let report = response.truncation.expect("request should have been truncated");
+
+        assert_eq!(report.strategy, TruncationStrategy::TruncateOldest);
+        assert!(report.final_tokens <= 8192 - 2048);
+        assert!(report.trimmed_tokens > 0);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_then_truncate_inserts_summary() {
+        let provider = MockProvider::new(ProviderType::Ollama).respond_with("Earlier parts covered logistics.");
+        let manager = test_manager(provider).await;
+
+        let mut messages = vec![ChatMessage::system("You write assessment questions.")];
+        for _ in 0..4 {
+            messages.push(ChatMessage::user("logistics ".repeat(2_000)));
+        }
+        messages.push(ChatMessage::user("Write three questions."));
+        let mut request = GenerationRequest::new(messages, "llama3.2")
+            .with_truncation(TruncationStrategy::SummarizeThenTruncate);
+
+        let provider = manager.current_provider().await.unwrap();
+        let report = manager.apply_truncation(&provider, &mut request).await.unwrap().unwrap();
+
+        assert_eq!(report.strategy, TruncationStrategy::SummarizeThenTruncate);
+        assert!(report.final_tokens <= 8192 - 2048);
+        assert_eq!(request.messages[0].content, "You write assessment questions.");
+        assert!(request.messages.iter().any(|m| m.content.contains("Earlier parts covered logistics.")));
+    }
+
+    #[tokio::test]
+    async fn test_model_list_is_cached_per_provider() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_models(&["llama3", "mistral"]);
+        let listed = provider.model_list_counter();
+        let manager = test_manager(provider).await;
+
+        let models = manager.get_available_models(false).await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(manager.get_available_models(false).await.unwrap().len(), 2);
+        assert_eq!(listed.load(Ordering::SeqCst), 1);
+
+        manager.get_available_models(true).await.unwrap();
+        assert_eq!(listed.load(Ordering::SeqCst), 2);
+
+        // Another provider gets its own list
+        let openai = MockProvider::new(ProviderType::OpenAI).with_models(&["gpt-4o"]);
+        let openai_listed = openai.model_list_counter();
+        manager.set_active_provider(Box::new(openai)).await;
+        let models = manager.get_available_models(false).await.unwrap();
+        assert_eq!(models.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), vec!["gpt-4o"]);
+        assert_eq!(openai_listed.load(Ordering::SeqCst), 1);
+
+        // An expired list is fetched again
+        manager.config.write().await.model_list_ttl_seconds = 0;
+        manager.get_available_models(false).await.unwrap();
+        assert_eq!(openai_listed.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_ollama_availability() {
+        let provider = MockProvider::new(ProviderType::Ollama)
+            .with_models(&["mistral:latest", "llama2:13b", "phi3:mini"]);
+        let manager = test_manager(provider).await;
+        manager.update_model_availability("codellama", true).await;
+
+        let sync = manager.refresh_ollama_availability().await.unwrap();
+
+        assert!(sync.now_available.contains(&"mistral".to_string()));
+        assert!(sync.now_available.contains(&"llama2".to_string()));
+        assert_eq!(sync.now_unavailable, vec!["codellama".to_string()]);
+        assert_eq!(sync.registered, vec!["phi3:mini".to_string()]);
+
+        let available: Vec<String> = manager.get_models_by_provider(&ProviderType::Ollama).await
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        assert!(available.contains(&"mistral".to_string()));
+        assert!(available.contains(&"phi3:mini".to_string()));
+        assert!(!available.contains(&"codellama".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_user_template_survives_restart() {
+        let pool = create_test_pool().await;
+        let manager = AIManager::detached(pool.clone());
+        let template = PromptTemplate::new("teaching_note", "Teaching Note", "Instructor notes", "guidance", "Notes for {{case_title}}")
+            .with_variable(prompts::TemplateVariable {
+                name: "case_title".to_string(),
+                description: "Title of the case study".to_string(),
+                variable_type: prompts::VariableType::String,
+                required: true,
+                default_value: None,
+                validation_pattern: None,
+            });
+        manager.create_prompt_template(template.clone()).await.unwrap();
+        assert!(manager.create_prompt_template(template).await.is_err());
+
+        let restarted = AIManager::detached(pool);
+        assert_eq!(restarted.load_persisted_templates().await.unwrap(), 1);
+
+        let mut variables = HashMap::new();
+        variables.insert("case_title".to_string(), serde_json::json!("Harbor Freight"));
+        let rendered = restarted.get_prompt_manager().render_template("teaching_note", &variables).unwrap();
+        assert_eq!(rendered.user_prompt, "Notes for Harbor Freight");
+        assert!(restarted.get_prompt_manager().get_template("case_study_generation").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_user_template_overrides_builtin_until_deleted() {
+        let manager = AIManager::detached(create_test_pool().await);
+        let builtin_prompt = manager.get_prompt_manager().get_template("case_outline").unwrap().user_prompt.clone();
+
+        let custom = PromptTemplate::new("case_outline", "My Outline", "Shorter outline", "planning", "Outline in five bullets");
+        manager.create_prompt_template(custom).await.unwrap();
+        assert_eq!(
+            manager.get_prompt_manager().get_template("case_outline").unwrap().user_prompt,
+            "Outline in five bullets"
+        );
+
+        assert!(manager.delete_prompt_template("case_outline").await.unwrap());
+        assert_eq!(manager.get_prompt_manager().get_template("case_outline").unwrap().user_prompt, builtin_prompt);
+        assert!(!manager.delete_prompt_template("case_outline").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_for_each_builtin_provider() {
+        let manager = AIManager::detached(create_test_pool().await);
+        {
+            let mut config = manager.config.write().await;
+            config.providers.insert(ProviderType::OpenAI, ProviderConfig::openai("sk-test"));
+            config.providers.insert(ProviderType::Anthropic, ProviderConfig::anthropic("sk-ant-test"));
+        }
+
+        let flags = |c: &ProviderCapabilities| (c.supports_streaming, c.supports_functions, c.supports_vision);
+        let openai = manager.get_provider_capabilities(&ProviderType::OpenAI).await.unwrap();
+        assert_eq!(flags(&openai), (true, true, true));
+        let anthropic = manager.get_provider_capabilities(&ProviderType::Anthropic).await.unwrap();
+        assert_eq!(flags(&anthropic), (true, true, true));
+        // Answered without contacting the (absent) local Ollama server
+        let ollama = manager.get_provider_capabilities(&ProviderType::Ollama).await.unwrap();
+        assert_eq!(flags(&ollama), (true, false, false));
+        assert_eq!(ollama.rate_limits, None);
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_of_unconfigured_provider_is_an_error() {
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+
+        let error = manager.get_provider_capabilities(&ProviderType::Anthropic).await.unwrap_err();
+        assert!(matches!(error, AIError::ConfigurationError(_)));
+        assert!(error.to_string().contains("anthropic is not configured"), "{}", error);
+
+        let active = manager.get_active_provider_capabilities().await.unwrap();
+        assert!(active.supports_streaming);
+        assert!(matches!(
+            AIManager::detached(create_test_pool().await).get_active_provider_capabilities().await,
+            Err(AIError::ProviderNotInitialized)
+        ));
+    }
+
     #[test]
     fn test_config_validation() {
         let config = AIConfig::default();
         assert!(!config.providers.is_empty());
     }
-}
\ No newline at end of file
+
+    /// Records each new span's name, its parent's name and its `request_id` field
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<std::sync::Mutex<Vec<(String, Option<String>, Option<String>)>>>);
+
+    impl<S> tracing_subscriber::Layer<S> for SpanRecorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, id: &tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            struct RequestId(Option<String>);
+            impl tracing::field::Visit for RequestId {
+                fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                    if field.name() == "request_id" {
+                        self.0 = Some(format!("{:?}", value));
+                    }
+                }
+            }
+
+            let mut request_id = RequestId(None);
+            attrs.record(&mut request_id);
+            let parent = ctx.span(id).and_then(|span| span.parent()).map(|parent| parent.name().to_string());
+            self.0.lock().unwrap().push((attrs.metadata().name().to_string(), parent, request_id.0));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_spans_carry_request_id() {
+        use tracing::instrument::WithSubscriber;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let recorder = SpanRecorder::default();
+        let dispatch = tracing::Dispatch::new(tracing_subscriber::registry().with(recorder.clone()));
+        let manager = test_manager(MockProvider::new(ProviderType::Ollama)).await;
+
+        manager.generate(request("first")).with_subscriber(dispatch.clone()).await.unwrap();
+        manager.generate(request("second")).with_subscriber(dispatch).await.unwrap();
+
+        let spans = recorder.0.lock().unwrap().clone();
+        let named = |name: &str| spans.iter().filter(|(n, _, _)| n == name).collect::<Vec<_>>();
+
+        let generations = named("ai.generate");
+        assert_eq!(generations.len(), 2);
+        let request_ids: Vec<&String> = generations.iter().filter_map(|(_, _, id)| id.as_ref()).collect();
+        assert_eq!(request_ids.len(), 2);
+        assert_ne!(request_ids[0], request_ids[1]);
+
+        // Provider calls and the history write are nested under the generation
+        for child in ["ai.provider_call", "db.generation_history.create"] {
+            let children = named(child);
+            assert_eq!(children.len(), 2, "{}", child);
+            assert!(children.iter().all(|(_, parent, _)| parent.as_deref() == Some("ai.generate")), "{:?}", children);
+        }
+    }
+}
+
@@ -23,6 +23,14 @@ pub struct ModelConfig {
     pub is_recommended: bool,
 }
 
+/// Changes made when syncing the registry with a provider's installed models
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelAvailabilitySync {
+    pub now_available: Vec<String>,
+    pub now_unavailable: Vec<String>,
+    pub registered: Vec<String>,
+}
+
 /// Model capabilities and features
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelCapabilities {
@@ -89,6 +97,47 @@ pub enum ModelUseCase {
     CreativeWriting,
 }
 
+/// Sampling preset for content generation, from focused and repeatable to varied
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationStyle {
+    Precise,
+    #[default]
+    Balanced,
+    Creative,
+}
+
+impl GenerationStyle {
+    pub const ALL: [GenerationStyle; 3] = [GenerationStyle::Precise, GenerationStyle::Balanced, GenerationStyle::Creative];
+
+    /// Sampling parameters the style aims for, before any model's constraints are applied.
+    /// Parameters the style doesn't care about are left unset.
+    pub fn target_params(&self) -> GenerationParams {
+        let (temperature, top_p) = match self {
+            GenerationStyle::Precise => (0.2, 0.8),
+            GenerationStyle::Balanced => (0.7, 0.9),
+            GenerationStyle::Creative => (1.2, 0.97),
+        };
+        GenerationParams {
+            temperature: Some(temperature),
+            max_tokens: None,
+            top_p: Some(top_p),
+            top_k: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+            stop_sequences: None,
+            seed: None,
+        }
+    }
+}
+
+/// A style's parameters as they would be sent to one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationStylePreset {
+    pub style: GenerationStyle,
+    pub params: GenerationParams,
+}
+
 /// Dynamic model configuration manager
 pub struct ModelConfigManager {
     models: HashMap<String, ModelConfig>,
@@ -173,6 +222,12 @@ impl ModelConfigManager {
         self.models.get(model_id)
     }
 
+    /// Whether a registered model can produce the given output format, e.g. "json"
+    pub fn supports_format(&self, model_id: &str, format: &str) -> bool {
+        self.get_model_config(model_id)
+            .is_some_and(|model| model.capabilities.supported_formats.iter().any(|f| f == format))
+    }
+
     /// Select best model based on criteria
     pub fn select_best_model(&self, criteria: &ModelSelectionCriteria) -> Result<&ModelConfig> {
         let mut candidates: Vec<&ModelConfig> = self.get_all_models();
@@ -347,6 +402,21 @@ impl ModelConfigManager {
         Ok(adjusted)
     }
 
+    /// A style's parameters clamped to the model's constraints. Models outside the
+    /// registry get the style's target values unchanged.
+    pub fn style_params(&self, model_id: &str, style: GenerationStyle) -> GenerationParams {
+        let params = style.target_params();
+        self.adjust_parameters(model_id, &params).unwrap_or(params)
+    }
+
+    /// Every style with the values it would use for the model
+    pub fn style_presets(&self, model_id: &str) -> Vec<GenerationStylePreset> {
+        GenerationStyle::ALL
+            .iter()
+            .map(|style| GenerationStylePreset { style: *style, params: self.style_params(model_id, *style) })
+            .collect()
+    }
+
     /// Estimate cost for a model based on token usage
     pub fn estimate_model_cost(&self, model: &ModelConfig, input_tokens: u32, output_tokens: u32) -> f64 {
         let input_cost = model.input_cost_per_1k.unwrap_or(0.0) * (input_tokens as f64 / 1000.0);
@@ -366,6 +436,43 @@ impl ModelConfigManager {
         }
     }
 
+    /// Match a provider's installed models against the registry: flip `is_available` on
+    /// known models and register any installed model that isn't known yet
+    pub fn sync_installed_models(&mut self, provider: &ProviderType, installed: &[ModelInfo]) -> ModelAvailabilitySync {
+        let installed_ids: Vec<String> = installed.iter().map(|m| normalize_model_tag(&m.id)).collect();
+        let mut sync = ModelAvailabilitySync::default();
+
+        let known_ids = self.provider_models.get(provider).cloned().unwrap_or_default();
+        for model_id in &known_ids {
+            // An untagged registry id like "llama2" matches any installed tag of it
+            let is_installed = installed_ids.iter().any(|id| {
+                id == model_id || (!model_id.contains(':') && id.split(':').next() == Some(model_id.as_str()))
+            });
+            if let Some(config) = self.models.get_mut(model_id) {
+                if config.is_available != is_installed {
+                    config.is_available = is_installed;
+                    if is_installed {
+                        sync.now_available.push(model_id.clone());
+                    } else {
+                        sync.now_unavailable.push(model_id.clone());
+                    }
+                }
+            }
+        }
+
+        for (model, model_id) in installed.iter().zip(installed_ids) {
+            let already_known = known_ids.iter().any(|known| {
+                *known == model_id || (!known.contains(':') && model_id.split(':').next() == Some(known.as_str()))
+            });
+            if !already_known && !sync.registered.contains(&model_id) {
+                self.add_model(create_installed_model_config(provider, &model_id, model));
+                sync.registered.push(model_id);
+            }
+        }
+
+        sync
+    }
+
     /// Convert ModelConfig to ModelInfo for compatibility
     pub fn to_model_info(&self, config: &ModelConfig) -> ModelInfo {
         ModelInfo {
@@ -619,7 +726,7 @@ fn create_claude_3_opus_config() -> ModelConfig {
         output_cost_per_1k: Some(0.075),
         capabilities: ModelCapabilities {
             supports_streaming: true,
-            supports_function_calling: false,
+            supports_function_calling: true,
             supports_vision: true,
             supports_system_prompt: true,
             max_output_tokens: Some(4096),
@@ -651,7 +758,7 @@ fn create_claude_3_sonnet_config() -> ModelConfig {
         output_cost_per_1k: Some(0.015),
         capabilities: ModelCapabilities {
             supports_streaming: true,
-            supports_function_calling: false,
+            supports_function_calling: true,
             supports_vision: true,
             supports_system_prompt: true,
             max_output_tokens: Some(4096),
@@ -683,7 +790,7 @@ fn create_claude_3_haiku_config() -> ModelConfig {
         output_cost_per_1k: Some(0.00125),
         capabilities: ModelCapabilities {
             supports_streaming: true,
-            supports_function_calling: false,
+            supports_function_calling: true,
             supports_vision: true,
             supports_system_prompt: true,
             max_output_tokens: Some(4096),
@@ -704,6 +811,23 @@ fn create_claude_3_haiku_config() -> ModelConfig {
     }
 }
 
+/// Strip the implicit `:latest` tag Ollama adds to model names
+fn normalize_model_tag(name: &str) -> String {
+    name.strip_suffix(":latest").unwrap_or(name).to_string()
+}
+
+/// Registry entry for an installed model the registry doesn't know about
+fn create_installed_model_config(provider: &ProviderType, model_id: &str, info: &ModelInfo) -> ModelConfig {
+    let mut config = create_llama2_config();
+    config.id = model_id.to_string();
+    config.name = info.name.clone();
+    config.provider = provider.clone();
+    config.description = info.description.clone().or_else(|| Some("Installed local model".to_string()));
+    config.context_length = info.context_length.or(config.context_length);
+    config.is_available = true;
+    config
+}
+
 fn create_llama2_config() -> ModelConfig {
     ModelConfig {
         id: "llama2".to_string(),
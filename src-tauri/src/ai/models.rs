@@ -3,6 +3,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use super::config::ProviderType;
+use super::truncation::{TruncationReport, TruncationStrategy};
 
 /// Role of a message in a conversation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -100,6 +102,35 @@ impl Default for GenerationParams {
     }
 }
 
+/// Tool the model may call, with a JSON Schema describing its arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+impl ToolDefinition {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
+        }
+    }
+}
+
+/// Tool invocation requested by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// Request metadata key recording the requested response format
+pub const RESPONSE_FORMAT_KEY: &str = "response_format";
+
 /// Request for AI content generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenerationRequest {
@@ -108,6 +139,12 @@ pub struct GenerationRequest {
     pub params: GenerationParams,
     pub stream: bool,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Applied before dispatch when the prompt exceeds the model's context window
+    #[serde(default)]
+    pub truncation: TruncationStrategy,
+    /// Tools the model may call; only sent to providers that support function calling
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
 }
 
 impl GenerationRequest {
@@ -118,6 +155,8 @@ impl GenerationRequest {
             params: GenerationParams::default(),
             stream: false,
             metadata: HashMap::new(),
+            truncation: TruncationStrategy::None,
+            tools: Vec::new(),
         }
     }
 
@@ -135,6 +174,26 @@ impl GenerationRequest {
         self.metadata.insert(key.into(), value);
         self
     }
+
+    pub fn with_truncation(mut self, strategy: TruncationStrategy) -> Self {
+        self.truncation = strategy;
+        self
+    }
+
+    pub fn with_tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Ask the provider to constrain output to a JSON object, where it supports doing so
+    pub fn with_json_output(self) -> Self {
+        self.with_metadata(RESPONSE_FORMAT_KEY, serde_json::json!("json"))
+    }
+
+    /// Whether the request asked for JSON output
+    pub fn wants_json(&self) -> bool {
+        self.metadata.get(RESPONSE_FORMAT_KEY).and_then(|v| v.as_str()) == Some("json")
+    }
 }
 
 /// Token usage information
@@ -165,6 +224,17 @@ pub struct GenerationResponse {
     pub response_time_ms: u64,
     pub metadata: HashMap<String, serde_json::Value>,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub cached: bool,
+    /// Provider that actually served the request
+    #[serde(default)]
+    pub provider: Option<ProviderType>,
+    /// Set when the request was truncated to fit the context window
+    #[serde(default)]
+    pub truncation: Option<TruncationReport>,
+    /// Tools the model asked to call, in the order it gave them
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
 }
 
 impl GenerationResponse {
@@ -177,6 +247,10 @@ impl GenerationResponse {
             response_time_ms: 0,
             metadata: HashMap::new(),
             created_at: Utc::now(),
+            cached: false,
+            provider: None,
+            truncation: None,
+            tool_calls: Vec::new(),
         }
     }
 
@@ -194,6 +268,11 @@ impl GenerationResponse {
         self.response_time_ms = ms;
         self
     }
+
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCall>) -> Self {
+        self.tool_calls = tool_calls;
+        self
+    }
 }
 
 /// Streaming response chunk
@@ -280,6 +359,29 @@ impl ModelInfo {
     }
 }
 
+/// Result of health-checking one configured provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Operational status: generation stats plus the health of the active provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AIStatus {
+    pub stats: GenerationStats,
+    /// Default provider in the configuration
+    pub configured_provider: ProviderType,
+    /// Provider serving requests; differs from `configured_provider` when switching failed
+    pub active_provider: Option<ProviderType>,
+    /// Latest health check of the active provider, which may be cached
+    pub health: Option<ProviderHealth>,
+    pub health_checked_at: Option<DateTime<Utc>>,
+    /// Models the active provider reports, if it could list them
+    pub model_count: Option<usize>,
+}
+
 /// Provider capabilities and features
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderCapabilities {
@@ -2,7 +2,8 @@
 
 use crate::ai::errors::{AIError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::OnceLock;
 use handlebars::Handlebars;
 
 /// Variable definition for prompt templates
@@ -96,7 +97,33 @@ impl PromptTemplate {
 
     /// Render the template with provided variables
     pub fn render(&self, variables: &HashMap<String, serde_json::Value>) -> Result<RenderedPrompt> {
-        let handlebars = Handlebars::new();
+        self.render_with(variables, false)
+    }
+
+    /// Render the template; when `strict`, first reject prompts that reference undeclared variables
+    pub fn render_with(&self, variables: &HashMap<String, serde_json::Value>, strict: bool) -> Result<RenderedPrompt> {
+        self.render_using(&Handlebars::new(), variables, strict)
+    }
+
+    /// Render the template against a Handlebars registry, so its registered partials resolve
+    pub fn render_using(
+        &self,
+        handlebars: &Handlebars<'_>,
+        variables: &HashMap<String, serde_json::Value>,
+        strict: bool,
+    ) -> Result<RenderedPrompt> {
+        if strict {
+            self.validate_template_completeness()?;
+        }
+
+        for partial in self.referenced_partials() {
+            if handlebars.get_template(&partial).is_none() {
+                return Err(AIError::TemplateError(format!(
+                    "Template '{}' uses unknown partial '{}'",
+                    self.id, partial
+                )));
+            }
+        }
 
         // Validate required variables
         for var in &self.variables {
@@ -199,6 +226,204 @@ impl PromptTemplate {
     }
 }
 
+impl PromptTemplate {
+    /// Top-level variable names referenced by `{{...}}` expressions in the prompts.
+    /// Expressions inside `#each`/`#with` blocks resolve against the block context and are skipped.
+    pub fn referenced_variables(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        for text in self.system_prompt.iter().chain(std::iter::once(&self.user_prompt)) {
+            collect_references(text, &mut names);
+        }
+        names.into_iter().collect()
+    }
+
+    /// Names of partials included with `{{> name}}` in the prompts
+    pub fn referenced_partials(&self) -> Vec<String> {
+        static PARTIAL: OnceLock<regex::Regex> = OnceLock::new();
+        let partial = PARTIAL.get_or_init(|| {
+            regex::Regex::new(r"\{\{~?#?>\s*([\w\-/]+)").expect("valid partial pattern")
+        });
+
+        let mut names = BTreeSet::new();
+        for text in self.system_prompt.iter().chain(std::iter::once(&self.user_prompt)) {
+            names.extend(partial.captures_iter(text).map(|c| c[1].to_string()));
+        }
+        names.into_iter().collect()
+    }
+
+    /// Referenced variables that are not declared in `variables`
+    pub fn undeclared_variables(&self) -> Vec<String> {
+        self.referenced_variables()
+            .into_iter()
+            .filter(|name| !self.variables.iter().any(|v| &v.name == name))
+            .collect()
+    }
+
+    /// Fail with a `TemplateError` naming every referenced variable that is not declared.
+    /// Handlebars renders undeclared references as empty strings, so this catches silent gaps.
+    pub fn validate_template_completeness(&self) -> Result<()> {
+        let undeclared = self.undeclared_variables();
+        if undeclared.is_empty() {
+            return Ok(());
+        }
+        Err(AIError::TemplateError(format!(
+            "Template '{}' references undeclared variables: {}",
+            self.id,
+            undeclared.join(", ")
+        )))
+    }
+
+    /// Check that the template is well-formed, returning every problem found
+    pub fn definition_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.id.trim().is_empty() {
+            errors.push("Template id is empty".to_string());
+        }
+        if self.user_prompt.trim().is_empty() {
+            errors.push("User prompt is empty".to_string());
+        }
+
+        for name in self.undeclared_variables() {
+            errors.push(format!("Prompt references undeclared variable '{}'", name));
+        }
+
+        for var in &self.variables {
+            if let Some(pattern) = &var.validation_pattern {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    errors.push(format!("Invalid regex pattern for variable '{}': {}", var.name, e));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Collect the variable names referenced by Handlebars expressions in `text`
+fn collect_references(text: &str, names: &mut BTreeSet<String>) {
+    static EXPRESSION: OnceLock<regex::Regex> = OnceLock::new();
+    let expression = EXPRESSION.get_or_init(|| {
+        regex::Regex::new(r"\{\{\{?~?\s*([^}]*?)\s*~?\}?\}\}").expect("valid expression pattern")
+    });
+
+    // Depth of blocks whose body is rendered against a nested context
+    let mut scoped_depth = 0usize;
+    for capture in expression.captures_iter(text) {
+        let body = capture[1].trim();
+        let mut tokens = body.split_whitespace();
+
+        match body.chars().next() {
+            None | Some('!') | Some('>') | Some('^') => {}
+            Some('/') => {
+                if matches!(body[1..].trim(), "each" | "with") {
+                    scoped_depth = scoped_depth.saturating_sub(1);
+                }
+            }
+            Some('#') => {
+                let helper = tokens.next().unwrap_or("#").trim_start_matches('#');
+                if scoped_depth == 0 {
+                    tokens.take_while(|t| *t != "as").for_each(|arg| add_reference(arg, names));
+                }
+                if matches!(helper, "each" | "with") {
+                    scoped_depth += 1;
+                }
+            }
+            Some(_) if scoped_depth > 0 => {}
+            Some(_) => {
+                let first = tokens.next().unwrap_or_default();
+                let args: Vec<&str> = tokens.collect();
+                if first == "else" {
+                    // `{{else if cond}}`: skip the chained helper name
+                    args.into_iter().skip(1).for_each(|arg| add_reference(arg, names));
+                } else if args.is_empty() {
+                    add_reference(first, names);
+                } else {
+                    // `{{helper arg1 arg2}}`: the arguments are the references
+                    args.into_iter().for_each(|arg| add_reference(arg, names));
+                }
+            }
+        }
+    }
+}
+
+/// Record the root of a path expression, ignoring literals, keywords and data variables
+fn add_reference(token: &str, names: &mut BTreeSet<String>) {
+    let token = token.trim_matches(|c: char| c == '(' || c == ')');
+    if token.is_empty()
+        || token.contains('=')
+        || token.starts_with(|c: char| c == '@' || c == '"' || c == '\'' || c == '.' || c.is_ascii_digit())
+        || matches!(token, "this" | "else" | "true" | "false" | "null")
+    {
+        return;
+    }
+    let root = token.split(|c: char| c == '.' || c == '/' || c == '[').next().unwrap_or(token);
+    if !root.is_empty() {
+        names.insert(root.to_string());
+    }
+}
+
+impl PromptTemplate {
+    /// Build a template from a persisted `prompt_templates` row
+    pub fn from_record(record: &crate::database::models::PromptTemplate) -> Result<Self> {
+        Ok(Self {
+            id: record.template_id.clone(),
+            name: record.name.clone(),
+            description: record.description.clone(),
+            category: record.category.clone(),
+            system_prompt: record.system_prompt.clone(),
+            user_prompt: record.user_prompt.clone(),
+            variables: serde_json::from_str(&record.variables)?,
+            example_values: match &record.example_values {
+                Some(json) => serde_json::from_str(json)?,
+                None => HashMap::new(),
+            },
+            tags: match &record.tags {
+                Some(json) => serde_json::from_str(json)?,
+                None => Vec::new(),
+            },
+            version: record.version.clone(),
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+        })
+    }
+
+    /// Row data for persisting this template as a user template
+    pub fn to_new_record(&self) -> Result<crate::database::models::NewPromptTemplate> {
+        Ok(crate::database::models::NewPromptTemplate {
+            template_id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            category: self.category.clone(),
+            system_prompt: self.system_prompt.clone(),
+            user_prompt: self.user_prompt.clone(),
+            variables: serde_json::to_string(&self.variables)?,
+            example_values: Some(serde_json::to_string(&self.example_values)?),
+            tags: Some(serde_json::to_string(&self.tags)?),
+            version: Some(self.version.clone()),
+            is_active: Some(true),
+            is_system_template: Some(false),
+            created_by: None,
+        })
+    }
+
+    /// Row changes that overwrite a persisted template with this one
+    pub fn to_update_record(&self) -> Result<crate::database::models::UpdatePromptTemplate> {
+        Ok(crate::database::models::UpdatePromptTemplate {
+            name: Some(self.name.clone()),
+            description: Some(self.description.clone()),
+            category: Some(self.category.clone()),
+            system_prompt: self.system_prompt.clone(),
+            user_prompt: Some(self.user_prompt.clone()),
+            variables: Some(serde_json::to_string(&self.variables)?),
+            example_values: Some(serde_json::to_string(&self.example_values)?),
+            tags: Some(serde_json::to_string(&self.tags)?),
+            version: Some(self.version.clone()),
+            is_active: Some(true),
+        })
+    }
+}
+
 /// A rendered prompt ready for AI generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenderedPrompt {
@@ -208,10 +433,59 @@ pub struct RenderedPrompt {
     pub variables_used: HashMap<String, serde_json::Value>,
 }
 
-/// Prompt template manager
+impl RenderedPrompt {
+    /// Replace the rendered system prompt with a caller's override, if given. The user
+    /// prompt is left as rendered.
+    pub fn with_system_prompt_override(mut self, system_prompt: Option<&str>) -> Self {
+        if let Some(system_prompt) = system_prompt {
+            self.system_prompt = Some(system_prompt.to_string());
+        }
+        self
+    }
+}
+
+/// Longest system prompt override accepted for a generation call, in characters
+pub const MAX_SYSTEM_PROMPT_OVERRIDE_CHARS: usize = 4000;
+
+/// Check a system prompt override supplied with generation parameters
+pub fn validate_system_prompt_override(system_prompt: Option<&str>) -> Result<()> {
+    match system_prompt {
+        Some(prompt) if prompt.trim().is_empty() => {
+            Err(AIError::ValidationError("System prompt override cannot be empty".to_string()))
+        }
+        Some(prompt) if prompt.chars().count() > MAX_SYSTEM_PROMPT_OVERRIDE_CHARS => Err(AIError::ValidationError(format!(
+            "System prompt override too long (max {} characters)",
+            MAX_SYSTEM_PROMPT_OVERRIDE_CHARS
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// A template that was rejected during import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedTemplate {
+    pub index: usize,
+    pub template_id: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// Outcome of importing templates from JSON
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateImportReport {
+    pub imported: usize,
+    pub imported_ids: Vec<String>,
+    pub skipped: Vec<SkippedTemplate>,
+}
+
+/// Prompt template manager.
+///
+/// Built-in templates are loaded first; a user template with the same id replaces the
+/// built-in, and deleting that user template restores the built-in again.
+#[derive(Clone)]
 pub struct PromptManager {
     templates: HashMap<String, PromptTemplate>,
     handlebars: Handlebars<'static>,
+    strict: bool,
 }
 
 impl PromptManager {
@@ -220,17 +494,41 @@ impl PromptManager {
         Self {
             templates: HashMap::new(),
             handlebars: Handlebars::new(),
+            strict: false,
         }
     }
 
+    /// Register a reusable partial that templates can include with `{{> name}}`
+    pub fn register_partial(&mut self, name: &str, partial: &str) -> Result<()> {
+        self.handlebars
+            .register_partial(name, partial)
+            .map_err(|e| AIError::TemplateError(format!("Invalid partial '{}': {}", name, e)))
+    }
+
+    /// Reject templates that reference undeclared variables when rendering
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
     /// Load default templates for case study generation
     pub fn load_default_templates(&mut self) {
-        self.add_template(create_case_study_template());
-        self.add_template(create_question_template());
-        self.add_template(create_outline_template());
-        self.add_template(create_background_template());
-        self.add_template(create_analysis_framework_template());
-        self.add_template(create_sample_solution_template());
+        for template in default_templates() {
+            self.add_template(template);
+        }
+    }
+
+    /// Whether a built-in template exists with this id
+    pub fn is_builtin(id: &str) -> bool {
+        default_templates().iter().any(|t| t.id == id)
+    }
+
+    /// Remove a user template, restoring the built-in with the same id if there is one
+    pub fn remove_user_template(&mut self, id: &str) -> Option<PromptTemplate> {
+        let removed = self.templates.remove(id);
+        if let Some(builtin) = default_templates().into_iter().find(|t| t.id == id) {
+            self.add_template(builtin);
+        }
+        removed
     }
 
     /// Add a template to the manager
@@ -278,7 +576,7 @@ impl PromptManager {
         let template = self.get_template(template_id)
             .ok_or_else(|| AIError::TemplateError(format!("Template '{}' not found", template_id)))?;
 
-        template.render(variables)
+        template.render_using(&self.handlebars, variables, self.strict)
     }
 
     /// Get template categories
@@ -299,22 +597,67 @@ impl PromptManager {
             .map_err(|e| AIError::SerializationError(e))
     }
 
-    /// Import templates from JSON
-    pub fn import_templates(&mut self, json: &str) -> Result<usize> {
-        let templates: Vec<PromptTemplate> = serde_json::from_str(json)
+    /// Parse and validate a JSON array of templates without adding them, keeping each
+    /// valid template's position in the array.
+    /// Fails only if the JSON is not an array; each invalid entry is reported as skipped.
+    pub fn validate_import(json: &str) -> Result<(Vec<(usize, PromptTemplate)>, Vec<SkippedTemplate>)> {
+        let entries: Vec<serde_json::Value> = serde_json::from_str(json)
             .map_err(|e| AIError::SerializationError(e))?;
 
-        let count = templates.len();
-        for template in templates {
+        let mut valid = Vec::new();
+        let mut skipped = Vec::new();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let template_id = entry.get("id").and_then(|v| v.as_str()).map(String::from);
+            let template: PromptTemplate = match serde_json::from_value(entry) {
+                Ok(template) => template,
+                Err(e) => {
+                    skipped.push(SkippedTemplate { index, template_id, errors: vec![e.to_string()] });
+                    continue;
+                }
+            };
+
+            let errors = template.definition_errors();
+            if errors.is_empty() {
+                valid.push((index, template));
+            } else {
+                skipped.push(SkippedTemplate { index, template_id, errors });
+            }
+        }
+
+        Ok((valid, skipped))
+    }
+
+    /// Import templates from JSON, skipping any that fail validation
+    pub fn import_templates(&mut self, json: &str) -> Result<TemplateImportReport> {
+        let (templates, skipped) = Self::validate_import(json)?;
+
+        let imported_ids: Vec<String> = templates.iter().map(|(_, t)| t.id.clone()).collect();
+        for (_, template) in templates {
             self.add_template(template);
         }
 
-        Ok(count)
+        Ok(TemplateImportReport {
+            imported: imported_ids.len(),
+            imported_ids,
+            skipped,
+        })
     }
 }
 
 /// Helper functions to create default templates
 
+fn default_templates() -> Vec<PromptTemplate> {
+    vec![
+        create_case_study_template(),
+        create_question_template(),
+        create_outline_template(),
+        create_background_template(),
+        create_analysis_framework_template(),
+        create_sample_solution_template(),
+        create_answer_grading_template(),
+    ]
+}
+
 fn create_case_study_template() -> PromptTemplate {
     PromptTemplate::new(
         "case_study_generation",
@@ -429,7 +772,11 @@ For each question, provide:
 - Ensure questions build on the case study content
 - Vary question types for engagement
 
-**Format**: Provide each question as a structured block with all required elements."#
+**Format**: Provide each question as a structured block with all required elements.
+{{#if include_rubric}}
+
+**Rubric Format**: Give each question's rubric on a single line starting with `Rubric:` followed by JSON of the form {"criteria": [{"name": "<criterion name>", "points": <number>}]}. Use two to five criteria whose points add up to exactly {{max_points}}.
+{{/if}}"#
     )
     .with_system_prompt("You are an educational assessment expert specializing in business case study evaluation. You create fair, comprehensive questions that effectively measure student understanding and analytical skills.")
     .with_variable(TemplateVariable {
@@ -488,6 +835,14 @@ For each question, provide:
         default_value: Some("10".to_string()),
         validation_pattern: None,
     })
+    .with_variable(TemplateVariable {
+        name: "include_rubric".to_string(),
+        description: "Whether to ask for a JSON rubric with each question".to_string(),
+        variable_type: VariableType::Boolean,
+        required: false,
+        default_value: None,
+        validation_pattern: None,
+    })
     .with_tags(vec!["questions".to_string(), "assessment".to_string(), "evaluation".to_string()])
 }
 
@@ -757,6 +1112,74 @@ Note: This is one possible approach - encourage students to consider alternative
     .with_tags(vec!["solution".to_string(), "example".to_string(), "analysis".to_string()])
 }
 
+fn create_answer_grading_template() -> PromptTemplate {
+    PromptTemplate::new(
+        "answer_grading",
+        "Answer Grading",
+        "Grade a free-text student answer against a rubric",
+        "assessment",
+        r#"Grade the following student answer.
+
+**Question** ({{max_points}} points):
+{{{question_text}}}
+
+**Rubric**:
+{{{rubric}}}
+
+**Sample Answer**:
+{{{sample_answer}}}
+
+**Student Answer**:
+{{{student_answer}}}
+
+Score each rubric criterion separately and never award more than the points available for it.
+Respond with JSON only, in exactly this shape:
+{"score": <total points awarded>, "criteria": [{"name": "<criterion name>", "points_awarded": <number>, "points_possible": <number>, "feedback": "<one or two sentences>"}], "overall_feedback": "<short summary for the student>"}"#
+    )
+    .with_system_prompt("You are an experienced business school instructor grading case study answers. You grade consistently against the rubric, reward sound reasoning even when it differs from the sample answer, and always respond with valid JSON.")
+    .with_variable(TemplateVariable {
+        name: "question_text".to_string(),
+        description: "The question the student answered".to_string(),
+        variable_type: VariableType::String,
+        required: true,
+        default_value: None,
+        validation_pattern: None,
+    })
+    .with_variable(TemplateVariable {
+        name: "rubric".to_string(),
+        description: "Grading criteria with the points available for each".to_string(),
+        variable_type: VariableType::String,
+        required: true,
+        default_value: None,
+        validation_pattern: None,
+    })
+    .with_variable(TemplateVariable {
+        name: "student_answer".to_string(),
+        description: "The answer being graded".to_string(),
+        variable_type: VariableType::String,
+        required: true,
+        default_value: None,
+        validation_pattern: None,
+    })
+    .with_variable(TemplateVariable {
+        name: "sample_answer".to_string(),
+        description: "Model answer for reference".to_string(),
+        variable_type: VariableType::String,
+        required: false,
+        default_value: Some("Not provided".to_string()),
+        validation_pattern: None,
+    })
+    .with_variable(TemplateVariable {
+        name: "max_points".to_string(),
+        description: "Total points available for the question".to_string(),
+        variable_type: VariableType::Number,
+        required: true,
+        default_value: None,
+        validation_pattern: None,
+    })
+    .with_tags(vec!["grading".to_string(), "assessment".to_string(), "evaluation".to_string()])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -854,4 +1277,153 @@ mod tests {
         assert!(manager.get_template("case_outline").is_some());
         assert!(manager.list_templates().len() >= 6);
     }
-}
\ No newline at end of file
+
+    fn declared(name: &str) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            description: String::new(),
+            variable_type: VariableType::String,
+            required: false,
+            default_value: None,
+            validation_pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_import_valid_templates() {
+        let templates = vec![
+            PromptTemplate::new("memo", "Memo", "Board memo", "content", "Write a memo about {{topic}}")
+                .with_variable(declared("topic")),
+            PromptTemplate::new("recap", "Recap", "Session recap", "content", "Recap the session"),
+        ];
+        let json = serde_json::to_string(&templates).unwrap();
+
+        let mut manager = PromptManager::new();
+        let report = manager.import_templates(&json).unwrap();
+
+        assert_eq!(report.imported, 2);
+        assert!(report.skipped.is_empty());
+        assert!(manager.get_template("memo").is_some());
+    }
+
+    #[test]
+    fn test_import_reports_each_invalid_template() {
+        let mut bad_pattern = declared("region");
+        bad_pattern.validation_pattern = Some("([a-z".to_string());
+        let templates = vec![
+            PromptTemplate::new("ok", "OK", "Fine", "content", "Discuss {{topic}}").with_variable(declared("topic")),
+            PromptTemplate::new("", "No id", "Missing id", "content", "Hello"),
+            PromptTemplate::new("undeclared", "Undeclared", "Bad refs", "content", "Compare {{market}} with {{#if region}}{{region}}{{/if}}")
+                .with_variable(bad_pattern),
+        ];
+        let mut entries = serde_json::to_value(&templates).unwrap();
+        entries.as_array_mut().unwrap().push(serde_json::json!({ "id": "partial", "name": "Partial" }));
+
+        let mut manager = PromptManager::new();
+        let report = manager.import_templates(&entries.to_string()).unwrap();
+
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.imported_ids, vec!["ok".to_string()]);
+        assert_eq!(report.skipped.len(), 3);
+
+        assert_eq!(report.skipped[0].index, 1);
+        assert_eq!(report.skipped[0].errors, vec!["Template id is empty".to_string()]);
+
+        let undeclared = &report.skipped[1];
+        assert_eq!(undeclared.template_id.as_deref(), Some("undeclared"));
+        assert_eq!(undeclared.errors.len(), 2);
+        assert!(undeclared.errors[0].contains("'market'"));
+        assert!(undeclared.errors[1].starts_with("Invalid regex pattern for variable 'region'"));
+
+        assert_eq!(report.skipped[2].template_id.as_deref(), Some("partial"));
+        assert!(manager.get_template("undeclared").is_none());
+    }
+
+    #[test]
+    fn test_import_malformed_json() {
+        let mut manager = PromptManager::new();
+        assert!(matches!(
+            manager.import_templates("[{\"id\": \"broken\""),
+            Err(AIError::SerializationError(_))
+        ));
+        assert!(manager.list_templates().is_empty());
+    }
+
+    #[test]
+    fn test_complete_template_passes_strict_rendering() {
+        let template = PromptTemplate::new("memo", "Memo", "Board memo", "content", "{{#if urgent}}URGENT: {{/if}}Memo on {{topic}}")
+            .with_variable(declared("topic"))
+            .with_variable(declared("urgent"));
+        assert!(template.validate_template_completeness().is_ok());
+
+        let mut variables = HashMap::new();
+        variables.insert("topic".to_string(), serde_json::json!("pricing"));
+        let rendered = template.render_with(&variables, true).unwrap();
+        assert_eq!(rendered.user_prompt, "Memo on pricing");
+    }
+
+    #[test]
+    fn test_undeclared_reference_is_reported() {
+        let template = PromptTemplate::new("memo", "Memo", "Board memo", "content", "Memo on {{topic}} for {{audience}}")
+            .with_system_prompt("Write for {{reader.role}}")
+            .with_variable(declared("topic"));
+
+        assert_eq!(template.undeclared_variables(), vec!["audience".to_string(), "reader".to_string()]);
+        match template.validate_template_completeness() {
+            Err(AIError::TemplateError(message)) => assert!(message.contains("audience, reader")),
+            other => panic!("expected template error, got {:?}", other),
+        }
+
+        let mut variables = HashMap::new();
+        variables.insert("topic".to_string(), serde_json::json!("pricing"));
+        assert_eq!(template.render(&variables).unwrap().user_prompt, "Memo on pricing for ");
+
+        let mut manager = PromptManager::new();
+        manager.add_template(template);
+        manager.set_strict(true);
+        assert!(matches!(manager.render_template("memo", &variables), Err(AIError::TemplateError(_))));
+    }
+
+    #[test]
+    fn test_registered_partial_and_conditional_render() {
+        let mut manager = PromptManager::new();
+        manager
+            .register_partial("guidelines", "Tone: professional. Length: about {{target_length}} words.")
+            .unwrap();
+        manager.add_template(
+            PromptTemplate::new(
+                "brief",
+                "Brief",
+                "Short brief",
+                "content",
+                "Write a brief on {{topic}}.{{#if audience}} Audience: {{audience}}.{{/if}}\n{{> guidelines}}",
+            )
+            .with_variable(declared("topic"))
+            .with_variable(declared("audience"))
+            .with_variable(declared("target_length")),
+        );
+
+        let mut variables = HashMap::new();
+        variables.insert("topic".to_string(), serde_json::json!("pricing"));
+        variables.insert("target_length".to_string(), serde_json::json!(300));
+        let rendered = manager.render_template("brief", &variables).unwrap();
+        assert_eq!(rendered.user_prompt, "Write a brief on pricing.\nTone: professional. Length: about 300 words.");
+
+        variables.insert("audience".to_string(), serde_json::json!("the board"));
+        let rendered = manager.render_template("brief", &variables).unwrap();
+        assert!(rendered.user_prompt.contains("Audience: the board."));
+    }
+
+    #[test]
+    fn test_unknown_partial_errors() {
+        let mut manager = PromptManager::new();
+        manager.add_template(PromptTemplate::new("brief", "Brief", "Short brief", "content", "{{> missing_block}}"));
+
+        match manager.render_template("brief", &HashMap::new()) {
+            Err(AIError::TemplateError(message)) => {
+                assert_eq!(message, "Template 'brief' uses unknown partial 'missing_block'")
+            }
+            other => panic!("expected template error, got {:?}", other),
+        }
+    }
+}
@@ -6,6 +6,7 @@ use crate::ai::{
     models::{
         ChatMessage, GenerationRequest, GenerationResponse, GenerationStats,
         MessageRole, ModelInfo, ProviderCapabilities, StreamResponse, TokenUsage,
+        ToolCall, ToolDefinition,
     },
     providers::AIProvider,
 };
@@ -32,9 +33,18 @@ struct AnthropicRequest {
     top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<AnthropicTool>>,
     stream: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct AnthropicTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
 #[derive(Debug, Serialize)]
 struct AnthropicMessage {
     role: String,
@@ -56,10 +66,18 @@ struct AnthropicResponse {
 }
 
 #[derive(Debug, Deserialize)]
-struct AnthropicContent {
-    #[serde(rename = "type")]
-    content_type: String,
-    text: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicContent {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
 }
 
 #[derive(Debug, Deserialize)]
@@ -117,6 +135,22 @@ pub struct AnthropicProvider {
 }
 
 impl AnthropicProvider {
+    /// What this provider supports; the same for every instance
+    pub fn capabilities() -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: true,
+            supports_functions: true,
+            supports_vision: true,     // Claude 3 models support vision
+            supports_fine_tuning: false,
+            max_context_length: Some(200000), // Claude 3 context length
+            supported_formats: vec!["text".to_string(), "image".to_string()],
+            rate_limits: Some([
+                ("requests_per_minute".to_string(), 4000),
+                ("tokens_per_minute".to_string(), 400000),
+            ].iter().cloned().collect()),
+        }
+    }
+
     /// Create a new Anthropic provider
     pub async fn new(config: ProviderConfig) -> Result<Self> {
         let mut headers = HeaderMap::new();
@@ -220,6 +254,7 @@ impl AnthropicProvider {
             top_p: request.params.top_p,
             top_k: request.params.top_k,
             stop_sequences: request.params.stop_sequences.clone(),
+            tools: (!request.tools.is_empty()).then(|| request.tools.iter().map(convert_tool).collect()),
             stream,
         })
     }
@@ -241,6 +276,9 @@ impl AnthropicProvider {
                     AIError::InvalidRequest(error_text.to_string())
                 }
             }
+            status if status.is_server_error() => {
+                AIError::ServiceUnavailable(format!("Anthropic API error ({}): {}", status, error_text))
+            }
             _ => AIError::ProviderError(format!("Anthropic API error ({}): {}", status, error_text))
         }
     }
@@ -274,13 +312,7 @@ impl AnthropicProvider {
 
         let response_time = start_time.elapsed().as_millis() as u64;
 
-        // Extract content from response
-        let content = anthropic_response
-            .content
-            .into_iter()
-            .map(|c| c.text)
-            .collect::<Vec<_>>()
-            .join("");
+        let (content, tool_calls) = split_content(anthropic_response.content);
 
         let usage = TokenUsage::new(
             anthropic_response.usage.input_tokens,
@@ -303,7 +335,8 @@ impl AnthropicProvider {
         Ok(GenerationResponse::new(content, request.model)
             .with_usage(usage)
             .with_finish_reason(anthropic_response.stop_reason.unwrap_or("unknown".to_string()))
-            .with_response_time(response_time))
+            .with_response_time(response_time)
+            .with_tool_calls(tool_calls))
     }
 
     /// Make a streaming request
@@ -375,6 +408,28 @@ impl AnthropicProvider {
     }
 }
 
+fn convert_tool(tool: &ToolDefinition) -> AnthropicTool {
+    AnthropicTool {
+        name: tool.name.clone(),
+        description: tool.description.clone(),
+        input_schema: tool.parameters.clone(),
+    }
+}
+
+/// Joined text of the response's text blocks, and its `tool_use` blocks as tool calls
+fn split_content(blocks: Vec<AnthropicContent>) -> (String, Vec<ToolCall>) {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block {
+            AnthropicContent::Text { text: block_text } => text.push_str(&block_text),
+            AnthropicContent::ToolUse { id, name, input } => tool_calls.push(ToolCall { id, name, arguments: input }),
+            AnthropicContent::Other => {}
+        }
+    }
+    (text, tool_calls)
+}
+
 #[async_trait]
 impl AIProvider for AnthropicProvider {
     async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse> {
@@ -427,6 +482,7 @@ impl AIProvider for AnthropicProvider {
             top_p: None,
             top_k: None,
             stop_sequences: None,
+            tools: None,
             stream: false,
         };
 
@@ -440,18 +496,7 @@ impl AIProvider for AnthropicProvider {
     }
 
     fn get_capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            supports_streaming: true,
-            supports_functions: false, // Claude doesn't support function calling in the same way
-            supports_vision: true,     // Claude 3 models support vision
-            supports_fine_tuning: false,
-            max_context_length: Some(200000), // Claude 3 context length
-            supported_formats: vec!["text".to_string(), "image".to_string()],
-            rate_limits: Some([
-                ("requests_per_minute".to_string(), 4000),
-                ("tokens_per_minute".to_string(), 400000),
-            ].iter().cloned().collect()),
-        }
+        Self::capabilities()
     }
 
     fn get_provider_type(&self) -> ProviderType {
@@ -550,6 +595,8 @@ mod tests {
             },
             stream: false,
             metadata: std::collections::HashMap::new(),
+            truncation: Default::default(),
+            tools: Vec::new(),
         };
 
         let anthropic_request = provider.create_anthropic_request(&request, true).unwrap();
@@ -560,6 +607,46 @@ mod tests {
         assert!(anthropic_request.stream);
     }
 
+    #[test]
+    fn test_tools_round_trip() {
+        let config = ProviderConfig::anthropic("test-key");
+        let provider = AnthropicProvider {
+            config,
+            client: Client::new(),
+            stats: RwLock::new(GenerationStats::default()),
+        };
+
+        let request = GenerationRequest::new(vec![ChatMessage::user("Weather in Perth?")], "claude-3-5-sonnet-20241022")
+            .with_tools(vec![ToolDefinition::new(
+                "get_weather",
+                "Current weather for a city",
+                serde_json::json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+            )]);
+        let body = serde_json::to_value(provider.create_anthropic_request(&request, false).unwrap()).unwrap();
+        assert_eq!(body["tools"][0]["name"], "get_weather");
+        assert_eq!(body["tools"][0]["input_schema"]["properties"]["city"]["type"], "string");
+
+        let response: AnthropicResponse = serde_json::from_str(r#"{
+            "id": "msg_1", "type": "message", "role": "assistant", "model": "claude-3-5-sonnet-20241022",
+            "content": [
+                { "type": "text", "text": "Let me check." },
+                { "type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": { "city": "Perth" } }
+            ],
+            "stop_reason": "tool_use", "stop_sequence": null,
+            "usage": { "input_tokens": 20, "output_tokens": 10 }
+        }"#).unwrap();
+        let (text, tool_calls) = split_content(response.content);
+        assert_eq!(text, "Let me check.");
+        assert_eq!(
+            tool_calls,
+            vec![ToolCall {
+                id: "toolu_1".to_string(),
+                name: "get_weather".to_string(),
+                arguments: serde_json::json!({ "city": "Perth" }),
+            }]
+        );
+    }
+
     #[test]
     fn test_cost_estimation() {
         let config = ProviderConfig::anthropic("test-key");
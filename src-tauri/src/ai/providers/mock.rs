@@ -0,0 +1,222 @@
+// Scripted AI provider used by unit tests
+
+use crate::ai::{
+    config::ProviderType,
+    errors::{AIError, Result},
+    models::{
+        GenerationRequest, GenerationResponse, GenerationStats,
+        ModelInfo, ProviderCapabilities, StreamResponse, TokenUsage, ToolCall,
+    },
+    providers::AIProvider,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Provider that replays scripted outcomes instead of calling a real API
+pub struct MockProvider {
+    provider_type: ProviderType,
+    outcomes: Mutex<VecDeque<Result<GenerationResponse>>>,
+    chunks: Vec<String>,
+    healthy: bool,
+    functions: bool,
+    delay: Option<Duration>,
+    models: Vec<String>,
+    vocabulary: Option<Vec<String>>,
+    calls: Arc<AtomicU32>,
+    model_calls: Arc<AtomicU32>,
+    in_flight: AtomicU32,
+    peak_in_flight: Arc<AtomicU32>,
+}
+
+impl MockProvider {
+    pub fn new(provider_type: ProviderType) -> Self {
+        Self {
+            provider_type,
+            outcomes: Mutex::new(VecDeque::new()),
+            chunks: Vec::new(),
+            healthy: true,
+            functions: false,
+            delay: None,
+            models: vec!["mock-model".to_string()],
+            vocabulary: None,
+            calls: Arc::new(AtomicU32::new(0)),
+            model_calls: Arc::new(AtomicU32::new(0)),
+            in_flight: AtomicU32::new(0),
+            peak_in_flight: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Queue an error to be returned by the next `generate` call
+    pub fn fail_with(self, error: AIError) -> Self {
+        self.outcomes.lock().unwrap().push_back(Err(error));
+        self
+    }
+
+    /// Queue a successful response for the next `generate` call
+    pub fn respond_with(self, content: impl Into<String>) -> Self {
+        let response = GenerationResponse::new(content, "mock-model")
+            .with_usage(TokenUsage::new(10, 20));
+        self.outcomes.lock().unwrap().push_back(Ok(response));
+        self
+    }
+
+    /// Queue a response asking for `tool_calls` for the next `generate` call
+    pub fn respond_with_tool_calls(self, tool_calls: Vec<ToolCall>) -> Self {
+        let response = GenerationResponse::new("", "mock-model")
+            .with_usage(TokenUsage::new(10, 20))
+            .with_finish_reason("tool_calls")
+            .with_tool_calls(tool_calls);
+        self.outcomes.lock().unwrap().push_back(Ok(response));
+        self
+    }
+
+    /// Report support for function calling
+    pub fn with_functions(mut self) -> Self {
+        self.functions = true;
+        self
+    }
+
+    /// Set the chunks yielded by `generate_stream`
+    pub fn with_chunks(mut self, chunks: &[&str]) -> Self {
+        self.chunks = chunks.iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    pub fn with_health(mut self, healthy: bool) -> Self {
+        self.healthy = healthy;
+        self
+    }
+
+    /// Set the model names reported by `get_models`
+    pub fn with_models(mut self, models: &[&str]) -> Self {
+        self.models = models.iter().map(|m| m.to_string()).collect();
+        self
+    }
+
+    /// Support `embed` with a bag-of-words embedder: one dimension per vocabulary
+    /// word, counting its case-insensitive occurrences in the text
+    pub fn with_embeddings(mut self, vocabulary: &[&str]) -> Self {
+        self.vocabulary = Some(vocabulary.iter().map(|w| w.to_lowercase()).collect());
+        self
+    }
+
+    /// Sleep before answering `generate` and `health_check`
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Shared counter of `generate` calls, readable after the provider is boxed
+    pub fn call_counter(&self) -> Arc<AtomicU32> {
+        self.calls.clone()
+    }
+
+    /// Shared counter of `get_models` calls
+    pub fn model_list_counter(&self) -> Arc<AtomicU32> {
+        self.model_calls.clone()
+    }
+
+    /// Shared high-water mark of concurrent `generate` calls
+    pub fn peak_concurrency(&self) -> Arc<AtomicU32> {
+        self.peak_in_flight.clone()
+    }
+}
+
+#[async_trait]
+impl AIProvider for MockProvider {
+    async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.outcomes
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| {
+                Ok(GenerationResponse::new(format!("mock response for {}", request.model), request.model.clone())
+                    .with_usage(TokenUsage::new(10, 20)))
+            })
+    }
+
+    async fn generate_stream(&self, _request: GenerationRequest) -> Result<Box<dyn Stream<Item = Result<StreamResponse>> + Unpin + Send>> {
+        let mut items: Vec<Result<StreamResponse>> = self
+            .chunks
+            .iter()
+            .map(|c| Ok(StreamResponse::chunk(c.clone())))
+            .collect();
+        items.push(Ok(StreamResponse::finished()));
+        Ok(Box::new(futures::stream::iter(items)))
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let vocabulary = self.vocabulary.as_ref()
+            .ok_or_else(|| AIError::InvalidRequest("Mock provider does not support embeddings".to_string()))?;
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let text = text.to_lowercase();
+                vocabulary.iter().map(|word| text.matches(word.as_str()).count() as f32).collect()
+            })
+            .collect())
+    }
+
+    async fn get_models(&self) -> Result<Vec<ModelInfo>> {
+        self.model_calls.fetch_add(1, Ordering::SeqCst);
+        Ok(self
+            .models
+            .iter()
+            .map(|m| ModelInfo::new(m.clone(), m.clone()).with_streaming(true))
+            .collect())
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        Ok(self.healthy)
+    }
+
+    fn get_capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: true,
+            supports_functions: self.functions,
+            ..ProviderCapabilities::default()
+        }
+    }
+
+    fn get_provider_type(&self) -> ProviderType {
+        self.provider_type.clone()
+    }
+
+    async fn get_stats(&self) -> Result<GenerationStats> {
+        Ok(GenerationStats::default())
+    }
+
+    fn get_name(&self) -> &str {
+        "Mock"
+    }
+
+    fn get_description(&self) -> &str {
+        "Scripted provider for tests"
+    }
+
+    fn validate_model(&self, _model_name: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_default_model(&self) -> &str {
+        "mock-model"
+    }
+
+    fn estimate_cost(&self, _prompt_tokens: u32, _completion_tokens: u32, _model: &str) -> Option<f64> {
+        Some(0.0)
+    }
+}
@@ -3,6 +3,8 @@
 pub mod openai;
 pub mod anthropic;
 pub mod ollama;
+#[cfg(test)]
+pub mod mock;
 
 use crate::ai::{
     config::{ProviderConfig, ProviderType},
@@ -61,15 +63,36 @@ pub trait AIProvider: Send + Sync {
         self.get_capabilities().supports_functions
     }
 
+    /// Embed each text as a vector for semantic search. Providers without an
+    /// embeddings endpoint report it as unsupported.
+    async fn embed(&self, _texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Err(AIError::InvalidRequest(format!("{} does not support embeddings", self.get_name())))
+    }
+
     /// Estimate cost for a request (if pricing is available)
     fn estimate_cost(&self, prompt_tokens: u32, completion_tokens: u32, model: &str) -> Option<f64>;
 }
 
+/// Reject a request that defines tools when the provider can't call them
+pub fn ensure_tool_support(provider: &dyn AIProvider, request: &GenerationRequest) -> Result<()> {
+    if !request.tools.is_empty() && !provider.supports_functions() {
+        return Err(AIError::InvalidRequest(format!("{} does not support tool calling", provider.get_name())));
+    }
+    Ok(())
+}
+
 /// Factory function to create provider instances
 pub async fn create_provider(
     provider_type: ProviderType,
     config: ProviderConfig,
 ) -> Result<Box<dyn AIProvider + Send + Sync>> {
+    // Keys loaded while encryption is locked are still ciphertext
+    if config.api_key.as_deref().is_some_and(crate::encryption::field_encryption::looks_like_encrypted_data) {
+        return Err(AIError::AuthenticationError(format!(
+            "API key for {} is encrypted; unlock encryption to use this provider", provider_type
+        )));
+    }
+
     match provider_type {
         ProviderType::OpenAI => {
             let provider = openai::OpenAIProvider::new(config).await?;
@@ -86,6 +109,15 @@ pub async fn create_provider(
     }
 }
 
+/// Capabilities of a built-in provider type, without connecting to it
+pub fn capabilities_for(provider_type: &ProviderType) -> ProviderCapabilities {
+    match provider_type {
+        ProviderType::OpenAI => openai::OpenAIProvider::capabilities(),
+        ProviderType::Anthropic => anthropic::AnthropicProvider::capabilities(),
+        ProviderType::Ollama => ollama::OllamaProvider::capabilities(),
+    }
+}
+
 /// Utility function to validate provider configuration
 pub fn validate_provider_config(config: &ProviderConfig) -> Result<()> {
     config.validate().map_err(|e| AIError::ConfigurationError(e))?;
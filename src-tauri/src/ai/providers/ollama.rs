@@ -33,6 +33,8 @@ struct OllamaChatRequest {
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<OllamaOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -102,6 +104,20 @@ struct OllamaModelsResponse {
     models: Vec<OllamaModel>,
 }
 
+/// Embedding model used for semantic search; must be pulled into the local Ollama
+pub const EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+#[derive(Debug, Serialize)]
+struct OllamaEmbedRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
 /// Ollama provider implementation
 pub struct OllamaProvider {
     config: ProviderConfig,
@@ -110,6 +126,19 @@ pub struct OllamaProvider {
 }
 
 impl OllamaProvider {
+    /// What this provider supports; the same for every instance
+    pub fn capabilities() -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: true,
+            supports_functions: false, // Ollama doesn't support function calling
+            supports_vision: false,    // Model-dependent, but generally no
+            supports_fine_tuning: false,
+            max_context_length: Some(8192), // Default, varies by model
+            supported_formats: vec!["text".to_string()],
+            rate_limits: None, // No rate limits for local Ollama
+        }
+    }
+
     /// Create a new Ollama provider
     pub async fn new(config: ProviderConfig) -> Result<Self> {
         let client = Client::builder()
@@ -164,6 +193,7 @@ impl OllamaProvider {
             messages: self.convert_messages(&request.messages),
             stream: false,
             options: Some(self.convert_options(&request)),
+            format: request.wants_json().then(|| "json".to_string()),
         };
 
         let url = format!("{}/api/chat", 
@@ -213,6 +243,7 @@ impl OllamaProvider {
             messages: self.convert_messages(&request.messages),
             stream: true,
             options: Some(self.convert_options(&request)),
+            format: request.wants_json().then(|| "json".to_string()),
         };
 
         let url = format!("{}/api/chat", 
@@ -276,6 +307,39 @@ impl AIProvider for OllamaProvider {
         self.chat_stream(request).await
     }
 
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}/api/embed",
+            self.config.api_base_url.as_ref().unwrap());
+        let expected = texts.len();
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&OllamaEmbedRequest { model: EMBEDDING_MODEL.to_string(), input: texts })
+            .send()
+            .await
+            .map_err(|e| AIError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AIError::ProviderError(format!("Ollama API error: {}", error_text)));
+        }
+
+        let embeddings: OllamaEmbedResponse = response
+            .json()
+            .await
+            .map_err(|e| AIError::ParsingError(e.to_string()))?;
+        if embeddings.embeddings.len() != expected {
+            return Err(AIError::ProviderError(format!(
+                "Ollama returned {} embeddings for {} inputs", embeddings.embeddings.len(), expected
+            )));
+        }
+        Ok(embeddings.embeddings)
+    }
+
     async fn get_models(&self) -> Result<Vec<ModelInfo>> {
         let url = format!("{}/api/tags", 
             self.config.api_base_url.as_ref().unwrap());
@@ -321,15 +385,7 @@ impl AIProvider for OllamaProvider {
     }
 
     fn get_capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            supports_streaming: true,
-            supports_functions: false, // Ollama doesn't support function calling
-            supports_vision: false,    // Model-dependent, but generally no
-            supports_fine_tuning: false,
-            max_context_length: Some(8192), // Default, varies by model
-            supported_formats: vec!["text".to_string()],
-            rate_limits: None, // No rate limits for local Ollama
-        }
+        Self::capabilities()
     }
 
     fn get_provider_type(&self) -> ProviderType {
@@ -419,6 +475,8 @@ mod tests {
             },
             stream: false,
             metadata: std::collections::HashMap::new(),
+            truncation: Default::default(),
+            tools: Vec::new(),
         };
 
         let options = provider.convert_options(&request);
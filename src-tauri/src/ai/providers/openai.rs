@@ -6,6 +6,7 @@ use crate::ai::{
     models::{
         ChatMessage, GenerationRequest, GenerationResponse, GenerationStats,
         MessageRole, ModelInfo, ProviderCapabilities, StreamResponse, TokenUsage,
+        ToolCall, ToolDefinition,
     },
     providers::AIProvider,
 };
@@ -35,6 +36,10 @@ struct OpenAIRequest {
     stop: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<OpenAITool>>,
     stream: bool,
 }
 
@@ -46,6 +51,20 @@ struct OpenAIMessage {
     name: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct OpenAITool {
+    #[serde(rename = "type")]
+    tool_type: String,
+    function: OpenAIFunction,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAIFunction {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
 /// OpenAI API response structures
 #[derive(Debug, Deserialize)]
 struct OpenAIResponse {
@@ -67,7 +86,23 @@ struct OpenAIChoice {
 #[derive(Debug, Deserialize)]
 struct OpenAIResponseMessage {
     role: String,
-    content: String,
+    /// Null when the model only returns tool calls
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAIToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIToolCall {
+    id: String,
+    function: OpenAIFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIFunctionCall {
+    name: String,
+    /// JSON-encoded arguments object
+    arguments: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -113,6 +148,26 @@ struct OpenAIModelsResponse {
     data: Vec<OpenAIModel>,
 }
 
+/// Embedding model used for semantic search
+pub const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+#[derive(Debug, Serialize)]
+struct OpenAIEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIEmbeddingResponse {
+    data: Vec<OpenAIEmbedding>,
+}
+
 #[derive(Debug, Deserialize)]
 struct OpenAIError {
     error: OpenAIErrorDetails,
@@ -135,6 +190,22 @@ pub struct OpenAIProvider {
 }
 
 impl OpenAIProvider {
+    /// What this provider supports; the same for every instance
+    pub fn capabilities() -> ProviderCapabilities {
+        ProviderCapabilities {
+            supports_streaming: true,
+            supports_functions: true,
+            supports_vision: true, // GPT-4V models support vision
+            supports_fine_tuning: true,
+            max_context_length: Some(128000), // GPT-4o context length
+            supported_formats: vec!["text".to_string(), "image".to_string()],
+            rate_limits: Some([
+                ("requests_per_minute".to_string(), 10000),
+                ("tokens_per_minute".to_string(), 2000000),
+            ].iter().cloned().collect()),
+        }
+    }
+
     /// Create a new OpenAI provider
     pub async fn new(config: ProviderConfig) -> Result<Self> {
         let mut headers = HeaderMap::new();
@@ -227,6 +298,8 @@ impl OpenAIProvider {
             presence_penalty: request.params.presence_penalty,
             stop: request.params.stop_sequences.clone(),
             seed: request.params.seed,
+            response_format: request.wants_json().then(|| serde_json::json!({ "type": "json_object" })),
+            tools: (!request.tools.is_empty()).then(|| request.tools.iter().map(convert_tool).collect()),
             stream,
         }
     }
@@ -248,6 +321,9 @@ impl OpenAIProvider {
                     AIError::InvalidRequest(error_text.to_string())
                 }
             }
+            status if status.is_server_error() => {
+                AIError::ServiceUnavailable(format!("OpenAI API error ({}): {}", status, error_text))
+            }
             _ => AIError::ProviderError(format!("OpenAI API error ({}): {}", status, error_text))
         }
     }
@@ -286,6 +362,7 @@ impl OpenAIProvider {
         }
 
         let choice = &openai_response.choices[0];
+        let tool_calls = parse_tool_calls(&choice.message)?;
         let usage = TokenUsage::new(
             openai_response.usage.prompt_tokens,
             openai_response.usage.completion_tokens,
@@ -304,10 +381,11 @@ impl OpenAIProvider {
             stats.add_request(true, usage.total_tokens, response_time, cost);
         }
 
-        Ok(GenerationResponse::new(choice.message.content.clone(), request.model)
+        Ok(GenerationResponse::new(choice.message.content.clone().unwrap_or_default(), request.model)
             .with_usage(usage)
             .with_finish_reason(choice.finish_reason.clone().unwrap_or("unknown".to_string()))
-            .with_response_time(response_time))
+            .with_response_time(response_time)
+            .with_tool_calls(tool_calls))
     }
 
     /// Make a streaming chat request
@@ -371,6 +449,35 @@ impl OpenAIProvider {
     }
 }
 
+fn convert_tool(tool: &ToolDefinition) -> OpenAITool {
+    OpenAITool {
+        tool_type: "function".to_string(),
+        function: OpenAIFunction {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: tool.parameters.clone(),
+        },
+    }
+}
+
+/// Tool calls from a response message, with their JSON-encoded arguments decoded
+fn parse_tool_calls(message: &OpenAIResponseMessage) -> Result<Vec<ToolCall>> {
+    message
+        .tool_calls
+        .iter()
+        .map(|call| {
+            let arguments = serde_json::from_str(&call.function.arguments).map_err(|e| {
+                AIError::ParsingError(format!("Invalid arguments for tool {}: {}", call.function.name, e))
+            })?;
+            Ok(ToolCall {
+                id: call.id.clone(),
+                name: call.function.name.clone(),
+                arguments,
+            })
+        })
+        .collect()
+}
+
 #[async_trait]
 impl AIProvider for OpenAIProvider {
     async fn generate(&self, request: GenerationRequest) -> Result<GenerationResponse> {
@@ -383,6 +490,43 @@ impl AIProvider for OpenAIProvider {
         self.chat_stream(request).await
     }
 
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = format!("{}/embeddings",
+            self.config.api_base_url.as_ref().unwrap());
+        let expected = texts.len();
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&OpenAIEmbeddingRequest { model: EMBEDDING_MODEL.to_string(), input: texts })
+            .send()
+            .await
+            .map_err(|e| AIError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(self.handle_error(status, &error_text));
+        }
+
+        let mut embeddings: OpenAIEmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| AIError::ParsingError(e.to_string()))?;
+        if embeddings.data.len() != expected {
+            return Err(AIError::ProviderError(format!(
+                "OpenAI returned {} embeddings for {} inputs", embeddings.data.len(), expected
+            )));
+        }
+
+        // The API doesn't promise to return embeddings in input order
+        embeddings.data.sort_by_key(|e| e.index);
+        Ok(embeddings.data.into_iter().map(|e| e.embedding).collect())
+    }
+
     async fn get_models(&self) -> Result<Vec<ModelInfo>> {
         let url = format!("{}/models", 
             self.config.api_base_url.as_ref().unwrap());
@@ -460,18 +604,7 @@ impl AIProvider for OpenAIProvider {
     }
 
     fn get_capabilities(&self) -> ProviderCapabilities {
-        ProviderCapabilities {
-            supports_streaming: true,
-            supports_functions: true,
-            supports_vision: true, // GPT-4V models support vision
-            supports_fine_tuning: true,
-            max_context_length: Some(128000), // GPT-4o context length
-            supported_formats: vec!["text".to_string(), "image".to_string()],
-            rate_limits: Some([
-                ("requests_per_minute".to_string(), 10000),
-                ("tokens_per_minute".to_string(), 2000000),
-            ].iter().cloned().collect()),
-        }
+        Self::capabilities()
     }
 
     fn get_provider_type(&self) -> ProviderType {
@@ -566,6 +699,8 @@ mod tests {
             },
             stream: false,
             metadata: std::collections::HashMap::new(),
+            truncation: Default::default(),
+            tools: Vec::new(),
         };
 
         let openai_request = provider.create_openai_request(&request, true);
@@ -575,6 +710,59 @@ mod tests {
         assert!(openai_request.stream);
     }
 
+    #[test]
+    fn test_tools_round_trip() {
+        let config = ProviderConfig::openai("test-key");
+        let provider = OpenAIProvider {
+            config,
+            client: Client::new(),
+            stats: RwLock::new(GenerationStats::default()),
+        };
+
+        let request = GenerationRequest::new(vec![ChatMessage::user("Weather in Perth?")], "gpt-4o-mini")
+            .with_tools(vec![ToolDefinition::new(
+                "get_weather",
+                "Current weather for a city",
+                serde_json::json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+            )]);
+        let body = serde_json::to_value(provider.create_openai_request(&request, false)).unwrap();
+        assert_eq!(body["tools"][0]["type"], "function");
+        assert_eq!(body["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(body["tools"][0]["function"]["parameters"]["properties"]["city"]["type"], "string");
+
+        let without_tools = GenerationRequest::new(vec![ChatMessage::user("Hi")], "gpt-4o-mini");
+        let body = serde_json::to_value(provider.create_openai_request(&without_tools, false)).unwrap();
+        assert!(body.get("tools").is_none());
+
+        let response: OpenAIResponse = serde_json::from_str(r#"{
+            "id": "chatcmpl-1", "object": "chat.completion", "created": 1700000000, "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Perth\"}" }
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 20, "completion_tokens": 10, "total_tokens": 30 }
+        }"#).unwrap();
+        let message = &response.choices[0].message;
+        assert_eq!(message.content, None);
+        assert_eq!(
+            parse_tool_calls(message).unwrap(),
+            vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                arguments: serde_json::json!({ "city": "Perth" }),
+            }]
+        );
+    }
+
     #[test]
     fn test_cost_estimation() {
         let config = ProviderConfig::openai("test-key");
@@ -2,8 +2,14 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::ai::{AIManager, GenerationRequest, models::ChatMessage};
+use crate::ai::{AIManager, GenerationRequest, GenerationRequestBuilder, GenerationStyle, ModelUseCase};
+use crate::ai::models::{ChatMessage, GenerationParams};
 use crate::ai::errors::{AIError, Result};
+use crate::ai::prompts::{validate_system_prompt_override, RenderedPrompt};
+use crate::ai::sanitize::Sanitizer;
+use crate::assessment::RubricCriterion;
+use crate::case_study::{CaseStudyError, CaseStudyManager};
+use crate::database::{AssessmentQuestionRepository, NewCaseStudyQuestion, QuestionType as StoredQuestionType};
 
 /// Parameters for question generation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +25,15 @@ pub struct QuestionGenerationParams {
     pub include_rubric: bool,
     pub target_duration_minutes: Option<u32>,
     pub focus_areas: Vec<String>,
+    /// Replaces the question template's system prompt for this call
+    #[serde(default)]
+    pub system_prompt_override: Option<String>,
+    /// Sampling preset for the main generation call
+    #[serde(default)]
+    pub style: GenerationStyle,
+    /// Explicit sampling parameters; any that are set take precedence over `style`
+    #[serde(default)]
+    pub generation_params: Option<GenerationParams>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -66,6 +81,9 @@ pub struct AssessmentQuestion {
     pub sample_answer: Option<String>, // For subjective questions
     pub explanation: Option<String>,
     pub evaluation_criteria: Option<Vec<String>>, // For subjective questions
+    /// Grading rubric whose points add up to the question's points
+    #[serde(default)]
+    pub rubric: Option<Vec<RubricCriterion>>,
     pub keywords: Vec<String>,
     pub learning_objective: Option<String>,
 }
@@ -87,6 +105,30 @@ pub struct AssessmentMetadata {
     pub estimated_grading_time_minutes: u32,
 }
 
+impl GeneratedAssessment {
+    /// Strip disallowed HTML from the instructions, rubric and every question
+    pub fn sanitize(&mut self, sanitizer: &Sanitizer) {
+        sanitizer.clean_in_place(&mut self.instructions);
+        sanitizer.clean_option(&mut self.rubric);
+        for question in &mut self.questions {
+            sanitizer.clean_in_place(&mut question.question_text);
+            sanitizer.clean_option(&mut question.correct_answer);
+            sanitizer.clean_option(&mut question.sample_answer);
+            sanitizer.clean_option(&mut question.explanation);
+            if let Some(criteria) = &mut question.evaluation_criteria {
+                sanitizer.clean_all(criteria);
+            }
+            for criterion in question.rubric.iter_mut().flatten() {
+                sanitizer.clean_in_place(&mut criterion.name);
+            }
+            for option in question.options.iter_mut().flatten() {
+                sanitizer.clean_in_place(&mut option.text);
+                sanitizer.clean_option(&mut option.explanation);
+            }
+        }
+    }
+}
+
 /// Question generation service
 pub struct QuestionGenerator {
     ai_manager: AIManager,
@@ -128,7 +170,7 @@ impl QuestionGenerator {
         let total_points = all_questions.iter().map(|q| q.points).sum();
         let estimated_duration = self.estimate_assessment_duration(&all_questions, &params);
         
-        Ok(GeneratedAssessment {
+        let mut assessment = GeneratedAssessment {
             title: format!("Assessment: {}", params.case_study_title),
             instructions,
             questions: all_questions,
@@ -136,7 +178,28 @@ impl QuestionGenerator {
             estimated_duration_minutes: estimated_duration,
             rubric,
             metadata,
-        })
+        };
+        let config = self.ai_manager.get_config().await.sanitization;
+        assessment.sanitize(&Sanitizer::new(&config));
+        Ok(assessment)
+    }
+
+    /// Generate questions for a saved case study and store them after its existing questions.
+    /// Every question is validated before anything is written. Returns the new question ids in order.
+    pub async fn generate_and_save_questions(
+        &self,
+        case_studies: &CaseStudyManager,
+        repository: &AssessmentQuestionRepository,
+        case_study_id: &str,
+        params: QuestionGenerationParams,
+    ) -> Result<Vec<i64>> {
+        if !case_studies.case_study_exists(case_study_id).await? {
+            return Err(CaseStudyError::NotFound(case_study_id.to_string()).into());
+        }
+
+        let max_points = params.max_points_per_question;
+        let assessment = self.generate_assessment(params).await?;
+        save_questions(repository, case_study_id, &assessment.questions, max_points).await
     }
 
     /// Validate generation parameters
@@ -177,6 +240,8 @@ impl QuestionGenerator {
             return Err(AIError::ValidationError("At least one learning objective must be provided".to_string()));
         }
 
+        validate_system_prompt_override(params.system_prompt_override.as_deref())?;
+
         Ok(())
     }
 
@@ -202,6 +267,36 @@ impl QuestionGenerator {
         question_type: &QuestionType,
         count: u32,
     ) -> Result<Vec<AssessmentQuestion>> {
+        let rendered = self.render_question_prompt(params, question_type, count)?;
+
+        // Create generation request
+        let mut messages = Vec::new();
+        if let Some(system) = rendered.system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(rendered.user_prompt));
+
+        let mut builder = GenerationRequestBuilder::new(messages)
+            .use_case(ModelUseCase::QuestionGeneration)
+            .style(params.style);
+        if let Some(overrides) = &params.generation_params {
+            builder = builder.params(overrides.clone());
+        }
+        let request = self.ai_manager.build_request(builder).await?
+            .with_metadata("generation_type", serde_json::json!("questions"));
+        let response = self.ai_manager.generate(request).await?;
+
+        // Parse the response into structured questions
+        self.parse_questions_response(&response.content, question_type, params).await
+    }
+
+    /// Render the question template for one question type, applying any system prompt override
+    fn render_question_prompt(
+        &self,
+        params: &QuestionGenerationParams,
+        question_type: &QuestionType,
+        count: u32,
+    ) -> Result<RenderedPrompt> {
         let prompt_manager = self.ai_manager.get_prompt_manager();
         
         // Prepare template variables
@@ -215,9 +310,11 @@ impl QuestionGenerator {
         variables.insert("num_questions".to_string(), serde_json::json!(count));
         variables.insert("max_points".to_string(), serde_json::json!(params.max_points_per_question));
         variables.insert("focus_areas".to_string(), serde_json::json!(params.focus_areas.join(", ")));
+        variables.insert("question_types".to_string(), serde_json::json!(question_type.to_string()));
+        variables.insert("include_rubric".to_string(), serde_json::json!(params.include_rubric && is_open_ended(question_type)));
 
         // Select appropriate template based on question type
-        let template_id = match question_type {
+        let type_template_id = match question_type {
             QuestionType::MultipleChoice => "multiple_choice_questions",
             QuestionType::ShortAnswer => "short_answer_questions",
             QuestionType::Essay => "essay_questions",
@@ -226,27 +323,15 @@ impl QuestionGenerator {
             QuestionType::Calculation => "calculation_questions",
             QuestionType::Scenario => "scenario_questions",
         };
+        // Without a template for the type, the general question template is used
+        let template_id = if prompt_manager.get_template(type_template_id).is_some() {
+            type_template_id
+        } else {
+            "assessment_questions"
+        };
 
-        // Render the template
         let rendered = prompt_manager.render_template(template_id, &variables)?;
-
-        // Create generation request
-        let mut messages = Vec::new();
-        if let Some(system) = rendered.system_prompt {
-            messages.push(ChatMessage::system(system));
-        }
-        messages.push(ChatMessage::user(rendered.user_prompt));
-
-        let config = self.ai_manager.get_config().await;
-        let default_provider = &config.default_provider;
-        let provider_config = config.providers.get(default_provider)
-            .ok_or_else(|| AIError::ConfigurationError("Default provider not configured".to_string()))?;
-        
-        let request = GenerationRequest::new(messages, provider_config.default_model.clone());
-        let response = self.ai_manager.generate(request).await?;
-
-        // Parse the response into structured questions
-        self.parse_questions_response(&response.content, question_type, params).await
+        Ok(rendered.with_system_prompt_override(params.system_prompt_override.as_deref()))
     }
 
     /// Parse AI response into structured questions
@@ -291,6 +376,7 @@ impl QuestionGenerator {
                     sample_answer: None,
                     explanation: None,
                     evaluation_criteria: None,
+                    rubric: None,
                     keywords: Vec::new(),
                     learning_objective: params.learning_objectives.first().cloned(),
                 });
@@ -341,6 +427,15 @@ impl QuestionGenerator {
                     }
                 }
             }
+            // Handle the JSON rubric requested by the rubric section of the prompt
+            else if let Some(raw) = trimmed.strip_prefix("Rubric:") {
+                if let Some(ref mut question) = current_question {
+                    let rubric = normalize_rubric(raw, question.points).map_err(|reason| {
+                        AIError::ValidationError(format!("Question {}: {}", question_counter - 1, reason))
+                    })?;
+                    question.rubric = Some(rubric);
+                }
+            }
         }
         
         // Don't forget the last question
@@ -373,7 +468,8 @@ impl QuestionGenerator {
         let provider_config = config.providers.get(default_provider)
             .ok_or_else(|| AIError::ConfigurationError("Default provider not configured".to_string()))?;
         
-        let request = GenerationRequest::new(messages, provider_config.default_model.clone());
+        let request = GenerationRequest::new(messages, provider_config.default_model.clone())
+            .with_metadata("generation_type", serde_json::json!("questions"));
         let response = self.ai_manager.generate(request).await?;
         Ok(response.content)
     }
@@ -407,7 +503,8 @@ impl QuestionGenerator {
         let provider_config = config.providers.get(default_provider)
             .ok_or_else(|| AIError::ConfigurationError("Default provider not configured".to_string()))?;
         
-        let request = GenerationRequest::new(messages, provider_config.default_model.clone());
+        let request = GenerationRequest::new(messages, provider_config.default_model.clone())
+            .with_metadata("generation_type", serde_json::json!("questions"));
         let response = self.ai_manager.generate(request).await?;
         Ok(response.content)
     }
@@ -536,6 +633,174 @@ impl QuestionGenerator {
     }
 }
 
+/// Questions graded against a rubric rather than an answer key
+fn is_open_ended(question_type: &QuestionType) -> bool {
+    !matches!(question_type, QuestionType::MultipleChoice | QuestionType::TrueFalse)
+}
+
+/// Rubric points may be off from the question's points by this much before being rescaled
+const RUBRIC_POINTS_TOLERANCE: f64 = 0.01;
+
+#[derive(Deserialize)]
+struct GeneratedRubric {
+    criteria: Vec<RubricCriterion>,
+}
+
+/// Parse a generated rubric in the `{"criteria": [{"name", "points"}]}` form the grading
+/// engine reads. Criteria that don't add up to `max_points` are scaled to it, rounded to
+/// two decimals; malformed JSON, blank or duplicate names and negative points are rejected.
+pub fn normalize_rubric(raw: &str, max_points: u32) -> std::result::Result<Vec<RubricCriterion>, String> {
+    let mut criteria = serde_json::from_str::<GeneratedRubric>(raw.trim())
+        .map_err(|e| format!("rubric is not valid JSON: {}", e))?
+        .criteria;
+
+    if criteria.is_empty() {
+        return Err("rubric has no criteria".to_string());
+    }
+    for (index, criterion) in criteria.iter_mut().enumerate() {
+        criterion.name = criterion.name.trim().to_string();
+        if criterion.name.is_empty() {
+            return Err(format!("rubric criterion {} has no name", index + 1));
+        }
+        if !criterion.points.is_finite() || criterion.points < 0.0 {
+            return Err(format!("rubric criterion '{}' has invalid points {}", criterion.name, criterion.points));
+        }
+    }
+    for (index, criterion) in criteria.iter().enumerate() {
+        if criteria[..index].iter().any(|c| c.name.eq_ignore_ascii_case(&criterion.name)) {
+            return Err(format!("rubric criterion '{}' appears more than once", criterion.name));
+        }
+    }
+
+    let total: f64 = criteria.iter().map(|c| c.points).sum();
+    if total <= 0.0 {
+        return Err("rubric criteria have no points".to_string());
+    }
+
+    let max_points = f64::from(max_points);
+    if (total - max_points).abs() > RUBRIC_POINTS_TOLERANCE {
+        for criterion in &mut criteria {
+            criterion.points = (criterion.points * max_points / total * 100.0).round() / 100.0;
+        }
+        // Rounding can leave a remainder; the largest criterion absorbs it
+        let remainder = max_points - criteria.iter().map(|c| c.points).sum::<f64>();
+        if let Some(largest) = criteria.iter_mut().max_by(|a, b| a.points.total_cmp(&b.points)) {
+            largest.points = ((largest.points + remainder) * 100.0).round() / 100.0;
+        }
+    }
+
+    Ok(criteria)
+}
+
+/// Validate generated questions and store them, in order, for a case study. Nothing is
+/// written unless every question is valid.
+pub async fn save_questions(
+    repository: &AssessmentQuestionRepository,
+    case_study_id: &str,
+    questions: &[AssessmentQuestion],
+    max_points: u32,
+) -> Result<Vec<i64>> {
+    if questions.is_empty() {
+        return Err(AIError::ValidationError("No questions to save".to_string()));
+    }
+
+    let rows = questions
+        .iter()
+        .enumerate()
+        .map(|(index, question)| {
+            validate_question(question, max_points)
+                .map_err(|reason| AIError::ValidationError(format!("Question {}: {}", index + 1, reason)))?;
+            to_stored_question(question)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    repository
+        .create_for_case_study(case_study_id, &rows)
+        .await
+        .map_err(|e| AIError::Unknown(format!("Failed to save questions: {}", e)))
+}
+
+/// Options a choice question is answered from. True/false questions without explicit
+/// options get the two obvious ones.
+fn choice_options(question: &AssessmentQuestion) -> Option<Vec<QuestionOption>> {
+    match (&question.question_type, &question.options) {
+        (QuestionType::TrueFalse, None) => Some(
+            ["True", "False"]
+                .iter()
+                .map(|text| QuestionOption {
+                    id: text.to_lowercase(),
+                    text: text.to_string(),
+                    is_correct: question
+                        .correct_answer
+                        .as_deref()
+                        .is_some_and(|answer| answer.trim().eq_ignore_ascii_case(text)),
+                    explanation: None,
+                })
+                .collect(),
+        ),
+        (_, options) => options.clone(),
+    }
+}
+
+/// Check that a question's type, options, answer and points agree
+fn validate_question(question: &AssessmentQuestion, max_points: u32) -> std::result::Result<(), String> {
+    if question.question_text.trim().is_empty() {
+        return Err("question text is empty".to_string());
+    }
+    if question.points == 0 || question.points > max_points {
+        return Err(format!("points must be between 1 and {}", max_points));
+    }
+
+    let options = choice_options(question).unwrap_or_default();
+    match question.question_type {
+        QuestionType::MultipleChoice | QuestionType::TrueFalse => {
+            if options.len() < 2 {
+                return Err(format!("{} questions need at least two options", question.question_type));
+            }
+            let answer = question
+                .correct_answer
+                .as_deref()
+                .map(str::trim)
+                .filter(|answer| !answer.is_empty())
+                .ok_or_else(|| "no correct answer given".to_string())?;
+            let is_answer = |option: &QuestionOption| {
+                option.id.eq_ignore_ascii_case(answer) || option.text.trim().eq_ignore_ascii_case(answer)
+            };
+            if !options.iter().any(is_answer) {
+                return Err(format!("correct answer '{}' is not one of the options", answer));
+            }
+        }
+        _ if !options.is_empty() => {
+            return Err(format!("{} questions can't have options", question.question_type));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn to_stored_question(question: &AssessmentQuestion) -> Result<NewCaseStudyQuestion> {
+    let question_type = match question.question_type {
+        QuestionType::MultipleChoice | QuestionType::TrueFalse => StoredQuestionType::MultipleChoice,
+        QuestionType::ShortAnswer | QuestionType::Calculation => StoredQuestionType::ShortAnswer,
+        QuestionType::Essay => StoredQuestionType::Essay,
+        QuestionType::CaseAnalysis => StoredQuestionType::Analysis,
+        QuestionType::Scenario => StoredQuestionType::Reflection,
+    };
+
+    Ok(NewCaseStudyQuestion {
+        question_text: question.question_text.clone(),
+        question_type,
+        options: choice_options(question).map(|options| serde_json::to_string(&options)).transpose()?,
+        correct_answer: question.correct_answer.clone(),
+        sample_answer: question.sample_answer.clone(),
+        rubric: match &question.rubric {
+            Some(criteria) => Some(serde_json::to_string(&serde_json::json!({ "criteria": criteria }))?),
+            None => question.evaluation_criteria.as_ref().map(serde_json::to_string).transpose()?,
+        },
+        points: i64::from(question.points),
+    })
+}
+
 impl Default for QuestionGenerationParams {
     fn default() -> Self {
         Self {
@@ -550,6 +815,9 @@ impl Default for QuestionGenerationParams {
             include_rubric: true,
             target_duration_minutes: Some(60),
             focus_areas: vec![],
+            system_prompt_override: None,
+            style: GenerationStyle::default(),
+            generation_params: None,
         }
     }
 }
@@ -577,4 +845,227 @@ impl std::fmt::Display for QuestionDifficulty {
             QuestionDifficulty::Mixed => write!(f, "mixed"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case_study::NewCaseStudy;
+    use crate::database::DatabaseManager;
+    use sqlx::Row;
+
+    async fn case_study(db: &DatabaseManager) -> String {
+        CaseStudyManager::new(db.clone())
+            .create_case_study(NewCaseStudy {
+                industry: "Retail".to_string(),
+                duration_minutes: 60,
+                learning_objectives: vec!["Evaluate market entry options".to_string()],
+                ..NewCaseStudy::for_test("Nordic Outfitters", &"The leadership team must decide whether to expand into a new region. ".repeat(3))
+            })
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn question(question_type: QuestionType, text: &str, answer: Option<&str>) -> AssessmentQuestion {
+        AssessmentQuestion {
+            id: text.to_string(),
+            question_type,
+            difficulty: QuestionDifficulty::Intermediate,
+            question_text: text.to_string(),
+            points: 5,
+            options: None,
+            correct_answer: answer.map(str::to_string),
+            sample_answer: None,
+            explanation: None,
+            evaluation_criteria: None,
+            rubric: None,
+            keywords: Vec::new(),
+            learning_objective: None,
+        }
+    }
+
+    fn multiple_choice(text: &str, answer: &str) -> AssessmentQuestion {
+        let mut question = question(QuestionType::MultipleChoice, text, Some(answer));
+        question.options = Some(
+            ["a", "b", "c"]
+                .iter()
+                .map(|id| QuestionOption {
+                    id: id.to_string(),
+                    text: format!("Option {}", id),
+                    is_correct: *id == answer,
+                    explanation: None,
+                })
+                .collect(),
+        );
+        question
+    }
+
+    async fn stored_questions(db: &DatabaseManager, case_study_id: &str) -> Vec<(String, String, i64)> {
+        sqlx::query("SELECT question_text, question_type, order_index FROM assessment_questions WHERE case_study_id = ? ORDER BY order_index")
+            .bind(case_study_id)
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get(0), row.get(1), row.get(2)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_questions_are_saved_in_order() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let case_study_id = case_study(&db).await;
+        let repository = AssessmentQuestionRepository::new(db.pool().clone());
+
+        let questions = vec![
+            multiple_choice("Which entry mode fits?", "b"),
+            question(QuestionType::TrueFalse, "The market is saturated.", Some("False")),
+            question(QuestionType::Essay, "Evaluate the board's options.", None),
+        ];
+        let ids = save_questions(&repository, &case_study_id, &questions, 10).await.unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // A second batch continues the ordering
+        let more = vec![question(QuestionType::CaseAnalysis, "Analyse the competitors.", None)];
+        save_questions(&repository, &case_study_id, &more, 10).await.unwrap();
+
+        assert_eq!(
+            stored_questions(&db, &case_study_id).await,
+            vec![
+                ("Which entry mode fits?".to_string(), "multiple_choice".to_string(), 0),
+                ("The market is saturated.".to_string(), "multiple_choice".to_string(), 1),
+                ("Evaluate the board's options.".to_string(), "essay".to_string(), 2),
+                ("Analyse the competitors.".to_string(), "analysis".to_string(), 3),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_multiple_choice_is_rejected_before_insert() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let case_study_id = case_study(&db).await;
+        let repository = AssessmentQuestionRepository::new(db.pool().clone());
+
+        let questions = vec![
+            question(QuestionType::ShortAnswer, "Name the main risk.", None),
+            multiple_choice("Which entry mode fits?", "e"),
+        ];
+        let err = save_questions(&repository, &case_study_id, &questions, 10).await.unwrap_err();
+        assert!(matches!(&err, AIError::ValidationError(msg) if msg.starts_with("Question 2")), "{}", err);
+        assert!(stored_questions(&db, &case_study_id).await.is_empty());
+
+        let mut no_answer = multiple_choice("Which entry mode fits?", "a");
+        no_answer.correct_answer = None;
+        assert!(save_questions(&repository, &case_study_id, &[no_answer], 10).await.is_err());
+
+        let mut with_options = multiple_choice("Discuss the strategy.", "a");
+        with_options.question_type = QuestionType::Essay;
+        assert!(save_questions(&repository, &case_study_id, &[with_options], 10).await.is_err());
+
+        let too_many_points = question(QuestionType::Essay, "Discuss the strategy.", None);
+        assert!(save_questions(&repository, &case_study_id, &[too_many_points], 4).await.is_err());
+        assert!(stored_questions(&db, &case_study_id).await.is_empty());
+    }
+
+    /// Generate one essay question with a rubric, the provider answering with `response`
+    async fn generate_essay(response: &str) -> Result<GeneratedAssessment> {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let manager = AIManager::detached(db.pool().clone());
+        let provider = crate::ai::providers::mock::MockProvider::new(crate::ai::ProviderType::Ollama)
+            .respond_with(response);
+        manager.set_active_provider(Box::new(provider)).await;
+
+        let params = QuestionGenerationParams {
+            case_study_content: "The leadership team must decide whether to expand into a new region. ".repeat(4),
+            question_types: vec![QuestionType::Essay],
+            num_questions: 1,
+            max_points_per_question: 10,
+            include_rubric: true,
+            ..QuestionGenerationParams::default()
+        };
+        QuestionGenerator::new(manager).generate_assessment(params).await
+    }
+
+    fn rubric_points(question: &AssessmentQuestion) -> Vec<(String, f64)> {
+        question.rubric.iter().flatten().map(|c| (c.name.clone(), c.points)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_generated_rubric_is_kept_and_stored_for_grading() {
+        let assessment = generate_essay(
+            "1. Evaluate the board's expansion options.\nRubric: {\"criteria\": [{\"name\": \"Analysis\", \"points\": 6}, {\"name\": \"Recommendation\", \"points\": 4}]}",
+        )
+        .await
+        .unwrap();
+        let question = &assessment.questions[0];
+        assert_eq!(rubric_points(question), vec![("Analysis".to_string(), 6.0), ("Recommendation".to_string(), 4.0)]);
+
+        // The stored rubric parses back to the same criteria in the grading engine
+        let stored = to_stored_question(question).unwrap();
+        let parsed = crate::assessment::rubric::parse_rubric(stored.rubric.as_deref(), 10.0);
+        assert_eq!(Some(parsed), question.rubric);
+    }
+
+    #[tokio::test]
+    async fn test_over_summed_rubric_is_scaled_to_question_points() {
+        let assessment = generate_essay(
+            "1. Evaluate the board's expansion options.\nRubric: {\"criteria\": [{\"name\": \"Analysis\", \"points\": 12}, {\"name\": \"Recommendation\", \"points\": 8}]}",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            rubric_points(&assessment.questions[0]),
+            vec![("Analysis".to_string(), 6.0), ("Recommendation".to_string(), 4.0)]
+        );
+
+        // Rounding remainders still leave an exact total
+        let thirds = normalize_rubric(r#"{"criteria": [{"name": "A", "points": 5}, {"name": "B", "points": 5}, {"name": "C", "points": 5}]}"#, 10).unwrap();
+        assert!((thirds.iter().map(|c| c.points).sum::<f64>() - 10.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_rubric_is_rejected() {
+        let err = generate_essay("1. Evaluate the board's expansion options.\nRubric: {\"criteria\": \"analysis\"}")
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, AIError::ValidationError(msg) if msg.starts_with("Question 1")), "{}", err);
+
+        for raw in [
+            r#"{"criteria": []}"#,
+            r#"{"criteria": [{"name": " ", "points": 10}]}"#,
+            r#"{"criteria": [{"name": "Analysis", "points": -2}, {"name": "Depth", "points": 12}]}"#,
+            r#"{"criteria": [{"name": "Analysis", "points": 5}, {"name": "analysis", "points": 5}]}"#,
+            r#"{"criteria": [{"name": "Analysis", "points": 0}]}"#,
+        ] {
+            assert!(normalize_rubric(raw, 10).is_err(), "{}", raw);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_override_replaces_template_persona() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let generator = QuestionGenerator::new(AIManager::detached(db.pool().clone()));
+        let params = QuestionGenerationParams {
+            case_study_content: "The leadership team must decide whether to expand into a new region. ".repeat(4),
+            ..QuestionGenerationParams::default()
+        };
+
+        let default = generator.render_question_prompt(&params, &QuestionType::Essay, 2).unwrap();
+        assert!(default.system_prompt.as_deref().unwrap().starts_with("You are an educational assessment expert"));
+
+        let with_override = QuestionGenerationParams {
+            system_prompt_override: Some("You write questions for executive MBA students.".to_string()),
+            ..params.clone()
+        };
+        let overridden = generator.render_question_prompt(&with_override, &QuestionType::Essay, 2).unwrap();
+        assert_eq!(overridden.system_prompt.as_deref(), Some("You write questions for executive MBA students."));
+        assert_eq!(overridden.user_prompt, default.user_prompt);
+
+        let too_long = QuestionGenerationParams {
+            system_prompt_override: Some("x".repeat(crate::ai::prompts::MAX_SYSTEM_PROMPT_OVERRIDE_CHARS + 1)),
+            ..params
+        };
+        assert!(matches!(generator.validate_parameters(&too_long), Err(AIError::ValidationError(_))));
+    }
+}
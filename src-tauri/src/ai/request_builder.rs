@@ -0,0 +1,263 @@
+// Builder for generation requests that resolves the model and its parameter defaults
+
+use crate::ai::config::AIConfig;
+use crate::ai::errors::{AIError, Result};
+use crate::ai::model_config::{GenerationStyle, ModelConfigManager, ModelUseCase};
+use crate::ai::models::{ChatMessage, GenerationParams, GenerationRequest};
+
+/// Builds a `GenerationRequest` for the configured default model, starting from the
+/// model's `default_params`, adjusted for the use case and style, then applying caller overrides
+pub struct GenerationRequestBuilder {
+    messages: Vec<ChatMessage>,
+    model: Option<String>,
+    use_case: ModelUseCase,
+    style: Option<GenerationStyle>,
+    overrides: GenerationParams,
+    json_output: bool,
+}
+
+impl GenerationRequestBuilder {
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        Self {
+            messages,
+            model: None,
+            use_case: ModelUseCase::GeneralChat,
+            style: None,
+            overrides: unset_params(),
+            json_output: false,
+        }
+    }
+
+    /// Use this model instead of the default provider's default model
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn use_case(mut self, use_case: ModelUseCase) -> Self {
+        self.use_case = use_case;
+        self
+    }
+
+    /// Sampling preset layered over the use case defaults; explicit parameters still win
+    pub fn style(mut self, style: GenerationStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.overrides.temperature = Some(temperature);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.overrides.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Override every parameter that is set in `params`
+    pub fn params(mut self, params: GenerationParams) -> Self {
+        self.overrides = merge(params, self.overrides);
+        self
+    }
+
+    pub fn json_output(mut self) -> Self {
+        self.json_output = true;
+        self
+    }
+
+    /// Resolve the model and parameters. Fails with `AIError::ConfigurationError` when no
+    /// model was given and the default provider is not configured. Parameters for models
+    /// in the registry are clamped to the model's constraints.
+    pub fn build(self, config: &AIConfig, models: &ModelConfigManager) -> Result<GenerationRequest> {
+        let model = match self.model {
+            Some(model) => model,
+            None => config
+                .providers
+                .get(&config.default_provider)
+                .map(|provider| provider.default_model.clone())
+                .ok_or_else(|| {
+                    AIError::ConfigurationError(format!("Default provider {} not configured", config.default_provider))
+                })?,
+        };
+
+        let model_defaults = models
+            .get_model_config(&model)
+            .map(|model_config| model_config.default_params.clone())
+            .unwrap_or_default();
+        let mut defaults = merge(use_case_params(&self.use_case), model_defaults);
+        if let Some(style) = self.style {
+            defaults = merge(style.target_params(), defaults);
+        }
+        let mut params = merge(self.overrides, defaults);
+        if models.get_model_config(&model).is_some() {
+            params = models.adjust_parameters(&model, &params)?;
+        }
+
+        let request = GenerationRequest::new(self.messages, model).with_params(params);
+        Ok(if self.json_output { request.with_json_output() } else { request })
+    }
+}
+
+/// Parameters that suit a use case better than the model's general defaults
+fn use_case_params(use_case: &ModelUseCase) -> GenerationParams {
+    let (temperature, max_tokens) = match use_case {
+        ModelUseCase::CaseStudyGeneration => (Some(0.7), Some(4096)),
+        ModelUseCase::QuestionGeneration => (Some(0.5), None),
+        ModelUseCase::ContentAnalysis => (Some(0.3), None),
+        ModelUseCase::SummaryGeneration => (Some(0.3), Some(1024)),
+        ModelUseCase::CodeGeneration => (Some(0.2), None),
+        ModelUseCase::CreativeWriting => (Some(0.9), None),
+        ModelUseCase::GeneralChat => (None, None),
+    };
+    GenerationParams {
+        temperature,
+        max_tokens,
+        ..unset_params()
+    }
+}
+
+fn unset_params() -> GenerationParams {
+    GenerationParams {
+        temperature: None,
+        max_tokens: None,
+        top_p: None,
+        top_k: None,
+        frequency_penalty: None,
+        presence_penalty: None,
+        stop_sequences: None,
+        seed: None,
+    }
+}
+
+/// Each parameter from `preferred` if set, otherwise from `fallback`
+fn merge(preferred: GenerationParams, fallback: GenerationParams) -> GenerationParams {
+    GenerationParams {
+        temperature: preferred.temperature.or(fallback.temperature),
+        max_tokens: preferred.max_tokens.or(fallback.max_tokens),
+        top_p: preferred.top_p.or(fallback.top_p),
+        top_k: preferred.top_k.or(fallback.top_k),
+        frequency_penalty: preferred.frequency_penalty.or(fallback.frequency_penalty),
+        presence_penalty: preferred.presence_penalty.or(fallback.presence_penalty),
+        stop_sequences: preferred.stop_sequences.or(fallback.stop_sequences),
+        seed: preferred.seed.or(fallback.seed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::config::ProviderConfig;
+    use crate::ai::ProviderType;
+
+    fn openai_config(default_model: &str) -> AIConfig {
+        let mut provider = ProviderConfig::openai("sk-test");
+        provider.default_model = default_model.to_string();
+        let mut config = AIConfig::default();
+        config.add_provider(provider).unwrap();
+        config.default_provider = ProviderType::OpenAI;
+        config
+    }
+
+    #[test]
+    fn test_missing_default_provider_is_a_configuration_error() {
+        let mut config = AIConfig::default();
+        config.providers.clear();
+
+        let result = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .build(&config, &ModelConfigManager::new());
+
+        assert!(matches!(result, Err(AIError::ConfigurationError(_))));
+    }
+
+    #[test]
+    fn test_defaults_come_from_model_config() {
+        let models = ModelConfigManager::new();
+        let model_defaults = models.get_model_config("gpt-4-turbo").unwrap().default_params.clone();
+
+        let request = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .build(&openai_config("gpt-4-turbo"), &models)
+            .unwrap();
+
+        assert_eq!(request.model, "gpt-4-turbo");
+        assert_eq!(request.params.top_p, model_defaults.top_p);
+        assert_eq!(request.params.temperature, model_defaults.temperature);
+        assert_eq!(request.params.max_tokens, model_defaults.max_tokens);
+        // The model's top_p differs from the generic defaults, so it really came from the model
+        assert_ne!(request.params.top_p, GenerationParams::default().top_p);
+    }
+
+    #[test]
+    fn test_use_case_and_overrides_take_precedence() {
+        let models = ModelConfigManager::new();
+        let config = openai_config("gpt-4-turbo");
+
+        let summary = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .use_case(ModelUseCase::SummaryGeneration)
+            .build(&config, &models)
+            .unwrap();
+        assert_eq!(summary.params.temperature, Some(0.3));
+        assert_eq!(summary.params.max_tokens, Some(1024));
+
+        let overridden = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .use_case(ModelUseCase::SummaryGeneration)
+            .temperature(0.1)
+            .max_tokens(100_000)
+            .json_output()
+            .build(&config, &models)
+            .unwrap();
+        assert_eq!(overridden.params.temperature, Some(0.1));
+        // Clamped to the model's limit
+        assert_eq!(overridden.params.max_tokens, Some(4096));
+        assert!(overridden.wants_json());
+    }
+
+    #[test]
+    fn test_every_style_fits_each_models_constraints() {
+        let models = ModelConfigManager::new();
+        for model in models.get_all_models() {
+            let constraints = &model.param_constraints;
+            for preset in models.style_presets(&model.id) {
+                let params = &preset.params;
+                if let (Some(temperature), Some(range)) = (params.temperature, &constraints.temperature) {
+                    assert!((range.min..=range.max).contains(&temperature), "{:?} temperature for {}", preset.style, model.id);
+                }
+                if let (Some(top_p), Some(range)) = (params.top_p, &constraints.top_p) {
+                    assert!((range.min..=range.max).contains(&top_p), "{:?} top_p for {}", preset.style, model.id);
+                }
+            }
+        }
+
+        // Styles stay distinct after clamping, and Creative is capped by Claude's temperature limit
+        let claude = models.style_presets("claude-3-sonnet");
+        let temperatures: Vec<f32> = claude.iter().map(|preset| preset.params.temperature.unwrap()).collect();
+        assert!(temperatures[0] < temperatures[1] && temperatures[1] < temperatures[2]);
+        assert_eq!(temperatures[2], 1.0);
+        assert_eq!(models.style_params("gpt-4-turbo", GenerationStyle::Creative).temperature, Some(1.2));
+    }
+
+    #[test]
+    fn test_explicit_params_take_precedence_over_style() {
+        let models = ModelConfigManager::new();
+        let config = openai_config("gpt-4-turbo");
+
+        let precise = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .use_case(ModelUseCase::CaseStudyGeneration)
+            .style(GenerationStyle::Precise)
+            .build(&config, &models)
+            .unwrap();
+        assert_eq!(precise.params.temperature, Some(0.2));
+        assert_eq!(precise.params.top_p, Some(0.8));
+        // Parameters the style leaves unset still come from the use case
+        assert_eq!(precise.params.max_tokens, Some(4096));
+
+        let overridden = GenerationRequestBuilder::new(vec![ChatMessage::user("Hi")])
+            .use_case(ModelUseCase::CaseStudyGeneration)
+            .style(GenerationStyle::Precise)
+            .params(GenerationParams { temperature: Some(0.9), ..unset_params() })
+            .build(&config, &models)
+            .unwrap();
+        assert_eq!(overridden.params.temperature, Some(0.9));
+        assert_eq!(overridden.params.top_p, Some(0.8));
+    }
+}
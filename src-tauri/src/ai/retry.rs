@@ -0,0 +1,100 @@
+// Retry with exponential backoff for transient AI provider failures
+
+use crate::ai::{config::RetryConfig, errors::Result};
+use std::future::Future;
+
+/// Run `operation` until it succeeds, fails with a non-retryable error,
+/// or `max_attempts` is reached. The closure receives the 1-based attempt number.
+pub async fn with_retry<T, F, Fut>(config: &RetryConfig, mut operation: F) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_attempts = config.max_attempts.max(1);
+    let mut attempt = 1;
+
+    loop {
+        match operation(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_retryable() && attempt < max_attempts => {
+                let delay = config.delay_for_attempt(attempt);
+                tracing::warn!(
+                    "AI request failed ({}), retrying in {}ms (attempt {}/{})",
+                    e,
+                    delay.as_millis(),
+                    attempt + 1,
+                    max_attempts
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::config::ProviderType;
+    use crate::ai::errors::AIError;
+    use crate::ai::models::{ChatMessage, GenerationRequest};
+    use crate::ai::providers::{mock::MockProvider, AIProvider};
+    use std::sync::atomic::Ordering;
+
+    fn fast_retry(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            base_delay_ms: 1,
+            max_delay_ms: 5,
+            jitter: false,
+        }
+    }
+
+    fn request() -> GenerationRequest {
+        GenerationRequest::new(vec![ChatMessage::user("Hello")], "mock-model")
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_until_success() {
+        let provider = MockProvider::new(ProviderType::OpenAI)
+            .fail_with(AIError::RateLimitError("429".to_string()))
+            .fail_with(AIError::ServiceUnavailable("503".to_string()))
+            .respond_with("done");
+        let calls = provider.call_counter();
+
+        let response = with_retry(&fast_retry(3), |_| provider.generate(request()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.content, "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_fails_fast() {
+        let provider = MockProvider::new(ProviderType::OpenAI)
+            .fail_with(AIError::AuthenticationError("bad key".to_string()))
+            .respond_with("unreachable");
+        let calls = provider.call_counter();
+
+        let result = with_retry(&fast_retry(3), |_| provider.generate(request())).await;
+
+        assert!(matches!(result, Err(AIError::AuthenticationError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let provider = MockProvider::new(ProviderType::OpenAI)
+            .fail_with(AIError::NetworkError("down".to_string()))
+            .fail_with(AIError::NetworkError("down".to_string()))
+            .respond_with("too late");
+        let calls = provider.call_counter();
+
+        let result = with_retry(&fast_retry(2), |_| provider.generate(request())).await;
+
+        assert!(matches!(result, Err(AIError::NetworkError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,135 @@
+// Sanitization of AI-generated markdown before it is stored or shown
+
+use ammonia::Builder;
+use std::collections::HashSet;
+use crate::ai::config::SanitizationConfig;
+
+/// Tags whose content is dropped along with the tag, and which can never be allowlisted
+const STRIPPED_CONTENT_TAGS: [&str; 2] = ["script", "style"];
+
+/// Strips disallowed HTML from markdown text while leaving the markdown itself intact
+pub struct Sanitizer<'a> {
+    builder: Option<Builder<'a>>,
+}
+
+impl<'a> Sanitizer<'a> {
+    pub fn new(config: &'a SanitizationConfig) -> Self {
+        if !config.enabled {
+            return Self { builder: None };
+        }
+
+        let tags: HashSet<&str> = config
+            .allowed_tags
+            .iter()
+            .map(String::as_str)
+            .filter(|tag| !STRIPPED_CONTENT_TAGS.contains(tag))
+            .collect();
+        // Event handlers are never allowed, and `rel` is set by the sanitizer itself
+        let attributes: HashSet<&str> = config
+            .allowed_attributes
+            .iter()
+            .map(String::as_str)
+            .filter(|attr| !attr.to_ascii_lowercase().starts_with("on") && *attr != "rel")
+            .collect();
+        let url_schemes: HashSet<&str> = config.allowed_url_schemes.iter().map(String::as_str).collect();
+
+        let mut builder = Builder::empty();
+        builder
+            .tags(tags)
+            .generic_attributes(attributes)
+            .url_schemes(url_schemes)
+            .clean_content_tags(STRIPPED_CONTENT_TAGS.into_iter().collect())
+            .link_rel(Some("noopener noreferrer"));
+        Self { builder: Some(builder) }
+    }
+
+    /// Return `text` with disallowed HTML removed. Text without any markup is returned unchanged.
+    pub fn clean(&self, text: &str) -> String {
+        let Some(builder) = &self.builder else {
+            return text.to_string();
+        };
+        if !text.contains('<') {
+            return text.to_string();
+        }
+
+        // The HTML serializer escapes text; undo the escapes that markdown relies on
+        // (blockquotes, ampersands). `&lt;` stays escaped so no new tags can appear.
+        builder
+            .clean(text)
+            .to_string()
+            .replace("&gt;", ">")
+            .replace("&nbsp;", "\u{a0}")
+            .replace("&amp;", "&")
+    }
+
+    pub fn clean_in_place(&self, text: &mut String) {
+        *text = self.clean(text);
+    }
+
+    pub fn clean_option(&self, text: &mut Option<String>) {
+        if let Some(text) = text {
+            self.clean_in_place(text);
+        }
+    }
+
+    pub fn clean_all(&self, texts: &mut [String]) {
+        for text in texts {
+            self.clean_in_place(text);
+        }
+    }
+}
+
+/// Sanitize a single piece of generated markdown
+pub fn sanitize_markdown(text: &str, config: &SanitizationConfig) -> String {
+    Sanitizer::new(config).clean(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_and_event_handlers_are_removed() {
+        let config = SanitizationConfig::default();
+        let text = "## Background\n\n<script>alert('x')</script>The firm grew.\n\n<img src=\"chart.png\" onerror=\"steal()\" alt=\"Chart\">\n<a href=\"javascript:alert(1)\" onclick=\"steal()\">link</a>";
+
+        let cleaned = sanitize_markdown(text, &config);
+
+        assert!(!cleaned.contains("<script"));
+        assert!(!cleaned.contains("alert('x')"));
+        assert!(!cleaned.contains("onerror"));
+        assert!(!cleaned.contains("onclick"));
+        assert!(!cleaned.contains("javascript:"));
+        assert!(cleaned.contains("## Background"));
+        assert!(cleaned.contains("The firm grew."));
+        assert!(cleaned.contains("alt=\"Chart\""));
+    }
+
+    #[test]
+    fn test_markdown_formatting_survives() {
+        let config = SanitizationConfig::default();
+        let text = "# Title\n\n- **Revenue** grew\n- *Costs* fell\n\n1. R&D\n2. Sales\n\n> The board must decide.\n\nSee <em>appendix</em> for <b>details</b>.";
+
+        let cleaned = sanitize_markdown(text, &config);
+
+        assert_eq!(cleaned, text);
+        // Plain markdown without markup is untouched byte for byte
+        let plain = "# Title\n\n> Quote & more\n\n- item";
+        assert_eq!(sanitize_markdown(plain, &config), plain);
+    }
+
+    #[test]
+    fn test_allowlist_is_configurable() {
+        let mut config = SanitizationConfig::default();
+        config.allowed_tags.retain(|tag| tag != "em");
+        config.allowed_tags.push("script".to_string());
+        config.allowed_attributes.push("onclick".to_string());
+
+        let cleaned = sanitize_markdown("<em onclick=\"x()\">Key</em> <script>x()</script>", &config);
+        assert_eq!(cleaned, "Key ");
+
+        config.enabled = false;
+        let raw = "<script>x()</script>";
+        assert_eq!(sanitize_markdown(raw, &config), raw);
+    }
+}
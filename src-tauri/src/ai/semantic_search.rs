@@ -0,0 +1,152 @@
+// Semantic case study search backed by provider embeddings
+
+use crate::ai::errors::{AIError, Result};
+use crate::ai::{with_timeout, AIManager, SharedProvider};
+use crate::case_study::embeddings::embedding_text;
+use crate::case_study::{CaseStudyManager, SemanticSearchHit};
+use serde::{Deserialize, Serialize};
+
+/// Case studies embedded per provider call during a backfill
+const EMBEDDING_BATCH_SIZE: usize = 16;
+
+/// Outcome of embedding existing case studies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingBackfillReport {
+    /// Case studies embedded in this run
+    pub embedded: usize,
+    /// Provider whose embeddings were written
+    pub provider: String,
+}
+
+impl AIManager {
+    /// Embed texts with the active provider, subject to its rate limit and timeout
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let provider = self.current_provider().await?;
+        self.embed_with(&provider, texts).await
+    }
+
+    /// Embed texts with `provider`, so every batch of a search or backfill uses the same one
+    async fn embed_with(&self, provider: &SharedProvider, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let provider_type = provider.get_provider_type();
+        self.acquire_rate_limit(&provider_type).await?;
+        let timeout = self.request_timeout(&provider_type).await;
+        with_timeout(timeout, provider.embed(texts)).await
+    }
+
+    /// Embed `query` and return the `k` stored case studies closest to it.
+    /// Only case studies embedded by the active provider are considered.
+    pub async fn semantic_search_case_studies(
+        &self,
+        case_studies: &CaseStudyManager,
+        query: &str,
+        k: usize,
+    ) -> Result<Vec<SemanticSearchHit>> {
+        let query = query.trim();
+        if query.is_empty() || k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let provider = self.current_provider().await?;
+        let query_embedding = self.embed_with(&provider, vec![query.to_string()]).await?
+            .pop()
+            .ok_or_else(|| AIError::ProviderError("Provider returned no embedding for the query".to_string()))?;
+        Ok(case_studies.nearest_by_embedding(&provider.get_provider_type().to_string(), &query_embedding, k).await?)
+    }
+
+    /// Embed every case study that has no embedding from the active provider yet,
+    /// or whose embedding predates its last edit
+    pub async fn backfill_case_study_embeddings(&self, case_studies: &CaseStudyManager) -> Result<EmbeddingBackfillReport> {
+        let provider = self.current_provider().await?;
+        let provider_name = provider.get_provider_type().to_string();
+        let pending = case_studies.case_studies_needing_embedding(&provider_name).await?;
+
+        let mut report = EmbeddingBackfillReport { embedded: 0, provider: provider_name };
+        for batch in pending.chunks(EMBEDDING_BATCH_SIZE) {
+            let embeddings = self.embed_with(&provider, batch.iter().map(embedding_text).collect()).await?;
+            if embeddings.len() != batch.len() {
+                return Err(AIError::ProviderError(format!(
+                    "Provider returned {} embeddings for {} case studies", embeddings.len(), batch.len()
+                )));
+            }
+            for (case_study, embedding) in batch.iter().zip(&embeddings) {
+                case_studies.store_embedding(case_study, &report.provider, embedding).await?;
+            }
+            report.embedded += batch.len();
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::providers::mock::MockProvider;
+    use crate::ai::ProviderType;
+    use crate::case_study::{CaseStudy, NewCaseStudy, UpdateCaseStudy};
+    use crate::database::DatabaseManager;
+
+    const VOCABULARY: &[&str] = &["pricing", "supply chain", "merger", "startup", "retail"];
+
+    async fn setup(provider: MockProvider) -> (AIManager, CaseStudyManager) {
+        let database = DatabaseManager::in_memory().await.unwrap();
+        let manager = AIManager::detached(database.pool().clone());
+        manager.set_active_provider(Box::new(provider)).await;
+        (manager, CaseStudyManager::new(database))
+    }
+
+    async fn create(case_studies: &CaseStudyManager, title: &str, content: &str) -> CaseStudy {
+        case_studies.create_case_study(NewCaseStudy::for_test(title, content)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_orders_by_similarity() {
+        let (manager, case_studies) = setup(MockProvider::new(ProviderType::Ollama).with_embeddings(VOCABULARY)).await;
+        let pricing = create(&case_studies, "Dynamic Pricing", "A retail chain rethinks pricing. Pricing drives margin.").await;
+        let logistics = create(&case_studies, "Port Delays", "A supply chain disruption hits a retail importer.").await;
+        let deal = create(&case_studies, "Hostile Bid", "A merger between two banks faces regulators.").await;
+
+        let report = manager.backfill_case_study_embeddings(&case_studies).await.unwrap();
+        assert_eq!(report.embedded, 3);
+        assert_eq!(report.provider, "ollama");
+
+        let hits = manager
+            .semantic_search_case_studies(&case_studies, "pricing strategy for retail", 2)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.case_study.id.as_str()).collect();
+        assert_eq!(ids, vec![pricing.id.as_str(), logistics.id.as_str()]);
+        assert!(hits[0].score > hits[1].score);
+
+        let hits = manager.semantic_search_case_studies(&case_studies, "merger talks", 1).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].case_study.id, deal.id);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_only_embeds_missing_or_stale() {
+        let (manager, case_studies) = setup(MockProvider::new(ProviderType::Ollama).with_embeddings(VOCABULARY)).await;
+        let first = create(&case_studies, "Startup Funding", "A startup raises a seed round.").await;
+        create(&case_studies, "Store Closures", "A retail brand closes stores.").await;
+
+        assert_eq!(manager.backfill_case_study_embeddings(&case_studies).await.unwrap().embedded, 2);
+        assert_eq!(manager.backfill_case_study_embeddings(&case_studies).await.unwrap().embedded, 0);
+
+        case_studies.update_case_study(&first.id, UpdateCaseStudy {
+            content: Some("A startup rethinks its supply chain after a merger.".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+        assert_eq!(manager.backfill_case_study_embeddings(&case_studies).await.unwrap().embedded, 1);
+
+        let hits = manager.semantic_search_case_studies(&case_studies, "merger", 1).await.unwrap();
+        assert_eq!(hits[0].case_study.id, first.id);
+    }
+
+    #[tokio::test]
+    async fn test_provider_without_embeddings_is_unsupported() {
+        let (manager, case_studies) = setup(MockProvider::new(ProviderType::Ollama)).await;
+        create(&case_studies, "Startup Funding", "A startup raises a seed round.").await;
+
+        let error = manager.semantic_search_case_studies(&case_studies, "startup", 3).await.unwrap_err();
+        assert!(error.to_string().contains("does not support embeddings"), "{}", error);
+    }
+}
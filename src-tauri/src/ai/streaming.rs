@@ -0,0 +1,252 @@
+// Forwarding of streamed generation chunks to the frontend
+
+use crate::ai::{
+    errors::Result,
+    models::{StreamResponse, TokenUsage},
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tokio_util::sync::CancellationToken;
+
+/// Event name a stream's chunks are emitted on
+pub fn stream_event_name(stream_id: &str) -> String {
+    format!("ai://stream/{}", stream_id)
+}
+
+/// Payload emitted for each step of a streamed generation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StreamEvent {
+    Chunk {
+        delta: String,
+    },
+    Done {
+        chunk_count: u32,
+        usage: Option<TokenUsage>,
+        response_time_ms: u64,
+        cancelled: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Receiver for stream events; returns false once the sink can no longer deliver them
+pub trait StreamEventSink: Send + Sync {
+    fn send(&self, event: &StreamEvent) -> bool;
+}
+
+/// Sink that emits stream events to the frontend on a per-stream event name.
+/// Emitting succeeds whether or not anything listens, so this sink cannot tell that the
+/// frontend has gone away; the frontend cancels with `cancel_content_stream` instead.
+pub struct TauriStreamSink {
+    app_handle: AppHandle,
+    event_name: String,
+}
+
+impl TauriStreamSink {
+    pub fn new(app_handle: AppHandle, stream_id: &str) -> Self {
+        Self {
+            app_handle,
+            event_name: stream_event_name(stream_id),
+        }
+    }
+}
+
+impl StreamEventSink for TauriStreamSink {
+    fn send(&self, event: &StreamEvent) -> bool {
+        match self.app_handle.emit(&self.event_name, event) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Failed to emit stream event: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Drive a provider stream to completion, forwarding each chunk to the sink.
+/// Stops as soon as `cancelled` fires, even while waiting on the provider,
+/// or when the sink fails to deliver an event.
+pub async fn forward_stream<S>(
+    mut stream: S,
+    sink: &dyn StreamEventSink,
+    cancelled: &CancellationToken,
+) -> Result<StreamEvent>
+where
+    S: Stream<Item = Result<StreamResponse>> + Unpin,
+{
+    let start_time = Instant::now();
+    let mut chunk_count = 0;
+    let mut usage = None;
+    let mut was_cancelled = false;
+
+    loop {
+        let item = tokio::select! {
+            biased;
+            _ = cancelled.cancelled() => {
+                was_cancelled = true;
+                break;
+            }
+            item = stream.next() => item,
+        };
+        let Some(item) = item else {
+            break;
+        };
+
+        let response = match item {
+            Ok(response) => response,
+            Err(e) => {
+                sink.send(&StreamEvent::Error { message: e.to_string() });
+                return Err(e);
+            }
+        };
+
+        if let Some(value) = response.metadata.get("usage") {
+            usage = serde_json::from_value(value.clone()).ok();
+        }
+
+        if !response.delta.is_empty() {
+            chunk_count += 1;
+            if !sink.send(&StreamEvent::Chunk { delta: response.delta }) {
+                was_cancelled = true;
+                break;
+            }
+        }
+
+        if response.finished {
+            break;
+        }
+    }
+
+    let done = StreamEvent::Done {
+        chunk_count,
+        usage,
+        response_time_ms: start_time.elapsed().as_millis() as u64,
+        cancelled: was_cancelled,
+    };
+    sink.send(&done);
+    Ok(done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::config::ProviderType;
+    use crate::ai::errors::AIError;
+    use crate::ai::models::{ChatMessage, GenerationRequest};
+    use crate::ai::providers::{mock::MockProvider, AIProvider};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<StreamEvent>>,
+        accept: bool,
+    }
+
+    impl RecordingSink {
+        fn accepting() -> Self {
+            Self { accept: true, ..Default::default() }
+        }
+    }
+
+    impl StreamEventSink for RecordingSink {
+        fn send(&self, event: &StreamEvent) -> bool {
+            self.events.lock().unwrap().push(event.clone());
+            self.accept
+        }
+    }
+
+    #[test]
+    fn test_stream_event_name() {
+        assert_eq!(stream_event_name("abc"), "ai://stream/abc");
+    }
+
+    #[tokio::test]
+    async fn test_forward_stream_emits_chunks_and_done() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_chunks(&["one", "two", "three"]);
+        let request = GenerationRequest::new(vec![ChatMessage::user("Hi")], "mock-model").with_streaming(true);
+        let stream = provider.generate_stream(request).await.unwrap();
+
+        let sink = RecordingSink::accepting();
+        forward_stream(stream, &sink, &CancellationToken::new()).await.unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        let deltas: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::Chunk { delta } => Some(delta.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deltas, vec!["one", "two", "three"]);
+        assert!(matches!(events[3], StreamEvent::Done { chunk_count: 3, cancelled: false, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_forward_stream_reports_provider_error() {
+        let items: Vec<Result<StreamResponse>> = vec![
+            Ok(StreamResponse::chunk("partial")),
+            Err(AIError::StreamingError("connection reset".to_string())),
+        ];
+        let sink = RecordingSink::accepting();
+        let result = forward_stream(futures::stream::iter(items), &sink, &CancellationToken::new()).await;
+
+        assert!(result.is_err());
+        let events = sink.events.lock().unwrap();
+        assert!(matches!(events.last(), Some(StreamEvent::Error { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_forward_stream_stops_when_listener_gone() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_chunks(&["one", "two", "three"]);
+        let request = GenerationRequest::new(vec![ChatMessage::user("Hi")], "mock-model");
+        let stream = provider.generate_stream(request).await.unwrap();
+
+        let sink = RecordingSink::default();
+        let done = forward_stream(stream, &sink, &CancellationToken::new()).await.unwrap();
+
+        assert!(matches!(done, StreamEvent::Done { chunk_count: 1, cancelled: true, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_forward_stream_honours_cancel_flag() {
+        let provider = MockProvider::new(ProviderType::Ollama).with_chunks(&["one", "two"]);
+        let request = GenerationRequest::new(vec![ChatMessage::user("Hi")], "mock-model");
+        let stream = provider.generate_stream(request).await.unwrap();
+
+        let sink = RecordingSink::accepting();
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        let done = forward_stream(stream, &sink, &cancelled).await.unwrap();
+
+        assert!(matches!(done, StreamEvent::Done { chunk_count: 0, cancelled: true, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_forward_stream_cancels_while_provider_stalls() {
+        // One chunk, then a provider that never sends anything again
+        let stream = futures::stream::iter(vec![Ok(StreamResponse::chunk("one"))])
+            .chain(futures::stream::pending());
+        let cancelled = CancellationToken::new();
+        let canceller = cancelled.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+
+        let sink = RecordingSink::accepting();
+        let done = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            forward_stream(Box::pin(stream), &sink, &cancelled),
+        )
+        .await
+        .expect("stream should stop promptly once cancelled")
+        .unwrap();
+
+        assert!(matches!(done, StreamEvent::Done { chunk_count: 1, cancelled: true, .. }));
+    }
+}
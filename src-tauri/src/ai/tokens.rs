@@ -0,0 +1,135 @@
+// Prompt token estimation for pre-flight budget checks
+
+use crate::ai::{config::ProviderType, models::GenerationRequest};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tiktoken_rs::{tokenizer::Tokenizer, CoreBPE};
+
+/// Tokens added per chat message for role and separators
+const TOKENS_PER_MESSAGE: u32 = 4;
+/// Tokens that prime the assistant reply
+const REPLY_PRIMING_TOKENS: u32 = 3;
+
+/// How a token count was produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenCountMethod {
+    Tiktoken,
+    Heuristic,
+}
+
+/// Estimated prompt size of a generation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenCount {
+    pub prompt_tokens: u32,
+    pub model: String,
+    pub method: TokenCountMethod,
+}
+
+static CL100K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+static O200K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+
+/// Exact tokenizer for OpenAI models that tiktoken knows about
+fn openai_encoder(model: &str) -> Option<&'static CoreBPE> {
+    match tiktoken_rs::tokenizer::get_tokenizer(model)? {
+        Tokenizer::O200kBase => O200K.get_or_init(|| tiktoken_rs::o200k_base().ok()).as_ref(),
+        Tokenizer::Cl100kBase => CL100K.get_or_init(|| tiktoken_rs::cl100k_base().ok()).as_ref(),
+        _ => None,
+    }
+}
+
+/// Rough token estimate from word and character counts.
+/// Anthropic models average ~3.5 characters per token; local models are closer to 4.
+fn heuristic_tokens(provider: &ProviderType, text: &str) -> u32 {
+    let chars = text.chars().count() as f64;
+    let words = text.split_whitespace().count() as f64;
+
+    let estimate = match provider {
+        ProviderType::Anthropic => chars / 3.5,
+        _ => (words * 4.0 / 3.0).max(chars / 4.0),
+    };
+    estimate.ceil() as u32
+}
+
+/// Count tokens in a piece of text for the given provider and model
+pub fn count_text_tokens(provider: &ProviderType, model: &str, text: &str) -> (u32, TokenCountMethod) {
+    if *provider == ProviderType::OpenAI {
+        if let Some(encoder) = openai_encoder(model) {
+            let tokens = encoder.encode_with_special_tokens(text).len() as u32;
+            return (tokens, TokenCountMethod::Tiktoken);
+        }
+    }
+    (heuristic_tokens(provider, text), TokenCountMethod::Heuristic)
+}
+
+/// Estimate the prompt tokens a request will consume, including chat formatting overhead
+pub fn count_request_tokens(provider: &ProviderType, request: &GenerationRequest) -> TokenCount {
+    let mut method = TokenCountMethod::Tiktoken;
+    let mut prompt_tokens = REPLY_PRIMING_TOKENS;
+
+    for message in &request.messages {
+        let (tokens, message_method) = count_text_tokens(provider, &request.model, &message.content);
+        if message_method == TokenCountMethod::Heuristic {
+            method = TokenCountMethod::Heuristic;
+        }
+        prompt_tokens += tokens + TOKENS_PER_MESSAGE;
+    }
+
+    if request.messages.is_empty() {
+        method = count_text_tokens(provider, &request.model, "").1;
+    }
+
+    TokenCount {
+        prompt_tokens,
+        model: request.model.clone(),
+        method,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::models::ChatMessage;
+
+    const PARAGRAPH: &str = "The regional retailer faced declining foot traffic after a competitor \
+        opened nearby. Management must decide whether to invest in an online storefront, renovate \
+        existing stores, or negotiate a merger with a logistics partner.";
+
+    #[test]
+    fn test_openai_uses_tiktoken() {
+        let (tokens, method) = count_text_tokens(&ProviderType::OpenAI, "gpt-4", "Hello, world!");
+        assert_eq!(method, TokenCountMethod::Tiktoken);
+        assert_eq!(tokens, 4);
+    }
+
+    #[test]
+    fn test_heuristic_within_tolerance_of_tiktoken() {
+        let (exact, _) = count_text_tokens(&ProviderType::OpenAI, "gpt-4", PARAGRAPH);
+        for provider in [ProviderType::Ollama, ProviderType::Anthropic] {
+            let (estimate, method) = count_text_tokens(&provider, "llama3.2", PARAGRAPH);
+            assert_eq!(method, TokenCountMethod::Heuristic);
+            let ratio = estimate as f64 / exact as f64;
+            assert!((0.75..=1.35).contains(&ratio), "{} estimate {} vs exact {}", provider, estimate, exact);
+        }
+    }
+
+    #[test]
+    fn test_unknown_openai_model_falls_back_to_heuristic() {
+        let (_, method) = count_text_tokens(&ProviderType::OpenAI, "not-a-real-model", PARAGRAPH);
+        assert_eq!(method, TokenCountMethod::Heuristic);
+    }
+
+    #[test]
+    fn test_request_includes_message_overhead() {
+        let request = GenerationRequest::new(
+            vec![ChatMessage::system("You are helpful."), ChatMessage::user("Hello, world!")],
+            "gpt-4",
+        );
+        let count = count_request_tokens(&ProviderType::OpenAI, &request);
+        let (system, _) = count_text_tokens(&ProviderType::OpenAI, "gpt-4", "You are helpful.");
+
+        assert_eq!(count.prompt_tokens, system + 4 + 2 * TOKENS_PER_MESSAGE + REPLY_PRIMING_TOKENS);
+        assert_eq!(count.method, TokenCountMethod::Tiktoken);
+        assert_eq!(count.model, "gpt-4");
+    }
+}
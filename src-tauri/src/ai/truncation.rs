@@ -0,0 +1,264 @@
+// Context-window truncation for oversized generation requests
+
+use crate::ai::{
+    config::ProviderType,
+    errors::{AIError, Result},
+    models::{ChatMessage, GenerationRequest, MessageRole},
+    tokens::{count_request_tokens, count_text_tokens},
+};
+use serde::{Deserialize, Serialize};
+
+/// Marker left where text was removed from a message
+const ELISION_MARKER: &str = "\n[...]\n";
+
+/// How to shrink a request that does not fit the model's context window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    #[default]
+    None,
+    TruncateOldest,
+    TruncateMiddle,
+    SummarizeThenTruncate,
+}
+
+/// What truncation did to a request before it was sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TruncationReport {
+    pub strategy: TruncationStrategy,
+    pub original_tokens: u32,
+    pub final_tokens: u32,
+    pub trimmed_tokens: u32,
+}
+
+/// Shrink `request` to fit within `budget` prompt tokens. System messages are never removed
+/// or shortened, and the final message is only shortened once everything else is gone.
+/// Returns `None` if the request already fits or the strategy is `None`.
+pub fn truncate_request(
+    provider: &ProviderType,
+    request: &mut GenerationRequest,
+    budget: u32,
+    strategy: TruncationStrategy,
+) -> Result<Option<TruncationReport>> {
+    let original_tokens = count_request_tokens(provider, request).prompt_tokens;
+    if strategy == TruncationStrategy::None || original_tokens <= budget {
+        return Ok(None);
+    }
+
+    match strategy {
+        TruncationStrategy::TruncateMiddle => {
+            drop_middle(provider, request, budget);
+        }
+        _ => {
+            drop_oldest(provider, request, budget);
+        }
+    }
+    shorten_messages(provider, request, budget, strategy)?;
+
+    let final_tokens = count_request_tokens(provider, request).prompt_tokens;
+    Ok(Some(TruncationReport {
+        strategy,
+        original_tokens,
+        final_tokens,
+        trimmed_tokens: original_tokens.saturating_sub(final_tokens),
+    }))
+}
+
+/// Remove the oldest non-system messages (keeping the last message) until the request fits.
+/// Returns the removed messages in their original order.
+pub fn drop_oldest(provider: &ProviderType, request: &mut GenerationRequest, budget: u32) -> Vec<ChatMessage> {
+    let mut removed = Vec::new();
+    while count_request_tokens(provider, request).prompt_tokens > budget {
+        let last = request.messages.len().saturating_sub(1);
+        match request.messages.iter().position(|m| m.role != MessageRole::System) {
+            Some(index) if index < last => removed.push(request.messages.remove(index)),
+            _ => break,
+        }
+    }
+    removed
+}
+
+/// Remove messages from the middle of the conversation, keeping the first
+/// non-system message and the last message, until the request fits
+fn drop_middle(provider: &ProviderType, request: &mut GenerationRequest, budget: u32) {
+    while count_request_tokens(provider, request).prompt_tokens > budget {
+        let candidates: Vec<usize> = request
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.role != MessageRole::System)
+            .map(|(i, _)| i)
+            .collect();
+
+        // Need a first and last message to keep plus at least one between them
+        if candidates.len() < 3 {
+            break;
+        }
+        let middle = candidates[candidates.len() / 2];
+        request.messages.remove(middle);
+    }
+}
+
+/// Cut text out of the largest non-system messages until the request fits
+fn shorten_messages(
+    provider: &ProviderType,
+    request: &mut GenerationRequest,
+    budget: u32,
+    strategy: TruncationStrategy,
+) -> Result<()> {
+    loop {
+        let total = count_request_tokens(provider, request).prompt_tokens;
+        if total <= budget {
+            return Ok(());
+        }
+
+        let largest = request
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.role != MessageRole::System && !m.content.is_empty())
+            .max_by_key(|(_, m)| m.content.len())
+            .map(|(i, _)| i);
+
+        let Some(index) = largest else {
+            return Err(AIError::InvalidRequest(
+                "System prompt alone exceeds the model's context window".to_string(),
+            ));
+        };
+
+        let message_tokens = count_text_tokens(provider, &request.model, &request.messages[index].content).0;
+        let allowed = message_tokens.saturating_sub(total - budget);
+        let content = &request.messages[index].content;
+        request.messages[index].content = match strategy {
+            TruncationStrategy::TruncateMiddle => keep_ends(provider, &request.model, content, allowed),
+            _ => keep_tail(provider, &request.model, content, allowed),
+        };
+    }
+}
+
+/// Longest suffix of `text` that fits in `max_tokens`
+fn keep_tail(provider: &ProviderType, model: &str, text: &str, max_tokens: u32) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let fits = |n: usize| {
+        let candidate: String = chars[chars.len() - n..].iter().collect();
+        count_text_tokens(provider, model, &format!("{}{}", ELISION_MARKER, candidate)).0 <= max_tokens
+    };
+    let keep = largest_fitting(chars.len(), fits);
+    if keep == 0 {
+        return String::new();
+    }
+    format!("{}{}", ELISION_MARKER.trim_start(), chars[chars.len() - keep..].iter().collect::<String>())
+}
+
+/// Head and tail of `text` around an elided middle, fitting in `max_tokens`
+fn keep_ends(provider: &ProviderType, model: &str, text: &str, max_tokens: u32) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let join = |n: usize| {
+        let head: String = chars[..n / 2].iter().collect();
+        let tail: String = chars[chars.len() - (n - n / 2)..].iter().collect();
+        format!("{}{}{}", head, ELISION_MARKER, tail)
+    };
+    let keep = largest_fitting(chars.len(), |n| count_text_tokens(provider, model, &join(n)).0 <= max_tokens);
+    if keep == 0 {
+        return String::new();
+    }
+    join(keep)
+}
+
+/// Binary search for the largest `n` in `0..=max` where `fits(n)` holds
+fn largest_fitting(max: usize, fits: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, max);
+    while low < high {
+        let mid = (low + high + 1) / 2;
+        if fits(mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_PROMPT: &str = "You are an expert business school instructor.";
+
+    fn over_budget_request() -> GenerationRequest {
+        let mut messages = vec![ChatMessage::system(SYSTEM_PROMPT)];
+        for i in 0..6 {
+            messages.push(ChatMessage::user(format!("Background part {}: {}", i, "supply chain ".repeat(200))));
+            messages.push(ChatMessage::assistant(format!("Noted part {}.", i)));
+        }
+        messages.push(ChatMessage::user(format!("Write questions about: {}", "pricing ".repeat(400))));
+        GenerationRequest::new(messages, "llama3.2")
+    }
+
+    fn assert_fits_with_system_prompt(request: &GenerationRequest, budget: u32) {
+        assert!(count_request_tokens(&ProviderType::Ollama, request).prompt_tokens <= budget);
+        assert_eq!(request.messages[0].role, MessageRole::System);
+        assert_eq!(request.messages[0].content, SYSTEM_PROMPT);
+    }
+
+    #[test]
+    fn test_none_strategy_leaves_request_unchanged() {
+        let mut request = over_budget_request();
+        let before = request.messages.len();
+        let report = truncate_request(&ProviderType::Ollama, &mut request, 500, TruncationStrategy::None).unwrap();
+        assert!(report.is_none());
+        assert_eq!(request.messages.len(), before);
+    }
+
+    #[test]
+    fn test_truncate_oldest_fits_window() {
+        let mut request = over_budget_request();
+        let budget = 1000;
+        let report = truncate_request(&ProviderType::Ollama, &mut request, budget, TruncationStrategy::TruncateOldest)
+            .unwrap()
+            .unwrap();
+
+        assert_fits_with_system_prompt(&request, budget);
+        assert!(request.messages.last().unwrap().content.contains("pricing"));
+        assert_eq!(report.strategy, TruncationStrategy::TruncateOldest);
+        assert_eq!(report.trimmed_tokens, report.original_tokens - report.final_tokens);
+        assert!(report.trimmed_tokens > 0);
+    }
+
+    #[test]
+    fn test_truncate_middle_fits_window() {
+        let mut request = over_budget_request();
+        let budget = 1000;
+        truncate_request(&ProviderType::Ollama, &mut request, budget, TruncationStrategy::TruncateMiddle)
+            .unwrap()
+            .unwrap();
+
+        assert_fits_with_system_prompt(&request, budget);
+        assert!(request.messages[1].content.starts_with("Background part 0"));
+        assert!(request.messages.last().unwrap().content.trim_end().ends_with("pricing"));
+    }
+
+    #[test]
+    fn test_single_oversized_message_is_shortened() {
+        let mut request = GenerationRequest::new(
+            vec![ChatMessage::system(SYSTEM_PROMPT), ChatMessage::user("revenue ".repeat(2000))],
+            "llama3.2",
+        );
+        truncate_request(&ProviderType::Ollama, &mut request, 300, TruncationStrategy::TruncateOldest)
+            .unwrap()
+            .unwrap();
+
+        assert_fits_with_system_prompt(&request, 300);
+        assert_eq!(request.messages.len(), 2);
+    }
+
+    #[test]
+    fn test_system_prompt_over_budget_is_rejected() {
+        let mut request = GenerationRequest::new(
+            vec![ChatMessage::system("policy ".repeat(500)), ChatMessage::user("Hi")],
+            "llama3.2",
+        );
+        let result = truncate_request(&ProviderType::Ollama, &mut request, 50, TruncationStrategy::TruncateOldest);
+        assert!(matches!(result, Err(AIError::InvalidRequest(_))));
+    }
+}
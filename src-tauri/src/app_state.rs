@@ -0,0 +1,104 @@
+// Startup initialization status, so the frontend can report failures and retry
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Where database-backed startup is up to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AppInitStatus {
+    Initializing,
+    Ready,
+    Failed { error: String },
+}
+
+/// Tracks the outcome of startup initialization and serializes retries
+pub struct AppInitState {
+    status: RwLock<AppInitStatus>,
+    /// Held while an attempt runs so a retry can't overlap startup
+    attempt: Mutex<()>,
+}
+
+pub type AppInitStateHandle = Arc<AppInitState>;
+
+impl AppInitState {
+    pub fn new() -> Self {
+        Self {
+            status: RwLock::new(AppInitStatus::Initializing),
+            attempt: Mutex::new(()),
+        }
+    }
+
+    pub async fn status(&self) -> AppInitStatus {
+        self.status.read().await.clone()
+    }
+
+    /// Run `init` and record its outcome. Does nothing once initialization has succeeded;
+    /// a call made while another attempt is running waits for it first.
+    pub async fn initialize<F, Fut, E>(&self, init: F) -> AppInitStatus
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Display,
+    {
+        let _attempt = self.attempt.lock().await;
+        if *self.status.read().await == AppInitStatus::Ready {
+            return AppInitStatus::Ready;
+        }
+
+        *self.status.write().await = AppInitStatus::Initializing;
+        let status = match init().await {
+            Ok(()) => AppInitStatus::Ready,
+            Err(e) => AppInitStatus::Failed { error: e.to_string() },
+        };
+        *self.status.write().await = status.clone();
+        status
+    }
+}
+
+impl Default for AppInitState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{DatabaseConfig, DatabaseManager};
+    use std::path::Path;
+
+    async fn open_database(path: &Path) -> Result<(), sqlx::Error> {
+        DatabaseManager::connect(path, &DatabaseConfig::default()).await.map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn test_failed_startup_is_reported_and_retry_recovers() {
+        let dir = std::env::temp_dir().join(format!("case-crafter-init-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // A file where the database directory should be makes the path unusable
+        let blocker = dir.join("database");
+        std::fs::write(&blocker, b"not a directory").unwrap();
+        let db_path = blocker.join("case_crafter.db");
+
+        let state = AppInitState::new();
+        assert_eq!(state.status().await, AppInitStatus::Initializing);
+
+        let status = state.initialize(|| open_database(&db_path)).await;
+        assert!(matches!(status, AppInitStatus::Failed { .. }), "{:?}", status);
+        assert_eq!(state.status().await, status);
+
+        std::fs::remove_file(&blocker).unwrap();
+        assert_eq!(state.initialize(|| open_database(&db_path)).await, AppInitStatus::Ready);
+        assert_eq!(state.status().await, AppInitStatus::Ready);
+
+        // Once ready, further attempts are no-ops
+        let status = state.initialize(|| async { Err::<(), _>("should not run") }).await;
+        assert_eq!(status, AppInitStatus::Ready);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
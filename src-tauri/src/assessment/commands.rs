@@ -3,13 +3,18 @@
 use super::models::*;
 use super::workflow::AssessmentWorkflow;
 use super::session::AssessmentSession;
+use super::grading::{AnswerGrade, GradingQuestion};
+use super::export::ResultsExportOptions;
+use super::rubric::{RubricAward, RubricScore};
 use super::AssessmentError;
+use crate::ai::AIManager;
 use crate::database::DatabaseManager;
 use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
 
-type AssessmentWorkflowState = Arc<RwLock<Option<AssessmentWorkflow>>>;
+pub type AssessmentWorkflowState = Arc<RwLock<Option<AssessmentWorkflow>>>;
+type AIManagerState = Arc<RwLock<Option<AIManager>>>;
 
 /// Initialize assessment workflow state
 pub fn setup_assessment_workflow_state(db: DatabaseManager) -> AssessmentWorkflowState {
@@ -33,6 +38,27 @@ pub async fn create_assessment_workflow(
     }
 }
 
+/// Assemble one assessment from a collection's case studies, in collection order.
+/// Missing questions are generated when an AI provider is configured.
+#[tauri::command]
+pub async fn create_assessment_from_collection(
+    collection_id: i64,
+    questions_per_case: u32,
+    params: CollectionAssessmentParams,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> Result<CollectionAssessment, String> {
+    let ai_lock = ai_manager_state.read().await;
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.create_assessment_from_collection(collection_id, questions_per_case, params, ai_lock.as_ref())
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
 /// Get assessment workflow by ID
 #[tauri::command]
 pub async fn get_assessment_workflow(
@@ -65,12 +91,56 @@ pub async fn update_assessment_workflow(
 /// Delete assessment workflow
 #[tauri::command]
 pub async fn delete_assessment_workflow(
+    id: String,
+    deleted_by: Option<String>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<bool, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository.delete_workflow(&id, deleted_by.as_deref()).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Restore a deleted assessment workflow
+#[tauri::command]
+pub async fn restore_deleted_assessment_workflow(
+    id: String,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<bool, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository.restore_deleted_workflow(&id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Permanently remove a deleted assessment workflow
+#[tauri::command]
+pub async fn purge_assessment_workflow(
     id: String,
     workflow_state: State<'_, AssessmentWorkflowState>,
 ) -> Result<bool, String> {
     let workflow_lock = workflow_state.read().await;
     if let Some(workflow) = workflow_lock.as_ref() {
-        workflow.repository.delete_workflow(&id).await.map_err(|e| e.to_string())
+        workflow.repository.purge_workflow(&id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// List deleted assessment workflows
+#[tauri::command]
+pub async fn list_deleted_assessment_workflows(
+    limit: i32,
+    offset: i32,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<Vec<AssessmentWorkflowModel>, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository.list_deleted_workflows(limit, offset).await.map_err(|e| e.to_string())
     } else {
         Err("Assessment workflow not initialized".to_string())
     }
@@ -174,11 +244,12 @@ pub async fn submit_assessment_answer(
 pub async fn navigate_to_assessment_question(
     session_id: String,
     question_id: String,
+    option_count: Option<usize>,
     workflow_state: State<'_, AssessmentWorkflowState>,
 ) -> Result<AssessmentSessionModel, String> {
     let workflow_lock = workflow_state.read().await;
     if let Some(workflow) = workflow_lock.as_ref() {
-        workflow.navigate_to_question(&session_id, &question_id).await.map_err(|e| e.to_string())
+        workflow.navigate_to_question(&session_id, &question_id, option_count).await.map_err(|e| e.to_string())
     } else {
         Err("Assessment workflow not initialized".to_string())
     }
@@ -226,6 +297,76 @@ pub async fn submit_assessment_for_grading(
     }
 }
 
+/// Suggest grades for free-text answers using the AI grader
+#[tauri::command]
+pub async fn auto_grade_assessment_session(
+    session_id: String,
+    questions: Vec<GradingQuestion>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+    ai_manager_state: State<'_, AIManagerState>,
+) -> std::result::Result<Vec<AnswerGrade>, String> {
+    let ai_lock = ai_manager_state.read().await;
+    let ai_manager = ai_lock.as_ref().ok_or_else(|| "AI manager not initialized".to_string())?;
+
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.auto_grade_session(&session_id, ai_manager, &questions).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Replace a response's grade with the instructor's
+#[tauri::command]
+pub async fn override_assessment_answer_grade(
+    session_id: String,
+    question_id: String,
+    score: f64,
+    max_score: f64,
+    feedback: Option<String>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> std::result::Result<AssessmentSessionModel, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.override_answer_grade(&session_id, &question_id, score, max_score, feedback)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Export all session results for a workflow as CSV text
+#[tauri::command]
+pub async fn export_assessment_results(
+    workflow_id: String,
+    anonymize: Option<bool>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<String, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        let options = ResultsExportOptions { anonymize: anonymize.unwrap_or(false) };
+        workflow.export_results_csv(&workflow_id, &options).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Score a question's rubric from per-criterion awarded points
+#[tauri::command]
+pub async fn score_with_rubric(
+    question_id: i64,
+    awards: Vec<RubricAward>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<RubricScore, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.score_with_rubric(question_id, &awards).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
 /// Get assessment result for completed session
 #[tauri::command]
 pub async fn get_assessment_result(
@@ -255,6 +396,20 @@ pub async fn get_user_assessment_sessions(
     }
 }
 
+/// Reopen the user's most recent unfinished session, e.g. after the app closed mid-assessment
+#[tauri::command]
+pub async fn recover_assessment_session(
+    user_id: String,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<Option<AssessmentSessionModel>, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.recover_session(&user_id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
 /// Get assessment statistics
 #[tauri::command]
 pub async fn get_assessment_statistics(
@@ -268,6 +423,57 @@ pub async fn get_assessment_statistics(
     }
 }
 
+// Question Bank Commands
+
+/// Add a reusable question to the question bank
+#[tauri::command]
+pub async fn add_question_bank_entry(
+    entry: NewQuestionBankEntry,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<QuestionBankEntry, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository.add_bank_question(entry).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Search the question bank by tags, difficulty, type and text
+#[tauri::command]
+pub async fn search_question_bank(
+    filter: QuestionBankFilter,
+    limit: i32,
+    offset: i32,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<Vec<QuestionBankEntry>, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository.search_bank_questions(&filter, limit, offset).await.map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
+/// Import a case study's assessment questions into the question bank
+#[tauri::command]
+pub async fn import_questions_to_bank(
+    case_study_id: i64,
+    difficulty_level: String,
+    tags: Vec<String>,
+    workflow_state: State<'_, AssessmentWorkflowState>,
+) -> Result<Vec<QuestionBankEntry>, String> {
+    let workflow_lock = workflow_state.read().await;
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.repository
+            .import_case_study_questions(case_study_id, &difficulty_level, &tags)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Assessment workflow not initialized".to_string())
+    }
+}
+
 // Utility Commands
 
 /// Create default assessment configuration
@@ -366,9 +572,8 @@ pub async fn get_session_statistics(
 ) -> Result<super::session::SessionStatistics, String> {
     let workflow_lock = workflow_state.read().await;
     if let Some(workflow_manager) = workflow_lock.as_ref() {
-        let session = workflow_manager.repository.find_session_by_id(&session_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Session not found".to_string())?;
+        let session = workflow_manager.enforce_time_limit(&session_id).await
+            .map_err(|e| e.to_string())?;
 
         let workflow_data = workflow_manager.repository.find_workflow_by_id(&session.workflow_id).await
             .map_err(|e| e.to_string())?
@@ -389,9 +594,8 @@ pub async fn get_session_warnings(
 ) -> Result<Vec<super::session::SessionWarning>, String> {
     let workflow_lock = workflow_state.read().await;
     if let Some(workflow_manager) = workflow_lock.as_ref() {
-        let session = workflow_manager.repository.find_session_by_id(&session_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Session not found".to_string())?;
+        let session = workflow_manager.enforce_time_limit(&session_id).await
+            .map_err(|e| e.to_string())?;
 
         let workflow_data = workflow_manager.repository.find_workflow_by_id(&session.workflow_id).await
             .map_err(|e| e.to_string())?
@@ -412,9 +616,8 @@ pub async fn can_submit_assessment_session(
 ) -> Result<bool, String> {
     let workflow_lock = workflow_state.read().await;
     if let Some(workflow_manager) = workflow_lock.as_ref() {
-        let session = workflow_manager.repository.find_session_by_id(&session_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Session not found".to_string())?;
+        let session = workflow_manager.enforce_time_limit(&session_id).await
+            .map_err(|e| e.to_string())?;
 
         let workflow_data = workflow_manager.repository.find_workflow_by_id(&session.workflow_id).await
             .map_err(|e| e.to_string())?
@@ -438,22 +641,8 @@ pub async fn bookmark_assessment_question(
     workflow_state: State<'_, AssessmentWorkflowState>,
 ) -> Result<AssessmentSessionModel, String> {
     let workflow_lock = workflow_state.read().await;
-    if let Some(workflow_manager) = workflow_lock.as_ref() {
-        let mut session = workflow_manager.repository.find_session_by_id(&session_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Session not found".to_string())?;
-
-        let workflow_data = workflow_manager.repository.find_workflow_by_id(&session.workflow_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Workflow not found".to_string())?;
-
-        let mut session_manager = AssessmentSession::new(session.clone(), workflow_data);
-        session_manager.bookmark_question(question_id);
-        
-        session = session_manager.get_session().clone();
-        workflow_manager.repository.update_session(&session).await.map_err(|e| e.to_string())?;
-        
-        Ok(session)
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.bookmark_question(&session_id, &question_id).await.map_err(|e| e.to_string())
     } else {
         Err("Assessment workflow not initialized".to_string())
     }
@@ -468,22 +657,8 @@ pub async fn update_assessment_question_notes(
     workflow_state: State<'_, AssessmentWorkflowState>,
 ) -> Result<AssessmentSessionModel, String> {
     let workflow_lock = workflow_state.read().await;
-    if let Some(workflow_manager) = workflow_lock.as_ref() {
-        let mut session = workflow_manager.repository.find_session_by_id(&session_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Session not found".to_string())?;
-
-        let workflow_data = workflow_manager.repository.find_workflow_by_id(&session.workflow_id).await
-            .map_err(|e| e.to_string())?
-            .ok_or_else(|| "Workflow not found".to_string())?;
-
-        let mut session_manager = AssessmentSession::new(session.clone(), workflow_data);
-        session_manager.update_question_notes(question_id, notes);
-        
-        session = session_manager.get_session().clone();
-        workflow_manager.repository.update_session(&session).await.map_err(|e| e.to_string())?;
-        
-        Ok(session)
+    if let Some(workflow) = workflow_lock.as_ref() {
+        workflow.update_question_notes(&session_id, &question_id, notes).await.map_err(|e| e.to_string())
     } else {
         Err("Assessment workflow not initialized".to_string())
     }
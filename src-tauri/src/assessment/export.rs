@@ -0,0 +1,235 @@
+// CSV export of assessment session results
+
+use super::models::*;
+use super::{AssessmentError, Result};
+use std::collections::{BTreeSet, HashMap};
+
+/// Options for exporting assessment results
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ResultsExportOptions {
+    /// Replace user ids with participant numbers
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
+const FIXED_COLUMNS: [&str; 9] = [
+    "session_id",
+    "user",
+    "attempt",
+    "state",
+    "started_at",
+    "completed_at",
+    "time_spent_seconds",
+    "total_score",
+    "passed",
+];
+
+/// Write one CSV row per session, with a score and answer column for every question
+/// answered in any of the sessions
+pub fn write_results_csv<W: std::io::Write>(
+    sessions: &[AssessmentSessionModel],
+    options: &ResultsExportOptions,
+    writer: W,
+) -> Result<()> {
+    let question_ids: BTreeSet<&String> = sessions.iter()
+        .flat_map(|session| session.responses.keys())
+        .collect();
+
+    let mut sessions: Vec<&AssessmentSessionModel> = sessions.iter().collect();
+    sessions.sort_by_key(|session| session.start_time);
+
+    let mut participants: HashMap<&str, usize> = HashMap::new();
+    let mut csv = csv::Writer::from_writer(writer);
+
+    let mut header: Vec<String> = FIXED_COLUMNS.iter().map(|c| c.to_string()).collect();
+    for question_id in &question_ids {
+        header.push(format!("{} score", question_id));
+        header.push(format!("{} answer", question_id));
+    }
+    csv.write_record(&header).map_err(export_error)?;
+
+    for session in sessions {
+        let user = if options.anonymize {
+            let next = participants.len() + 1;
+            format!("participant-{}", participants.entry(session.user_id.as_str()).or_insert(next))
+        } else {
+            session.user_id.clone()
+        };
+
+        let mut record = vec![
+            session.id.clone(),
+            user,
+            session.attempt_number.to_string(),
+            session_state_label(&session.session_state).to_string(),
+            session.start_time.to_rfc3339(),
+            session.end_time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            session.time_spent.to_string(),
+            session.final_score.map(|s| s.to_string()).unwrap_or_default(),
+            session.passed.map(|p| p.to_string()).unwrap_or_default(),
+        ];
+
+        for question_id in &question_ids {
+            match session.responses.get(*question_id) {
+                Some(response) => {
+                    record.push(response.score().0.to_string());
+                    record.push(answer_text(&response.answer));
+                }
+                None => record.extend([String::new(), String::new()]),
+            }
+        }
+
+        csv.write_record(&record).map_err(export_error)?;
+    }
+
+    csv.flush().map_err(|e| AssessmentError::ExportError(e.to_string()))?;
+    Ok(())
+}
+
+fn export_error(error: csv::Error) -> AssessmentError {
+    AssessmentError::ExportError(error.to_string())
+}
+
+fn session_state_label(state: &SessionState) -> &'static str {
+    match state {
+        SessionState::NotStarted => "not_started",
+        SessionState::InProgress => "in_progress",
+        SessionState::Paused => "paused",
+        SessionState::Completed => "completed",
+        SessionState::Submitted => "submitted",
+        SessionState::TimedOut => "timed_out",
+        SessionState::Abandoned => "abandoned",
+    }
+}
+
+/// Plain-text form of an answer for a spreadsheet cell
+fn answer_text(answer: &ResponseAnswer) -> String {
+    match answer {
+        ResponseAnswer::SingleChoice(option) => option.clone(),
+        ResponseAnswer::MultipleChoice(options) | ResponseAnswer::Ranking(options) => options.join("; "),
+        ResponseAnswer::TextInput(text) | ResponseAnswer::Essay(text) => text.clone(),
+        ResponseAnswer::NumericInput(value) => value.to_string(),
+        ResponseAnswer::Matching(pairs) => {
+            let mut pairs: Vec<String> = pairs.iter().map(|(item, matched)| format!("{} -> {}", item, matched)).collect();
+            pairs.sort();
+            pairs.join("; ")
+        }
+        ResponseAnswer::TrueFalse(value) => value.to_string(),
+        ResponseAnswer::FileUpload(reference) => reference.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn session(id: &str, user_id: &str, minutes_ago: i64, responses: Vec<(&str, ResponseAnswer, bool)>) -> AssessmentSessionModel {
+        let start_time = Utc::now() - Duration::minutes(minutes_ago);
+        let responses = responses.into_iter()
+            .map(|(question_id, answer, correct)| (question_id.to_string(), QuestionResponse {
+                question_id: question_id.to_string(),
+                answer,
+                response_time: 30,
+                attempts: 1,
+                confidence_level: None,
+                flagged_for_review: false,
+                submitted_at: start_time,
+                is_correct: Some(correct),
+                partial_credit: None,
+                feedback_shown: false,
+                grade: None,
+            }))
+            .collect();
+
+        AssessmentSessionModel {
+            id: id.to_string(),
+            workflow_id: "workflow-1".to_string(),
+            user_id: user_id.to_string(),
+            session_state: SessionState::Submitted,
+            current_question_id: None,
+            responses,
+            start_time,
+            end_time: Some(start_time + Duration::minutes(20)),
+            last_activity: start_time,
+            time_spent: 1200,
+            attempt_number: 1,
+            completion_percentage: 100.0,
+            current_score: Some(50.0),
+            final_score: Some(50.0),
+            passed: Some(false),
+            session_data: SessionData {
+                question_sequence: Vec::new(),
+                active_since: None,
+                question_order: Vec::new(),
+                option_orders: HashMap::new(),
+                time_per_question: HashMap::new(),
+                navigation_history: Vec::new(),
+                bookmarked_questions: Vec::new(),
+                notes: HashMap::new(),
+                case_study_interactions: Vec::new(),
+                adaptive_data: None,
+                browser_data: BrowserSessionData {
+                    user_agent: None,
+                    screen_resolution: None,
+                    timezone: None,
+                    connection_events: Vec::new(),
+                    performance_metrics: PerformanceMetrics {
+                        average_response_time: 0.0,
+                        page_load_times: Vec::new(),
+                        network_latency: None,
+                        client_errors: Vec::new(),
+                    },
+                },
+            },
+            created_at: start_time,
+            updated_at: start_time,
+        }
+    }
+
+    fn sessions() -> Vec<AssessmentSessionModel> {
+        vec![
+            session("s2", "bob@example.edu", 30, vec![
+                ("q1", ResponseAnswer::SingleChoice("2".to_string()), false),
+            ]),
+            session("s1", "alice@example.edu", 60, vec![
+                ("q1", ResponseAnswer::SingleChoice("1".to_string()), true),
+                ("q2", ResponseAnswer::Essay("Enter Brazil first, then Chile.\nRisks: currency, \"regulation\"".to_string()), false),
+            ]),
+        ]
+    }
+
+    fn read_back(options: &ResultsExportOptions) -> (csv::StringRecord, Vec<csv::StringRecord>) {
+        let mut buffer = Vec::new();
+        write_results_csv(&sessions(), options, &mut buffer).unwrap();
+
+        let mut reader = csv::Reader::from_reader(buffer.as_slice());
+        let header = reader.headers().unwrap().clone();
+        let rows = reader.records().collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        (header, rows)
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let (header, rows) = read_back(&ResultsExportOptions::default());
+
+        let mut expected: Vec<&str> = FIXED_COLUMNS.to_vec();
+        expected.extend(["q1 score", "q1 answer", "q2 score", "q2 answer"]);
+        assert_eq!(header.iter().collect::<Vec<_>>(), expected);
+
+        // Oldest session first
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][0], "s1");
+        assert_eq!(&rows[0][1], "alice@example.edu");
+        assert_eq!(&rows[0][9], "1");
+        assert_eq!(&rows[0][12], "Enter Brazil first, then Chile.\nRisks: currency, \"regulation\"");
+        assert_eq!(&rows[1][12], "");
+    }
+
+    #[test]
+    fn test_csv_anonymizes_users() {
+        let (_, rows) = read_back(&ResultsExportOptions { anonymize: true });
+
+        assert_eq!(&rows[0][1], "participant-1");
+        assert_eq!(&rows[1][1], "participant-2");
+    }
+}
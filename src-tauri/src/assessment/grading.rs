@@ -0,0 +1,292 @@
+// AI-assisted grading of free-text answers against question rubrics
+
+use super::rubric::{parse_rubric, RubricCriterion};
+use super::{AssessmentError, Result};
+use crate::ai::{AIManager, GenerationRequest, models::ChatMessage};
+use crate::database::models::AssessmentQuestion;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Prompt template used to grade answers
+pub const GRADING_TEMPLATE_ID: &str = "answer_grading";
+
+/// A free-text question with everything needed to grade answers to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradingQuestion {
+    pub question_id: String,
+    pub question_text: String,
+    pub rubric: Option<String>, // JSON rubric
+    pub sample_answer: Option<String>,
+    pub points: f64,
+}
+
+impl From<&AssessmentQuestion> for GradingQuestion {
+    fn from(question: &AssessmentQuestion) -> Self {
+        Self {
+            question_id: question.id.to_string(),
+            question_text: question.question_text.clone(),
+            rubric: question.rubric.clone(),
+            sample_answer: question.sample_answer.clone(),
+            points: question.points as f64,
+        }
+    }
+}
+
+/// How a response's grade was decided
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeStatus {
+    AutoGraded,
+    NeedsManualGrading,
+    InstructorOverride,
+}
+
+/// Points awarded for one rubric criterion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriterionGrade {
+    pub name: String,
+    pub points_awarded: f64,
+    pub points_possible: f64,
+    pub feedback: Option<String>,
+}
+
+/// Grade for a single answer, suggested by the AI or set by an instructor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerGrade {
+    pub question_id: String,
+    pub status: GradeStatus,
+    pub score: Option<f64>,
+    pub max_score: f64,
+    pub criteria: Vec<CriterionGrade>,
+    pub feedback: Option<String>,
+    pub graded_at: DateTime<Utc>,
+}
+
+impl AnswerGrade {
+    fn needs_manual_grading(question_id: &str, max_score: f64, reason: impl Into<String>) -> Self {
+        Self {
+            question_id: question_id.to_string(),
+            status: GradeStatus::NeedsManualGrading,
+            score: None,
+            max_score,
+            criteria: Vec::new(),
+            feedback: Some(reason.into()),
+            graded_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelGrade {
+    score: Option<f64>,
+    #[serde(default)]
+    criteria: Vec<ModelCriterion>,
+    overall_feedback: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelCriterion {
+    name: String,
+    points_awarded: f64,
+    points_possible: Option<f64>,
+    feedback: Option<String>,
+}
+
+/// Ask the AI to grade a free-text answer. Provider failures are returned as errors;
+/// output that cannot be mapped onto the rubric is marked as needing manual grading.
+pub async fn auto_grade_answer(
+    ai_manager: &AIManager,
+    question: &GradingQuestion,
+    student_answer: &str,
+) -> Result<AnswerGrade> {
+    let rubric = parse_rubric(question.rubric.as_deref(), question.points);
+    let max_score: f64 = rubric.iter().map(|c| c.points).sum();
+
+    let rubric_text = rubric
+        .iter()
+        .map(|c| format!("- {} ({} points)", c.name, c.points))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut variables = HashMap::new();
+    variables.insert("question_text".to_string(), serde_json::json!(question.question_text));
+    variables.insert("rubric".to_string(), serde_json::json!(rubric_text));
+    variables.insert("student_answer".to_string(), serde_json::json!(student_answer));
+    variables.insert("sample_answer".to_string(), serde_json::json!(
+        question.sample_answer.clone().unwrap_or_else(|| "Not provided".to_string())
+    ));
+    variables.insert("max_points".to_string(), serde_json::json!(max_score));
+
+    let rendered = ai_manager.get_prompt_manager()
+        .render_template(GRADING_TEMPLATE_ID, &variables)
+        .map_err(|e| AssessmentError::AIError(e.to_string()))?;
+
+    let mut messages = Vec::new();
+    if let Some(system) = rendered.system_prompt {
+        messages.push(ChatMessage::system(system));
+    }
+    messages.push(ChatMessage::user(rendered.user_prompt));
+
+    let config = ai_manager.get_config().await;
+    let model = config.providers.get(&config.default_provider)
+        .map(|provider| provider.default_model.clone())
+        .ok_or_else(|| AssessmentError::AIError("Default provider not configured".to_string()))?;
+
+    let request = GenerationRequest::new(messages, model)
+        .with_metadata("generation_type", serde_json::json!("grading"));
+    let response = ai_manager.generate(request).await
+        .map_err(|e| AssessmentError::AIError(e.to_string()))?;
+
+    Ok(grade_from_model_output(question, &rubric, &response.content))
+}
+
+/// Map the model's JSON onto the rubric, falling back to manual grading when it doesn't fit
+fn grade_from_model_output(question: &GradingQuestion, rubric: &[RubricCriterion], output: &str) -> AnswerGrade {
+    let max_score: f64 = rubric.iter().map(|c| c.points).sum();
+
+    let model_grade = match extract_json(output).and_then(|json| serde_json::from_str::<ModelGrade>(json).ok()) {
+        Some(grade) => grade,
+        None => return AnswerGrade::needs_manual_grading(
+            &question.question_id,
+            max_score,
+            "AI grader returned output that could not be parsed",
+        ),
+    };
+
+    let mut criteria = Vec::with_capacity(rubric.len());
+    for criterion in rubric {
+        let matched = model_grade.criteria.iter()
+            .find(|c| c.name.trim().eq_ignore_ascii_case(criterion.name.trim()));
+
+        let (awarded, feedback) = match matched {
+            Some(c) => {
+                // Rescale when the model graded the criterion out of a different total
+                let awarded = match c.points_possible {
+                    Some(possible) if possible > 0.0 && possible != criterion.points => {
+                        c.points_awarded / possible * criterion.points
+                    }
+                    _ => c.points_awarded,
+                };
+                (awarded, c.feedback.clone())
+            }
+            // A single-criterion rubric can be graded from the overall score alone
+            None if rubric.len() == 1 && model_grade.criteria.is_empty() => match model_grade.score {
+                Some(score) => (score, None),
+                None => return AnswerGrade::needs_manual_grading(
+                    &question.question_id,
+                    max_score,
+                    "AI grader did not return a score",
+                ),
+            },
+            None => return AnswerGrade::needs_manual_grading(
+                &question.question_id,
+                max_score,
+                format!("AI grader did not score criterion '{}'", criterion.name),
+            ),
+        };
+
+        if !awarded.is_finite() {
+            return AnswerGrade::needs_manual_grading(
+                &question.question_id,
+                max_score,
+                format!("AI grader returned an invalid score for '{}'", criterion.name),
+            );
+        }
+
+        criteria.push(CriterionGrade {
+            name: criterion.name.clone(),
+            points_awarded: awarded.clamp(0.0, criterion.points),
+            points_possible: criterion.points,
+            feedback,
+        });
+    }
+
+    AnswerGrade {
+        question_id: question.question_id.clone(),
+        status: GradeStatus::AutoGraded,
+        score: Some(criteria.iter().map(|c| c.points_awarded).sum()),
+        max_score,
+        criteria,
+        feedback: model_grade.overall_feedback,
+        graded_at: Utc::now(),
+    }
+}
+
+/// The outermost JSON object in the output, ignoring any surrounding prose or code fences
+fn extract_json(output: &str) -> Option<&str> {
+    let start = output.find('{')?;
+    let end = output.rfind('}')?;
+    (start < end).then(|| &output[start..=end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ProviderType;
+    use crate::ai::providers::mock::MockProvider;
+    use crate::database::DatabaseManager;
+
+    const RUBRIC: &str = r#"{"criteria": [{"name": "Analysis Depth", "points": 4}, {"name": "Use of Frameworks", "points": 3}, {"name": "Practical Recommendations", "points": 3}]}"#;
+
+    fn question() -> GradingQuestion {
+        GradingQuestion {
+            question_id: "q1".to_string(),
+            question_text: "What factors should the company consider before entering a new market?".to_string(),
+            rubric: Some(RUBRIC.to_string()),
+            sample_answer: Some("Market size, competition, regulation and cultural fit.".to_string()),
+            points: 10.0,
+        }
+    }
+
+    async fn grader(response: &str) -> AIManager {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let manager = AIManager::detached(db.pool().clone());
+        manager
+            .set_active_provider(Box::new(MockProvider::new(ProviderType::Ollama).respond_with(response)))
+            .await;
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_structured_output_maps_onto_rubric() {
+        let ai_manager = grader(r#"```json
+{"score": 7, "criteria": [
+  {"name": "Analysis Depth", "points_awarded": 3, "points_possible": 4, "feedback": "Covers market size and competition."},
+  {"name": "use of frameworks", "points_awarded": 5, "points_possible": 10, "feedback": "Mentions PESTEL without applying it."},
+  {"name": "Practical Recommendations", "points_awarded": 9, "points_possible": 3, "feedback": "Clear next steps."}
+], "overall_feedback": "Solid answer."}
+```"#).await;
+
+        let grade = auto_grade_answer(&ai_manager, &question(), "Look at market size, rivals and PESTEL.").await.unwrap();
+
+        assert_eq!(grade.status, GradeStatus::AutoGraded);
+        assert_eq!(grade.max_score, 10.0);
+        let awarded: Vec<f64> = grade.criteria.iter().map(|c| c.points_awarded).collect();
+        // Frameworks rescaled from 5/10 to the rubric's 3 points; recommendations capped at 3
+        assert_eq!(awarded, vec![3.0, 1.5, 3.0]);
+        assert_eq!(grade.score, Some(7.5));
+        assert_eq!(grade.feedback.as_deref(), Some("Solid answer."));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_output_needs_manual_grading() {
+        let ai_manager = grader("I think this answer deserves about a B+.").await;
+
+        let grade = auto_grade_answer(&ai_manager, &question(), "Market size matters.").await.unwrap();
+
+        assert_eq!(grade.status, GradeStatus::NeedsManualGrading);
+        assert_eq!(grade.score, None);
+        assert_eq!(grade.max_score, 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_missing_criterion_needs_manual_grading() {
+        let ai_manager = grader(r#"{"score": 4, "criteria": [{"name": "Analysis Depth", "points_awarded": 4}]}"#).await;
+
+        let grade = auto_grade_answer(&ai_manager, &question(), "Market size matters.").await.unwrap();
+
+        assert_eq!(grade.status, GradeStatus::NeedsManualGrading);
+        assert!(grade.feedback.unwrap().contains("Use of Frameworks"));
+    }
+}
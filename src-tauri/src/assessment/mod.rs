@@ -4,12 +4,19 @@ pub mod models;
 pub mod repository;
 pub mod workflow;
 pub mod session;
+pub mod grading;
+pub mod rubric;
+pub mod shuffle;
+pub mod export;
 pub mod commands;
 
 pub use models::*;
 pub use repository::AssessmentRepository;
 pub use workflow::AssessmentWorkflow;
 pub use session::AssessmentSession;
+pub use grading::{AnswerGrade, GradeStatus, GradingQuestion};
+pub use export::ResultsExportOptions;
+pub use rubric::{RubricAward, RubricCriterion, RubricScore};
 
 use thiserror::Error;
 
@@ -33,6 +40,8 @@ pub enum AssessmentError {
     CaseStudyError(String),
     #[error("AI generation error: {0}")]
     AIError(String),
+    #[error("Export error: {0}")]
+    ExportError(String),
 }
 
 pub type Result<T> = std::result::Result<T, AssessmentError>;
\ No newline at end of file
@@ -1,5 +1,7 @@
 // Assessment workflow models
 
+use super::grading::AnswerGrade;
+use crate::ai::question_generator::QuestionGenerationParams;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -48,11 +50,14 @@ pub enum AssessmentWorkflowStatus {
 /// Assessment configuration settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssessmentConfiguration {
-    pub time_limit: Option<i32>, // minutes
+    #[serde(alias = "time_limit")]
+    pub time_limit_minutes: Option<i32>,
     pub allow_retakes: bool,
     pub max_attempts: Option<i32>,
-    pub randomize_questions: bool,
-    pub randomize_options: bool,
+    #[serde(alias = "randomize_questions")]
+    pub shuffle_questions: bool,
+    #[serde(alias = "randomize_options")]
+    pub shuffle_options: bool,
     pub show_feedback: bool,
     pub show_correct_answers: bool,
     pub require_all_questions: bool,
@@ -60,6 +65,27 @@ pub struct AssessmentConfiguration {
     pub question_weighting: QuestionWeighting,
     pub navigation_settings: NavigationSettings,
     pub scoring_settings: ScoringSettings,
+    #[serde(default)]
+    pub question_source: QuestionSource,
+}
+
+/// Where a workflow's questions come from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum QuestionSource {
+    /// Questions generated for the workflow's case study
+    #[default]
+    CaseStudy,
+    /// Questions drawn from the question bank
+    QuestionBank {
+        filter: QuestionBankFilter,
+        question_count: Option<usize>,
+    },
+    /// Questions assembled from a collection's case studies, in collection order
+    Collection {
+        collection_id: i64,
+        question_ids: Vec<i64>,
+    },
 }
 
 /// Question weighting configuration
@@ -156,6 +182,8 @@ pub struct QuestionResponse {
     pub is_correct: Option<bool>,
     pub partial_credit: Option<f64>,
     pub feedback_shown: bool,
+    #[serde(default)]
+    pub grade: Option<AnswerGrade>, // AI-suggested or instructor grade for free-text answers
 }
 
 /// Different types of answers
@@ -176,6 +204,12 @@ pub enum ResponseAnswer {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
     pub question_sequence: Vec<String>,     // Actual question order for this session
+    #[serde(default)]
+    pub active_since: Option<DateTime<Utc>>, // Set while the session clock is running
+    #[serde(default)]
+    pub question_order: Vec<usize>,         // Canonical index of each question in question_sequence
+    #[serde(default)]
+    pub option_orders: HashMap<String, Vec<usize>>, // question_id -> canonical index of each displayed option
     pub time_per_question: HashMap<String, i32>, // question_id -> seconds spent
     pub navigation_history: Vec<NavigationEvent>,
     pub bookmarked_questions: Vec<String>,
@@ -407,6 +441,49 @@ pub struct NewAssessmentWorkflow {
     pub created_by: Option<String>,
 }
 
+/// Settings for assembling one assessment from every case study in a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionAssessmentParams {
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub configuration: AssessmentConfiguration,
+    pub estimated_duration: i32,
+    pub difficulty_level: String,
+    pub learning_objectives: Vec<String>,
+    pub instructions: Option<String>,
+    pub created_by: Option<String>,
+    /// Used for cases without enough saved questions. Title, content and count are
+    /// filled in per case study.
+    #[serde(default)]
+    pub generation: QuestionGenerationParams,
+}
+
+/// Questions a case study contributed to a collection assessment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseContribution {
+    pub case_study_id: String,
+    pub title: String,
+    pub question_ids: Vec<i64>,
+    pub generated_count: usize,
+}
+
+/// A collection member that contributed no questions, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedCaseContribution {
+    pub case_study_id: String,
+    pub title: String,
+    pub reason: String,
+}
+
+/// An assessment assembled from a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionAssessment {
+    pub workflow: AssessmentWorkflowModel,
+    pub contributions: Vec<CaseContribution>,
+    pub failed_cases: Vec<FailedCaseContribution>,
+}
+
 /// Assessment workflow update
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateAssessmentWorkflow {
@@ -419,6 +496,9 @@ pub struct UpdateAssessmentWorkflow {
     pub learning_objectives: Option<Vec<String>>,
     pub instructions: Option<String>,
     pub metadata: Option<AssessmentMetadata>,
+    /// User making the change, recorded in the audit log
+    #[serde(default)]
+    pub updated_by: Option<String>,
 }
 
 /// Assessment workflow filter for queries
@@ -438,6 +518,52 @@ pub struct AssessmentWorkflowFilter {
     pub target_audience: Option<Vec<String>>,
 }
 
+/// A reusable question stored in the question bank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionBankEntry {
+    pub id: String,
+    pub question_text: String,
+    pub question_type: String,
+    pub options: Vec<String>,
+    pub correct_answer: Option<String>,
+    pub sample_answer: Option<String>,
+    pub rubric: Option<String>, // JSON rubric
+    pub points: i64,
+    pub difficulty_level: String,
+    pub tags: Vec<String>,
+    pub source_question_id: Option<i64>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// New question bank entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewQuestionBankEntry {
+    pub question_text: String,
+    pub question_type: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    pub correct_answer: Option<String>,
+    pub sample_answer: Option<String>,
+    pub rubric: Option<String>,
+    pub points: Option<i64>,
+    pub difficulty_level: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_by: Option<String>,
+}
+
+/// Question bank filter; entries must carry every listed tag
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct QuestionBankFilter {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub difficulty_level: Option<String>,
+    pub question_type: Option<String>,
+    pub search: Option<String>,
+}
+
 /// Assessment session filter
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AssessmentSessionFilter {
@@ -481,11 +607,11 @@ pub struct MonthlyActivity {
 impl Default for AssessmentConfiguration {
     fn default() -> Self {
         Self {
-            time_limit: None,
+            time_limit_minutes: None,
             allow_retakes: true,
             max_attempts: Some(3),
-            randomize_questions: false,
-            randomize_options: false,
+            shuffle_questions: false,
+            shuffle_options: false,
             show_feedback: true,
             show_correct_answers: true,
             require_all_questions: true,
@@ -493,6 +619,7 @@ impl Default for AssessmentConfiguration {
             question_weighting: QuestionWeighting::default(),
             navigation_settings: NavigationSettings::default(),
             scoring_settings: ScoringSettings::default(),
+            question_source: QuestionSource::default(),
         }
     }
 }
@@ -549,4 +676,15 @@ impl Default for AssessmentMetadata {
             author_notes: None,
         }
     }
-}
\ No newline at end of file
+}
+
+impl QuestionResponse {
+    /// Points earned and available: graded free-text answers score on their rubric,
+    /// everything else is right or wrong
+    pub fn score(&self) -> (f64, f64) {
+        match &self.grade {
+            Some(AnswerGrade { score: Some(score), max_score, .. }) => (*score, *max_score),
+            _ => (if self.is_correct.unwrap_or(false) { 1.0 } else { 0.0 }, 1.0),
+        }
+    }
+}
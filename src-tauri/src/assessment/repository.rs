@@ -2,20 +2,26 @@
 
 use super::models::*;
 use super::{AssessmentError, Result};
-use crate::database::DatabaseManager;
+use crate::database::{DatabaseManager, StatsCache, Validator};
+use crate::database::audit::{AuditAction, AuditLogger};
+use crate::database::models::AssessmentQuestion;
 use chrono::Utc;
-use sqlx::Row;
+use sqlx::{Row, SqliteConnection};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Entity type recorded in the audit log
+const AUDIT_ENTITY: &str = "assessment_workflow";
+
 /// Repository for assessment workflow database operations
 pub struct AssessmentRepository {
     db: DatabaseManager,
+    statistics: StatsCache<AssessmentStatistics>,
 }
 
 impl AssessmentRepository {
     pub fn new(db: DatabaseManager) -> Self {
-        Self { db }
+        Self { db, statistics: StatsCache::default() }
     }
 
     /// Create a new assessment workflow
@@ -28,6 +34,7 @@ impl AssessmentRepository {
         let metadata_json = serde_json::to_string(&new_workflow.metadata)?;
         let workflow_type_str = serde_json::to_string(&new_workflow.workflow_type)?;
 
+        let mut tx = self.db.pool().begin().await?;
         sqlx::query(
             r#"
             INSERT INTO assessment_workflows (
@@ -52,26 +59,39 @@ impl AssessmentRepository {
         .bind(&new_workflow.created_by)
         .bind(&now)
         .bind(&now)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        self.find_workflow_by_id(&id).await?
-            .ok_or_else(|| AssessmentError::NotFound("Failed to create workflow".to_string()))
+        let workflow = self.find_workflow_by_id_on(&mut tx, &id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Failed to create workflow".to_string()))?;
+
+        let diff = AuditLogger::diff(None, Some(&workflow));
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, &id, AuditAction::Create, workflow.created_by.as_deref(), &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(workflow)
     }
 
     /// Find assessment workflow by ID
     pub async fn find_workflow_by_id(&self, id: &str) -> Result<Option<AssessmentWorkflowModel>> {
+        let mut conn = self.db.pool().acquire().await?;
+        self.find_workflow_by_id_on(&mut conn, id).await
+    }
+
+    /// Find assessment workflow by ID on a specific connection, e.g. inside a transaction
+    pub async fn find_workflow_by_id_on(&self, conn: &mut SqliteConnection, id: &str) -> Result<Option<AssessmentWorkflowModel>> {
         let row = sqlx::query(
             r#"
             SELECT id, title, description, case_study_id, workflow_type, configuration,
                    status, estimated_duration, difficulty_level, learning_objectives,
                    instructions, metadata, created_by, created_at, updated_at, published_at
             FROM assessment_workflows 
-            WHERE id = ? AND status != 'deleted'
+            WHERE id = ? AND deleted_at IS NULL
             "#
         )
         .bind(id)
-        .fetch_optional(self.db.pool())
+        .fetch_optional(&mut *conn)
         .await?;
 
         if let Some(row) = row {
@@ -81,9 +101,22 @@ impl AssessmentRepository {
         }
     }
 
+    /// Find a case study assessment question by ID
+    pub async fn find_question_by_id(&self, id: i64) -> Result<Option<AssessmentQuestion>> {
+        let question = sqlx::query_as::<_, AssessmentQuestion>(
+            "SELECT * FROM assessment_questions WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        Ok(question)
+    }
+
     /// Update assessment workflow
     pub async fn update_workflow(&self, id: &str, update: UpdateAssessmentWorkflow) -> Result<Option<AssessmentWorkflowModel>> {
-        let current = match self.find_workflow_by_id(id).await? {
+        let mut tx = self.db.pool().begin().await?;
+        let current = match self.find_workflow_by_id_on(&mut tx, id).await? {
             Some(workflow) => workflow,
             None => return Ok(None),
         };
@@ -101,7 +134,7 @@ impl AssessmentRepository {
                 .bind(title)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(description) = &update.description {
@@ -109,7 +142,7 @@ impl AssessmentRepository {
                 .bind(description)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(workflow_type) = &update.workflow_type {
@@ -118,7 +151,7 @@ impl AssessmentRepository {
                 .bind(&workflow_type_str)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(configuration) = &update.configuration {
@@ -127,7 +160,7 @@ impl AssessmentRepository {
                 .bind(&configuration_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(estimated_duration) = update.estimated_duration {
@@ -135,7 +168,7 @@ impl AssessmentRepository {
                 .bind(estimated_duration)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(difficulty_level) = &update.difficulty_level {
@@ -143,7 +176,7 @@ impl AssessmentRepository {
                 .bind(difficulty_level)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(learning_objectives) = &update.learning_objectives {
@@ -152,7 +185,7 @@ impl AssessmentRepository {
                 .bind(&learning_objectives_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(instructions) = &update.instructions {
@@ -160,7 +193,7 @@ impl AssessmentRepository {
                 .bind(instructions)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(metadata) = &update.metadata {
@@ -169,14 +202,33 @@ impl AssessmentRepository {
                 .bind(&metadata_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
+        }
+
+        let updated = self.find_workflow_by_id_on(&mut tx, id).await?;
+        if let Some(ref workflow) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(workflow));
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, update.updated_by.as_deref(), &diff).await?;
         }
+        tx.commit().await?;
+        self.statistics.invalidate();
 
-        self.find_workflow_by_id(id).await
+        Ok(updated)
     }
 
     /// Change workflow status
     pub async fn update_workflow_status(&self, id: &str, status: AssessmentWorkflowStatus) -> Result<Option<AssessmentWorkflowModel>> {
+        if status == AssessmentWorkflowStatus::Deleted {
+            self.delete_workflow(id, None).await?;
+            return self.find_workflow_by_id(id).await;
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        let current = match self.find_workflow_by_id_on(&mut tx, id).await? {
+            Some(workflow) => workflow,
+            None => return Ok(None),
+        };
+
         let now = Utc::now();
         let published_at = if status == AssessmentWorkflowStatus::Published {
             Some(now)
@@ -197,23 +249,128 @@ impl AssessmentRepository {
         .bind(published_at)
         .bind(now)
         .bind(id)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        self.find_workflow_by_id(id).await
+        let updated = self.find_workflow_by_id_on(&mut tx, id).await?;
+        if let Some(ref workflow) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(workflow));
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        }
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(updated)
     }
 
     /// Delete workflow (soft delete)
-    pub async fn delete_workflow(&self, id: &str) -> Result<bool> {
+    pub async fn delete_workflow(&self, id: &str, deleted_by: Option<&str>) -> Result<bool> {
+        let now = Utc::now();
+        let mut tx = self.db.pool().begin().await?;
         let result = sqlx::query(
-            "UPDATE assessment_workflows SET status = 'deleted', updated_at = ? WHERE id = ? AND status != 'deleted'"
+            "UPDATE assessment_workflows SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL"
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            let diff = serde_json::json!({ "deleted_at": { "old": null, "new": now } });
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, deleted_by, &diff).await?;
+        }
+        tx.commit().await?;
+        if deleted {
+            self.statistics.invalidate();
+        }
+
+        Ok(deleted)
+    }
+
+    /// Restore a soft-deleted workflow; legacy rows deleted by status return as drafts
+    pub async fn restore_deleted_workflow(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let previous: Option<(chrono::DateTime<Utc>, String)> = sqlx::query_as(
+            "SELECT deleted_at, status FROM assessment_workflows WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((deleted_at, status)) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            "UPDATE assessment_workflows SET deleted_at = NULL, status = CASE WHEN status = 'deleted' THEN 'draft' ELSE status END, updated_at = ? WHERE id = ?"
         )
         .bind(Utc::now())
         .bind(id)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
+        .await?;
+
+        let mut diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null } });
+        if status == "deleted" {
+            diff["status"] = serde_json::json!({ "old": "deleted", "new": "draft" });
+        }
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(true)
+    }
+
+    /// Permanently remove a soft-deleted workflow along with its sessions
+    pub async fn purge_workflow(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let deleted_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT deleted_at FROM assessment_workflows WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(deleted_at) = deleted_at else {
+            return Ok(false);
+        };
+
+        sqlx::query("DELETE FROM assessment_workflows WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null }, "purged": true });
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, None, &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(true)
+    }
+
+    /// List soft-deleted workflows, most recently deleted first
+    pub async fn list_deleted_workflows(&self, limit: i32, offset: i32) -> Result<Vec<AssessmentWorkflowModel>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, title, description, case_study_id, workflow_type, configuration,
+                   status, estimated_duration, difficulty_level, learning_objectives,
+                   instructions, metadata, created_by, created_at, updated_at, published_at
+            FROM assessment_workflows
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            LIMIT ? OFFSET ?
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db.pool())
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        let mut workflows = Vec::new();
+        for row in rows {
+            workflows.push(self.parse_workflow_row(row).await?);
+        }
+
+        Ok(workflows)
     }
 
     /// List workflows with filtering and pagination
@@ -224,7 +381,7 @@ impl AssessmentRepository {
                    status, estimated_duration, difficulty_level, learning_objectives,
                    instructions, metadata, created_by, created_at, updated_at, published_at
             FROM assessment_workflows 
-            WHERE status != 'deleted'
+            WHERE deleted_at IS NULL
             "#
         );
 
@@ -311,6 +468,9 @@ impl AssessmentRepository {
 
         let session_data = SessionData {
             question_sequence: Vec::new(),
+            active_since: None,
+            question_order: Vec::new(),
+            option_orders: HashMap::new(),
             time_per_question: HashMap::new(),
             navigation_history: Vec::new(),
             bookmarked_questions: Vec::new(),
@@ -359,6 +519,7 @@ impl AssessmentRepository {
         .bind(&now)
         .execute(self.db.pool())
         .await?;
+        self.statistics.invalidate();
 
         self.find_session_by_id(&id).await?
             .ok_or_else(|| AssessmentError::NotFound("Failed to create session".to_string()))
@@ -425,6 +586,7 @@ impl AssessmentRepository {
         .bind(&session.id)
         .execute(self.db.pool())
         .await?;
+        self.statistics.invalidate();
 
         Ok(())
     }
@@ -455,8 +617,240 @@ impl AssessmentRepository {
         Ok(sessions)
     }
 
-    /// Get assessment statistics
+    /// The user's most recently active session that is still in progress or paused
+    pub async fn find_latest_unfinished_session(&self, user_id: &str) -> Result<Option<AssessmentSessionModel>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, workflow_id, user_id, session_state, current_question_id, responses,
+                   start_time, end_time, last_activity, time_spent, attempt_number,
+                   completion_percentage, current_score, final_score, passed,
+                   session_data, created_at, updated_at
+            FROM assessment_sessions
+            WHERE user_id = ? AND session_state IN ('in_progress', 'paused')
+            ORDER BY last_activity DESC
+            LIMIT 1
+            "#
+        )
+        .bind(user_id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        if let Some(row) = row {
+            Ok(Some(self.parse_session_row(row).await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get all sessions for a workflow, oldest first
+    pub async fn get_workflow_sessions(&self, workflow_id: &str) -> Result<Vec<AssessmentSessionModel>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, workflow_id, user_id, session_state, current_question_id, responses,
+                   start_time, end_time, last_activity, time_spent, attempt_number,
+                   completion_percentage, current_score, final_score, passed,
+                   session_data, created_at, updated_at
+            FROM assessment_sessions 
+            WHERE workflow_id = ?
+            ORDER BY start_time ASC
+            "#
+        )
+        .bind(workflow_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(self.parse_session_row(row).await?);
+        }
+
+        Ok(sessions)
+    }
+
+    /// Add a question to the question bank
+    pub async fn add_bank_question(&self, entry: NewQuestionBankEntry) -> Result<QuestionBankEntry> {
+        if entry.question_type == "multiple_choice" {
+            Validator::validate_choice_answer(&entry.options, entry.correct_answer.as_deref())
+                .map_err(|e| AssessmentError::ValidationError(e.to_string()))?;
+        }
+
+        let mut conn = self.db.pool().acquire().await?;
+        let id = Self::insert_bank_question_on(&mut conn, &entry, None).await?
+            .ok_or_else(|| AssessmentError::ValidationError("Question was not added to the bank".to_string()))?;
+        drop(conn);
+
+        self.find_bank_question_by_id(&id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Failed to create bank question".to_string()))
+    }
+
+    /// Find a question bank entry by ID
+    pub async fn find_bank_question_by_id(&self, id: &str) -> Result<Option<QuestionBankEntry>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, question_text, question_type, options, correct_answer, sample_answer,
+                   rubric, points, difficulty_level, tags, source_question_id, created_by,
+                   created_at, updated_at
+            FROM question_bank
+            WHERE id = ?
+            "#
+        )
+        .bind(id)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.parse_bank_row(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Search the question bank, oldest entries first so selections are stable
+    pub async fn search_bank_questions(&self, filter: &QuestionBankFilter, limit: i32, offset: i32) -> Result<Vec<QuestionBankEntry>> {
+        let mut query = String::from(
+            r#"
+            SELECT id, question_text, question_type, options, correct_answer, sample_answer,
+                   rubric, points, difficulty_level, tags, source_question_id, created_by,
+                   created_at, updated_at
+            FROM question_bank
+            WHERE 1 = 1
+            "#
+        );
+
+        let mut params: Vec<String> = Vec::new();
+
+        for tag in normalize_tags(&filter.tags) {
+            query.push_str(" AND EXISTS (SELECT 1 FROM json_each(question_bank.tags) WHERE json_each.value = ?)");
+            params.push(tag);
+        }
+
+        if let Some(ref difficulty_level) = filter.difficulty_level {
+            query.push_str(" AND difficulty_level = ?");
+            params.push(difficulty_level.clone());
+        }
+
+        if let Some(ref question_type) = filter.question_type {
+            query.push_str(" AND question_type = ?");
+            params.push(question_type.clone());
+        }
+
+        if let Some(ref search) = filter.search {
+            query.push_str(" AND question_text LIKE ?");
+            params.push(format!("%{}%", search));
+        }
+
+        query.push_str(" ORDER BY created_at ASC, rowid ASC LIMIT ? OFFSET ?");
+        params.push(limit.to_string());
+        params.push(offset.to_string());
+
+        let mut query_builder = sqlx::query(&query);
+        for param in params {
+            query_builder = query_builder.bind(param);
+        }
+
+        let rows = query_builder.fetch_all(self.db.pool()).await?;
+        rows.into_iter().map(|row| self.parse_bank_row(row)).collect()
+    }
+
+    /// Copy a case study's questions into the bank. Questions imported before are skipped,
+    /// so only the newly added entries are returned.
+    pub async fn import_case_study_questions(
+        &self,
+        case_study_id: i64,
+        difficulty_level: &str,
+        tags: &[String],
+    ) -> Result<Vec<QuestionBankEntry>> {
+        let questions = sqlx::query_as::<_, AssessmentQuestion>(
+            "SELECT * FROM assessment_questions WHERE case_study_id = ? ORDER BY order_index"
+        )
+        .bind(case_study_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut tx = self.db.pool().begin().await?;
+        let mut imported_ids = Vec::new();
+        for question in questions {
+            let entry = NewQuestionBankEntry {
+                question_text: question.question_text,
+                question_type: question.question_type,
+                options: question.options
+                    .and_then(|options| serde_json::from_str(&options).ok())
+                    .unwrap_or_default(),
+                correct_answer: question.correct_answer,
+                sample_answer: question.sample_answer,
+                rubric: question.rubric,
+                points: Some(question.points),
+                difficulty_level: difficulty_level.to_string(),
+                tags: tags.to_vec(),
+                created_by: None,
+            };
+            if let Some(id) = Self::insert_bank_question_on(&mut tx, &entry, Some(question.id)).await? {
+                imported_ids.push(id);
+            }
+        }
+        tx.commit().await?;
+
+        let mut imported = Vec::with_capacity(imported_ids.len());
+        for id in imported_ids {
+            if let Some(entry) = self.find_bank_question_by_id(&id).await? {
+                imported.push(entry);
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Insert a bank question, returning its ID, or `None` if its source was already imported
+    async fn insert_bank_question_on(
+        conn: &mut SqliteConnection,
+        entry: &NewQuestionBankEntry,
+        source_question_id: Option<i64>,
+    ) -> Result<Option<String>> {
+        if entry.question_text.trim().is_empty() {
+            return Err(AssessmentError::ValidationError("Question text is required".to_string()));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO question_bank (
+                id, question_text, question_type, options, correct_answer, sample_answer,
+                rubric, points, difficulty_level, tags, source_question_id, created_by,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&id)
+        .bind(&entry.question_text)
+        .bind(&entry.question_type)
+        .bind(serde_json::to_string(&entry.options)?)
+        .bind(&entry.correct_answer)
+        .bind(&entry.sample_answer)
+        .bind(&entry.rubric)
+        .bind(entry.points.unwrap_or(1))
+        .bind(&entry.difficulty_level)
+        .bind(serde_json::to_string(&normalize_tags(&entry.tags))?)
+        .bind(source_question_id)
+        .bind(&entry.created_by)
+        .bind(&now)
+        .bind(&now)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(id))
+    }
+
+    /// Get assessment statistics, served from a short-lived cache that workflow and session writes clear
     pub async fn get_assessment_statistics(&self) -> Result<AssessmentStatistics> {
+        self.statistics.get_or_compute(|| self.compute_assessment_statistics()).await
+    }
+
+    /// Drop cached statistics so the next read recomputes them
+    pub fn invalidate_statistics(&self) {
+        self.statistics.invalidate();
+    }
+
+    async fn compute_assessment_statistics(&self) -> Result<AssessmentStatistics> {
         // Basic counts
         let counts = sqlx::query(
             r#"
@@ -464,7 +858,7 @@ impl AssessmentRepository {
                 COUNT(*) as total_workflows,
                 COUNT(CASE WHEN status = 'published' THEN 1 END) as published_workflows
             FROM assessment_workflows 
-            WHERE status != 'deleted'
+            WHERE deleted_at IS NULL
             "#
         )
         .fetch_one(self.db.pool())
@@ -571,6 +965,28 @@ impl AssessmentRepository {
         })
     }
 
+    fn parse_bank_row(&self, row: sqlx::sqlite::SqliteRow) -> Result<QuestionBankEntry> {
+        let options_json: String = row.try_get("options")?;
+        let tags_json: String = row.try_get("tags")?;
+
+        Ok(QuestionBankEntry {
+            id: row.try_get("id")?,
+            question_text: row.try_get("question_text")?,
+            question_type: row.try_get("question_type")?,
+            options: serde_json::from_str(&options_json)?,
+            correct_answer: row.try_get("correct_answer")?,
+            sample_answer: row.try_get("sample_answer")?,
+            rubric: row.try_get("rubric")?,
+            points: row.try_get("points")?,
+            difficulty_level: row.try_get("difficulty_level")?,
+            tags: serde_json::from_str(&tags_json)?,
+            source_question_id: row.try_get("source_question_id")?,
+            created_by: row.try_get("created_by")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
     async fn parse_session_row(&self, row: sqlx::sqlite::SqliteRow) -> Result<AssessmentSessionModel> {
         let responses_json: String = row.try_get("responses")?;
         let session_data_json: String = row.try_get("session_data")?;
@@ -611,4 +1027,15 @@ impl AssessmentRepository {
             updated_at: row.try_get("updated_at")?,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Lowercase, trimmed and de-duplicated tags, so tag filters match case-insensitively
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags.iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
@@ -0,0 +1,202 @@
+// Rubric parsing and partial-credit scoring
+
+use super::grading::CriterionGrade;
+use super::{AssessmentError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Awards may exceed a criterion's maximum by this much to absorb float rounding
+const POINTS_TOLERANCE: f64 = 1e-9;
+
+/// A rubric criterion and the points available for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubricCriterion {
+    pub name: String,
+    pub points: f64,
+}
+
+/// Points a grader awards for one criterion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricAward {
+    pub criterion: String,
+    pub points: f64,
+    pub feedback: Option<String>,
+}
+
+/// Total for a question with the per-criterion breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricScore {
+    pub question_id: String,
+    pub total: f64,
+    pub max_score: f64,
+    pub breakdown: Vec<CriterionGrade>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RubricEntry {
+    Named(String),
+    Detailed {
+        name: String,
+        points: Option<f64>,
+        weight: Option<f64>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct RubricDocument {
+    criteria: Vec<RubricEntry>,
+}
+
+/// Parse a stored rubric. Criteria with `points` keep them; the rest of the question's
+/// points are shared between the other criteria in proportion to their `weight`
+/// (1 when omitted). A missing or unreadable rubric becomes a single "Overall" criterion.
+pub fn parse_rubric(rubric: Option<&str>, question_points: f64) -> Vec<RubricCriterion> {
+    let entries = rubric
+        .and_then(|r| serde_json::from_str::<RubricDocument>(r).ok())
+        .map(|doc| doc.criteria)
+        .unwrap_or_default();
+
+    if entries.is_empty() {
+        return vec![RubricCriterion { name: "Overall".to_string(), points: question_points }];
+    }
+
+    // (name, fixed points, weight)
+    let entries: Vec<(String, Option<f64>, f64)> = entries
+        .into_iter()
+        .map(|entry| match entry {
+            RubricEntry::Named(name) => (name, None, 1.0),
+            RubricEntry::Detailed { name, points, weight } => (name, points, weight.unwrap_or(1.0).max(0.0)),
+        })
+        .collect();
+
+    let fixed: f64 = entries.iter().filter_map(|(_, points, _)| *points).sum();
+    let total_weight: f64 = entries.iter().filter(|(_, points, _)| points.is_none()).map(|(_, _, w)| w).sum();
+    let remaining = (question_points - fixed).max(0.0);
+
+    entries
+        .into_iter()
+        .map(|(name, points, weight)| {
+            let points = points.unwrap_or_else(|| {
+                if total_weight > 0.0 { remaining * weight / total_weight } else { 0.0 }
+            });
+            RubricCriterion { name, points }
+        })
+        .collect()
+}
+
+/// Total the awarded points against the rubric. Criteria without an award score zero;
+/// unknown, duplicate, negative or over-maximum awards are rejected.
+pub fn score_rubric(question_id: &str, rubric: &[RubricCriterion], awards: &[RubricAward]) -> Result<RubricScore> {
+    let mut by_criterion: HashMap<String, &RubricAward> = HashMap::new();
+    for award in awards {
+        let key = award.criterion.trim().to_lowercase();
+        if !rubric.iter().any(|c| c.name.trim().to_lowercase() == key) {
+            return Err(AssessmentError::ValidationError(format!("Unknown rubric criterion '{}'", award.criterion)));
+        }
+        if by_criterion.insert(key, award).is_some() {
+            return Err(AssessmentError::ValidationError(format!("Criterion '{}' awarded more than once", award.criterion)));
+        }
+    }
+
+    let mut breakdown = Vec::with_capacity(rubric.len());
+    for criterion in rubric {
+        let award = by_criterion.get(&criterion.name.trim().to_lowercase());
+        let points = award.map(|a| a.points).unwrap_or(0.0);
+
+        if !points.is_finite() || points < 0.0 {
+            return Err(AssessmentError::ValidationError(format!(
+                "Invalid points {} for criterion '{}'", points, criterion.name
+            )));
+        }
+        if points > criterion.points + POINTS_TOLERANCE {
+            return Err(AssessmentError::ValidationError(format!(
+                "Awarded {} points for '{}' but the maximum is {}", points, criterion.name, criterion.points
+            )));
+        }
+
+        breakdown.push(CriterionGrade {
+            name: criterion.name.clone(),
+            points_awarded: points.min(criterion.points),
+            points_possible: criterion.points,
+            feedback: award.and_then(|a| a.feedback.clone()),
+        });
+    }
+
+    Ok(RubricScore {
+        question_id: question_id.to_string(),
+        total: breakdown.iter().map(|c| c.points_awarded).sum(),
+        max_score: rubric.iter().map(|c| c.points).sum(),
+        breakdown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUBRIC: &str = r#"{"criteria": [{"name": "Analysis Depth", "points": 4}, {"name": "Use of Frameworks", "points": 3}, {"name": "Practical Recommendations", "points": 3}]}"#;
+
+    fn award(criterion: &str, points: f64) -> RubricAward {
+        RubricAward { criterion: criterion.to_string(), points, feedback: None }
+    }
+
+    #[test]
+    fn test_parse_rubric_shares_points_between_named_criteria() {
+        let rubric = parse_rubric(Some(r#"{"criteria": ["clarity", "depth"]}"#), 10.0);
+        assert_eq!(rubric, vec![
+            RubricCriterion { name: "clarity".to_string(), points: 5.0 },
+            RubricCriterion { name: "depth".to_string(), points: 5.0 },
+        ]);
+
+        let fallback = parse_rubric(None, 6.0);
+        assert_eq!(fallback, vec![RubricCriterion { name: "Overall".to_string(), points: 6.0 }]);
+    }
+
+    #[test]
+    fn test_score_sums_partial_credit() {
+        let rubric = parse_rubric(Some(RUBRIC), 10.0);
+
+        let score = score_rubric("q1", &rubric, &[
+            award("Analysis Depth", 2.5),
+            award("practical recommendations", 3.0),
+        ]).unwrap();
+
+        assert_eq!(score.total, 5.5);
+        assert_eq!(score.max_score, 10.0);
+        let awarded: Vec<f64> = score.breakdown.iter().map(|c| c.points_awarded).collect();
+        assert_eq!(awarded, vec![2.5, 0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_score_rejects_over_award() {
+        let rubric = parse_rubric(Some(RUBRIC), 10.0);
+
+        let over = score_rubric("q1", &rubric, &[award("Use of Frameworks", 3.5)]);
+        assert!(matches!(over, Err(AssessmentError::ValidationError(_))));
+
+        let unknown = score_rubric("q1", &rubric, &[award("Spelling", 1.0)]);
+        assert!(matches!(unknown, Err(AssessmentError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_weighted_criteria_share_remaining_points() {
+        let rubric = parse_rubric(Some(
+            r#"{"criteria": [{"name": "Accuracy", "points": 4}, {"name": "Analysis", "weight": 2}, "Clarity"]}"#
+        ), 10.0);
+
+        assert_eq!(rubric, vec![
+            RubricCriterion { name: "Accuracy".to_string(), points: 4.0 },
+            RubricCriterion { name: "Analysis".to_string(), points: 4.0 },
+            RubricCriterion { name: "Clarity".to_string(), points: 2.0 },
+        ]);
+
+        let score = score_rubric("q1", &rubric, &[
+            award("Accuracy", 4.0),
+            award("Analysis", 3.0),
+            award("Clarity", 2.0),
+        ]).unwrap();
+        assert_eq!(score.total, 9.0);
+        assert!(score_rubric("q1", &rubric, &[award("Clarity", 3.0)]).is_err());
+    }
+}
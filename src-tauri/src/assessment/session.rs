@@ -2,18 +2,23 @@
 
 use super::models::*;
 use super::{AssessmentError, Result};
-use chrono::{Duration, Utc};
-use std::collections::HashMap;
+use crate::clock::{Clock, SystemClock};
+use std::sync::Arc;
 
 /// Assessment session manager for real-time session operations
 pub struct AssessmentSession {
     session: AssessmentSessionModel,
     workflow: AssessmentWorkflowModel,
+    clock: Arc<dyn Clock>,
 }
 
 impl AssessmentSession {
     pub fn new(session: AssessmentSessionModel, workflow: AssessmentWorkflowModel) -> Self {
-        Self { session, workflow }
+        Self::with_clock(session, workflow, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(session: AssessmentSessionModel, workflow: AssessmentWorkflowModel, clock: Arc<dyn Clock>) -> Self {
+        Self { session, workflow, clock }
     }
 
     /// Get current session state
@@ -26,30 +31,65 @@ impl AssessmentSession {
         &mut self.session
     }
 
+    /// Take back the session model after updates
+    pub fn into_session(self) -> AssessmentSessionModel {
+        self.session
+    }
+
     /// Get workflow configuration
     pub fn get_workflow(&self) -> &AssessmentWorkflowModel {
         &self.workflow
     }
 
+    /// Active time in seconds, excluding paused intervals
+    pub fn elapsed_seconds(&self) -> i32 {
+        let running = self.session.session_data.active_since
+            .map(|since| (self.clock.now() - since).num_seconds().max(0) as i32)
+            .unwrap_or(0);
+        self.session.time_spent + running
+    }
+
+    fn time_limit_seconds(&self) -> Option<i32> {
+        self.workflow.configuration.time_limit_minutes.map(|minutes| minutes * 60)
+    }
+
     /// Check if session has timed out
     pub fn is_timed_out(&self) -> bool {
-        if let Some(time_limit) = self.workflow.configuration.time_limit {
-            let elapsed_minutes = self.session.time_spent / 60;
-            elapsed_minutes >= time_limit
-        } else {
-            false
+        if self.session.session_state == SessionState::TimedOut {
+            return true;
         }
+
+        self.time_limit_seconds()
+            .map(|limit| self.elapsed_seconds() >= limit)
+            .unwrap_or(false)
     }
 
     /// Get remaining time in seconds
     pub fn get_remaining_time(&self) -> Option<i32> {
-        if let Some(time_limit) = self.workflow.configuration.time_limit {
-            let time_limit_seconds = time_limit * 60;
-            let remaining = time_limit_seconds - self.session.time_spent;
-            Some(remaining.max(0))
-        } else {
-            None
+        self.time_limit_seconds()
+            .map(|limit| (limit - self.elapsed_seconds()).max(0))
+    }
+
+    /// Start counting active time
+    pub fn start_clock(&mut self) {
+        if self.session.session_data.active_since.is_none() {
+            self.session.session_data.active_since = Some(self.clock.now());
+        }
+    }
+
+    /// Stop counting active time, folding the running interval into `time_spent`
+    pub fn stop_clock(&mut self) {
+        self.session.time_spent = self.elapsed_seconds();
+        self.session.session_data.active_since = None;
+    }
+
+    /// Mark the session as timed out, counting no more time than the limit allows
+    pub fn expire(&mut self) {
+        self.stop_clock();
+        if let Some(limit) = self.time_limit_seconds() {
+            self.session.time_spent = self.session.time_spent.min(limit);
         }
+        self.set_session_state(SessionState::TimedOut);
     }
 
     /// Check if session is still active
@@ -186,7 +226,7 @@ impl AssessmentSession {
 
     /// Calculate session statistics
     pub fn get_session_statistics(&self) -> SessionStatistics {
-        let total_time = self.session.time_spent;
+        let total_time = self.elapsed_seconds();
         let answered_questions = self.get_answered_count();
         let total_questions = self.get_total_questions();
         
@@ -229,20 +269,20 @@ impl AssessmentSession {
     /// Update session with new response (mutable version)
     pub fn update_with_response(&mut self, question_id: String, response: QuestionResponse) {
         self.session.responses.insert(question_id, response);
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
         self.update_progress();
     }
 
     /// Add navigation event
     pub fn add_navigation_event(&mut self, event: NavigationEvent) {
         self.session.session_data.navigation_history.push(event);
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
     }
 
     /// Add case study interaction
     pub fn add_case_study_interaction(&mut self, interaction: CaseStudyInteraction) {
         self.session.session_data.case_study_interactions.push(interaction);
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
     }
 
     /// Bookmark question
@@ -250,40 +290,42 @@ impl AssessmentSession {
         if !self.session.session_data.bookmarked_questions.contains(&question_id) {
             self.session.session_data.bookmarked_questions.push(question_id);
         }
+        self.session.last_activity = self.clock.now();
     }
 
     /// Remove bookmark
     pub fn remove_bookmark(&mut self, question_id: &str) {
         self.session.session_data.bookmarked_questions.retain(|id| id != question_id);
+        self.session.last_activity = self.clock.now();
     }
 
     /// Add or update question notes
     pub fn update_question_notes(&mut self, question_id: String, notes: String) {
         self.session.session_data.notes.insert(question_id, notes);
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
     }
 
     /// Update current question
     pub fn set_current_question(&mut self, question_id: Option<String>) {
         self.session.current_question_id = question_id;
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
     }
 
     /// Update session state
     pub fn set_session_state(&mut self, state: SessionState) {
         let state_clone = state.clone();
         self.session.session_state = state;
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
         
         if matches!(state_clone, SessionState::Completed | SessionState::Submitted | SessionState::TimedOut) {
-            self.session.end_time = Some(Utc::now());
+            self.session.end_time = Some(self.clock.now());
         }
     }
 
     /// Update time spent
     pub fn update_time_spent(&mut self, additional_seconds: i32) {
         self.session.time_spent += additional_seconds;
-        self.session.last_activity = Utc::now();
+        self.session.last_activity = self.clock.now();
     }
 
     /// Set question time tracking
@@ -324,19 +366,21 @@ impl AssessmentSession {
     pub fn get_session_warnings(&self) -> Vec<SessionWarning> {
         let mut warnings = Vec::new();
 
-        // Time warnings
-        if let Some(remaining) = self.get_remaining_time() {
+        // Time warnings, only while the clock is running
+        let remaining = self.get_remaining_time()
+            .filter(|_| self.session.session_state == SessionState::InProgress);
+        if let Some(remaining) = remaining {
             if remaining <= 300 { // 5 minutes
                 warnings.push(SessionWarning {
                     warning_type: SessionWarningType::TimeRunningOut,
-                    message: format!("Only {} minutes remaining", remaining / 60),
+                    message: format!("Only {} minutes remaining", (remaining + 59) / 60),
                     severity: if remaining <= 60 { WarningLevel::Critical } else { WarningLevel::Warning },
                 });
             }
         }
 
         // Progress warnings
-        if self.get_progress_percentage() < 50.0 && self.session.time_spent > 1800 { // 30 minutes
+        if self.get_progress_percentage() < 50.0 && self.elapsed_seconds() > 1800 { // 30 minutes
             warnings.push(SessionWarning {
                 warning_type: SessionWarningType::SlowProgress,
                 message: "You may want to increase your pace".to_string(),
@@ -0,0 +1,86 @@
+// Deterministic per-session ordering of questions and answer options
+
+use super::models::ResponseAnswer;
+use super::{AssessmentError, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+/// Permutation of `0..len` derived from `key`. Entry `i` is the canonical index of the
+/// item shown at position `i`; the same key always yields the same order.
+pub fn permutation(key: &str, len: usize) -> Vec<usize> {
+    let seed: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+    let mut order: Vec<usize> = (0..len).collect();
+    order.shuffle(&mut StdRng::from_seed(seed));
+    order
+}
+
+/// Question order for a session
+pub fn question_order(session_id: &str, len: usize) -> Vec<usize> {
+    permutation(session_id, len)
+}
+
+/// Option order for one question in a session
+pub fn option_order(session_id: &str, question_id: &str, len: usize) -> Vec<usize> {
+    permutation(&format!("{}:{}", session_id, question_id), len)
+}
+
+/// Arrange `items` in presentation order
+pub fn apply<T: Clone>(order: &[usize], items: &[T]) -> Vec<T> {
+    order.iter().map(|&i| items[i].clone()).collect()
+}
+
+/// Map an option index as displayed back to its canonical index
+pub fn canonical_option(order: &[usize], displayed: &str) -> Result<String> {
+    displayed
+        .parse::<usize>()
+        .ok()
+        .and_then(|position| order.get(position))
+        .map(|canonical| canonical.to_string())
+        .ok_or_else(|| AssessmentError::ValidationError(format!("Invalid option '{}'", displayed)))
+}
+
+/// Rewrite a choice answer given in displayed option positions into canonical indices,
+/// so it compares directly with the question's `correct_answer`
+pub fn canonical_answer(order: &[usize], answer: ResponseAnswer) -> Result<ResponseAnswer> {
+    let map_all = |options: Vec<String>| {
+        options.iter().map(|o| canonical_option(order, o)).collect::<Result<Vec<_>>>()
+    };
+
+    Ok(match answer {
+        ResponseAnswer::SingleChoice(option) => ResponseAnswer::SingleChoice(canonical_option(order, &option)?),
+        ResponseAnswer::MultipleChoice(options) => ResponseAnswer::MultipleChoice(map_all(options)?),
+        ResponseAnswer::Ranking(options) => ResponseAnswer::Ranking(map_all(options)?),
+        other => other,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutation_is_stable_per_key() {
+        let order = permutation("session-a", 10);
+
+        assert_eq!(order, permutation("session-a", 10));
+        assert_ne!(order, permutation("session-b", 10));
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_canonical_answer_maps_displayed_positions() {
+        let order = vec![2, 0, 1];
+
+        let single = canonical_answer(&order, ResponseAnswer::SingleChoice("0".to_string())).unwrap();
+        assert!(matches!(single, ResponseAnswer::SingleChoice(ref o) if o == "2"));
+
+        let ranking = canonical_answer(&order, ResponseAnswer::Ranking(vec!["1".into(), "2".into(), "0".into()])).unwrap();
+        assert!(matches!(ranking, ResponseAnswer::Ranking(ref o) if o == &["0", "1", "2"]));
+
+        assert!(canonical_answer(&order, ResponseAnswer::SingleChoice("3".to_string())).is_err());
+    }
+}
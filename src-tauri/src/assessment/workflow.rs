@@ -1,25 +1,41 @@
 // Assessment workflow orchestration and business logic
 
 use super::models::*;
+use super::grading::{self, AnswerGrade, GradeStatus, GradingQuestion};
 use super::repository::AssessmentRepository;
+use super::rubric::{self, RubricAward, RubricScore};
+use super::session::AssessmentSession;
+use crate::clock::{Clock, SystemClock};
+use super::shuffle;
+use super::export::{self, ResultsExportOptions};
 use super::{AssessmentError, Result};
 // use crate::ai::providers::question_generator::QuestionGenerator;
+use crate::ai::AIManager;
+use crate::ai::question_generator::QuestionGenerator;
 use crate::case_study::models::CaseStudy;
 use crate::case_study::repository::CaseStudyRepository;
-use crate::database::DatabaseManager;
+use crate::case_study::CaseStudyManager;
+use crate::database::{AssessmentQuestionRepository, CollectionError, CollectionRepository, DatabaseManager};
 use chrono::Utc;
 use std::collections::HashMap;
-use uuid::Uuid;
+use std::sync::Arc;
 
 /// Main assessment workflow orchestrator
 pub struct AssessmentWorkflow {
     pub repository: AssessmentRepository,
     case_study_repository: CaseStudyRepository,
+    db: DatabaseManager,
+    clock: Arc<dyn Clock>,
     // question_generator: QuestionGenerator,
 }
 
 impl AssessmentWorkflow {
     pub fn new(db: DatabaseManager) -> Self {
+        Self::with_clock(db, Arc::new(SystemClock))
+    }
+
+    /// Create a workflow that times sessions with the given clock
+    pub fn with_clock(db: DatabaseManager, clock: Arc<dyn Clock>) -> Self {
         let repository = AssessmentRepository::new(db.clone());
         let case_study_repository = CaseStudyRepository::new(db.clone());
         // let question_generator = QuestionGenerator::new();
@@ -27,6 +43,8 @@ impl AssessmentWorkflow {
         Self {
             repository,
             case_study_repository,
+            db,
+            clock,
             // question_generator,
         }
     }
@@ -49,6 +67,144 @@ impl AssessmentWorkflow {
         Ok(workflow)
     }
 
+    /// Assemble one assessment from a collection's case studies, in collection order. Each
+    /// case contributes up to `questions_per_case` of its saved questions; when it has fewer,
+    /// the shortfall is generated if an AI manager is given. Cases that end up with no
+    /// questions are reported rather than failing the whole assessment.
+    pub async fn create_assessment_from_collection(
+        &self,
+        collection_id: i64,
+        questions_per_case: u32,
+        params: CollectionAssessmentParams,
+        ai_manager: Option<&AIManager>,
+    ) -> Result<CollectionAssessment> {
+        if questions_per_case == 0 {
+            return Err(AssessmentError::ValidationError("At least one question per case is required".to_string()));
+        }
+
+        let members = CollectionRepository::new(self.db.pool().clone())
+            .list_case_studies(collection_id)
+            .await
+            .map_err(|e| match e {
+                CollectionError::CollectionNotFound(id) => AssessmentError::NotFound(format!("Collection {}", id)),
+                other => AssessmentError::CaseStudyError(other.to_string()),
+            })?;
+        if members.is_empty() {
+            return Err(AssessmentError::ValidationError("Collection has no case studies".to_string()));
+        }
+
+        let questions = AssessmentQuestionRepository::new(self.db.pool().clone());
+        let case_studies = CaseStudyManager::new(self.db.clone());
+        let generator = ai_manager.map(|manager| QuestionGenerator::new(manager.clone()));
+        let wanted = questions_per_case as usize;
+
+        let mut contributions = Vec::new();
+        let mut failed_cases = Vec::new();
+        for member in members {
+            let fail = |reason: String| FailedCaseContribution {
+                case_study_id: member.case_study_id.clone(),
+                title: member.title.clone(),
+                reason,
+            };
+
+            let mut question_ids = match questions.list_ids_for_case_study(&member.case_study_id).await {
+                Ok(ids) => ids,
+                Err(e) => {
+                    failed_cases.push(fail(e.to_string()));
+                    continue;
+                }
+            };
+            question_ids.truncate(wanted);
+
+            let mut generated_count = 0;
+            let mut generation_error = None;
+            if question_ids.len() < wanted {
+                match &generator {
+                    Some(generator) => {
+                        let shortfall = wanted - question_ids.len();
+                        match self.generate_case_questions(generator, &case_studies, &questions, &member.case_study_id, shortfall, &params).await {
+                            Ok(ids) => {
+                                generated_count = ids.len().min(shortfall);
+                                question_ids.extend(ids.into_iter().take(shortfall));
+                            }
+                            Err(e) => generation_error = Some(e.to_string()),
+                        }
+                    }
+                    None => generation_error = Some("No saved questions and no AI provider to generate them".to_string()),
+                }
+            }
+
+            if question_ids.is_empty() {
+                failed_cases.push(fail(generation_error.unwrap_or_else(|| "No questions available".to_string())));
+                continue;
+            }
+            contributions.push(CaseContribution {
+                case_study_id: member.case_study_id,
+                title: member.title,
+                question_ids,
+                generated_count,
+            });
+        }
+
+        let first_case = contributions.first()
+            .ok_or_else(|| AssessmentError::ValidationError("No case study in the collection contributed questions".to_string()))?
+            .case_study_id
+            .clone();
+
+        let mut configuration = params.configuration;
+        configuration.question_source = QuestionSource::Collection {
+            collection_id,
+            question_ids: contributions.iter().flat_map(|c| c.question_ids.iter().copied()).collect(),
+        };
+
+        let workflow = self.create_workflow(NewAssessmentWorkflow {
+            title: params.title,
+            description: params.description,
+            case_study_id: first_case,
+            workflow_type: AssessmentWorkflowType::Portfolio,
+            configuration,
+            estimated_duration: params.estimated_duration,
+            difficulty_level: params.difficulty_level,
+            learning_objectives: params.learning_objectives,
+            instructions: params.instructions,
+            metadata: AssessmentMetadata::default(),
+            created_by: params.created_by,
+        }).await?;
+
+        Ok(CollectionAssessment { workflow, contributions, failed_cases })
+    }
+
+    /// Generate and save `count` questions for one collection member
+    async fn generate_case_questions(
+        &self,
+        generator: &QuestionGenerator,
+        case_studies: &CaseStudyManager,
+        questions: &AssessmentQuestionRepository,
+        case_study_id: &str,
+        count: usize,
+        params: &CollectionAssessmentParams,
+    ) -> Result<Vec<i64>> {
+        let case_study = self.case_study_repository
+            .find_by_id(case_study_id)
+            .await
+            .map_err(|e| AssessmentError::CaseStudyError(e.to_string()))?
+            .ok_or_else(|| AssessmentError::NotFound("Case study not found".to_string()))?;
+
+        let mut generation = params.generation.clone();
+        generation.case_study_title = case_study.title;
+        generation.case_study_content = case_study.content;
+        generation.case_study_summary = case_study.summary;
+        if !case_study.learning_objectives.is_empty() {
+            generation.learning_objectives = case_study.learning_objectives;
+        }
+        generation.num_questions = count as u32;
+
+        generator
+            .generate_and_save_questions(case_studies, questions, case_study_id, generation)
+            .await
+            .map_err(|e| AssessmentError::AIError(e.to_string()))
+    }
+
     /// Update an existing workflow
     pub async fn update_workflow(&self, id: &str, update: UpdateAssessmentWorkflow) -> Result<Option<AssessmentWorkflowModel>> {
         // Validate update data
@@ -97,11 +253,21 @@ impl AssessmentWorkflow {
         let mut session = self.repository.find_session_by_id(session_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
 
+        if self.apply_time_limit(&mut session).await? {
+            return Err(AssessmentError::InvalidState("Session time limit has been exceeded".to_string()));
+        }
+
         // Validate session state
         if !matches!(session.session_state, SessionState::InProgress) {
             return Err(AssessmentError::InvalidState("Session is not in progress".to_string()));
         }
 
+        // Choice answers arrive in displayed positions; store them canonically for grading
+        let answer = match session.session_data.option_orders.get(question_id) {
+            Some(order) => shuffle::canonical_answer(order, answer)?,
+            None => answer,
+        };
+
         // Calculate response time
         let response_time = self.calculate_response_time(&session, question_id)?;
 
@@ -113,23 +279,25 @@ impl AssessmentWorkflow {
             attempts: session.responses.get(question_id).map(|r| r.attempts + 1).unwrap_or(1),
             confidence_level,
             flagged_for_review: false,
-            submitted_at: Utc::now(),
+            submitted_at: self.clock.now(),
             is_correct: None, // Will be calculated
             partial_credit: None,
             feedback_shown: false,
+            grade: None,
         };
 
         // Update session with response
         session.responses.insert(question_id.to_string(), response);
-        session.last_activity = Utc::now();
+        session.last_activity = self.clock.now();
 
         // Calculate progress
         self.update_session_progress(&mut session).await?;
 
         // Check if session is complete
         if self.is_session_complete(&session)? {
+            session = self.stop_session_clock(session).await?;
             session.session_state = SessionState::Completed;
-            session.end_time = Some(Utc::now());
+            session.end_time = Some(self.clock.now());
             
             // Calculate final score
             self.calculate_final_score(&mut session).await?;
@@ -141,11 +309,21 @@ impl AssessmentWorkflow {
         Ok(session)
     }
 
-    /// Navigate to a specific question in a session
-    pub async fn navigate_to_question(&self, session_id: &str, question_id: &str) -> Result<AssessmentSessionModel> {
+    /// Navigate to a specific question in a session. When options are shuffled and
+    /// `option_count` is given, the question's option order is recorded on the session.
+    pub async fn navigate_to_question(
+        &self,
+        session_id: &str,
+        question_id: &str,
+        option_count: Option<usize>,
+    ) -> Result<AssessmentSessionModel> {
         let mut session = self.repository.find_session_by_id(session_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
 
+        if self.apply_time_limit(&mut session).await? {
+            return Err(AssessmentError::InvalidState("Session time limit has been exceeded".to_string()));
+        }
+
         // Validate navigation is allowed
         let workflow = self.repository.find_workflow_by_id(&session.workflow_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Workflow not found".to_string()))?;
@@ -165,13 +343,19 @@ impl AssessmentWorkflow {
             }
         }
 
+        if let Some(count) = option_count.filter(|_| workflow.configuration.shuffle_options) {
+            session.session_data.option_orders
+                .entry(question_id.to_string())
+                .or_insert_with(|| shuffle::option_order(session_id, question_id, count));
+        }
+
         // Update current question
         session.current_question_id = Some(question_id.to_string());
-        session.last_activity = Utc::now();
+        session.last_activity = self.clock.now();
 
         // Record navigation event
         let nav_event = NavigationEvent {
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             event_type: NavigationEventType::QuestionViewed,
             from_question: session.current_question_id.clone(),
             to_question: Some(question_id.to_string()),
@@ -185,21 +369,76 @@ impl AssessmentWorkflow {
         Ok(session)
     }
 
+    /// Bookmark a question, saving the session straight away
+    pub async fn bookmark_question(&self, session_id: &str, question_id: &str) -> Result<AssessmentSessionModel> {
+        self.update_session_view(session_id, |view| view.bookmark_question(question_id.to_string())).await
+    }
+
+    /// Set the user's notes for a question, saving the session straight away
+    pub async fn update_question_notes(&self, session_id: &str, question_id: &str, notes: String) -> Result<AssessmentSessionModel> {
+        self.update_session_view(session_id, |view| view.update_question_notes(question_id.to_string(), notes)).await
+    }
+
+    /// Reopen the user's most recently active unfinished session after the app stopped
+    /// unexpectedly. Answers, bookmarks, notes and the current question are already saved
+    /// with each change; a running clock is stopped at the last saved activity, so time the
+    /// app was down isn't counted, and restarted now.
+    pub async fn recover_session(&self, user_id: &str) -> Result<Option<AssessmentSessionModel>> {
+        let Some(mut session) = self.repository.find_latest_unfinished_session(user_id).await? else {
+            return Ok(None);
+        };
+
+        if let Some(active_since) = session.session_data.active_since {
+            let last_saved = session.last_activity.max(active_since);
+            session.time_spent += (last_saved - active_since).num_seconds().max(0) as i32;
+            session.session_data.active_since = Some(self.clock.now());
+            session.last_activity = self.clock.now();
+            self.repository.update_session(&session).await?;
+        }
+
+        self.apply_time_limit(&mut session).await?;
+        Ok(Some(session))
+    }
+
+    /// Apply `change` to the session through its view and save the result
+    async fn update_session_view(
+        &self,
+        session_id: &str,
+        change: impl FnOnce(&mut AssessmentSession),
+    ) -> Result<AssessmentSessionModel> {
+        let session = self.repository.find_session_by_id(session_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
+        let workflow = self.repository.find_workflow_by_id(&session.workflow_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Workflow not found".to_string()))?;
+
+        let mut view = AssessmentSession::with_clock(session, workflow, self.clock.clone());
+        change(&mut view);
+        let session = view.into_session();
+        self.repository.update_session(&session).await?;
+        Ok(session)
+    }
+
     /// Pause an assessment session
     pub async fn pause_session(&self, session_id: &str) -> Result<AssessmentSessionModel> {
         let mut session = self.repository.find_session_by_id(session_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
 
+        if self.apply_time_limit(&mut session).await? {
+            return Err(AssessmentError::InvalidState("Session time limit has been exceeded".to_string()));
+        }
+
         if session.session_state != SessionState::InProgress {
             return Err(AssessmentError::InvalidState("Session is not in progress".to_string()));
         }
 
+        // Paused time doesn't count towards the time limit
+        let mut session = self.stop_session_clock(session).await?;
         session.session_state = SessionState::Paused;
-        session.last_activity = Utc::now();
+        session.last_activity = self.clock.now();
 
         // Record navigation event
         let nav_event = NavigationEvent {
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             event_type: NavigationEventType::AssessmentPaused,
             from_question: session.current_question_id.clone(),
             to_question: None,
@@ -221,11 +460,12 @@ impl AssessmentWorkflow {
         }
 
         session.session_state = SessionState::InProgress;
-        session.last_activity = Utc::now();
+        session.session_data.active_since = Some(self.clock.now());
+        session.last_activity = self.clock.now();
 
         // Record navigation event
         let nav_event = NavigationEvent {
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             event_type: NavigationEventType::AssessmentResumed,
             from_question: session.current_question_id.clone(),
             to_question: None,
@@ -242,13 +482,19 @@ impl AssessmentWorkflow {
         let mut session = self.repository.find_session_by_id(session_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
 
+        // An expired session has already been submitted for grading
+        if self.apply_time_limit(&mut session).await? {
+            return self.generate_assessment_result(&session).await;
+        }
+
         if !matches!(session.session_state, SessionState::InProgress | SessionState::Completed) {
             return Err(AssessmentError::InvalidState("Session cannot be submitted".to_string()));
         }
 
         // Mark as submitted
+        let mut session = self.stop_session_clock(session).await?;
         session.session_state = SessionState::Submitted;
-        session.end_time = Some(Utc::now());
+        session.end_time = Some(self.clock.now());
 
         // Calculate final score and grade responses
         self.calculate_final_score(&mut session).await?;
@@ -262,12 +508,118 @@ impl AssessmentWorkflow {
         Ok(result)
     }
 
+    /// Export every session of a workflow as CSV
+    pub async fn export_results_csv(&self, workflow_id: &str, options: &ResultsExportOptions) -> Result<String> {
+        self.repository.find_workflow_by_id(workflow_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Workflow not found".to_string()))?;
+
+        let sessions = self.repository.get_workflow_sessions(workflow_id).await?;
+        let mut buffer = Vec::new();
+        export::write_results_csv(&sessions, options, &mut buffer)?;
+
+        String::from_utf8(buffer).map_err(|e| AssessmentError::ExportError(e.to_string()))
+    }
+
+    /// Time out the session if its limit has passed, returning the up-to-date session
+    pub async fn enforce_time_limit(&self, session_id: &str) -> Result<AssessmentSessionModel> {
+        let mut session = self.repository.find_session_by_id(session_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
+
+        self.apply_time_limit(&mut session).await?;
+        Ok(session)
+    }
+
+    /// Optional AI grading pass over free-text answers. Suggested grades are stored on the
+    /// responses for instructor review; answers an instructor has already graded are skipped.
+    pub async fn auto_grade_session(
+        &self,
+        session_id: &str,
+        ai_manager: &AIManager,
+        questions: &[GradingQuestion],
+    ) -> Result<Vec<AnswerGrade>> {
+        let mut session = self.repository.find_session_by_id(session_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
+
+        let mut grades = Vec::new();
+        for question in questions {
+            let response = match session.responses.get_mut(&question.question_id) {
+                Some(response) => response,
+                None => continue,
+            };
+
+            let answer = match &response.answer {
+                ResponseAnswer::TextInput(text) | ResponseAnswer::Essay(text) => text.clone(),
+                _ => continue,
+            };
+
+            if response.grade.as_ref().is_some_and(|g| g.status == GradeStatus::InstructorOverride) {
+                continue;
+            }
+
+            let grade = grading::auto_grade_answer(ai_manager, question, &answer).await?;
+            response.partial_credit = grade.score
+                .filter(|_| grade.max_score > 0.0)
+                .map(|score| score / grade.max_score);
+            response.grade = Some(grade.clone());
+            grades.push(grade);
+        }
+
+        self.repository.update_session(&session).await?;
+        Ok(grades)
+    }
+
+    /// Set an instructor's grade for a response, replacing any AI suggestion
+    pub async fn override_answer_grade(
+        &self,
+        session_id: &str,
+        question_id: &str,
+        score: f64,
+        max_score: f64,
+        feedback: Option<String>,
+    ) -> Result<AssessmentSessionModel> {
+        if max_score <= 0.0 || !(0.0..=max_score).contains(&score) {
+            return Err(AssessmentError::ValidationError(
+                "Score must be between 0 and the maximum score".to_string()
+            ));
+        }
+
+        let mut session = self.repository.find_session_by_id(session_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
+
+        let response = session.responses.get_mut(question_id)
+            .ok_or_else(|| AssessmentError::NotFound("Response not found".to_string()))?;
+
+        let criteria = response.grade.take().map(|g| g.criteria).unwrap_or_default();
+        response.partial_credit = Some(score / max_score);
+        response.grade = Some(AnswerGrade {
+            question_id: question_id.to_string(),
+            status: GradeStatus::InstructorOverride,
+            score: Some(score),
+            max_score,
+            criteria,
+            feedback,
+            graded_at: self.clock.now(),
+        });
+
+        self.repository.update_session(&session).await?;
+        Ok(session)
+    }
+
+    /// Compute partial credit for a question from per-criterion awards
+    pub async fn score_with_rubric(&self, question_id: i64, awards: &[RubricAward]) -> Result<RubricScore> {
+        let question = self.repository.find_question_by_id(question_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Question not found".to_string()))?;
+
+        let criteria = rubric::parse_rubric(question.rubric.as_deref(), question.points as f64);
+        rubric::score_rubric(&question.id.to_string(), &criteria, awards)
+    }
+
     /// Get assessment result for a completed session
     pub async fn get_assessment_result(&self, session_id: &str) -> Result<AssessmentResult> {
         let session = self.repository.find_session_by_id(session_id).await?
             .ok_or_else(|| AssessmentError::NotFound("Session not found".to_string()))?;
 
-        if !matches!(session.session_state, SessionState::Completed | SessionState::Submitted) {
+        if !matches!(session.session_state, SessionState::Completed | SessionState::Submitted | SessionState::TimedOut) {
             return Err(AssessmentError::InvalidState("Session is not completed".to_string()));
         }
 
@@ -276,6 +628,43 @@ impl AssessmentWorkflow {
 
     /// Private helper methods
 
+    /// Time out an in-progress session whose active time has reached the limit, scoring it
+    /// when the workflow auto-submits. Returns whether the session expired.
+    async fn apply_time_limit(&self, session: &mut AssessmentSessionModel) -> Result<bool> {
+        if session.session_state != SessionState::InProgress {
+            return Ok(false);
+        }
+
+        let workflow = self.repository.find_workflow_by_id(&session.workflow_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Workflow not found".to_string()))?;
+        let auto_submit = workflow.configuration.navigation_settings.auto_submit_on_time_limit;
+
+        let mut timer = AssessmentSession::with_clock(session.clone(), workflow, self.clock.clone());
+        if !timer.is_timed_out() {
+            return Ok(false);
+        }
+
+        timer.expire();
+        *session = timer.into_session();
+
+        if auto_submit {
+            self.calculate_final_score(session).await?;
+        }
+
+        self.repository.update_session(session).await?;
+        Ok(true)
+    }
+
+    /// Fold the running clock into the session's active time
+    async fn stop_session_clock(&self, session: AssessmentSessionModel) -> Result<AssessmentSessionModel> {
+        let workflow = self.repository.find_workflow_by_id(&session.workflow_id).await?
+            .ok_or_else(|| AssessmentError::NotFound("Workflow not found".to_string()))?;
+
+        let mut timer = AssessmentSession::with_clock(session, workflow, self.clock.clone());
+        timer.stop_clock();
+        Ok(timer.into_session())
+    }
+
     async fn validate_workflow_data(&self, workflow: &NewAssessmentWorkflow) -> Result<()> {
         if workflow.title.trim().is_empty() {
             return Err(AssessmentError::ValidationError("Title is required".to_string()));
@@ -297,8 +686,15 @@ impl AssessmentWorkflow {
         Ok(())
     }
 
-    async fn validate_workflow_for_publishing(&self, _workflow: &AssessmentWorkflowModel) -> Result<()> {
+    async fn validate_workflow_for_publishing(&self, workflow: &AssessmentWorkflowModel) -> Result<()> {
         // Validate workflow has questions, proper configuration, etc.
+        if let QuestionSource::QuestionBank { filter, .. } = &workflow.configuration.question_source {
+            if self.repository.search_bank_questions(filter, 1, 0).await?.is_empty() {
+                return Err(AssessmentError::ValidationError(
+                    "No question bank entries match the workflow's filter".to_string()
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -314,25 +710,45 @@ impl AssessmentWorkflow {
             .ok_or_else(|| AssessmentError::NotFound("Case study not found".to_string()))?;
 
         // Generate questions for this session
-        let question_sequence = self.generate_question_sequence(&workflow, &case_study).await?;
+        let canonical_sequence = self.generate_question_sequence(&workflow, &case_study).await?;
 
         let mut updated_session = session.clone();
-        updated_session.session_data.question_sequence = question_sequence;
+        if workflow.configuration.shuffle_questions {
+            let order = shuffle::question_order(&session.id, canonical_sequence.len());
+            updated_session.session_data.question_sequence = shuffle::apply(&order, &canonical_sequence);
+            updated_session.session_data.question_order = order;
+        } else {
+            updated_session.session_data.question_sequence = canonical_sequence;
+        }
         updated_session.session_state = SessionState::InProgress;
+        updated_session.session_data.active_since = Some(self.clock.now());
         updated_session.current_question_id = updated_session.session_data.question_sequence.first().cloned();
 
         self.repository.update_session(&updated_session).await?;
         Ok(updated_session)
     }
 
-    async fn generate_question_sequence(&self, _workflow: &AssessmentWorkflowModel, _case_study: &CaseStudy) -> Result<Vec<String>> {
-        // This would integrate with the question generation system
-        // For now, return placeholder questions
-        Ok(vec![
-            Uuid::new_v4().to_string(),
-            Uuid::new_v4().to_string(),
-            Uuid::new_v4().to_string(),
-        ])
+    async fn generate_question_sequence(&self, workflow: &AssessmentWorkflowModel, _case_study: &CaseStudy) -> Result<Vec<String>> {
+        match &workflow.configuration.question_source {
+            QuestionSource::CaseStudy => {
+                // This would integrate with the question generation system
+                // For now, return placeholder questions, shared by every session of the workflow
+                Ok((1..=3).map(|n| format!("{}-q{}", workflow.id, n)).collect())
+            }
+            QuestionSource::QuestionBank { filter, question_count } => {
+                let limit = question_count.map_or(i32::MAX, |count| count.min(i32::MAX as usize) as i32);
+                let questions = self.repository.search_bank_questions(filter, limit, 0).await?;
+                if questions.is_empty() {
+                    return Err(AssessmentError::ValidationError(
+                        "No question bank entries match the workflow's filter".to_string()
+                    ));
+                }
+                Ok(questions.into_iter().map(|question| question.id).collect())
+            }
+            QuestionSource::Collection { question_ids, .. } => {
+                Ok(question_ids.iter().map(|id| id.to_string()).collect())
+            }
+        }
     }
 
     fn calculate_response_time(&self, _session: &AssessmentSessionModel, _question_id: &str) -> Result<i32> {
@@ -397,11 +813,13 @@ impl AssessmentWorkflow {
 
         // Generate question results
         let question_results: Vec<QuestionResult> = session.responses.iter().map(|(question_id, response)| {
+            let (score, max_score) = response.score();
+
             QuestionResult {
                 question_id: question_id.clone(),
                 correct: response.is_correct.unwrap_or(false),
-                score: if response.is_correct.unwrap_or(false) { 1.0 } else { 0.0 },
-                max_score: 1.0,
+                score,
+                max_score,
                 response_time: response.response_time,
                 attempts: response.attempts,
                 difficulty_level: "intermediate".to_string(), // Placeholder
@@ -452,4 +870,444 @@ impl AssessmentWorkflow {
             generated_at: Utc::now(),
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assessment::session::SessionWarningType;
+    use crate::clock::MockClock;
+    use crate::case_study::models::NewCaseStudy;
+    use crate::database::{NewCaseStudyQuestion, QuestionType as StoredQuestionType};
+    use chrono::Duration;
+
+    /// A draft assessment with the given configuration
+    async fn draft_workflow(clock: Arc<MockClock>, configuration: AssessmentConfiguration) -> (AssessmentWorkflow, AssessmentWorkflowModel) {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let case_study = CaseStudyRepository::new(db.clone())
+            .create(NewCaseStudy {
+                industry: "Energy".to_string(),
+                duration_minutes: 30,
+                learning_objectives: vec!["Market analysis".to_string()],
+                ..NewCaseStudy::for_test("Market Entry for Solis Energy", "The board debates a market entry strategy for solar panels in Brazil.")
+            })
+            .await
+            .unwrap();
+
+        let workflow = AssessmentWorkflow::with_clock(db, clock);
+        let model = workflow
+            .create_workflow(NewAssessmentWorkflow {
+                title: "Solis Energy quiz".to_string(),
+                description: None,
+                case_study_id: case_study.id,
+                workflow_type: AssessmentWorkflowType::Timed,
+                configuration,
+                estimated_duration: 30,
+                difficulty_level: "intermediate".to_string(),
+                learning_objectives: vec!["Market analysis".to_string()],
+                instructions: None,
+                metadata: AssessmentMetadata::default(),
+                created_by: None,
+            })
+            .await
+            .unwrap();
+        (workflow, model)
+    }
+
+    /// A published assessment with the given configuration
+    async fn published_workflow(clock: Arc<MockClock>, configuration: AssessmentConfiguration) -> (AssessmentWorkflow, AssessmentWorkflowModel) {
+        let (workflow, model) = draft_workflow(clock, configuration).await;
+        let model = workflow.publish_workflow(&model.id).await.unwrap().unwrap();
+        (workflow, model)
+    }
+
+    fn bank_question(text: &str, difficulty_level: &str, tags: &[&str]) -> NewQuestionBankEntry {
+        NewQuestionBankEntry {
+            question_text: text.to_string(),
+            question_type: "single_choice".to_string(),
+            options: vec!["Expand".to_string(), "Hold".to_string(), "Divest".to_string()],
+            correct_answer: Some("0".to_string()),
+            sample_answer: None,
+            rubric: None,
+            points: Some(2),
+            difficulty_level: difficulty_level.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created_by: None,
+        }
+    }
+
+    /// A published 30-minute assessment with one session started at the clock's current time
+    async fn timed_session(clock: Arc<MockClock>) -> (AssessmentWorkflow, AssessmentSessionModel) {
+        let configuration = AssessmentConfiguration {
+            time_limit_minutes: Some(30),
+            ..AssessmentConfiguration::default()
+        };
+        let (workflow, model) = published_workflow(clock, configuration).await;
+
+        let session = workflow.start_session(&model.id, "student-1").await.unwrap();
+        (workflow, session)
+    }
+
+    async fn session_view(workflow: &AssessmentWorkflow, session: AssessmentSessionModel) -> AssessmentSession {
+        let model = workflow.repository.find_workflow_by_id(&session.workflow_id).await.unwrap().unwrap();
+        AssessmentSession::with_clock(session, model, workflow.clock.clone())
+    }
+
+    #[tokio::test]
+    async fn test_paused_time_is_not_counted() {
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let (workflow, session) = timed_session(clock.clone()).await;
+
+        clock.advance(Duration::minutes(20));
+        workflow.pause_session(&session.id).await.unwrap();
+        clock.advance(Duration::hours(2));
+        workflow.resume_session(&session.id).await.unwrap();
+        clock.advance(Duration::minutes(7));
+
+        let session = workflow.enforce_time_limit(&session.id).await.unwrap();
+        assert_eq!(session.session_state, SessionState::InProgress);
+
+        let view = session_view(&workflow, session).await;
+        assert_eq!(view.elapsed_seconds(), 27 * 60);
+        assert_eq!(view.get_remaining_time(), Some(3 * 60));
+        assert!(view.get_session_warnings().iter()
+            .any(|w| matches!(w.warning_type, SessionWarningType::TimeRunningOut)));
+    }
+
+    #[tokio::test]
+    async fn test_session_auto_submits_when_time_limit_exceeded() {
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let (workflow, session) = timed_session(clock.clone()).await;
+
+        clock.advance(Duration::minutes(29));
+        let in_progress = workflow.enforce_time_limit(&session.id).await.unwrap();
+        assert_eq!(in_progress.session_state, SessionState::InProgress);
+        // Still blocked by unanswered questions, not by the clock
+        assert!(matches!(
+            session_view(&workflow, in_progress).await.can_submit(),
+            Err(AssessmentError::ValidationError(_))
+        ));
+
+        clock.advance(Duration::minutes(2));
+        let expired = workflow.enforce_time_limit(&session.id).await.unwrap();
+
+        assert_eq!(expired.session_state, SessionState::TimedOut);
+        assert_eq!(expired.time_spent, 30 * 60);
+        assert_eq!(expired.session_data.active_since, None);
+        assert!(expired.final_score.is_some());
+
+        let view = session_view(&workflow, expired).await;
+        assert!(view.is_timed_out());
+        assert!(matches!(view.can_submit(), Err(AssessmentError::InvalidState(_))));
+
+        let answer = workflow
+            .submit_answer(&session.id, "q1", ResponseAnswer::TextInput("Too late".to_string()), None)
+            .await;
+        assert!(matches!(answer, Err(AssessmentError::InvalidState(_))));
+        assert!(workflow.get_assessment_result(&session.id).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_session_recovers_after_crash() {
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let (workflow, session) = timed_session(clock.clone()).await;
+        let q1 = session.session_data.question_sequence[0].clone();
+        let q2 = session.session_data.question_sequence[1].clone();
+
+        clock.advance(Duration::minutes(4));
+        workflow
+            .submit_answer(&session.id, &q1, ResponseAnswer::TextInput("Enter through a joint venture".to_string()), None)
+            .await
+            .unwrap();
+        clock.advance(Duration::minutes(3));
+        workflow.navigate_to_question(&session.id, &q2, None).await.unwrap();
+        clock.advance(Duration::minutes(1));
+        workflow.bookmark_question(&session.id, &q2).await.unwrap();
+        workflow.update_question_notes(&session.id, &q2, "Check the currency risk".to_string()).await.unwrap();
+
+        // The app goes away without pausing; a new workflow over the same database starts hours later
+        clock.advance(Duration::hours(3));
+        let relaunched = AssessmentWorkflow::with_clock(workflow.db.clone(), clock.clone());
+        let recovered = relaunched.recover_session("student-1").await.unwrap().unwrap();
+
+        assert_eq!(recovered.id, session.id);
+        assert_eq!(recovered.session_state, SessionState::InProgress);
+        assert_eq!(recovered.current_question_id.as_deref(), Some(q2.as_str()));
+        assert!(matches!(&recovered.responses[&q1].answer, ResponseAnswer::TextInput(text) if text == "Enter through a joint venture"));
+        assert_eq!(recovered.session_data.bookmarked_questions, vec![q2.clone()]);
+        assert_eq!(recovered.session_data.notes[&q2], "Check the currency risk");
+        // Only time up to the last saved change counts; the downtime doesn't
+        assert_eq!(recovered.time_spent, 8 * 60);
+
+        clock.advance(Duration::minutes(2));
+        let view = session_view(&relaunched, recovered).await;
+        assert_eq!(view.elapsed_seconds(), 10 * 60);
+        assert_eq!(view.get_remaining_time(), Some(20 * 60));
+
+        assert!(relaunched.recover_session("student-2").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shuffled_sessions_grade_identically() {
+        let configuration = AssessmentConfiguration {
+            shuffle_questions: true,
+            shuffle_options: true,
+            ..AssessmentConfiguration::default()
+        };
+        let (workflow, model) = published_workflow(Arc::new(MockClock::new(Utc::now())), configuration).await;
+
+        // Session ids are random, so keep starting sessions until one is shown the questions
+        // in a different order from the first; a shuffle that does nothing never gets there
+        let first = workflow.start_session(&model.id, "student-1").await.unwrap();
+        let mut second = workflow.start_session(&model.id, "student-2").await.unwrap();
+        for student in 3..=12 {
+            if second.session_data.question_order != first.session_data.question_order {
+                break;
+            }
+            second = workflow.start_session(&model.id, &format!("student-{}", student)).await.unwrap();
+        }
+        assert_ne!(first.session_data.question_order, second.session_data.question_order);
+        assert_ne!(first.session_data.question_sequence, second.session_data.question_sequence);
+
+        let mut finished = Vec::new();
+        for session in [first, second] {
+            assert_eq!(session.session_data.question_order, shuffle::question_order(&session.id, 3));
+
+            let mut canonical = session.session_data.question_sequence.clone();
+            canonical.sort();
+            assert_eq!(canonical, vec![format!("{}-q1", model.id), format!("{}-q2", model.id), format!("{}-q3", model.id)]);
+
+            let mut latest = session.clone();
+            for question_id in &canonical {
+                let viewed = workflow.navigate_to_question(&session.id, question_id, Some(4)).await.unwrap();
+                let order = &viewed.session_data.option_orders[question_id];
+                assert_eq!(order, &shuffle::option_order(&session.id, question_id, 4));
+
+                // Pick whichever displayed position holds canonical option 2
+                let displayed = order.iter().position(|&canonical| canonical == 2).unwrap();
+                latest = workflow
+                    .submit_answer(&session.id, question_id, ResponseAnswer::SingleChoice(displayed.to_string()), None)
+                    .await
+                    .unwrap();
+            }
+            finished.push(latest);
+        }
+
+        let answers = |session: &AssessmentSessionModel| {
+            let mut answers: Vec<(String, serde_json::Value)> = session.responses.iter()
+                .map(|(question_id, response)| (question_id.clone(), serde_json::to_value(&response.answer).unwrap()))
+                .collect();
+            answers.sort_by(|a, b| a.0.cmp(&b.0));
+            answers
+        };
+
+        assert_ne!(finished[0].session_data.option_orders, finished[1].session_data.option_orders);
+        assert_eq!(answers(&finished[0]), answers(&finished[1]));
+        assert!(answers(&finished[0]).iter().all(|(_, answer)| answer == &serde_json::json!({ "SingleChoice": "2" })));
+        assert_eq!(finished[0].session_state, SessionState::Completed);
+        assert_eq!(finished[0].final_score, finished[1].final_score);
+    }
+
+    #[tokio::test]
+    async fn test_bank_search_by_tag_and_difficulty() {
+        let (workflow, _) = draft_workflow(Arc::new(MockClock::new(Utc::now())), AssessmentConfiguration::default()).await;
+        let repository = &workflow.repository;
+
+        let npv = repository.add_bank_question(bank_question("Which project has the higher NPV?", "intermediate", &["Finance", "valuation"])).await.unwrap();
+        repository.add_bank_question(bank_question("What does ROI measure?", "beginner", &["finance"])).await.unwrap();
+        repository.add_bank_question(bank_question("Which segment should the launch target?", "intermediate", &["marketing"])).await.unwrap();
+        assert_eq!(npv.tags, vec!["finance", "valuation"]);
+
+        let filter = QuestionBankFilter {
+            tags: vec!["FINANCE".to_string()],
+            difficulty_level: Some("intermediate".to_string()),
+            ..QuestionBankFilter::default()
+        };
+        let found = repository.search_bank_questions(&filter, 10, 0).await.unwrap();
+        assert_eq!(found.iter().map(|q| q.id.as_str()).collect::<Vec<_>>(), vec![npv.id.as_str()]);
+        assert_eq!(found[0].options.len(), 3);
+
+        let finance = QuestionBankFilter { tags: vec!["finance".to_string()], ..QuestionBankFilter::default() };
+        assert_eq!(repository.search_bank_questions(&finance, 10, 0).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_draws_questions_from_bank() {
+        let configuration = AssessmentConfiguration {
+            question_source: QuestionSource::QuestionBank {
+                filter: QuestionBankFilter {
+                    tags: vec!["strategy".to_string()],
+                    difficulty_level: Some("advanced".to_string()),
+                    ..QuestionBankFilter::default()
+                },
+                question_count: Some(2),
+            },
+            ..AssessmentConfiguration::default()
+        };
+        let (workflow, model) = draft_workflow(Arc::new(MockClock::new(Utc::now())), configuration).await;
+
+        // Nothing in the bank matches yet
+        assert!(matches!(
+            workflow.publish_workflow(&model.id).await,
+            Err(AssessmentError::ValidationError(_))
+        ));
+
+        let mut expected = Vec::new();
+        for text in ["Should Solis build or buy its distribution?", "Which five forces matter most?", "How should Solis respond to a price war?"] {
+            let entry = workflow.repository.add_bank_question(bank_question(text, "advanced", &["strategy"])).await.unwrap();
+            expected.push(entry.id);
+        }
+        workflow.repository.add_bank_question(bank_question("Define market share.", "beginner", &["strategy"])).await.unwrap();
+        expected.truncate(2);
+
+        workflow.publish_workflow(&model.id).await.unwrap();
+        let session = workflow.start_session(&model.id, "student-1").await.unwrap();
+
+        assert_eq!(session.session_data.question_sequence, expected);
+        assert_eq!(session.current_question_id.as_deref(), Some(expected[0].as_str()));
+    }
+
+    /// A collection of three case studies, added in the order given, with `saved` questions each
+    async fn seeded_collection(saved: [usize; 3]) -> (AssessmentWorkflow, i64, Vec<String>, Vec<Vec<i64>>) {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let case_studies = CaseStudyRepository::new(db.clone());
+        let questions = AssessmentQuestionRepository::new(db.pool().clone());
+        let collections = CollectionRepository::new(db.pool().clone());
+
+        let user_id = sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(db.pool()).await.unwrap().last_insert_rowid();
+        let collection_id = sqlx::query("INSERT INTO collections (name, created_by) VALUES ('Strategy module', ?)")
+            .bind(user_id)
+            .execute(db.pool()).await.unwrap().last_insert_rowid();
+
+        let mut case_ids = Vec::new();
+        let mut question_ids = Vec::new();
+        for (title, count) in ["Solis Energy", "Nordic Outfitters", "Harbour Freight"].into_iter().zip(saved) {
+            let case_study = case_studies
+                .create(NewCaseStudy {
+                    industry: "Retail".to_string(),
+                    duration_minutes: 30,
+                    learning_objectives: vec!["Market analysis".to_string()],
+                    ..NewCaseStudy::for_test(title, &format!("{} must decide whether to expand into a new region. ", title).repeat(6))
+                })
+                .await
+                .unwrap();
+
+            let rows: Vec<NewCaseStudyQuestion> = (1..=count)
+                .map(|n| NewCaseStudyQuestion {
+                    question_text: format!("{} question {}", title, n),
+                    question_type: StoredQuestionType::Essay,
+                    options: None,
+                    correct_answer: None,
+                    sample_answer: None,
+                    rubric: None,
+                    points: 5,
+                })
+                .collect();
+            let ids = if rows.is_empty() {
+                Vec::new()
+            } else {
+                questions.create_for_case_study(&case_study.id, &rows).await.unwrap()
+            };
+
+            collections.add_case_study(collection_id, &case_study.id).await.unwrap();
+            case_ids.push(case_study.id);
+            question_ids.push(ids);
+        }
+
+        (AssessmentWorkflow::new(db), collection_id, case_ids, question_ids)
+    }
+
+    fn collection_params() -> CollectionAssessmentParams {
+        CollectionAssessmentParams {
+            title: "Strategy module exam".to_string(),
+            description: None,
+            configuration: AssessmentConfiguration::default(),
+            estimated_duration: 90,
+            difficulty_level: "intermediate".to_string(),
+            learning_objectives: vec!["Market analysis".to_string()],
+            instructions: None,
+            created_by: None,
+            generation: crate::ai::question_generator::QuestionGenerationParams {
+                question_types: vec![crate::ai::question_generator::QuestionType::Essay],
+                include_rubric: false,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collection_assessment_follows_collection_order() {
+        let (workflow, collection_id, case_ids, saved) = seeded_collection([3, 2, 2]).await;
+
+        // Move the last case to the front; the assessment follows order_index, not creation order
+        let reordered = vec![case_ids[2].clone(), case_ids[0].clone(), case_ids[1].clone()];
+        CollectionRepository::new(workflow.db.pool().clone())
+            .reorder(collection_id, &reordered)
+            .await
+            .unwrap();
+
+        let assembled = workflow
+            .create_assessment_from_collection(collection_id, 2, collection_params(), None)
+            .await
+            .unwrap();
+
+        assert!(assembled.failed_cases.is_empty());
+        assert_eq!(
+            assembled.contributions.iter().map(|c| c.case_study_id.clone()).collect::<Vec<_>>(),
+            reordered
+        );
+        let expected: Vec<i64> = [&saved[2], &saved[0], &saved[1]]
+            .iter()
+            .flat_map(|ids| ids.iter().take(2).copied())
+            .collect();
+        assert!(matches!(
+            &assembled.workflow.configuration.question_source,
+            QuestionSource::Collection { question_ids, .. } if question_ids == &expected
+        ));
+        assert_eq!(assembled.workflow.workflow_type, AssessmentWorkflowType::Portfolio);
+
+        let model = workflow.publish_workflow(&assembled.workflow.id).await.unwrap().unwrap();
+        let session = workflow.start_session(&model.id, "student-1").await.unwrap();
+        assert_eq!(
+            session.session_data.question_sequence,
+            expected.iter().map(|id| id.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collection_assessment_generates_missing_questions_and_reports_failures() {
+        let (workflow, collection_id, case_ids, saved) = seeded_collection([1, 0, 2]).await;
+
+        // Without an AI provider the case with no saved questions can't contribute
+        let assembled = workflow
+            .create_assessment_from_collection(collection_id, 1, collection_params(), None)
+            .await
+            .unwrap();
+        assert_eq!(assembled.contributions.len(), 2);
+        assert_eq!(assembled.failed_cases.len(), 1);
+        assert_eq!(assembled.failed_cases[0].case_study_id, case_ids[1]);
+
+        // With one, the shortfall is generated and saved for that case
+        let ai_manager = AIManager::detached(workflow.db.pool().clone());
+        let provider = crate::ai::providers::mock::MockProvider::new(crate::ai::ProviderType::Ollama)
+            .respond_with("1. Should Nordic Outfitters enter the new region?");
+        ai_manager.set_active_provider(Box::new(provider)).await;
+
+        let assembled = workflow
+            .create_assessment_from_collection(collection_id, 1, collection_params(), Some(&ai_manager))
+            .await
+            .unwrap();
+        assert!(assembled.failed_cases.is_empty());
+        assert_eq!(
+            assembled.contributions.iter().map(|c| (c.case_study_id.clone(), c.generated_count)).collect::<Vec<_>>(),
+            vec![(case_ids[0].clone(), 0), (case_ids[1].clone(), 1), (case_ids[2].clone(), 0)]
+        );
+        let generated = AssessmentQuestionRepository::new(workflow.db.pool().clone())
+            .list_ids_for_case_study(&case_ids[1])
+            .await
+            .unwrap();
+        assert_eq!(assembled.contributions[1].question_ids, generated);
+        assert_eq!(assembled.contributions[0].question_ids, vec![saved[0][0]]);
+        assert_eq!(assembled.contributions[2].question_ids, vec![saved[2][0]]);
+    }
+}
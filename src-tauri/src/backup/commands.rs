@@ -1,8 +1,8 @@
 // Tauri commands for backup management
 
 use super::{
-    BackupManager, BackupConfig, BackupInfo, BackupStats,
-    scheduler::{BackupScheduler, BackupSchedule, SchedulerStats, BackupEvent, SchedulerStatus}
+    BackupManager, BackupConfig, BackupInfo, BackupOptions, BackupStats, RestorePreview,
+    scheduler::{BackupScheduler, BackupSchedule, SchedulerStats, BackupEvent, SchedulerStatusReport}
 };
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -11,8 +11,8 @@ use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
 // Type aliases for state management
-type BackupManagerState = Arc<Mutex<Arc<BackupManager>>>;
-type BackupSchedulerState = Arc<Mutex<BackupScheduler>>;
+pub type BackupManagerState = Arc<Mutex<Arc<BackupManager>>>;
+pub type BackupSchedulerState = Arc<Mutex<BackupScheduler>>;
 
 /// Initialize backup with configuration
 #[tauri::command]
@@ -20,6 +20,7 @@ pub async fn initialize_backup(
     config: BackupConfig,
     backup_manager_state: State<'_, BackupManagerState>,
 ) -> std::result::Result<bool, String> {
+    config.validate_filename_template().map_err(|e| e.to_string())?;
     let manager_arc = backup_manager_state.lock().await;
     // Note: BackupManager doesn't have set_config as mutable, 
     // this would need to be redesigned for proper state management
@@ -36,14 +37,29 @@ pub async fn get_backup_config(
     Ok(manager_arc.get_config().clone())
 }
 
-/// Create a manual backup
+/// Create a manual backup, emitting `backup://progress` events while tables are exported.
+/// `destination` is an absolute directory outside the app data dir; `passphrase` encrypts
+/// this backup with its own key instead of the app's master key; `tables` limits the backup
+/// to an allowlist of tables.
 #[tauri::command]
 pub async fn create_backup(
     description: Option<String>,
+    destination: Option<String>,
+    passphrase: Option<String>,
+    tables: Option<Vec<String>>,
+    app_handle: AppHandle,
     backup_manager_state: State<'_, BackupManagerState>,
 ) -> std::result::Result<BackupInfo, String> {
+    let options = BackupOptions {
+        destination: destination.map(PathBuf::from),
+        passphrase,
+        tables,
+    };
     let manager_arc = backup_manager_state.lock().await;
-    manager_arc.create_backup(description).await.map_err(|e| e.to_string())
+    manager_arc
+        .create_backup_with_options(description, options, &app_handle)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// List all available backups
@@ -55,21 +71,56 @@ pub async fn list_backups(
     manager.list_backups().await.map_err(|e| e.to_string())
 }
 
-/// Restore from a backup
+/// Restore from a backup, emitting `backup://progress` events while tables are restored.
+/// Passphrase-protected backups fail with "Backup is protected by a passphrase" until one is given.
 #[tauri::command]
 pub async fn restore_backup(
     backup_path: String,
     force: bool,
+    passphrase: Option<String>,
+    app_handle: AppHandle,
+    backup_manager_state: State<'_, BackupManagerState>,
+) -> std::result::Result<bool, String> {
+    let manager = backup_manager_state.lock().await;
+    manager
+        .restore_backup_with_passphrase(&PathBuf::from(backup_path), force, passphrase.as_deref(), &app_handle)
+        .await
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore only the given tables from a backup, leaving all other tables untouched
+#[tauri::command]
+pub async fn restore_backup_tables(
+    backup_path: String,
+    tables: Vec<String>,
+    force: bool,
+    passphrase: Option<String>,
+    app_handle: AppHandle,
     backup_manager_state: State<'_, BackupManagerState>,
 ) -> std::result::Result<bool, String> {
     let manager = backup_manager_state.lock().await;
     manager
-        .restore_backup(&PathBuf::from(backup_path), force)
+        .restore_backup_tables(&PathBuf::from(backup_path), &tables, force, passphrase.as_deref(), &app_handle)
         .await
         .map(|_| true)
         .map_err(|e| e.to_string())
 }
 
+/// Preview what restoring a backup would change, without modifying any data
+#[tauri::command]
+pub async fn preview_restore(
+    backup_path: String,
+    passphrase: Option<String>,
+    backup_manager_state: State<'_, BackupManagerState>,
+) -> std::result::Result<RestorePreview, String> {
+    let manager = backup_manager_state.lock().await;
+    manager
+        .preview_restore(&PathBuf::from(backup_path), passphrase.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Delete a backup file
 #[tauri::command]
 pub async fn delete_backup(
@@ -156,13 +207,13 @@ pub async fn update_backup_schedule(
         .map_err(|e| e.to_string())
 }
 
-/// Get current scheduler status
+/// Get current scheduler status and when the next backup is scheduled
 #[tauri::command]
 pub async fn get_scheduler_status(
     backup_scheduler_state: State<'_, BackupSchedulerState>,
-) -> std::result::Result<SchedulerStatus, String> {
+) -> std::result::Result<SchedulerStatusReport, String> {
     let scheduler = backup_scheduler_state.lock().await;
-    Ok(scheduler.get_status().await)
+    Ok(scheduler.get_status_report().await)
 }
 
 /// Get current backup schedule
@@ -335,10 +386,11 @@ pub async fn get_backup_system_info(
 
 // Helper function to get app data directory
 fn get_app_data_dir(app_handle: &AppHandle) -> std::result::Result<PathBuf, String> {
-    // Temporary workaround - in production this would use app_handle.path()
-    let home_dir = std::env::var("HOME").map_err(|_| "Could not get home directory".to_string())?;
-    let app_data_dir = PathBuf::from(home_dir).join(".local/share/case-crafter");
-    Ok(app_data_dir)
+    use tauri::Manager;
+
+    app_handle.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
 }
 
 /// Setup backup state for Tauri app
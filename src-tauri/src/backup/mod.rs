@@ -1,7 +1,9 @@
 // Automated backup system for local database
 
+use crate::clock::{Clock, SystemClock};
 use crate::database::DatabaseManager;
-use crate::encryption::{EncryptionManager, EncryptedData};
+use crate::encryption::{self, Argon2Params, EncryptionManager, EncryptedData};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use chrono::{DateTime, Utc, Duration};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -10,11 +12,20 @@ use std::sync::Arc;
 use tauri::AppHandle;
 use thiserror::Error;
 use tokio::fs;
-use sqlx::{Row, Column};
+use tokio::io::AsyncWriteExt;
+use sqlx::{Row, Column, Connection, SqlitePool, Sqlite, TypeInfo, ValueRef};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
 
 pub mod commands;
+pub mod progress;
+pub mod retention;
 pub mod scheduler;
 
+use progress::{BackupStage, NoopProgress, ProgressSink, ProgressTracker};
+pub use retention::TieredRetention;
+
 #[derive(Error, Debug)]
 pub enum BackupError {
     #[error("Database error: {0}")]
@@ -31,10 +42,81 @@ pub enum BackupError {
     NotFound(String),
     #[error("Configuration error: {0}")]
     Configuration(String),
+    #[error("Backup is protected by a passphrase")]
+    PassphraseRequired,
+    #[error("Invalid backup passphrase")]
+    InvalidPassphrase,
+    #[error("Restore would break table dependencies: {0}")]
+    DependencyViolation(String),
 }
 
 pub type Result<T> = std::result::Result<T, BackupError>;
 
+/// Schema version written into every backup file
+pub const BACKUP_SCHEMA_VERSION: &str = "1.0";
+
+/// Checksum algorithm used for new backups
+pub const CHECKSUM_ALGORITHM: &str = "sha256";
+
+/// Tables included in backups, ordered so that referenced tables come before
+/// the tables that reference them. Restores clear them in reverse order.
+pub const BACKUP_TABLES: &[&str] = &[
+    "users",
+    "domains",
+    "configuration_templates",
+    "case_studies",
+    "assessment_questions",
+    "generation_history",
+    "user_progress",
+    "app_settings",
+    "attachments",
+    "collections",
+    "collection_case_studies",
+];
+
+/// Kept beside rather than inside the backup directory so it is never listed as a backup
+const SCHEDULER_STATE_FILE: &str = "backup_scheduler.json";
+
+/// Tables holding per-user data, skipped when `include_user_data` is off
+const USER_DATA_TABLES: &[&str] = &["users", "user_progress"];
+
+/// Payload key holding the embedded attachment files
+const ATTACHMENT_FILES_KEY: &str = "attachment_files";
+
+/// Directory under the app data dir that restored attachments are moved into when their
+/// recorded path cannot be reused
+const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Rows fetched per query while exporting a table
+const EXPORT_BATCH_ROWS: i64 = 500;
+
+/// Plaintext bytes collected before an encrypted backup writes out a chunk
+const ENCRYPTED_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Alias for the rowid selected alongside each row so exports can page by it
+const EXPORT_ROWID_COLUMN: &str = "backup_export_rowid";
+
+/// Columns checked, in order of preference, to decide whether a row changed since a backup
+const CHANGE_TRACKING_COLUMNS: &[&str] = &["updated_at", "created_at", "added_at"];
+
+/// Backup filename used when the configuration doesn't set one, before the `.json` extension
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "case_crafter_backup_{timestamp}_{id}";
+
+/// Placeholders a backup filename template may use
+const FILENAME_PLACEHOLDERS: &[&str] = &["timestamp", "id", "kind", "app_version"];
+
+/// Characters that aren't allowed in a file name on at least one supported platform
+const UNSAFE_FILENAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Whether a backup contains every row or only rows changed since its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupKind {
+    #[default]
+    Full,
+    Incremental,
+}
+
 /// Backup configuration settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupConfig {
@@ -47,6 +129,22 @@ pub struct BackupConfig {
     pub include_attachments: bool,
     pub include_user_data: bool,
     pub exclude_temporary_data: bool,
+    #[serde(default)]
+    pub kind: BackupKind,
+    /// Backups older than this many days are pruned even when within `max_backups`
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// Daily/weekly/monthly thinning applied on top of the count and age limits
+    #[serde(default)]
+    pub tiered_retention: Option<TieredRetention>,
+    /// Backup filename without extension. `{timestamp}`, `{id}`, `{kind}` and `{app_version}`
+    /// are replaced; a counter is appended when the name is already taken.
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+}
+
+fn default_filename_template() -> String {
+    DEFAULT_FILENAME_TEMPLATE.to_string()
 }
 
 impl Default for BackupConfig {
@@ -61,10 +159,63 @@ impl Default for BackupConfig {
             include_attachments: true,
             include_user_data: true,
             exclude_temporary_data: true,
+            kind: BackupKind::Full,
+            max_age_days: None,
+            tiered_retention: None,
+            filename_template: default_filename_template(),
         }
     }
 }
 
+impl BackupConfig {
+    /// Check that the filename template only uses known placeholders and renders to a
+    /// filesystem-safe name
+    pub fn validate_filename_template(&self) -> Result<()> {
+        render_backup_filename(&self.filename_template, Utc::now(), &uuid::Uuid::nil().to_string(), BackupKind::Incremental)
+            .map(|_| ())
+    }
+}
+
+/// Per-backup options for manual backups
+#[derive(Debug, Clone, Default)]
+pub struct BackupOptions {
+    /// Absolute directory to write the backup to instead of the configured backup directory
+    pub destination: Option<PathBuf>,
+    /// Passphrase deriving a key used for this backup only, instead of the app's master key
+    pub passphrase: Option<String>,
+    /// Back up only these tables instead of every table the configuration selects
+    pub tables: Option<Vec<String>>,
+}
+
+/// Key derivation inputs for a passphrase-protected backup; the key itself is never stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupKeyDerivation {
+    /// Base64-encoded Argon2 salt
+    pub salt: String,
+    pub params: Argon2Params,
+}
+
+impl BackupKeyDerivation {
+    fn generate() -> Self {
+        let mut salt = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            salt: BASE64.encode(salt),
+            params: Argon2Params::default(),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; 32]>> {
+        let salt: [u8; 32] = BASE64
+            .decode(&self.salt)
+            .ok()
+            .and_then(|salt| salt.try_into().ok())
+            .ok_or_else(|| BackupError::InvalidFormat("Invalid backup key salt".to_string()))?;
+        encryption::derive_key(passphrase, &salt, &self.params)
+            .map_err(|e| BackupError::Encryption(e.to_string()))
+    }
+}
+
 /// Metadata for a backup file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupMetadata {
@@ -76,9 +227,63 @@ pub struct BackupMetadata {
     pub compressed: bool,
     pub encrypted: bool,
     pub checksum: String,
+    /// Hash function used to compute `checksum`; backups written before this
+    /// field existed used MD5
+    #[serde(default = "legacy_checksum_algorithm")]
+    pub checksum_algorithm: String,
     pub tables_included: Vec<String>,
     pub record_counts: HashMap<String, u64>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub kind: BackupKind,
+    /// For incremental backups, the id of the backup this one builds on
+    #[serde(default)]
+    pub parent_backup_id: Option<String>,
+    /// Present when the backup is encrypted with its own passphrase rather than the master key
+    #[serde(default)]
+    pub key_derivation: Option<BackupKeyDerivation>,
+    /// Whether the backup was limited to an explicit table allowlist
+    #[serde(default)]
+    pub partial: bool,
+    /// Whether the encrypted payload is a sequence of newline-separated chunks rather than a
+    /// single encrypted document
+    #[serde(default)]
+    pub chunked: bool,
+}
+
+fn legacy_checksum_algorithm() -> String {
+    "md5".to_string()
+}
+
+/// What restoring a backup would do to one table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableRestorePreview {
+    pub table: String,
+    /// Rows the table will hold once the restore completes
+    pub rows_to_restore: u64,
+    pub current_rows: u64,
+    /// Backed-up rows the database doesn't have
+    pub added: u64,
+    /// Rows in the database that the restore will drop
+    pub removed: u64,
+    /// Rows present in both whose backed-up values differ from the stored ones
+    pub changed: u64,
+}
+
+/// Summary of a restore, computed without touching the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreview {
+    pub backup_id: String,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+    pub database_version: String,
+    pub schema_version: String,
+    /// False when restoring would need `force` because the backup's schema version differs
+    pub schema_compatible: bool,
+    pub kind: BackupKind,
+    /// Number of backup files read: the full backup plus any incrementals on top of it
+    pub chain_length: usize,
+    pub tables: Vec<TableRestorePreview>,
 }
 
 /// Information about a backup file
@@ -96,6 +301,7 @@ pub struct BackupManager {
     app_handle: AppHandle,
     database_manager: Arc<DatabaseManager>,
     encryption_manager: Option<Arc<EncryptionManager>>,
+    clock: Arc<dyn Clock>,
 }
 
 impl BackupManager {
@@ -110,9 +316,15 @@ impl BackupManager {
             app_handle,
             database_manager,
             encryption_manager,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Read backup timestamps and retention ages from `clock` instead of the system time
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
     /// Update backup configuration
     pub fn set_config(&mut self, config: BackupConfig) {
         self.config = config;
@@ -125,29 +337,107 @@ impl BackupManager {
 
     /// Create a manual backup
     pub async fn create_backup(&self, description: Option<String>) -> Result<BackupInfo> {
+        self.create_backup_with_progress(description, &NoopProgress).await
+    }
+
+    /// Create a manual backup, reporting per-table export progress to `progress`
+    pub async fn create_backup_with_progress(
+        &self,
+        description: Option<String>,
+        progress: &dyn ProgressSink,
+    ) -> Result<BackupInfo> {
+        self.create_backup_with_options(description, BackupOptions::default(), progress).await
+    }
+
+    /// Create a manual backup, optionally written to an external directory and protected by
+    /// its own passphrase
+    #[tracing::instrument(name = "backup.create", skip_all)]
+    pub async fn create_backup_with_options(
+        &self,
+        description: Option<String>,
+        options: BackupOptions,
+        progress: &dyn ProgressSink,
+    ) -> Result<BackupInfo> {
         let backup_id = uuid::Uuid::new_v4().to_string();
-        let timestamp = Utc::now();
+        let timestamp = self.clock.now();
+
+        if let Some(unknown) = options.tables.iter().flatten().find(|table| !BACKUP_TABLES.contains(&table.as_str())) {
+            return Err(BackupError::Configuration(format!("Table {} cannot be backed up", unknown)));
+        }
         
         // Create backup directory if it doesn't exist
-        let backup_dir = self.get_backup_directory()?;
-        fs::create_dir_all(&backup_dir).await?;
+        let backup_dir = match &options.destination {
+            Some(destination) => prepare_external_directory(destination).await?,
+            None => {
+                let backup_dir = self.get_backup_directory()?;
+                fs::create_dir_all(&backup_dir).await?;
+                backup_dir
+            },
+        };
 
-        // Generate backup filename
-        let filename = format!(
-            "case_crafter_backup_{}_{}.json",
-            timestamp.format("%Y%m%d_%H%M%S"),
-            &backup_id[..8]
-        );
-        let backup_path = backup_dir.join(&filename);
+        // Incremental backups build on the most recent complete backup; without one we fall back to a
+        // full backup. External and partial backups are always full so they restore on their own.
+        let partial = options.tables.is_some();
+        let parent = match self.config.kind {
+            BackupKind::Incremental if options.destination.is_none() && !partial => self
+                .list_backups()
+                .await?
+                .into_iter()
+                .find(|backup| !backup.metadata.partial),
+            _ => None,
+        };
+        let kind = if parent.is_some() { BackupKind::Incremental } else { BackupKind::Full };
+        let since = parent.as_ref().map(|p| p.metadata.created_at);
+
+        let filename = render_backup_filename(&self.config.filename_template, timestamp, &backup_id, kind)?;
+        let backup_path = unique_backup_path(&backup_dir, &filename);
+
+        let key_derivation = options.passphrase.as_ref().map(|_| BackupKeyDerivation::generate());
+        let cipher = match (&key_derivation, &options.passphrase) {
+            (Some(derivation), Some(passphrase)) => Some(PayloadCipher::Passphrase(derivation.derive_key(passphrase)?)),
+            _ if self.config.encrypt => self.encryption_manager.as_deref().map(PayloadCipher::Master),
+            _ => None,
+        };
+        let encrypted = cipher.is_some();
+
+        // Stream the payload into a temporary file so a failed backup never looks like a real one
+        let partial_path = backup_path.with_extension("json.partial");
+        let mut writer = PayloadWriter::create(&partial_path, cipher).await?;
+        let tables = self.backup_table_selection(options.tables.as_deref());
+        let attachments_root = if tables.contains(&"attachments") {
+            Some(self.get_app_data_dir()?)
+        } else {
+            None
+        };
+        let payload = write_backup_payload(
+            self.database_manager.pool(),
+            &tables,
+            timestamp,
+            since,
+            attachments_root.as_deref(),
+            &mut writer,
+            progress,
+        )
+        .await;
+        let record_counts = match payload {
+            Ok(record_counts) => record_counts,
+            Err(e) => {
+                drop(writer);
+                let _ = fs::remove_file(&partial_path).await;
+                return Err(e);
+            }
+        };
+        let (checksum, size_bytes) = writer.finish().await?;
+        fs::rename(&partial_path, &backup_path).await?;
 
-        // Create backup data
-        let backup_data = self.create_backup_data().await?;
-        
-        // Calculate checksum
-        let checksum = self.calculate_checksum(&backup_data)?;
-        
         // Get table information
-        let (tables_included, record_counts) = self.get_table_info().await?;
+        let tables_included = BACKUP_TABLES
+            .iter()
+            .filter(|table| record_counts.contains_key(**table))
+            .map(|table| table.to_string())
+            .collect();
+
+        let rows_written: u64 = record_counts.values().sum();
 
         // Create metadata
         let metadata = BackupMetadata {
@@ -155,26 +445,31 @@ impl BackupManager {
             created_at: timestamp,
             database_version: "1.0".to_string(),
             app_version: env!("CARGO_PKG_VERSION").to_string(),
-            size_bytes: backup_data.len() as u64,
+            size_bytes,
             compressed: self.config.compress,
-            encrypted: self.config.encrypt,
+            encrypted,
             checksum,
+            checksum_algorithm: CHECKSUM_ALGORITHM.to_string(),
             tables_included,
             record_counts,
             description,
+            kind,
+            parent_backup_id: parent.map(|p| p.metadata.id),
+            key_derivation,
+            partial,
+            chunked: encrypted,
         };
 
-        // Process backup data (compression and encryption)
-        let final_data = self.process_backup_data(backup_data, &metadata).await?;
-        
-        // Write backup file
-        fs::write(&backup_path, &final_data).await?;
+        // Write the metadata alongside the backup file
+        fs::write(metadata_path(&backup_path), serde_json::to_vec_pretty(&metadata)?).await?;
 
         let file_size = fs::metadata(&backup_path).await?.len();
 
         // Cleanup old backups
         self.cleanup_old_backups().await?;
 
+        ProgressTracker::new(progress, BackupStage::Exporting, rows_written).finish();
+
         Ok(BackupInfo {
             metadata,
             file_path: backup_path,
@@ -211,6 +506,57 @@ impl BackupManager {
 
     /// Restore from a backup
     pub async fn restore_backup(&self, backup_path: &Path, force: bool) -> Result<()> {
+        self.restore_backup_with_progress(backup_path, force, &NoopProgress).await
+    }
+
+    /// Restore from a backup, reporting per-table restore progress to `progress`
+    pub async fn restore_backup_with_progress(
+        &self,
+        backup_path: &Path,
+        force: bool,
+        progress: &dyn ProgressSink,
+    ) -> Result<()> {
+        self.restore_backup_with_passphrase(backup_path, force, None, progress).await
+    }
+
+    /// Restore from a backup that may be protected by its own passphrase. Fails with
+    /// `PassphraseRequired` when the backup needs one and none was given.
+    pub async fn restore_backup_with_passphrase(
+        &self,
+        backup_path: &Path,
+        force: bool,
+        passphrase: Option<&str>,
+        progress: &dyn ProgressSink,
+    ) -> Result<()> {
+        self.restore_from_backup(backup_path, None, force, passphrase, progress).await
+    }
+
+    /// Restore only `tables` from a backup, leaving every other table untouched. Each table must
+    /// be present in the backup, and the restore is refused if it would leave rows in other
+    /// tables pointing at rows that no longer exist.
+    pub async fn restore_backup_tables(
+        &self,
+        backup_path: &Path,
+        tables: &[String],
+        force: bool,
+        passphrase: Option<&str>,
+        progress: &dyn ProgressSink,
+    ) -> Result<()> {
+        if tables.is_empty() {
+            return Err(BackupError::Configuration("No tables selected for restore".to_string()));
+        }
+        self.restore_from_backup(backup_path, Some(tables), force, passphrase, progress).await
+    }
+
+    #[tracing::instrument(name = "backup.restore", skip_all, fields(backup = %backup_path.display()))]
+    async fn restore_from_backup(
+        &self,
+        backup_path: &Path,
+        tables: Option<&[String]>,
+        force: bool,
+        passphrase: Option<&str>,
+        progress: &dyn ProgressSink,
+    ) -> Result<()> {
         if !backup_path.exists() {
             return Err(BackupError::NotFound(backup_path.to_string_lossy().to_string()));
         }
@@ -221,27 +567,81 @@ impl BackupManager {
             return Err(BackupError::InvalidFormat("Backup validation failed".to_string()));
         }
 
-        // Read backup file
-        let encrypted_data = fs::read(backup_path).await?;
-        
-        // Process backup data (decryption and decompression)
-        let backup_data = self.restore_backup_data(encrypted_data, &backup_info.metadata).await?;
-        
-        // Parse backup data
-        let backup_content: serde_json::Value = serde_json::from_slice(&backup_data)?;
+        // Incremental backups are applied on top of the full backup they descend from
+        let chain = self.resolve_backup_chain(backup_info).await?;
+        let contents = self.read_chain_contents(&chain, passphrase).await?;
+
+        // Refuse to load data written for a different schema unless forced
+        if let Some(schema_version) = contents.iter().map(schema_version_of).find(|v| *v != BACKUP_SCHEMA_VERSION) {
+            if !force {
+                return Err(BackupError::InvalidFormat(format!(
+                    "Backup schema version {} does not match current version {}",
+                    schema_version, BACKUP_SCHEMA_VERSION
+                )));
+            }
+        }
+
         
+        if let (Some(tables), Some(base)) = (tables, contents.first()) {
+            if let Some(missing) = tables.iter().find(|table| base.get(table.as_str()).is_none()) {
+                return Err(BackupError::NotFound(format!("Table {} in backup {}", missing, backup_path.display())));
+            }
+        }
+
         // Restore database
-        self.restore_database_from_backup(&backup_content).await?;
+        self.restore_database_from_backup(&contents, tables, progress).await?;
 
-        println!("Database restored successfully from backup: {}", backup_path.display());
+        // Attachment files follow the attachments table
+        let restore_files = match tables {
+            Some(tables) => tables.iter().any(|table| table == "attachments"),
+            None => true,
+        };
+        if restore_files {
+            let restored = restore_attachment_files(self.database_manager.pool(), &self.get_app_data_dir()?, &contents).await?;
+            tracing::info!("Restored {} attachment files", restored);
+        }
+
+        tracing::info!("Database restored successfully from backup: {}", backup_path.display());
         Ok(())
     }
 
+    /// Report what restoring a backup would change, without modifying the database. Like a
+    /// restore, a passphrase-protected backup needs its passphrase.
+    pub async fn preview_restore(&self, backup_path: &Path, passphrase: Option<&str>) -> Result<RestorePreview> {
+        if !backup_path.exists() {
+            return Err(BackupError::NotFound(backup_path.to_string_lossy().to_string()));
+        }
+
+        let backup_info = self.load_backup_info(backup_path).await?;
+        let metadata = backup_info.metadata.clone();
+        let chain = self.resolve_backup_chain(backup_info).await?;
+        let contents = self.read_chain_contents(&chain, passphrase).await?;
+        let tables = preview_tables(self.database_manager.pool(), &contents).await?;
+
+        Ok(RestorePreview {
+            backup_id: metadata.id,
+            created_at: metadata.created_at,
+            app_version: metadata.app_version,
+            database_version: metadata.database_version,
+            schema_version: contents.last().map(schema_version_of).unwrap_or("unknown").to_string(),
+            schema_compatible: contents.iter().all(|content| schema_version_of(content) == BACKUP_SCHEMA_VERSION),
+            kind: metadata.kind,
+            chain_length: chain.len(),
+            tables,
+        })
+    }
+
     /// Delete a backup file
+    #[tracing::instrument(name = "backup.delete", skip_all, fields(backup = %backup_path.display()))]
     pub async fn delete_backup(&self, backup_path: &Path) -> Result<()> {
         if backup_path.exists() {
             fs::remove_file(backup_path).await?;
-            println!("Backup deleted: {}", backup_path.display());
+            tracing::info!("Backup deleted: {}", backup_path.display());
+        }
+
+        let meta_path = metadata_path(backup_path);
+        if meta_path.exists() {
+            fs::remove_file(meta_path).await?;
         }
         Ok(())
     }
@@ -252,11 +652,10 @@ impl BackupManager {
         
         // Read and process backup data
         let encrypted_data = fs::read(backup_path).await?;
-        let backup_data = self.restore_backup_data(encrypted_data, &backup_info.metadata).await?;
+        let backup_data = self.restore_backup_data(encrypted_data, &backup_info.metadata, None).await?;
         
-        // Verify checksum
-        let calculated_checksum = self.calculate_checksum(&backup_data)?;
-        let checksum_valid = calculated_checksum == backup_info.metadata.checksum;
+        // Verify checksum using the algorithm recorded when the backup was made
+        let checksum_valid = verify_checksum(&backup_info.metadata, &backup_data)?;
         
         // Verify JSON structure
         let json_valid = serde_json::from_slice::<serde_json::Value>(&backup_data).is_ok();
@@ -298,105 +697,86 @@ impl BackupManager {
 
     // Private helper methods
 
-    async fn create_backup_data(&self) -> Result<Vec<u8>> {
-        // Create a comprehensive backup of the database
-        let mut backup_data = HashMap::new();
-
-        // Export all tables
-        if self.config.include_user_data {
-            backup_data.insert("users", self.export_table_data("users").await?);
-        }
-        
-        backup_data.insert("domains", self.export_table_data("domains").await?);
-        backup_data.insert("configuration_templates", self.export_table_data("configuration_templates").await?);
-        backup_data.insert("case_studies", self.export_table_data("case_studies").await?);
-        backup_data.insert("assessment_questions", self.export_table_data("assessment_questions").await?);
-        backup_data.insert("generation_history", self.export_table_data("generation_history").await?);
-        
-        if self.config.include_user_data {
-            backup_data.insert("user_progress", self.export_table_data("user_progress").await?);
-        }
-        
-        backup_data.insert("app_settings", self.export_table_data("app_settings").await?);
-        backup_data.insert("attachments", self.export_table_data("attachments").await?);
-        backup_data.insert("collections", self.export_table_data("collections").await?);
-        backup_data.insert("collection_case_studies", self.export_table_data("collection_case_studies").await?);
-
-        // Include schema information
-        backup_data.insert("schema_version", serde_json::json!("1.0"));
-        backup_data.insert("backup_timestamp", serde_json::json!(Utc::now()));
-
-        Ok(serde_json::to_vec(&backup_data)?)
+    /// Tables to back up: an explicit allowlist, or every table the configuration selects.
+    /// Attachment files are backed up whenever the attachments table is.
+    fn backup_table_selection(&self, allowlist: Option<&[String]>) -> Vec<&'static str> {
+        BACKUP_TABLES
+            .iter()
+            .copied()
+            .filter(|table| match allowlist {
+                Some(allowlist) => allowlist.iter().any(|allowed| allowed == table),
+                None => {
+                    (self.config.include_user_data || !USER_DATA_TABLES.contains(table))
+                        && (self.config.include_attachments || *table != "attachments")
+                },
+            })
+            .collect()
     }
 
-    async fn export_table_data(&self, table_name: &str) -> Result<serde_json::Value> {
-        let query = format!("SELECT * FROM {}", table_name);
-        let rows = sqlx::query(&query)
-            .fetch_all(self.database_manager.pool())
-            .await?;
+    /// Read, decrypt and parse the contents of a backup file
+    async fn read_backup_content(&self, backup: &BackupInfo, passphrase: Option<&str>) -> Result<serde_json::Value> {
+        let encrypted_data = fs::read(&backup.file_path).await?;
+        let backup_data = self.restore_backup_data(encrypted_data, &backup.metadata, passphrase).await?;
+        Ok(serde_json::from_slice(&backup_data)?)
+    }
 
-        let mut table_data = Vec::new();
-        for row in rows {
-            let mut row_data = HashMap::new();
-            for (i, column) in row.columns().iter().enumerate() {
-                let column_name = column.name();
-                let value: Option<String> = row.try_get(i).unwrap_or(None);
-                row_data.insert(column_name.to_string(), serde_json::json!(value));
-            }
-            table_data.push(row_data);
+    /// Read and decode every backup in a chain, oldest first
+    async fn read_chain_contents(&self, chain: &[BackupInfo], passphrase: Option<&str>) -> Result<Vec<serde_json::Value>> {
+        let mut contents = Vec::with_capacity(chain.len());
+        for backup in chain {
+            contents.push(self.read_backup_content(backup, passphrase).await?);
         }
-
-        Ok(serde_json::json!(table_data))
+        Ok(contents)
     }
 
-    async fn get_table_info(&self) -> Result<(Vec<String>, HashMap<String, u64>)> {
-        let tables = vec![
-            "users", "domains", "configuration_templates", "case_studies",
-            "assessment_questions", "generation_history", "user_progress",
-            "app_settings", "attachments", "collections", "collection_case_studies"
-        ];
-
-        let mut record_counts = HashMap::new();
-        
-        for table in &tables {
-            let count: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table))
-                .fetch_one(self.database_manager.pool())
-                .await?;
-            record_counts.insert(table.to_string(), count.0 as u64);
+    /// Walk from a backup back to its nearest full ancestor, returning the chain oldest first
+    async fn resolve_backup_chain(&self, backup: BackupInfo) -> Result<Vec<BackupInfo>> {
+        if backup.metadata.kind == BackupKind::Full {
+            return Ok(vec![backup]);
         }
 
-        Ok((tables.iter().map(|s| s.to_string()).collect(), record_counts))
-    }
+        let available = self.list_backups().await?;
+        let mut chain = vec![backup];
 
-    async fn process_backup_data(&self, mut data: Vec<u8>, metadata: &BackupMetadata) -> Result<Vec<u8>> {
-        // Compress if enabled
-        if self.config.compress {
-            data = self.compress_data(data)?;
-        }
+        while let Some(current) = chain.last().filter(|b| b.metadata.kind == BackupKind::Incremental) {
+            let parent_id = current.metadata.parent_backup_id.clone().ok_or_else(|| {
+                BackupError::InvalidFormat(format!("Incremental backup {} has no parent", current.metadata.id))
+            })?;
 
-        // Encrypt if enabled and encryption manager is available
-        if self.config.encrypt && metadata.encrypted {
-            if let Some(encryption_manager) = &self.encryption_manager {
-                let encrypted = encryption_manager.encrypt(&String::from_utf8_lossy(&data))
-                    .map_err(|e| BackupError::Encryption(e.to_string()))?;
-                data = serde_json::to_vec(&encrypted)?;
+            if chain.len() > available.len() {
+                return Err(BackupError::InvalidFormat(format!("Backup chain for {} is cyclic", parent_id)));
             }
+
+            let parent = available
+                .iter()
+                .find(|b| b.metadata.id == parent_id)
+                .cloned()
+                .ok_or_else(|| BackupError::NotFound(format!("Parent backup {}", parent_id)))?;
+            chain.push(parent);
         }
 
-        Ok(data)
+        chain.reverse();
+        Ok(chain)
     }
 
-    async fn restore_backup_data(&self, mut data: Vec<u8>, metadata: &BackupMetadata) -> Result<Vec<u8>> {
+    async fn restore_backup_data(
+        &self,
+        mut data: Vec<u8>,
+        metadata: &BackupMetadata,
+        passphrase: Option<&str>,
+    ) -> Result<Vec<u8>> {
         // Decrypt if encrypted
         if metadata.encrypted {
-            if let Some(encryption_manager) = &self.encryption_manager {
-                let encrypted_data: EncryptedData = serde_json::from_slice(&data)?;
-                let decrypted = encryption_manager.decrypt(&encrypted_data)
-                    .map_err(|e| BackupError::Encryption(e.to_string()))?;
-                data = decrypted.into_bytes();
+            let cipher = self.restore_cipher(metadata, passphrase)?;
+            data = if metadata.chunked {
+                let mut decrypted = Vec::with_capacity(data.len());
+                for chunk in data.split(|byte| *byte == b'\n').filter(|chunk| !chunk.is_empty()) {
+                    decrypted.extend(cipher.decrypt(&serde_json::from_slice(chunk)?)?.into_bytes());
+                }
+                decrypted
             } else {
-                return Err(BackupError::Encryption("Backup is encrypted but no encryption manager available".to_string()));
-            }
+                cipher.decrypt(&serde_json::from_slice(&data)?)?.into_bytes()
+            };
         }
 
         // Decompress if compressed
@@ -407,49 +787,43 @@ impl BackupManager {
         Ok(data)
     }
 
-    async fn restore_database_from_backup(&self, backup_content: &serde_json::Value) -> Result<()> {
-        // This would need to be implemented with proper transaction handling
-        // For now, this is a placeholder that shows the structure
-        
-        println!("Starting database restoration...");
-        
-        // Begin transaction
-        let mut tx = self.database_manager.begin_transaction().await
-            .map_err(|e| BackupError::Database(e))?;
+    /// Cipher that decrypts a backup: its own passphrase if it has one, otherwise the master key
+    fn restore_cipher(&self, metadata: &BackupMetadata, passphrase: Option<&str>) -> Result<PayloadCipher<'_>> {
+        if let Some(derivation) = &metadata.key_derivation {
+            let passphrase = passphrase.ok_or(BackupError::PassphraseRequired)?;
+            return Ok(PayloadCipher::Passphrase(derivation.derive_key(passphrase)?));
+        }
 
-        // Clear existing data (with confirmation in a real implementation)
-        // This is dangerous and should have multiple confirmations
-        
-        // Restore each table
-        if let Some(tables) = backup_content.as_object() {
-            for (table_name, table_data) in tables {
-                if table_name == "schema_version" || table_name == "backup_timestamp" {
-                    continue;
-                }
-                
-                println!("Restoring table: {}", table_name);
-                // Implementation would restore table data here
-                // This requires careful handling of foreign keys and constraints
-            }
+        self.encryption_manager
+            .as_deref()
+            .map(PayloadCipher::Master)
+            .ok_or_else(|| BackupError::Encryption("Backup is encrypted but no encryption manager available".to_string()))
+    }
+
+    async fn restore_database_from_backup(
+        &self,
+        backup_contents: &[serde_json::Value],
+        tables: Option<&[String]>,
+        progress: &dyn ProgressSink,
+    ) -> Result<()> {
+        tracing::info!("Starting database restoration...");
+
+        let restored = restore_selected_tables(self.database_manager.pool(), backup_contents, tables, progress).await?;
+        for (table_name, count) in &restored {
+            tracing::info!("Restored {} rows into {}", count, table_name);
         }
 
-        // Commit transaction
-        tx.commit().await.map_err(|e| BackupError::Database(e))?;
-        
-        println!("Database restoration completed");
+        tracing::info!("Database restoration completed");
         Ok(())
     }
 
+    #[tracing::instrument(name = "backup.cleanup", skip_all)]
     async fn cleanup_old_backups(&self) -> Result<()> {
         let backups = self.list_backups().await?;
-        
-        if backups.len() > self.config.max_backups {
-            let to_delete = &backups[self.config.max_backups..];
-            
-            for backup in to_delete {
-                if let Err(e) = self.delete_backup(&backup.file_path).await {
-                    eprintln!("Failed to delete old backup {}: {}", backup.file_path.display(), e);
-                }
+
+        for backup in retention::backups_to_prune(&backups, &self.config, self.clock.now()) {
+            if let Err(e) = self.delete_backup(&backup.file_path).await {
+                tracing::warn!("Failed to delete old backup {}: {}", backup.file_path.display(), e);
             }
         }
 
@@ -459,8 +833,6 @@ impl BackupManager {
     async fn load_backup_info(&self, backup_path: &Path) -> Result<BackupInfo> {
         let file_size = fs::metadata(backup_path).await?.len();
         
-        // For simplicity, we'll extract metadata from the filename and file
-        // In a real implementation, metadata might be stored separately or embedded
         let filename = backup_path.file_stem()
             .and_then(|s| s.to_str())
             .ok_or_else(|| BackupError::InvalidFormat("Invalid filename".to_string()))?;
@@ -468,19 +840,37 @@ impl BackupManager {
         // Basic validation - just check if file exists and is readable
         let is_valid = backup_path.exists() && backup_path.is_file();
 
-        // Create basic metadata (in a real implementation, this would be stored properly)
+        // Prefer the metadata written alongside the backup
+        let meta_path = metadata_path(backup_path);
+        if meta_path.exists() {
+            let metadata: BackupMetadata = serde_json::from_slice(&fs::read(&meta_path).await?)?;
+            return Ok(BackupInfo {
+                metadata,
+                file_path: backup_path.to_path_buf(),
+                file_size,
+                is_valid,
+            });
+        }
+
+        // Older backups have no metadata file, so fall back to what the current config implies
         let metadata = BackupMetadata {
-            id: uuid::Uuid::new_v4().to_string(),
-            created_at: Utc::now(),
+            id: filename.to_string(),
+            created_at: self.clock.now(),
             database_version: "1.0".to_string(),
             app_version: env!("CARGO_PKG_VERSION").to_string(),
             size_bytes: file_size,
             compressed: self.config.compress,
             encrypted: self.config.encrypt,
             checksum: "placeholder".to_string(),
+            checksum_algorithm: legacy_checksum_algorithm(),
             tables_included: vec![],
             record_counts: HashMap::new(),
             description: None,
+            kind: BackupKind::Full,
+            parent_backup_id: None,
+            key_derivation: None,
+            partial: false,
+            chunked: false,
         };
 
         Ok(BackupInfo {
@@ -491,25 +881,22 @@ impl BackupManager {
         })
     }
 
+    /// File where the scheduler records its last successful backup across restarts
+    pub fn scheduler_state_path(&self) -> Result<PathBuf> {
+        Ok(self.get_app_data_dir()?.join(SCHEDULER_STATE_FILE))
+    }
+
     fn get_backup_directory(&self) -> Result<PathBuf> {
         let app_data_dir = self.get_app_data_dir()?;
         Ok(app_data_dir.join(&self.config.backup_directory))
     }
 
     fn get_app_data_dir(&self) -> Result<PathBuf> {
-        // Temporary workaround - in production this would use app_handle.path()
-        let home_dir = std::env::var("HOME")
-            .map_err(|_| BackupError::Configuration("Could not get home directory".to_string()))?;
-        Ok(PathBuf::from(home_dir).join(".local/share/case-crafter"))
-    }
-
-    fn calculate_checksum(&self, data: &[u8]) -> Result<String> {
-        Ok(format!("{:x}", md5::compute(data)))
-    }
+        use tauri::Manager;
 
-    fn compress_data(&self, data: Vec<u8>) -> Result<Vec<u8>> {
-        // Placeholder for compression - would use flate2 or similar
-        Ok(data) // For now, return uncompressed
+        self.app_handle.path()
+            .app_data_dir()
+            .map_err(|e| BackupError::Configuration(format!("Failed to get app data directory: {}", e)))
     }
 
     fn decompress_data(&self, data: Vec<u8>) -> Result<Vec<u8>> {
@@ -518,6 +905,781 @@ impl BackupManager {
     }
 }
 
+/// Check that an external backup destination is absolute and writable, creating it if needed
+async fn prepare_external_directory(destination: &Path) -> Result<PathBuf> {
+    if !destination.is_absolute() {
+        return Err(BackupError::Configuration(format!(
+            "Backup destination must be an absolute path: {}",
+            destination.display()
+        )));
+    }
+
+    fs::create_dir_all(destination).await.map_err(|e| {
+        BackupError::Configuration(format!("Cannot create backup destination {}: {}", destination.display(), e))
+    })?;
+
+    let probe = destination.join(format!(".case_crafter_write_test_{}", uuid::Uuid::new_v4()));
+    fs::write(&probe, b"").await.map_err(|e| {
+        BackupError::Configuration(format!("Backup destination {} is not writable: {}", destination.display(), e))
+    })?;
+    let _ = fs::remove_file(&probe).await;
+
+    Ok(destination.to_path_buf())
+}
+
+/// Fill in a backup filename template and check the result is safe to use as a file name
+fn render_backup_filename(template: &str, timestamp: DateTime<Utc>, backup_id: &str, kind: BackupKind) -> Result<String> {
+    let invalid = |reason: &str| BackupError::Configuration(format!("Invalid backup filename template '{}': {}", template, reason));
+
+    let mut filename = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filename.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| invalid("unclosed placeholder"))? + start;
+        let value = match &rest[start + 1..end] {
+            "timestamp" => timestamp.format("%Y%m%d_%H%M%S").to_string(),
+            "id" => backup_id.chars().take(8).collect(),
+            "kind" => match kind {
+                BackupKind::Full => "full".to_string(),
+                BackupKind::Incremental => "incremental".to_string(),
+            },
+            "app_version" => env!("CARGO_PKG_VERSION").to_string(),
+            other => {
+                return Err(invalid(&format!(
+                    "unknown placeholder '{{{}}}' (expected one of {})",
+                    other,
+                    FILENAME_PLACEHOLDERS.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+                )))
+            }
+        };
+        filename.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    filename.push_str(rest);
+
+    if filename.trim().is_empty() {
+        return Err(invalid("the name is empty"));
+    }
+    if filename.starts_with('.') || filename.ends_with(' ') || filename.ends_with('.') {
+        return Err(invalid("the name can't start with a dot or end with a dot or space"));
+    }
+    if filename.chars().any(|c| c.is_control() || UNSAFE_FILENAME_CHARS.contains(&c)) {
+        return Err(invalid("the name contains characters that aren't allowed in file names"));
+    }
+    if filename.len() > 200 {
+        return Err(invalid("the name is longer than 200 bytes"));
+    }
+    Ok(filename)
+}
+
+/// `<filename>.json` in `dir`, or `<filename>_2.json`, `<filename>_3.json`, ... when a
+/// backup, its metadata or an unfinished backup already uses the name
+fn unique_backup_path(dir: &Path, filename: &str) -> PathBuf {
+    let taken = |path: &Path| path.exists() || metadata_path(path).exists() || path.with_extension("json.partial").exists();
+
+    let mut path = dir.join(format!("{}.json", filename));
+    let mut counter = 2;
+    while taken(&path) {
+        path = dir.join(format!("{}_{}.json", filename, counter));
+        counter += 1;
+    }
+    path
+}
+
+/// Path of the metadata file stored next to a backup
+fn metadata_path(backup_path: &Path) -> PathBuf {
+    backup_path.with_extension("meta")
+}
+
+/// Compute a hex checksum of the backup payload with the named algorithm
+fn calculate_checksum(algorithm: &str, data: &[u8]) -> Result<String> {
+    match algorithm {
+        "sha256" => Ok(format!("{:x}", Sha256::digest(data))),
+        "md5" => Ok(format!("{:x}", md5::compute(data))),
+        other => Err(BackupError::InvalidFormat(format!("Unsupported checksum algorithm: {}", other))),
+    }
+}
+
+/// Check the payload against the checksum stored in the backup metadata
+fn verify_checksum(metadata: &BackupMetadata, data: &[u8]) -> Result<bool> {
+    Ok(calculate_checksum(&metadata.checksum_algorithm, data)? == metadata.checksum)
+}
+
+/// Key used to encrypt or decrypt a backup payload
+enum PayloadCipher<'a> {
+    Master(&'a EncryptionManager),
+    Passphrase(Zeroizing<[u8; 32]>),
+}
+
+impl PayloadCipher<'_> {
+    fn encrypt(&self, plaintext: &str) -> Result<EncryptedData> {
+        let encrypted = match self {
+            Self::Master(manager) => manager.encrypt(plaintext),
+            Self::Passphrase(key) => encryption::encrypt_with_key(key, plaintext),
+        };
+        encrypted.map_err(|e| BackupError::Encryption(e.to_string()))
+    }
+
+    fn decrypt(&self, encrypted: &EncryptedData) -> Result<String> {
+        match self {
+            Self::Master(manager) => manager.decrypt(encrypted).map_err(|e| BackupError::Encryption(e.to_string())),
+            Self::Passphrase(key) => encryption::decrypt_with_key(key, encrypted).map_err(|_| BackupError::InvalidPassphrase),
+        }
+    }
+}
+
+/// Streams a backup payload to disk, hashing the plaintext as it passes through. With a cipher
+/// the payload is encrypted in chunks of about `ENCRYPTED_CHUNK_BYTES`, one per line, so no more
+/// than one chunk is ever held in memory.
+struct PayloadWriter<'a> {
+    file: tokio::io::BufWriter<fs::File>,
+    cipher: Option<PayloadCipher<'a>>,
+    hasher: Sha256,
+    plaintext_bytes: u64,
+    pending: Vec<u8>,
+    peak_pending: usize,
+}
+
+impl<'a> PayloadWriter<'a> {
+    async fn create(path: &Path, cipher: Option<PayloadCipher<'a>>) -> Result<Self> {
+        Ok(Self {
+            file: tokio::io::BufWriter::new(fs::File::create(path).await?),
+            cipher,
+            hasher: Sha256::new(),
+            plaintext_bytes: 0,
+            pending: Vec::new(),
+            peak_pending: 0,
+        })
+    }
+
+    /// Write one piece of the payload. Pieces must be complete UTF-8, since encrypted chunks
+    /// only ever split between them.
+    async fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.hasher.update(bytes);
+        self.plaintext_bytes += bytes.len() as u64;
+
+        if self.cipher.is_none() {
+            self.file.write_all(bytes).await?;
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(bytes);
+        self.peak_pending = self.peak_pending.max(self.pending.len());
+        if self.pending.len() >= ENCRYPTED_CHUNK_BYTES {
+            self.flush_chunk().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush_chunk(&mut self) -> Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(());
+        };
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let plaintext = std::str::from_utf8(&self.pending)
+            .map_err(|e| BackupError::InvalidFormat(format!("Backup payload is not UTF-8: {}", e)))?;
+        let mut line = serde_json::to_vec(&cipher.encrypt(plaintext)?)?;
+        line.push(b'\n');
+        self.file.write_all(&line).await?;
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Flush everything to disk, returning the plaintext checksum and size
+    async fn finish(mut self) -> Result<(String, u64)> {
+        self.flush_chunk().await?;
+        self.file.flush().await?;
+        Ok((format!("{:x}", self.hasher.finalize()), self.plaintext_bytes))
+    }
+}
+
+/// Write the backup payload for `tables` as one JSON object, a batch of rows at a time,
+/// stamped with `timestamp`. With `since`, only rows changed at or after it are written.
+/// When the attachments table is included and `attachments_root` is given, the files it
+/// references are embedded as well. Returns the rows written per table.
+async fn write_backup_payload(
+    pool: &SqlitePool,
+    tables: &[&str],
+    timestamp: DateTime<Utc>,
+    since: Option<DateTime<Utc>>,
+    attachments_root: Option<&Path>,
+    writer: &mut PayloadWriter<'_>,
+    progress: &dyn ProgressSink,
+) -> Result<HashMap<String, u64>> {
+    // Incremental exports usually finish below this total; the tracker jumps to 100% at the end
+    let mut rows_total = 0;
+    for table in tables {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await?;
+        rows_total += count as u64;
+    }
+    let mut tracker = ProgressTracker::new(progress, BackupStage::Exporting, rows_total);
+
+    // Schema information comes first so every table entry can be written with a leading comma
+    let header = serde_json::json!({
+        "schema_version": BACKUP_SCHEMA_VERSION,
+        "backup_timestamp": timestamp,
+    })
+    .to_string();
+    writer.write(header.trim_end_matches('}').as_bytes()).await?;
+
+    let mut record_counts = HashMap::new();
+    for table in tables {
+        writer.write(format!(",{}:[", serde_json::to_string(table)?).as_bytes()).await?;
+
+        let mut export = TableExport::new(pool, table, since).await?;
+        let mut rows_written = 0u64;
+        while let Some(batch) = export.next_batch(pool).await? {
+            for row in batch {
+                if rows_written > 0 {
+                    writer.write(b",").await?;
+                }
+                writer.write(&serde_json::to_vec(&row)?).await?;
+                rows_written += 1;
+            }
+        }
+
+        writer.write(b"]").await?;
+        tracker.table_done(table, rows_written);
+        record_counts.insert(table.to_string(), rows_written);
+    }
+    if let Some(root) = attachments_root.filter(|_| tables.contains(&"attachments")) {
+        write_attachment_files(pool, root, since, writer).await?;
+    }
+    writer.write(b"}").await?;
+
+    Ok(record_counts)
+}
+
+/// Embed the files referenced by exported attachment rows, base64-encoded, one entry per file.
+/// Relative paths are resolved against `root`; files that no longer exist are skipped.
+async fn write_attachment_files(
+    pool: &SqlitePool,
+    root: &Path,
+    since: Option<DateTime<Utc>>,
+    writer: &mut PayloadWriter<'_>,
+) -> Result<u64> {
+    writer.write(format!(",{}:[", serde_json::to_string(ATTACHMENT_FILES_KEY)?).as_bytes()).await?;
+
+    let mut export = TableExport::new(pool, "attachments", since).await?;
+    let mut files_written = 0u64;
+    while let Some(batch) = export.next_batch(pool).await? {
+        for row in batch {
+            let (Some(id), Some(file_path)) = (row["id"].as_i64(), row["file_path"].as_str()) else {
+                continue;
+            };
+
+            let source = root.join(file_path);
+            let content = match fs::read(&source).await {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("Skipping attachment file {}: {}", source.display(), e);
+                    continue;
+                }
+            };
+
+            if files_written > 0 {
+                writer.write(b",").await?;
+            }
+            let entry = serde_json::json!({
+                "attachment_id": id,
+                "file_path": file_path,
+                "content": BASE64.encode(content),
+            });
+            writer.write(&serde_json::to_vec(&entry)?).await?;
+            files_written += 1;
+        }
+    }
+
+    writer.write(b"]").await?;
+    Ok(files_written)
+}
+
+/// Write embedded attachment files back under `root`, oldest backup layer first. Files whose
+/// recorded path is absolute or escapes `root` are placed in the attachments directory and
+/// their attachment rows updated to the new relative path. Returns the number of files written.
+async fn restore_attachment_files(
+    pool: &SqlitePool,
+    root: &Path,
+    backup_contents: &[serde_json::Value],
+) -> Result<u64> {
+    let mut restored = 0u64;
+    for content in backup_contents {
+        let Some(files) = content.get(ATTACHMENT_FILES_KEY).and_then(|files| files.as_array()) else {
+            continue;
+        };
+
+        for file in files {
+            let (Some(id), Some(file_path), Some(encoded)) = (
+                file["attachment_id"].as_i64(),
+                file["file_path"].as_str(),
+                file["content"].as_str(),
+            ) else {
+                return Err(BackupError::InvalidFormat("Invalid attachment file entry".to_string()));
+            };
+            let bytes = BASE64
+                .decode(encoded)
+                .map_err(|e| BackupError::InvalidFormat(format!("Invalid attachment {} content: {}", id, e)))?;
+
+            let relative = attachment_restore_path(id, file_path);
+            let target = root.join(&relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::write(&target, bytes).await?;
+
+            let relative = relative.to_string_lossy();
+            if relative != file_path {
+                sqlx::query("UPDATE attachments SET file_path = ? WHERE id = ?")
+                    .bind(relative.as_ref())
+                    .bind(id)
+                    .execute(pool)
+                    .await?;
+            }
+            restored += 1;
+        }
+    }
+
+    Ok(restored)
+}
+
+/// Relative path an attachment is restored to: its recorded path when that stays inside the
+/// app data directory, otherwise `attachments/<id>_<file name>`
+fn attachment_restore_path(id: i64, file_path: &str) -> PathBuf {
+    let path = Path::new(file_path);
+    let stays_inside = !file_path.is_empty()
+        && path.components().all(|component| matches!(component, std::path::Component::Normal(_)));
+    if stays_inside {
+        return path.to_path_buf();
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string());
+    Path::new(ATTACHMENTS_DIR).join(format!("{}_{}", id, name))
+}
+
+/// Pages through a table in rowid order so an export never holds more than
+/// `EXPORT_BATCH_ROWS` rows at once.
+///
+/// With `since`, only rows created or updated at or after it are exported. Tables without a
+/// timestamp column are exported in full. Deleted rows are not tracked, so an incremental
+/// restore never removes rows that exist in the parent backup.
+struct TableExport {
+    query: String,
+    since: Option<String>,
+    last_rowid: i64,
+    finished: bool,
+}
+
+impl TableExport {
+    async fn new(pool: &SqlitePool, table_name: &str, since: Option<DateTime<Utc>>) -> Result<Self> {
+        let mut filter = String::new();
+        let mut since_param = None;
+
+        if let Some(since) = since {
+            let columns: Vec<String> = sqlx::query(&format!("PRAGMA table_info({})", table_name))
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(|row| row.get::<String, _>("name"))
+                .collect();
+
+            let tracking: Vec<&str> = CHANGE_TRACKING_COLUMNS
+                .iter()
+                .copied()
+                .filter(|column| columns.iter().any(|c| c == column))
+                .collect();
+
+            let changed_at = match tracking.len() {
+                0 => None,
+                1 => Some(tracking[0].to_string()),
+                _ => Some(format!("COALESCE({})", tracking.join(", "))),
+            };
+
+            // datetime() normalizes both SQLite's CURRENT_TIMESTAMP format and RFC 3339 strings
+            if let Some(changed_at) = changed_at {
+                filter = format!(" AND datetime({}) >= datetime(?)", changed_at);
+                since_param = Some(since.format("%Y-%m-%d %H:%M:%S").to_string());
+            }
+        }
+
+        Ok(Self {
+            query: format!(
+                "SELECT rowid AS {}, * FROM {} WHERE rowid > ?{} ORDER BY rowid LIMIT ?",
+                EXPORT_ROWID_COLUMN, table_name, filter
+            ),
+            since: since_param,
+            last_rowid: i64::MIN,
+            finished: false,
+        })
+    }
+
+    /// Fetch the next batch of rows, or `None` once the table is exhausted
+    async fn next_batch(&mut self, pool: &SqlitePool) -> Result<Option<Vec<serde_json::Value>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut query = sqlx::query(&self.query).bind(self.last_rowid);
+        if let Some(since) = &self.since {
+            query = query.bind(since);
+        }
+        let rows = query.bind(EXPORT_BATCH_ROWS).fetch_all(pool).await?;
+
+        if (rows.len() as i64) < EXPORT_BATCH_ROWS {
+            self.finished = true;
+        }
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut batch = Vec::with_capacity(rows.len());
+        for row in &rows {
+            self.last_rowid = row.try_get(EXPORT_ROWID_COLUMN)?;
+            batch.push(row_to_json(row)?);
+        }
+        Ok(Some(batch))
+    }
+}
+
+#[cfg(test)]
+async fn export_table(pool: &SqlitePool, table_name: &str) -> Result<serde_json::Value> {
+    collect_table_export(pool, table_name, None).await
+}
+
+#[cfg(test)]
+async fn export_table_since(pool: &SqlitePool, table_name: &str, since: DateTime<Utc>) -> Result<serde_json::Value> {
+    collect_table_export(pool, table_name, Some(since)).await
+}
+
+#[cfg(test)]
+async fn collect_table_export(
+    pool: &SqlitePool,
+    table_name: &str,
+    since: Option<DateTime<Utc>>,
+) -> Result<serde_json::Value> {
+    let mut export = TableExport::new(pool, table_name, since).await?;
+    let mut rows = Vec::new();
+    while let Some(batch) = export.next_batch(pool).await? {
+        rows.extend(batch);
+    }
+    Ok(serde_json::Value::Array(rows))
+}
+
+/// Convert an exported row to a JSON object, leaving out the rowid used for paging
+fn row_to_json(row: &sqlx::sqlite::SqliteRow) -> Result<serde_json::Value> {
+    let mut row_data = serde_json::Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        if column.name() != EXPORT_ROWID_COLUMN {
+            row_data.insert(column.name().to_string(), column_to_json(row, i)?);
+        }
+    }
+    Ok(serde_json::Value::Object(row_data))
+}
+
+/// Convert a single column value to JSON based on its runtime storage class
+fn column_to_json(row: &sqlx::sqlite::SqliteRow, index: usize) -> Result<serde_json::Value> {
+    let raw = row.try_get_raw(index)?;
+    if raw.is_null() {
+        return Ok(serde_json::Value::Null);
+    }
+
+    let type_name = raw.type_info().name().to_string();
+    let value = match type_name.as_str() {
+        "INTEGER" => serde_json::json!(row.try_get_unchecked::<i64, _>(index)?),
+        "REAL" => serde_json::json!(row.try_get_unchecked::<f64, _>(index)?),
+        "BLOB" => {
+            use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+            serde_json::json!(BASE64.encode(row.try_get_unchecked::<Vec<u8>, _>(index)?))
+        }
+        _ => serde_json::json!(row.try_get_unchecked::<String, _>(index)?),
+    };
+
+    Ok(value)
+}
+
+/// Replace the contents of every known table present in the base backup with the backed-up rows,
+/// then layer any incremental backups on top of it.
+///
+/// `backup_contents` must start with a full backup followed by its incrementals, oldest first.
+/// Foreign-key enforcement is disabled while the tables are cleared and reloaded, then the
+/// whole load is checked with `PRAGMA foreign_key_check` before the transaction commits.
+/// Returns the number of rows restored per table.
+async fn restore_tables(
+    pool: &SqlitePool,
+    backup_contents: &[serde_json::Value],
+    progress: &dyn ProgressSink,
+) -> Result<Vec<(String, u64)>> {
+    restore_selected_tables(pool, backup_contents, None, progress).await
+}
+
+/// Restore the backed-up tables, or only `selection` when given. Tables outside the selection
+/// are left as they are, so rows in them that would lose their parent row abort the restore.
+async fn restore_selected_tables(
+    pool: &SqlitePool,
+    backup_contents: &[serde_json::Value],
+    selection: Option<&[String]>,
+    progress: &dyn ProgressSink,
+) -> Result<Vec<(String, u64)>> {
+    let (base, incrementals) = backup_contents
+        .split_first()
+        .ok_or_else(|| BackupError::InvalidFormat("No backup content to restore".to_string()))?;
+
+    let mut layers = Vec::with_capacity(backup_contents.len());
+    for content in std::iter::once(base).chain(incrementals) {
+        layers.push(content.as_object().ok_or_else(|| {
+            BackupError::InvalidFormat("Backup content is not a JSON object".to_string())
+        })?);
+    }
+
+    // Only restore tables we know about, in dependency order
+    let restore_order = |tables: &serde_json::Map<String, serde_json::Value>| -> Vec<&'static str> {
+        BACKUP_TABLES
+            .iter()
+            .copied()
+            .filter(|table| tables.contains_key(*table))
+            .filter(|table| match selection {
+                Some(selection) => selection.iter().any(|selected| selected == table),
+                None => true,
+            })
+            .collect()
+    };
+
+    let rows_total = layers
+        .iter()
+        .flat_map(|tables| restore_order(tables).into_iter().map(move |table| &tables[table]))
+        .filter_map(|rows| rows.as_array())
+        .map(|rows| rows.len() as u64)
+        .sum();
+    let mut tracker = ProgressTracker::new(progress, BackupStage::Restoring, rows_total);
+
+    // PRAGMA foreign_keys is a no-op inside a transaction, so it has to be toggled on the
+    // connection before the transaction starts and restored before the connection goes back
+    // to the pool.
+    let mut conn = pool.acquire().await?;
+    sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+
+    let result: Result<Vec<(String, u64)>> = async {
+        let mut tx = conn.begin().await?;
+
+        for table_name in restore_order(layers[0]).iter().rev() {
+            sqlx::query(&format!("DELETE FROM {}", table_name))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let mut restored: Vec<(String, u64)> = Vec::new();
+        for tables in &layers {
+            for table_name in restore_order(tables) {
+                let rows = tables[table_name].as_array().ok_or_else(|| {
+                    BackupError::InvalidFormat(format!("Table {} is not an array of rows", table_name))
+                })?;
+
+                for row in rows {
+                    insert_row(&mut tx, table_name, row).await?;
+                }
+                tracker.table_done(table_name, rows.len() as u64);
+
+                match restored.iter_mut().find(|(name, _)| name == table_name) {
+                    Some((_, count)) => *count += rows.len() as u64,
+                    None => restored.push((table_name.to_string(), rows.len() as u64)),
+                }
+            }
+        }
+
+        let violations = sqlx::query("PRAGMA foreign_key_check")
+            .fetch_all(&mut *tx)
+            .await?;
+        if !violations.is_empty() {
+            tx.rollback().await?;
+            if selection.is_some() {
+                return Err(BackupError::DependencyViolation(describe_violations(&violations)));
+            }
+            return Err(BackupError::InvalidFormat(format!(
+                "Restored data has {} foreign key violations",
+                violations.len()
+            )));
+        }
+
+        tx.commit().await?;
+        tracker.finish();
+        Ok(restored)
+    }
+    .await;
+
+    sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+    let enabled: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(&mut *conn)
+        .await?;
+    if enabled != 1 {
+        return Err(BackupError::Configuration("Failed to re-enable foreign key enforcement".to_string()));
+    }
+
+    result
+}
+
+fn schema_version_of(content: &serde_json::Value) -> &str {
+    content.get("schema_version").and_then(|v| v.as_str()).unwrap_or("unknown")
+}
+
+/// Compare the backed-up tables with the database without changing anything. Rows are matched
+/// by primary key (the whole row for tables without one), incrementals replace base rows the way
+/// a restore does, and a matched row is changed when any backed-up column differs.
+async fn preview_tables(pool: &SqlitePool, backup_contents: &[serde_json::Value]) -> Result<Vec<TableRestorePreview>> {
+    let mut layers = Vec::with_capacity(backup_contents.len());
+    for content in backup_contents {
+        layers.push(content.as_object().ok_or_else(|| {
+            BackupError::InvalidFormat("Backup content is not a JSON object".to_string())
+        })?);
+    }
+    let base = layers
+        .first()
+        .ok_or_else(|| BackupError::InvalidFormat("No backup content to restore".to_string()))?;
+
+    let mut previews = Vec::new();
+    for table_name in BACKUP_TABLES.iter().copied().filter(|table| base.contains_key(*table)) {
+        let key_columns = primary_key_columns(pool, table_name).await?;
+
+        let mut restored: HashMap<String, &serde_json::Map<String, serde_json::Value>> = HashMap::new();
+        for tables in &layers {
+            let Some(rows) = tables.get(table_name) else { continue };
+            let rows = rows.as_array().ok_or_else(|| {
+                BackupError::InvalidFormat(format!("Table {} is not an array of rows", table_name))
+            })?;
+            for row in rows {
+                let row = row.as_object().ok_or_else(|| {
+                    BackupError::InvalidFormat(format!("Row in table {} is not an object", table_name))
+                })?;
+                restored.insert(row_key(row, &key_columns), row);
+            }
+        }
+
+        let mut current = HashMap::new();
+        let mut export = TableExport::new(pool, table_name, None).await?;
+        while let Some(batch) = export.next_batch(pool).await? {
+            for row in batch {
+                if let serde_json::Value::Object(row) = row {
+                    current.insert(row_key(&row, &key_columns), row);
+                }
+            }
+        }
+
+        let mut preview = TableRestorePreview {
+            table: table_name.to_string(),
+            rows_to_restore: restored.len() as u64,
+            current_rows: current.len() as u64,
+            added: 0,
+            removed: current.keys().filter(|key| !restored.contains_key(*key)).count() as u64,
+            changed: 0,
+        };
+        for (key, row) in &restored {
+            match current.get(key) {
+                None => preview.added += 1,
+                Some(existing) if row.iter().any(|(column, value)| existing.get(column) != Some(value)) => {
+                    preview.changed += 1
+                }
+                Some(_) => {}
+            }
+        }
+        previews.push(preview);
+    }
+
+    Ok(previews)
+}
+
+/// Primary key columns of a table, in key order
+async fn primary_key_columns(pool: &SqlitePool, table_name: &str) -> Result<Vec<String>> {
+    let mut columns: Vec<(i64, String)> = sqlx::query(&format!("PRAGMA table_info({})", table_name))
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| (row.get::<i64, _>("pk"), row.get::<String, _>("name")))
+        .filter(|(pk, _)| *pk > 0)
+        .collect();
+    columns.sort();
+    Ok(columns.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Identity of a row for diffing: its primary key values, or the whole row without a key
+fn row_key(row: &serde_json::Map<String, serde_json::Value>, key_columns: &[String]) -> String {
+    if key_columns.is_empty() {
+        return serde_json::Value::Object(row.clone()).to_string();
+    }
+    let key: Vec<&serde_json::Value> = key_columns
+        .iter()
+        .map(|column| row.get(column).unwrap_or(&serde_json::Value::Null))
+        .collect();
+    serde_json::json!(key).to_string()
+}
+
+/// Summarize `PRAGMA foreign_key_check` rows as "child -> parent (count)" pairs
+fn describe_violations(violations: &[sqlx::sqlite::SqliteRow]) -> String {
+    let mut counts: Vec<((String, String), usize)> = Vec::new();
+    for violation in violations {
+        let pair = (violation.get::<String, _>("table"), violation.get::<String, _>("parent"));
+        match counts.iter_mut().find(|(existing, _)| *existing == pair) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((pair, 1)),
+        }
+    }
+
+    counts
+        .iter()
+        .map(|((table, parent), count)| format!("{} rows in {} would reference missing {} rows", count, table, parent))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Insert a single backed-up row using a parameterized statement, replacing any row with the same key
+async fn insert_row(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table_name: &str,
+    row: &serde_json::Value,
+) -> Result<()> {
+    let columns = row.as_object().ok_or_else(|| {
+        BackupError::InvalidFormat(format!("Row in table {} is not an object", table_name))
+    })?;
+
+    if columns.is_empty() {
+        return Ok(());
+    }
+
+    if let Some(bad) = columns.keys().find(|name| !is_valid_identifier(name)) {
+        return Err(BackupError::InvalidFormat(format!("Invalid column name {} in table {}", bad, table_name)));
+    }
+
+    let column_list = columns.keys().map(|name| format!("\"{}\"", name)).collect::<Vec<_>>().join(", ");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let sql = format!("INSERT OR REPLACE INTO {} ({}) VALUES ({})", table_name, column_list, placeholders);
+
+    let mut query = sqlx::query(&sql);
+    for value in columns.values() {
+        query = match value {
+            serde_json::Value::Null => query.bind(None::<String>),
+            serde_json::Value::Bool(b) => query.bind(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => query.bind(i),
+                None => query.bind(n.as_f64()),
+            },
+            serde_json::Value::String(s) => query.bind(s.clone()),
+            other => query.bind(other.to_string()),
+        };
+    }
+
+    query.execute(&mut **tx).await?;
+    Ok(())
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 /// Statistics about backups
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupStats {
@@ -542,6 +1704,7 @@ mod tests {
         assert_eq!(config.max_backups, 30);
         assert!(config.compress);
         assert!(config.encrypt);
+        assert_eq!(config.kind, BackupKind::Full);
     }
 
     #[test]
@@ -555,9 +1718,15 @@ mod tests {
             compressed: true,
             encrypted: true,
             checksum: "abc123".to_string(),
+            checksum_algorithm: CHECKSUM_ALGORITHM.to_string(),
             tables_included: vec!["users".to_string(), "case_studies".to_string()],
             record_counts: HashMap::new(),
             description: Some("Test backup".to_string()),
+            kind: BackupKind::Incremental,
+            parent_backup_id: Some("parent-id".to_string()),
+            key_derivation: None,
+            partial: false,
+            chunked: false,
         };
 
         let json = serde_json::to_string(&metadata).unwrap();
@@ -565,5 +1734,625 @@ mod tests {
         
         assert_eq!(metadata.id, deserialized.id);
         assert_eq!(metadata.database_version, deserialized.database_version);
+        assert_eq!(deserialized.kind, BackupKind::Incremental);
+        assert_eq!(deserialized.parent_backup_id.as_deref(), Some("parent-id"));
+    }
+
+    fn metadata_for(payload: &[u8], algorithm: &str) -> BackupMetadata {
+        BackupMetadata {
+            id: "test-id".to_string(),
+            created_at: Utc::now(),
+            database_version: "1.0".to_string(),
+            app_version: "0.1.0".to_string(),
+            size_bytes: payload.len() as u64,
+            compressed: false,
+            encrypted: false,
+            checksum: calculate_checksum(algorithm, payload).unwrap(),
+            checksum_algorithm: algorithm.to_string(),
+            tables_included: vec![],
+            record_counts: HashMap::new(),
+            description: None,
+            kind: BackupKind::Full,
+            parent_backup_id: None,
+            key_derivation: None,
+            partial: false,
+            chunked: false,
+        }
+    }
+
+    #[test]
+    fn test_tampered_payload_fails_checksum() {
+        let payload = br#"{"schema_version":"1.0","users":[]}"#.to_vec();
+        let metadata = metadata_for(&payload, CHECKSUM_ALGORITHM);
+        assert_eq!(metadata.checksum.len(), 64);
+        assert!(verify_checksum(&metadata, &payload).unwrap());
+
+        let mut tampered = payload.clone();
+        tampered[5] ^= 0x01;
+        assert!(!verify_checksum(&metadata, &tampered).unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_legacy_md5_metadata_still_validates() {
+        let payload = b"legacy backup".to_vec();
+        let mut metadata = metadata_for(&payload, "md5");
+        assert!(verify_checksum(&metadata, &payload).unwrap());
+
+        // Metadata written before the algorithm tag existed defaults to MD5
+        let mut json = serde_json::to_value(&metadata).unwrap();
+        json.as_object_mut().unwrap().remove("checksum_algorithm");
+        metadata = serde_json::from_value(json).unwrap();
+        assert_eq!(metadata.checksum_algorithm, "md5");
+        assert!(verify_checksum(&metadata, &payload).unwrap());
+    }
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(include_str!("../../schema.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to create schema");
+        pool
+    }
+
+    async fn count_rows(pool: &SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backup_wipe_restore_round_trip() {
+        let pool = create_test_pool().await;
+
+        sqlx::query("INSERT INTO users (username, email) VALUES ('instructor', 'i@example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO case_studies (title, domain_id, content, created_by) VALUES ('Case', 1, 'Body', 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO assessment_questions (case_study_id, question_text, question_type, points) \
+             VALUES (1, 'Why?', 'essay', 5)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut backup = serde_json::Map::new();
+        let mut expected = HashMap::new();
+        for table in BACKUP_TABLES {
+            backup.insert(table.to_string(), export_table(&pool, table).await.unwrap());
+            expected.insert(*table, count_rows(&pool, table).await);
+        }
+        let backup = serde_json::Value::Object(backup);
+
+        for table in BACKUP_TABLES.iter().rev() {
+            sqlx::query(&format!("DELETE FROM {}", table)).execute(&pool).await.unwrap();
+        }
+        assert_eq!(count_rows(&pool, "case_studies").await, 0);
+
+        restore_tables(&pool, std::slice::from_ref(&backup), &NoopProgress).await.unwrap();
+
+        for table in BACKUP_TABLES {
+            assert_eq!(count_rows(&pool, table).await, expected[table], "row count mismatch for {}", table);
+        }
+
+        let points: i64 = sqlx::query_scalar("SELECT points FROM assessment_questions WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(points, 5);
+
+        let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys").fetch_one(&pool).await.unwrap();
+        assert_eq!(foreign_keys, 1);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_export_contains_only_changed_rows() {
+        let pool = create_test_pool().await;
+
+        sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for title in ["Unchanged", "Edited"] {
+            sqlx::query(
+                "INSERT INTO case_studies (title, domain_id, content, created_by, created_at, updated_at) \
+                 VALUES (?, 1, 'Body', 1, '2020-01-01 00:00:00', '2020-01-01 00:00:00')",
+            )
+            .bind(title)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let parent_created_at = Utc::now() - Duration::seconds(1);
+        sqlx::query("UPDATE case_studies SET content = 'Revised' WHERE title = 'Edited'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let changed = export_table_since(&pool, "case_studies", parent_created_at).await.unwrap();
+        let rows = changed.as_array().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["title"], "Edited");
+        assert_eq!(rows[0]["content"], "Revised");
+
+        // Applying the incremental on top of the full backup keeps the untouched row
+        let mut full = serde_json::Map::new();
+        full.insert("case_studies".to_string(), serde_json::json!([
+            { "id": 1, "title": "Unchanged", "domain_id": 1, "content": "Body", "created_by": 1 },
+            { "id": 2, "title": "Edited", "domain_id": 1, "content": "Body", "created_by": 1 },
+        ]));
+        let mut incremental = serde_json::Map::new();
+        incremental.insert("case_studies".to_string(), changed);
+
+        restore_tables(
+            &pool,
+            &[serde_json::Value::Object(full), serde_json::Value::Object(incremental)],
+            &NoopProgress,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(count_rows(&pool, "case_studies").await, 2);
+        let content: String = sqlx::query_scalar("SELECT content FROM case_studies WHERE id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(content, "Revised");
+    }
+
+    struct RecordingSink {
+        events: std::sync::Mutex<Vec<progress::BackupProgress>>,
+    }
+
+    impl ProgressSink for RecordingSink {
+        fn report(&self, progress: &progress::BackupProgress) {
+            self.events.lock().unwrap().push(progress.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_progress_is_monotonic_and_completes() {
+        let pool = create_test_pool().await;
+        let backup = serde_json::json!({
+            "schema_version": BACKUP_SCHEMA_VERSION,
+            "users": [{ "id": 1, "username": "instructor" }],
+            "case_studies": [
+                { "id": 1, "title": "A", "domain_id": 1, "content": "Body", "created_by": 1 },
+                { "id": 2, "title": "B", "domain_id": 1, "content": "Body", "created_by": 1 },
+            ],
+        });
+
+        let sink = RecordingSink { events: std::sync::Mutex::new(Vec::new()) };
+        restore_tables(&pool, &[backup], &sink).await.unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.windows(2).all(|pair| pair[0].percent <= pair[1].percent));
+        assert!(events.windows(2).all(|pair| pair[0].rows_done <= pair[1].rows_done));
+        assert_eq!(events[0].table.as_deref(), Some("users"));
+
+        let last = events.last().unwrap();
+        assert_eq!(last.stage, BackupStage::Completed);
+        assert_eq!(last.percent, 100.0);
+        assert_eq!(last.rows_done, 3);
+    }
+
+    async fn test_backup_manager(app_handle: AppHandle) -> BackupManager {
+        let database_manager = Arc::new(DatabaseManager::in_memory().await.unwrap());
+        let mut manager = BackupManager::new(app_handle, database_manager, None);
+        manager.set_config(BackupConfig { encrypt: false, ..BackupConfig::default() });
+        manager
+    }
+
+    fn external_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("case-crafter-backup-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_filename_template_rendering() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-03-05T14:07:09Z").unwrap().with_timezone(&Utc);
+        let id = "0f1e2d3c-4b5a-6978-8695-a4b3c2d1e0f9";
+        let render = |template: &str| render_backup_filename(template, timestamp, id, BackupKind::Incremental);
+
+        assert_eq!(render(DEFAULT_FILENAME_TEMPLATE).unwrap(), "case_crafter_backup_20240305_140709_0f1e2d3c");
+        assert_eq!(
+            render("{kind}-{timestamp}-v{app_version}").unwrap(),
+            format!("incremental-20240305_140709-v{}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(render("nightly").unwrap(), "nightly");
+
+        for template in ["{date}_{id}", "backup_{id", "../{id}", "{kind}/{id}", "a:b", ".{id}", "", "{kind}\n"] {
+            assert!(matches!(render(template), Err(BackupError::Configuration(_))), "{:?}", template);
+        }
+
+        let config = BackupConfig { filename_template: "{id}?".to_string(), ..BackupConfig::default() };
+        assert!(config.validate_filename_template().is_err());
+        assert!(BackupConfig::default().validate_filename_template().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_taken_backup_name_gets_counter() {
+        let app = tauri::test::mock_app();
+        let mut manager = test_backup_manager(app.handle().clone()).await;
+        manager.set_config(BackupConfig {
+            encrypt: false,
+            filename_template: "nightly_{kind}".to_string(),
+            ..BackupConfig::default()
+        });
+        let destination = external_dir();
+        let options = || BackupOptions { destination: Some(destination.clone()), ..BackupOptions::default() };
+
+        let first = manager.create_backup_with_options(None, options(), &NoopProgress).await.unwrap();
+        let second = manager.create_backup_with_options(None, options(), &NoopProgress).await.unwrap();
+        assert_eq!(first.file_path, destination.join("nightly_full.json"));
+        assert_eq!(second.file_path, destination.join("nightly_full_2.json"));
+        assert!(manager.validate_backup(&first.file_path).await.unwrap());
+        assert!(manager.validate_backup(&second.file_path).await.unwrap());
+
+        // A leftover metadata file also reserves its name
+        std::fs::write(destination.join("nightly_full_3.meta"), b"{}").unwrap();
+        assert_eq!(unique_backup_path(&destination, "nightly_full"), destination.join("nightly_full_4.json"));
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backup_written_to_external_directory() {
+        let app = tauri::test::mock_app();
+        let manager = test_backup_manager(app.handle().clone()).await;
+        let destination = external_dir();
+
+        let options = BackupOptions { destination: Some(destination.clone()), ..BackupOptions::default() };
+        let backup = manager
+            .create_backup_with_options(Some("USB drive".to_string()), options, &NoopProgress)
+            .await
+            .unwrap();
+
+        assert!(backup.file_path.starts_with(&destination));
+        assert!(metadata_path(&backup.file_path).exists());
+        assert!(manager.validate_backup(&backup.file_path).await.unwrap());
+
+        let relative = BackupOptions { destination: Some(PathBuf::from("backups/external")), ..BackupOptions::default() };
+        assert!(matches!(
+            manager.create_backup_with_options(None, relative, &NoopProgress).await,
+            Err(BackupError::Configuration(_))
+        ));
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_passphrase_protected_backup() {
+        let app = tauri::test::mock_app();
+        let manager = test_backup_manager(app.handle().clone()).await;
+        let pool = manager.database_manager.pool().clone();
+        let destination = external_dir();
+
+        sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let options = BackupOptions {
+            destination: Some(destination.clone()),
+            passphrase: Some("offsite passphrase".to_string()),
+            ..BackupOptions::default()
+        };
+        let backup = manager.create_backup_with_options(None, options, &NoopProgress).await.unwrap();
+
+        assert!(backup.metadata.encrypted);
+        let metadata_json = std::fs::read_to_string(metadata_path(&backup.file_path)).unwrap();
+        assert!(metadata_json.contains("\"salt\""));
+        assert!(!std::fs::read_to_string(&backup.file_path).unwrap().contains("instructor"));
+
+        sqlx::query("DELETE FROM users").execute(&pool).await.unwrap();
+
+        assert!(matches!(
+            manager.restore_backup(&backup.file_path, false).await,
+            Err(BackupError::PassphraseRequired)
+        ));
+        assert!(matches!(
+            manager
+                .restore_backup_with_passphrase(&backup.file_path, false, Some("wrong"), &NoopProgress)
+                .await,
+            Err(BackupError::InvalidPassphrase)
+        ));
+
+        manager
+            .restore_backup_with_passphrase(&backup.file_path, false, Some("offsite passphrase"), &NoopProgress)
+            .await
+            .unwrap();
+        assert_eq!(count_rows(&pool, "users").await, 1);
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+
+    async fn export_all(pool: &SqlitePool) -> serde_json::Value {
+        let mut backup = serde_json::Map::new();
+        for table in BACKUP_TABLES {
+            backup.insert(table.to_string(), export_table(pool, table).await.unwrap());
+        }
+        serde_json::Value::Object(backup)
+    }
+
+    #[tokio::test]
+    async fn test_restore_single_table_leaves_others_untouched() {
+        let pool = create_test_pool().await;
+        sqlx::query("INSERT INTO users (username, full_name) VALUES ('instructor', 'Original Name')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO case_studies (title, domain_id, content, created_by) VALUES ('Original', 1, 'Body', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let backup = export_all(&pool).await;
+
+        sqlx::query("UPDATE case_studies SET title = 'Edited'").execute(&pool).await.unwrap();
+        sqlx::query("UPDATE users SET full_name = 'Changed Name'").execute(&pool).await.unwrap();
+
+        let selection = vec!["case_studies".to_string()];
+        let restored = restore_selected_tables(&pool, &[backup], Some(&selection), &NoopProgress)
+            .await
+            .unwrap();
+        assert_eq!(restored, vec![("case_studies".to_string(), 1)]);
+
+        let title: String = sqlx::query_scalar("SELECT title FROM case_studies WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(title, "Original");
+        let full_name: String = sqlx::query_scalar("SELECT full_name FROM users WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(full_name, "Changed Name");
+    }
+
+    #[tokio::test]
+    async fn test_preview_matches_restore_without_changing_data() {
+        let pool = create_test_pool().await;
+        for username in ["alice", "bob", "carol"] {
+            sqlx::query("INSERT INTO users (username) VALUES (?)")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let backup = export_all(&pool).await;
+
+        sqlx::query("UPDATE users SET full_name = 'Edited' WHERE username = 'alice'").execute(&pool).await.unwrap();
+        sqlx::query("DELETE FROM users WHERE username = 'bob'").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO users (username) VALUES ('dave')").execute(&pool).await.unwrap();
+        let before = export_all(&pool).await;
+
+        let previews = preview_tables(&pool, std::slice::from_ref(&backup)).await.unwrap();
+        assert_eq!(export_all(&pool).await, before, "preview must not change data");
+
+        let users = previews.iter().find(|p| p.table == "users").unwrap();
+        assert_eq!(users.rows_to_restore, 3);
+        assert_eq!(users.current_rows, 3);
+        assert_eq!((users.added, users.removed, users.changed), (1, 1, 1));
+
+        let restored = restore_tables(&pool, std::slice::from_ref(&backup), &NoopProgress).await.unwrap();
+        for preview in &previews {
+            assert_eq!(count_rows(&pool, &preview.table).await as u64, preview.rows_to_restore, "{}", preview.table);
+        }
+        for (table, count) in restored {
+            let preview = previews.iter().find(|p| p.table == table).unwrap();
+            assert_eq!(preview.rows_to_restore, count, "{}", table);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preview_restore_reports_backup_versions() {
+        let app = tauri::test::mock_app();
+        let manager = test_backup_manager(app.handle().clone()).await;
+        let pool = manager.database_manager.pool().clone();
+        let destination = external_dir();
+
+        let options = BackupOptions { destination: Some(destination.clone()), ..BackupOptions::default() };
+        let backup = manager.create_backup_with_options(None, options, &NoopProgress).await.unwrap();
+        sqlx::query("INSERT INTO users (username) VALUES ('instructor')").execute(&pool).await.unwrap();
+
+        let preview = manager.preview_restore(&backup.file_path, None).await.unwrap();
+        assert_eq!(preview.backup_id, backup.metadata.id);
+        assert_eq!(preview.app_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(preview.schema_version, BACKUP_SCHEMA_VERSION);
+        assert!(preview.schema_compatible);
+        assert_eq!(preview.chain_length, 1);
+        let users = preview.tables.iter().find(|p| p.table == "users").unwrap();
+        assert_eq!((users.rows_to_restore, users.removed), (0, 1));
+        assert_eq!(count_rows(&pool, "users").await, 1);
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_selective_restore_rejects_orphaned_rows() {
+        let pool = create_test_pool().await;
+        sqlx::query("INSERT INTO users (username) VALUES ('student')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let backup = export_all(&pool).await;
+
+        // Progress recorded after the backup points at a case study the backup does not contain
+        sqlx::query("INSERT INTO case_studies (title, domain_id, content, created_by) VALUES ('New', 1, 'Body', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_progress (user_id, case_study_id) VALUES (1, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let selection = vec!["case_studies".to_string()];
+        let error = restore_selected_tables(&pool, &[backup], Some(&selection), &NoopProgress)
+            .await
+            .unwrap_err();
+        assert!(matches!(&error, BackupError::DependencyViolation(message) if message.contains("user_progress")));
+
+        assert_eq!(count_rows(&pool, "case_studies").await, 1);
+        assert_eq!(count_rows(&pool, "user_progress").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_table_allowlist_backup_and_missing_table_restore() {
+        let app = tauri::test::mock_app();
+        let manager = test_backup_manager(app.handle().clone()).await;
+        let destination = external_dir();
+
+        let options = BackupOptions {
+            destination: Some(destination.clone()),
+            tables: Some(vec!["users".to_string()]),
+            ..BackupOptions::default()
+        };
+        let backup = manager.create_backup_with_options(None, options, &NoopProgress).await.unwrap();
+        assert!(backup.metadata.partial);
+        assert_eq!(backup.metadata.tables_included, vec!["users".to_string()]);
+
+        let missing = manager
+            .restore_backup_tables(&backup.file_path, &["case_studies".to_string()], false, None, &NoopProgress)
+            .await;
+        assert!(matches!(missing, Err(BackupError::NotFound(_))));
+
+        let unknown = BackupOptions { tables: Some(vec!["sqlite_master".to_string()]), ..BackupOptions::default() };
+        assert!(matches!(
+            manager.create_backup_with_options(None, unknown, &NoopProgress).await,
+            Err(BackupError::Configuration(_))
+        ));
+
+        std::fs::remove_dir_all(&destination).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_large_backup_streams_in_bounded_chunks() {
+        let pool = create_test_pool().await;
+        sqlx::query(
+            "INSERT INTO users (username, preferences) \
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 3000) \
+             SELECT 'user' || i, hex(randomblob(500)) FROM n",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let path = std::env::temp_dir().join(format!("case-crafter-stream-{}.json", uuid::Uuid::new_v4()));
+        let key = Zeroizing::new([7u8; 32]);
+        let mut writer = PayloadWriter::create(&path, Some(PayloadCipher::Passphrase(key.clone()))).await.unwrap();
+
+        let counts = write_backup_payload(&pool, &["users"], Utc::now(), None, None, &mut writer, &NoopProgress).await.unwrap();
+        assert_eq!(counts["users"], 3000);
+
+        // Memory held at any point is one chunk plus at most one row, far below the payload size
+        let peak_pending = writer.peak_pending;
+        let (checksum, size_bytes) = writer.finish().await.unwrap();
+        assert!(size_bytes > 8 * ENCRYPTED_CHUNK_BYTES as u64);
+        assert!(peak_pending < ENCRYPTED_CHUNK_BYTES + 4096);
+
+        let file = std::fs::read(&path).unwrap();
+        let chunks: Vec<&[u8]> = file.split(|byte| *byte == b'\n').filter(|chunk| !chunk.is_empty()).collect();
+        assert!(chunks.len() > 8);
+
+        let cipher = PayloadCipher::Passphrase(key);
+        let mut plaintext = Vec::new();
+        for chunk in chunks {
+            plaintext.extend(cipher.decrypt(&serde_json::from_slice(chunk).unwrap()).unwrap().into_bytes());
+        }
+        assert_eq!(plaintext.len() as u64, size_bytes);
+        assert_eq!(calculate_checksum(CHECKSUM_ALGORITHM, &plaintext).unwrap(), checksum);
+
+        let payload: serde_json::Value = serde_json::from_slice(&plaintext).unwrap();
+        assert_eq!(payload["schema_version"], BACKUP_SCHEMA_VERSION);
+        assert_eq!(payload["users"].as_array().unwrap().len(), 3000);
+        assert!(payload["users"][0].get(EXPORT_ROWID_COLUMN).is_none());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_attachment_files_survive_backup_and_restore() {
+        let pool = create_test_pool().await;
+        let root = std::env::temp_dir().join(format!("case-crafter-attachments-{}", uuid::Uuid::new_v4()));
+        let outside = root.with_extension("outside");
+        std::fs::create_dir_all(root.join("attachments")).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+
+        let report: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        std::fs::write(root.join("attachments/report.pdf"), &report).unwrap();
+        std::fs::write(outside.join("notes.txt"), b"external notes").unwrap();
+
+        sqlx::query("INSERT INTO users (username) VALUES ('instructor')").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO case_studies (title, domain_id, content, created_by) VALUES ('Case', 1, 'Body', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO attachments (case_study_id, filename, file_path) VALUES (1, 'report.pdf', 'attachments/report.pdf')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO attachments (case_study_id, filename, file_path) VALUES (1, 'notes.txt', ?)")
+            .bind(outside.join("notes.txt").to_string_lossy().to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let path = root.with_extension("json");
+        let mut writer = PayloadWriter::create(&path, None).await.unwrap();
+        let timestamp = Utc::now() - chrono::Duration::days(3);
+        write_backup_payload(&pool, BACKUP_TABLES, timestamp, None, Some(&root), &mut writer, &NoopProgress)
+            .await
+            .unwrap();
+        writer.finish().await.unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(payload["backup_timestamp"], serde_json::json!(timestamp));
+        assert_eq!(payload[ATTACHMENT_FILES_KEY].as_array().unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+        sqlx::query("DELETE FROM attachments").execute(&pool).await.unwrap();
+
+        let backup = std::slice::from_ref(&payload);
+        restore_tables(&pool, backup, &NoopProgress).await.unwrap();
+        assert_eq!(restore_attachment_files(&pool, &root, backup).await.unwrap(), 2);
+
+        assert_eq!(std::fs::read(root.join("attachments/report.pdf")).unwrap(), report);
+
+        // The file from outside the app data dir is brought inside and its row follows it
+        let moved: String = sqlx::query_scalar("SELECT file_path FROM attachments WHERE id = 2")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(Path::new(&moved), Path::new("attachments").join("2_notes.txt"));
+        assert_eq!(std::fs::read(root.join(&moved)).unwrap(), b"external notes");
+
+        std::fs::remove_dir_all(&root).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_excluding_attachments_drops_table_and_files() {
+        let app = tauri::test::mock_app();
+        let mut manager = test_backup_manager(app.handle().clone()).await;
+        assert!(manager.backup_table_selection(None).contains(&"attachments"));
+
+        manager.set_config(BackupConfig { include_attachments: false, encrypt: false, ..BackupConfig::default() });
+        let tables = manager.backup_table_selection(None);
+        assert!(!tables.contains(&"attachments"));
+        assert!(tables.contains(&"case_studies"));
+    }
+}
@@ -0,0 +1,95 @@
+// Progress reporting for long-running backup and restore operations
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted to the frontend while a backup or restore is running
+pub const BACKUP_PROGRESS_EVENT: &str = "backup://progress";
+
+/// Phase of a backup operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupStage {
+    Exporting,
+    Restoring,
+    Completed,
+}
+
+/// Progress payload sent with each event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupProgress {
+    pub stage: BackupStage,
+    pub table: Option<String>,
+    pub rows_done: u64,
+    pub rows_total: u64,
+    pub percent: f64,
+}
+
+/// Receiver for backup progress updates
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, progress: &BackupProgress);
+}
+
+impl ProgressSink for AppHandle {
+    fn report(&self, progress: &BackupProgress) {
+        if let Err(e) = self.emit(BACKUP_PROGRESS_EVENT, progress) {
+            tracing::warn!("Failed to emit backup progress: {}", e);
+        }
+    }
+}
+
+/// Sink that discards progress updates, for callers without a UI to notify
+pub struct NoopProgress;
+
+impl ProgressSink for NoopProgress {
+    fn report(&self, _progress: &BackupProgress) {}
+}
+
+/// Tracks rows processed across tables and reports cumulative progress to a sink
+pub struct ProgressTracker<'a> {
+    sink: &'a dyn ProgressSink,
+    stage: BackupStage,
+    rows_done: u64,
+    rows_total: u64,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub fn new(sink: &'a dyn ProgressSink, stage: BackupStage, rows_total: u64) -> Self {
+        Self {
+            sink,
+            stage,
+            rows_done: 0,
+            rows_total,
+        }
+    }
+
+    /// Record that a table has been processed
+    pub fn table_done(&mut self, table: &str, rows: u64) {
+        self.rows_done += rows;
+        self.rows_total = self.rows_total.max(self.rows_done);
+        self.emit(Some(table.to_string()), self.stage);
+    }
+
+    /// Report completion at 100%
+    pub fn finish(&mut self) {
+        self.rows_done = self.rows_total.max(self.rows_done);
+        self.rows_total = self.rows_done;
+        self.emit(None, BackupStage::Completed);
+    }
+
+    fn emit(&self, table: Option<String>, stage: BackupStage) {
+        let percent = if self.rows_total == 0 || stage == BackupStage::Completed {
+            100.0
+        } else {
+            (self.rows_done as f64 / self.rows_total as f64 * 100.0).min(100.0)
+        };
+
+        self.sink.report(&BackupProgress {
+            stage,
+            table,
+            rows_done: self.rows_done,
+            rows_total: self.rows_total,
+            percent,
+        });
+    }
+}
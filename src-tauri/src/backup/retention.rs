@@ -0,0 +1,229 @@
+// Retention rules deciding which old backups to prune
+
+use super::{BackupConfig, BackupInfo};
+use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Tiered retention: every backup from the last `daily_days` days, then the newest backup
+/// of each ISO week for `weekly_weeks` weeks, then the newest backup of each calendar month
+/// for `monthly_months` months. Each window is measured back from now.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieredRetention {
+    pub daily_days: u32,
+    pub weekly_weeks: u32,
+    pub monthly_months: u32,
+}
+
+impl Default for TieredRetention {
+    fn default() -> Self {
+        Self {
+            daily_days: 7,
+            weekly_weeks: 4,
+            monthly_months: 12,
+        }
+    }
+}
+
+impl TieredRetention {
+    /// Ids of the backups this policy keeps. `backups` must be sorted newest first.
+    fn retained<'a>(&self, backups: &'a [BackupInfo], now: DateTime<Utc>) -> HashSet<&'a str> {
+        let daily_cutoff = now - Duration::days(i64::from(self.daily_days));
+        let weekly_cutoff = now - Duration::weeks(i64::from(self.weekly_weeks));
+        let monthly_cutoff = now
+            .checked_sub_months(Months::new(self.monthly_months))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let mut weeks_seen = HashSet::new();
+        let mut months_seen = HashSet::new();
+        let mut keep = HashSet::new();
+        for backup in backups {
+            let created_at = backup.metadata.created_at;
+            let week = created_at.iso_week();
+            // Newest first, so the first backup seen in a week or month is its newest
+            let newest_in_week = weeks_seen.insert((week.year(), week.week()));
+            let newest_in_month = months_seen.insert((created_at.year(), created_at.month()));
+
+            if created_at > daily_cutoff
+                || (newest_in_week && created_at > weekly_cutoff)
+                || (newest_in_month && created_at > monthly_cutoff)
+            {
+                keep.insert(backup.metadata.id.as_str());
+            }
+        }
+        keep
+    }
+}
+
+/// Backups that `config` says to delete. A backup is pruned when any rule rejects it:
+/// it falls beyond `max_backups`, is older than `max_age_days`, or is outside the tiered
+/// policy. The newest complete backup and every backup a retained incremental backup
+/// depends on are always kept. `backups` must be sorted newest first.
+pub fn backups_to_prune<'a>(backups: &'a [BackupInfo], config: &BackupConfig, now: DateTime<Utc>) -> Vec<&'a BackupInfo> {
+    let age_cutoff = config.max_age_days.map(|days| now - Duration::days(i64::from(days)));
+    let tiered = config.tiered_retention.as_ref().map(|policy| policy.retained(backups, now));
+
+    let mut keep: HashSet<&str> = backups
+        .iter()
+        .enumerate()
+        .filter(|(index, backup)| {
+            let id = backup.metadata.id.as_str();
+            *index < config.max_backups
+                && !matches!(age_cutoff, Some(cutoff) if backup.metadata.created_at < cutoff)
+                && !matches!(&tiered, Some(retained) if !retained.contains(id))
+        })
+        .map(|(_, backup)| backup.metadata.id.as_str())
+        .collect();
+
+    if let Some(latest) = backups.iter().find(|b| b.is_valid && !b.metadata.partial) {
+        keep.insert(latest.metadata.id.as_str());
+    }
+
+    // Never delete a backup that a retained incremental backup still depends on
+    loop {
+        let parents: Vec<&str> = backups
+            .iter()
+            .filter(|b| keep.contains(b.metadata.id.as_str()))
+            .filter_map(|b| b.metadata.parent_backup_id.as_deref())
+            .filter(|id| !keep.contains(id))
+            .collect();
+        if parents.is_empty() {
+            break;
+        }
+        keep.extend(parents);
+    }
+
+    backups
+        .iter()
+        .filter(|b| !keep.contains(b.metadata.id.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{BackupKind, BackupMetadata};
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 30, 12, 0, 0).unwrap()
+    }
+
+    /// One backup per entry, `age_days` old, sorted newest first like `list_backups`
+    fn backups(ages_days: &[i64]) -> Vec<BackupInfo> {
+        let mut backups: Vec<BackupInfo> = ages_days
+            .iter()
+            .map(|age| BackupInfo {
+                metadata: BackupMetadata {
+                    id: format!("day-{}", age),
+                    created_at: now() - Duration::days(*age),
+                    database_version: "1.0".to_string(),
+                    app_version: "0.1.0".to_string(),
+                    size_bytes: 0,
+                    compressed: false,
+                    encrypted: false,
+                    checksum: String::new(),
+                    checksum_algorithm: "sha256".to_string(),
+                    tables_included: vec![],
+                    record_counts: HashMap::new(),
+                    description: None,
+                    kind: BackupKind::Full,
+                    parent_backup_id: None,
+                    key_derivation: None,
+                    partial: false,
+                    chunked: false,
+                },
+                file_path: PathBuf::from(format!("day-{}.json", age)),
+                file_size: 0,
+                is_valid: true,
+            })
+            .collect();
+        backups.sort_by(|a, b| b.metadata.created_at.cmp(&a.metadata.created_at));
+        backups
+    }
+
+    fn pruned(backups: &[BackupInfo], config: &BackupConfig) -> Vec<String> {
+        let mut ids: Vec<String> = backups_to_prune(backups, config, now())
+            .into_iter()
+            .map(|b| b.metadata.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn config(max_backups: usize, max_age_days: Option<u32>, tiered: Option<TieredRetention>) -> BackupConfig {
+        BackupConfig {
+            max_backups,
+            max_age_days,
+            tiered_retention: tiered,
+            ..BackupConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_count_only_keeps_newest() {
+        let backups = backups(&[0, 1, 2, 3, 4]);
+        assert_eq!(pruned(&backups, &config(3, None, None)), vec!["day-3", "day-4"]);
+        assert!(pruned(&backups, &config(10, None, None)).is_empty());
+    }
+
+    #[test]
+    fn test_age_only_prunes_within_count() {
+        let backups = backups(&[0, 5, 20, 45, 90]);
+        assert_eq!(pruned(&backups, &config(30, Some(30), None)), vec!["day-45", "day-90"]);
+    }
+
+    #[test]
+    fn test_combined_rules_apply_union() {
+        let backups = backups(&[0, 1, 2, 40, 50]);
+        // Count alone would prune only day-50; age also prunes day-40
+        assert_eq!(pruned(&backups, &config(4, Some(30), None)), vec!["day-40", "day-50"]);
+        // Count prunes day-2 even though it is young
+        assert_eq!(pruned(&backups, &config(2, Some(30), None)), vec!["day-2", "day-40", "day-50"]);
+    }
+
+    #[test]
+    fn test_tiered_keeps_daily_then_weekly_then_monthly() {
+        // Daily backups for 120 days
+        let ages: Vec<i64> = (0..120).collect();
+        let backups = backups(&ages);
+        let policy = TieredRetention { daily_days: 7, weekly_weeks: 4, monthly_months: 3 };
+        let kept: Vec<i64> = {
+            let pruned = pruned(&backups, &config(1000, None, Some(policy)));
+            ages.iter().copied().filter(|age| !pruned.contains(&format!("day-{}", age))).collect()
+        };
+
+        // Everything from the last week
+        assert!((0..7).all(|age| kept.contains(&age)));
+        // One per ISO week within four weeks: 2024-06-23 (day 7) is a Sunday, so the newest
+        // of each earlier week is the Sunday 7, 14 and 21 days back
+        assert!(kept.contains(&14) && kept.contains(&21));
+        assert!(!kept.contains(&8) && !kept.contains(&15));
+        // Month ends within three months: May 31, April 30
+        assert!(kept.contains(&30) && kept.contains(&61));
+        assert!(!kept.contains(&45));
+        // Nothing beyond three months
+        assert!(kept.iter().all(|age| *age < 92));
+    }
+
+    #[test]
+    fn test_newest_complete_backup_is_never_pruned() {
+        let mut backups = backups(&[40, 50]);
+        assert_eq!(pruned(&backups, &config(30, Some(30), None)), vec!["day-50"]);
+
+        // A partial backup doesn't count as a complete restore point
+        backups[0].metadata.partial = true;
+        assert!(pruned(&backups, &config(30, Some(30), None)).contains(&"day-40".to_string()));
+        assert!(!pruned(&backups, &config(30, Some(30), None)).contains(&"day-50".to_string()));
+    }
+
+    #[test]
+    fn test_parent_of_retained_incremental_is_kept() {
+        let mut backups = backups(&[0, 1, 45]);
+        backups[1].metadata.kind = BackupKind::Incremental;
+        backups[1].metadata.parent_backup_id = Some("day-45".to_string());
+        assert!(pruned(&backups, &config(30, Some(30), None)).is_empty());
+    }
+}
@@ -1,8 +1,11 @@
 // Automated backup scheduler
 
 use super::{BackupManager, BackupConfig, BackupError, Result};
+use crate::clock::{Clock, SystemClock};
 use chrono::{DateTime, Utc, Duration};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::{interval, Duration as TokioDuration, sleep};
@@ -17,6 +20,9 @@ pub struct BackupSchedule {
     pub last_backup: Option<DateTime<Utc>>,
     pub retry_attempts: u32,
     pub retry_delay_minutes: u64,
+    /// Scheduled backups are moved by a random offset of up to this many minutes either way
+    #[serde(default)]
+    pub jitter_minutes: u64,
 }
 
 impl Default for BackupSchedule {
@@ -28,10 +34,17 @@ impl Default for BackupSchedule {
             last_backup: None,
             retry_attempts: 3,
             retry_delay_minutes: 30,
+            jitter_minutes: 0,
         }
     }
 }
 
+/// Scheduler state persisted across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PersistedSchedulerState {
+    last_backup: Option<DateTime<Utc>>,
+}
+
 /// Backup scheduler status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SchedulerStatus {
@@ -41,6 +54,14 @@ pub enum SchedulerStatus {
     Error(String),
 }
 
+/// Scheduler status together with when the next backup is due
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerStatusReport {
+    pub status: SchedulerStatus,
+    pub next_backup_time: Option<DateTime<Utc>>,
+    pub last_backup_time: Option<DateTime<Utc>>,
+}
+
 /// Statistics for the backup scheduler
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchedulerStats {
@@ -73,13 +94,23 @@ pub struct BackupScheduler {
     status: Arc<RwLock<SchedulerStatus>>,
     stats: Arc<RwLock<SchedulerStats>>,
     task_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Held for the duration of every backup so shutdown can wait for one in flight
+    backup_lock: Arc<Mutex<()>>,
     event_log: Arc<RwLock<Vec<BackupEvent>>>,
     started_at: Option<DateTime<Utc>>,
+    clock: Arc<dyn Clock>,
+    state_path: Option<PathBuf>,
 }
 
 impl BackupScheduler {
     /// Create a new backup scheduler
     pub fn new(backup_manager: Arc<BackupManager>) -> Self {
+        Self::with_clock(backup_manager, Arc::new(SystemClock))
+    }
+
+    /// Create a backup scheduler that reads the time from `clock`
+    pub fn with_clock(backup_manager: Arc<BackupManager>, clock: Arc<dyn Clock>) -> Self {
+        let state_path = backup_manager.scheduler_state_path().ok();
         Self {
             backup_manager,
             schedule: Arc::new(RwLock::new(BackupSchedule::default())),
@@ -94,11 +125,19 @@ impl BackupScheduler {
                 uptime_hours: 0.0,
             })),
             task_handle: Arc::new(Mutex::new(None)),
+            backup_lock: Arc::new(Mutex::new(())),
             event_log: Arc::new(RwLock::new(Vec::new())),
             started_at: None,
+            clock,
+            state_path,
         }
     }
 
+    /// Override where the last successful backup time is persisted
+    pub fn set_state_path(&mut self, state_path: PathBuf) {
+        self.state_path = Some(state_path);
+    }
+
     /// Start the backup scheduler
     pub async fn start(&mut self) -> Result<()> {
         let mut task_handle = self.task_handle.lock().await;
@@ -110,14 +149,15 @@ impl BackupScheduler {
 
         // Update status
         *self.status.write().await = SchedulerStatus::Running;
-        self.started_at = Some(Utc::now());
+        self.started_at = Some(self.clock.now());
 
         // Log event
         self.log_event(BackupEvent::SchedulerStarted { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
-        // Calculate next backup time
+        // Pick up the last backup from before the restart; a missed backup runs on the first check
+        self.load_persisted_state().await;
         self.update_next_backup_time().await;
 
         // Start scheduler task
@@ -126,14 +166,17 @@ impl BackupScheduler {
         let status = Arc::clone(&self.status);
         let stats = Arc::clone(&self.stats);
         let event_log = Arc::clone(&self.event_log);
+        let backup_lock = Arc::clone(&self.backup_lock);
+        let clock = Arc::clone(&self.clock);
+        let state_path = self.state_path.clone();
 
         let handle = tokio::spawn(async move {
-            Self::scheduler_loop(backup_manager, schedule, status, stats, event_log).await;
+            Self::scheduler_loop(backup_manager, schedule, status, stats, event_log, backup_lock, clock, state_path).await;
         });
 
         *task_handle = Some(handle);
         
-        println!("Backup scheduler started");
+        tracing::info!("Backup scheduler started");
         Ok(())
     }
 
@@ -148,22 +191,42 @@ impl BackupScheduler {
         *self.status.write().await = SchedulerStatus::Stopped;
         
         self.log_event(BackupEvent::SchedulerStopped { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
-        println!("Backup scheduler stopped");
+        tracing::info!("Backup scheduler stopped");
         Ok(())
     }
 
+    /// Stop the scheduler for app exit, first giving a backup that is already running up to
+    /// `timeout` to finish. Returns false if it had to be cut off.
+    pub async fn shutdown(&mut self, timeout: TokioDuration) -> bool {
+        *self.status.write().await = SchedulerStatus::Stopped;
+
+        // Holding the lock also keeps the loop from starting another backup before it's aborted
+        let backup_guard = tokio::time::timeout(timeout, self.backup_lock.lock()).await;
+        let finished = backup_guard.is_ok();
+        if let Some(handle) = self.task_handle.lock().await.take() {
+            handle.abort();
+        }
+        drop(backup_guard);
+
+        self.log_event(BackupEvent::SchedulerStopped {
+            timestamp: self.clock.now()
+        }).await;
+
+        finished
+    }
+
     /// Pause the backup scheduler
     pub async fn pause(&self) -> Result<()> {
         *self.status.write().await = SchedulerStatus::Paused;
         
         self.log_event(BackupEvent::SchedulerPaused { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
-        println!("Backup scheduler paused");
+        tracing::info!("Backup scheduler paused");
         Ok(())
     }
 
@@ -172,12 +235,13 @@ impl BackupScheduler {
         *self.status.write().await = SchedulerStatus::Running;
         
         self.log_event(BackupEvent::SchedulerResumed { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
+        self.load_persisted_state().await;
         self.update_next_backup_time().await;
         
-        println!("Backup scheduler resumed");
+        tracing::info!("Backup scheduler resumed");
         Ok(())
     }
 
@@ -186,12 +250,12 @@ impl BackupScheduler {
         *self.schedule.write().await = new_schedule;
         
         self.log_event(BackupEvent::ConfigurationChanged { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
         self.update_next_backup_time().await;
         
-        println!("Backup schedule updated");
+        tracing::info!("Backup schedule updated");
         Ok(())
     }
 
@@ -200,6 +264,29 @@ impl BackupScheduler {
         self.status.read().await.clone()
     }
 
+    /// Get current scheduler status with the next and last backup times
+    pub async fn get_status_report(&self) -> SchedulerStatusReport {
+        let schedule = self.schedule.read().await;
+        SchedulerStatusReport {
+            status: self.status.read().await.clone(),
+            next_backup_time: schedule.next_backup,
+            last_backup_time: schedule.last_backup,
+        }
+    }
+
+    /// Run the scheduled backup if it is due. Returns whether a backup was attempted.
+    pub async fn run_pending(&self) -> bool {
+        Self::run_if_due(
+            &self.backup_manager,
+            &self.schedule,
+            &self.stats,
+            &self.event_log,
+            &self.backup_lock,
+            self.clock.as_ref(),
+            self.state_path.as_deref(),
+        ).await
+    }
+
     /// Get current backup schedule
     pub async fn get_schedule(&self) -> BackupSchedule {
         self.schedule.read().await.clone()
@@ -212,7 +299,7 @@ impl BackupScheduler {
         // Update uptime if running
         if let Some(started_at) = self.started_at {
             if matches!(stats.status, SchedulerStatus::Running) {
-                let uptime = Utc::now().signed_duration_since(started_at);
+                let uptime = self.clock.now().signed_duration_since(started_at);
                 stats.uptime_hours = uptime.num_milliseconds() as f64 / (1000.0 * 60.0 * 60.0);
             }
         }
@@ -233,8 +320,9 @@ impl BackupScheduler {
 
     /// Trigger an immediate backup
     pub async fn trigger_backup(&self, description: Option<String>) -> Result<()> {
+        let _backup_guard = self.backup_lock.lock().await;
         self.log_event(BackupEvent::BackupStarted { 
-            timestamp: Utc::now() 
+            timestamp: self.clock.now() 
         }).await;
 
         match self.backup_manager.create_backup(description).await {
@@ -251,6 +339,7 @@ impl BackupScheduler {
                     let mut schedule = self.schedule.write().await;
                     schedule.last_backup = Some(backup_info.metadata.created_at);
                 }
+                Self::persist_last_backup(self.state_path.as_deref(), backup_info.metadata.created_at).await;
 
                 self.log_event(BackupEvent::BackupCompleted {
                     timestamp: backup_info.metadata.created_at,
@@ -260,7 +349,7 @@ impl BackupScheduler {
 
                 self.update_next_backup_time().await;
                 
-                println!("Manual backup completed successfully");
+                tracing::info!("Manual backup completed successfully");
                 Ok(())
             }
             Err(e) => {
@@ -271,7 +360,7 @@ impl BackupScheduler {
                 }
 
                 self.log_event(BackupEvent::BackupFailed {
-                    timestamp: Utc::now(),
+                    timestamp: self.clock.now(),
                     error: e.to_string(),
                     retry_count: 0,
                 }).await;
@@ -283,12 +372,16 @@ impl BackupScheduler {
 
     // Private helper methods
 
+    #[allow(clippy::too_many_arguments)]
     async fn scheduler_loop(
         backup_manager: Arc<BackupManager>,
         schedule: Arc<RwLock<BackupSchedule>>,
         status: Arc<RwLock<SchedulerStatus>>,
         stats: Arc<RwLock<SchedulerStats>>,
         event_log: Arc<RwLock<Vec<BackupEvent>>>,
+        backup_lock: Arc<Mutex<()>>,
+        clock: Arc<dyn Clock>,
+        state_path: Option<PathBuf>,
     ) {
         let mut check_interval = interval(TokioDuration::from_secs(60)); // Check every minute
 
@@ -304,23 +397,40 @@ impl BackupScheduler {
                 SchedulerStatus::Running => {}
             }
 
-            // Check if backup is needed
-            let schedule_data = schedule.read().await.clone();
-            if !schedule_data.enabled {
-                continue;
-            }
+            Self::run_if_due(
+                &backup_manager,
+                &schedule,
+                &stats,
+                &event_log,
+                &backup_lock,
+                clock.as_ref(),
+                state_path.as_deref(),
+            ).await;
+        }
+    }
 
-            if let Some(next_backup) = schedule_data.next_backup {
-                if Utc::now() >= next_backup {
-                    // Time for a backup
-                    Self::perform_scheduled_backup(
-                        &backup_manager,
-                        &schedule,
-                        &stats,
-                        &event_log,
-                    ).await;
-                }
-            }
+    async fn run_if_due(
+        backup_manager: &Arc<BackupManager>,
+        schedule: &Arc<RwLock<BackupSchedule>>,
+        stats: &Arc<RwLock<SchedulerStats>>,
+        event_log: &Arc<RwLock<Vec<BackupEvent>>>,
+        backup_lock: &Mutex<()>,
+        clock: &dyn Clock,
+        state_path: Option<&Path>,
+    ) -> bool {
+        // Check if backup is needed
+        let schedule_data = schedule.read().await.clone();
+        if !schedule_data.enabled {
+            return false;
+        }
+
+        match schedule_data.next_backup {
+            Some(next_backup) if clock.now() >= next_backup => {
+                let _backup_guard = backup_lock.lock().await;
+                Self::perform_scheduled_backup(backup_manager, schedule, stats, event_log, clock, state_path).await;
+                true
+            },
+            _ => false,
         }
     }
 
@@ -329,6 +439,8 @@ impl BackupScheduler {
         schedule: &Arc<RwLock<BackupSchedule>>,
         stats: &Arc<RwLock<SchedulerStats>>,
         event_log: &Arc<RwLock<Vec<BackupEvent>>>,
+        clock: &dyn Clock,
+        state_path: Option<&Path>,
     ) {
         let mut retry_count = 0;
         let max_retries = schedule.read().await.retry_attempts;
@@ -337,7 +449,7 @@ impl BackupScheduler {
         // Log backup start
         Self::log_event_static(
             event_log,
-            BackupEvent::BackupStarted { timestamp: Utc::now() }
+            BackupEvent::BackupStarted { timestamp: clock.now() }
         ).await;
 
         // Update stats
@@ -350,20 +462,21 @@ impl BackupScheduler {
             match backup_manager.create_backup(Some("Scheduled backup".to_string())).await {
                 Ok(backup_info) => {
                     // Success - update stats and schedule
+                    let next_backup = {
+                        let mut schedule_data = schedule.write().await;
+                        schedule_data.last_backup = Some(backup_info.metadata.created_at);
+                        // Calculate next backup time
+                        schedule_data.next_backup = next_backup_time(&schedule_data, clock.now());
+                        schedule_data.next_backup
+                    };
+
                     {
                         let mut stats = stats.write().await;
                         stats.successful_backups += 1;
                         stats.last_backup_time = Some(backup_info.metadata.created_at);
+                        stats.next_backup_time = next_backup;
                     }
-
-                    {
-                        let mut schedule_data = schedule.write().await;
-                        schedule_data.last_backup = Some(backup_info.metadata.created_at);
-                        // Calculate next backup time
-                        schedule_data.next_backup = Some(
-                            backup_info.metadata.created_at + Duration::hours(schedule_data.interval_hours as i64)
-                        );
-                    }
+                    Self::persist_last_backup(state_path, backup_info.metadata.created_at).await;
 
                     Self::log_event_static(
                         event_log,
@@ -374,7 +487,7 @@ impl BackupScheduler {
                         }
                     ).await;
 
-                    println!("Scheduled backup completed successfully");
+                    tracing::info!("Scheduled backup completed successfully");
                     break;
                 }
                 Err(e) => {
@@ -383,7 +496,7 @@ impl BackupScheduler {
                     Self::log_event_static(
                         event_log,
                         BackupEvent::BackupFailed {
-                            timestamp: Utc::now(),
+                            timestamp: clock.now(),
                             error: e.to_string(),
                             retry_count,
                         }
@@ -400,15 +513,16 @@ impl BackupScheduler {
                             let mut schedule_data = schedule.write().await;
                             // Schedule next attempt in the normal interval
                             schedule_data.next_backup = Some(
-                                Utc::now() + Duration::hours(schedule_data.interval_hours as i64)
+                                clock.now() + Duration::hours(schedule_data.interval_hours as i64)
+                                    + jitter_offset(schedule_data.jitter_minutes)
                             );
                         }
 
-                        eprintln!("Scheduled backup failed after {} retries: {}", max_retries, e);
+                        tracing::error!("Scheduled backup failed after {} retries: {}", max_retries, e);
                         break;
                     } else {
                         // Wait before retry
-                        println!("Backup failed, retrying in {} minutes (attempt {}/{})", retry_delay, retry_count, max_retries);
+                        tracing::info!("Backup failed, retrying in {} minutes (attempt {}/{})", retry_delay, retry_count, max_retries);
                         sleep(TokioDuration::from_secs(retry_delay * 60)).await;
                     }
                 }
@@ -418,18 +532,44 @@ impl BackupScheduler {
 
     async fn update_next_backup_time(&self) {
         let mut schedule = self.schedule.write().await;
-        
-        if schedule.enabled {
-            let next_time = if let Some(last_backup) = schedule.last_backup {
-                last_backup + Duration::hours(schedule.interval_hours as i64)
-            } else {
-                // No previous backup, schedule for now + interval
-                Utc::now() + Duration::hours(schedule.interval_hours as i64)
-            };
-            
-            schedule.next_backup = Some(next_time);
-        } else {
-            schedule.next_backup = None;
+        schedule.next_backup = next_backup_time(&schedule, self.clock.now());
+        self.stats.write().await.next_backup_time = schedule.next_backup;
+    }
+
+    /// Adopt the persisted last backup time if it is newer than the one in memory
+    async fn load_persisted_state(&self) {
+        let Some(state_path) = &self.state_path else {
+            return;
+        };
+        let Ok(data) = tokio::fs::read(state_path).await else {
+            return;
+        };
+        let persisted: PersistedSchedulerState = match serde_json::from_slice(&data) {
+            Ok(persisted) => persisted,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable backup scheduler state {}: {}", state_path.display(), e);
+                return;
+            }
+        };
+
+        let mut schedule = self.schedule.write().await;
+        if persisted.last_backup > schedule.last_backup {
+            schedule.last_backup = persisted.last_backup;
+            self.stats.write().await.last_backup_time = persisted.last_backup;
+        }
+    }
+
+    async fn persist_last_backup(state_path: Option<&Path>, last_backup: DateTime<Utc>) {
+        let Some(state_path) = state_path else {
+            return;
+        };
+        let state = PersistedSchedulerState { last_backup: Some(last_backup) };
+        let result = match serde_json::to_vec(&state) {
+            Ok(data) => tokio::fs::write(state_path, data).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist backup scheduler state: {}", e);
         }
     }
 
@@ -448,9 +588,35 @@ impl BackupScheduler {
     }
 }
 
+/// When the next backup is due: immediately if more than an interval has passed since the last
+/// backup (the app was closed or asleep), otherwise one interval on, moved by the schedule's jitter
+fn next_backup_time(schedule: &BackupSchedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if !schedule.enabled {
+        return None;
+    }
+
+    let interval = Duration::hours(schedule.interval_hours as i64);
+    let jitter = jitter_offset(schedule.jitter_minutes);
+    Some(match schedule.last_backup {
+        Some(last_backup) if now - last_backup > interval => now,
+        Some(last_backup) => last_backup + interval + jitter,
+        None => now + interval + jitter,
+    })
+}
+
+/// Random offset of up to `jitter_minutes` either way
+fn jitter_offset(jitter_minutes: u64) -> Duration {
+    if jitter_minutes == 0 {
+        return Duration::zero();
+    }
+    let max_seconds = jitter_minutes as i64 * 60;
+    Duration::seconds(rand::thread_rng().gen_range(-max_seconds..=max_seconds))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
 
     #[test]
     fn test_backup_schedule_default() {
@@ -480,4 +646,172 @@ mod tests {
             _ => panic!("Event serialization failed"),
         }
     }
-}
\ No newline at end of file
+
+    /// Scheduler whose persisted state says the last backup ran at `last_backup`. The scheduler
+    /// and its backup manager both read the time from `clock`.
+    async fn scheduler_after_downtime(
+        app_handle: tauri::AppHandle,
+        clock: Arc<MockClock>,
+        last_backup: DateTime<Utc>,
+    ) -> (BackupScheduler, PathBuf) {
+        let work_dir = std::env::temp_dir().join(format!("case-crafter-scheduler-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&work_dir).unwrap();
+
+        let database_manager = Arc::new(crate::database::DatabaseManager::in_memory().await.unwrap());
+        let mut backup_manager = BackupManager::new(app_handle, database_manager, None);
+        backup_manager.set_config(BackupConfig {
+            encrypt: false,
+            backup_directory: work_dir.join("backups"),
+            ..BackupConfig::default()
+        });
+        backup_manager.set_clock(clock.clone());
+
+        let state_path = work_dir.join("scheduler.json");
+        let state = PersistedSchedulerState { last_backup: Some(last_backup) };
+        std::fs::write(&state_path, serde_json::to_vec(&state).unwrap()).unwrap();
+
+        let mut scheduler = BackupScheduler::with_clock(Arc::new(backup_manager), clock);
+        scheduler.set_state_path(state_path);
+        (scheduler, work_dir)
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_backup_runs_after_missed_interval() {
+        let app = tauri::test::mock_app();
+        let now = Utc::now();
+        let last_backup = now - Duration::hours(30);
+        let (scheduler, work_dir) = scheduler_after_downtime(app.handle().clone(), Arc::new(MockClock::new(now)), last_backup).await;
+
+        scheduler.resume().await.unwrap();
+        let report = scheduler.get_status_report().await;
+        assert_eq!(report.last_backup_time, Some(last_backup));
+        assert_eq!(report.next_backup_time, Some(now));
+
+        assert!(scheduler.run_pending().await);
+        assert_eq!(scheduler.get_stats().await.successful_backups, 1);
+
+        let persisted: PersistedSchedulerState =
+            serde_json::from_slice(&std::fs::read(work_dir.join("scheduler.json")).unwrap()).unwrap();
+        assert!(persisted.last_backup.unwrap() > last_backup);
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_catch_up_within_interval() {
+        let app = tauri::test::mock_app();
+        let now = Utc::now();
+        let last_backup = now - Duration::hours(2);
+        let (scheduler, work_dir) = scheduler_after_downtime(app.handle().clone(), Arc::new(MockClock::new(now)), last_backup).await;
+
+        scheduler.resume().await.unwrap();
+        let report = scheduler.get_status_report().await;
+        assert_eq!(report.next_backup_time, Some(last_backup + Duration::hours(24)));
+
+        assert!(!scheduler.run_pending().await);
+        assert_eq!(scheduler.get_stats().await.successful_backups, 0);
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backup_runs_once_clock_reaches_interval() {
+        let app = tauri::test::mock_app();
+        let start = Utc::now();
+        let clock = Arc::new(MockClock::new(start));
+        let last_backup = start - Duration::hours(2);
+        let (scheduler, work_dir) = scheduler_after_downtime(app.handle().clone(), clock.clone(), last_backup).await;
+        scheduler.resume().await.unwrap();
+
+        clock.advance(Duration::hours(21) + Duration::minutes(59));
+        assert!(!scheduler.run_pending().await);
+
+        clock.advance(Duration::minutes(1));
+        assert!(scheduler.run_pending().await);
+        let backed_up_at = start + Duration::hours(22);
+        let report = scheduler.get_status_report().await;
+        assert_eq!(report.last_backup_time, Some(backed_up_at));
+        assert_eq!(report.next_backup_time, Some(backed_up_at + Duration::hours(24)));
+
+        // Nothing more is due until a full interval has passed on the clock
+        clock.advance(Duration::hours(23));
+        assert!(!scheduler.run_pending().await);
+        clock.advance(Duration::hours(1));
+        assert!(scheduler.run_pending().await);
+        assert_eq!(scheduler.get_stats().await.successful_backups, 2);
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[test]
+    fn test_jitter_stays_within_window() {
+        let now = Utc::now();
+        let last_backup = now - Duration::hours(1);
+        let schedule = BackupSchedule {
+            last_backup: Some(last_backup),
+            jitter_minutes: 15,
+            ..BackupSchedule::default()
+        };
+
+        let due = last_backup + Duration::hours(24);
+        for _ in 0..50 {
+            let next = next_backup_time(&schedule, now).unwrap();
+            assert!(next >= due - Duration::minutes(15) && next <= due + Duration::minutes(15));
+        }
+
+        let disabled = BackupSchedule { enabled: false, ..schedule };
+        assert_eq!(next_backup_time(&disabled, now), None);
+    }
+
+    /// Hold the scheduler's backup lock for `duration`, as a running backup would.
+    /// Resolves once the lock is held; the returned flag is set when the "backup" finishes.
+    async fn simulate_backup(scheduler: &BackupScheduler, duration: TokioDuration) -> Arc<std::sync::atomic::AtomicBool> {
+        let lock = Arc::clone(&scheduler.backup_lock);
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let (locked_tx, locked_rx) = tokio::sync::oneshot::channel();
+        let done = Arc::clone(&finished);
+        tokio::spawn(async move {
+            let _guard = lock.lock().await;
+            locked_tx.send(()).unwrap();
+            sleep(duration).await;
+            done.store(true, std::sync::atomic::Ordering::SeqCst);
+        });
+        locked_rx.await.unwrap();
+        finished
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_running_backup() {
+        let app = tauri::test::mock_app();
+        let now = Utc::now();
+        let (mut scheduler, work_dir) = scheduler_after_downtime(app.handle().clone(), Arc::new(MockClock::new(now)), now - Duration::hours(2)).await;
+        scheduler.start().await.unwrap();
+
+        let finished = simulate_backup(&scheduler, TokioDuration::from_millis(150)).await;
+        assert!(scheduler.shutdown(TokioDuration::from_secs(5)).await);
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+
+        assert!(matches!(scheduler.get_status().await, SchedulerStatus::Stopped));
+        assert!(scheduler.task_handle.lock().await.is_none());
+        assert!(matches!(scheduler.get_recent_events(1).await[..], [BackupEvent::SchedulerStopped { .. }]));
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_gives_up_after_timeout() {
+        let app = tauri::test::mock_app();
+        let now = Utc::now();
+        let (mut scheduler, work_dir) = scheduler_after_downtime(app.handle().clone(), Arc::new(MockClock::new(now)), now - Duration::hours(2)).await;
+        scheduler.start().await.unwrap();
+
+        let finished = simulate_backup(&scheduler, TokioDuration::from_secs(30)).await;
+        assert!(!scheduler.shutdown(TokioDuration::from_millis(50)).await);
+        assert!(!finished.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(matches!(scheduler.get_status().await, SchedulerStatus::Stopped));
+        assert!(scheduler.task_handle.lock().await.is_none());
+
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+}
+
@@ -2,29 +2,62 @@
 
 use super::manager::CaseStudyManager;
 use super::models::*;
+use super::export::{ExportFormat, ExportOptions};
+use super::readability::Readability;
+use super::version_control::VersionDiff;
 use super::{CaseStudyError, Result as CaseStudyResult};
 use crate::database::DatabaseManager;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
 
-type CaseStudyManagerState = Arc<RwLock<Option<CaseStudyManager>>>;
+pub type CaseStudyManagerState = Arc<RwLock<Option<CaseStudyManager>>>;
 
-/// Initialize case study manager state
-pub fn setup_case_study_manager_state(db: DatabaseManager) -> CaseStudyManagerState {
-    let manager = CaseStudyManager::new(db);
+/// Initialize case study manager state. Relative attachment paths are resolved against
+/// `attachments_root` when attachments are permanently deleted.
+pub fn setup_case_study_manager_state(db: DatabaseManager, attachments_root: Option<PathBuf>) -> CaseStudyManagerState {
+    let mut manager = CaseStudyManager::new(db);
+    if let Some(root) = attachments_root {
+        manager.set_attachments_root(root);
+    }
     Arc::new(RwLock::new(Some(manager)))
 }
 
-/// Create a new case study
+/// Create a new case study. Fails with a possible-duplicate error when it closely
+/// resembles an existing case study, unless `allow_duplicate` is set.
 #[tauri::command]
 pub async fn create_case_study(
     new_case_study: NewCaseStudy,
+    allow_duplicate: Option<bool>,
     manager_state: State<'_, CaseStudyManagerState>,
 ) -> Result<CaseStudy, String> {
     let manager_lock = manager_state.read().await;
     if let Some(manager) = manager_lock.as_ref() {
-        manager.create_case_study(new_case_study).await.map_err(|e| e.to_string())
+        manager
+            .create_case_study_checked(new_case_study, allow_duplicate.unwrap_or(false))
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Find existing case studies similar to a draft's title and content
+#[tauri::command]
+pub async fn find_similar_case_studies(
+    title: String,
+    content: String,
+    threshold: Option<f64>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Vec<SimilarCaseStudy>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let threshold = threshold.unwrap_or(super::similarity::DEFAULT_SIMILARITY_THRESHOLD);
+        manager
+            .find_similar_case_studies(&title, &content, threshold)
+            .await
+            .map_err(|e| e.to_string())
     } else {
         Err("Case study manager not initialized".to_string())
     }
@@ -44,6 +77,20 @@ pub async fn get_case_study(
     }
 }
 
+/// Get word count, sentence count and reading grade level for a case study
+#[tauri::command]
+pub async fn get_case_study_readability(
+    id: String,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Option<Readability>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.get_case_study_readability(&id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
 /// Update case study
 #[tauri::command]
 pub async fn update_case_study(
@@ -62,12 +109,76 @@ pub async fn update_case_study(
 /// Delete case study
 #[tauri::command]
 pub async fn delete_case_study(
+    id: String,
+    deleted_by: Option<String>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<bool, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.delete_case_study(&id, deleted_by.as_deref()).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Move case studies to the trash, or with `permanent` delete them along with their
+/// questions, progress, collection links and attachments
+#[tauri::command]
+pub async fn bulk_delete_case_studies(
+    ids: Vec<String>,
+    permanent: Option<bool>,
+    deleted_by: Option<String>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<BulkDeleteReport, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager
+            .bulk_delete_case_studies(&ids, permanent.unwrap_or(false), deleted_by.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Restore a deleted case study
+#[tauri::command]
+pub async fn restore_deleted_case_study(
+    id: String,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Option<CaseStudy>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.restore_deleted_case_study(&id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Permanently remove a deleted case study
+#[tauri::command]
+pub async fn purge_case_study(
     id: String,
     manager_state: State<'_, CaseStudyManagerState>,
 ) -> Result<bool, String> {
     let manager_lock = manager_state.read().await;
     if let Some(manager) = manager_lock.as_ref() {
-        manager.delete_case_study(&id).await.map_err(|e| e.to_string())
+        manager.purge_case_study(&id).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// List deleted case studies
+#[tauri::command]
+pub async fn list_deleted_case_studies(
+    limit: Option<i32>,
+    offset: Option<i32>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Vec<CaseStudy>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.list_deleted_case_studies(limit.unwrap_or(20), offset.unwrap_or(0)).await.map_err(|e| e.to_string())
     } else {
         Err("Case study manager not initialized".to_string())
     }
@@ -107,6 +218,84 @@ pub async fn search_case_studies(
     }
 }
 
+/// Search case studies with relevance ranks and highlighted snippets
+#[tauri::command]
+pub async fn search_case_studies_ranked(
+    query: CaseStudySearchQuery,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Vec<CaseStudySearchHit>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.search_case_studies_ranked(query).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// List case studies with cursor pagination; pass the previous page's `next_cursor` to continue
+#[tauri::command]
+pub async fn list_case_studies_page(
+    filter: Option<CaseStudyFilter>,
+    limit: Option<i32>,
+    cursor: Option<String>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<CaseStudyPage, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager
+            .list_case_studies_page(filter.unwrap_or_default(), PageRequest { limit, cursor })
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Search case studies with cursor pagination
+#[tauri::command]
+pub async fn search_case_studies_page(
+    query: String,
+    filter: Option<CaseStudyFilter>,
+    limit: Option<i32>,
+    cursor: Option<String>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<CaseStudyPage, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager
+            .search_case_studies_page(&query, filter.unwrap_or_default(), PageRequest { limit, cursor })
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Export a case study to a file in the given format
+#[tauri::command]
+pub async fn export_case_study(
+    case_study_id: String,
+    format: ExportFormat,
+    include_sample_solution: Option<bool>,
+    output_path: String,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<String, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let options = ExportOptions {
+            format,
+            include_sample_solution: include_sample_solution.unwrap_or(false),
+        };
+        manager
+            .export_case_study(&case_study_id, options, &PathBuf::from(output_path))
+            .await
+            .map(|path| path.display().to_string())
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
 /// Publish case study
 #[tauri::command]
 pub async fn publish_case_study(
@@ -149,6 +338,21 @@ pub async fn restore_case_study(
     }
 }
 
+/// Change the status of many case studies at once; all or nothing
+#[tauri::command]
+pub async fn bulk_update_case_study_status(
+    ids: Vec<String>,
+    status: CaseStudyStatus,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<BulkStatusUpdateReport, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.bulk_update_status(ids, status).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
 /// Duplicate case study
 #[tauri::command]
 pub async fn duplicate_case_study(
@@ -193,6 +397,22 @@ pub async fn get_case_study_version(
     }
 }
 
+/// Diff two versions of a case study field by field
+#[tauri::command]
+pub async fn diff_case_study_versions(
+    case_study_id: String,
+    from_version: i32,
+    to_version: i32,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<VersionDiff, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.diff_case_study_versions(&case_study_id, from_version, to_version).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
 /// Restore case study to specific version
 #[tauri::command]
 pub async fn restore_to_version(
@@ -236,6 +456,38 @@ pub async fn get_recent_case_studies(
     }
 }
 
+/// List tags in use with their case study counts, for autocomplete
+#[tauri::command]
+pub async fn list_case_study_tags(
+    prefix: Option<String>,
+    limit: Option<i32>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<Vec<TagUsage>, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        let limit = limit.unwrap_or(50);
+        manager.list_tags_with_counts(prefix.as_deref(), limit).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
+/// Merge one tag into another across all case studies, returning how many were rewritten
+#[tauri::command]
+pub async fn merge_case_study_tags(
+    from: String,
+    to: String,
+    merged_by: Option<String>,
+    manager_state: State<'_, CaseStudyManagerState>,
+) -> Result<u64, String> {
+    let manager_lock = manager_state.read().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        manager.merge_tags(&from, &to, merged_by.as_deref()).await.map_err(|e| e.to_string())
+    } else {
+        Err("Case study manager not initialized".to_string())
+    }
+}
+
 /// Get case studies by category
 #[tauri::command]
 pub async fn get_case_studies_by_category(
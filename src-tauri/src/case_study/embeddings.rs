@@ -0,0 +1,68 @@
+// Embedding vectors for semantic case study search
+
+use super::models::CaseStudy;
+
+/// Upper bound on characters sent to the embedding model per case study, well
+/// inside the input limits of the supported embedding models
+const MAX_EMBEDDING_CHARS: usize = 8000;
+
+/// Text embedded for a case study: the fields that describe what it is about
+pub fn embedding_text(case_study: &CaseStudy) -> String {
+    let mut parts = vec![case_study.title.as_str(), case_study.industry.as_str()];
+    parts.extend(case_study.description.as_deref());
+    parts.extend(case_study.summary.as_deref());
+    parts.push(&case_study.content);
+
+    let text = parts.join("\n\n");
+    match text.char_indices().nth(MAX_EMBEDDING_CHARS) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text,
+    }
+}
+
+/// Cosine similarity of two vectors; 0.0 when either is all zeros or the lengths differ
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (dot, norm_a, norm_b) = a.iter().zip(b).fold((0.0f32, 0.0f32, 0.0f32), |(dot, na, nb), (x, y)| {
+        (dot + x * y, na + x * x, nb + y * y)
+    });
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Serialize a vector as little-endian f32 bytes for BLOB storage
+pub fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Inverse of `encode_embedding`
+pub fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding_round_trips() {
+        let embedding = vec![0.25, -1.5, 3.0e-7, 42.0];
+        assert_eq!(decode_embedding(&encode_embedding(&embedding)), embedding);
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 1.0], &[1.0, 0.0]) - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+}
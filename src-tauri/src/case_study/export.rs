@@ -0,0 +1,405 @@
+// Export of case studies to shareable documents (Markdown, HTML, PDF)
+
+use super::models::CaseStudy;
+use super::{CaseStudyError, Result};
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Custom metadata fields that hold the structured sections of a case study
+pub const PROBLEM_STATEMENT_FIELD: &str = "problem_statement";
+pub const ANALYSIS_FRAMEWORK_FIELD: &str = "analysis_framework";
+pub const SAMPLE_SOLUTION_FIELD: &str = "sample_solution";
+
+/// Output format for exported case studies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Options controlling what goes into an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub include_sample_solution: bool,
+}
+
+/// A titled section of an exported document
+#[derive(Debug, Clone)]
+pub struct ExportSection {
+    pub heading: String,
+    pub body: String,
+}
+
+/// Format-independent layout of an exported case study
+#[derive(Debug, Clone)]
+pub struct ExportDocument {
+    pub title: String,
+    pub description: Option<String>,
+    pub details: Vec<(String, String)>,
+    pub learning_objectives: Vec<String>,
+    pub sections: Vec<ExportSection>,
+}
+
+impl ExportDocument {
+    /// Assemble the document: background, problem statement, case content, analysis
+    /// framework and, when requested, the sample solution. Empty sections are left out.
+    pub fn from_case_study(case_study: &CaseStudy, include_sample_solution: bool) -> Self {
+        let custom_text = |field: &str| {
+            case_study
+                .metadata
+                .get_custom_field(field)
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+
+        let mut candidates = vec![
+            ("Background", case_study.summary.clone()),
+            ("Problem Statement", custom_text(PROBLEM_STATEMENT_FIELD)),
+            ("Case", Some(case_study.content.clone())),
+            ("Analysis Framework", custom_text(ANALYSIS_FRAMEWORK_FIELD)),
+        ];
+        if include_sample_solution {
+            candidates.push(("Sample Solution", custom_text(SAMPLE_SOLUTION_FIELD)));
+        }
+
+        let sections = candidates
+            .into_iter()
+            .filter_map(|(heading, body)| {
+                body.filter(|b| !b.trim().is_empty()).map(|body| ExportSection {
+                    heading: heading.to_string(),
+                    body: body.trim().to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            title: case_study.title.clone(),
+            description: case_study.description.clone().filter(|d| !d.trim().is_empty()),
+            details: vec![
+                ("Industry".to_string(), case_study.industry.clone()),
+                ("Difficulty".to_string(), case_study.difficulty_level.clone()),
+                ("Duration".to_string(), format!("{} minutes", case_study.duration_minutes)),
+            ],
+            learning_objectives: case_study.learning_objectives.clone(),
+            sections,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title);
+
+        if let Some(description) = &self.description {
+            out.push_str(&format!("*{}*\n\n", description));
+        }
+
+        let details: Vec<String> = self
+            .details
+            .iter()
+            .map(|(label, value)| format!("**{}:** {}", label, value))
+            .collect();
+        out.push_str(&details.join(" · "));
+        out.push_str("\n\n");
+
+        if !self.learning_objectives.is_empty() {
+            out.push_str("## Learning Objectives\n\n");
+            for objective in &self.learning_objectives {
+                out.push_str(&format!("- {}\n", objective));
+            }
+            out.push('\n');
+        }
+
+        for section in &self.sections {
+            out.push_str(&format!("## {}\n\n{}\n\n", section.heading, section.body));
+        }
+
+        out.trim_end().to_string() + "\n"
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut body = format!("<h1>{}</h1>\n", escape_html(&self.title));
+
+        if let Some(description) = &self.description {
+            body.push_str(&format!("<p class=\"description\"><em>{}</em></p>\n", escape_html(description)));
+        }
+
+        body.push_str("<ul class=\"details\">\n");
+        for (label, value) in &self.details {
+            body.push_str(&format!("<li><strong>{}:</strong> {}</li>\n", escape_html(label), escape_html(value)));
+        }
+        body.push_str("</ul>\n");
+
+        if !self.learning_objectives.is_empty() {
+            body.push_str("<h2>Learning Objectives</h2>\n<ul>\n");
+            for objective in &self.learning_objectives {
+                body.push_str(&format!("<li>{}</li>\n", escape_html(objective)));
+            }
+            body.push_str("</ul>\n");
+        }
+
+        for section in &self.sections {
+            body.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.heading)));
+            for paragraph in paragraphs(&section.body) {
+                body.push_str(&format!("<p>{}</p>\n", escape_html(&paragraph).replace('\n', "<br>\n")));
+            }
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+             <style>body {{ font-family: Georgia, serif; max-width: 48rem; margin: 2rem auto; line-height: 1.5; }}\n\
+             .details {{ list-style: none; padding: 0; color: #555; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(&self.title),
+            body
+        )
+    }
+
+    /// Lay the document out on A4 pages using the built-in Helvetica fonts
+    pub fn to_pdf(&self) -> Result<Vec<u8>> {
+        let (doc, page, layer) = PdfDocument::new(&self.title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Layer 1");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(pdf_error)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(pdf_error)?;
+
+        let mut writer = PdfWriter {
+            layer: doc.get_page(page).get_layer(layer),
+            doc: &doc,
+            y: PAGE_HEIGHT_MM - MARGIN_MM,
+        };
+
+        writer.write_wrapped(&self.title, 20.0, &bold);
+        if let Some(description) = &self.description {
+            writer.write_wrapped(description, 11.0, &regular);
+        }
+        let details: Vec<String> = self.details.iter().map(|(l, v)| format!("{}: {}", l, v)).collect();
+        writer.write_wrapped(&details.join("   "), 10.0, &regular);
+        writer.gap();
+
+        if !self.learning_objectives.is_empty() {
+            writer.write_wrapped("Learning Objectives", 14.0, &bold);
+            for objective in &self.learning_objectives {
+                writer.write_wrapped(&format!("- {}", objective), 11.0, &regular);
+            }
+            writer.gap();
+        }
+
+        for section in &self.sections {
+            writer.write_wrapped(&section.heading, 14.0, &bold);
+            for paragraph in paragraphs(&section.body) {
+                writer.write_wrapped(&paragraph, 11.0, &regular);
+                writer.gap();
+            }
+        }
+
+        doc.save_to_bytes().map_err(pdf_error)
+    }
+
+    /// Render in the requested format
+    pub fn render(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        match format {
+            ExportFormat::Markdown => Ok(self.to_markdown().into_bytes()),
+            ExportFormat::Html => Ok(self.to_html().into_bytes()),
+            ExportFormat::Pdf => self.to_pdf(),
+        }
+    }
+}
+
+/// Write a case study to `output_path`, adding the format's extension if the path has none
+pub fn export_case_study(case_study: &CaseStudy, options: &ExportOptions, output_path: &Path) -> Result<PathBuf> {
+    let document = ExportDocument::from_case_study(case_study, options.include_sample_solution);
+    let bytes = document.render(options.format)?;
+
+    let path = if output_path.extension().is_none() {
+        output_path.with_extension(options.format.extension())
+    } else {
+        output_path.to_path_buf()
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, bytes)?;
+
+    Ok(path)
+}
+
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 20.0;
+const POINT_TO_MM: f32 = 0.3528;
+
+/// Cursor that flows lines of text down the page, starting new pages as needed
+struct PdfWriter<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfWriter<'_> {
+    fn write_wrapped(&mut self, text: &str, font_size: f32, font: &IndirectFontRef) {
+        // Helvetica averages roughly half an em per character
+        let usable_width_pt = (PAGE_WIDTH_MM - 2.0 * MARGIN_MM) / POINT_TO_MM;
+        let max_chars = (usable_width_pt / (font_size * 0.5)) as usize;
+        let line_height = font_size * POINT_TO_MM * 1.4;
+
+        for line in text.lines().flat_map(|l| wrap_line(l, max_chars)) {
+            if self.y - line_height < MARGIN_MM {
+                let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Layer 1");
+                self.layer = self.doc.get_page(page).get_layer(layer);
+                self.y = PAGE_HEIGHT_MM - MARGIN_MM;
+            }
+            self.y -= line_height;
+            self.layer.use_text(line, font_size, Mm(MARGIN_MM), Mm(self.y), font);
+        }
+    }
+
+    fn gap(&mut self) {
+        self.y -= 3.0;
+    }
+}
+
+/// Greedy word wrap to at most `max_chars` characters per line
+fn wrap_line(line: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in line.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// Split text into paragraphs on blank lines
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn pdf_error(e: printpdf::Error) -> CaseStudyError {
+    CaseStudyError::ExportError(format!("Failed to build PDF: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case_study::models::{CaseStudyMetadata, CaseStudyStatus};
+    use chrono::Utc;
+
+    fn sample_case_study() -> CaseStudy {
+        let mut metadata = CaseStudyMetadata::default();
+        metadata.add_custom_field(PROBLEM_STATEMENT_FIELD.to_string(), serde_json::json!("Should the firm enter Brazil?"));
+        metadata.add_custom_field(ANALYSIS_FRAMEWORK_FIELD.to_string(), serde_json::json!("Use Porter's Five Forces."));
+        metadata.add_custom_field(SAMPLE_SOLUTION_FIELD.to_string(), serde_json::json!("Enter through a joint venture."));
+
+        CaseStudy {
+            id: "cs-1".to_string(),
+            title: "Market Entry for Solis Energy".to_string(),
+            description: Some("A solar manufacturer weighs expansion.".to_string()),
+            content: "Solis Energy makes residential panels.\n\nThe board meets next week.".to_string(),
+            summary: Some("Founded in 2009 in Lisbon.".to_string()),
+            status: CaseStudyStatus::Published,
+            category_id: None,
+            industry: "Energy".to_string(),
+            difficulty_level: "advanced".to_string(),
+            duration_minutes: 90,
+            word_count: 12,
+            learning_objectives: vec!["Market analysis".to_string(), "Risk assessment".to_string()],
+            tags: vec!["market-entry".to_string()],
+            metadata,
+            version: 1,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            published_at: None,
+            archived_at: None,
+        }
+    }
+
+    fn headings(markdown: &str) -> Vec<&str> {
+        markdown.lines().filter(|l| l.starts_with('#')).collect()
+    }
+
+    #[test]
+    fn test_markdown_structure() {
+        let markdown = ExportDocument::from_case_study(&sample_case_study(), true).to_markdown();
+
+        assert_eq!(
+            headings(&markdown),
+            vec![
+                "# Market Entry for Solis Energy",
+                "## Learning Objectives",
+                "## Background",
+                "## Problem Statement",
+                "## Case",
+                "## Analysis Framework",
+                "## Sample Solution",
+            ]
+        );
+        assert!(markdown.contains("**Industry:** Energy · **Difficulty:** advanced · **Duration:** 90 minutes"));
+        assert!(markdown.contains("- Risk assessment\n"));
+        assert!(markdown.contains("Solis Energy makes residential panels.\n\nThe board meets next week."));
+    }
+
+    #[test]
+    fn test_sample_solution_excluded_by_default_option() {
+        let markdown = ExportDocument::from_case_study(&sample_case_study(), false).to_markdown();
+
+        assert!(!headings(&markdown).contains(&"## Sample Solution"));
+        assert!(!markdown.contains("joint venture"));
+    }
+
+    #[test]
+    fn test_html_escapes_content() {
+        let mut case_study = sample_case_study();
+        case_study.title = "R&D <Strategy>".to_string();
+
+        let html = ExportDocument::from_case_study(&case_study, false).to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>R&amp;D &lt;Strategy&gt;</h1>"));
+        assert!(html.contains("<p>The board meets next week.</p>"));
+    }
+
+    #[test]
+    fn test_pdf_export_writes_valid_file() {
+        let mut case_study = sample_case_study();
+        case_study.content = "The board reviews the proposal in detail. ".repeat(400);
+        let output = std::env::temp_dir().join(format!("case-study-export-{}", uuid::Uuid::new_v4()));
+
+        let options = ExportOptions { format: ExportFormat::Pdf, include_sample_solution: true };
+        let path = export_case_study(&case_study, &options, &output).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(path.extension().unwrap(), "pdf");
+        assert!(bytes.len() > 1000);
+        assert!(bytes.starts_with(b"%PDF-"));
+    }
+}
@@ -3,9 +3,13 @@
 use super::models::*;
 use super::repository::CaseStudyRepository;
 use super::search::CaseStudySearchEngine;
-use super::version_control::CaseStudyVersionControl;
+use super::similarity::{Fingerprint, DEFAULT_SIMILARITY_THRESHOLD};
+use super::version_control::{CaseStudyVersionControl, VersionDiff};
+use super::export::{self, ExportOptions};
+use super::readability::{self, Readability};
 use super::{CaseStudyError, Result};
-use crate::database::DatabaseManager;
+use crate::database::{DatabaseManager, StatsCache};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -14,6 +18,9 @@ pub struct CaseStudyManager {
     repository: CaseStudyRepository,
     search_engine: CaseStudySearchEngine,
     version_control: CaseStudyVersionControl,
+    statistics: StatsCache<CaseStudyStatistics>,
+    /// Directory relative attachment paths are resolved against
+    attachments_root: Option<PathBuf>,
 }
 
 impl CaseStudyManager {
@@ -26,28 +33,101 @@ impl CaseStudyManager {
             repository,
             search_engine,
             version_control,
+            statistics: StatsCache::default(),
+            attachments_root: None,
         }
     }
 
-    /// Create a new case study
+    /// Set the directory relative attachment file paths are resolved against, normally the
+    /// app data directory; without it only absolute paths are removed on a permanent delete
+    pub fn set_attachments_root(&mut self, root: PathBuf) {
+        self.attachments_root = Some(root);
+    }
+
+    /// Set how long idempotency keys from `NewCaseStudy` are remembered
+    pub fn set_idempotency_window(&mut self, window: chrono::Duration) {
+        self.repository.set_idempotency_window(window);
+    }
+
+    /// Create a new case study. A repeat of an earlier create with the same idempotency
+    /// key returns the case study that create made.
     pub async fn create_case_study(&self, new_case_study: NewCaseStudy) -> Result<CaseStudy> {
         // Validate the case study data
         self.validate_case_study_data(&new_case_study)?;
 
         // Create the case study
-        let case_study = self.repository.create(new_case_study).await?;
+        let (case_study, created) = self.repository.create_or_replay(new_case_study).await?;
+        if !created {
+            return Ok(case_study);
+        }
+        self.statistics.invalidate();
 
         // Index for search
         self.search_engine.index_case_study(&case_study).await?;
 
         // Create initial version
-        self.version_control.create_version(&case_study, "Initial version", None).await?;
+        self.version_control.create_version(&case_study, "Initial version").await?;
 
         Ok(case_study)
     }
 
+    /// Create a new case study unless it closely resembles an existing one. Pass
+    /// `allow_duplicate` to create it regardless.
+    pub async fn create_case_study_checked(&self, new_case_study: NewCaseStudy, allow_duplicate: bool) -> Result<CaseStudy> {
+        // A retried create would otherwise look like a duplicate of its own first attempt
+        if let Some(key) = &new_case_study.idempotency_key {
+            if let Some(existing) = self.repository.find_by_idempotency_key(key).await? {
+                return Ok(existing);
+            }
+        }
+
+        if !allow_duplicate {
+            let similar = self
+                .find_similar_case_studies(&new_case_study.title, &new_case_study.content, DEFAULT_SIMILARITY_THRESHOLD)
+                .await?;
+            if !similar.is_empty() {
+                return Err(CaseStudyError::PossibleDuplicate(similar));
+            }
+        }
+        self.create_case_study(new_case_study).await
+    }
+
+    /// Existing case studies at least `threshold` similar to the given title and content,
+    /// most similar first
+    pub async fn find_similar_case_studies(&self, title: &str, content: &str, threshold: f64) -> Result<Vec<SimilarCaseStudy>> {
+        let draft = Fingerprint::new(title, content);
+        let mut similar: Vec<SimilarCaseStudy> = self
+            .repository
+            .list_titles_and_content()
+            .await?
+            .into_iter()
+            .filter_map(|(id, title, content)| {
+                let similarity = draft.compare(&Fingerprint::new(&title, &content));
+                (similarity.combined >= threshold).then(|| SimilarCaseStudy {
+                    id,
+                    title,
+                    title_similarity: similarity.title,
+                    content_similarity: similarity.content,
+                    similarity: similarity.combined,
+                })
+            })
+            .collect();
+        similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        Ok(similar)
+    }
+
     /// Update an existing case study
     pub async fn update_case_study(&self, id: &str, update: UpdateCaseStudy) -> Result<Option<CaseStudy>> {
+        self.update_case_study_with_summary(id, update, "Content updated").await
+    }
+
+    /// Update an existing case study, describing the change in the new version with `changes_summary`
+    pub async fn update_case_study_with_summary(
+        &self,
+        id: &str,
+        update: UpdateCaseStudy,
+        changes_summary: &str,
+    ) -> Result<Option<CaseStudy>> {
         // Check if case study exists and is editable
         let current = match self.repository.find_by_id(id).await? {
             Some(case_study) => case_study,
@@ -65,6 +145,7 @@ impl CaseStudyManager {
 
         // Update the case study
         let updated = self.repository.update(id, update).await?;
+        self.statistics.invalidate();
 
         if let Some(ref case_study) = updated {
             // Update search index
@@ -72,7 +153,7 @@ impl CaseStudyManager {
 
             // Create new version if content changed
             if case_study.version > current.version {
-                self.version_control.create_version(case_study, "Content updated", Some(&current)).await?;
+                self.version_control.create_version(case_study, changes_summary).await?;
             }
         }
 
@@ -84,8 +165,28 @@ impl CaseStudyManager {
         self.repository.find_by_id(id).await
     }
 
+    /// Readability statistics for a case study, computed on the fly for case studies
+    /// stored before they were recorded
+    pub async fn get_case_study_readability(&self, id: &str) -> Result<Option<Readability>> {
+        let case_study = self.repository.find_by_id(id).await?;
+        Ok(case_study.map(|case_study| {
+            case_study
+                .metadata
+                .readability
+                .unwrap_or_else(|| readability::analyze(&case_study.content))
+        }))
+    }
+
+    /// Export a case study to a Markdown, HTML or PDF file, returning the written path
+    pub async fn export_case_study(&self, id: &str, options: ExportOptions, output_path: &Path) -> Result<PathBuf> {
+        let case_study = self.repository.find_by_id(id).await?
+            .ok_or_else(|| CaseStudyError::NotFound(id.to_string()))?;
+
+        export::export_case_study(&case_study, &options, output_path)
+    }
+
     /// Delete case study (soft delete)
-    pub async fn delete_case_study(&self, id: &str) -> Result<bool> {
+    pub async fn delete_case_study(&self, id: &str, deleted_by: Option<&str>) -> Result<bool> {
         // Check if case study exists
         let case_study = match self.repository.find_by_id(id).await? {
             Some(case_study) => case_study,
@@ -93,9 +194,10 @@ impl CaseStudyManager {
         };
 
         // Soft delete
-        let deleted = self.repository.delete(id).await?;
+        let deleted = self.repository.delete(id, deleted_by).await?;
 
         if deleted {
+            self.statistics.invalidate();
             // Remove from search index
             self.search_engine.remove_case_study_index(id).await?;
         }
@@ -103,6 +205,75 @@ impl CaseStudyManager {
         Ok(deleted)
     }
 
+    /// Restore a soft-deleted case study
+    pub async fn restore_deleted_case_study(&self, id: &str) -> Result<Option<CaseStudy>> {
+        if !self.repository.restore_deleted(id).await? {
+            return Ok(None);
+        }
+        self.statistics.invalidate();
+
+        let case_study = self.repository.find_by_id(id).await?;
+        if let Some(ref case_study) = case_study {
+            self.search_engine.index_case_study(case_study).await?;
+        }
+
+        Ok(case_study)
+    }
+
+    /// Permanently remove a soft-deleted case study
+    pub async fn purge_case_study(&self, id: &str) -> Result<bool> {
+        let purged = self.repository.purge(id).await?;
+        if purged {
+            self.statistics.invalidate();
+        }
+        Ok(purged)
+    }
+
+    /// Delete several case studies, moving them to the trash or, with `permanent`, removing
+    /// them with everything that depends on them, attachment files included
+    pub async fn bulk_delete_case_studies(
+        &self,
+        ids: &[String],
+        permanent: bool,
+        deleted_by: Option<&str>,
+    ) -> Result<BulkDeleteReport> {
+        let report = self.repository.bulk_delete(ids, permanent, deleted_by).await?;
+        if !report.deleted_ids.is_empty() {
+            self.statistics.invalidate();
+            for id in &report.deleted_ids {
+                self.search_engine.remove_case_study_index(id).await?;
+            }
+        }
+        self.remove_attachment_files(&report.attachment_files).await;
+        Ok(report)
+    }
+
+    /// Remove attachment files whose rows are gone. The rows are already committed, so a
+    /// file that can't be removed is logged rather than failing the delete.
+    async fn remove_attachment_files(&self, file_paths: &[String]) {
+        for file_path in file_paths {
+            let path = Path::new(file_path);
+            let path = match &self.attachments_root {
+                _ if path.is_absolute() => path.to_path_buf(),
+                Some(root) => root.join(path),
+                None => {
+                    tracing::warn!("Not removing attachment file {}: no attachments root is set", file_path);
+                    continue;
+                }
+            };
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Failed to remove attachment file {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// List soft-deleted case studies
+    pub async fn list_deleted_case_studies(&self, limit: i32, offset: i32) -> Result<Vec<CaseStudy>> {
+        self.repository.list_deleted(limit, offset).await
+    }
+
     /// List case studies with filtering and pagination
     pub async fn list_case_studies(
         &self,
@@ -118,6 +289,41 @@ impl CaseStudyManager {
         self.search_engine.search(query).await
     }
 
+    /// List case studies one keyset page at a time, newest first
+    pub async fn list_case_studies_page(&self, filter: CaseStudyFilter, page: PageRequest) -> Result<CaseStudyPage> {
+        self.repository.list_page(filter, page).await
+    }
+
+    /// Full-text search paged by `(created_at, id)` cursor
+    pub async fn search_case_studies_page(
+        &self,
+        query: &str,
+        filter: CaseStudyFilter,
+        page: PageRequest,
+    ) -> Result<CaseStudyPage> {
+        self.repository.search_page(query, filter, page).await
+    }
+
+    /// Search case studies, returning relevance ranks and highlighted snippets
+    pub async fn search_case_studies_ranked(&self, query: CaseStudySearchQuery) -> Result<Vec<CaseStudySearchHit>> {
+        self.search_engine.search_ranked(query).await
+    }
+
+    /// Store a case study's embedding from `provider`
+    pub async fn store_embedding(&self, case_study: &CaseStudy, provider: &str, embedding: &[f32]) -> Result<()> {
+        self.search_engine.store_embedding(case_study, provider, embedding).await
+    }
+
+    /// Case studies whose `provider` embedding is missing or stale
+    pub async fn case_studies_needing_embedding(&self, provider: &str) -> Result<Vec<CaseStudy>> {
+        self.search_engine.case_studies_needing_embedding(provider).await
+    }
+
+    /// Nearest case studies to a query embedding by cosine similarity
+    pub async fn nearest_by_embedding(&self, provider: &str, query: &[f32], k: usize) -> Result<Vec<SemanticSearchHit>> {
+        self.search_engine.nearest_by_embedding(provider, query, k).await
+    }
+
     /// Publish case study
     pub async fn publish_case_study(&self, id: &str) -> Result<Option<CaseStudy>> {
         let case_study = match self.repository.find_by_id(id).await? {
@@ -126,17 +332,18 @@ impl CaseStudyManager {
         };
 
         // Validate that case study is ready for publishing
-        self.validate_for_publishing(&case_study)?;
+        self.validate_status_transition(&case_study, &CaseStudyStatus::Published)?;
 
         // Update status to published
         let published = self.repository.update_status(id, CaseStudyStatus::Published).await?;
+        self.statistics.invalidate();
 
         if let Some(ref case_study) = published {
             // Update search index
             self.search_engine.update_case_study_index(case_study).await?;
 
             // Create version for publishing
-            self.version_control.create_version(case_study, "Published", None).await?;
+            self.version_control.create_version(case_study, "Published").await?;
         }
 
         Ok(published)
@@ -145,13 +352,14 @@ impl CaseStudyManager {
     /// Archive case study
     pub async fn archive_case_study(&self, id: &str) -> Result<Option<CaseStudy>> {
         let archived = self.repository.update_status(id, CaseStudyStatus::Archived).await?;
+        self.statistics.invalidate();
 
         if let Some(ref case_study) = archived {
             // Update search index
             self.search_engine.update_case_study_index(case_study).await?;
 
             // Create version for archiving
-            self.version_control.create_version(case_study, "Archived", None).await?;
+            self.version_control.create_version(case_study, "Archived").await?;
         }
 
         Ok(archived)
@@ -164,25 +372,71 @@ impl CaseStudyManager {
             None => return Ok(None),
         };
 
-        if case_study.status != CaseStudyStatus::Archived {
-            return Err(CaseStudyError::InvalidData(
-                "Only archived case studies can be restored".to_string()
-            ));
-        }
+        self.validate_status_transition(&case_study, &CaseStudyStatus::Draft)?;
 
         let restored = self.repository.update_status(id, CaseStudyStatus::Draft).await?;
+        self.statistics.invalidate();
 
         if let Some(ref case_study) = restored {
             // Update search index
             self.search_engine.update_case_study_index(case_study).await?;
 
             // Create version for restoration
-            self.version_control.create_version(case_study, "Restored from archive", None).await?;
+            self.version_control.create_version(case_study, "Restored from archive").await?;
         }
 
         Ok(restored)
     }
 
+    /// Move many case studies to `status` in one transaction. Every id is checked
+    /// against the same rules as the single-item path; if any id is missing or has
+    /// an invalid transition the whole batch is rolled back.
+    pub async fn bulk_update_status(&self, ids: Vec<String>, status: CaseStudyStatus) -> Result<BulkStatusUpdateReport> {
+        let mut tx = self.repository.begin().await?;
+        let mut updated = Vec::new();
+        let mut failures = Vec::new();
+
+        for id in ids {
+            let case_study = match self.repository.find_by_id_on(&mut tx, &id).await? {
+                Some(case_study) => case_study,
+                None => {
+                    failures.push(BulkStatusFailure { id, reason: BulkStatusFailureReason::NotFound });
+                    continue;
+                }
+            };
+
+            let changes_summary = match self.validate_status_transition(&case_study, &status) {
+                Ok(changes_summary) => changes_summary,
+                Err(e) => {
+                    failures.push(BulkStatusFailure {
+                        id,
+                        reason: BulkStatusFailureReason::InvalidTransition { message: e.to_string() },
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(case_study) = self.repository.update_status_on(&mut tx, &id, status.clone()).await? {
+                self.version_control.create_version_on(&mut tx, &case_study, changes_summary).await?;
+                updated.push(case_study);
+            }
+        }
+
+        if !failures.is_empty() {
+            tx.rollback().await?;
+            return Ok(BulkStatusUpdateReport { committed: false, updated: Vec::new(), failures });
+        }
+
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        for case_study in &updated {
+            self.search_engine.update_case_study_index(case_study).await?;
+        }
+
+        Ok(BulkStatusUpdateReport { committed: true, updated, failures })
+    }
+
     /// Get case study versions
     pub async fn get_case_study_versions(&self, case_study_id: &str) -> Result<Vec<CaseStudyVersion>> {
         self.version_control.get_versions(case_study_id).await
@@ -193,6 +447,11 @@ impl CaseStudyManager {
         self.version_control.get_version(case_study_id, version).await
     }
 
+    /// Diff two versions of a case study
+    pub async fn diff_case_study_versions(&self, case_study_id: &str, from_version: i32, to_version: i32) -> Result<VersionDiff> {
+        self.version_control.diff_versions(case_study_id, from_version, to_version).await
+    }
+
     /// Restore case study to specific version
     pub async fn restore_to_version(&self, case_study_id: &str, version: i32) -> Result<Option<CaseStudy>> {
         let current = match self.repository.find_by_id(case_study_id).await? {
@@ -223,29 +482,47 @@ impl CaseStudyManager {
             difficulty_level: None,
             duration_minutes: None,
             learning_objectives: None,
+            tags: None,
+            updated_by: None,
         };
 
         // Update case study
         let updated = self.repository.update(case_study_id, update).await?;
+        self.statistics.invalidate();
 
         if let Some(ref case_study) = updated {
             // Update search index
             self.search_engine.update_case_study_index(case_study).await?;
 
             // Create version for restoration
-            self.version_control.create_version(
-                case_study, 
-                &format!("Restored to version {}", version), 
-                Some(&current)
-            ).await?;
+            self.version_control.create_version(case_study, &format!("Restored to version {}", version)).await?;
         }
 
         Ok(updated)
     }
 
-    /// Get case study statistics
+    /// Merge tag `from` into `to` across all case studies, returning how many were rewritten
+    pub async fn merge_tags(&self, from: &str, to: &str, merged_by: Option<&str>) -> Result<u64> {
+        let merged = self.repository.merge_tags(from, to, merged_by).await?;
+        if merged > 0 {
+            self.statistics.invalidate();
+        }
+        Ok(merged)
+    }
+
+    /// Tags in use with their case study counts, optionally narrowed to a prefix
+    pub async fn list_tags_with_counts(&self, prefix: Option<&str>, limit: i32) -> Result<Vec<TagUsage>> {
+        self.repository.list_tags_with_counts(prefix, limit).await
+    }
+
+    /// Get case study statistics, served from a short-lived cache that every write clears
     pub async fn get_statistics(&self) -> Result<CaseStudyStatistics> {
-        self.repository.get_statistics().await
+        self.statistics.get_or_compute(|| self.repository.get_statistics()).await
+    }
+
+    /// Drop cached statistics so the next read recomputes them
+    pub fn invalidate_statistics(&self) {
+        self.statistics.invalidate();
     }
 
     /// Get recent case studies
@@ -292,8 +569,10 @@ impl CaseStudyManager {
             difficulty_level: original.difficulty_level,
             duration_minutes: original.duration_minutes,
             learning_objectives: original.learning_objectives,
+            tags: original.tags,
             metadata: original.metadata,
             created_by: None, // Will be set to current user
+            idempotency_key: None,
         };
 
         self.create_case_study(new_case_study).await
@@ -333,6 +612,12 @@ impl CaseStudyManager {
             return Err(CaseStudyError::InvalidData("At least one learning objective is required".to_string()));
         }
 
+        if let Some(key) = &case_study.idempotency_key {
+            if key.trim().is_empty() || key.len() > 200 {
+                return Err(CaseStudyError::InvalidData("Idempotency key must be 1 to 200 characters".to_string()));
+            }
+        }
+
         if case_study.learning_objectives.len() > 10 {
             return Err(CaseStudyError::InvalidData("Too many learning objectives (max 10)".to_string()));
         }
@@ -398,6 +683,28 @@ impl CaseStudyManager {
         Ok(())
     }
 
+    /// Check a status transition and return the summary for the version it creates
+    fn validate_status_transition(&self, case_study: &CaseStudy, status: &CaseStudyStatus) -> Result<&'static str> {
+        match status {
+            CaseStudyStatus::Published => {
+                self.validate_for_publishing(case_study)?;
+                Ok("Published")
+            }
+            CaseStudyStatus::Archived => Ok("Archived"),
+            CaseStudyStatus::Draft => {
+                if case_study.status != CaseStudyStatus::Archived {
+                    return Err(CaseStudyError::InvalidData(
+                        "Only archived case studies can be restored".to_string()
+                    ));
+                }
+                Ok("Restored from archive")
+            }
+            CaseStudyStatus::Review | CaseStudyStatus::Deleted => Err(CaseStudyError::InvalidData(
+                format!("Cannot transition case studies to {}", status)
+            )),
+        }
+    }
+
     /// Validate case study for publishing
     fn validate_for_publishing(&self, case_study: &CaseStudy) -> Result<()> {
         if case_study.status != CaseStudyStatus::Review && case_study.status != CaseStudyStatus::Draft {
@@ -420,4 +727,483 @@ impl CaseStudyManager {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::audit::{AuditAction, AuditLogger};
+
+    async fn test_manager() -> CaseStudyManager {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        CaseStudyManager::new(db)
+    }
+
+    /// A case study long enough and complete enough to pass the publishing checks
+    fn publishable(title: &str) -> NewCaseStudy {
+        NewCaseStudy {
+            industry: "Retail".to_string(),
+            duration_minutes: 60,
+            learning_objectives: vec!["Evaluate market entry options".to_string()],
+            ..NewCaseStudy::for_test(title, &"The leadership team must decide whether to expand into a new region. ".repeat(4))
+        }
+    }
+
+    async fn publishable_case_study(manager: &CaseStudyManager, title: &str) -> CaseStudy {
+        manager.create_case_study(publishable(title)).await.unwrap()
+    }
+
+    async fn tagged_case_study(manager: &CaseStudyManager, title: &str, tags: &[&str]) -> CaseStudy {
+        let case_study = publishable_case_study(manager, title).await;
+        manager.update_case_study(&case_study.id, UpdateCaseStudy {
+            title: None,
+            description: None,
+            content: None,
+            summary: None,
+            category_id: None,
+            industry: None,
+            difficulty_level: None,
+            duration_minutes: None,
+            learning_objectives: None,
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            metadata: None,
+            updated_by: None,
+        }).await.unwrap().unwrap()
+    }
+
+    fn usage(tags: &[TagUsage]) -> Vec<(&str, i32)> {
+        tags.iter().map(|t| (t.tag_name.as_str(), t.usage_count)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_normalized_on_write() {
+        let manager = test_manager().await;
+        let mut new_case_study = NewCaseStudy {
+            industry: "Retail".to_string(),
+            duration_minutes: 60,
+            learning_objectives: vec!["Evaluate market entry options".to_string()],
+            tags: vec!["Market Entry".to_string(), " market-entry ".to_string(), "Retail_Strategy".to_string(), "  ".to_string()],
+            ..NewCaseStudy::for_test("Nordic Outfitters", "The leadership team must decide whether to expand into a new region.")
+        };
+        let created = manager.create_case_study(new_case_study.clone()).await.unwrap();
+        assert_eq!(created.tags, vec!["market-entry", "retail-strategy"]);
+
+        let stored = manager.get_case_study(&created.id).await.unwrap().unwrap();
+        assert_eq!(stored.tags, created.tags);
+
+        new_case_study.title = "Harbor Bank".to_string();
+        new_case_study.tags = vec!["MARKET   ENTRY".to_string()];
+        let second = manager.create_case_study(new_case_study).await.unwrap();
+        assert_eq!(second.tags, vec!["market-entry"]);
+    }
+
+    #[tokio::test]
+    async fn test_similar_case_study_is_flagged_before_create() {
+        let manager = test_manager().await;
+        let template = |title: &str, company: &str| NewCaseStudy {
+            industry: "Energy".to_string(),
+            duration_minutes: 60,
+            learning_objectives: vec!["Evaluate market entry options".to_string()],
+            ..NewCaseStudy::for_test(title, &format!(
+                "{} is weighing entry into the Brazilian market. The leadership team must choose between a joint venture and a greenfield investment while managing currency risk and local regulation.",
+                company
+            ))
+        };
+        let original = manager
+            .create_case_study(template("Market Entry Strategy Challenge - AlphaA", "AlphaA"))
+            .await
+            .unwrap();
+
+        let similar = manager
+            .find_similar_case_studies(
+                "Market Entry Strategy Challenge - BetaB",
+                &template("", "BetaB").content,
+                DEFAULT_SIMILARITY_THRESHOLD,
+            )
+            .await
+            .unwrap();
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].id, original.id);
+        assert_eq!(similar[0].title_similarity, 1.0);
+        assert!(similar[0].similarity >= DEFAULT_SIMILARITY_THRESHOLD);
+
+        let duplicate = template("Market Entry Strategy Challenge - BetaB", "BetaB");
+        let err = manager.create_case_study_checked(duplicate.clone(), false).await.unwrap_err();
+        assert!(matches!(err, CaseStudyError::PossibleDuplicate(ref s) if s.len() == 1));
+        assert!(err.to_string().contains("Market Entry Strategy Challenge - AlphaA"));
+        manager.create_case_study_checked(duplicate, true).await.unwrap();
+
+        let mut distinct = template("Harbor Bank Turnaround", "");
+        distinct.content = "A regional bank's new CEO must rebuild trust with branch managers after a failed systems migration and rising customer complaints.".to_string();
+        assert!(manager
+            .find_similar_case_studies(&distinct.title, &distinct.content, DEFAULT_SIMILARITY_THRESHOLD)
+            .await
+            .unwrap()
+            .is_empty());
+        manager.create_case_study_checked(distinct, false).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_readability_is_recomputed_when_content_changes() {
+        let manager = test_manager().await;
+        let case_study = publishable_case_study(&manager, "Regional Expansion").await;
+
+        let initial = case_study.metadata.readability.unwrap();
+        assert_eq!(initial.word_count, 48);
+        assert_eq!(initial.sentence_count, 4);
+        assert!((4.0..=8.0).contains(&initial.flesch_kincaid_grade));
+        assert_eq!(manager.get_case_study_readability(&case_study.id).await.unwrap(), Some(initial));
+
+        let update = |content: Option<String>, metadata: Option<CaseStudyMetadata>| UpdateCaseStudy {
+            title: None,
+            description: None,
+            content,
+            summary: None,
+            category_id: None,
+            industry: None,
+            difficulty_level: None,
+            duration_minutes: None,
+            learning_objectives: None,
+            tags: None,
+            metadata,
+            updated_by: None,
+        };
+        let dense = "Organizational transformation necessitates comprehensive stakeholder engagement and deliberate communication strategies. ".repeat(2);
+        manager.update_case_study(&case_study.id, update(Some(dense), None)).await.unwrap();
+
+        let updated = manager.get_case_study_readability(&case_study.id).await.unwrap().unwrap();
+        assert_eq!(updated.word_count, 20);
+        assert!(updated.flesch_kincaid_grade > 12.0);
+
+        // Replacing the metadata keeps the readability of the stored content
+        manager
+            .update_case_study(&case_study.id, update(None, Some(CaseStudyMetadata::default())))
+            .await
+            .unwrap();
+        assert_eq!(manager.get_case_study_readability(&case_study.id).await.unwrap(), Some(updated));
+        assert!(manager.get_case_study_readability("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_tags_rewrites_every_case_study() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let manager = CaseStudyManager::new(db.clone());
+        let first = tagged_case_study(&manager, "Nordic Outfitters", &["expansion", "retail"]).await;
+        let second = tagged_case_study(&manager, "Harbor Bank", &["Expansion", "market-entry"]).await;
+        let untouched = tagged_case_study(&manager, "FreshCart", &["operations"]).await;
+
+        let merged = manager.merge_tags("EXPANSION", "Market Entry", Some("admin")).await.unwrap();
+        assert_eq!(merged, 2);
+
+        let first = manager.get_case_study(&first.id).await.unwrap().unwrap();
+        assert_eq!(first.tags, vec!["market-entry", "retail"]);
+        // Already tagged with the target, so the merge must not duplicate it
+        let second = manager.get_case_study(&second.id).await.unwrap().unwrap();
+        assert_eq!(second.tags, vec!["market-entry"]);
+        let untouched = manager.get_case_study(&untouched.id).await.unwrap().unwrap();
+        assert_eq!(untouched.tags, vec!["operations"]);
+
+        // Stored JSON stays a valid array
+        let raw: Vec<String> = sqlx::query_scalar("SELECT tags FROM case_studies")
+            .fetch_all(db.pool())
+            .await
+            .unwrap();
+        for tags in raw {
+            assert!(serde_json::from_str::<Vec<String>>(&tags).is_ok());
+        }
+
+        assert_eq!(manager.merge_tags("expansion", "market-entry", None).await.unwrap(), 0);
+        assert!(manager.merge_tags(" ", "market-entry", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_tags_with_counts() {
+        let manager = test_manager().await;
+        tagged_case_study(&manager, "Nordic Outfitters", &["market-entry", "retail"]).await;
+        tagged_case_study(&manager, "Harbor Bank", &["Market Entry", "finance"]).await;
+        let deleted = tagged_case_study(&manager, "FreshCart", &["market-entry", "marketing"]).await;
+        tagged_case_study(&manager, "Solis Energy", &["marketing", "market-entry"]).await;
+        manager.delete_case_study(&deleted.id, None).await.unwrap();
+
+        let all = manager.list_tags_with_counts(None, 50).await.unwrap();
+        assert_eq!(usage(&all), vec![("market-entry", 3), ("finance", 1), ("marketing", 1), ("retail", 1)]);
+
+        let prefixed = manager.list_tags_with_counts(Some("Market"), 50).await.unwrap();
+        assert_eq!(usage(&prefixed), vec![("market-entry", 3), ("marketing", 1)]);
+
+        let limited = manager.list_tags_with_counts(None, 1).await.unwrap();
+        assert_eq!(usage(&limited), vec![("market-entry", 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_publish_commits_all() {
+        let manager = test_manager().await;
+        let first = publishable_case_study(&manager, "Nordic Outfitters").await;
+        let second = publishable_case_study(&manager, "Harbor Bank").await;
+
+        let report = manager
+            .bulk_update_status(vec![first.id.clone(), second.id.clone()], CaseStudyStatus::Published)
+            .await
+            .unwrap();
+
+        assert!(report.committed);
+        assert!(report.failures.is_empty());
+        assert_eq!(report.updated.len(), 2);
+        for id in [&first.id, &second.id] {
+            let case_study = manager.get_case_study(id).await.unwrap().unwrap();
+            assert_eq!(case_study.status, CaseStudyStatus::Published);
+            assert_eq!(manager.get_case_study_versions(id).await.unwrap().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_update_rolls_back_mixed_batch() {
+        let manager = test_manager().await;
+        let valid = publishable_case_study(&manager, "Nordic Outfitters").await;
+        let draft = publishable_case_study(&manager, "Harbor Bank").await;
+
+        let report = manager
+            .bulk_update_status(
+                vec![valid.id.clone(), "missing-id".to_string(), draft.id.clone()],
+                CaseStudyStatus::Draft,
+            )
+            .await
+            .unwrap();
+
+        assert!(!report.committed);
+        assert!(report.updated.is_empty());
+        assert_eq!(report.failures.len(), 3);
+        assert_eq!(report.failures[1].id, "missing-id");
+        assert_eq!(report.failures[1].reason, BulkStatusFailureReason::NotFound);
+        assert!(matches!(
+            &report.failures[0].reason,
+            BulkStatusFailureReason::InvalidTransition { message } if message.contains("Only archived")
+        ));
+
+        let report = manager
+            .bulk_update_status(vec![valid.id.clone(), "missing-id".to_string()], CaseStudyStatus::Archived)
+            .await
+            .unwrap();
+
+        assert!(!report.committed);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].id, "missing-id");
+
+        let unchanged = manager.get_case_study(&valid.id).await.unwrap().unwrap();
+        assert_eq!(unchanged.status, CaseStudyStatus::Draft);
+        assert_eq!(unchanged.version, valid.version);
+        assert_eq!(manager.get_case_study_versions(&valid.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_case_studies_can_be_restored_or_purged() {
+        let manager = test_manager().await;
+        let kept = publishable_case_study(&manager, "Nordic Outfitters").await;
+        let dropped = publishable_case_study(&manager, "Harbor Bank").await;
+        manager.archive_case_study(&kept.id).await.unwrap();
+
+        assert!(manager.delete_case_study(&kept.id, None).await.unwrap());
+        assert!(manager.delete_case_study(&dropped.id, None).await.unwrap());
+
+        assert!(manager.get_case_study(&kept.id).await.unwrap().is_none());
+        assert!(manager.list_case_studies(CaseStudyFilter::default(), 20, 0).await.unwrap().is_empty());
+        let deleted = manager.list_deleted_case_studies(20, 0).await.unwrap();
+        assert_eq!(deleted.len(), 2);
+
+        // Restoring keeps the status the case study had before it was deleted
+        let restored = manager.restore_deleted_case_study(&kept.id).await.unwrap().unwrap();
+        assert_eq!(restored.status, CaseStudyStatus::Archived);
+        assert_eq!(manager.list_case_studies(CaseStudyFilter::default(), 20, 0).await.unwrap().len(), 1);
+
+        // Only deleted case studies can be purged
+        assert!(!manager.purge_case_study(&kept.id).await.unwrap());
+        assert!(manager.purge_case_study(&dropped.id).await.unwrap());
+        assert!(manager.list_deleted_case_studies(20, 0).await.unwrap().is_empty());
+        assert!(manager.restore_deleted_case_study(&dropped.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_permanent_bulk_delete_removes_attachment_files() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let mut manager = CaseStudyManager::new(db.clone());
+        let root = std::env::temp_dir().join(format!("case-crafter-attachments-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("attachments")).unwrap();
+        manager.set_attachments_root(root.clone());
+
+        let case_study = publishable_case_study(&manager, "Nordic Outfitters").await;
+        std::fs::write(root.join("attachments/data.csv"), b"region,revenue").unwrap();
+        sqlx::query("INSERT INTO attachments (case_study_id, filename, file_path) VALUES (?, 'data.csv', 'attachments/data.csv')")
+            .bind(&case_study.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        // Moving to the trash keeps the file so a restore still finds it
+        manager.bulk_delete_case_studies(&[case_study.id.clone()], false, None).await.unwrap();
+        assert!(root.join("attachments/data.csv").exists());
+
+        let report = manager.bulk_delete_case_studies(&[case_study.id.clone()], true, None).await.unwrap();
+        assert_eq!(report.attachments, 1);
+        assert!(!root.join("attachments/data.csv").exists());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_purge_older_than_keeps_recent_deletes() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let manager = CaseStudyManager::new(db.clone());
+        let case_study = publishable_case_study(&manager, "Nordic Outfitters").await;
+        manager.delete_case_study(&case_study.id, None).await.unwrap();
+
+        let purged = db.purge_deleted_older_than(30).await.unwrap();
+        assert_eq!(purged["case_studies"], 0);
+        assert_eq!(manager.list_deleted_case_studies(20, 0).await.unwrap().len(), 1);
+
+        sqlx::query("UPDATE case_studies SET deleted_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now() - chrono::Duration::days(31))
+            .bind(&case_study.id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let purged = db.purge_deleted_older_than(30).await.unwrap();
+        assert_eq!(purged["case_studies"], 1);
+        assert!(manager.list_deleted_case_studies(20, 0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_records_one_audit_entry_with_diff() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let manager = CaseStudyManager::new(db.clone());
+        let case_study = publishable_case_study(&manager, "Nordic Outfitters").await;
+
+        manager.update_case_study(&case_study.id, UpdateCaseStudy {
+            title: Some("Nordic Outfitters Expansion".to_string()),
+            description: None,
+            content: None,
+            summary: None,
+            category_id: None,
+            industry: None,
+            difficulty_level: None,
+            duration_minutes: None,
+            learning_objectives: None,
+            tags: None,
+            metadata: None,
+            updated_by: Some("instructor-7".to_string()),
+        }).await.unwrap();
+
+        let log = AuditLogger::new(db.clone()).get_entity_log("case_study", &case_study.id).await.unwrap();
+        let actions: Vec<AuditAction> = log.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![AuditAction::Create, AuditAction::Update]);
+
+        let update = &log[1];
+        assert_eq!(update.user_id.as_deref(), Some("instructor-7"));
+        assert_eq!(update.diff, serde_json::json!({
+            "title": { "old": "Nordic Outfitters", "new": "Nordic Outfitters Expansion" },
+            "version": { "old": 1, "new": 2 },
+        }));
+
+        // Entries cannot be altered after the fact
+        assert!(sqlx::query("UPDATE audit_log SET user_id = 'someone-else'").execute(db.pool()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_records_one_status_audit_entry() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let manager = CaseStudyManager::new(db.clone());
+        let case_study = publishable_case_study(&manager, "Nordic Outfitters").await;
+
+        manager.publish_case_study(&case_study.id).await.unwrap().unwrap();
+
+        let log = AuditLogger::new(db.clone()).get_entity_log("case_study", &case_study.id).await.unwrap();
+        let actions: Vec<AuditAction> = log.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![AuditAction::Create, AuditAction::Update]);
+        assert_eq!(log[1].diff["status"], serde_json::json!({ "old": "draft", "new": "published" }));
+    }
+
+    #[tokio::test]
+    async fn test_statistics_are_cached_until_a_write() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let manager = CaseStudyManager::new(db.clone());
+        let first = publishable_case_study(&manager, "Nordic Outfitters").await;
+        publishable_case_study(&manager, "Harbor Bank").await;
+        manager.publish_case_study(&first.id).await.unwrap();
+
+        let stats = manager.get_statistics().await.unwrap();
+        assert_eq!((stats.total_count, stats.published_count, stats.draft_count), (2, 1, 1));
+        assert_eq!(stats.most_popular_industry.as_deref(), Some("Retail"));
+        assert_eq!(stats.average_duration, 60.0);
+
+        // Writes that bypass the manager aren't seen until the cache is cleared
+        sqlx::query(
+            "INSERT INTO case_studies (id, title, content, industry, difficulty_level, duration_minutes, learning_objectives) \
+             VALUES ('raw', 'Raw insert', 'Content', 'Energy', 'beginner', 30, '[]')"
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(manager.get_statistics().await.unwrap().total_count, 2);
+
+        publishable_case_study(&manager, "Solis Energy").await;
+        let stats = manager.get_statistics().await.unwrap();
+        assert_eq!((stats.total_count, stats.published_count, stats.draft_count), (4, 1, 3));
+        assert_eq!(stats.average_duration, 52.5);
+
+        manager.delete_case_study("raw", None).await.unwrap();
+        assert_eq!(manager.get_statistics().await.unwrap().total_count, 3);
+    }
+
+    fn keyed_case_study(title: &str, key: &str) -> NewCaseStudy {
+        NewCaseStudy { idempotency_key: Some(key.to_string()), ..publishable(title) }
+    }
+
+    async fn case_study_count(db: &DatabaseManager) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM case_studies")
+            .fetch_one(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retried_create_with_same_key_returns_first_case_study() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let manager = CaseStudyManager::new(db.clone());
+
+        let first = manager.create_case_study(keyed_case_study("Nordic Outfitters", "request-1")).await.unwrap();
+        let retry = manager.create_case_study(keyed_case_study("Nordic Outfitters", "request-1")).await.unwrap();
+        assert_eq!(retry.id, first.id);
+        assert_eq!(case_study_count(&db).await, 1);
+
+        // The duplicate check doesn't flag a retry as a copy of its own first attempt
+        let checked = manager
+            .create_case_study_checked(keyed_case_study("Nordic Outfitters", "request-1"), false)
+            .await
+            .unwrap();
+        assert_eq!(checked.id, first.id);
+
+        let other = manager.create_case_study(keyed_case_study("Baltic Outfitters", "request-2")).await.unwrap();
+        assert_ne!(other.id, first.id);
+        assert_eq!(case_study_count(&db).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_idempotency_key_creates_new_case_study() {
+        let db = DatabaseManager::in_memory().await.unwrap();
+        let mut manager = CaseStudyManager::new(db.clone());
+        manager.set_idempotency_window(chrono::Duration::zero());
+
+        let first = manager.create_case_study(keyed_case_study("Nordic Outfitters", "request-1")).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let second = manager.create_case_study(keyed_case_study("Nordic Outfitters", "request-1")).await.unwrap();
+        assert_ne!(second.id, first.id);
+        assert_eq!(case_study_count(&db).await, 2);
+
+        // Blank keys are rejected rather than shared
+        assert!(matches!(
+            manager.create_case_study(keyed_case_study("Nordic Outfitters", " ")).await,
+            Err(CaseStudyError::InvalidData(_))
+        ));
+    }
+}
+
@@ -5,18 +5,26 @@ pub mod manager;
 pub mod models;
 pub mod repository;
 pub mod search;
+pub mod embeddings;
+pub mod tags;
+pub mod similarity;
+pub mod readability;
 pub mod version_control;
+pub mod export;
 pub mod commands;
 
 pub use manager::CaseStudyManager;
 pub use models::{
     CaseStudy, CaseStudyMetadata, CaseStudyStatus, CaseStudyVersion, 
-    NewCaseStudy, UpdateCaseStudy, CaseStudyFilter, CaseStudySearchQuery,
-    CaseStudyTag, CaseStudyCategory, CaseStudyStatistics
+    NewCaseStudy, UpdateCaseStudy, CaseStudyFilter, CaseStudySearchQuery, CaseStudySearchHit, SemanticSearchHit, PageRequest, CaseStudyPage,
+    CaseStudyTag, CaseStudyCategory, CaseStudyStatistics, TagUsage, SimilarCaseStudy,
+    BulkStatusUpdateReport, BulkStatusFailure, BulkStatusFailureReason
 };
 pub use repository::CaseStudyRepository;
 pub use search::CaseStudySearchEngine;
-pub use version_control::CaseStudyVersionControl;
+pub use readability::Readability;
+pub use version_control::{CaseStudyVersionControl, DiffLine, FieldChange, FieldDiff, VersionDiff};
+pub use export::{ExportFormat, ExportOptions};
 
 use crate::database::DatabaseManager;
 use std::sync::Arc;
@@ -37,9 +45,15 @@ pub enum CaseStudyError {
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
     
+    #[error("Possible duplicate of {}; create it anyway with allow_duplicate", describe_similar(.0))]
+    PossibleDuplicate(Vec<SimilarCaseStudy>),
+
     #[error("Version conflict: {0}")]
     VersionConflict(String),
     
+    #[error("Export failed: {0}")]
+    ExportError(String),
+    
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
     
@@ -50,4 +64,12 @@ pub enum CaseStudyError {
     IoError(#[from] std::io::Error),
 }
 
-pub type Result<T> = std::result::Result<T, CaseStudyError>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, CaseStudyError>;
+
+fn describe_similar(similar: &[SimilarCaseStudy]) -> String {
+    similar
+        .iter()
+        .map(|s| format!("\"{}\" ({:.0}% similar)", s.title, s.similarity * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
\ No newline at end of file
@@ -3,6 +3,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use super::readability::Readability;
 
 /// Main case study model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -19,6 +20,9 @@ pub struct CaseStudy {
     pub duration_minutes: i32,
     pub word_count: i32,
     pub learning_objectives: Vec<String>, // JSON array in DB
+    /// Normalized tags, stored as a JSON array
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub metadata: CaseStudyMetadata,
     pub version: i32,
     pub created_by: Option<String>,
@@ -56,6 +60,9 @@ pub struct CaseStudyMetadata {
     pub ai_generated: bool,
     pub generation_params: Option<serde_json::Value>,
     pub custom_fields: HashMap<String, serde_json::Value>,
+    /// Computed from the content whenever it is written
+    #[serde(default)]
+    pub readability: Option<Readability>,
 }
 
 /// Model for creating new case studies
@@ -70,12 +77,41 @@ pub struct NewCaseStudy {
     pub difficulty_level: String,
     pub duration_minutes: i32,
     pub learning_objectives: Vec<String>,
+    /// Free-form tags; normalized before they are stored
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub metadata: CaseStudyMetadata,
     pub created_by: Option<String>,
+    /// Client-supplied request id; creating again with the same key returns the case study
+    /// made the first time instead of a new one
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+#[cfg(test)]
+impl NewCaseStudy {
+    /// Draft case study for tests; change other fields with struct update syntax
+    pub fn for_test(title: &str, content: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            description: None,
+            content: content.to_string(),
+            summary: None,
+            category_id: None,
+            industry: "General".to_string(),
+            difficulty_level: "intermediate".to_string(),
+            duration_minutes: 45,
+            learning_objectives: Vec::new(),
+            tags: Vec::new(),
+            metadata: CaseStudyMetadata::default(),
+            created_by: None,
+            idempotency_key: None,
+        }
+    }
 }
 
 /// Model for updating case studies
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UpdateCaseStudy {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -86,7 +122,12 @@ pub struct UpdateCaseStudy {
     pub difficulty_level: Option<String>,
     pub duration_minutes: Option<i32>,
     pub learning_objectives: Option<Vec<String>>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
     pub metadata: Option<CaseStudyMetadata>,
+    /// User making the change, recorded in the audit log
+    #[serde(default)]
+    pub updated_by: Option<String>,
 }
 
 /// Case study version tracking
@@ -136,6 +177,64 @@ pub struct CaseStudySearchQuery {
     pub include_archived: bool,
 }
 
+/// A full-text search match with its relevance and highlighted excerpt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseStudySearchHit {
+    pub case_study: CaseStudy,
+    /// bm25 score; lower is more relevant
+    pub rank: f64,
+    /// Excerpt with matched terms wrapped in `<mark>` tags
+    pub snippet: Option<String>,
+}
+
+/// A semantic search match ranked by embedding similarity to the query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticSearchHit {
+    pub case_study: CaseStudy,
+    /// Cosine similarity to the query; higher is more similar
+    pub score: f32,
+}
+
+/// Keyset pagination request. `cursor` is the `next_cursor` of the previous page;
+/// omit it to start from the newest case study.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageRequest {
+    pub limit: Option<i32>,
+    pub cursor: Option<String>,
+}
+
+/// One page of case studies ordered newest first by `(created_at, id)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseStudyPage {
+    pub items: Vec<CaseStudy>,
+    /// Opaque cursor for the following page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Outcome of a bulk status change. Changes are only committed when no id failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStatusUpdateReport {
+    pub committed: bool,
+    /// Case studies after the change; empty when the batch was rolled back
+    pub updated: Vec<CaseStudy>,
+    pub failures: Vec<BulkStatusFailure>,
+}
+
+/// An id that blocked a bulk status change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkStatusFailure {
+    pub id: String,
+    #[serde(flatten)]
+    pub reason: BulkStatusFailureReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum BulkStatusFailureReason {
+    NotFound,
+    InvalidTransition { message: String },
+}
+
 /// Case study tag model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CaseStudyTag {
@@ -187,6 +286,17 @@ pub struct MonthlyCount {
     pub count: i32,
 }
 
+/// Existing case study that closely resembles one being created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarCaseStudy {
+    pub id: String,
+    pub title: String,
+    pub title_similarity: f64,
+    pub content_similarity: f64,
+    /// Weighted combination of the title and content scores, compared against the threshold
+    pub similarity: f64,
+}
+
 /// Tag usage statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TagUsage {
@@ -194,6 +304,23 @@ pub struct TagUsage {
     pub usage_count: i32,
 }
 
+/// What a bulk delete removed. Dependent counts cover every deleted case study.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkDeleteReport {
+    /// Whether the case studies were removed for good rather than moved to the trash
+    pub permanent: bool,
+    pub deleted_ids: Vec<String>,
+    pub not_found: Vec<String>,
+    pub assessment_questions: u64,
+    pub user_progress: u64,
+    pub collection_links: u64,
+    pub attachments: u64,
+    /// Generation history is kept; its link to the case study is cleared
+    pub generation_history_unlinked: u64,
+    /// Recorded paths of the files behind the removed attachment rows
+    pub attachment_files: Vec<String>,
+}
+
 /// Case study relationship mapping
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CaseStudyRelation {
@@ -244,6 +371,7 @@ impl Default for CaseStudyMetadata {
             ai_generated: false,
             generation_params: None,
             custom_fields: HashMap::new(),
+            readability: None,
         }
     }
 }
@@ -0,0 +1,136 @@
+// Word, sentence and reading-level statistics for case study content
+
+use serde::{Deserialize, Serialize};
+
+/// Readability statistics computed from a case study's content
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Readability {
+    pub word_count: u32,
+    pub sentence_count: u32,
+    pub syllable_count: u32,
+    /// Flesch-Kincaid grade level, rounded to one decimal and never below zero
+    pub flesch_kincaid_grade: f64,
+}
+
+/// Compute readability statistics for `text`. Syllables are estimated from vowel groups,
+/// so the grade level is approximate.
+pub fn analyze(text: &str) -> Readability {
+    let mut word_count = 0u32;
+    let mut syllable_count = 0u32;
+    for word in text.split_whitespace().filter(|w| w.chars().any(char::is_alphabetic)) {
+        word_count += 1;
+        syllable_count += count_syllables(word);
+    }
+    let sentence_count = count_sentences(text);
+
+    let flesch_kincaid_grade = if word_count == 0 || sentence_count == 0 {
+        0.0
+    } else {
+        let words_per_sentence = f64::from(word_count) / f64::from(sentence_count);
+        let syllables_per_word = f64::from(syllable_count) / f64::from(word_count);
+        let grade = 0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59;
+        (grade.max(0.0) * 10.0).round() / 10.0
+    };
+
+    Readability {
+        word_count,
+        sentence_count,
+        syllable_count,
+        flesch_kincaid_grade,
+    }
+}
+
+/// Vowel groups in `word`, not counting a silent trailing "e"; at least one
+fn count_syllables(word: &str) -> u32 {
+    let letters: Vec<char> = word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let is_vowel = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+
+    let mut syllables = 0u32;
+    let mut previous_vowel = false;
+    for &c in &letters {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            syllables += 1;
+        }
+        previous_vowel = vowel;
+    }
+
+    // "make" has one syllable, but "table" keeps its consonant + "le" syllable
+    if let [.., before, last_but_one, 'e'] = letters.as_slice() {
+        let consonant_le = *last_but_one == 'l' && !is_vowel(*before);
+        if syllables > 1 && !is_vowel(*last_but_one) && !consonant_le {
+            syllables -= 1;
+        }
+    }
+    syllables.max(1)
+}
+
+/// Sentences end at ".", "!" or "?" followed by whitespace, or at a line break, so
+/// headings and list items count as sentences of their own
+fn count_sentences(text: &str) -> u32 {
+    let mut sentences = 0u32;
+    let mut in_sentence = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let ends_sentence = match c {
+            '.' | '!' | '?' => !matches!(chars.peek(), Some(next) if !next.is_whitespace()),
+            '\n' => true,
+            _ => {
+                in_sentence |= c.is_alphanumeric();
+                false
+            }
+        };
+        if ends_sentence && in_sentence {
+            sentences += 1;
+            in_sentence = false;
+        }
+    }
+    if in_sentence {
+        sentences += 1;
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syllable_estimates() {
+        for (word, expected) in [("cat", 1), ("the", 1), ("make", 1), ("table", 2), ("decide", 2), ("company", 3), ("Year,", 1)] {
+            assert_eq!(count_syllables(word), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_grade_level_of_known_text() {
+        // 12 words, 19 syllables, 1 sentence: 0.39 * 12 + 11.8 * 19 / 12 - 15.59 = 7.77
+        let readability = analyze("The company must decide whether to expand into new markets this year.");
+        assert_eq!(readability.word_count, 12);
+        assert_eq!(readability.sentence_count, 1);
+        assert_eq!(readability.syllable_count, 19);
+        assert!((7.0..=8.5).contains(&readability.flesch_kincaid_grade));
+
+        let simple = analyze("The cat sat on the mat. The dog ran.");
+        assert_eq!(simple.sentence_count, 2);
+        assert!(simple.flesch_kincaid_grade < 2.0);
+
+        let dense = analyze(
+            "Organizational transformation necessitates comprehensive stakeholder engagement and deliberate communication strategies.",
+        );
+        assert!(dense.flesch_kincaid_grade > 12.0);
+    }
+
+    #[test]
+    fn test_empty_and_markdown_content() {
+        assert_eq!(analyze("  ").flesch_kincaid_grade, 0.0);
+
+        let readability = analyze("# Background\n\nRevenue fell 3.5% last year. Costs rose!");
+        assert_eq!(readability.sentence_count, 3);
+        assert_eq!(readability.word_count, 7);
+    }
+}
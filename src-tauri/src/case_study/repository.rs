@@ -1,39 +1,128 @@
 // Database repository for case study management
 
 use super::models::*;
+use super::readability;
+use super::search::{build_match_expression, push_filter_clauses};
+use super::tags::{normalize_tag, normalize_tags, replace_tag};
 use super::{CaseStudyError, Result};
 use crate::database::DatabaseManager;
-use sqlx::{Row, Sqlite};
-use chrono::Utc;
+use crate::database::audit::{AuditAction, AuditLogger};
+use crate::database::collection_repository::compact_order;
+use crate::database::CollectionError;
+use sqlx::{Row, Sqlite, SqliteConnection, Transaction};
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{Duration, Utc};
 use uuid::Uuid;
 use std::collections::HashMap;
 
+/// Entity type recorded in the audit log
+const AUDIT_ENTITY: &str = "case_study";
+
+/// Page size used when a page request doesn't give one
+const DEFAULT_PAGE_SIZE: i32 = 20;
+
+/// Largest page a single request may ask for
+const MAX_PAGE_SIZE: i32 = 100;
+
+/// How long an idempotency key is remembered when no window is configured
+pub const DEFAULT_IDEMPOTENCY_WINDOW_HOURS: i64 = 24;
+
 /// Repository for case study database operations
 pub struct CaseStudyRepository {
     db: DatabaseManager,
+    idempotency_window: Duration,
 }
 
 impl CaseStudyRepository {
     pub fn new(db: DatabaseManager) -> Self {
-        Self { db }
+        Self {
+            db,
+            idempotency_window: Duration::hours(DEFAULT_IDEMPOTENCY_WINDOW_HOURS),
+        }
+    }
+
+    /// Set how long idempotency keys are remembered
+    pub fn set_idempotency_window(&mut self, window: Duration) {
+        self.idempotency_window = window;
     }
 
     /// Create a new case study
     pub async fn create(&self, new_case_study: NewCaseStudy) -> Result<CaseStudy> {
+        self.create_or_replay(new_case_study).await.map(|(case_study, _)| case_study)
+    }
+
+    /// Case study created within the idempotency window with `key`
+    pub async fn find_by_idempotency_key(&self, key: &str) -> Result<Option<CaseStudy>> {
+        let mut conn = self.db.pool().acquire().await?;
+        let case_study_id: Option<String> = sqlx::query_scalar(
+            "SELECT case_study_id FROM case_study_idempotency_keys WHERE idempotency_key = ? AND created_at >= ?"
+        )
+        .bind(key)
+        .bind(Utc::now() - self.idempotency_window)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match case_study_id {
+            Some(id) => self.find_by_id_on(&mut conn, &id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Create a new case study, or return the one an earlier create with the same
+    /// idempotency key made. The flag is true when a new case study was inserted.
+    #[tracing::instrument(name = "db.case_studies.create", skip_all)]
+    pub async fn create_or_replay(&self, new_case_study: NewCaseStudy) -> Result<(CaseStudy, bool)> {
+        let mut tx = self.db.pool().begin().await?;
+
+        if let Some(key) = &new_case_study.idempotency_key {
+            // Writing first takes the write lock, so a concurrent create with the same key
+            // waits here until this one has committed its key
+            sqlx::query("DELETE FROM case_study_idempotency_keys WHERE created_at < ?")
+                .bind(Utc::now() - self.idempotency_window)
+                .execute(&mut *tx)
+                .await?;
+
+            let existing: Option<String> = sqlx::query_scalar(
+                "SELECT case_study_id FROM case_study_idempotency_keys WHERE idempotency_key = ?"
+            )
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(existing_id) = existing {
+                match self.find_by_id_on(&mut tx, &existing_id).await? {
+                    Some(case_study) => {
+                        tx.commit().await?;
+                        return Ok((case_study, false));
+                    }
+                    // The earlier case study has since been deleted, so the key is free again
+                    None => {
+                        sqlx::query("DELETE FROM case_study_idempotency_keys WHERE idempotency_key = ?")
+                            .bind(key)
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                }
+            }
+        }
+
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
         let word_count = new_case_study.content.split_whitespace().count() as i32;
+        let mut metadata = new_case_study.metadata.clone();
+        metadata.readability = Some(readability::analyze(&new_case_study.content));
         
-        let metadata_json = serde_json::to_string(&new_case_study.metadata)?;
+        let metadata_json = serde_json::to_string(&metadata)?;
         let learning_objectives_json = serde_json::to_string(&new_case_study.learning_objectives)?;
+        let tags_json = serde_json::to_string(&normalize_tags(&new_case_study.tags))?;
 
         sqlx::query(
             r#"
             INSERT INTO case_studies (
                 id, title, description, content, summary, status, category_id,
                 industry, difficulty_level, duration_minutes, word_count,
-                learning_objectives, metadata, version, created_by, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                learning_objectives, tags, metadata, version, created_by, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&id)
@@ -48,35 +137,56 @@ impl CaseStudyRepository {
         .bind(new_case_study.duration_minutes)
         .bind(word_count)
         .bind(&learning_objectives_json)
+        .bind(&tags_json)
         .bind(&metadata_json)
         .bind(1)
         .bind(&new_case_study.created_by)
         .bind(&now)
         .bind(&now)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(key) = &new_case_study.idempotency_key {
+            sqlx::query("INSERT INTO case_study_idempotency_keys (idempotency_key, case_study_id, created_at) VALUES (?, ?, ?)")
+                .bind(key)
+                .bind(&id)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+        }
+
         // Fetch the created case study
-        let case_study = self.find_by_id(&id).await?
+        let case_study = self.find_by_id_on(&mut tx, &id).await?
             .ok_or_else(|| CaseStudyError::NotFound("Failed to create case study".to_string()))?;
 
-        Ok(case_study)
+        let diff = AuditLogger::diff(None, Some(&case_study));
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, &id, AuditAction::Create, case_study.created_by.as_deref(), &diff).await?;
+        tx.commit().await?;
+
+        Ok((case_study, true))
     }
 
     /// Find case study by ID
+    #[tracing::instrument(name = "db.case_studies.find", skip_all, fields(id = %id))]
     pub async fn find_by_id(&self, id: &str) -> Result<Option<CaseStudy>> {
+        let mut conn = self.db.pool().acquire().await?;
+        self.find_by_id_on(&mut conn, id).await
+    }
+
+    /// Find case study by ID on a specific connection, e.g. inside a transaction
+    pub async fn find_by_id_on(&self, conn: &mut SqliteConnection, id: &str) -> Result<Option<CaseStudy>> {
         let row = sqlx::query(
             r#"
             SELECT id, title, description, content, summary, status, category_id,
                    industry, difficulty_level, duration_minutes, word_count,
-                   learning_objectives, metadata, version, created_by,
+                   learning_objectives, tags, metadata, version, created_by,
                    created_at, updated_at, published_at, archived_at
             FROM case_studies 
-            WHERE id = ? AND status != 'deleted'
+            WHERE id = ? AND deleted_at IS NULL
             "#
         )
         .bind(id)
-        .fetch_optional(self.db.pool())
+        .fetch_optional(&mut *conn)
         .await?;
 
         if let Some(row) = row {
@@ -87,8 +197,10 @@ impl CaseStudyRepository {
     }
 
     /// Update case study
+    #[tracing::instrument(name = "db.case_studies.update", skip_all, fields(id = %id))]
     pub async fn update(&self, id: &str, update: UpdateCaseStudy) -> Result<Option<CaseStudy>> {
-        let current = match self.find_by_id(id).await? {
+        let mut tx = self.db.pool().begin().await?;
+        let current = match self.find_by_id_on(&mut tx, id).await? {
             Some(case_study) => case_study,
             None => return Ok(None),
         };
@@ -108,7 +220,7 @@ impl CaseStudyRepository {
                 .bind(title)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(description) = &update.description {
@@ -116,17 +228,21 @@ impl CaseStudyRepository {
                 .bind(description)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(content) = &update.content {
             let word_count = content.split_whitespace().count() as i32;
-            sqlx::query("UPDATE case_studies SET content = ?, word_count = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+            let mut metadata = update.metadata.clone().unwrap_or_else(|| current.metadata.clone());
+            metadata.readability = Some(readability::analyze(content));
+            let metadata_json = serde_json::to_string(&metadata)?;
+            sqlx::query("UPDATE case_studies SET content = ?, word_count = ?, metadata = ?, updated_at = ?, version = version + 1 WHERE id = ?")
                 .bind(content)
                 .bind(word_count)
+                .bind(&metadata_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(summary) = &update.summary {
@@ -134,7 +250,7 @@ impl CaseStudyRepository {
                 .bind(summary)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(category_id) = &update.category_id {
@@ -142,7 +258,7 @@ impl CaseStudyRepository {
                 .bind(category_id)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(industry) = &update.industry {
@@ -150,7 +266,7 @@ impl CaseStudyRepository {
                 .bind(industry)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(difficulty_level) = &update.difficulty_level {
@@ -158,7 +274,7 @@ impl CaseStudyRepository {
                 .bind(difficulty_level)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(duration_minutes) = update.duration_minutes {
@@ -166,7 +282,7 @@ impl CaseStudyRepository {
                 .bind(duration_minutes)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(learning_objectives) = &update.learning_objectives {
@@ -175,23 +291,61 @@ impl CaseStudyRepository {
                 .bind(&learning_objectives_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
+        }
+
+        if let Some(tags) = &update.tags {
+            let tags_json = serde_json::to_string(&normalize_tags(tags))?;
+            sqlx::query("UPDATE case_studies SET tags = ?, updated_at = ?, version = version + 1 WHERE id = ?")
+                .bind(&tags_json)
+                .bind(&now)
+                .bind(id)
+                .execute(&mut *tx).await?;
         }
 
-        if let Some(metadata) = &update.metadata {
-            let metadata_json = serde_json::to_string(metadata)?;
+        // Metadata given alongside new content was already written with the content
+        if let Some(metadata) = update.metadata.as_ref().filter(|_| update.content.is_none()) {
+            let mut metadata = metadata.clone();
+            metadata.readability = Some(readability::analyze(&current.content));
+            let metadata_json = serde_json::to_string(&metadata)?;
             sqlx::query("UPDATE case_studies SET metadata = ?, updated_at = ?, version = version + 1 WHERE id = ?")
                 .bind(&metadata_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
+        }
+
+        let updated = self.find_by_id_on(&mut tx, id).await?;
+        if let Some(ref case_study) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(case_study));
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, update.updated_by.as_deref(), &diff).await?;
         }
+        tx.commit().await?;
 
-        self.find_by_id(id).await
+        Ok(updated)
     }
 
     /// Change case study status
     pub async fn update_status(&self, id: &str, status: CaseStudyStatus) -> Result<Option<CaseStudy>> {
+        let mut tx = self.db.pool().begin().await?;
+        let updated = self.update_status_on(&mut tx, id, status).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    /// Update case study status on a specific connection, e.g. inside a transaction.
+    /// Bumps the version so the status change can be recorded as its own version.
+    pub async fn update_status_on(
+        &self,
+        conn: &mut SqliteConnection,
+        id: &str,
+        status: CaseStudyStatus,
+    ) -> Result<Option<CaseStudy>> {
+        let current = match self.find_by_id_on(&mut *conn, id).await? {
+            Some(case_study) => case_study,
+            None => return Ok(None),
+        };
+
         let now = Utc::now();
         let published_at = if status == CaseStudyStatus::Published {
             Some(now)
@@ -205,7 +359,7 @@ impl CaseStudyRepository {
         };
 
         sqlx::query(
-            "UPDATE case_studies SET status = ?, published_at = ?, archived_at = ?, updated_at = ? WHERE id = ?"
+            "UPDATE case_studies SET status = ?, published_at = ?, archived_at = ?, version = version + 1, updated_at = ? WHERE id = ?"
         )
         .bind(match status {
             CaseStudyStatus::Draft => "draft",
@@ -218,23 +372,252 @@ impl CaseStudyRepository {
         .bind(archived_at)
         .bind(now)
         .bind(id)
-        .execute(self.db.pool())
+        .execute(&mut *conn)
         .await?;
 
-        self.find_by_id(id).await
+        let updated = self.find_by_id_on(&mut *conn, id).await?;
+        if let Some(ref case_study) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(case_study));
+            AuditLogger::record_on(conn, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Begin a transaction for multi-statement updates
+    pub async fn begin(&self) -> Result<Transaction<'static, Sqlite>> {
+        Ok(self.db.pool().begin().await?)
     }
 
     /// Delete case study (soft delete)
-    pub async fn delete(&self, id: &str) -> Result<bool> {
+    #[tracing::instrument(name = "db.case_studies.delete", skip_all, fields(id = %id))]
+    pub async fn delete(&self, id: &str, deleted_by: Option<&str>) -> Result<bool> {
+        let now = Utc::now();
+        let mut tx = self.db.pool().begin().await?;
         let result = sqlx::query(
-            "UPDATE case_studies SET status = 'deleted', updated_at = ? WHERE id = ? AND status != 'deleted'"
+            "UPDATE case_studies SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL"
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            let diff = serde_json::json!({ "deleted_at": { "old": null, "new": now } });
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, deleted_by, &diff).await?;
+        }
+        tx.commit().await?;
+
+        Ok(deleted)
+    }
+
+    /// Restore a soft-deleted case study; legacy rows deleted by status return as drafts
+    pub async fn restore_deleted(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let previous: Option<(chrono::DateTime<Utc>, String)> = sqlx::query_as(
+            "SELECT deleted_at, status FROM case_studies WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((deleted_at, status)) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            "UPDATE case_studies SET deleted_at = NULL, status = CASE WHEN status = 'deleted' THEN 'draft' ELSE status END, updated_at = ? WHERE id = ?"
         )
         .bind(Utc::now())
         .bind(id)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        let mut diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null } });
+        if status == "deleted" {
+            diff["status"] = serde_json::json!({ "old": "deleted", "new": "draft" });
+        }
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Permanently remove a soft-deleted case study along with the rows that depend on it
+    pub async fn purge(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let deleted_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT deleted_at FROM case_studies WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(deleted_at) = deleted_at else {
+            return Ok(false);
+        };
+
+        let mut report = BulkDeleteReport::default();
+        let collections = Self::delete_dependents_on(&mut tx, id, &mut report).await?;
+        sqlx::query("DELETE FROM case_studies WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        Self::compact_collections_on(&mut tx, &collections).await?;
+
+        let diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null }, "purged": true });
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, None, &diff).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Delete case studies in one transaction. Without `permanent` live case studies are
+    /// moved to the trash like `delete`; ids already in the trash are reported as not found.
+    /// With `permanent` live and trashed case studies are removed along with the rows that
+    /// depend on them; dependents go first so no foreign key is left dangling, and
+    /// collections that lose members are renumbered. Unknown ids are reported, not errors.
+    #[tracing::instrument(name = "db.case_studies.bulk_delete", skip_all, fields(count = ids.len(), permanent = permanent))]
+    pub async fn bulk_delete(&self, ids: &[String], permanent: bool, deleted_by: Option<&str>) -> Result<BulkDeleteReport> {
+        let mut tx = self.db.pool().begin().await?;
+        let report = Self::bulk_delete_on(&mut tx, ids, permanent, deleted_by).await?;
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    /// `bulk_delete` on a specific connection, e.g. inside a caller's transaction
+    pub async fn bulk_delete_on(
+        conn: &mut SqliteConnection,
+        ids: &[String],
+        permanent: bool,
+        deleted_by: Option<&str>,
+    ) -> Result<BulkDeleteReport> {
+        let mut report = BulkDeleteReport { permanent, ..BulkDeleteReport::default() };
+        let mut collections = Vec::new();
+        let now = Utc::now();
+
+        for id in ids {
+            if report.deleted_ids.contains(id) || report.not_found.contains(id) {
+                continue;
+            }
+            let deleted_at: Option<Option<chrono::DateTime<Utc>>> =
+                sqlx::query_scalar("SELECT deleted_at FROM case_studies WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+            let deleted_at = match deleted_at {
+                Some(None) if !permanent => {
+                    sqlx::query("UPDATE case_studies SET deleted_at = ?, updated_at = ? WHERE id = ?")
+                        .bind(now)
+                        .bind(now)
+                        .bind(id)
+                        .execute(&mut *conn)
+                        .await?;
+
+                    let diff = serde_json::json!({ "deleted_at": { "old": null, "new": now } });
+                    AuditLogger::record_on(&mut *conn, AUDIT_ENTITY, id, AuditAction::Delete, deleted_by, &diff).await?;
+                    report.deleted_ids.push(id.clone());
+                    continue;
+                }
+                Some(deleted_at) if permanent => deleted_at,
+                _ => {
+                    report.not_found.push(id.clone());
+                    continue;
+                }
+            };
+
+            for collection_id in Self::delete_dependents_on(&mut *conn, id, &mut report).await? {
+                if !collections.contains(&collection_id) {
+                    collections.push(collection_id);
+                }
+            }
+            sqlx::query("DELETE FROM case_studies WHERE id = ?")
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+
+            let diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null }, "purged": true });
+            AuditLogger::record_on(&mut *conn, AUDIT_ENTITY, id, AuditAction::Delete, deleted_by, &diff).await?;
+            report.deleted_ids.push(id.clone());
+        }
+
+        Self::compact_collections_on(&mut *conn, &collections).await?;
+        Ok(report)
+    }
+
+    /// Remove the rows that reference a case study, adding them to `report`. Returns the
+    /// collections it was removed from.
+    async fn delete_dependents_on(
+        conn: &mut SqliteConnection,
+        id: &str,
+        report: &mut BulkDeleteReport,
+    ) -> Result<Vec<i64>> {
+        let collections: Vec<i64> = sqlx::query_scalar(
+            "SELECT collection_id FROM collection_case_studies WHERE case_study_id = ?"
+        )
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let attachment_files: Vec<String> = sqlx::query_scalar(
+            "SELECT file_path FROM attachments WHERE case_study_id = ?"
+        )
+        .bind(id)
+        .fetch_all(&mut *conn)
+        .await?;
+        report.attachment_files.extend(attachment_files);
+
+        let delete = |sql: &'static str| sqlx::query(sql).bind(id.to_string());
+        report.assessment_questions += delete("DELETE FROM assessment_questions WHERE case_study_id = ?")
+            .execute(&mut *conn).await?.rows_affected();
+        report.user_progress += delete("DELETE FROM user_progress WHERE case_study_id = ?")
+            .execute(&mut *conn).await?.rows_affected();
+        report.collection_links += delete("DELETE FROM collection_case_studies WHERE case_study_id = ?")
+            .execute(&mut *conn).await?.rows_affected();
+        report.attachments += delete("DELETE FROM attachments WHERE case_study_id = ?")
+            .execute(&mut *conn).await?.rows_affected();
+        report.generation_history_unlinked += delete("UPDATE generation_history SET case_study_id = NULL WHERE case_study_id = ?")
+            .execute(&mut *conn).await?.rows_affected();
+
+        Ok(collections)
+    }
+
+    /// Close the gaps left in collection order by removed members
+    async fn compact_collections_on(conn: &mut SqliteConnection, collections: &[i64]) -> Result<()> {
+        for &collection_id in collections {
+            compact_order(conn, collection_id).await.map_err(|e| match e {
+                CollectionError::DatabaseError(e) => CaseStudyError::DatabaseError(e),
+                other => CaseStudyError::InvalidData(other.to_string()),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// List soft-deleted case studies, most recently deleted first
+    pub async fn list_deleted(&self, limit: i32, offset: i32) -> Result<Vec<CaseStudy>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, title, description, content, summary, status, category_id,
+                   industry, difficulty_level, duration_minutes, word_count,
+                   learning_objectives, tags, metadata, version, created_by,
+                   created_at, updated_at, published_at, archived_at
+            FROM case_studies
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            LIMIT ? OFFSET ?
+            "#
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut case_studies = Vec::new();
+        for row in rows {
+            case_studies.push(self.parse_case_study_row(row).await?);
+        }
+
+        Ok(case_studies)
     }
 
     /// List case studies with basic filtering and pagination
@@ -244,10 +627,10 @@ impl CaseStudyRepository {
             r#"
             SELECT id, title, description, content, summary, status, category_id,
                    industry, difficulty_level, duration_minutes, word_count,
-                   learning_objectives, metadata, version, created_by,
+                   learning_objectives, tags, metadata, version, created_by,
                    created_at, updated_at, published_at, archived_at
             FROM case_studies 
-            WHERE status != 'deleted'
+            WHERE deleted_at IS NULL
             ORDER BY updated_at DESC
             LIMIT ? OFFSET ?
             "#
@@ -271,25 +654,273 @@ impl CaseStudyRepository {
         self.list(CaseStudyFilter::default(), limit, offset).await
     }
 
-    /// Get case study statistics  
+    /// Id, title and content of every case study that hasn't been deleted
+    pub async fn list_titles_and_content(&self) -> Result<Vec<(String, String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            "SELECT id, title, content FROM case_studies WHERE deleted_at IS NULL"
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        Ok(rows)
+    }
+
+    /// List case studies newest first, one keyset page at a time. Unlike `list`,
+    /// rows inserted or deleted between requests never shift later pages.
+    #[tracing::instrument(name = "db.case_studies.list", skip_all)]
+    pub async fn list_page(&self, filter: CaseStudyFilter, page: PageRequest) -> Result<CaseStudyPage> {
+        self.fetch_page(None, &filter, page).await
+    }
+
+    /// Full-text search paged the same way as `list_page`, ordered by `(created_at, id)`
+    /// rather than relevance. A query with no searchable terms lists everything.
+    #[tracing::instrument(name = "db.case_studies.search", skip_all)]
+    pub async fn search_page(&self, query: &str, filter: CaseStudyFilter, page: PageRequest) -> Result<CaseStudyPage> {
+        self.fetch_page(build_match_expression(query), &filter, page).await
+    }
+
+    async fn fetch_page(
+        &self,
+        match_expression: Option<String>,
+        filter: &CaseStudyFilter,
+        page: PageRequest,
+    ) -> Result<CaseStudyPage> {
+        let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        // The cursor carries created_at exactly as stored so comparisons match the ORDER BY
+        let mut sql_query = String::from(
+            r#"
+            SELECT id, title, description, content, summary, status, category_id,
+                   industry, difficulty_level, duration_minutes, word_count,
+                   learning_objectives, tags, metadata, version, created_by,
+                   created_at, updated_at, published_at, archived_at,
+                   CAST(created_at AS TEXT) AS cursor_created_at
+            FROM case_studies
+            WHERE deleted_at IS NULL
+            "#
+        );
+        let mut params = Vec::new();
+
+        if let Some(match_expression) = match_expression {
+            sql_query.push_str(" AND id IN (SELECT case_study_id FROM case_studies_fts WHERE case_studies_fts MATCH ?)");
+            params.push(match_expression);
+        }
+        push_filter_clauses(&mut sql_query, &mut params, filter);
+        if let Some(cursor) = page.cursor.as_deref() {
+            let (created_at, id) = decode_cursor(cursor)?;
+            sql_query.push_str(" AND (created_at < ? OR (created_at = ? AND id < ?))");
+            params.extend([created_at.clone(), created_at, id]);
+        }
+        // Fetch one extra row to learn whether another page follows
+        sql_query.push_str(" ORDER BY created_at DESC, id DESC LIMIT ?");
+
+        let mut query_builder = sqlx::query(&sql_query);
+        for param in params {
+            query_builder = query_builder.bind(param);
+        }
+        let mut rows = query_builder.bind(limit + 1).fetch_all(self.db.pool()).await?;
+
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            let last = &rows[rows.len() - 1];
+            Some(encode_cursor(&last.try_get::<String, _>("cursor_created_at")?, &last.try_get::<String, _>("id")?))
+        } else {
+            None
+        };
+
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            items.push(self.parse_case_study_row(row).await?);
+        }
+
+        Ok(CaseStudyPage { items, next_cursor })
+    }
+
+    /// Aggregate statistics over non-deleted case studies
+    #[tracing::instrument(name = "db.case_studies.statistics", skip_all)]
     pub async fn get_statistics(&self) -> Result<CaseStudyStatistics> {
-        // Simplified implementation returning default/empty statistics
-        // This avoids SQLx query macro issues while maintaining API compatibility
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS total_count,
+                   COALESCE(SUM(status = 'published'), 0) AS published_count,
+                   COALESCE(SUM(status = 'draft'), 0) AS draft_count,
+                   COALESCE(SUM(status = 'archived'), 0) AS archived_count,
+                   COALESCE(AVG(word_count), 0.0) AS average_word_count,
+                   COALESCE(AVG(duration_minutes), 0.0) AS average_duration
+            FROM case_studies
+            WHERE deleted_at IS NULL
+            "#
+        )
+        .fetch_one(self.db.pool())
+        .await?;
+
+        let categories = sqlx::query(
+            "SELECT category_id, COUNT(*) AS count FROM case_studies \
+             WHERE deleted_at IS NULL AND category_id IS NOT NULL GROUP BY category_id"
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        let mut categories_distribution = HashMap::new();
+        for row in categories {
+            categories_distribution.insert(row.try_get("category_id")?, row.try_get("count")?);
+        }
+
+        // Last twelve months with any activity, oldest first
+        let months = sqlx::query(
+            r#"
+            SELECT CAST(substr(created_at, 1, 4) AS INTEGER) AS year,
+                   CAST(substr(created_at, 6, 2) AS INTEGER) AS month,
+                   COUNT(*) AS count
+            FROM case_studies
+            WHERE deleted_at IS NULL
+            GROUP BY substr(created_at, 1, 7)
+            ORDER BY substr(created_at, 1, 7) DESC
+            LIMIT 12
+            "#
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        let mut monthly_creation_trend = months
+            .into_iter()
+            .map(|row| Ok(MonthlyCount {
+                year: row.try_get("year")?,
+                month: row.try_get("month")?,
+                count: row.try_get("count")?,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        monthly_creation_trend.reverse();
+
         Ok(CaseStudyStatistics {
-            total_count: 0,
-            published_count: 0,
-            draft_count: 0,
-            archived_count: 0,
-            average_word_count: 0.0,
-            average_duration: 0.0,
-            most_popular_industry: None,
-            most_popular_difficulty: None,
-            categories_distribution: HashMap::new(),
-            monthly_creation_trend: Vec::new(),
-            top_tags: Vec::new(),
+            total_count: totals.try_get("total_count")?,
+            published_count: totals.try_get("published_count")?,
+            draft_count: totals.try_get("draft_count")?,
+            archived_count: totals.try_get("archived_count")?,
+            average_word_count: totals.try_get("average_word_count")?,
+            average_duration: totals.try_get("average_duration")?,
+            most_popular_industry: self.most_common_value("industry").await?,
+            most_popular_difficulty: self.most_common_value("difficulty_level").await?,
+            categories_distribution,
+            monthly_creation_trend,
+            top_tags: self.list_tags_with_counts(None, 10).await?,
         })
     }
 
+    /// Most frequent value of `column` among non-deleted case studies; ties go alphabetically
+    async fn most_common_value(&self, column: &str) -> Result<Option<String>> {
+        let value: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT {column} FROM case_studies WHERE deleted_at IS NULL \
+             GROUP BY {column} ORDER BY COUNT(*) DESC, {column} ASC LIMIT 1"
+        ))
+        .fetch_optional(self.db.pool())
+        .await?;
+        Ok(value)
+    }
+
+    /// Rename tag `from` to `to` on every case study that uses it, including deleted ones.
+    /// Both tags are normalized first. Returns the number of case studies rewritten.
+    pub async fn merge_tags(&self, from: &str, to: &str, merged_by: Option<&str>) -> Result<u64> {
+        let from = normalize_tag(from)
+            .ok_or_else(|| CaseStudyError::InvalidData("Tag to merge cannot be empty".to_string()))?;
+        let to = normalize_tag(to)
+            .ok_or_else(|| CaseStudyError::InvalidData("Target tag cannot be empty".to_string()))?;
+        if from == to {
+            return Ok(0);
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        let rows = sqlx::query(
+            "SELECT id, tags FROM case_studies WHERE EXISTS (SELECT 1 FROM json_each(case_studies.tags) WHERE value = ?)"
+        )
+        .bind(&from)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let now = Utc::now();
+        let mut merged = 0;
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let tags_json: String = row.try_get("tags")?;
+            let tags: Vec<String> = serde_json::from_str(&tags_json)?;
+            let Some(new_tags) = replace_tag(&tags, &from, &to) else {
+                continue;
+            };
+
+            sqlx::query("UPDATE case_studies SET tags = ?, updated_at = ? WHERE id = ?")
+                .bind(serde_json::to_string(&new_tags)?)
+                .bind(now)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+
+            let diff = serde_json::json!({ "tags": { "old": tags, "new": new_tags } });
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, &id, AuditAction::Update, merged_by, &diff).await?;
+            merged += 1;
+        }
+        tx.commit().await?;
+
+        Ok(merged)
+    }
+
+    /// Move every case study in industry `from`, matched ignoring case and including deleted
+    /// ones, to industry `to`. Like a tag merge this is not a content edit, so no version is
+    /// created; each case study gets an audit entry. Returns the number rewritten.
+    pub async fn rename_industry_on(
+        conn: &mut SqliteConnection,
+        from: &str,
+        to: &str,
+        renamed_by: Option<&str>,
+    ) -> Result<u64> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, industry FROM case_studies WHERE industry = ? COLLATE NOCASE AND industry != ?"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let now = Utc::now();
+        for (id, industry) in &rows {
+            sqlx::query("UPDATE case_studies SET industry = ?, updated_at = ? WHERE id = ?")
+                .bind(to)
+                .bind(now)
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+
+            let diff = serde_json::json!({ "industry": { "old": industry, "new": to } });
+            AuditLogger::record_on(&mut *conn, AUDIT_ENTITY, id, AuditAction::Update, renamed_by, &diff).await?;
+        }
+
+        Ok(rows.len() as u64)
+    }
+
+    /// Tags in use on non-deleted case studies with how many use each, most used first.
+    /// `prefix` narrows the list for autocomplete and is normalized like a tag.
+    pub async fn list_tags_with_counts(&self, prefix: Option<&str>, limit: i32) -> Result<Vec<TagUsage>> {
+        let prefix = prefix.and_then(normalize_tag).unwrap_or_default();
+        let rows = sqlx::query(
+            r#"
+            SELECT tag.value AS tag_name, COUNT(DISTINCT cs.id) AS usage_count
+            FROM case_studies cs, json_each(cs.tags) AS tag
+            WHERE cs.deleted_at IS NULL AND substr(tag.value, 1, length(?)) = ?
+            GROUP BY tag.value
+            ORDER BY usage_count DESC, tag_name ASC
+            LIMIT ?
+            "#
+        )
+        .bind(&prefix)
+        .bind(&prefix)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(TagUsage {
+                tag_name: row.try_get("tag_name")?,
+                usage_count: row.try_get("usage_count")?,
+            }))
+            .collect()
+    }
+
     /// Get case studies by category
     pub async fn list_by_category(&self, category_id: &str, limit: i32, offset: i32) -> Result<Vec<CaseStudy>> {
         let filter = CaseStudyFilter {
@@ -318,7 +949,7 @@ impl CaseStudyRepository {
     /// Check if case study exists
     pub async fn exists(&self, id: &str) -> Result<bool> {
         let count = sqlx::query(
-            "SELECT COUNT(*) as count FROM case_studies WHERE id = ? AND status != 'deleted'"
+            "SELECT COUNT(*) as count FROM case_studies WHERE id = ? AND deleted_at IS NULL"
         )
         .bind(id)
         .fetch_one(self.db.pool())
@@ -334,7 +965,7 @@ impl CaseStudyRepository {
         // For now, provide basic counts for main use cases
         if let Some(status) = filter.status {
             let count = sqlx::query(
-                "SELECT COUNT(*) as count FROM case_studies WHERE status != 'deleted' AND status = ?"
+                "SELECT COUNT(*) as count FROM case_studies WHERE deleted_at IS NULL AND status = ?"
             )
             .bind(match status {
                 CaseStudyStatus::Draft => "draft",
@@ -351,7 +982,7 @@ impl CaseStudyRepository {
 
         if let Some(ref category_id) = filter.category_id {
             let count = sqlx::query(
-                "SELECT COUNT(*) as count FROM case_studies WHERE status != 'deleted' AND category_id = ?"
+                "SELECT COUNT(*) as count FROM case_studies WHERE deleted_at IS NULL AND category_id = ?"
             )
             .bind(category_id)
             .fetch_one(self.db.pool())
@@ -362,7 +993,7 @@ impl CaseStudyRepository {
 
         if let Some(ref industry) = filter.industry {
             let count = sqlx::query(
-                "SELECT COUNT(*) as count FROM case_studies WHERE status != 'deleted' AND industry = ?"
+                "SELECT COUNT(*) as count FROM case_studies WHERE deleted_at IS NULL AND industry = ?"
             )
             .bind(industry)
             .fetch_one(self.db.pool())
@@ -373,7 +1004,7 @@ impl CaseStudyRepository {
 
         // Default: count all non-deleted case studies
         let count = sqlx::query(
-            "SELECT COUNT(*) as count FROM case_studies WHERE status != 'deleted'"
+            "SELECT COUNT(*) as count FROM case_studies WHERE deleted_at IS NULL"
         )
         .fetch_one(self.db.pool())
         .await?;
@@ -385,9 +1016,11 @@ impl CaseStudyRepository {
     /// Helper method to parse case study row
     async fn parse_case_study_row(&self, row: sqlx::sqlite::SqliteRow) -> Result<CaseStudy> {
         let learning_objectives_json: String = row.try_get("learning_objectives")?;
+        let tags_json: String = row.try_get("tags")?;
         let metadata_json: String = row.try_get("metadata")?;
 
         let learning_objectives: Vec<String> = serde_json::from_str(&learning_objectives_json)?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json)?;
         let metadata: CaseStudyMetadata = serde_json::from_str(&metadata_json)?;
 
         let status_str: String = row.try_get("status")?;
@@ -412,6 +1045,7 @@ impl CaseStudyRepository {
             duration_minutes: row.try_get("duration_minutes")?,
             word_count: row.try_get("word_count")?,
             learning_objectives,
+            tags,
             metadata,
             version: row.try_get("version")?,
             created_by: row.try_get("created_by")?,
@@ -421,4 +1055,217 @@ impl CaseStudyRepository {
             archived_at: row.try_get("archived_at")?,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Encode a page position as an opaque, URL-safe token
+fn encode_cursor(created_at: &str, id: &str) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}\n{}", created_at, id))
+}
+
+fn decode_cursor(cursor: &str) -> Result<(String, String)> {
+    let invalid = || CaseStudyError::InvalidData("Invalid page cursor".to_string());
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let (created_at, id) = decoded.split_once('\n').ok_or_else(invalid)?;
+    Ok((created_at.to_string(), id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn repository() -> CaseStudyRepository {
+        CaseStudyRepository::new(DatabaseManager::in_memory().await.unwrap())
+    }
+
+    async fn create(repository: &CaseStudyRepository, title: &str, industry: &str) -> CaseStudy {
+        repository
+            .create(NewCaseStudy {
+                industry: industry.to_string(),
+                ..NewCaseStudy::for_test(title, &format!("{} faces a difficult decision.", title))
+            })
+            .await
+            .unwrap()
+    }
+
+    fn page(limit: i32, cursor: Option<String>) -> PageRequest {
+        PageRequest { limit: Some(limit), cursor }
+    }
+
+    #[tokio::test]
+    async fn test_pages_are_stable_across_inserts() {
+        let repository = repository().await;
+        let mut expected = Vec::new();
+        for i in 0..5 {
+            expected.push(create(&repository, &format!("Case {}", i), "Retail").await.id);
+        }
+        expected.reverse();
+
+        let first = repository.list_page(CaseStudyFilter::default(), page(2, None)).await.unwrap();
+        let mut seen: Vec<String> = first.items.iter().map(|c| c.id.clone()).collect();
+
+        // A newer case study sorts before the cursor and an older one after it
+        create(&repository, "Newer", "Retail").await;
+        let older = create(&repository, "Older", "Retail").await;
+        sqlx::query("UPDATE case_studies SET created_at = '2000-01-01T00:00:00+00:00' WHERE id = ?")
+            .bind(&older.id)
+            .execute(repository.db.pool())
+            .await
+            .unwrap();
+        expected.push(older.id);
+
+        let mut cursor = first.next_cursor;
+        while let Some(next) = cursor {
+            let page = repository.list_page(CaseStudyFilter::default(), page(2, Some(next))).await.unwrap();
+            assert!(page.items.len() <= 2);
+            seen.extend(page.items.iter().map(|c| c.id.clone()));
+            cursor = page.next_cursor;
+        }
+
+        assert_eq!(seen, expected);
+    }
+
+    #[tokio::test]
+    async fn test_search_page_applies_query_and_filters() {
+        let repository = repository().await;
+        let retail = create(&repository, "Pricing at Nordic Outfitters", "Retail").await;
+        create(&repository, "Pricing at Harbor Bank", "Finance").await;
+        create(&repository, "Leadership at Solis Energy", "Retail").await;
+
+        let filter = CaseStudyFilter { industry: Some("Retail".to_string()), ..Default::default() };
+        let results = repository.search_page("pricing", filter, page(10, None)).await.unwrap();
+        assert_eq!(results.items.len(), 1);
+        assert_eq!(results.items[0].id, retail.id);
+        assert!(results.next_cursor.is_none());
+
+        let exact = repository.list_page(CaseStudyFilter::default(), page(3, None)).await.unwrap();
+        assert_eq!(exact.items.len(), 3);
+        assert!(exact.next_cursor.is_none());
+
+        let invalid = repository.list_page(CaseStudyFilter::default(), page(3, Some("not a cursor".to_string()))).await;
+        assert!(matches!(invalid, Err(CaseStudyError::InvalidData(_))));
+    }
+
+    async fn count_rows(repository: &CaseStudyRepository, table: &str, case_study_id: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE case_study_id = ?", table))
+            .bind(case_study_id)
+            .fetch_one(repository.db.pool())
+            .await
+            .unwrap()
+    }
+
+    /// A case study with two questions, progress, an attachment, generation history and
+    /// membership of `collection_id`
+    async fn case_study_with_dependents(repository: &CaseStudyRepository, title: &str, user_id: i64, collection_id: i64) -> String {
+        use crate::database::{AssessmentQuestionRepository, CollectionRepository, NewCaseStudyQuestion, QuestionType};
+
+        let id = create(repository, title, "Retail").await.id;
+        let pool = repository.db.pool().clone();
+        let question = |text: &str| NewCaseStudyQuestion {
+            question_text: text.to_string(),
+            question_type: QuestionType::Essay,
+            options: None,
+            correct_answer: None,
+            sample_answer: None,
+            rubric: None,
+            points: 5,
+        };
+        AssessmentQuestionRepository::new(pool.clone())
+            .create_for_case_study(&id, &[question("Evaluate the options."), question("Recommend a course of action.")])
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_progress (user_id, case_study_id, status) VALUES (?, ?, 'in_progress')")
+            .bind(user_id).bind(&id).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO attachments (case_study_id, filename, file_path) VALUES (?, 'data.csv', 'attachments/data.csv')")
+            .bind(&id).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO generation_history (case_study_id, generation_type) VALUES (?, 'case_study')")
+            .bind(&id).execute(&pool).await.unwrap();
+        CollectionRepository::new(pool).add_case_study(collection_id, &id).await.unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_removes_dependents_atomically() {
+        let repository = repository().await;
+        let pool = repository.db.pool().clone();
+        let user_id = sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(&pool).await.unwrap().last_insert_rowid();
+        let collection_id = sqlx::query("INSERT INTO collections (name, created_by) VALUES ('Strategy', ?)")
+            .bind(user_id).execute(&pool).await.unwrap().last_insert_rowid();
+
+        let first = case_study_with_dependents(&repository, "Nordic Outfitters", user_id, collection_id).await;
+        let trashed = case_study_with_dependents(&repository, "Solis Energy", user_id, collection_id).await;
+        let kept = case_study_with_dependents(&repository, "Harbour Freight", user_id, collection_id).await;
+        repository.delete(&trashed, None).await.unwrap();
+
+        // A failure part-way through leaves everything in place
+        sqlx::query(&format!(
+            "CREATE TRIGGER block_delete BEFORE DELETE ON case_studies WHEN OLD.id = '{}' BEGIN SELECT RAISE(ABORT, 'blocked'); END",
+            kept
+        ))
+        .execute(&pool).await.unwrap();
+        assert!(repository.bulk_delete(&[first.clone(), kept.clone()], true, None).await.is_err());
+        assert!(repository.exists(&first).await.unwrap());
+        for table in ["assessment_questions", "user_progress", "attachments", "collection_case_studies", "generation_history"] {
+            assert!(count_rows(&repository, table, &first).await > 0, "{} was changed", table);
+        }
+        sqlx::query("DROP TRIGGER block_delete").execute(&pool).await.unwrap();
+
+        // Soft-deleted case studies are purged too; repeats and unknown ids are harmless
+        let ids = vec![first.clone(), trashed.clone(), "missing".to_string(), first.clone()];
+        let report = repository.bulk_delete(&ids, true, Some("admin")).await.unwrap();
+        assert_eq!(report.deleted_ids, vec![first.clone(), trashed.clone()]);
+        assert_eq!(report.not_found, vec!["missing".to_string()]);
+        assert_eq!(
+            (report.assessment_questions, report.user_progress, report.collection_links, report.attachments, report.generation_history_unlinked),
+            (4, 2, 2, 2, 2)
+        );
+        assert_eq!(report.attachment_files, vec!["attachments/data.csv".to_string(); 2]);
+
+        for id in [&first, &trashed] {
+            for table in ["case_studies_all", "assessment_questions", "user_progress", "attachments", "collection_case_studies", "generation_history"] {
+                let count = match table {
+                    "case_studies_all" => sqlx::query_scalar("SELECT COUNT(*) FROM case_studies WHERE id = ?")
+                        .bind(id).fetch_one(&pool).await.unwrap(),
+                    _ => count_rows(&repository, table, id).await,
+                };
+                assert_eq!(count, 0, "{} still has rows for {}", table, id);
+            }
+        }
+        let history: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM generation_history WHERE case_study_id IS NULL")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(history, 2);
+
+        // The surviving member moves up to the front of the collection
+        let members = crate::database::CollectionRepository::new(pool).list_case_studies(collection_id).await.unwrap();
+        assert_eq!(members.iter().map(|m| (m.case_study_id.clone(), m.order_index)).collect::<Vec<_>>(), vec![(kept.clone(), 0)]);
+        assert_eq!(count_rows(&repository, "assessment_questions", &kept).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_without_permanent_moves_to_trash() {
+        let repository = repository().await;
+        let pool = repository.db.pool().clone();
+        let user_id = sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(&pool).await.unwrap().last_insert_rowid();
+        let collection_id = sqlx::query("INSERT INTO collections (name, created_by) VALUES ('Strategy', ?)")
+            .bind(user_id).execute(&pool).await.unwrap().last_insert_rowid();
+
+        let live = case_study_with_dependents(&repository, "Nordic Outfitters", user_id, collection_id).await;
+        let trashed = case_study_with_dependents(&repository, "Solis Energy", user_id, collection_id).await;
+        repository.delete(&trashed, None).await.unwrap();
+
+        let report = repository.bulk_delete(&[live.clone(), trashed.clone()], false, None).await.unwrap();
+        assert_eq!(report.deleted_ids, vec![live.clone()]);
+        assert_eq!(report.not_found, vec![trashed.clone()]);
+        assert!(report.attachment_files.is_empty());
+
+        // Both sit in the trash with their dependents intact, so either can be restored
+        let deleted: Vec<String> = repository.list_deleted(20, 0).await.unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(deleted.len(), 2);
+        for table in ["assessment_questions", "user_progress", "attachments", "collection_case_studies"] {
+            assert!(count_rows(&repository, table, &live).await > 0, "{} was changed", table);
+        }
+        assert!(repository.restore_deleted(&live).await.unwrap());
+    }
+}
@@ -1,8 +1,11 @@
 // Advanced search engine for case studies
 
 use super::models::*;
+use super::embeddings::{cosine_similarity, decode_embedding, encode_embedding};
+use super::tags::normalize_tags;
 use super::{CaseStudyError, Result};
 use crate::database::DatabaseManager;
+use chrono::Utc;
 use sqlx::Row;
 use std::collections::HashMap;
 
@@ -16,31 +19,33 @@ impl CaseStudySearchEngine {
         Self { db }
     }
 
-    /// Advanced search with multiple criteria and ranking
+    /// Advanced search with multiple criteria, ranked by full-text relevance
     pub async fn search(&self, query: CaseStudySearchQuery) -> Result<Vec<CaseStudy>> {
-        if query.query.trim().is_empty() {
-            return self.list_with_filters(query).await;
-        }
-
-        let search_terms = self.parse_search_query(&query.query);
-        let mut search_results = Vec::new();
-
-        // Perform search with relevance scoring
-        for case_study in self.execute_search_query(&query, &search_terms).await? {
-            let score = self.calculate_relevance_score(&case_study, &search_terms);
-            search_results.push((case_study, score));
-        }
+        Ok(self
+            .search_ranked(query)
+            .await?
+            .into_iter()
+            .map(|hit| hit.case_study)
+            .collect())
+    }
 
-        // Sort by relevance score
-        search_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    /// Full-text search returning each match with its bm25 rank and a highlighted snippet.
+    /// Supports quoted phrases (`"market entry"`) and column filters (`title:strategy`);
+    /// terms are OR-ed so case studies matching more of them rank higher.
+    pub async fn search_ranked(&self, query: CaseStudySearchQuery) -> Result<Vec<CaseStudySearchHit>> {
+        let match_expression = match build_match_expression(&query.query) {
+            Some(expression) => expression,
+            None => {
+                return Ok(self
+                    .list_with_filters(query)
+                    .await?
+                    .into_iter()
+                    .map(|case_study| CaseStudySearchHit { case_study, rank: 0.0, snippet: None })
+                    .collect());
+            }
+        };
 
-        // Extract case studies and apply pagination
-        let case_studies: Vec<CaseStudy> = search_results.into_iter().map(|(cs, _)| cs).collect();
-        
-        let offset = query.offset.unwrap_or(0) as usize;
-        let limit = query.limit.unwrap_or(20) as usize;
-        
-        Ok(case_studies.into_iter().skip(offset).take(limit).collect())
+        self.execute_search_query(&query, match_expression).await
     }
 
     /// Search for similar case studies based on content and metadata
@@ -113,22 +118,106 @@ impl CaseStudySearchEngine {
         Ok(Vec::new())
     }
 
+    /// Store a case study's embedding from `provider`, replacing any previous one
+    pub async fn store_embedding(&self, case_study: &CaseStudy, provider: &str, embedding: &[f32]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO case_study_embeddings
+                (case_study_id, provider, dimensions, embedding, source_updated_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(case_study_id, provider) DO UPDATE SET
+                dimensions = excluded.dimensions,
+                embedding = excluded.embedding,
+                source_updated_at = excluded.source_updated_at,
+                created_at = excluded.created_at
+            "#
+        )
+        .bind(&case_study.id)
+        .bind(provider)
+        .bind(embedding.len() as i64)
+        .bind(encode_embedding(embedding))
+        .bind(case_study.updated_at)
+        .bind(Utc::now())
+        .execute(self.db.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Case studies with no embedding from `provider`, or one taken before their last update
+    pub async fn case_studies_needing_embedding(&self, provider: &str) -> Result<Vec<CaseStudy>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT cs.id, cs.title, cs.description, cs.content, cs.summary, cs.status, cs.category_id,
+                   cs.industry, cs.difficulty_level, cs.duration_minutes, cs.word_count,
+                   cs.learning_objectives, cs.tags, cs.metadata, cs.version, cs.created_by,
+                   cs.created_at, cs.updated_at, cs.published_at, cs.archived_at
+            FROM case_studies cs
+            LEFT JOIN case_study_embeddings e ON e.case_study_id = cs.id AND e.provider = ?
+            WHERE cs.deleted_at IS NULL
+              AND (e.case_study_id IS NULL OR e.source_updated_at != cs.updated_at)
+            ORDER BY cs.created_at
+            "#
+        )
+        .bind(provider)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut case_studies = Vec::with_capacity(rows.len());
+        for row in rows {
+            case_studies.push(self.parse_case_study_row(row).await?);
+        }
+        Ok(case_studies)
+    }
+
+    /// The `k` case studies whose `provider` embeddings are most similar to `query`.
+    /// Embeddings with a different dimension (from another model) are ignored.
+    pub async fn nearest_by_embedding(&self, provider: &str, query: &[f32], k: usize) -> Result<Vec<SemanticSearchHit>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.case_study_id, e.embedding
+            FROM case_study_embeddings e
+            JOIN case_studies cs ON cs.id = e.case_study_id
+            WHERE e.provider = ? AND e.dimensions = ? AND cs.deleted_at IS NULL
+            "#
+        )
+        .bind(provider)
+        .bind(query.len() as i64)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut scored = Vec::with_capacity(rows.len());
+        for row in rows {
+            let case_study_id: String = row.try_get("case_study_id")?;
+            let embedding: Vec<u8> = row.try_get("embedding")?;
+            scored.push((case_study_id, cosine_similarity(query, &decode_embedding(&embedding))));
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(k);
+
+        let mut hits = Vec::with_capacity(scored.len());
+        for (case_study_id, score) in scored {
+            if let Some(case_study) = self.get_case_study_for_search(&case_study_id).await? {
+                hits.push(SemanticSearchHit { case_study, score });
+            }
+        }
+        Ok(hits)
+    }
+
     /// Index case study for search (called when case study is created/updated)
     pub async fn index_case_study(&self, case_study: &CaseStudy) -> Result<()> {
-        // For now, this is a placeholder for future full-text search indexing
-        // In a production system, you might update a dedicated search index here
+        // The FTS index is kept in sync by triggers on case_studies
         Ok(())
     }
 
     /// Update case study index (called when case study is updated)
     pub async fn update_case_study_index(&self, case_study: &CaseStudy) -> Result<()> {
-        // Placeholder for updating search index
+        // Maintained by the case_studies_fts_update trigger
         Ok(())
     }
 
     /// Remove case study from search index (called when case study is deleted)
     pub async fn remove_case_study_index(&self, case_study_id: &str) -> Result<()> {
-        // Placeholder for removing from search index
+        // Maintained by the case_studies_fts_delete trigger
         Ok(())
     }
 
@@ -155,59 +244,72 @@ impl CaseStudySearchEngine {
         self.list_with_filters(query).await
     }
 
-    /// Execute the main search query
+    /// Execute the full-text search query
+    /// Excerpt of a case study around the first whole-word match of the query, with the
+    /// matched text wrapped in `<mark>` tags. Title, description, summary and content are
+    /// tried in that order; `None` when no field contains a query term.
+    pub fn snippet(&self, query: &str, case_study: &CaseStudy) -> Option<String> {
+        let fields = [
+            Some(case_study.title.as_str()),
+            case_study.description.as_deref(),
+            case_study.summary.as_deref(),
+            Some(case_study.content.as_str()),
+        ];
+        fields
+            .into_iter()
+            .flatten()
+            .find_map(|text| extract_snippet(text, query, SNIPPET_RADIUS))
+    }
+
     async fn execute_search_query(
         &self,
         query: &CaseStudySearchQuery,
-        search_terms: &[String],
-    ) -> Result<Vec<CaseStudy>> {
+        match_expression: String,
+    ) -> Result<Vec<CaseStudySearchHit>> {
+        // Column weights follow the FTS column order: case_study_id, title, description,
+        // summary, content, industry
         let mut sql_query = String::from(
             r#"
-            SELECT id, title, description, content, summary, status, category_id,
-                   industry, difficulty_level, duration_minutes, word_count,
-                   learning_objectives, metadata, version, created_by,
-                   created_at, updated_at, published_at, archived_at
-            FROM case_studies 
-            WHERE status != 'deleted'
+            SELECT cs.id, cs.title, cs.description, cs.content, cs.summary, cs.status, cs.category_id,
+                   cs.industry, cs.difficulty_level, cs.duration_minutes, cs.word_count,
+                   cs.learning_objectives, cs.tags, cs.metadata, cs.version, cs.created_by,
+                   cs.created_at, cs.updated_at, cs.published_at, cs.archived_at,
+                   hits.rank AS search_rank, hits.snippet AS search_snippet
+            FROM case_studies cs
+            JOIN (
+                SELECT case_study_id,
+                       bm25(case_studies_fts, 0.0, 10.0, 5.0, 3.0, 2.0, 7.0) AS rank,
+                       snippet(case_studies_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet
+                FROM case_studies_fts
+                WHERE case_studies_fts MATCH ?
+            ) hits ON hits.case_study_id = cs.id
+            WHERE cs.deleted_at IS NULL
             "#
         );
 
-        let mut params: Vec<String> = Vec::new();
-
-        // Add search conditions
-        if !search_terms.is_empty() {
-            let search_conditions: Vec<String> = search_terms
-                .iter()
-                .map(|term| {
-                    params.push(format!("%{}%", term));
-                    params.push(format!("%{}%", term));
-                    params.push(format!("%{}%", term));
-                    params.push(format!("%{}%", term));
-                    "(title LIKE ? OR description LIKE ? OR content LIKE ? OR industry LIKE ?)".to_string()
-                })
-                .collect();
-
-            sql_query.push_str(&format!(" AND ({})", search_conditions.join(" OR ")));
-        }
+        let mut params: Vec<String> = vec![match_expression];
 
         // Apply filters
         self.apply_filters_to_query(&mut sql_query, &mut params, &query.filters).await?;
 
-        // Add sorting
-        if let Some(ref sort_by) = query.sort_by {
-            let sort_order = query.sort_order.as_deref().unwrap_or("desc");
-            match sort_by.as_str() {
-                "title" => sql_query.push_str(&format!(" ORDER BY title {}", sort_order)),
-                "created_at" => sql_query.push_str(&format!(" ORDER BY created_at {}", sort_order)),
-                "updated_at" => sql_query.push_str(&format!(" ORDER BY updated_at {}", sort_order)),
-                "word_count" => sql_query.push_str(&format!(" ORDER BY word_count {}", sort_order)),
-                "duration" => sql_query.push_str(&format!(" ORDER BY duration_minutes {}", sort_order)),
-                _ => sql_query.push_str(" ORDER BY updated_at DESC"),
-            }
-        } else {
-            sql_query.push_str(" ORDER BY updated_at DESC");
+        // Add sorting; bm25 ranks better matches lower
+        let sort_order = query.sort_order.as_deref().unwrap_or("desc");
+        match query.sort_by.as_deref() {
+            Some("title") => sql_query.push_str(&format!(" ORDER BY cs.title {}", sort_order)),
+            Some("created_at") => sql_query.push_str(&format!(" ORDER BY cs.created_at {}", sort_order)),
+            Some("updated_at") => sql_query.push_str(&format!(" ORDER BY cs.updated_at {}", sort_order)),
+            Some("word_count") => sql_query.push_str(&format!(" ORDER BY cs.word_count {}", sort_order)),
+            Some("duration") => sql_query.push_str(&format!(" ORDER BY cs.duration_minutes {}", sort_order)),
+            _ => sql_query.push_str(" ORDER BY hits.rank ASC"),
         }
 
+        // Add pagination
+        sql_query.push_str(&format!(
+            " LIMIT {} OFFSET {}",
+            query.limit.unwrap_or(20),
+            query.offset.unwrap_or(0)
+        ));
+
         // Execute query
         let mut query_builder = sqlx::query(&sql_query);
         for param in params {
@@ -215,13 +317,22 @@ impl CaseStudySearchEngine {
         }
 
         let rows = query_builder.fetch_all(self.db.pool()).await?;
-        let mut case_studies = Vec::new();
+        let mut hits = Vec::new();
 
         for row in rows {
-            case_studies.push(self.parse_case_study_row(row).await?);
+            let rank: f64 = row.try_get("search_rank")?;
+            // FTS snippets count tokens and may include diacritic-folded matches; prefer our
+            // own character-bounded excerpt and fall back to the FTS one
+            let fts_snippet: Option<String> = row.try_get("search_snippet")?;
+            let case_study = self.parse_case_study_row(row).await?;
+            hits.push(CaseStudySearchHit {
+                snippet: self.snippet(&query.query, &case_study).or(fts_snippet),
+                case_study,
+                rank,
+            });
         }
 
-        Ok(case_studies)
+        Ok(hits)
     }
 
     /// List case studies with filters (no text search)
@@ -230,10 +341,10 @@ impl CaseStudySearchEngine {
             r#"
             SELECT id, title, description, content, summary, status, category_id,
                    industry, difficulty_level, duration_minutes, word_count,
-                   learning_objectives, metadata, version, created_by,
+                   learning_objectives, tags, metadata, version, created_by,
                    created_at, updated_at, published_at, archived_at
             FROM case_studies 
-            WHERE status != 'deleted'
+            WHERE deleted_at IS NULL
             "#
         );
 
@@ -287,117 +398,12 @@ impl CaseStudySearchEngine {
         filters: &Option<CaseStudyFilter>,
     ) -> Result<()> {
         if let Some(filter) = filters {
-            if let Some(ref status) = filter.status {
-                sql_query.push_str(" AND status = ?");
-                params.push(status.to_string());
-            }
-
-            if let Some(ref category_id) = filter.category_id {
-                sql_query.push_str(" AND category_id = ?");
-                params.push(category_id.clone());
-            }
-
-            if let Some(ref industry) = filter.industry {
-                sql_query.push_str(" AND industry = ?");
-                params.push(industry.clone());
-            }
-
-            if let Some(ref difficulty_level) = filter.difficulty_level {
-                sql_query.push_str(" AND difficulty_level = ?");
-                params.push(difficulty_level.clone());
-            }
-
-            if let Some(ref created_by) = filter.created_by {
-                sql_query.push_str(" AND created_by = ?");
-                params.push(created_by.clone());
-            }
-
-            if let Some(created_after) = filter.created_after {
-                sql_query.push_str(" AND created_at >= ?");
-                params.push(created_after.to_rfc3339());
-            }
-
-            if let Some(created_before) = filter.created_before {
-                sql_query.push_str(" AND created_at <= ?");
-                params.push(created_before.to_rfc3339());
-            }
-
-            if let Some(min_duration) = filter.min_duration {
-                sql_query.push_str(" AND duration_minutes >= ?");
-                params.push(min_duration.to_string());
-            }
-
-            if let Some(max_duration) = filter.max_duration {
-                sql_query.push_str(" AND duration_minutes <= ?");
-                params.push(max_duration.to_string());
-            }
-
-            if let Some(min_word_count) = filter.min_word_count {
-                sql_query.push_str(" AND word_count >= ?");
-                params.push(min_word_count.to_string());
-            }
-
-            if let Some(max_word_count) = filter.max_word_count {
-                sql_query.push_str(" AND word_count <= ?");
-                params.push(max_word_count.to_string());
-            }
+            push_filter_clauses(sql_query, params, filter);
         }
 
         Ok(())
     }
 
-    /// Parse search query into individual terms
-    fn parse_search_query(&self, query: &str) -> Vec<String> {
-        query
-            .split_whitespace()
-            .filter(|term| term.len() > 2) // Filter out very short terms
-            .map(|term| term.to_lowercase())
-            .collect()
-    }
-
-    /// Calculate relevance score for search results
-    fn calculate_relevance_score(&self, case_study: &CaseStudy, search_terms: &[String]) -> f64 {
-        let mut score = 0.0;
-
-        for term in search_terms {
-            // Title matches have highest weight
-            if case_study.title.to_lowercase().contains(term) {
-                score += 10.0;
-            }
-
-            // Description matches
-            if let Some(ref description) = case_study.description {
-                if description.to_lowercase().contains(term) {
-                    score += 5.0;
-                }
-            }
-
-            // Industry matches
-            if case_study.industry.to_lowercase().contains(term) {
-                score += 7.0;
-            }
-
-            // Content matches (lower weight due to volume)
-            if case_study.content.to_lowercase().contains(term) {
-                score += 2.0;
-            }
-
-            // Learning objectives matches
-            for objective in &case_study.learning_objectives {
-                if objective.to_lowercase().contains(term) {
-                    score += 3.0;
-                }
-            }
-        }
-
-        // Boost score for published content
-        if case_study.status == CaseStudyStatus::Published {
-            score *= 1.2;
-        }
-
-        score
-    }
-
     /// Extract key terms from content for similarity matching
     fn extract_key_terms(&self, content: &str) -> Vec<String> {
         let words: Vec<String> = content
@@ -429,10 +435,10 @@ impl CaseStudySearchEngine {
             r#"
             SELECT id, title, description, content, summary, status, category_id,
                    industry, difficulty_level, duration_minutes, word_count,
-                   learning_objectives, metadata, version, created_by,
+                   learning_objectives, tags, metadata, version, created_by,
                    created_at, updated_at, published_at, archived_at
             FROM case_studies 
-            WHERE id = ? AND status != 'deleted'
+            WHERE id = ? AND deleted_at IS NULL
             "#
         )
         .bind(id)
@@ -449,9 +455,11 @@ impl CaseStudySearchEngine {
     /// Helper method to parse case study row
     async fn parse_case_study_row(&self, row: sqlx::sqlite::SqliteRow) -> Result<CaseStudy> {
         let learning_objectives_json: String = row.try_get("learning_objectives")?;
+        let tags_json: String = row.try_get("tags")?;
         let metadata_json: String = row.try_get("metadata")?;
 
         let learning_objectives: Vec<String> = serde_json::from_str(&learning_objectives_json)?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json)?;
         let metadata: CaseStudyMetadata = serde_json::from_str(&metadata_json)?;
 
         Ok(CaseStudy {
@@ -467,6 +475,7 @@ impl CaseStudySearchEngine {
             duration_minutes: row.try_get("duration_minutes")?,
             word_count: row.try_get("word_count")?,
             learning_objectives,
+            tags,
             metadata,
             version: row.try_get("version")?,
             created_by: row.try_get("created_by")?,
@@ -476,4 +485,370 @@ impl CaseStudySearchEngine {
             archived_at: row.try_get("archived_at")?,
         })
     }
-}
\ No newline at end of file
+}
+
+/// Columns that can be targeted with `column:term` in search queries
+const SEARCHABLE_COLUMNS: &[&str] = &["title", "description", "summary", "content", "industry"];
+
+/// Split search input on whitespace, keeping quoted phrases together
+fn split_search_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+
+    terms
+}
+
+/// Append ` AND ...` clauses for `filter` to a query over unaliased `case_studies` columns
+pub(super) fn push_filter_clauses(sql_query: &mut String, params: &mut Vec<String>, filter: &CaseStudyFilter) {
+    if let Some(ref status) = filter.status {
+        sql_query.push_str(" AND status = ?");
+        params.push(status.to_string());
+    }
+
+    if let Some(ref category_id) = filter.category_id {
+        sql_query.push_str(" AND category_id = ?");
+        params.push(category_id.clone());
+    }
+
+    if let Some(ref industry) = filter.industry {
+        sql_query.push_str(" AND industry = ?");
+        params.push(industry.clone());
+    }
+
+    if let Some(ref difficulty_level) = filter.difficulty_level {
+        sql_query.push_str(" AND difficulty_level = ?");
+        params.push(difficulty_level.clone());
+    }
+
+    if let Some(ref created_by) = filter.created_by {
+        sql_query.push_str(" AND created_by = ?");
+        params.push(created_by.clone());
+    }
+
+    if let Some(created_after) = filter.created_after {
+        sql_query.push_str(" AND created_at >= ?");
+        params.push(created_after.to_rfc3339());
+    }
+
+    if let Some(created_before) = filter.created_before {
+        sql_query.push_str(" AND created_at <= ?");
+        params.push(created_before.to_rfc3339());
+    }
+
+    if let Some(min_duration) = filter.min_duration {
+        sql_query.push_str(" AND duration_minutes >= ?");
+        params.push(min_duration.to_string());
+    }
+
+    if let Some(max_duration) = filter.max_duration {
+        sql_query.push_str(" AND duration_minutes <= ?");
+        params.push(max_duration.to_string());
+    }
+
+    if let Some(min_word_count) = filter.min_word_count {
+        sql_query.push_str(" AND word_count >= ?");
+        params.push(min_word_count.to_string());
+    }
+
+    if let Some(max_word_count) = filter.max_word_count {
+        sql_query.push_str(" AND word_count <= ?");
+        params.push(max_word_count.to_string());
+    }
+
+    if let Some(ref tags) = filter.tags {
+        for tag in normalize_tags(tags) {
+            sql_query.push_str(" AND EXISTS (SELECT 1 FROM json_each(tags) WHERE value = ?)");
+            params.push(tag);
+        }
+    }
+}
+
+/// Characters of context kept on each side of a snippet's match
+const SNIPPET_RADIUS: usize = 60;
+
+/// Longest snippet returned, in characters, not counting markers and ellipses
+const SNIPPET_MAX_CHARS: usize = 240;
+
+/// A search term to look for in snippet text
+struct SnippetTerm {
+    chars: Vec<char>,
+    /// A trailing `*` lets the term end mid-word
+    is_prefix: bool,
+}
+
+/// Letters, digits and combining marks all continue a word, so accents written as
+/// combining characters don't count as word boundaries
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c as u32, 0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F)
+}
+
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Terms from search input, without column filters, quotes or prefix markers
+fn snippet_terms(query: &str) -> Vec<SnippetTerm> {
+    split_search_terms(query)
+        .into_iter()
+        .filter_map(|term| {
+            let (has_column, text) = match term.split_once(':') {
+                Some((column, rest)) if SEARCHABLE_COLUMNS.contains(&column.to_lowercase().as_str()) => (true, rest.to_string()),
+                _ => (false, term),
+            };
+            let is_phrase = text.starts_with('"');
+            let is_prefix = !is_phrase && text.ends_with('*');
+            let words = text.trim_matches('"').trim_end_matches('*').replace('"', "");
+            let chars: Vec<char> = words.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+
+            // Skip the same too-short bare terms the MATCH expression does
+            let too_short = !is_phrase && !has_column && chars.len() <= 2;
+            (!too_short && chars.iter().any(|c| c.is_alphanumeric())).then_some(SnippetTerm { chars, is_prefix })
+        })
+        .collect()
+}
+
+/// Length of `term` if it matches `text` at `start` on word boundaries
+fn match_at(text: &[char], start: usize, term: &SnippetTerm) -> Option<usize> {
+    let end = start + term.chars.len();
+    if end > text.len() || (start > 0 && is_word_char(text[start - 1]) && is_word_char(text[start])) {
+        return None;
+    }
+    if !text[start..end].iter().zip(&term.chars).all(|(&a, &b)| chars_match(a, b)) {
+        return None;
+    }
+    let ends_word = end == text.len() || !is_word_char(text[end]) || !is_word_char(text[end - 1]);
+    (ends_word || term.is_prefix).then_some(term.chars.len())
+}
+
+/// Excerpt of `text` around the first whole-word match of any query term, keeping up to
+/// `radius` characters on each side and wrapping the match in `<mark>` tags. Whitespace is
+/// collapsed, the window is trimmed back to word boundaries, and the text is sliced by
+/// character so the result is always valid UTF-8. Elided ends are marked with `…`.
+pub fn extract_snippet(text: &str, query: &str, radius: usize) -> Option<String> {
+    let terms = snippet_terms(query);
+    if terms.is_empty() {
+        return None;
+    }
+
+    let text: Vec<char> = text.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+    let (start, length) = (0..text.len()).find_map(|start| {
+        terms
+            .iter()
+            .filter_map(|term| match_at(&text, start, term))
+            .max()
+            .map(|length| (start, length))
+    })?;
+    let end = start + length;
+
+    let radius = radius.min(SNIPPET_MAX_CHARS.saturating_sub(length) / 2);
+    let mut window_start = start.saturating_sub(radius);
+    let mut window_end = (end + radius).min(text.len());
+
+    // Don't begin or end part-way through a word. Text without spaces, such as CJK,
+    // keeps the character-bounded window.
+    if window_start > 0 && is_word_char(text[window_start - 1]) {
+        if let Some(space) = (window_start..start).find(|&i| text[i] == ' ') {
+            window_start = space + 1;
+        }
+    }
+    if window_end < text.len() && is_word_char(text[window_end]) {
+        if let Some(space) = (end..window_end).rev().find(|&i| text[i] == ' ') {
+            window_end = space;
+        }
+    }
+
+    let slice = |from: usize, to: usize| text[from..to].iter().collect::<String>();
+    let mut snippet = String::new();
+    if window_start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(slice(window_start, start).trim_start());
+    snippet.push_str("<mark>");
+    snippet.push_str(&slice(start, end));
+    snippet.push_str("</mark>");
+    snippet.push_str(slice(end, window_end).trim_end());
+    if window_end < text.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// Translate search input into an FTS5 MATCH expression. Every term is quoted so
+/// punctuation cannot be read as FTS syntax; phrases, `column:` filters and a trailing
+/// `*` prefix marker are preserved. Returns `None` when there is nothing to search for.
+pub(super) fn build_match_expression(query: &str) -> Option<String> {
+    let mut expressions = Vec::new();
+
+    for term in split_search_terms(query) {
+        let (column, text) = match term.split_once(':') {
+            Some((column, rest)) if SEARCHABLE_COLUMNS.contains(&column.to_lowercase().as_str()) => {
+                (Some(column.to_lowercase()), rest)
+            }
+            _ => (None, term.as_str()),
+        };
+
+        let is_phrase = text.starts_with('"');
+        let is_prefix = !is_phrase && text.ends_with('*');
+        let words = text.trim_matches('"').trim_end_matches('*').replace('"', "");
+
+        if !words.chars().any(|c| c.is_alphanumeric()) {
+            continue;
+        }
+        // Very short bare terms match too broadly to be useful
+        if !is_phrase && column.is_none() && words.chars().count() <= 2 {
+            continue;
+        }
+
+        let quoted = format!("\"{}\"{}", words, if is_prefix { "*" } else { "" });
+        expressions.push(match column {
+            Some(column) => format!("{} : {}", column, quoted),
+            None => quoted,
+        });
+    }
+
+    if expressions.is_empty() {
+        None
+    } else {
+        Some(expressions.join(" OR "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case_study::CaseStudyRepository;
+
+    async fn seeded_engine() -> CaseStudySearchEngine {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let repository = CaseStudyRepository::new(db.clone());
+
+        let studies = [
+            ("Pricing Strategy at Nordic Outfitters", "A retailer reconsiders its pricing after a competitor enters the market."),
+            ("Warehouse Automation at FreshCart", "Operations leaders weigh robotics investment. Pricing is mentioned once in passing."),
+            ("Leadership Transition at Harbor Bank", "A new chief executive must rebuild trust with regional managers."),
+            ("Market Entry for Solis Energy", "The board debates a market entry strategy for solar panels in Brazil."),
+        ];
+        for (title, content) in studies {
+            repository
+                .create(NewCaseStudy {
+                    duration_minutes: 60,
+                    learning_objectives: vec!["Decision making".to_string()],
+                    ..NewCaseStudy::for_test(title, content)
+                })
+                .await
+                .expect("Failed to create case study");
+        }
+
+        CaseStudySearchEngine::new(db)
+    }
+
+    fn text_query(query: &str) -> CaseStudySearchQuery {
+        CaseStudySearchQuery {
+            query: query.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_match_expression() {
+        assert_eq!(
+            build_match_expression(r#"title:strategy "market entry" c++ at"#).as_deref(),
+            Some(r#"title : "strategy" OR "market entry" OR "c++""#)
+        );
+        assert_eq!(build_match_expression("manag*").as_deref(), Some(r#""manag"*"#));
+        assert_eq!(build_match_expression("a -- ok"), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_title_matches_first() {
+        let engine = seeded_engine().await;
+
+        let hits = engine.search_ranked(text_query("pricing")).await.unwrap();
+
+        let titles: Vec<&str> = hits.iter().map(|h| h.case_study.title.as_str()).collect();
+        assert_eq!(titles, vec!["Pricing Strategy at Nordic Outfitters", "Warehouse Automation at FreshCart"]);
+        assert!(hits[0].rank < hits[1].rank);
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_highlighted_snippets() {
+        let engine = seeded_engine().await;
+
+        let hits = engine.search_ranked(text_query("robotics")).await.unwrap();
+
+        assert_eq!(hits.len(), 1);
+        let snippet = hits[0].snippet.as_deref().unwrap();
+        assert!(snippet.contains("<mark>robotics</mark>"), "unexpected snippet: {}", snippet);
+    }
+
+    #[tokio::test]
+    async fn test_phrase_and_column_filters() {
+        let engine = seeded_engine().await;
+
+        let phrase = engine.search(text_query(r#""market entry""#)).await.unwrap();
+        assert_eq!(phrase.len(), 1);
+        assert_eq!(phrase[0].title, "Market Entry for Solis Energy");
+
+        // "strategy" appears in one title and in another case study's content
+        let in_title = engine.search(text_query("title:strategy")).await.unwrap();
+        assert_eq!(in_title.len(), 1);
+        assert_eq!(in_title[0].title, "Pricing Strategy at Nordic Outfitters");
+    }
+
+    #[test]
+    fn test_snippet_centers_on_whole_word_match() {
+        let text = format!("{} The résumé of the Zürich team showed strong growth. {}", "Lorem ipsum dolor. ".repeat(10), "Sit amet. ".repeat(10));
+
+        let snippet = extract_snippet(&text, "zürich", 30).unwrap();
+        assert!(snippet.contains("<mark>Zürich</mark>"), "unexpected snippet: {}", snippet);
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        let (before, after) = snippet.split_once("<mark>Zürich</mark>").unwrap();
+        let (before, after) = (before.trim_start_matches('…').chars().count(), after.trim_end_matches('…').chars().count());
+        assert!(before <= 30 && after <= 30 && before.abs_diff(after) <= 10, "{} / {}", before, after);
+
+        // "team" must not match inside "teammates"; whitespace is collapsed
+        let snippet = extract_snippet("The teammates\n\n  met the  team.", "team", 60).unwrap();
+        assert_eq!(snippet, "The teammates met the <mark>team</mark>.");
+        assert_eq!(extract_snippet("Teammates only", "team", 60), None);
+        assert_eq!(extract_snippet("Teammates only", "team*", 60).as_deref(), Some("<mark>Team</mark>mates only"));
+    }
+
+    #[test]
+    fn test_snippet_never_splits_multibyte_characters() {
+        let text = "日本語のテキスト。".repeat(20) + " 東京 " + &"😀é".repeat(40);
+
+        for radius in 0..12 {
+            let snippet = extract_snippet(&text, "東京", radius).unwrap();
+            assert!(std::str::from_utf8(snippet.as_bytes()).is_ok());
+            assert!(snippet.contains("<mark>東京</mark>"), "unexpected snippet: {}", snippet);
+            assert!(snippet.chars().count() <= 2 * radius + 2 + "<mark></mark>……".chars().count());
+        }
+
+        // A combining accent continues the word, so "cafe" doesn't match "café" spelled with one
+        assert_eq!(extract_snippet("Le cafe\u{301} du coin", "cafe", 20), None);
+        assert_eq!(extract_snippet("Le cafe\u{301} du coin", "cafe\u{301}", 20).as_deref(), Some("Le <mark>cafe\u{301}</mark> du coin"));
+
+        // Snippets are capped however large the radius
+        let long = "word ".repeat(500) + "needle " + &"word ".repeat(500);
+        assert!(extract_snippet(&long, "needle", 10_000).unwrap().chars().count() <= SNIPPET_MAX_CHARS + 15);
+    }
+}
@@ -0,0 +1,103 @@
+// Near-duplicate detection by normalized title and content token overlap
+
+use std::collections::HashSet;
+
+/// Combined similarity at or above which a new case study is treated as a likely duplicate
+pub const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Share of the combined score that comes from the title; the rest comes from the content
+const TITLE_WEIGHT: f64 = 0.3;
+
+/// Normalized title: lowercase words with punctuation removed and a trailing
+/// variant suffix such as " - AlphaA" dropped
+pub fn normalize_title(title: &str) -> String {
+    let base = match title.rsplit_once(" - ") {
+        Some((base, _)) if !base.trim().is_empty() => base,
+        _ => title,
+    };
+    words(base).collect::<Vec<_>>().join(" ")
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// How closely two case studies resemble each other, each score in 0.0..=1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Similarity {
+    pub title: f64,
+    pub content: f64,
+    pub combined: f64,
+}
+
+/// Pre-tokenized title and content, so one draft can be compared against many case studies
+pub struct Fingerprint {
+    title: String,
+    title_words: HashSet<String>,
+    content_words: HashSet<String>,
+}
+
+impl Fingerprint {
+    pub fn new(title: &str, content: &str) -> Self {
+        let title = normalize_title(title);
+        Self {
+            title_words: title.split(' ').filter(|w| !w.is_empty()).map(str::to_string).collect(),
+            content_words: words(content).collect(),
+            title,
+        }
+    }
+
+    /// Titles that normalize to the same text score 1.0; otherwise titles and content
+    /// are each scored by word-set Jaccard similarity
+    pub fn compare(&self, other: &Fingerprint) -> Similarity {
+        let title = if !self.title.is_empty() && self.title == other.title {
+            1.0
+        } else {
+            jaccard(&self.title_words, &other.title_words)
+        };
+        let content = jaccard(&self.content_words, &other.content_words);
+        Similarity {
+            title,
+            content,
+            combined: TITLE_WEIGHT * title + (1.0 - TITLE_WEIGHT) * content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_title_drops_variant_suffix() {
+        assert_eq!(
+            normalize_title("Market Entry Strategy Challenge - AlphaA"),
+            "market entry strategy challenge"
+        );
+        assert_eq!(normalize_title("Market-Entry: Strategy!"), "market entry strategy");
+        assert_eq!(normalize_title(" - Untitled"), "untitled");
+    }
+
+    #[test]
+    fn test_compare_scores() {
+        let a = Fingerprint::new("Harbor Bank Turnaround", "The new CEO must rebuild trust.");
+        let b = Fingerprint::new("harbor bank turnaround - v2", "The new CEO must rebuild trust!");
+        let similarity = a.compare(&b);
+        assert_eq!(similarity.title, 1.0);
+        assert_eq!(similarity.content, 1.0);
+        assert!((similarity.combined - 1.0).abs() < 1e-9);
+
+        let c = Fingerprint::new("Solar Expansion", "A manufacturer weighs entering Brazil.");
+        assert_eq!(a.compare(&c).combined, 0.0);
+    }
+}
@@ -0,0 +1,79 @@
+// Tag normalization so free-form tags collapse to one canonical spelling
+
+/// Canonical form of a tag: trimmed, lowercased, with runs of whitespace, underscores
+/// and hyphens collapsed to a single hyphen. Returns `None` for tags with no content.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let mut normalized = String::with_capacity(tag.len());
+    let mut pending_separator = false;
+
+    for c in tag.trim().chars() {
+        if c.is_whitespace() || c == '_' || c == '-' {
+            pending_separator = true;
+            continue;
+        }
+        if pending_separator && !normalized.is_empty() {
+            normalized.push('-');
+        }
+        pending_separator = false;
+        normalized.extend(c.to_lowercase());
+    }
+
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Normalize a tag list, dropping empty tags and duplicates while keeping first-seen order
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        if let Some(tag) = normalize_tag(tag.as_ref()) {
+            if !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+    }
+    normalized
+}
+
+/// Replace `from` with `to` in a normalized tag list, returning `None` if `from` isn't present
+pub fn replace_tag(tags: &[String], from: &str, to: &str) -> Option<Vec<String>> {
+    if !tags.iter().any(|t| t == from) {
+        return None;
+    }
+    Some(normalize_tags(tags.iter().map(|t| if t == from { to } else { t.as_str() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variants_collapse_to_one_tag() {
+        for variant in ["market-entry", "Market Entry", "  market   entry ", "MARKET_ENTRY", "market--entry-"] {
+            assert_eq!(normalize_tag(variant).as_deref(), Some("market-entry"), "{}", variant);
+        }
+        assert_eq!(normalize_tag("  - _ "), None);
+
+        assert_eq!(
+            normalize_tags(["Market Entry", "finance", "market-entry", "", "Finance"]),
+            vec!["market-entry".to_string(), "finance".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_replace_tag_dedupes() {
+        let tags = vec!["strategy".to_string(), "growth".to_string(), "expansion".to_string()];
+        assert_eq!(
+            replace_tag(&tags, "expansion", "growth"),
+            Some(vec!["strategy".to_string(), "growth".to_string()])
+        );
+        assert_eq!(replace_tag(&tags, "pricing", "growth"), None);
+    }
+}
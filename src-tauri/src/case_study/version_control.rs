@@ -1,9 +1,10 @@
 // Version control system for case studies
 
 use super::models::*;
+use super::export::{ANALYSIS_FRAMEWORK_FIELD, PROBLEM_STATEMENT_FIELD, SAMPLE_SOLUTION_FIELD};
 use super::{CaseStudyError, Result};
 use crate::database::DatabaseManager;
-use sqlx::Row;
+use sqlx::{Row, SqliteConnection};
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -22,7 +23,17 @@ impl CaseStudyVersionControl {
         &self,
         case_study: &CaseStudy,
         changes_summary: &str,
-        previous_version: Option<&CaseStudy>,
+    ) -> Result<CaseStudyVersion> {
+        let mut conn = self.db.pool().acquire().await?;
+        self.create_version_on(&mut conn, case_study, changes_summary).await
+    }
+
+    /// Create a new version on a specific connection, e.g. inside a transaction
+    pub async fn create_version_on(
+        &self,
+        conn: &mut SqliteConnection,
+        case_study: &CaseStudy,
+        changes_summary: &str,
     ) -> Result<CaseStudyVersion> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
@@ -48,7 +59,7 @@ impl CaseStudyVersionControl {
         .bind(&metadata_json)
         .bind(&case_study.created_by)
         .bind(now)
-        .fetch_one(self.db.pool())
+        .fetch_one(&mut *conn)
         .await?;
 
         self.parse_version_row(version).await
@@ -142,6 +153,22 @@ impl CaseStudyVersionControl {
         })
     }
 
+    /// Field-by-field diff between two versions, with line-level diffs for long text fields
+    pub async fn diff_versions(
+        &self,
+        case_study_id: &str,
+        from_version: i32,
+        to_version: i32,
+    ) -> Result<VersionDiff> {
+        let from = self.get_version(case_study_id, from_version).await?
+            .ok_or_else(|| CaseStudyError::NotFound(format!("Version {} not found", from_version)))?;
+
+        let to = self.get_version(case_study_id, to_version).await?
+            .ok_or_else(|| CaseStudyError::NotFound(format!("Version {} not found", to_version)))?;
+
+        Ok(diff_version_pair(&from, &to))
+    }
+
     /// Delete old versions (keep only recent N versions)
     pub async fn cleanup_old_versions(&self, case_study_id: &str, keep_count: i32) -> Result<i32> {
         let deleted_count = sqlx::query(
@@ -311,4 +338,208 @@ pub struct VersionStatistics {
 pub struct VersionContributor {
     pub user_id: String,
     pub version_count: i32,
-}
\ No newline at end of file
+}
+
+/// Structured diff between two versions of a case study; only changed fields are listed
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct VersionDiff {
+    pub case_study_id: String,
+    pub from_version: i32,
+    pub to_version: i32,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Change to a single field
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FieldDiff {
+    pub field: String,
+    #[serde(flatten)]
+    pub change: FieldChange,
+}
+
+/// Short fields are reported as a whole value; long text fields as a line diff
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FieldChange {
+    Value {
+        old_value: Option<String>,
+        new_value: Option<String>,
+    },
+    Text {
+        lines: Vec<DiffLine>,
+    },
+}
+
+/// One line of a text diff. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum DiffLine {
+    Unchanged { text: String, old_line: usize, new_line: usize },
+    Added { text: String, new_line: usize },
+    Removed { text: String, old_line: usize },
+}
+
+/// Diff two loaded versions
+fn diff_version_pair(from: &CaseStudyVersion, to: &CaseStudyVersion) -> VersionDiff {
+    let mut fields = Vec::new();
+
+    if from.title != to.title {
+        fields.push(FieldDiff {
+            field: "title".to_string(),
+            change: FieldChange::Value {
+                old_value: Some(from.title.clone()),
+                new_value: Some(to.title.clone()),
+            },
+        });
+    }
+
+    let custom_text = |version: &CaseStudyVersion, field: &str| {
+        version.metadata.get_custom_field(field).and_then(|v| v.as_str()).map(String::from)
+    };
+
+    let text_fields = [
+        ("summary", from.summary.clone(), to.summary.clone()),
+        ("content", Some(from.content.clone()), Some(to.content.clone())),
+        (PROBLEM_STATEMENT_FIELD, custom_text(from, PROBLEM_STATEMENT_FIELD), custom_text(to, PROBLEM_STATEMENT_FIELD)),
+        (ANALYSIS_FRAMEWORK_FIELD, custom_text(from, ANALYSIS_FRAMEWORK_FIELD), custom_text(to, ANALYSIS_FRAMEWORK_FIELD)),
+        (SAMPLE_SOLUTION_FIELD, custom_text(from, SAMPLE_SOLUTION_FIELD), custom_text(to, SAMPLE_SOLUTION_FIELD)),
+    ];
+
+    for (field, old, new) in text_fields {
+        if old != new {
+            fields.push(FieldDiff {
+                field: field.to_string(),
+                change: FieldChange::Text {
+                    lines: diff_lines(old.as_deref().unwrap_or(""), new.as_deref().unwrap_or("")),
+                },
+            });
+        }
+    }
+
+    VersionDiff {
+        case_study_id: from.case_study_id.clone(),
+        from_version: from.version_number,
+        to_version: to.version_number,
+        fields,
+    }
+}
+
+/// Line diff based on the longest common subsequence of lines
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lines.push(DiffLine::Unchanged { text: a[i].to_string(), old_line: i + 1, new_line: j + 1 });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed { text: a[i].to_string(), old_line: i + 1 });
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added { text: b[j].to_string(), new_line: j + 1 });
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().enumerate().map(|(k, text)| DiffLine::Removed { text: text.to_string(), old_line: i + k + 1 }));
+    lines.extend(b[j..].iter().enumerate().map(|(k, text)| DiffLine::Added { text: text.to_string(), new_line: j + k + 1 }));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case_study::CaseStudyRepository;
+
+    const ORIGINAL_CONTENT: &str = "Solis Energy makes residential solar panels.\n\
+        Revenue grew 12% last year.\n\
+        The board meets in March.\n\
+        Competitors are cutting prices.";
+
+    fn changed_lines(diff: &VersionDiff, field: &str) -> Vec<DiffLine> {
+        let field = diff.fields.iter().find(|f| f.field == field).expect("field missing from diff");
+        match &field.change {
+            FieldChange::Text { lines } => lines
+                .iter()
+                .filter(|l| !matches!(l, DiffLine::Unchanged { .. }))
+                .cloned()
+                .collect(),
+            FieldChange::Value { .. } => panic!("expected a text diff for {}", field.field),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diff_reports_changed_lines() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let original = CaseStudyRepository::new(db.clone())
+            .create(NewCaseStudy {
+                summary: Some("Founded in Lisbon.".to_string()),
+                industry: "Energy".to_string(),
+                duration_minutes: 60,
+                learning_objectives: vec!["Market analysis".to_string()],
+                ..NewCaseStudy::for_test("Solis Energy", ORIGINAL_CONTENT)
+            })
+            .await
+            .unwrap();
+
+        let version_control = CaseStudyVersionControl::new(db);
+        version_control.create_version(&original, "Initial version").await.unwrap();
+
+        let mut edited = original.clone();
+        edited.version = 2;
+        edited.title = "Solis Energy Expansion".to_string();
+        edited.content = ORIGINAL_CONTENT
+            .replace("Revenue grew 12% last year.", "Revenue grew 18% last year.")
+            .replace("The board meets in March.\n", "");
+        edited.content.push_str("\nA Brazilian partner has approached the CEO.");
+        edited.metadata.add_custom_field(PROBLEM_STATEMENT_FIELD.to_string(), serde_json::json!("Should Solis enter Brazil?"));
+        version_control.create_version(&edited, "Content updated").await.unwrap();
+
+        let diff = version_control.diff_versions(&original.id, 1, 2).await.unwrap();
+
+        let changed: Vec<&str> = diff.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(changed, vec!["title", "content", PROBLEM_STATEMENT_FIELD]);
+
+        assert_eq!(
+            changed_lines(&diff, "content"),
+            vec![
+                DiffLine::Removed { text: "Revenue grew 12% last year.".to_string(), old_line: 2 },
+                DiffLine::Removed { text: "The board meets in March.".to_string(), old_line: 3 },
+                DiffLine::Added { text: "Revenue grew 18% last year.".to_string(), new_line: 2 },
+                DiffLine::Added { text: "A Brazilian partner has approached the CEO.".to_string(), new_line: 4 },
+            ]
+        );
+        assert_eq!(
+            changed_lines(&diff, PROBLEM_STATEMENT_FIELD),
+            vec![DiffLine::Added { text: "Should Solis enter Brazil?".to_string(), new_line: 1 }]
+        );
+        assert!(matches!(
+            &diff.fields[0].change,
+            FieldChange::Value { new_value: Some(title), .. } if title == "Solis Energy Expansion"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_diff_missing_version() {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        let version_control = CaseStudyVersionControl::new(db);
+
+        let result = version_control.diff_versions("missing", 1, 2).await;
+        assert!(matches!(result, Err(CaseStudyError::NotFound(_))));
+    }
+}
@@ -0,0 +1,39 @@
+// Injectable source of the current time
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current time, injectable so time-dependent behavior can be tested
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to
+#[cfg(test)]
+pub(crate) struct MockClock(std::sync::Mutex<DateTime<Utc>>);
+
+#[cfg(test)]
+impl MockClock {
+    pub(crate) fn new(start: DateTime<Utc>) -> Self {
+        Self(std::sync::Mutex::new(start))
+    }
+
+    pub(crate) fn advance(&self, duration: chrono::Duration) {
+        *self.0.lock().unwrap() += duration;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
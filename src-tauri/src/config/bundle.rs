@@ -0,0 +1,570 @@
+// Self-contained export bundles of configuration templates and the references they use
+
+use super::models::*;
+use super::repository::{ConfigurationRepository, ReferenceKind};
+use crate::config::{ConfigurationError, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Format version written to the bundle header. Bundles with another major version are
+/// refused; 1.x bundles predate bundled references.
+pub const BUNDLE_FORMAT_VERSION: &str = "2.0";
+
+/// Build a bundle of the given configurations with the categories and frameworks they
+/// refer to. Unknown ids are left out.
+pub async fn export_bundle(
+    repository: &ConfigurationRepository,
+    template_ids: &[String],
+    include_metadata: bool,
+) -> Result<ConfigurationTemplatePackage> {
+    let mut templates = Vec::new();
+    for id in template_ids {
+        if let Some(stored_config) = repository.find_by_id(id).await? {
+            templates.push(template_export(stored_config, include_metadata)?);
+        }
+    }
+    let total = templates.len();
+    for metadata in templates.iter_mut().filter_map(|t| t.export_metadata.as_mut()) {
+        metadata.total_templates = total;
+    }
+
+    let mut categories: Vec<ConfigurationReference> = Vec::new();
+    let mut frameworks: Vec<ConfigurationReference> = Vec::new();
+    for template in &templates {
+        let refs = [
+            (ReferenceKind::Category, Some(&template.category)),
+            (ReferenceKind::Framework, template.framework.as_ref()),
+        ];
+        for (kind, id) in refs {
+            let Some(id) = id else { continue };
+            let list = match kind {
+                ReferenceKind::Category => &mut categories,
+                ReferenceKind::Framework => &mut frameworks,
+            };
+            if list.iter().any(|r| &r.id == id) {
+                continue;
+            }
+            let reference = repository.find_reference(kind, id).await?.unwrap_or_else(|| ConfigurationReference {
+                id: id.clone(),
+                name: id.clone(),
+                description: None,
+            });
+            list.push(reference);
+        }
+    }
+
+    Ok(ConfigurationTemplatePackage {
+        version: BUNDLE_FORMAT_VERSION.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        templates,
+        metadata: if include_metadata {
+            Some(PackageMetadata {
+                exported_by: "Case Crafter".to_string(),
+                export_tool_version: env!("CARGO_PKG_VERSION").to_string(),
+                description: "Configuration template export from Case Crafter".to_string(),
+            })
+        } else {
+            None
+        },
+        categories,
+        frameworks,
+    })
+}
+
+fn template_export(stored_config: StoredConfigurationSchema, include_metadata: bool) -> Result<ConfigurationTemplateExport> {
+    let schema = stored_config.to_configuration_schema()?;
+
+    // Sample form data from field defaults, overridden by schema-level defaults
+    let mut sample_data: HashMap<String, Value> = schema.sections
+        .iter()
+        .flat_map(|section| section.fields.iter())
+        .filter_map(|field| field.default_value.clone().map(|value| (field.id.clone(), value)))
+        .collect();
+    sample_data.extend(schema.defaults.clone());
+
+    Ok(ConfigurationTemplateExport {
+        id: stored_config.id,
+        name: stored_config.name,
+        description: stored_config.description,
+        version: stored_config.version,
+        framework: stored_config.framework,
+        category: stored_config.category,
+        schema,
+        tags: parse_list(&stored_config.tags),
+        target_audience: parse_list(&stored_config.target_audience),
+        difficulty_level: stored_config.difficulty_level,
+        estimated_minutes: stored_config.estimated_minutes,
+        locale: stored_config.locale,
+        created_at: stored_config.created_at.to_rfc3339(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        export_metadata: if include_metadata {
+            Some(ExportMetadata {
+                exporter_version: env!("CARGO_PKG_VERSION").to_string(),
+                export_format_version: BUNDLE_FORMAT_VERSION.to_string(),
+                total_templates: 0,
+            })
+        } else {
+            None
+        },
+        sample_data: if sample_data.is_empty() { None } else { Some(sample_data) },
+    })
+}
+
+/// Tags and audiences are stored as JSON arrays
+fn parse_list(stored: &str) -> Vec<String> {
+    serde_json::from_str(stored).unwrap_or_default()
+}
+
+/// Import a bundle. Categories and frameworks are imported first; templates referring to
+/// one that is neither bundled nor known locally are reported as errors. Items whose id
+/// exists locally with different content are resolved with `strategy` and reported as
+/// conflicts; identical items are skipped.
+pub async fn import_bundle(
+    repository: &ConfigurationRepository,
+    package: ConfigurationTemplatePackage,
+    strategy: MergeStrategy,
+) -> Result<ConfigurationImportResult> {
+    let major = package.version.split('.').next().unwrap_or_default();
+    if major != "1" && major != "2" {
+        return Err(ConfigurationError::ValidationError(format!(
+            "Unsupported bundle format version {} (expected {})", package.version, BUNDLE_FORMAT_VERSION
+        )));
+    }
+    // 1.x bundles carry no references, so whatever their templates name is created on import
+    let legacy = major == "1";
+
+    let mut result = ConfigurationImportResult {
+        total_templates: package.templates.len(),
+        imported_count: 0,
+        skipped_count: 0,
+        error_count: 0,
+        imported_ids: Vec::new(),
+        skipped_templates: Vec::new(),
+        errors: Vec::new(),
+        conflicts: Vec::new(),
+    };
+
+    let mut renamed_categories = HashMap::new();
+    for reference in &package.categories {
+        if let Some(new_id) = import_reference(repository, ReferenceKind::Category, reference, strategy, &mut result).await? {
+            renamed_categories.insert(reference.id.clone(), new_id);
+        }
+    }
+    let mut renamed_frameworks = HashMap::new();
+    for reference in &package.frameworks {
+        if let Some(new_id) = import_reference(repository, ReferenceKind::Framework, reference, strategy, &mut result).await? {
+            renamed_frameworks.insert(reference.id.clone(), new_id);
+        }
+    }
+
+    for mut template in package.templates {
+        if let Some(new_id) = renamed_categories.get(&template.category) {
+            template.category = new_id.clone();
+            template.schema.category = new_id.clone();
+        }
+        if let Some(new_id) = template.framework.as_ref().and_then(|f| renamed_frameworks.get(f)) {
+            template.framework = Some(new_id.clone());
+            template.schema.framework = Some(new_id.clone());
+        }
+
+        if !legacy {
+            if let Some(missing) = missing_reference(repository, &package.categories, &package.frameworks, &template).await? {
+                result.error_count += 1;
+                result.errors.push(ImportError {
+                    template_id: template.id.clone(),
+                    template_name: template.name.clone(),
+                    error: missing,
+                });
+                continue;
+            }
+        }
+
+        if let Err(e) = import_template(repository, template.clone(), strategy, &mut result).await {
+            result.error_count += 1;
+            result.errors.push(ImportError {
+                template_id: template.id.clone(),
+                template_name: template.name.clone(),
+                error: format!("Failed to import template: {}", e),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Import one category or framework, returning its new id if it was renamed
+async fn import_reference(
+    repository: &ConfigurationRepository,
+    kind: ReferenceKind,
+    reference: &ConfigurationReference,
+    strategy: MergeStrategy,
+    result: &mut ConfigurationImportResult,
+) -> Result<Option<String>> {
+    let local = match repository.find_reference(kind, &reference.id).await? {
+        None => {
+            repository.save_reference(kind, reference).await?;
+            return Ok(None);
+        }
+        Some(local) => local,
+    };
+    if &local == reference {
+        return Ok(None);
+    }
+
+    let new_id = match strategy {
+        MergeStrategy::Skip => None,
+        MergeStrategy::Overwrite => {
+            repository.save_reference(kind, reference).await?;
+            None
+        }
+        MergeStrategy::Rename => {
+            let new_id = unused_reference_id(repository, kind, &reference.id).await?;
+            repository.save_reference(kind, &ConfigurationReference { id: new_id.clone(), ..reference.clone() }).await?;
+            Some(new_id)
+        }
+    };
+
+    result.conflicts.push(ImportConflict {
+        kind: kind.as_str().to_string(),
+        id: reference.id.clone(),
+        resolution: strategy,
+        new_id: new_id.clone(),
+    });
+    Ok(new_id)
+}
+
+async fn unused_reference_id(repository: &ConfigurationRepository, kind: ReferenceKind, id: &str) -> Result<String> {
+    let mut suffix = 1;
+    loop {
+        let candidate = if suffix == 1 { format!("{}-imported", id) } else { format!("{}-imported-{}", id, suffix) };
+        if repository.find_reference(kind, &candidate).await?.is_none() {
+            return Ok(candidate);
+        }
+        suffix += 1;
+    }
+}
+
+/// Describe the first category or framework the template uses that can't be resolved
+async fn missing_reference(
+    repository: &ConfigurationRepository,
+    categories: &[ConfigurationReference],
+    frameworks: &[ConfigurationReference],
+    template: &ConfigurationTemplateExport,
+) -> Result<Option<String>> {
+    let refs = [
+        (ReferenceKind::Category, Some(&template.category), categories),
+        (ReferenceKind::Framework, template.framework.as_ref(), frameworks),
+    ];
+    for (kind, id, bundled) in refs {
+        let Some(id) = id else { continue };
+        let known = bundled.iter().any(|r| &r.id == id) || repository.find_reference(kind, id).await?.is_some();
+        if !known {
+            return Ok(Some(format!(
+                "References {} '{}' which is neither in the bundle nor in this installation",
+                kind.as_str(), id
+            )));
+        }
+    }
+    Ok(None)
+}
+
+async fn import_template(
+    repository: &ConfigurationRepository,
+    template: ConfigurationTemplateExport,
+    strategy: MergeStrategy,
+    result: &mut ConfigurationImportResult,
+) -> Result<()> {
+    let local = match repository.find_by_id(&template.id).await? {
+        Some(local) => local,
+        None => {
+            let created = repository.create_with_id(template.id.clone(), new_configuration(&template)).await?;
+            result.imported_count += 1;
+            result.imported_ids.push(created.id);
+            return Ok(());
+        }
+    };
+
+    let local_export = template_export(local, false)?;
+    if template_content(&local_export)? == template_content(&template)? {
+        result.skipped_count += 1;
+        result.skipped_templates.push(ImportSkippedTemplate {
+            id: template.id.clone(),
+            name: template.name.clone(),
+            reason: "Template already exists with the same content".to_string(),
+        });
+        return Ok(());
+    }
+
+    let mut conflict = ImportConflict {
+        kind: "template".to_string(),
+        id: template.id.clone(),
+        resolution: strategy,
+        new_id: None,
+    };
+    match strategy {
+        MergeStrategy::Skip => {
+            result.skipped_count += 1;
+            result.skipped_templates.push(ImportSkippedTemplate {
+                id: template.id.clone(),
+                name: template.name.clone(),
+                reason: "Template already exists with different content".to_string(),
+            });
+        }
+        MergeStrategy::Overwrite => {
+            let new_config = new_configuration(&template);
+            repository.update(&template.id, UpdateConfiguration {
+                name: Some(new_config.name),
+                description: new_config.description,
+                version: Some(new_config.version),
+                framework: new_config.framework,
+                category: Some(new_config.category),
+                schema: Some(new_config.schema),
+                status: None,
+                is_template: Some(true),
+                tags: Some(new_config.tags),
+                target_audience: Some(new_config.target_audience),
+                difficulty_level: new_config.difficulty_level,
+                estimated_minutes: new_config.estimated_minutes,
+                locale: Some(new_config.locale),
+                custom_metadata: None,
+                updated_by: new_config.created_by,
+            }).await?;
+            result.imported_count += 1;
+            result.imported_ids.push(template.id.clone());
+        }
+        MergeStrategy::Rename => {
+            let mut new_config = new_configuration(&template);
+            new_config.name = format!("{} (imported)", template.name);
+            let created = repository.create(new_config).await?;
+            conflict.new_id = Some(created.id.clone());
+            result.imported_count += 1;
+            result.imported_ids.push(created.id);
+        }
+    }
+    result.conflicts.push(conflict);
+
+    Ok(())
+}
+
+fn new_configuration(template: &ConfigurationTemplateExport) -> NewConfiguration {
+    NewConfiguration {
+        name: template.name.clone(),
+        description: template.description.clone(),
+        version: template.version.clone(),
+        framework: template.framework.clone(),
+        category: template.category.clone(),
+        schema: template.schema.clone(),
+        is_template: true,
+        tags: template.tags.clone(),
+        target_audience: template.target_audience.clone(),
+        difficulty_level: template.difficulty_level.clone(),
+        estimated_minutes: template.estimated_minutes,
+        locale: template.locale.clone(),
+        custom_metadata: HashMap::new(),
+        created_by: Some("Imported".to_string()),
+    }
+}
+
+/// The parts of a template that make two copies the same, ignoring timestamps and export details
+fn template_content(template: &ConfigurationTemplateExport) -> Result<Value> {
+    let mut content = serde_json::to_value(template)?;
+    if let Value::Object(ref mut fields) = content {
+        for key in ["created_at", "exported_at", "export_metadata", "sample_data"] {
+            fields.remove(key);
+        }
+        if let Some(Value::Object(schema)) = fields.get_mut("schema") {
+            schema.remove("created_at");
+            schema.remove("updated_at");
+        }
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{ConfigurationSchema, SchemaMetadata};
+    use crate::database::DatabaseManager;
+    use chrono::Utc;
+
+    async fn test_repository() -> ConfigurationRepository {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        ConfigurationRepository::new(db)
+    }
+
+    fn template(id: &str, name: &str, category: &str, framework: &str) -> ConfigurationTemplateExport {
+        ConfigurationTemplateExport {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            framework: Some(framework.to_string()),
+            category: category.to_string(),
+            schema: ConfigurationSchema {
+                id: id.to_string(),
+                name: name.to_string(),
+                description: None,
+                version: "1.0".to_string(),
+                framework: Some(framework.to_string()),
+                category: category.to_string(),
+                sections: Vec::new(),
+                global_validations: Vec::new(),
+                conditional_logic: Vec::new(),
+                defaults: HashMap::new(),
+                metadata: SchemaMetadata::default(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                created_by: None,
+                schema_version: 1,
+                data_migrations: Vec::new(),
+            },
+            tags: vec!["strategy".to_string()],
+            target_audience: Vec::new(),
+            difficulty_level: None,
+            estimated_minutes: None,
+            locale: "en".to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            exported_at: Utc::now().to_rfc3339(),
+            export_metadata: None,
+            sample_data: None,
+        }
+    }
+
+    fn reference(id: &str, description: &str) -> ConfigurationReference {
+        ConfigurationReference { id: id.to_string(), name: id.to_string(), description: Some(description.to_string()) }
+    }
+
+    fn bundle(templates: Vec<ConfigurationTemplateExport>, frameworks: Vec<ConfigurationReference>) -> ConfigurationTemplatePackage {
+        ConfigurationTemplatePackage {
+            version: BUNDLE_FORMAT_VERSION.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            templates,
+            metadata: None,
+            categories: vec![reference("strategy", "Strategic analysis")],
+            frameworks,
+        }
+    }
+
+    /// A local install holding "swot" and the SWOT framework, and a bundle whose copies differ
+    async fn conflicting_import(strategy: MergeStrategy) -> (ConfigurationRepository, ConfigurationImportResult) {
+        let repository = test_repository().await;
+        import_bundle(
+            &repository,
+            bundle(vec![template("swot", "SWOT", "strategy", "SWOT")], vec![reference("SWOT", "Strengths and weaknesses")]),
+            MergeStrategy::Skip,
+        )
+        .await
+        .unwrap();
+
+        let result = import_bundle(
+            &repository,
+            bundle(
+                vec![template("swot", "SWOT Analysis v2", "strategy", "SWOT")],
+                vec![reference("SWOT", "Strengths, weaknesses, opportunities, threats")],
+            ),
+            strategy,
+        )
+        .await
+        .unwrap();
+        (repository, result)
+    }
+
+    #[tokio::test]
+    async fn test_clean_import_round_trips_through_export() {
+        let source = test_repository().await;
+        let package = bundle(
+            vec![template("swot", "SWOT", "strategy", "SWOT")],
+            vec![reference("SWOT", "Strengths and weaknesses")],
+        );
+        let result = import_bundle(&source, package, MergeStrategy::Skip).await.unwrap();
+        assert_eq!(result.imported_ids, vec!["swot"]);
+        assert!(result.conflicts.is_empty());
+
+        let exported = export_bundle(&source, &["swot".to_string()], true).await.unwrap();
+        assert_eq!(exported.version, BUNDLE_FORMAT_VERSION);
+        assert_eq!(exported.frameworks, vec![reference("SWOT", "Strengths and weaknesses")]);
+        assert_eq!(exported.categories, vec![reference("strategy", "Strategic analysis")]);
+        assert_eq!(exported.templates[0].tags, vec!["strategy"]);
+
+        // Another install gets the template and its references
+        let target = test_repository().await;
+        let result = import_bundle(&target, exported, MergeStrategy::Skip).await.unwrap();
+        assert_eq!(result.imported_count, 1);
+        assert_eq!(result.error_count, 0);
+        assert_eq!(target.find_by_id("swot").await.unwrap().unwrap().name, "SWOT");
+        assert_eq!(
+            target.find_reference(ReferenceKind::Framework, "SWOT").await.unwrap(),
+            Some(reference("SWOT", "Strengths and weaknesses"))
+        );
+
+        // Importing the same bundle again changes nothing
+        let again = export_bundle(&source, &["swot".to_string()], false).await.unwrap();
+        let result = import_bundle(&target, again, MergeStrategy::Overwrite).await.unwrap();
+        assert_eq!((result.imported_count, result.skipped_count), (0, 1));
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_conflict_skip_keeps_local() {
+        let (repository, result) = conflicting_import(MergeStrategy::Skip).await;
+
+        assert_eq!(result.skipped_count, 1);
+        assert_eq!(result.conflicts.len(), 2);
+        assert!(result.conflicts.iter().all(|c| c.resolution == MergeStrategy::Skip));
+        assert_eq!(repository.find_by_id("swot").await.unwrap().unwrap().name, "SWOT");
+        assert_eq!(
+            repository.find_reference(ReferenceKind::Framework, "SWOT").await.unwrap().unwrap().description.as_deref(),
+            Some("Strengths and weaknesses")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflict_overwrite_replaces_local() {
+        let (repository, result) = conflicting_import(MergeStrategy::Overwrite).await;
+
+        assert_eq!(result.imported_ids, vec!["swot"]);
+        assert_eq!(result.conflicts.len(), 2);
+        assert_eq!(repository.find_by_id("swot").await.unwrap().unwrap().name, "SWOT Analysis v2");
+        assert_eq!(
+            repository.find_reference(ReferenceKind::Framework, "SWOT").await.unwrap().unwrap().description.as_deref(),
+            Some("Strengths, weaknesses, opportunities, threats")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflict_rename_imports_alongside() {
+        let (repository, result) = conflicting_import(MergeStrategy::Rename).await;
+
+        let framework = result.conflicts.iter().find(|c| c.kind == "framework").unwrap();
+        assert_eq!(framework.new_id.as_deref(), Some("SWOT-imported"));
+        let template = result.conflicts.iter().find(|c| c.kind == "template").unwrap();
+        let new_id = template.new_id.clone().unwrap();
+        assert_ne!(new_id, "swot");
+
+        assert_eq!(repository.find_by_id("swot").await.unwrap().unwrap().name, "SWOT");
+        let renamed = repository.find_by_id(&new_id).await.unwrap().unwrap();
+        assert_eq!(renamed.name, "SWOT Analysis v2 (imported)");
+        assert_eq!(renamed.framework.as_deref(), Some("SWOT-imported"));
+        assert!(repository.find_reference(ReferenceKind::Framework, "SWOT-imported").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_missing_framework_is_reported() {
+        let repository = test_repository().await;
+        let package = bundle(
+            vec![
+                template("porter", "Five Forces", "strategy", "Porter's Five Forces"),
+                template("swot", "SWOT", "strategy", "SWOT"),
+            ],
+            vec![reference("SWOT", "Strengths and weaknesses")],
+        );
+
+        let result = import_bundle(&repository, package, MergeStrategy::Skip).await.unwrap();
+
+        assert_eq!(result.imported_ids, vec!["swot"]);
+        assert_eq!(result.error_count, 1);
+        assert_eq!(result.errors[0].template_id, "porter");
+        assert!(result.errors[0].error.contains("Porter's Five Forces"));
+        assert!(repository.find_by_id("porter").await.unwrap().is_none());
+    }
+}
@@ -5,6 +5,7 @@ use super::schema::*;
 use super::repository::ConfigurationRepository;
 use super::validation::ValidationEngine;
 use super::conditional::{ConditionalEngine, ConditionalResult};
+use super::bundle::{export_bundle, import_bundle};
 use crate::config::{ConfigurationError, Result};
 use crate::database::DatabaseManager;
 use tauri::{AppHandle, Manager, State};
@@ -53,14 +54,38 @@ pub async fn create_configuration(
         .map_err(|e| format!("Failed to create configuration: {}", e))
 }
 
-/// Get configuration by ID
+/// Get configuration by ID, with a count of stored submissions that need upgrading to
+/// the current schema version
 #[tauri::command]
 pub async fn get_configuration(
     service: State<'_, ConfigurationService>,
     id: String,
 ) -> std::result::Result<Option<StoredConfigurationSchema>, String> {
-    service.repository.find_by_id(&id).await
-        .map_err(|e| format!("Failed to get configuration: {}", e))
+    let mut stored_config = match service.repository.find_by_id(&id).await
+        .map_err(|e| format!("Failed to get configuration: {}", e))? {
+        Some(stored_config) => stored_config,
+        None => return Ok(None),
+    };
+
+    let schema = stored_config.to_configuration_schema()
+        .map_err(|e| format!("Failed to parse configuration schema: {}", e))?;
+    stored_config.outdated_submissions = service.repository
+        .count_outdated_submissions(&id, schema.schema_version).await
+        .map_err(|e| format!("Failed to check stored form data: {}", e))?;
+
+    Ok(Some(stored_config))
+}
+
+/// Upgrade a configuration's stored form data from one schema version to another
+#[tauri::command]
+pub async fn migrate_configuration_data(
+    service: State<'_, ConfigurationService>,
+    config_id: String,
+    from: u32,
+    to: u32,
+) -> std::result::Result<u64, String> {
+    service.repository.migrate_configuration_data(&config_id, from, to).await
+        .map_err(|e| format!("Failed to migrate form data: {}", e))
 }
 
 /// Get configuration schema by ID (for form rendering)
@@ -111,11 +136,43 @@ pub async fn update_configuration(
 pub async fn delete_configuration(
     service: State<'_, ConfigurationService>,
     id: String,
+    deleted_by: Option<String>,
 ) -> std::result::Result<bool, String> {
-    service.repository.delete(&id).await
+    service.repository.delete(&id, deleted_by.as_deref()).await
         .map_err(|e| format!("Failed to delete configuration: {}", e))
 }
 
+/// Restore a deleted configuration
+#[tauri::command]
+pub async fn restore_deleted_configuration(
+    service: State<'_, ConfigurationService>,
+    id: String,
+) -> std::result::Result<bool, String> {
+    service.repository.restore_deleted(&id).await
+        .map_err(|e| format!("Failed to restore configuration: {}", e))
+}
+
+/// Permanently remove a deleted configuration
+#[tauri::command]
+pub async fn purge_configuration(
+    service: State<'_, ConfigurationService>,
+    id: String,
+) -> std::result::Result<bool, String> {
+    service.repository.purge(&id).await
+        .map_err(|e| format!("Failed to purge configuration: {}", e))
+}
+
+/// List deleted configurations
+#[tauri::command]
+pub async fn list_deleted_configurations(
+    service: State<'_, ConfigurationService>,
+    limit: i32,
+    offset: i32,
+) -> std::result::Result<Vec<StoredConfigurationSchema>, String> {
+    service.repository.list_deleted(limit, offset).await
+        .map_err(|e| format!("Failed to list deleted configurations: {}", e))
+}
+
 /// List configurations with filtering
 #[tauri::command]
 pub async fn list_configurations(
@@ -255,13 +312,14 @@ pub async fn submit_form_data(
     Ok(format!("Form submitted successfully for configuration {}", submission.configuration_id))
 }
 
-/// Get form field options dynamically
+/// Get form field options dynamically. `dependencies` is the current (partial) form data,
+/// used as parameters for cascading sources.
 #[tauri::command]
 pub async fn get_dynamic_field_options(
     service: State<'_, ConfigurationService>,
     configuration_id: String,
     field_id: String,
-    _dependencies: HashMap<String, Value>,
+    dependencies: HashMap<String, Value>,
 ) -> std::result::Result<Vec<OptionItem>, String> {
     // Get the configuration schema
     let stored_config = service.repository.find_by_id(&configuration_id).await
@@ -277,6 +335,11 @@ pub async fn get_dynamic_field_options(
 
     // Get field options
     if let Some(ref options) = field.options {
+        if let Some(ref source) = options.dynamic_source {
+            return service.repository.resolve_option_source(source, &dependencies).await
+                .map_err(|e| format!("Failed to load options: {}", e));
+        }
+
         if let Some(ref static_options) = options.static_options {
             return Ok(static_options.clone());
         }
@@ -340,10 +403,27 @@ pub async fn get_recent_configurations(
 #[tauri::command]
 pub async fn validate_configuration_schema(
     service: State<'_, ValidationEngine>,
+    config_service: State<'_, ConfigurationService>,
     schema: ConfigurationSchema,
+    configuration_id: Option<String>,
 ) -> std::result::Result<ValidationResults, String> {
-    service.validate_schema(&schema)
-        .map_err(|e| format!("Schema validation failed: {}", e))
+    let mut results = service.validate_schema(&schema)
+        .map_err(|e| format!("Schema validation failed: {}", e))?;
+
+    // Flag stored form data the schema's current version no longer matches
+    if let Some(id) = configuration_id {
+        let outdated = config_service.repository
+            .count_outdated_submissions(&id, schema.schema_version).await
+            .map_err(|e| format!("Failed to check stored form data: {}", e))?;
+        if outdated > 0 {
+            results.add_warning(format!(
+                "{} stored submission(s) predate schema version {} and need migrating",
+                outdated, schema.schema_version
+            ));
+        }
+    }
+
+    Ok(results)
 }
 
 /// Export configuration templates to JSON file
@@ -355,63 +435,13 @@ pub async fn export_configuration_templates(
     include_metadata: bool,
 ) -> std::result::Result<String, String> {
     
-    // Get all specified templates
-    let mut templates = Vec::new();
-    for id in template_ids {
-        if let Some(stored_config) = service.repository.find_by_id(&id).await
-            .map_err(|e| format!("Failed to get configuration {}: {}", id, e))? {
-            
-            let config_schema = stored_config.to_configuration_schema()
-                .map_err(|e| format!("Failed to parse configuration schema: {}", e))?;
-                
-            templates.push(ConfigurationTemplateExport {
-                id: stored_config.id,
-                name: stored_config.name,
-                description: stored_config.description,
-                version: stored_config.version,
-                framework: stored_config.framework,
-                category: stored_config.category,
-                schema: config_schema,
-                tags: stored_config.tags.split(',').map(|s| s.trim().to_string()).collect(),
-                target_audience: stored_config.target_audience.split(',').map(|s| s.trim().to_string()).collect(),
-                difficulty_level: stored_config.difficulty_level,
-                estimated_minutes: stored_config.estimated_minutes,
-                locale: stored_config.locale,
-                created_at: stored_config.created_at.to_rfc3339(),
-                exported_at: chrono::Utc::now().to_rfc3339(),
-                export_metadata: if include_metadata {
-                    Some(ExportMetadata {
-                        exporter_version: env!("CARGO_PKG_VERSION").to_string(),
-                        export_format_version: "1.0".to_string(),
-                        total_templates: templates.len() + 1,
-                    })
-                } else {
-                    None
-                },
-            });
-        }
-    }
-    
-    if templates.is_empty() {
+    let export_package = export_bundle(&service.repository, &template_ids, include_metadata).await
+        .map_err(|e| format!("Failed to export templates: {}", e))?;
+
+    if export_package.templates.is_empty() {
         return Err("No valid templates found to export".to_string());
     }
     
-    // Create export package
-    let export_package = ConfigurationTemplatePackage {
-        version: "1.0".to_string(),
-        exported_at: chrono::Utc::now().to_rfc3339(),
-        templates,
-        metadata: if include_metadata {
-            Some(PackageMetadata {
-                exported_by: "Case Crafter".to_string(),
-                export_tool_version: env!("CARGO_PKG_VERSION").to_string(),
-                description: "Configuration template export from Case Crafter".to_string(),
-            })
-        } else {
-            None
-        },
-    };
-    
     // Serialize to JSON
     let json_content = serde_json::to_string_pretty(&export_package)
         .map_err(|e| format!("Failed to serialize templates: {}", e))?;
@@ -436,12 +466,15 @@ pub async fn export_configuration_templates(
     }
 }
 
-/// Import configuration templates from JSON file
+/// Import configuration templates from JSON file. Templates, categories and frameworks
+/// that exist with different content are resolved with `merge_strategy`, which defaults
+/// to overwrite or skip according to `overwrite_existing`.
 #[tauri::command]
 pub async fn import_configuration_templates(
     app_handle: AppHandle,
     service: State<'_, ConfigurationService>,
     overwrite_existing: bool,
+    merge_strategy: Option<MergeStrategy>,
 ) -> std::result::Result<ConfigurationImportResult, String> {
     
     // Show open dialog
@@ -463,68 +496,14 @@ pub async fn import_configuration_templates(
     let import_package: ConfigurationTemplatePackage = serde_json::from_str(&json_content)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
     
-    let mut import_result = ConfigurationImportResult {
-        total_templates: import_package.templates.len(),
-        imported_count: 0,
-        skipped_count: 0,
-        error_count: 0,
-        imported_ids: Vec::new(),
-        skipped_templates: Vec::new(),
-        errors: Vec::new(),
-    };
-    
-    // Import each template
-    for template in import_package.templates {
-        // Check if template already exists
-        let exists = service.repository.exists(&template.id).await
-            .map_err(|e| format!("Failed to check if template exists: {}", e))?;
-        
-        if exists && !overwrite_existing {
-            import_result.skipped_count += 1;
-            import_result.skipped_templates.push(ImportSkippedTemplate {
-                id: template.id.clone(),
-                name: template.name.clone(),
-                reason: "Template already exists".to_string(),
-            });
-            continue;
-        }
-        
-        // Create new configuration from template
-        let new_config = NewConfiguration {
-            name: template.name.clone(),
-            description: template.description.clone(),
-            version: template.version.clone(),
-            framework: template.framework.clone(),
-            category: template.category.clone(),
-            schema: template.schema.clone(),
-            is_template: true,
-            tags: template.tags.clone(),
-            target_audience: template.target_audience.clone(),
-            difficulty_level: template.difficulty_level.clone(),
-            estimated_minutes: template.estimated_minutes,
-            locale: template.locale.clone(),
-            custom_metadata: std::collections::HashMap::new(),
-            created_by: Some("Imported".to_string()),
-        };
-        
-        // Validate and create
-        match service.repository.create(new_config).await {
-            Ok(created_config) => {
-                import_result.imported_count += 1;
-                import_result.imported_ids.push(created_config.id);
-            }
-            Err(e) => {
-                import_result.error_count += 1;
-                import_result.errors.push(ImportError {
-                    template_id: template.id.clone(),
-                    template_name: template.name.clone(),
-                    error: format!("Failed to create template: {}", e),
-                });
-            }
-        }
-    }
-    
-    Ok(import_result)
+    let strategy = merge_strategy.unwrap_or(if overwrite_existing {
+        MergeStrategy::Overwrite
+    } else {
+        MergeStrategy::Skip
+    });
+
+    import_bundle(&service.repository, import_package, strategy).await
+        .map_err(|e| format!("Failed to import templates: {}", e))
 }
 
 /// Evaluate conditional logic for a form
@@ -649,6 +628,14 @@ pub async fn create_conditional_expression(
             field: field_id,
             value,
         }),
+        "greater_than_or_equal" => Ok(ConditionalExpression::GreaterThanOrEqual {
+            field: field_id,
+            value,
+        }),
+        "less_than_or_equal" => Ok(ConditionalExpression::LessThanOrEqual {
+            field: field_id,
+            value,
+        }),
         "contains" => Ok(ConditionalExpression::Contains {
             field: field_id,
             value,
@@ -138,6 +138,12 @@ impl ConditionalEngine {
             ConditionalExpression::LessThan { field, value } => {
                 self.evaluate_comparison_condition(field, value, context, |a, b| a < b)
             },
+            ConditionalExpression::GreaterThanOrEqual { field, value } => {
+                self.evaluate_comparison_condition(field, value, context, |a, b| a >= b)
+            },
+            ConditionalExpression::LessThanOrEqual { field, value } => {
+                self.evaluate_comparison_condition(field, value, context, |a, b| a <= b)
+            },
             ConditionalExpression::Contains { field, value } => {
                 self.evaluate_contains_condition(field, value, context)
             },
@@ -302,6 +308,8 @@ impl ConditionalEngine {
             ConditionalExpression::NotEquals { field, .. } |
             ConditionalExpression::GreaterThan { field, .. } |
             ConditionalExpression::LessThan { field, .. } |
+            ConditionalExpression::GreaterThanOrEqual { field, .. } |
+            ConditionalExpression::LessThanOrEqual { field, .. } |
             ConditionalExpression::Contains { field, .. } |
             ConditionalExpression::IsEmpty { field } |
             ConditionalExpression::IsNotEmpty { field } |
@@ -330,4 +338,71 @@ impl Default for ConditionalEngine {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(form_data: Value) -> EvaluationContext {
+        EvaluationContext {
+            form_data: serde_json::from_value(form_data).unwrap(),
+            field_definitions: HashMap::new(),
+            current_field_id: "target".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_nested_groups_with_short_tags() {
+        // any[ all[a > 1, b == "x"], not[c in [1, 2]] ]
+        let expression: ConditionalExpression = serde_json::from_value(json!({
+            "type": "any",
+            "config": {"expressions": [
+                {"type": "all", "config": {"expressions": [
+                    {"type": "gt", "config": {"field": "a", "value": 1}},
+                    {"type": "eq", "config": {"field": "b", "value": "x"}}
+                ]}},
+                {"type": "not", "config": {"expression":
+                    {"type": "in", "config": {"field": "c", "values": [1, 2]}}
+                }}
+            ]}
+        })).unwrap();
+
+        let mut engine = ConditionalEngine::new();
+        let cases = [
+            (json!({"a": 2, "b": "x", "c": 1}), true),  // first branch
+            (json!({"a": 1, "b": "x", "c": 3}), true),  // second branch
+            (json!({"a": 5, "b": "y", "c": 2}), false), // neither
+            (json!({"a": "3", "b": "x", "c": 2}), true), // numeric string coerced
+            (json!({"a": 0}), true),                     // missing c is not in the list
+            (json!({"c": 1}), false),
+        ];
+        for (form_data, expected) in cases {
+            assert_eq!(
+                engine.evaluate_condition(&expression, &context(form_data.clone())),
+                expected,
+                "form data: {}",
+                form_data
+            );
+        }
+        assert_eq!(engine.get_dependencies(&expression), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_inclusive_comparisons_and_legacy_tags() {
+        let mut engine = ConditionalEngine::new();
+        let gte: ConditionalExpression =
+            serde_json::from_value(json!({"type": "gte", "config": {"field": "a", "value": 3}})).unwrap();
+        let lte: ConditionalExpression =
+            serde_json::from_value(json!({"type": "LessThanOrEqual", "config": {"field": "a", "value": 3}})).unwrap();
+        let legacy: ConditionalExpression =
+            serde_json::from_value(json!({"type": "Equals", "config": {"field": "a", "value": 3}})).unwrap();
+
+        assert!(engine.evaluate_condition(&gte, &context(json!({"a": 3}))));
+        assert!(!engine.evaluate_condition(&gte, &context(json!({"a": 2}))));
+        assert!(engine.evaluate_condition(&lte, &context(json!({"a": 3}))));
+        assert!(!engine.evaluate_condition(&lte, &context(json!({"a": 4}))));
+        assert!(engine.evaluate_condition(&legacy, &context(json!({"a": 3}))));
+    }
+}
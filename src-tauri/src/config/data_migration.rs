@@ -0,0 +1,162 @@
+// Upgrades stored form data between configuration schema versions
+
+use super::schema::*;
+use crate::config::{ConfigurationError, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Upgrade form data saved against schema version `from` to version `to` by applying the
+/// schema's data migrations in sequence. Fails if any step of the path is not mapped.
+pub fn migrate_form_data(
+    schema: &ConfigurationSchema,
+    mut data: HashMap<String, Value>,
+    from: u32,
+    to: u32,
+) -> Result<HashMap<String, Value>> {
+    if from > to {
+        return Err(ConfigurationError::DataMigration(format!(
+            "Cannot downgrade form data from schema version {} to {}", from, to
+        )));
+    }
+
+    let mut version = from;
+    while version < to {
+        let step = schema.data_migrations
+            .iter()
+            .filter(|m| m.from_version == version && m.to_version > version && m.to_version <= to)
+            .max_by_key(|m| m.to_version)
+            .ok_or_else(|| ConfigurationError::DataMigration(format!(
+                "No data migration from schema version {} towards version {} in '{}'",
+                version, to, schema.id
+            )))?;
+
+        for transform in &step.transforms {
+            apply_transform(transform, &mut data);
+        }
+        version = step.to_version;
+    }
+
+    Ok(data)
+}
+
+fn apply_transform(transform: &FieldTransform, data: &mut HashMap<String, Value>) {
+    match transform {
+        FieldTransform::RenameField { from, to } => {
+            if let Some(value) = data.remove(from) {
+                data.insert(to.clone(), value);
+            }
+        },
+        FieldTransform::AddDefault { field, value } => {
+            let entry = data.entry(field.clone()).or_insert(Value::Null);
+            if entry.is_null() {
+                *entry = value.clone();
+            }
+        },
+        FieldTransform::SplitField { field, into, separator } => {
+            // Only text can be split; anything else is left for validation to report
+            let text = match data.get(field) {
+                Some(Value::String(text)) => text.clone(),
+                _ => return,
+            };
+            if !into.contains(field) {
+                data.remove(field);
+            }
+            for (target, part) in into.iter().zip(text.splitn(into.len(), separator.as_str())) {
+                data.insert(target.clone(), Value::String(part.trim().to_string()));
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn schema(data_migrations: Vec<DataMigration>) -> ConfigurationSchema {
+        ConfigurationSchema {
+            id: "company_profile".to_string(),
+            name: "Company Profile".to_string(),
+            description: None,
+            version: "3.0".to_string(),
+            framework: None,
+            category: "test".to_string(),
+            sections: Vec::new(),
+            global_validations: Vec::new(),
+            conditional_logic: Vec::new(),
+            defaults: HashMap::new(),
+            metadata: SchemaMetadata::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            created_by: None,
+            schema_version: 3,
+            data_migrations,
+        }
+    }
+
+    fn profile_schema() -> ConfigurationSchema {
+        schema(vec![
+            DataMigration {
+                from_version: 1,
+                to_version: 2,
+                transforms: vec![
+                    FieldTransform::RenameField { from: "company".to_string(), to: "company_name".to_string() },
+                    FieldTransform::AddDefault { field: "currency".to_string(), value: json!("USD") },
+                ],
+            },
+            DataMigration {
+                from_version: 2,
+                to_version: 3,
+                transforms: vec![FieldTransform::SplitField {
+                    field: "contact".to_string(),
+                    into: vec!["contact_name".to_string(), "contact_email".to_string()],
+                    separator: ";".to_string(),
+                }],
+            },
+        ])
+    }
+
+    fn data(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_upgrades_through_each_version() {
+        let upgraded = migrate_form_data(
+            &profile_schema(),
+            data(json!({"company": "Acme", "contact": "Jo Smith; jo@acme.test"})),
+            1,
+            3,
+        )
+        .unwrap();
+
+        assert_eq!(upgraded, data(json!({
+            "company_name": "Acme",
+            "currency": "USD",
+            "contact_name": "Jo Smith",
+            "contact_email": "jo@acme.test",
+        })));
+
+        // Existing values are kept and a later start skips earlier steps
+        let partial = migrate_form_data(
+            &profile_schema(),
+            data(json!({"company": "Acme", "currency": "EUR"})),
+            2,
+            3,
+        )
+        .unwrap();
+        assert_eq!(partial, data(json!({"company": "Acme", "currency": "EUR"})));
+    }
+
+    #[test]
+    fn test_unmapped_version_is_an_error() {
+        let schema = schema(vec![DataMigration { from_version: 2, to_version: 3, transforms: Vec::new() }]);
+
+        let error = migrate_form_data(&schema, HashMap::new(), 1, 3).unwrap_err();
+        assert!(matches!(error, ConfigurationError::DataMigration(_)));
+        assert!(error.to_string().contains("schema version 1"));
+
+        assert!(migrate_form_data(&schema, HashMap::new(), 3, 2).is_err());
+    }
+}
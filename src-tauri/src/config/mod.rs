@@ -6,6 +6,8 @@ pub mod validation;
 pub mod repository;
 pub mod commands;
 pub mod conditional;
+pub mod data_migration;
+pub mod bundle;
 
 pub use models::*;
 pub use schema::*;
@@ -13,6 +15,7 @@ pub use validation::*;
 pub use repository::ConfigurationRepository;
 pub use commands::ConfigurationService;
 pub use conditional::*;
+pub use data_migration::*;
 
 use thiserror::Error;
 
@@ -30,6 +33,8 @@ pub enum ConfigurationError {
     ValidationError(String),
     #[error("Dependency error: {0}")]
     DependencyError(String),
+    #[error("Data migration error: {0}")]
+    DataMigration(String),
     #[error("Database error: {0}")]
     DatabaseError(#[from] sqlx::Error),
     #[error("Serialization error: {0}")]
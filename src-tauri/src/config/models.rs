@@ -44,6 +44,9 @@ pub struct StoredConfigurationSchema {
     pub created_at: DateTime<Utc>,
     /// When this configuration was last updated
     pub updated_at: DateTime<Utc>,
+    /// Stored form submissions saved against an older schema version
+    #[serde(default)]
+    pub outdated_submissions: i64,
 }
 
 /// Status of a configuration schema
@@ -95,6 +98,9 @@ pub struct UpdateConfiguration {
     pub estimated_minutes: Option<i32>,
     pub locale: Option<String>,
     pub custom_metadata: Option<HashMap<String, serde_json::Value>>,
+    /// User making the change, recorded in the audit log
+    #[serde(default)]
+    pub updated_by: Option<String>,
 }
 
 /// Filter for querying configurations
@@ -284,6 +290,7 @@ impl NewConfiguration {
             created_by: self.created_by.clone(),
             created_at: now,
             updated_at: now,
+            outdated_submissions: 0,
         })
     }
 }
@@ -329,10 +336,48 @@ impl ValidationResults {
 // Import/Export models
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConfigurationTemplatePackage {
+    /// Bundle format version
     pub version: String,
     pub exported_at: String,
     pub templates: Vec<ConfigurationTemplateExport>,
     pub metadata: Option<PackageMetadata>,
+    /// Categories referenced by the templates
+    #[serde(default)]
+    pub categories: Vec<ConfigurationReference>,
+    /// Frameworks referenced by the templates
+    #[serde(default)]
+    pub frameworks: Vec<ConfigurationReference>,
+}
+
+/// A category or framework that configurations refer to by id
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConfigurationReference {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// How to import a bundled item whose id exists locally with different content
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Keep the local item
+    Skip,
+    /// Replace the local item with the bundled one
+    Overwrite,
+    /// Import the bundled item under a new id
+    Rename,
+}
+
+/// A bundled item that clashed with a local one
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportConflict {
+    /// "template", "category" or "framework"
+    pub kind: String,
+    pub id: String,
+    pub resolution: MergeStrategy,
+    /// Id the item was imported under when renamed
+    pub new_id: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -352,6 +397,9 @@ pub struct ConfigurationTemplateExport {
     pub created_at: String,
     pub exported_at: String,
     pub export_metadata: Option<ExportMetadata>,
+    /// Example form data built from the schema's defaults
+    #[serde(default)]
+    pub sample_data: Option<HashMap<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -377,6 +425,8 @@ pub struct ConfigurationImportResult {
     pub imported_ids: Vec<String>,
     pub skipped_templates: Vec<ImportSkippedTemplate>,
     pub errors: Vec<ImportError>,
+    #[serde(default)]
+    pub conflicts: Vec<ImportConflict>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
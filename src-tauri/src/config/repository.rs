@@ -1,29 +1,97 @@
 // Database repository for configuration management
 
 use super::models::*;
-use super::schema::ConfigurationSchema;
+use super::schema::{ConfigurationSchema, DynamicSource, OptionItem};
+use super::data_migration::migrate_form_data;
 use crate::config::{ConfigurationError, Result};
-use crate::database::DatabaseManager;
-use sqlx::{Row, Sqlite};
+use crate::database::{DatabaseManager, StatsCache};
+use crate::database::audit::{AuditAction, AuditLogger};
+use sqlx::{Row, Sqlite, SqliteConnection};
 use chrono::Utc;
 use uuid::Uuid;
 use std::collections::HashMap;
+use serde_json::Value;
+
+/// Entity type recorded in the audit log
+const AUDIT_ENTITY: &str = "configuration";
+
+/// A named option query that schema fields can reference through `dynamic_source`.
+/// Only these queries can run, so a stored schema never supplies its own SQL.
+pub struct OptionSource {
+    pub name: &'static str,
+    /// Parameters bound to the query's placeholders, in order
+    pub parameters: &'static [&'static str],
+    /// Selects `value`, `label` and `description`
+    sql: &'static str,
+}
+
+/// Registered option sources
+pub const OPTION_SOURCES: &[OptionSource] = &[
+    OptionSource {
+        name: "domains",
+        parameters: &[],
+        sql: "SELECT id AS value, name AS label, description FROM domains ORDER BY name",
+    },
+    // Case studies record their domain by name in `industry`
+    OptionSource {
+        name: "case_studies_by_domain",
+        parameters: &["domain_id"],
+        sql: r#"
+            SELECT cs.id AS value, cs.title AS label, cs.description
+            FROM case_studies cs
+            JOIN domains d ON d.name = cs.industry COLLATE NOCASE
+            WHERE d.id = ? AND cs.deleted_at IS NULL
+            ORDER BY cs.title
+        "#,
+    },
+];
+
+/// Kind of entity a configuration refers to by id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReferenceKind {
+    Category,
+    Framework,
+}
+
+impl ReferenceKind {
+    fn table(&self) -> &'static str {
+        match self {
+            ReferenceKind::Category => "configuration_categories",
+            ReferenceKind::Framework => "configuration_frameworks",
+        }
+    }
+
+    /// Name used when reporting import conflicts
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::Category => "category",
+            ReferenceKind::Framework => "framework",
+        }
+    }
+}
 
 /// Repository for configuration database operations
 pub struct ConfigurationRepository {
     db: DatabaseManager,
+    statistics: StatsCache<ConfigurationStatistics>,
 }
 
 impl ConfigurationRepository {
     pub fn new(db: DatabaseManager) -> Self {
-        Self { db }
+        Self { db, statistics: StatsCache::default() }
     }
 
     /// Create a new configuration
     pub async fn create(&self, new_config: NewConfiguration) -> Result<StoredConfigurationSchema> {
-        let id = Uuid::new_v4().to_string();
+        self.create_with_id(Uuid::new_v4().to_string(), new_config).await
+    }
+
+    /// Create a configuration under a given id, e.g. one kept from an imported bundle
+    pub async fn create_with_id(&self, id: String, new_config: NewConfiguration) -> Result<StoredConfigurationSchema> {
         let stored_config = new_config.to_stored_configuration(id)?;
-        
+
+        let mut tx = self.db.pool().begin().await?;
+        self.ensure_references_on(&mut tx, Some(&stored_config.category), stored_config.framework.as_deref()).await?;
         sqlx::query(
             r#"
             INSERT INTO configurations (
@@ -52,15 +120,28 @@ impl ConfigurationRepository {
         .bind(&stored_config.created_by)
         .bind(&stored_config.created_at)
         .bind(&stored_config.updated_at)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        self.find_by_id(&stored_config.id).await?
-            .ok_or_else(|| ConfigurationError::ConfigurationNotFound("Failed to create configuration".to_string()))
+        let created = self.find_by_id_on(&mut tx, &stored_config.id).await?
+            .ok_or_else(|| ConfigurationError::ConfigurationNotFound("Failed to create configuration".to_string()))?;
+
+        let diff = AuditLogger::diff(None, Some(&created));
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, &created.id, AuditAction::Create, created.created_by.as_deref(), &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(created)
     }
 
     /// Find configuration by ID
     pub async fn find_by_id(&self, id: &str) -> Result<Option<StoredConfigurationSchema>> {
+        let mut conn = self.db.pool().acquire().await?;
+        self.find_by_id_on(&mut conn, id).await
+    }
+
+    /// Find configuration by ID on a specific connection, e.g. inside a transaction
+    pub async fn find_by_id_on(&self, conn: &mut SqliteConnection, id: &str) -> Result<Option<StoredConfigurationSchema>> {
         let row = sqlx::query(
             r#"
             SELECT id, name, description, version, framework, category,
@@ -68,11 +149,11 @@ impl ConfigurationRepository {
                    difficulty_level, estimated_minutes, locale, custom_metadata,
                    created_by, created_at, updated_at
             FROM configurations 
-            WHERE id = ? AND status != 'deleted'
+            WHERE id = ? AND deleted_at IS NULL
             "#
         )
         .bind(id)
-        .fetch_optional(self.db.pool())
+        .fetch_optional(&mut *conn)
         .await?;
 
         if let Some(row) = row {
@@ -84,7 +165,8 @@ impl ConfigurationRepository {
 
     /// Update configuration
     pub async fn update(&self, id: &str, update: UpdateConfiguration) -> Result<Option<StoredConfigurationSchema>> {
-        let current = match self.find_by_id(id).await? {
+        let mut tx = self.db.pool().begin().await?;
+        let current = match self.find_by_id_on(&mut tx, id).await? {
             Some(config) => config,
             None => return Ok(None),
         };
@@ -102,7 +184,7 @@ impl ConfigurationRepository {
                 .bind(name)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(description) = &update.description {
@@ -110,7 +192,7 @@ impl ConfigurationRepository {
                 .bind(description)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(version) = &update.version {
@@ -118,7 +200,7 @@ impl ConfigurationRepository {
                 .bind(version)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(framework) = &update.framework {
@@ -126,7 +208,7 @@ impl ConfigurationRepository {
                 .bind(framework)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(category) = &update.category {
@@ -134,7 +216,7 @@ impl ConfigurationRepository {
                 .bind(category)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(schema) = &update.schema {
@@ -143,7 +225,7 @@ impl ConfigurationRepository {
                 .bind(&schema_data)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(status) = &update.status {
@@ -157,7 +239,7 @@ impl ConfigurationRepository {
                 .bind(status_str)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(is_template) = update.is_template {
@@ -165,7 +247,7 @@ impl ConfigurationRepository {
                 .bind(is_template)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(tags) = &update.tags {
@@ -174,7 +256,7 @@ impl ConfigurationRepository {
                 .bind(&tags_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(target_audience) = &update.target_audience {
@@ -183,7 +265,7 @@ impl ConfigurationRepository {
                 .bind(&audience_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(difficulty_level) = &update.difficulty_level {
@@ -191,7 +273,7 @@ impl ConfigurationRepository {
                 .bind(difficulty_level)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(estimated_minutes) = update.estimated_minutes {
@@ -199,7 +281,7 @@ impl ConfigurationRepository {
                 .bind(estimated_minutes)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(locale) = &update.locale {
@@ -207,7 +289,7 @@ impl ConfigurationRepository {
                 .bind(locale)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
         if let Some(custom_metadata) = &update.custom_metadata {
@@ -216,14 +298,29 @@ impl ConfigurationRepository {
                 .bind(&metadata_json)
                 .bind(&now)
                 .bind(id)
-                .execute(self.db.pool()).await?;
+                .execute(&mut *tx).await?;
         }
 
-        self.find_by_id(id).await
+        self.ensure_references_on(&mut tx, update.category.as_deref(), update.framework.as_deref()).await?;
+
+        let updated = self.find_by_id_on(&mut tx, id).await?;
+        if let Some(ref config) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(config));
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, update.updated_by.as_deref(), &diff).await?;
+        }
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(updated)
     }
 
     /// Change configuration status
     pub async fn update_status(&self, id: &str, status: ConfigurationStatus) -> Result<Option<StoredConfigurationSchema>> {
+        if status == ConfigurationStatus::Deleted {
+            self.delete(id, None).await?;
+            return self.find_by_id(id).await;
+        }
+
         let status_str = match status {
             ConfigurationStatus::Draft => "draft",
             ConfigurationStatus::Active => "active",
@@ -231,33 +328,136 @@ impl ConfigurationRepository {
             ConfigurationStatus::Deleted => "deleted",
         };
 
+        let mut tx = self.db.pool().begin().await?;
+        let current = match self.find_by_id_on(&mut tx, id).await? {
+            Some(config) => config,
+            None => return Ok(None),
+        };
+
         sqlx::query("UPDATE configurations SET status = ?, updated_at = ? WHERE id = ?")
             .bind(status_str)
             .bind(Utc::now())
             .bind(id)
-            .execute(self.db.pool())
+            .execute(&mut *tx)
             .await?;
 
-        self.find_by_id(id).await
+        let updated = self.find_by_id_on(&mut tx, id).await?;
+        if let Some(ref config) = updated {
+            let diff = AuditLogger::diff(Some(&current), Some(config));
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        }
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(updated)
     }
 
     /// Delete configuration (soft delete)
-    pub async fn delete(&self, id: &str) -> Result<bool> {
+    pub async fn delete(&self, id: &str, deleted_by: Option<&str>) -> Result<bool> {
+        let now = Utc::now();
+        let mut tx = self.db.pool().begin().await?;
         let result = sqlx::query(
-            "UPDATE configurations SET status = 'deleted', updated_at = ? WHERE id = ? AND status != 'deleted'"
+            "UPDATE configurations SET deleted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL"
+        )
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted = result.rows_affected() > 0;
+        if deleted {
+            let diff = serde_json::json!({ "deleted_at": { "old": null, "new": now } });
+            AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, deleted_by, &diff).await?;
+        }
+        tx.commit().await?;
+        if deleted {
+            self.statistics.invalidate();
+        }
+
+        Ok(deleted)
+    }
+
+    /// Restore a soft-deleted configuration; legacy rows deleted by status return as drafts
+    pub async fn restore_deleted(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let previous: Option<(chrono::DateTime<Utc>, String)> = sqlx::query_as(
+            "SELECT deleted_at, status FROM configurations WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((deleted_at, status)) = previous else {
+            return Ok(false);
+        };
+
+        sqlx::query(
+            "UPDATE configurations SET deleted_at = NULL, status = CASE WHEN status = 'deleted' THEN 'draft' ELSE status END, updated_at = ? WHERE id = ?"
         )
         .bind(Utc::now())
         .bind(id)
-        .execute(self.db.pool())
+        .execute(&mut *tx)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        let mut diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null } });
+        if status == "deleted" {
+            diff["status"] = serde_json::json!({ "old": "deleted", "new": "draft" });
+        }
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Update, None, &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(true)
+    }
+
+    /// Permanently remove a soft-deleted configuration
+    pub async fn purge(&self, id: &str) -> Result<bool> {
+        let mut tx = self.db.pool().begin().await?;
+        let deleted_at: Option<chrono::DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT deleted_at FROM configurations WHERE id = ? AND deleted_at IS NOT NULL"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(deleted_at) = deleted_at else {
+            return Ok(false);
+        };
+
+        sqlx::query("DELETE FROM configurations WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let diff = serde_json::json!({ "deleted_at": { "old": deleted_at, "new": null }, "purged": true });
+        AuditLogger::record_on(&mut tx, AUDIT_ENTITY, id, AuditAction::Delete, None, &diff).await?;
+        tx.commit().await?;
+        self.statistics.invalidate();
+
+        Ok(true)
+    }
+
+    /// List soft-deleted configurations, most recently deleted first
+    pub async fn list_deleted(&self, limit: i32, offset: i32) -> Result<Vec<StoredConfigurationSchema>> {
+        let rows = sqlx::query(
+            "SELECT id, name, description, version, framework, category, schema_data, status, is_template, tags, target_audience, difficulty_level, estimated_minutes, locale, custom_metadata, created_by, created_at, updated_at FROM configurations WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC LIMIT ? OFFSET ?"
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut configurations = Vec::new();
+        for row in rows {
+            configurations.push(self.parse_configuration_row(row)?);
+        }
+
+        Ok(configurations)
     }
 
     /// List configurations with filtering and pagination
     pub async fn list(&self, filter: ConfigurationFilter, limit: i32, offset: i32) -> Result<Vec<StoredConfigurationSchema>> {
         // Build query dynamically based on filter
-        let mut query = "SELECT id, name, description, version, framework, category, schema_data, status, is_template, tags, target_audience, difficulty_level, estimated_minutes, locale, custom_metadata, created_by, created_at, updated_at FROM configurations WHERE status != 'deleted'".to_string();
+        let mut query = "SELECT id, name, description, version, framework, category, schema_data, status, is_template, tags, target_audience, difficulty_level, estimated_minutes, locale, custom_metadata, created_by, created_at, updated_at FROM configurations WHERE deleted_at IS NULL".to_string();
         let mut bind_values: Vec<String> = Vec::new();
 
         if let Some(status) = &filter.status {
@@ -337,10 +537,19 @@ impl ConfigurationRepository {
         self.list(filter, limit, offset).await
     }
 
-    /// Get configuration statistics
+    /// Get configuration statistics, served from a short-lived cache that every write clears
     pub async fn get_statistics(&self) -> Result<ConfigurationStatistics> {
+        self.statistics.get_or_compute(|| self.compute_statistics()).await
+    }
+
+    /// Drop cached statistics so the next read recomputes them
+    pub fn invalidate_statistics(&self) {
+        self.statistics.invalidate();
+    }
+
+    async fn compute_statistics(&self) -> Result<ConfigurationStatistics> {
         // Get total count
-        let total_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE status != 'deleted'")
+        let total_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE deleted_at IS NULL")
             .fetch_one(self.db.pool()).await?;
         let total_configurations: i32 = total_row.try_get("count")?;
 
@@ -350,7 +559,7 @@ impl ConfigurationRepository {
         let active_configurations: i32 = active_row.try_get("count")?;
 
         // Get template count
-        let template_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE is_template = true AND status != 'deleted'")
+        let template_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE is_template = true AND deleted_at IS NULL")
             .fetch_one(self.db.pool()).await?;
         let template_configurations: i32 = template_row.try_get("count")?;
 
@@ -442,7 +651,7 @@ impl ConfigurationRepository {
 
     /// Check if configuration exists
     pub async fn exists(&self, id: &str) -> Result<bool> {
-        let count_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE id = ? AND status != 'deleted'")
+        let count_row = sqlx::query("SELECT COUNT(*) as count FROM configurations WHERE id = ? AND deleted_at IS NULL")
             .bind(id)
             .fetch_one(self.db.pool())
             .await?;
@@ -453,7 +662,7 @@ impl ConfigurationRepository {
 
     /// Count configurations matching filter
     pub async fn count(&self, filter: ConfigurationFilter) -> Result<i32> {
-        let mut query = "SELECT COUNT(*) as count FROM configurations WHERE status != 'deleted'".to_string();
+        let mut query = "SELECT COUNT(*) as count FROM configurations WHERE deleted_at IS NULL".to_string();
         let mut bind_values: Vec<String> = Vec::new();
 
         if let Some(status) = &filter.status {
@@ -520,6 +729,162 @@ impl ConfigurationRepository {
         self.list(filter, limit, 0).await
     }
 
+    /// Record categories and frameworks a configuration uses that aren't known yet
+    async fn ensure_references_on(
+        &self,
+        conn: &mut SqliteConnection,
+        category: Option<&str>,
+        framework: Option<&str>,
+    ) -> Result<()> {
+        for (kind, id) in [(ReferenceKind::Category, category), (ReferenceKind::Framework, framework)] {
+            if let Some(id) = id {
+                sqlx::query(&format!("INSERT OR IGNORE INTO {} (id, name) VALUES (?, ?)", kind.table()))
+                    .bind(id)
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a category or framework by id
+    pub async fn find_reference(&self, kind: ReferenceKind, id: &str) -> Result<Option<ConfigurationReference>> {
+        let row = sqlx::query(&format!("SELECT id, name, description FROM {} WHERE id = ?", kind.table()))
+            .bind(id)
+            .fetch_optional(self.db.pool())
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(ConfigurationReference {
+                id: row.try_get("id")?,
+                name: row.try_get("name")?,
+                description: row.try_get("description")?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a category or framework, replacing the name and description if it exists
+    pub async fn save_reference(&self, kind: ReferenceKind, reference: &ConfigurationReference) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT INTO {} (id, name, description) VALUES (?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, description = excluded.description",
+            kind.table()
+        ))
+        .bind(&reference.id)
+        .bind(&reference.name)
+        .bind(&reference.description)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Number of stored submissions for a configuration saved against a schema version
+    /// older than `current_version`
+    pub async fn count_outdated_submissions(&self, configuration_id: &str, current_version: u32) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM form_submissions WHERE configuration_id = ? AND schema_version < ?"
+        )
+        .bind(configuration_id)
+        .bind(current_version)
+        .fetch_one(self.db.pool())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Upgrade stored submissions of a configuration from schema version `from` to `to`
+    /// using the schema's data migrations. Every submission is transformed before any is
+    /// written, so an unmapped version leaves the stored data untouched.
+    pub async fn migrate_configuration_data(&self, configuration_id: &str, from: u32, to: u32) -> Result<u64> {
+        let schema = self.find_by_id(configuration_id).await?
+            .ok_or_else(|| ConfigurationError::ConfigurationNotFound(configuration_id.to_string()))?
+            .to_configuration_schema()?;
+
+        if to > schema.schema_version {
+            return Err(ConfigurationError::DataMigration(format!(
+                "Schema version {} is newer than the configuration's current version {}", to, schema.schema_version
+            )));
+        }
+
+        let rows = sqlx::query(
+            "SELECT id, form_data FROM form_submissions WHERE configuration_id = ? AND schema_version = ?"
+        )
+        .bind(configuration_id)
+        .bind(from)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut upgraded = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: String = row.try_get("id")?;
+            let form_data: String = row.try_get("form_data")?;
+            let data = migrate_form_data(&schema, serde_json::from_str(&form_data)?, from, to)?;
+            upgraded.push((id, serde_json::to_string(&data)?));
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        for (id, form_data) in &upgraded {
+            sqlx::query("UPDATE form_submissions SET form_data = ?, schema_version = ?, updated_at = ? WHERE id = ?")
+                .bind(form_data)
+                .bind(to)
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(upgraded.len() as u64)
+    }
+
+    /// Load options for a registered source, taking parameter values from the current
+    /// form data. Until every parameter has a value (e.g. the parent select of a cascade
+    /// is still empty) no options are returned.
+    pub async fn resolve_option_source(
+        &self,
+        source: &DynamicSource,
+        form_data: &HashMap<String, Value>,
+    ) -> Result<Vec<OptionItem>> {
+        let registered = OPTION_SOURCES
+            .iter()
+            .find(|s| s.name == source.name)
+            .ok_or_else(|| ConfigurationError::ValidationError(format!("Unknown option source '{}'", source.name)))?;
+
+        let mut query = sqlx::query(registered.sql);
+        for parameter in registered.parameters {
+            let field_id = source.parameters.get(*parameter).map(String::as_str).unwrap_or(*parameter);
+            query = match form_data.get(field_id) {
+                Some(Value::Number(n)) if n.is_i64() => query.bind(n.as_i64()),
+                Some(Value::Number(n)) => query.bind(n.as_f64()),
+                Some(Value::String(s)) if !s.trim().is_empty() => query.bind(s.clone()),
+                Some(Value::Bool(b)) => query.bind(*b),
+                _ => return Ok(Vec::new()),
+            };
+        }
+
+        let rows = query.fetch_all(self.db.pool()).await?;
+        rows.into_iter()
+            .map(|row| -> Result<OptionItem> {
+                let value = match row.try_get::<i64, _>("value") {
+                    Ok(id) => Value::from(id),
+                    Err(_) => Value::from(row.try_get::<String, _>("value")?),
+                };
+                Ok(OptionItem {
+                    value,
+                    label: row.try_get("label")?,
+                    description: row.try_get("description")?,
+                    disabled: false,
+                    icon: None,
+                    group: None,
+                    metadata: None,
+                })
+            })
+            .collect()
+    }
+
     /// Helper method to parse configuration row
     fn parse_configuration_row(&self, row: sqlx::sqlite::SqliteRow) -> Result<StoredConfigurationSchema> {
         let status_str: String = row.try_get("status")?;
@@ -550,6 +915,209 @@ impl ConfigurationRepository {
             created_by: row.try_get("created_by")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
+            outdated_submissions: 0,
         })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{DataMigration, FieldTransform};
+    use serde_json::json;
+
+    async fn test_repository() -> ConfigurationRepository {
+        let db = DatabaseManager::in_memory().await.expect("Failed to create test database");
+        ConfigurationRepository::new(db)
+    }
+
+    async fn insert_case_study(repository: &ConfigurationRepository, id: &str, title: &str, industry: &str) {
+        sqlx::query(
+            "INSERT INTO case_studies (id, title, content, industry, difficulty_level, duration_minutes, learning_objectives) VALUES (?, ?, 'Content', ?, 'beginner', 30, '[]')"
+        )
+        .bind(id)
+        .bind(title)
+        .bind(industry)
+        .execute(repository.db.pool())
+        .await
+        .unwrap();
+    }
+
+    async fn profile_configuration(repository: &ConfigurationRepository) -> StoredConfigurationSchema {
+        let schema = ConfigurationSchema {
+            id: "company_profile".to_string(),
+            name: "Company Profile".to_string(),
+            description: None,
+            version: "2.0".to_string(),
+            framework: None,
+            category: "profile".to_string(),
+            sections: Vec::new(),
+            global_validations: Vec::new(),
+            conditional_logic: Vec::new(),
+            defaults: HashMap::new(),
+            metadata: Default::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            created_by: None,
+            schema_version: 2,
+            data_migrations: vec![DataMigration {
+                from_version: 1,
+                to_version: 2,
+                transforms: vec![
+                    FieldTransform::RenameField { from: "company".to_string(), to: "company_name".to_string() },
+                    FieldTransform::AddDefault { field: "currency".to_string(), value: json!("USD") },
+                ],
+            }],
+        };
+
+        repository
+            .create(NewConfiguration {
+                name: "Company Profile".to_string(),
+                description: None,
+                version: "2.0".to_string(),
+                framework: None,
+                category: "profile".to_string(),
+                schema,
+                is_template: true,
+                tags: Vec::new(),
+                target_audience: Vec::new(),
+                difficulty_level: None,
+                estimated_minutes: None,
+                locale: "en".to_string(),
+                custom_metadata: HashMap::new(),
+                created_by: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn insert_submission(repository: &ConfigurationRepository, id: &str, configuration_id: &str, form_data: Value, version: u32) {
+        sqlx::query("INSERT INTO form_submissions (id, configuration_id, form_data, schema_version) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(configuration_id)
+            .bind(form_data.to_string())
+            .bind(version)
+            .execute(repository.db.pool())
+            .await
+            .unwrap();
+    }
+
+    async fn stored_form_data(repository: &ConfigurationRepository, id: &str) -> (Value, i64) {
+        let row = sqlx::query("SELECT form_data, schema_version FROM form_submissions WHERE id = ?")
+            .bind(id)
+            .fetch_one(repository.db.pool())
+            .await
+            .unwrap();
+        let form_data: String = row.get("form_data");
+        (serde_json::from_str(&form_data).unwrap(), row.get("schema_version"))
+    }
+
+    fn source(name: &str, parameters: &[(&str, &str)]) -> DynamicSource {
+        DynamicSource {
+            name: name.to_string(),
+            parameters: parameters.iter().map(|(p, f)| (p.to_string(), f.to_string())).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_domain_options_source() {
+        let repository = test_repository().await;
+
+        let options = repository.resolve_option_source(&source("domains", &[]), &HashMap::new()).await.unwrap();
+
+        let labels: Vec<&str> = options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, vec!["Business", "Healthcare", "Science", "Technology"]);
+        assert!(options.iter().all(|o| o.value.is_i64()));
+
+        let unknown = repository
+            .resolve_option_source(&source("SELECT * FROM users", &[]), &HashMap::new())
+            .await;
+        assert!(matches!(unknown, Err(ConfigurationError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_case_studies_cascade_from_selected_domain() {
+        let repository = test_repository().await;
+        insert_case_study(&repository, "cs-1", "Cloud Migration", "Technology").await;
+        insert_case_study(&repository, "cs-2", "API Platform", "technology").await;
+        insert_case_study(&repository, "cs-3", "Ward Staffing", "Healthcare").await;
+
+        let domains = repository.resolve_option_source(&source("domains", &[]), &HashMap::new()).await.unwrap();
+        let technology = domains.iter().find(|o| o.label == "Technology").unwrap().value.clone();
+        let by_domain = source("case_studies_by_domain", &[("domain_id", "domain")]);
+
+        let mut form_data = HashMap::new();
+        // Nothing to cascade from until a domain is picked
+        assert!(repository.resolve_option_source(&by_domain, &form_data).await.unwrap().is_empty());
+
+        form_data.insert("domain".to_string(), technology);
+        let options = repository.resolve_option_source(&by_domain, &form_data).await.unwrap();
+        let values: Vec<Value> = options.iter().map(|o| o.value.clone()).collect();
+        assert_eq!(values, vec![json!("cs-2"), json!("cs-1")]);
+
+        sqlx::query("UPDATE case_studies SET deleted_at = CURRENT_TIMESTAMP WHERE id = 'cs-2'")
+            .execute(repository.db.pool())
+            .await
+            .unwrap();
+        let options = repository.resolve_option_source(&by_domain, &form_data).await.unwrap();
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].label, "Cloud Migration");
+    }
+
+    #[tokio::test]
+    async fn test_stored_form_data_upgraded_to_current_schema() {
+        let repository = test_repository().await;
+        let config = profile_configuration(&repository).await;
+        insert_submission(&repository, "old", &config.id, json!({"company": "Acme"}), 1).await;
+        insert_submission(&repository, "new", &config.id, json!({"company_name": "Globex", "currency": "EUR"}), 2).await;
+
+        assert_eq!(repository.count_outdated_submissions(&config.id, 2).await.unwrap(), 1);
+
+        let migrated = repository.migrate_configuration_data(&config.id, 1, 2).await.unwrap();
+
+        assert_eq!(migrated, 1);
+        assert_eq!(stored_form_data(&repository, "old").await, (json!({"company_name": "Acme", "currency": "USD"}), 2));
+        assert_eq!(stored_form_data(&repository, "new").await, (json!({"company_name": "Globex", "currency": "EUR"}), 2));
+        assert_eq!(repository.count_outdated_submissions(&config.id, 2).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unmapped_schema_version_leaves_data_untouched() {
+        let repository = test_repository().await;
+        let config = profile_configuration(&repository).await;
+        insert_submission(&repository, "ancient", &config.id, json!({"company": "Initech"}), 0).await;
+
+        let result = repository.migrate_configuration_data(&config.id, 0, 2).await;
+
+        match result {
+            Err(ConfigurationError::DataMigration(message)) => assert!(message.contains("schema version 0")),
+            other => panic!("expected a data migration error, got {:?}", other),
+        }
+        assert_eq!(stored_form_data(&repository, "ancient").await, (json!({"company": "Initech"}), 0));
+        assert!(repository.migrate_configuration_data(&config.id, 1, 3).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_statistics_are_cached_until_a_write() {
+        let repository = test_repository().await;
+        let first = profile_configuration(&repository).await;
+        repository.update_status(&first.id, ConfigurationStatus::Active).await.unwrap();
+
+        let stats = repository.get_statistics().await.unwrap();
+        assert_eq!((stats.total_configurations, stats.active_configurations, stats.template_configurations), (1, 1, 1));
+
+        // Writes that bypass the repository aren't seen until the cache is cleared
+        sqlx::query("UPDATE configurations SET is_template = false WHERE id = ?")
+            .bind(&first.id)
+            .execute(repository.db.pool())
+            .await
+            .unwrap();
+        assert_eq!(repository.get_statistics().await.unwrap().template_configurations, 1);
+
+        profile_configuration(&repository).await;
+        let stats = repository.get_statistics().await.unwrap();
+        assert_eq!((stats.total_configurations, stats.active_configurations, stats.template_configurations), (2, 1, 1));
+
+        repository.delete(&first.id, None).await.unwrap();
+        assert_eq!(repository.get_statistics().await.unwrap().total_configurations, 1);
+    }
+}
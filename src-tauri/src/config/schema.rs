@@ -35,6 +35,41 @@ pub struct ConfigurationSchema {
     pub updated_at: DateTime<Utc>,
     /// Who created/owns this schema
     pub created_by: Option<String>,
+    /// Version of the field layout; stored form data records the version it was entered against
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+    /// Steps that upgrade form data saved against older schema versions
+    #[serde(default)]
+    pub data_migrations: Vec<DataMigration>,
+}
+
+fn default_schema_version() -> u32 {
+    1
+}
+
+/// Upgrade of stored form data from one schema version to a later one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataMigration {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Applied in order
+    pub transforms: Vec<FieldTransform>,
+}
+
+/// A single change to stored form data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum FieldTransform {
+    /// Move a value to a new field id
+    RenameField { from: String, to: String },
+    /// Fill a new field where no value is stored
+    AddDefault { field: String, value: serde_json::Value },
+    /// Split a text value into several fields on a separator
+    SplitField {
+        field: String,
+        into: Vec<String>,
+        separator: String,
+    },
 }
 
 /// A logical grouping of related fields
@@ -246,6 +281,9 @@ pub struct FieldOptions {
     pub static_options: Option<Vec<OptionItem>>,
     /// Dynamic options loaded from an API or database
     pub dynamic_options: Option<DynamicOptionsConfig>,
+    /// Options loaded from a registered database query
+    #[serde(default)]
+    pub dynamic_source: Option<DynamicSource>,
     /// Whether to allow custom values not in the list
     pub allow_custom: bool,
     /// Custom value validation when allow_custom is true
@@ -284,6 +322,17 @@ pub struct DynamicOptionsConfig {
     pub cache_config: Option<CacheConfig>,
 }
 
+/// Reference to a registered option query (see `ConfigurationRepository::resolve_option_source`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicSource {
+    /// Name of the registered source
+    pub name: String,
+    /// Source parameter name mapped to the form field that supplies its value,
+    /// so one select can narrow another
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
 /// Caching configuration for dynamic options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
@@ -408,6 +457,63 @@ pub struct CrossFieldValidation {
     pub message: String,
     /// When to trigger this validation
     pub trigger: ValidationTrigger,
+    /// Structured rule to evaluate; errors are reported against the fields it involves
+    #[serde(default)]
+    pub rule: Option<CrossFieldRule>,
+}
+
+/// A check over two or more fields, run after each field has passed its own validation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum CrossFieldRule {
+    /// `left` compared with `right` as dates, numbers or text; e.g. end_date > start_date
+    Compare {
+        left: String,
+        operator: ComparisonOperator,
+        right: String,
+    },
+    /// `field` must have a value whenever `condition` holds
+    RequiredIf {
+        field: String,
+        condition: ConditionalExpression,
+    },
+}
+
+/// Operator for comparing two field values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl ComparisonOperator {
+    /// Whether `ordering` (left compared with right) satisfies this operator
+    pub fn accepts(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            ComparisonOperator::Eq => ordering == Equal,
+            ComparisonOperator::Ne => ordering != Equal,
+            ComparisonOperator::Gt => ordering == Greater,
+            ComparisonOperator::Gte => ordering != Less,
+            ComparisonOperator::Lt => ordering == Less,
+            ComparisonOperator::Lte => ordering != Greater,
+        }
+    }
+}
+
+impl CrossFieldRule {
+    /// Fields that receive the error when the rule fails
+    pub fn error_fields(&self) -> Vec<&str> {
+        match self {
+            CrossFieldRule::Compare { left, right, .. } => vec![left.as_str(), right.as_str()],
+            CrossFieldRule::RequiredIf { field, .. } => vec![field.as_str()],
+        }
+    }
 }
 
 /// When to trigger validation
@@ -457,31 +563,49 @@ pub enum ConditionalAction {
     SetOptions(Vec<OptionItem>),
 }
 
-/// Conditional expression for evaluating when rules should apply
+/// Conditional expression for evaluating when rules should apply.
+/// Comparison and group variants also accept short tags (`eq`, `gte`, `all`, `any`, ...).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
 pub enum ConditionalExpression {
     /// Simple field equals value
+    #[serde(alias = "eq")]
     Equals {
         field: String,
         value: serde_json::Value,
     },
     /// Field not equals value
+    #[serde(alias = "ne")]
     NotEquals {
         field: String,
         value: serde_json::Value,
     },
     /// Field greater than value
+    #[serde(alias = "gt")]
     GreaterThan {
         field: String,
         value: serde_json::Value,
     },
     /// Field less than value
+    #[serde(alias = "lt")]
     LessThan {
         field: String,
         value: serde_json::Value,
     },
+    /// Field greater than or equal to value
+    #[serde(alias = "gte")]
+    GreaterThanOrEqual {
+        field: String,
+        value: serde_json::Value,
+    },
+    /// Field less than or equal to value
+    #[serde(alias = "lte")]
+    LessThanOrEqual {
+        field: String,
+        value: serde_json::Value,
+    },
     /// Field contains value (for arrays/strings)
+    #[serde(alias = "contains")]
     Contains {
         field: String,
         value: serde_json::Value,
@@ -500,19 +624,23 @@ pub enum ConditionalExpression {
         pattern: String,
     },
     /// Field value is in list
+    #[serde(alias = "in")]
     In {
         field: String,
         values: Vec<serde_json::Value>,
     },
-    /// AND operation
+    /// AND operation; stops at the first false expression
+    #[serde(alias = "all")]
     And {
         expressions: Vec<ConditionalExpression>,
     },
-    /// OR operation
+    /// OR operation; stops at the first true expression
+    #[serde(alias = "any")]
     Or {
         expressions: Vec<ConditionalExpression>,
     },
     /// NOT operation
+    #[serde(alias = "not")]
     Not {
         expression: Box<ConditionalExpression>,
     },
@@ -637,6 +765,8 @@ impl ConfigurationSchema {
             ConditionalExpression::NotEquals { field, .. } |
             ConditionalExpression::GreaterThan { field, .. } |
             ConditionalExpression::LessThan { field, .. } |
+            ConditionalExpression::GreaterThanOrEqual { field, .. } |
+            ConditionalExpression::LessThanOrEqual { field, .. } |
             ConditionalExpression::Contains { field, .. } |
             ConditionalExpression::IsEmpty { field } |
             ConditionalExpression::IsNotEmpty { field } |
@@ -735,6 +865,8 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             created_by: None,
+            schema_version: 1,
+            data_migrations: Vec::new(),
         };
 
         assert!(schema.validate().is_ok());
@@ -800,6 +932,8 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             created_by: None,
+            schema_version: 1,
+            data_migrations: Vec::new(),
         };
 
         assert!(schema.validate().is_err());
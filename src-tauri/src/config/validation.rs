@@ -2,6 +2,7 @@
 
 use super::schema::*;
 use super::models::*;
+use super::conditional::{ConditionalEngine, EvaluationContext};
 use crate::config::{ConfigurationError, Result};
 use std::collections::HashMap;
 use regex::Regex;
@@ -73,6 +74,21 @@ impl ValidationEngine {
                 results.add_global_error(format!("Cross-field validation '{}' invalid: {}", validation.id, e));
             }
         }
+
+        // Validate the upgrade path for stored form data
+        for migration in &schema.data_migrations {
+            if migration.to_version <= migration.from_version || migration.to_version > schema.schema_version {
+                results.add_global_error(format!(
+                    "Data migration {} -> {} is invalid for schema version {}",
+                    migration.from_version, migration.to_version, schema.schema_version
+                ));
+            }
+        }
+        for version in 1..schema.schema_version {
+            if !schema.data_migrations.iter().any(|m| m.from_version == version) {
+                results.add_warning(format!("Form data saved at schema version {} cannot be upgraded", version));
+            }
+        }
         
         Ok(results)
     }
@@ -116,8 +132,20 @@ impl ValidationEngine {
             }
         }
         
-        // Validate global/cross-field validations
+        // Validate global/cross-field validations. These run after the per-field checks so
+        // structured rules can skip fields that are missing or already invalid.
         for validation in &schema.global_validations {
+            if let Some(ref rule) = validation.rule {
+                if self.cross_field_rule_applies(rule, &results, form_data)
+                    && !self.evaluate_cross_field_rule(rule, schema, form_data)
+                {
+                    for field_id in rule.error_fields() {
+                        results.add_field_error(field_id, validation.message.clone());
+                    }
+                }
+                continue;
+            }
+
             match self.validate_cross_field_rule(validation, form_data) {
                 Ok(validation_result) => {
                     if !validation_result.is_valid {
@@ -518,6 +546,8 @@ impl ValidationEngine {
             ConditionalExpression::NotEquals { field, .. } |
             ConditionalExpression::GreaterThan { field, .. } |
             ConditionalExpression::LessThan { field, .. } |
+            ConditionalExpression::GreaterThanOrEqual { field, .. } |
+            ConditionalExpression::LessThanOrEqual { field, .. } |
             ConditionalExpression::Contains { field, .. } |
             ConditionalExpression::IsEmpty { field } |
             ConditionalExpression::IsNotEmpty { field } |
@@ -557,9 +587,124 @@ impl ValidationEngine {
                 ));
             }
         }
+
+        if let Some(ref rule) = validation.rule {
+            for field_id in self.cross_field_rule_fields(rule) {
+                if !field_ids.contains(&field_id) {
+                    return Err(ConfigurationError::SchemaValidation(
+                        format!("Cross-field rule references unknown field: {}", field_id)
+                    ));
+                }
+            }
+        }
         
         Ok(())
     }
+
+    /// All fields a structured cross-field rule reads
+    fn cross_field_rule_fields(&self, rule: &CrossFieldRule) -> Vec<String> {
+        match rule {
+            CrossFieldRule::Compare { left, right, .. } => vec![left.clone(), right.clone()],
+            CrossFieldRule::RequiredIf { field, condition } => {
+                let mut fields = ConditionalEngine::new().get_dependencies(condition);
+                fields.push(field.clone());
+                fields
+            },
+        }
+    }
+
+    /// A rule is only checked once the fields it reads have passed their own validation;
+    /// comparisons also need both values to be present
+    fn cross_field_rule_applies(
+        &self,
+        rule: &CrossFieldRule,
+        results: &ValidationResults,
+        form_data: &HashMap<String, Value>,
+    ) -> bool {
+        let fields = self.cross_field_rule_fields(rule);
+        if fields.iter().any(|field_id| results.field_errors.contains_key(field_id)) {
+            return false;
+        }
+
+        match rule {
+            CrossFieldRule::Compare { .. } => fields.iter().all(|field_id| {
+                form_data.get(field_id).is_some_and(|value| !self.is_empty_value(value))
+            }),
+            CrossFieldRule::RequiredIf { .. } => true,
+        }
+    }
+
+    /// Evaluate a structured cross-field rule against the submitted data
+    fn evaluate_cross_field_rule(
+        &self,
+        rule: &CrossFieldRule,
+        schema: &ConfigurationSchema,
+        form_data: &HashMap<String, Value>,
+    ) -> bool {
+        match rule {
+            CrossFieldRule::Compare { left, operator, right } => {
+                // Values that can't be compared are left to per-field validation
+                match self.compare_field_values(schema, form_data, left, right) {
+                    Some(ordering) => operator.accepts(ordering),
+                    None => true,
+                }
+            },
+            CrossFieldRule::RequiredIf { field, condition } => {
+                let context = EvaluationContext {
+                    form_data: form_data.clone(),
+                    field_definitions: HashMap::new(),
+                    current_field_id: field.clone(),
+                };
+                !ConditionalEngine::new().evaluate_condition(condition, &context)
+                    || form_data.get(field).is_some_and(|value| !self.is_empty_value(value))
+            },
+        }
+    }
+
+    /// Order two field values as dates (using the fields' formats), numbers or text
+    fn compare_field_values(
+        &self,
+        schema: &ConfigurationSchema,
+        form_data: &HashMap<String, Value>,
+        left: &str,
+        right: &str,
+    ) -> Option<std::cmp::Ordering> {
+        let left_value = form_data.get(left)?;
+        let right_value = form_data.get(right)?;
+
+        let left_date = self.parse_date_value(schema.get_field(left), left_value);
+        let right_date = self.parse_date_value(schema.get_field(right), right_value);
+        if let (Some(a), Some(b)) = (left_date, right_date) {
+            return Some(a.cmp(&b));
+        }
+
+        let as_number = |value: &Value| match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        };
+        if let (Some(a), Some(b)) = (as_number(left_value), as_number(right_value)) {
+            return a.partial_cmp(&b);
+        }
+
+        match (left_value.as_str(), right_value.as_str()) {
+            (Some(a), Some(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    fn parse_date_value(&self, field: Option<&FieldDefinition>, value: &Value) -> Option<chrono::NaiveDateTime> {
+        let text = value.as_str()?;
+        match field.map(|f| &f.field_type) {
+            Some(FieldType::Date { format, .. }) => {
+                chrono::NaiveDate::parse_from_str(text, format).ok()?.and_hms_opt(0, 0, 0)
+            },
+            Some(FieldType::DateTime { format, .. }) => {
+                chrono::NaiveDateTime::parse_from_str(text, format).ok()
+            },
+            _ => None,
+        }
+    }
     
     /// Validate a cross-field rule
     fn validate_cross_field_rule(&self, validation: &CrossFieldValidation, form_data: &HashMap<String, Value>) -> Result<ValidationResult> {
@@ -657,4 +802,156 @@ impl ValidationResult {
             message: Some(message),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(id: &str, field_type: FieldType) -> FieldDefinition {
+        FieldDefinition {
+            id: id.to_string(),
+            label: id.to_string(),
+            field_type,
+            required: false,
+            placeholder: None,
+            help_text: None,
+            default_value: None,
+            validations: Vec::new(),
+            options: None,
+            display: FieldDisplay::default(),
+            visibility_conditions: None,
+            dependent_fields: Vec::new(),
+            framework_mapping: None,
+        }
+    }
+
+    fn date(id: &str) -> FieldDefinition {
+        field(id, FieldType::Date { min_date: None, max_date: None, format: "%Y-%m-%d".to_string() })
+    }
+
+    fn text(id: &str) -> FieldDefinition {
+        field(id, FieldType::Text { min_length: None, max_length: None, pattern: None })
+    }
+
+    fn schema(fields: Vec<FieldDefinition>, id: &str, message: &str, rule: CrossFieldRule) -> ConfigurationSchema {
+        ConfigurationSchema {
+            id: "test".to_string(),
+            name: "Test Schema".to_string(),
+            description: None,
+            version: "1.0".to_string(),
+            framework: None,
+            category: "test".to_string(),
+            sections: vec![FieldSection {
+                id: "section1".to_string(),
+                title: "Section 1".to_string(),
+                description: None,
+                order: 1,
+                collapsible: false,
+                collapsed_by_default: false,
+                icon: None,
+                fields,
+                visibility_conditions: None,
+            }],
+            global_validations: vec![CrossFieldValidation {
+                id: id.to_string(),
+                name: id.to_string(),
+                fields: Vec::new(),
+                expression: String::new(),
+                message: message.to_string(),
+                trigger: ValidationTrigger::OnSubmit,
+                rule: Some(rule),
+            }],
+            conditional_logic: Vec::new(),
+            defaults: HashMap::new(),
+            metadata: SchemaMetadata::default(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            created_by: None,
+            schema_version: 1,
+            data_migrations: Vec::new(),
+        }
+    }
+
+    fn data(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_date_ordering_rule() {
+        let schema = schema(
+            vec![date("start_date"), date("end_date")],
+            "dates",
+            "End date must be after start date",
+            CrossFieldRule::Compare {
+                left: "end_date".to_string(),
+                operator: ComparisonOperator::Gt,
+                right: "start_date".to_string(),
+            },
+        );
+        let engine = ValidationEngine::new();
+        assert!(engine.validate_schema(&schema).unwrap().is_valid);
+
+        let ordered = engine
+            .validate_form_data(&schema, &data(json!({"start_date": "2024-01-10", "end_date": "2024-02-01"})))
+            .unwrap();
+        assert!(ordered.is_valid);
+
+        let reversed = engine
+            .validate_form_data(&schema, &data(json!({"start_date": "2024-03-01", "end_date": "2024-02-01"})))
+            .unwrap();
+        assert!(!reversed.is_valid);
+        assert_eq!(reversed.field_errors["end_date"], vec!["End date must be after start date"]);
+        assert_eq!(reversed.field_errors["start_date"], vec!["End date must be after start date"]);
+        assert!(reversed.global_errors.is_empty());
+
+        // An absent end date is not compared
+        let partial = engine.validate_form_data(&schema, &data(json!({"start_date": "2024-03-01"}))).unwrap();
+        assert!(partial.is_valid);
+
+        // An unparseable date is reported by its own field only
+        let invalid = engine
+            .validate_form_data(&schema, &data(json!({"start_date": "2024-03-01", "end_date": "soon"})))
+            .unwrap();
+        assert_eq!(invalid.field_errors.len(), 1);
+        assert_eq!(invalid.field_errors["end_date"].len(), 1);
+        assert!(invalid.field_errors["end_date"][0].starts_with("Invalid value for field type"));
+    }
+
+    #[test]
+    fn test_conditional_required_rule() {
+        let schema = schema(
+            vec![
+                field("type", FieldType::Select { searchable: false, clearable: false, placeholder: None }),
+                text("other_text"),
+            ],
+            "other",
+            "Please describe the other type",
+            CrossFieldRule::RequiredIf {
+                field: "other_text".to_string(),
+                condition: ConditionalExpression::Equals { field: "type".to_string(), value: json!("other") },
+            },
+        );
+        let engine = ValidationEngine::new();
+
+        let missing = engine.validate_form_data(&schema, &data(json!({"type": "other"}))).unwrap();
+        assert!(!missing.is_valid);
+        assert_eq!(missing.field_errors["other_text"], vec!["Please describe the other type"]);
+        assert!(!missing.field_errors.contains_key("type"));
+
+        let blank = engine.validate_form_data(&schema, &data(json!({"type": "other", "other_text": "  "}))).unwrap();
+        assert!(!blank.is_valid);
+
+        let provided = engine
+            .validate_form_data(&schema, &data(json!({"type": "other", "other_text": "Consulting"})))
+            .unwrap();
+        assert!(provided.is_valid);
+
+        let not_other = engine.validate_form_data(&schema, &data(json!({"type": "retail"}))).unwrap();
+        assert!(not_other.is_valid);
+
+        // With the controlling field absent the condition does not hold
+        let absent = engine.validate_form_data(&schema, &data(json!({}))).unwrap();
+        assert!(absent.is_valid);
+    }
+}
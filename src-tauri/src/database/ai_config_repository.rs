@@ -1,20 +1,45 @@
 use crate::ai::config::AIConfig;
+use crate::encryption::commands::EncryptionState;
+use crate::encryption::{field_encryption, EncryptionError};
 use sqlx::{SqlitePool, Row};
 use serde_json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AIConfigRepositoryError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Encryption must be initialized before API keys can be saved")]
+    EncryptionNotInitialized,
+    #[error("Encryption error: {0}")]
+    Encryption(#[from] EncryptionError),
+}
+
+pub type Result<T> = std::result::Result<T, AIConfigRepositoryError>;
 
 pub struct AIConfigRepository {
     pool: SqlitePool,
+    encryption: Option<EncryptionState>,
 }
 
 impl AIConfigRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { pool, encryption: None }
     }
 
-    /// Save AI configuration to database
-    pub async fn save_config(&self, config: &AIConfig) -> Result<(), sqlx::Error> {
-        let config_json = serde_json::to_string(config)
-            .map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize config: {}", e)))?;
+    /// Repository that encrypts provider API keys at rest with `encryption`
+    pub fn with_encryption(pool: SqlitePool, encryption: Option<EncryptionState>) -> Self {
+        Self { pool, encryption }
+    }
+
+    /// Save AI configuration to database. API keys are stored encrypted; saving a config
+    /// that has any API key fails unless encryption is initialized.
+    pub async fn save_config(&self, config: &AIConfig) -> Result<()> {
+        let mut stored = config.clone();
+        self.encrypt_api_keys(&mut stored).await?;
+        let config_json = serde_json::to_string(&stored)?;
 
         sqlx::query(
             r#"
@@ -29,16 +54,18 @@ impl AIConfigRepository {
         Ok(())
     }
 
-    /// Load AI configuration from database
-    pub async fn load_config(&self) -> Result<Option<AIConfig>, sqlx::Error> {
+    /// Load AI configuration from database. Encrypted API keys are decrypted when
+    /// encryption is initialized and otherwise kept as ciphertext, so they survive
+    /// until the keys can be decrypted (saving is refused in the meantime).
+    pub async fn load_config(&self) -> Result<Option<AIConfig>> {
         let row = sqlx::query("SELECT config_data FROM ai_configurations WHERE id = 1")
             .fetch_optional(&self.pool)
             .await?;
 
         if let Some(row) = row {
             let config_data: String = row.get("config_data");
-            let config: AIConfig = serde_json::from_str(&config_data)
-                .map_err(|e| sqlx::Error::Protocol(format!("Failed to deserialize config: {}", e)))?;
+            let mut config: AIConfig = serde_json::from_str(&config_data)?;
+            self.decrypt_api_keys(&mut config).await?;
             Ok(Some(config))
         } else {
             Ok(None)
@@ -46,12 +73,159 @@ impl AIConfigRepository {
     }
 
     /// Check if configuration exists
-    pub async fn config_exists(&self) -> Result<bool, sqlx::Error> {
+    pub async fn config_exists(&self) -> Result<bool> {
         let row = sqlx::query("SELECT COUNT(*) as count FROM ai_configurations WHERE id = 1")
             .fetch_one(&self.pool)
             .await?;
-        
+
         let count: i64 = row.get("count");
         Ok(count > 0)
     }
-}
\ No newline at end of file
+
+    async fn encrypt_api_keys(&self, config: &mut AIConfig) -> Result<()> {
+        let has_keys = config
+            .providers
+            .values()
+            .any(|provider| provider.api_key.as_deref().is_some_and(|key| !key.is_empty()));
+        if !has_keys {
+            return Ok(());
+        }
+
+        let encryption = self.encryption.as_ref().ok_or(AIConfigRepositoryError::EncryptionNotInitialized)?;
+        let manager = encryption.lock().await;
+        if !manager.is_initialized() {
+            return Err(AIConfigRepositoryError::EncryptionNotInitialized);
+        }
+
+        for provider in config.providers.values_mut() {
+            // Keys loaded while locked are still ciphertext and are stored as they are
+            if let Some(key) = provider.api_key.as_mut()
+                .filter(|key| !key.is_empty() && !field_encryption::looks_like_encrypted_data(key))
+            {
+                *key = field_encryption::encrypt_if_sensitive(&manager, "api_key", key)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn decrypt_api_keys(&self, config: &mut AIConfig) -> Result<()> {
+        let manager = match &self.encryption {
+            Some(encryption) => Some(encryption.lock().await),
+            None => None,
+        };
+
+        for (provider_type, provider) in config.providers.iter_mut() {
+            let Some(key) = provider.api_key.as_mut() else {
+                continue;
+            };
+            if !field_encryption::looks_like_encrypted_data(key) {
+                // Stored before keys were encrypted; it is encrypted on the next save
+                continue;
+            }
+            match &manager {
+                Some(manager) if manager.is_initialized() => {
+                    *key = field_encryption::decrypt_if_encrypted(manager, "api_key", key)?;
+                }
+                _ => {
+                    tracing::warn!("API key for {} stays encrypted until encryption is unlocked", provider_type);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::config::ProviderConfig;
+    use crate::encryption::EncryptionManager;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(include_str!("migrations/006_ai_configurations.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn encryption_state(initialized: bool) -> EncryptionState {
+        let app = tauri::test::mock_app();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        if initialized {
+            manager.initialize_with_key([42u8; 32]);
+        }
+        Arc::new(Mutex::new(manager))
+    }
+
+    fn config_with_key(key: &str) -> AIConfig {
+        let mut config = AIConfig::default();
+        config.add_provider(ProviderConfig::openai(key)).unwrap();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_api_keys_are_encrypted_at_rest() {
+        let pool = create_test_pool().await;
+        let repository = AIConfigRepository::with_encryption(pool.clone(), Some(encryption_state(true)));
+
+        repository.save_config(&config_with_key("sk-test-secret")).await.unwrap();
+
+        let stored: String = sqlx::query_scalar("SELECT config_data FROM ai_configurations WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(!stored.contains("sk-test-secret"));
+        assert!(stored.contains("AES-256-GCM"));
+
+        let loaded = repository.load_config().await.unwrap().unwrap();
+        let openai = loaded.providers.values().find(|p| p.api_key.is_some()).unwrap();
+        assert_eq!(openai.api_key.as_deref(), Some("sk-test-secret"));
+
+        // Without an initialized key the ciphertext is kept, and saving it back is refused
+        // rather than overwriting the stored key
+        let locked = AIConfigRepository::with_encryption(pool.clone(), Some(encryption_state(false)));
+        let loaded = locked.load_config().await.unwrap().unwrap();
+        let openai = loaded.providers.values().find(|p| p.api_key.is_some()).unwrap();
+        assert!(field_encryption::looks_like_encrypted_data(openai.api_key.as_deref().unwrap()));
+        assert!(matches!(
+            locked.save_config(&loaded).await,
+            Err(AIConfigRepositoryError::EncryptionNotInitialized)
+        ));
+        let unchanged: String = sqlx::query_scalar("SELECT config_data FROM ai_configurations WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unchanged, stored);
+
+        // Once unlocked, saving a config that still holds ciphertext doesn't encrypt it twice
+        repository.save_config(&loaded).await.unwrap();
+        let reloaded = repository.load_config().await.unwrap().unwrap();
+        let openai = reloaded.providers.values().find(|p| p.api_key.is_some()).unwrap();
+        assert_eq!(openai.api_key.as_deref(), Some("sk-test-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_saving_api_keys_requires_encryption() {
+        let pool = create_test_pool().await;
+
+        for repository in [
+            AIConfigRepository::new(pool.clone()),
+            AIConfigRepository::with_encryption(pool.clone(), Some(encryption_state(false))),
+        ] {
+            let result = repository.save_config(&config_with_key("sk-test-secret")).await;
+            assert!(matches!(result, Err(AIConfigRepositoryError::EncryptionNotInitialized)));
+            assert!(!repository.config_exists().await.unwrap());
+        }
+
+        // Configs without keys can still be saved
+        AIConfigRepository::new(pool.clone()).save_config(&AIConfig::default()).await.unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+// Append-only audit trail of entity changes
+
+use super::DatabaseManager;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::{Row, SqliteConnection};
+
+/// Fields that change on every write and would only add noise to a diff
+const IGNORED_FIELDS: &[&str] = &["updated_at"];
+
+/// Kind of change recorded in the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(AuditAction::Create),
+            "update" => Some(AuditAction::Update),
+            "delete" => Some(AuditAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: AuditAction,
+    pub user_id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Changed fields as `{"field": {"old": ..., "new": ...}}`
+    pub diff: Value,
+}
+
+/// Records changes and reads them back. Writes take the caller's connection so the
+/// entry commits or rolls back with the change it describes.
+pub struct AuditLogger {
+    db: DatabaseManager,
+}
+
+impl AuditLogger {
+    pub fn new(db: DatabaseManager) -> Self {
+        Self { db }
+    }
+
+    /// Append an entry on the given connection, normally the transaction making the change
+    pub async fn record_on(
+        conn: &mut SqliteConnection,
+        entity_type: &str,
+        entity_id: &str,
+        action: AuditAction,
+        user_id: Option<&str>,
+        diff: &Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audit_log (entity_type, entity_id, action, user_id, timestamp, diff_json) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .bind(action.as_str())
+        .bind(user_id)
+        .bind(Utc::now())
+        .bind(diff.to_string())
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Field-level differences between two states of an entity; `None` stands for the
+    /// entity not existing, so a create lists every field with an `old` of null
+    pub fn diff<T: Serialize>(before: Option<&T>, after: Option<&T>) -> Value {
+        let fields = |state: Option<&T>| match state.map(serde_json::to_value) {
+            Some(Ok(Value::Object(fields))) => fields,
+            _ => Map::new(),
+        };
+        let before = fields(before);
+        let after = fields(after);
+
+        let mut changes = Map::new();
+        for key in before.keys().chain(after.keys()) {
+            if IGNORED_FIELDS.contains(&key.as_str()) || changes.contains_key(key) {
+                continue;
+            }
+            let old = before.get(key).unwrap_or(&Value::Null);
+            let new = after.get(key).unwrap_or(&Value::Null);
+            if old != new {
+                changes.insert(key.clone(), json!({ "old": old, "new": new }));
+            }
+        }
+
+        Value::Object(changes)
+    }
+
+    /// Entries for one entity, oldest first
+    pub async fn get_entity_log(&self, entity_type: &str, entity_id: &str) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT * FROM audit_log WHERE entity_type = ? AND entity_id = ? ORDER BY id ASC"
+        )
+        .bind(entity_type)
+        .bind(entity_id)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter().map(Self::parse_row).collect()
+    }
+
+    /// Changes made by a user, most recent first
+    pub async fn get_user_activity(&self, user_id: &str, limit: i32, offset: i32) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT * FROM audit_log WHERE user_id = ? ORDER BY id DESC LIMIT ? OFFSET ?"
+        )
+        .bind(user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        rows.into_iter().map(Self::parse_row).collect()
+    }
+
+    fn parse_row(row: sqlx::sqlite::SqliteRow) -> Result<AuditEntry, sqlx::Error> {
+        let action: String = row.try_get("action")?;
+        let diff_json: String = row.try_get("diff_json")?;
+
+        Ok(AuditEntry {
+            id: row.try_get("id")?,
+            entity_type: row.try_get("entity_type")?,
+            entity_id: row.try_get("entity_id")?,
+            action: AuditAction::parse(&action)
+                .ok_or_else(|| sqlx::Error::Decode(format!("Unknown audit action '{}'", action).into()))?,
+            user_id: row.try_get("user_id")?,
+            timestamp: row.try_get("timestamp")?,
+            diff: serde_json::from_str(&diff_json).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lists_changed_fields_only() {
+        let before = json!({"title": "Old", "industry": "Retail", "updated_at": "2024-01-01"});
+        let after = json!({"title": "New", "industry": "Retail", "updated_at": "2024-02-01"});
+
+        assert_eq!(
+            AuditLogger::diff(Some(&before), Some(&after)),
+            json!({"title": {"old": "Old", "new": "New"}})
+        );
+        assert_eq!(
+            AuditLogger::diff(None, Some(&json!({"title": "New"}))),
+            json!({"title": {"old": null, "new": "New"}})
+        );
+    }
+}
@@ -0,0 +1,284 @@
+// Repository for managing which case studies belong to a collection, and in what order
+
+use crate::database::models::CollectionMember;
+use chrono::Utc;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::HashSet;
+
+/// Errors from collection membership operations
+#[derive(thiserror::Error, Debug)]
+pub enum CollectionError {
+    #[error("Collection not found: {0}")]
+    CollectionNotFound(i64),
+
+    #[error("Case study not found: {0}")]
+    CaseStudyNotFound(String),
+
+    #[error("Case study {case_study_id} is already in collection {collection_id}")]
+    AlreadyMember { collection_id: i64, case_study_id: String },
+
+    #[error("Invalid collection order: {0}")]
+    InvalidOrder(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+pub type Result<T> = std::result::Result<T, CollectionError>;
+
+/// Repository for collection membership. `order_index` is kept contiguous from 0.
+pub struct CollectionRepository {
+    pool: SqlitePool,
+}
+
+impl CollectionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Append a case study to the end of a collection
+    pub async fn add_case_study(&self, collection_id: i64, case_study_id: &str) -> Result<Vec<CollectionMember>> {
+        let mut tx = self.pool.begin().await?;
+        ensure_collection_exists(&mut tx, collection_id).await?;
+
+        let case_study_exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM case_studies WHERE id = ? AND deleted_at IS NULL)"
+        )
+        .bind(case_study_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if !case_study_exists {
+            return Err(CollectionError::CaseStudyNotFound(case_study_id.to_string()));
+        }
+
+        let members = member_ids(&mut tx, collection_id).await?;
+        if members.iter().any(|id| id == case_study_id) {
+            return Err(CollectionError::AlreadyMember {
+                collection_id,
+                case_study_id: case_study_id.to_string(),
+            });
+        }
+
+        sqlx::query(
+            "INSERT INTO collection_case_studies (collection_id, case_study_id, order_index, added_at) VALUES (?, ?, ?, ?)"
+        )
+        .bind(collection_id)
+        .bind(case_study_id)
+        .bind(members.len() as i64)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.list_case_studies(collection_id).await
+    }
+
+    /// Remove a case study from a collection, closing the gap it leaves in the order.
+    /// Returns false if it wasn't a member.
+    pub async fn remove_case_study(&self, collection_id: i64, case_study_id: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        ensure_collection_exists(&mut tx, collection_id).await?;
+
+        let removed = sqlx::query("DELETE FROM collection_case_studies WHERE collection_id = ? AND case_study_id = ?")
+            .bind(collection_id)
+            .bind(case_study_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() > 0;
+
+        if removed {
+            let remaining = member_ids(&mut tx, collection_id).await?;
+            write_order(&mut tx, collection_id, &remaining).await?;
+        }
+
+        tx.commit().await?;
+        Ok(removed)
+    }
+
+    /// Reorder a collection. `ordered_ids` must list every current member exactly once.
+    pub async fn reorder(&self, collection_id: i64, ordered_ids: &[String]) -> Result<Vec<CollectionMember>> {
+        let mut tx = self.pool.begin().await?;
+        ensure_collection_exists(&mut tx, collection_id).await?;
+
+        let current: HashSet<String> = member_ids(&mut tx, collection_id).await?.into_iter().collect();
+        let mut seen = HashSet::new();
+        for id in ordered_ids {
+            if !current.contains(id) {
+                return Err(CollectionError::InvalidOrder(format!("{} is not in collection {}", id, collection_id)));
+            }
+            if !seen.insert(id) {
+                return Err(CollectionError::InvalidOrder(format!("{} is listed more than once", id)));
+            }
+        }
+        if seen.len() != current.len() {
+            let missing: Vec<&str> = current.iter().filter(|id| !seen.contains(id)).map(String::as_str).collect();
+            return Err(CollectionError::InvalidOrder(format!("missing {}", missing.join(", "))));
+        }
+
+        write_order(&mut tx, collection_id, ordered_ids).await?;
+        tx.commit().await?;
+        self.list_case_studies(collection_id).await
+    }
+
+    /// Members of a collection in order, skipping soft-deleted case studies
+    pub async fn list_case_studies(&self, collection_id: i64) -> Result<Vec<CollectionMember>> {
+        let mut conn = self.pool.acquire().await?;
+        ensure_collection_exists(&mut conn, collection_id).await?;
+
+        let members = sqlx::query_as::<_, CollectionMember>(
+            r#"
+            SELECT ccs.collection_id, ccs.case_study_id, cs.title, cs.status, ccs.order_index, ccs.added_at
+            FROM collection_case_studies ccs
+            JOIN case_studies cs ON cs.id = ccs.case_study_id
+            WHERE ccs.collection_id = ? AND cs.deleted_at IS NULL
+            ORDER BY ccs.order_index
+            "#
+        )
+        .bind(collection_id)
+        .fetch_all(&mut *conn)
+        .await?;
+        Ok(members)
+    }
+}
+
+async fn ensure_collection_exists(conn: &mut SqliteConnection, collection_id: i64) -> Result<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM collections WHERE id = ?)")
+        .bind(collection_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    if exists {
+        Ok(())
+    } else {
+        Err(CollectionError::CollectionNotFound(collection_id))
+    }
+}
+
+/// Renumber a collection's members from 0 after members were removed outside this repository
+pub(crate) async fn compact_order(conn: &mut SqliteConnection, collection_id: i64) -> Result<()> {
+    let remaining = member_ids(conn, collection_id).await?;
+    write_order(conn, collection_id, &remaining).await
+}
+
+/// Current member ids in order; ties (e.g. from older data) fall back to insertion time
+async fn member_ids(conn: &mut SqliteConnection, collection_id: i64) -> Result<Vec<String>> {
+    let rows = sqlx::query(
+        "SELECT case_study_id FROM collection_case_studies WHERE collection_id = ? ORDER BY order_index, added_at"
+    )
+    .bind(collection_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    rows.iter()
+        .map(|row| row.try_get::<String, _>("case_study_id").map_err(CollectionError::from))
+        .collect()
+}
+
+/// Set `order_index` to each member's position in `ordered_ids`
+async fn write_order(conn: &mut SqliteConnection, collection_id: i64, ordered_ids: &[String]) -> Result<()> {
+    for (index, case_study_id) in ordered_ids.iter().enumerate() {
+        sqlx::query("UPDATE collection_case_studies SET order_index = ? WHERE collection_id = ? AND case_study_id = ?")
+            .bind(index as i64)
+            .bind(collection_id)
+            .bind(case_study_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseManager;
+
+    async fn setup(case_studies: &[&str]) -> (CollectionRepository, i64) {
+        let database = DatabaseManager::in_memory().await.unwrap();
+        let pool = database.pool().clone();
+
+        let user_id = sqlx::query("INSERT INTO users (username) VALUES ('instructor')")
+            .execute(&pool).await.unwrap().last_insert_rowid();
+        let collection_id = sqlx::query("INSERT INTO collections (name, created_by) VALUES ('Strategy', ?)")
+            .bind(user_id)
+            .execute(&pool).await.unwrap().last_insert_rowid();
+        for id in case_studies {
+            sqlx::query(
+                "INSERT INTO case_studies (id, title, content, industry, difficulty_level, duration_minutes, learning_objectives) \
+                 VALUES (?, ?, 'Content', 'Retail', 'beginner', 30, '[]')"
+            )
+            .bind(id)
+            .bind(format!("Case {}", id))
+            .execute(&pool).await.unwrap();
+        }
+
+        (CollectionRepository::new(pool), collection_id)
+    }
+
+    fn order(members: &[CollectionMember]) -> Vec<(&str, i64)> {
+        members.iter().map(|m| (m.case_study_id.as_str(), m.order_index)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_members() {
+        let (repository, collection_id) = setup(&["a", "b"]).await;
+
+        repository.add_case_study(collection_id, "a").await.unwrap();
+        let members = repository.add_case_study(collection_id, "b").await.unwrap();
+        assert_eq!(order(&members), vec![("a", 0), ("b", 1)]);
+        assert_eq!(members[0].title, "Case a");
+
+        let duplicate = repository.add_case_study(collection_id, "a").await.unwrap_err();
+        assert!(matches!(duplicate, CollectionError::AlreadyMember { .. }));
+        assert!(matches!(
+            repository.add_case_study(collection_id, "missing").await.unwrap_err(),
+            CollectionError::CaseStudyNotFound(_)
+        ));
+        assert!(matches!(
+            repository.add_case_study(collection_id + 1, "a").await.unwrap_err(),
+            CollectionError::CollectionNotFound(_)
+        ));
+
+        assert!(repository.remove_case_study(collection_id, "a").await.unwrap());
+        assert!(!repository.remove_case_study(collection_id, "a").await.unwrap());
+        let members = repository.list_case_studies(collection_id).await.unwrap();
+        assert_eq!(order(&members), vec![("b", 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_full_reorder() {
+        let (repository, collection_id) = setup(&["a", "b", "c"]).await;
+        for id in ["a", "b", "c"] {
+            repository.add_case_study(collection_id, id).await.unwrap();
+        }
+
+        let ordered = vec!["c".to_string(), "a".to_string(), "b".to_string()];
+        let members = repository.reorder(collection_id, &ordered).await.unwrap();
+        assert_eq!(order(&members), vec![("c", 0), ("a", 1), ("b", 2)]);
+
+        let incomplete = vec!["c".to_string(), "a".to_string()];
+        assert!(matches!(repository.reorder(collection_id, &incomplete).await, Err(CollectionError::InvalidOrder(_))));
+        let repeated = vec!["c".to_string(), "a".to_string(), "a".to_string()];
+        assert!(matches!(repository.reorder(collection_id, &repeated).await, Err(CollectionError::InvalidOrder(_))));
+        let unknown = vec!["c".to_string(), "a".to_string(), "z".to_string()];
+        assert!(matches!(repository.reorder(collection_id, &unknown).await, Err(CollectionError::InvalidOrder(_))));
+
+        // Rejected reorders leave the order untouched
+        let members = repository.list_case_studies(collection_id).await.unwrap();
+        assert_eq!(order(&members), vec![("c", 0), ("a", 1), ("b", 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_removal_compacts_indices() {
+        let (repository, collection_id) = setup(&["a", "b", "c", "d"]).await;
+        for id in ["a", "b", "c", "d"] {
+            repository.add_case_study(collection_id, id).await.unwrap();
+        }
+
+        repository.remove_case_study(collection_id, "b").await.unwrap();
+        let members = repository.list_case_studies(collection_id).await.unwrap();
+        assert_eq!(order(&members), vec![("a", 0), ("c", 1), ("d", 2)]);
+
+        // New members go after the compacted tail
+        repository.remove_case_study(collection_id, "a").await.unwrap();
+        let members = repository.add_case_study(collection_id, "b").await.unwrap();
+        assert_eq!(order(&members), vec![("c", 0), ("d", 1), ("b", 2)]);
+    }
+}
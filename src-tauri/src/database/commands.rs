@@ -0,0 +1,261 @@
+// Tauri commands for schema migrations and database maintenance
+
+use super::audit::{AuditEntry, AuditLogger};
+use super::collection_repository::CollectionRepository;
+use super::migrations::{AppliedMigration, ChecksumMismatch, MigrationManager, MigrationStatus};
+use super::{
+    CollectionMember, DatabaseManager, Domain, DomainDeleteMode, DomainRepository, DomainUsage,
+    GenerationHistoryRepository, HistoryFilter, NewDomain, UpdateDomain,
+};
+use crate::case_study::commands::CaseStudyManagerState;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+type DatabaseManagerState = Arc<DatabaseManager>;
+
+async fn migration_manager(database_manager: &DatabaseManager) -> Result<MigrationManager, String> {
+    let manager = MigrationManager::new(database_manager.pool().clone());
+    manager.initialize().await.map_err(|e| e.to_string())?;
+    Ok(manager)
+}
+
+/// Roll back one applied migration. Nothing is changed unless `confirm` is true, and
+/// migrations that other applied migrations depend on are refused.
+#[tauri::command]
+pub async fn rollback_migration(
+    version: String,
+    confirm: bool,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<String, String> {
+    if !confirm {
+        return Err(format!("Rolling back migration {} must be confirmed", version));
+    }
+
+    let manager = migration_manager(database_manager_state.inner()).await?;
+    manager.rollback_migration(&version).await.map_err(|e| e.to_string())?;
+    Ok(format!("Rolled back migration {}", version))
+}
+
+/// Get applied and pending migrations
+#[tauri::command]
+pub async fn get_migration_status(
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<MigrationStatus, String> {
+    let manager = migration_manager(database_manager_state.inner()).await?;
+    manager.get_status().await.map_err(|e| e.to_string())
+}
+
+/// List migrations recorded as applied
+#[tauri::command]
+pub async fn list_applied_migrations(
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<AppliedMigration>, String> {
+    let manager = migration_manager(database_manager_state.inner()).await?;
+    manager.get_applied_migrations().await.map_err(|e| e.to_string())
+}
+
+/// Report applied migrations whose SQL has changed since they were applied
+#[tauri::command]
+pub async fn verify_migration_checksums(
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<ChecksumMismatch>, String> {
+    let manager = migration_manager(database_manager_state.inner()).await?;
+    manager.verify_checksums().await.map_err(|e| e.to_string())
+}
+
+/// Permanently remove records soft-deleted more than `days` days ago
+#[tauri::command]
+pub async fn purge_deleted_records(
+    days: u32,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<HashMap<String, u64>, String> {
+    database_manager_state.purge_deleted_older_than(days).await.map_err(|e| e.to_string())
+}
+
+/// Audit trail for one entity, oldest change first
+#[tauri::command]
+pub async fn get_audit_log(
+    entity_type: String,
+    entity_id: String,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<AuditEntry>, String> {
+    let logger = AuditLogger::new(database_manager_state.as_ref().clone());
+    logger.get_entity_log(&entity_type, &entity_id).await.map_err(|e| e.to_string())
+}
+
+/// Changes made by a user, most recent first
+#[tauri::command]
+pub async fn get_user_activity(
+    user_id: String,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<AuditEntry>, String> {
+    let logger = AuditLogger::new(database_manager_state.as_ref().clone());
+    logger
+        .get_user_activity(&user_id, limit.unwrap_or(50), offset.unwrap_or(0))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn collection_repository(database_manager: &DatabaseManager) -> CollectionRepository {
+    CollectionRepository::new(database_manager.pool().clone())
+}
+
+/// Append a case study to a collection, returning the collection's members in order
+#[tauri::command]
+pub async fn add_to_collection(
+    collection_id: i64,
+    case_study_id: String,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<CollectionMember>, String> {
+    collection_repository(database_manager_state.inner())
+        .add_case_study(collection_id, &case_study_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a case study from a collection; returns false if it wasn't a member
+#[tauri::command]
+pub async fn remove_from_collection(
+    collection_id: i64,
+    case_study_id: String,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<bool, String> {
+    collection_repository(database_manager_state.inner())
+        .remove_case_study(collection_id, &case_study_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Reorder a collection; `ordered_ids` must list every member exactly once
+#[tauri::command]
+pub async fn reorder_collection(
+    collection_id: i64,
+    ordered_ids: Vec<String>,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<CollectionMember>, String> {
+    collection_repository(database_manager_state.inner())
+        .reorder(collection_id, &ordered_ids)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Case studies in a collection, in order
+#[tauri::command]
+pub async fn list_collection_case_studies(
+    collection_id: i64,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<CollectionMember>, String> {
+    collection_repository(database_manager_state.inner())
+        .list_case_studies(collection_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export generation history matching `filter` to `file_path` as JSON Lines, returning
+/// the number of records written
+#[tauri::command]
+pub async fn export_generation_history(
+    file_path: String,
+    filter: Option<HistoryFilter>,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<usize, String> {
+    let file = tokio::fs::File::create(&file_path).await.map_err(|e| e.to_string())?;
+    let mut writer = tokio::io::BufWriter::new(file);
+    GenerationHistoryRepository::new(database_manager_state.pool().clone())
+        .export_jsonl(&filter.unwrap_or_default(), &mut writer)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Import a JSON Lines generation history export, updating records that already exist.
+/// Returns the number of records imported.
+#[tauri::command]
+pub async fn import_generation_history(
+    file_path: String,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<usize, String> {
+    let file = tokio::fs::File::open(&file_path).await.map_err(|e| e.to_string())?;
+    GenerationHistoryRepository::new(database_manager_state.pool().clone())
+        .import_jsonl(tokio::io::BufReader::new(file))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn domain_repository(database_manager: &DatabaseManager) -> DomainRepository {
+    DomainRepository::new(database_manager.pool().clone())
+}
+
+/// Create a domain. The name must be unique and the color, if given, a hex code.
+#[tauri::command]
+pub async fn create_domain(
+    domain: NewDomain,
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Domain, String> {
+    domain_repository(database_manager_state.inner())
+        .create(domain)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Update a domain's name, description, color or icon. A rename moves the domain's
+/// case studies to the new name.
+#[tauri::command]
+pub async fn update_domain(
+    id: i64,
+    update: UpdateDomain,
+    database_manager_state: State<'_, DatabaseManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+) -> Result<Domain, String> {
+    let domain = domain_repository(database_manager_state.inner())
+        .update(id, update)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(manager) = case_study_state.read().await.as_ref() {
+        manager.invalidate_statistics();
+    }
+    Ok(domain)
+}
+
+/// Delete a domain. Domains still used by case studies are refused unless `mode` is
+/// `cascade`, which moves those case studies to the trash.
+#[tauri::command]
+pub async fn delete_domain(
+    id: i64,
+    mode: Option<DomainDeleteMode>,
+    database_manager_state: State<'_, DatabaseManagerState>,
+    case_study_state: State<'_, CaseStudyManagerState>,
+) -> Result<(), String> {
+    domain_repository(database_manager_state.inner())
+        .delete(id, mode.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(manager) = case_study_state.read().await.as_ref() {
+        manager.invalidate_statistics();
+    }
+    Ok(())
+}
+
+/// List all domains ordered by name
+#[tauri::command]
+pub async fn list_domains(
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<Domain>, String> {
+    domain_repository(database_manager_state.inner())
+        .list_all()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// How many case studies use each domain
+#[tauri::command]
+pub async fn get_domain_usage(
+    database_manager_state: State<'_, DatabaseManagerState>,
+) -> Result<Vec<DomainUsage>, String> {
+    domain_repository(database_manager_state.inner())
+        .list_usage()
+        .await
+        .map_err(|e| e.to_string())
+}
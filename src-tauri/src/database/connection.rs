@@ -1,4 +1,7 @@
-use sqlx::{sqlite::SqliteConnectOptions, SqlitePool, Transaction, Sqlite};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::{SqlitePool, Transaction, Sqlite};
+use chrono::Utc;
+use std::collections::HashMap;
 use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
@@ -6,6 +9,30 @@ use tauri::AppHandle;
 
 use super::migrations::{MigrationManager, MigrationError};
 
+/// Tables whose deletes set `deleted_at` instead of removing the row
+pub const SOFT_DELETE_TABLES: &[&str] = &["case_studies", "assessment_workflows", "configurations"];
+
+/// Connection pool and SQLite settings applied to every pooled connection
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub max_connections: u32,
+    pub busy_timeout_ms: u64,
+    /// WAL by default, so readers don't block behind a writer
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            busy_timeout_ms: 30_000,
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Normal,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DatabaseManager {
     pool: SqlitePool,
@@ -15,7 +42,11 @@ impl DatabaseManager {
     /// Create a new database manager with connection to SQLite database
     pub async fn new(app_handle: &AppHandle) -> Result<Self, sqlx::Error> {
         let db_path = Self::get_database_path(app_handle)?;
-        
+        Self::connect(&db_path, &DatabaseConfig::default()).await
+    }
+
+    /// Open (creating if missing) and migrate the database at `db_path` using `config`
+    pub async fn connect(db_path: &Path, config: &DatabaseConfig) -> Result<Self, sqlx::Error> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)
@@ -25,16 +56,19 @@ impl DatabaseManager {
         // Create connection options with optimized settings
         let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", db_path.display()))?
             .create_if_missing(true)
-            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .journal_mode(config.journal_mode)
             .foreign_keys(true)
-            .busy_timeout(Duration::from_secs(30))
-            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(Duration::from_millis(config.busy_timeout_ms))
+            .synchronous(config.synchronous)
             .pragma("cache_size", "10000")
             .pragma("temp_store", "memory")
             .pragma("mmap_size", "268435456"); // 256MB
 
         // Create connection pool with optimized settings
-        let pool = SqlitePool::connect_with(options).await?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .connect_with(options)
+            .await?;
         
         // Run any pending migrations
         let migration_manager = MigrationManager::new(pool.clone());
@@ -44,9 +78,45 @@ impl DatabaseManager {
         migration_manager.migrate().await
             .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
 
+        // Edited migration SQL is reported but does not block startup
+        match migration_manager.verify_checksums().await {
+            Ok(mismatches) => {
+                for mismatch in mismatches {
+                    tracing::warn!(
+                        "Migration {} - {} has changed since it was applied (recorded checksum {}, current {})",
+                        mismatch.version, mismatch.name, mismatch.recorded, mismatch.expected
+                    );
+                }
+            }
+            Err(e) => tracing::warn!("Failed to verify migration checksums: {}", e),
+        }
+
+        Ok(Self { pool })
+    }
+
+    /// Create a migrated in-memory database for tests
+    #[cfg(test)]
+    pub(crate) async fn in_memory() -> Result<Self, sqlx::Error> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+
+        let migration_manager = MigrationManager::new(pool.clone());
+        migration_manager.initialize().await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        migration_manager.migrate().await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
         Ok(Self { pool })
     }
 
+    /// Wrap an existing pool without running migrations, for tests that need a specific schema
+    #[cfg(test)]
+    pub(crate) fn from_pool(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
     /// Get reference to the connection pool
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
@@ -120,11 +190,14 @@ impl DatabaseManager {
 
     /// Get connection pool statistics
     pub fn pool_stats(&self) -> PoolStats {
+        let connections = self.pool.size();
+        let idle_connections = self.pool.num_idle() as u32;
+
         PoolStats {
-            connections: self.pool.size() as u32,
-            idle_connections: self.pool.num_idle() as u32,
-            // SQLx doesn't expose max_connections directly, but we can set a reasonable default
-            max_connections: 10, // Default SQLite pool size
+            connections,
+            idle_connections,
+            in_use_connections: connections.saturating_sub(idle_connections),
+            max_connections: self.pool.options().get_max_connections(),
         }
     }
 
@@ -133,6 +206,17 @@ impl DatabaseManager {
         self.pool.close().await;
     }
 
+    /// Fold the WAL back into the main database file, then close the pool. Safe to call again
+    /// once closed.
+    pub async fn checkpoint_and_close(&self) -> Result<(), sqlx::Error> {
+        if self.pool.is_closed() {
+            return Ok(());
+        }
+        let checkpoint = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await;
+        self.pool.close().await;
+        checkpoint.map(|_| ())
+    }
+
     /// Check if pool is closed
     pub fn is_closed(&self) -> bool {
         self.pool.is_closed()
@@ -183,10 +267,31 @@ impl DatabaseManager {
         // Analyze tables for query optimization
         sqlx::query("ANALYZE").execute(&self.pool).await?;
         
-        println!("Database maintenance completed");
+        tracing::info!("Database maintenance completed");
         Ok(())
     }
 
+    /// Permanently remove rows soft-deleted more than `days` days ago, returning the
+    /// number purged per table
+    pub async fn purge_deleted_older_than(&self, days: u32) -> Result<HashMap<String, u64>, sqlx::Error> {
+        let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+        let mut tx = self.pool.begin().await?;
+        let mut purged = HashMap::new();
+
+        for table in SOFT_DELETE_TABLES {
+            let result = sqlx::query(&format!(
+                "DELETE FROM {} WHERE deleted_at IS NOT NULL AND deleted_at < ?", table
+            ))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?;
+            purged.insert(table.to_string(), result.rows_affected());
+        }
+
+        tx.commit().await?;
+        Ok(purged)
+    }
+
     /// Create a backup of the database
     pub async fn backup(&self, app_handle: &AppHandle, backup_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         let source_path = Self::get_database_path(app_handle)?;
@@ -199,7 +304,7 @@ impl DatabaseManager {
         // Copy the database file
         std::fs::copy(&source_path, backup_path)?;
         
-        println!("Database backup created: {}", backup_path.display());
+        tracing::info!("Database backup created: {}", backup_path.display());
         Ok(())
     }
 
@@ -218,7 +323,7 @@ impl DatabaseManager {
         // Copy backup over current database
         std::fs::copy(backup_path, &target_path)?;
         
-        println!("Database restored from: {}", backup_path.display());
+        tracing::info!("Database restored from: {}", backup_path.display());
         Ok(())
     }
 
@@ -267,6 +372,57 @@ pub struct DatabaseStats {
 pub struct PoolStats {
     pub connections: u32,
     pub idle_connections: u32,
+    pub in_use_connections: u32,
     pub max_connections: u32,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_custom_config_applied_to_connections() {
+        let dir = std::env::temp_dir().join(format!("case-crafter-db-{}", uuid::Uuid::new_v4()));
+        let config = DatabaseConfig {
+            max_connections: 3,
+            busy_timeout_ms: 1234,
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Full,
+        };
+
+        let manager = DatabaseManager::connect(&dir.join("test.db"), &config).await.unwrap();
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(manager.pool()).await.unwrap();
+        let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout").fetch_one(manager.pool()).await.unwrap();
+        let synchronous: i64 = sqlx::query_scalar("PRAGMA synchronous").fetch_one(manager.pool()).await.unwrap();
+        let stats = manager.pool_stats();
+
+        manager.close_pool().await;
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(journal_mode, "wal");
+        assert_eq!(busy_timeout, 1234);
+        assert_eq!(synchronous, 2); // FULL
+        assert_eq!(stats.max_connections, 3);
+        assert!(stats.connections >= 1);
+        assert_eq!(stats.in_use_connections, stats.connections - stats.idle_connections);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_and_close_empties_wal() {
+        let dir = std::env::temp_dir().join(format!("case-crafter-db-{}", uuid::Uuid::new_v4()));
+        let db_path = dir.join("test.db");
+        let manager = DatabaseManager::connect(&db_path, &DatabaseConfig::default()).await.unwrap();
+        sqlx::query("INSERT INTO users (username) VALUES ('instructor')").execute(manager.pool()).await.unwrap();
+
+        manager.checkpoint_and_close().await.unwrap();
+        assert!(manager.is_closed());
+        let wal = dir.join("test.db-wal");
+        assert!(!wal.exists() || std::fs::metadata(&wal).unwrap().len() == 0);
+
+        // A second shutdown is a no-op
+        manager.checkpoint_and_close().await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
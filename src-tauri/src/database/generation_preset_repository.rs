@@ -0,0 +1,319 @@
+// Repository for named case study generation parameters saved per user
+
+use crate::ai::{case_study_param_errors, CaseStudyGenerationParams};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+/// Errors from generation preset operations
+#[derive(thiserror::Error, Debug)]
+pub enum GenerationPresetError {
+    #[error("Generation preset not found: {0}")]
+    NotFound(i64),
+
+    #[error("A generation preset named '{0}' already exists")]
+    DuplicateName(String),
+
+    #[error("Invalid generation preset: {}", .0.join("; "))]
+    InvalidParams(Vec<String>),
+
+    #[error("Invalid preset overrides: {0}")]
+    InvalidOverrides(String),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+pub type Result<T> = std::result::Result<T, GenerationPresetError>;
+
+/// A user's saved case study generation parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationPreset {
+    pub id: i64,
+    pub user_id: String,
+    pub name: String,
+    pub params: CaseStudyGenerationParams,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GenerationPreset {
+    /// The preset's params with `overrides` (a partial params object) applied field by field
+    pub fn params_with_overrides(&self, overrides: Option<&serde_json::Value>) -> Result<CaseStudyGenerationParams> {
+        let Some(overrides) = overrides else {
+            return Ok(self.params.clone());
+        };
+        let overrides = overrides
+            .as_object()
+            .ok_or_else(|| GenerationPresetError::InvalidOverrides("expected an object of parameter fields".to_string()))?;
+
+        let mut merged = serde_json::to_value(&self.params)?;
+        if let Some(fields) = merged.as_object_mut() {
+            for (key, value) in overrides {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+
+        let params: CaseStudyGenerationParams = serde_json::from_value(merged)
+            .map_err(|e| GenerationPresetError::InvalidOverrides(e.to_string()))?;
+        validate(&params)?;
+        Ok(params)
+    }
+}
+
+/// Changes to a saved preset; unset fields are left as they are
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateGenerationPreset {
+    pub name: Option<String>,
+    pub params: Option<CaseStudyGenerationParams>,
+}
+
+/// Repository for generation presets. Names are unique per user.
+pub struct GenerationPresetRepository {
+    pool: SqlitePool,
+}
+
+impl GenerationPresetRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new preset, rejecting params that fail case study validation
+    pub async fn save(&self, user_id: &str, name: &str, params: &CaseStudyGenerationParams) -> Result<GenerationPreset> {
+        let name = validate_name(name)?;
+        validate(params)?;
+        self.ensure_name_available(user_id, &name, None).await?;
+
+        let now = Utc::now();
+        let id = sqlx::query(
+            "INSERT INTO generation_presets (user_id, name, params, created_at, updated_at) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(&name)
+        .bind(serde_json::to_string(params)?)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?
+        .last_insert_rowid();
+
+        self.get(id).await
+    }
+
+    /// A user's presets, by name
+    pub async fn list_for_user(&self, user_id: &str) -> Result<Vec<GenerationPreset>> {
+        let rows = sqlx::query("SELECT * FROM generation_presets WHERE user_id = ? ORDER BY name")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(preset_from_row).collect()
+    }
+
+    pub async fn find_by_id(&self, preset_id: i64) -> Result<Option<GenerationPreset>> {
+        let row = sqlx::query("SELECT * FROM generation_presets WHERE id = ?")
+            .bind(preset_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(preset_from_row).transpose()
+    }
+
+    /// A preset by id, failing with `NotFound` if it doesn't exist
+    pub async fn get(&self, preset_id: i64) -> Result<GenerationPreset> {
+        self.find_by_id(preset_id).await?.ok_or(GenerationPresetError::NotFound(preset_id))
+    }
+
+    /// Rename a preset and/or replace its params, validating them as on save
+    pub async fn update(&self, preset_id: i64, update: &UpdateGenerationPreset) -> Result<GenerationPreset> {
+        let mut preset = self.get(preset_id).await?;
+
+        if let Some(name) = &update.name {
+            let name = validate_name(name)?;
+            self.ensure_name_available(&preset.user_id, &name, Some(preset_id)).await?;
+            preset.name = name;
+        }
+        if let Some(params) = &update.params {
+            validate(params)?;
+            preset.params = params.clone();
+        }
+
+        sqlx::query("UPDATE generation_presets SET name = ?, params = ?, updated_at = ? WHERE id = ?")
+            .bind(&preset.name)
+            .bind(serde_json::to_string(&preset.params)?)
+            .bind(Utc::now())
+            .bind(preset_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get(preset_id).await
+    }
+
+    /// Delete a preset; returns false if it didn't exist
+    pub async fn delete(&self, preset_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM generation_presets WHERE id = ?")
+            .bind(preset_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn ensure_name_available(&self, user_id: &str, name: &str, except_id: Option<i64>) -> Result<()> {
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM generation_presets WHERE user_id = ? AND name = ? AND id != ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(except_id.unwrap_or(-1))
+        .fetch_one(&self.pool)
+        .await?;
+        if taken {
+            Err(GenerationPresetError::DuplicateName(name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn validate(params: &CaseStudyGenerationParams) -> Result<()> {
+    let errors = case_study_param_errors(params);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(GenerationPresetError::InvalidParams(errors))
+    }
+}
+
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(GenerationPresetError::InvalidParams(vec!["Preset name cannot be empty".to_string()]));
+    }
+    if name.len() > 100 {
+        return Err(GenerationPresetError::InvalidParams(vec!["Preset name too long (max 100 characters)".to_string()]));
+    }
+    Ok(name.to_string())
+}
+
+fn preset_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<GenerationPreset> {
+    let params: String = row.try_get("params")?;
+    Ok(GenerationPreset {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        name: row.try_get("name")?,
+        params: serde_json::from_str(&params)?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{CompanySize, GenerationStyle};
+    use crate::database::DatabaseManager;
+
+    async fn setup() -> GenerationPresetRepository {
+        let database = DatabaseManager::in_memory().await.unwrap();
+        GenerationPresetRepository::new(database.pool().clone())
+    }
+
+    fn params() -> CaseStudyGenerationParams {
+        CaseStudyGenerationParams {
+            industry: "Healthcare".to_string(),
+            target_length: 1200,
+            style: GenerationStyle::Precise,
+            ..CaseStudyGenerationParams::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_load_update_and_delete() {
+        let repository = setup().await;
+
+        let saved = repository.save("alice", " Hospital ops ", &params()).await.unwrap();
+        assert_eq!(saved.name, "Hospital ops");
+        repository.save("alice", "Another", &CaseStudyGenerationParams::default()).await.unwrap();
+        repository.save("bob", "Hospital ops", &params()).await.unwrap();
+
+        let presets = repository.list_for_user("alice").await.unwrap();
+        let names: Vec<&str> = presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Another", "Hospital ops"]);
+
+        let loaded = repository.get(saved.id).await.unwrap();
+        assert_eq!(loaded.params.industry, "Healthcare");
+        assert_eq!(loaded.params.target_length, 1200);
+        assert_eq!(loaded.params.style, GenerationStyle::Precise);
+
+        assert!(matches!(
+            repository.save("alice", "Hospital ops", &params()).await,
+            Err(GenerationPresetError::DuplicateName(_))
+        ));
+        let rename = UpdateGenerationPreset { name: Some("Another".to_string()), params: None };
+        assert!(matches!(repository.update(saved.id, &rename).await, Err(GenerationPresetError::DuplicateName(_))));
+
+        let update = UpdateGenerationPreset {
+            name: Some("Clinic ops".to_string()),
+            params: Some(CaseStudyGenerationParams { target_length: 900, ..params() }),
+        };
+        let updated = repository.update(saved.id, &update).await.unwrap();
+        assert_eq!(updated.name, "Clinic ops");
+        assert_eq!(updated.params.target_length, 900);
+        assert_eq!(updated.created_at, saved.created_at);
+
+        assert!(repository.delete(saved.id).await.unwrap());
+        assert!(!repository.delete(saved.id).await.unwrap());
+        assert!(matches!(repository.get(saved.id).await, Err(GenerationPresetError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_overrides_apply_on_top_of_preset() {
+        let repository = setup().await;
+        let preset = repository.save("alice", "Hospital ops", &params()).await.unwrap();
+
+        assert_eq!(preset.params_with_overrides(None).unwrap().target_length, 1200);
+
+        let overrides = serde_json::json!({ "target_length": 600, "company_size": "startup" });
+        let applied = preset.params_with_overrides(Some(&overrides)).unwrap();
+        assert_eq!(applied.target_length, 600);
+        assert!(matches!(applied.company_size, CompanySize::Startup));
+        // Fields not overridden keep the preset's values
+        assert_eq!(applied.industry, "Healthcare");
+        assert_eq!(applied.style, GenerationStyle::Precise);
+
+        // Overrides are validated like the preset itself
+        let too_short = serde_json::json!({ "target_length": 50 });
+        assert!(matches!(preset.params_with_overrides(Some(&too_short)), Err(GenerationPresetError::InvalidParams(_))));
+        let wrong_type = serde_json::json!({ "target_length": "long" });
+        assert!(matches!(preset.params_with_overrides(Some(&wrong_type)), Err(GenerationPresetError::InvalidOverrides(_))));
+        assert!(matches!(
+            preset.params_with_overrides(Some(&serde_json::json!([1, 2]))),
+            Err(GenerationPresetError::InvalidOverrides(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_preset_rejected_on_save() {
+        let repository = setup().await;
+
+        let invalid = CaseStudyGenerationParams {
+            industry: " ".to_string(),
+            learning_objectives: vec![],
+            ..CaseStudyGenerationParams::default()
+        };
+        match repository.save("alice", "Broken", &invalid).await {
+            Err(GenerationPresetError::InvalidParams(errors)) => {
+                assert_eq!(errors, case_study_param_errors(&invalid));
+                assert_eq!(errors.len(), 2);
+            }
+            other => panic!("expected invalid params, got {:?}", other),
+        }
+        assert!(repository.list_for_user("alice").await.unwrap().is_empty());
+
+        let preset = repository.save("alice", "Valid", &params()).await.unwrap();
+        let update = UpdateGenerationPreset { name: None, params: Some(invalid) };
+        assert!(matches!(repository.update(preset.id, &update).await, Err(GenerationPresetError::InvalidParams(_))));
+        assert_eq!(repository.get(preset.id).await.unwrap().params.industry, "Healthcare");
+    }
+}
@@ -16,6 +16,8 @@ pub enum MigrationError {
     InvalidVersion(String),
     #[error("Migration dependency not satisfied: {0} requires {1}")]
     DependencyNotSatisfied(String, String),
+    #[error("Migration {0} is still required by applied migration {1}")]
+    RequiredBy(String, String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,7 +31,7 @@ pub struct Migration {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppliedMigration {
     pub version: String,
     pub name: String,
@@ -37,6 +39,31 @@ pub struct AppliedMigration {
     pub checksum: String,
 }
 
+/// A registered migration that has not been applied yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMigration {
+    pub version: String,
+    pub name: String,
+    pub description: String,
+    pub dependencies: Vec<String>,
+}
+
+/// An applied migration whose recorded checksum no longer matches its registered SQL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumMismatch {
+    pub version: String,
+    pub name: String,
+    pub recorded: String,
+    pub expected: String,
+}
+
+/// Applied and pending migrations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub applied: Vec<AppliedMigration>,
+    pub pending: Vec<PendingMigration>,
+}
+
 pub struct MigrationManager {
     pool: SqlitePool,
     migrations: HashMap<String, Migration>,
@@ -142,8 +169,7 @@ impl MigrationManager {
             .execute(&mut *tx)
             .await?;
 
-        // Calculate checksum
-        let checksum = format!("{:x}", md5::compute(&migration.up_sql));
+        let checksum = Self::checksum(&migration.up_sql);
 
         // Record the migration
         sqlx::query(
@@ -159,10 +185,37 @@ impl MigrationManager {
         // Commit transaction
         tx.commit().await?;
 
-        println!("Applied migration: {} - {}", migration.version, migration.name);
+        tracing::info!("Applied migration: {} - {}", migration.version, migration.name);
         Ok(())
     }
 
+    fn checksum(up_sql: &str) -> String {
+        format!("{:x}", md5::compute(up_sql))
+    }
+
+    /// Compare each applied migration's recorded checksum with its registered `up_sql`.
+    /// Applied versions that are no longer registered are skipped.
+    pub async fn verify_checksums(&self) -> Result<Vec<ChecksumMismatch>, MigrationError> {
+        let mut mismatches: Vec<ChecksumMismatch> = self
+            .get_applied_migrations()
+            .await?
+            .into_iter()
+            .filter_map(|applied| {
+                let migration = self.migrations.get(&applied.version)?;
+                let expected = Self::checksum(&migration.up_sql);
+                (expected != applied.checksum).then(|| ChecksumMismatch {
+                    version: applied.version,
+                    name: applied.name,
+                    recorded: applied.checksum,
+                    expected,
+                })
+            })
+            .collect();
+
+        mismatches.sort_by(|a, b| a.version.cmp(&b.version));
+        Ok(mismatches)
+    }
+
     /// Apply all pending migrations
     pub async fn migrate(&self) -> Result<usize, MigrationError> {
         let pending = self.get_pending_migrations().await?;
@@ -173,9 +226,9 @@ impl MigrationManager {
         }
 
         if count > 0 {
-            println!("Applied {} migrations", count);
+            tracing::info!("Applied {} migrations", count);
         } else {
-            println!("No pending migrations");
+            tracing::info!("No pending migrations");
         }
 
         Ok(count)
@@ -194,6 +247,11 @@ impl MigrationManager {
             return Err(MigrationError::MigrationNotFound(version.to_string()));
         }
 
+        // Refuse while an applied migration still depends on this one
+        if let Some(dependent) = self.applied_dependents(version, &applied).first() {
+            return Err(MigrationError::RequiredBy(version.to_string(), dependent.clone()));
+        }
+
         // Start transaction
         let mut tx = self.pool.begin().await?;
 
@@ -211,10 +269,43 @@ impl MigrationManager {
         // Commit transaction
         tx.commit().await?;
 
-        println!("Rolled back migration: {} - {}", migration.version, migration.name);
+        tracing::info!("Rolled back migration: {} - {}", migration.version, migration.name);
         Ok(())
     }
 
+    /// Applied migrations that list `version` as a dependency, in version order
+    fn applied_dependents(&self, version: &str, applied: &[AppliedMigration]) -> Vec<String> {
+        let mut dependents: Vec<String> = applied
+            .iter()
+            .filter(|m| {
+                self.migrations
+                    .get(&m.version)
+                    .is_some_and(|migration| migration.dependencies.iter().any(|dep| dep == version))
+            })
+            .map(|m| m.version.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+
+    /// Applied and pending migrations
+    pub async fn get_status(&self) -> Result<MigrationStatus, MigrationError> {
+        let applied = self.get_applied_migrations().await?;
+        let pending = self
+            .get_pending_migrations()
+            .await?
+            .into_iter()
+            .map(|m| PendingMigration {
+                version: m.version.clone(),
+                name: m.name.clone(),
+                description: m.description.clone(),
+                dependencies: m.dependencies.clone(),
+            })
+            .collect();
+
+        Ok(MigrationStatus { applied, pending })
+    }
+
     /// Get migration status
     pub async fn status(&self) -> Result<(), MigrationError> {
         let applied = self.get_applied_migrations().await?;
@@ -570,6 +661,223 @@ impl MigrationManager {
         };
 
         self.register_migration(migration_012);
+
+        // Migration 013: Generation cost tracking
+        let migration_013 = Migration {
+            version: "013".to_string(),
+            name: "generation_cost_tracking".to_string(),
+            description: "Add estimated cost to generation history for usage reporting".to_string(),
+            up_sql: include_str!("migrations/013_generation_cost_tracking.sql").to_string(),
+            down_sql: r#"
+                -- Drop cost tracking column and index
+                DROP INDEX IF EXISTS idx_generation_history_provider;
+                ALTER TABLE generation_history DROP COLUMN estimated_cost;
+            "#.to_string(),
+            dependencies: vec!["001".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_013);
+
+        // Migration 014: Full-text search over case studies
+        let migration_014 = Migration {
+            version: "014".to_string(),
+            name: "case_study_fulltext_search".to_string(),
+            description: "Add FTS5 index over case study text with sync triggers".to_string(),
+            up_sql: include_str!("migrations/014_case_study_fulltext_search.sql").to_string(),
+            down_sql: r#"
+                -- Drop full-text search index and its triggers
+                DROP TRIGGER IF EXISTS case_studies_fts_insert;
+                DROP TRIGGER IF EXISTS case_studies_fts_update;
+                DROP TRIGGER IF EXISTS case_studies_fts_delete;
+                DROP TABLE IF EXISTS case_studies_fts;
+            "#.to_string(),
+            dependencies: vec!["010".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_014);
+
+        // Migration 015: Reusable question bank
+        let migration_015 = Migration {
+            version: "015".to_string(),
+            name: "question_bank".to_string(),
+            description: "Create question bank for questions reusable across assessments".to_string(),
+            up_sql: include_str!("migrations/015_question_bank.sql").to_string(),
+            down_sql: r#"
+                -- Drop question bank table, indexes and trigger
+                DROP TRIGGER IF EXISTS update_question_bank_updated_at;
+                DROP INDEX IF EXISTS idx_question_bank_difficulty;
+                DROP INDEX IF EXISTS idx_question_bank_type;
+                DROP INDEX IF EXISTS idx_question_bank_source;
+                DROP TABLE IF EXISTS question_bank;
+            "#.to_string(),
+            dependencies: vec!["001".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_015);
+
+        // Migration 016: Soft delete timestamps
+        let migration_016 = Migration {
+            version: "016".to_string(),
+            name: "soft_delete".to_string(),
+            description: "Add deleted_at to case studies, assessment workflows and configurations".to_string(),
+            up_sql: include_str!("migrations/016_soft_delete.sql").to_string(),
+            down_sql: r#"
+                -- Drop soft delete indexes and columns
+                DROP INDEX IF EXISTS idx_case_studies_deleted_at;
+                DROP INDEX IF EXISTS idx_assessment_workflows_deleted_at;
+                DROP INDEX IF EXISTS idx_configurations_deleted_at;
+                UPDATE case_studies SET status = 'deleted' WHERE deleted_at IS NOT NULL;
+                UPDATE assessment_workflows SET status = 'deleted' WHERE deleted_at IS NOT NULL;
+                UPDATE configurations SET status = 'deleted' WHERE deleted_at IS NOT NULL;
+                ALTER TABLE case_studies DROP COLUMN deleted_at;
+                ALTER TABLE assessment_workflows DROP COLUMN deleted_at;
+                ALTER TABLE configurations DROP COLUMN deleted_at;
+            "#.to_string(),
+            dependencies: vec!["010".to_string(), "011".to_string(), "012".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_016);
+
+        // Migration 017: Audit log
+        let migration_017 = Migration {
+            version: "017".to_string(),
+            name: "audit_log".to_string(),
+            description: "Create append-only audit log of entity changes".to_string(),
+            up_sql: include_str!("migrations/017_audit_log.sql").to_string(),
+            down_sql: r#"
+                -- Drop audit log table, indexes and triggers
+                DROP TRIGGER IF EXISTS audit_log_no_update;
+                DROP TRIGGER IF EXISTS audit_log_no_delete;
+                DROP INDEX IF EXISTS idx_audit_log_entity;
+                DROP INDEX IF EXISTS idx_audit_log_user;
+                DROP TABLE IF EXISTS audit_log;
+            "#.to_string(),
+            dependencies: vec!["001".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_017);
+
+        // Migration 018: Form data schema version
+        let migration_018 = Migration {
+            version: "018".to_string(),
+            name: "form_data_schema_version".to_string(),
+            description: "Record the configuration schema version of stored form submissions".to_string(),
+            up_sql: include_str!("migrations/018_form_data_schema_version.sql").to_string(),
+            down_sql: r#"
+                -- Drop form data schema version
+                DROP INDEX IF EXISTS idx_form_submissions_schema_version;
+                ALTER TABLE form_submissions DROP COLUMN schema_version;
+            "#.to_string(),
+            dependencies: vec!["012".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_018);
+
+        // Migration 019: Configuration categories and frameworks
+        let migration_019 = Migration {
+            version: "019".to_string(),
+            name: "configuration_references".to_string(),
+            description: "Create category and framework tables referenced by configurations".to_string(),
+            up_sql: include_str!("migrations/019_configuration_references.sql").to_string(),
+            down_sql: r#"
+                -- Drop configuration reference tables
+                DROP TABLE IF EXISTS configuration_frameworks;
+                DROP TABLE IF EXISTS configuration_categories;
+            "#.to_string(),
+            dependencies: vec!["012".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_019);
+
+        // Migration 020: Case study tags
+        let migration_020 = Migration {
+            version: "020".to_string(),
+            name: "case_study_tags".to_string(),
+            description: "Store normalized tags on case studies".to_string(),
+            up_sql: include_str!("migrations/020_case_study_tags.sql").to_string(),
+            down_sql: r#"
+                -- Drop case study tags
+                ALTER TABLE case_studies DROP COLUMN tags;
+            "#.to_string(),
+            dependencies: vec!["010".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_020);
+
+        // Migration 021: Case study embeddings
+        let migration_021 = Migration {
+            version: "021".to_string(),
+            name: "case_study_embeddings".to_string(),
+            description: "Store case study embedding vectors for semantic search".to_string(),
+            up_sql: include_str!("migrations/021_case_study_embeddings.sql").to_string(),
+            down_sql: r#"
+                -- Drop case study embeddings
+                DROP TABLE IF EXISTS case_study_embeddings;
+            "#.to_string(),
+            dependencies: vec!["010".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_021);
+
+        // Migration 022: Case study idempotency keys
+        let migration_022 = Migration {
+            version: "022".to_string(),
+            name: "case_study_idempotency_keys".to_string(),
+            description: "Remember client request ids so retried case study creates aren't duplicated".to_string(),
+            up_sql: include_str!("migrations/022_case_study_idempotency_keys.sql").to_string(),
+            down_sql: r#"
+                -- Drop case study idempotency keys
+                DROP TABLE IF EXISTS case_study_idempotency_keys;
+            "#.to_string(),
+            dependencies: vec!["010".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_022);
+
+        // Migration 023: Indexes for common query paths
+        let migration_023 = Migration {
+            version: "023".to_string(),
+            name: "query_path_indexes".to_string(),
+            description: "Add composite indexes for case study, progress and question lookups".to_string(),
+            up_sql: include_str!("migrations/023_query_path_indexes.sql").to_string(),
+            down_sql: r#"
+                -- Drop query path indexes
+                DROP INDEX IF EXISTS idx_case_studies_status_deleted_at;
+                DROP INDEX IF EXISTS idx_case_studies_deleted_at_created_at;
+                DROP INDEX IF EXISTS idx_user_progress_user_case_study;
+                DROP INDEX IF EXISTS idx_assessment_questions_case_study_order;
+            "#.to_string(),
+            dependencies: vec!["001".to_string(), "016".to_string()],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_023);
+
+        // Migration 024: Generation presets
+        let migration_024 = Migration {
+            version: "024".to_string(),
+            name: "generation_presets".to_string(),
+            description: "Store named case study generation parameters per user".to_string(),
+            up_sql: include_str!("migrations/024_generation_presets.sql").to_string(),
+            down_sql: r#"
+                -- Drop generation presets
+                DROP TABLE IF EXISTS generation_presets;
+            "#.to_string(),
+            dependencies: vec![],
+            created_at: Utc::now(),
+        };
+
+        self.register_migration(migration_024);
     }
 }
 
@@ -625,4 +933,137 @@ mod tests {
         assert!(!migration.up_sql.is_empty());
         assert!(!migration.down_sql.is_empty());
     }
-}
\ No newline at end of file
+
+    /// A manager with only the given migrations registered, on a fresh in-memory database
+    async fn manager_with(migrations: Vec<Migration>) -> MigrationManager {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let mut manager = MigrationManager { pool, migrations: HashMap::new() };
+        for migration in migrations {
+            manager.register_migration(migration);
+        }
+        manager.initialize().await.unwrap();
+        manager
+    }
+
+    fn migration(version: &str, table: &str, dependencies: &[&str]) -> Migration {
+        Migration {
+            version: version.to_string(),
+            name: format!("create_{}", table),
+            description: format!("Create {}", table),
+            up_sql: format!("CREATE TABLE {} (id INTEGER PRIMARY KEY);", table),
+            down_sql: format!("DROP TABLE {};", table),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rollback_refused_while_dependent_applied() {
+        let manager = manager_with(vec![
+            migration("001", "parents", &[]),
+            migration("002", "children", &["001"]),
+        ]).await;
+        assert_eq!(manager.migrate().await.unwrap(), 2);
+
+        let refused = manager.rollback_migration("001").await;
+        assert!(matches!(refused, Err(MigrationError::RequiredBy(ref v, ref by)) if v == "001" && by == "002"));
+        assert_eq!(manager.get_applied_migrations().await.unwrap().len(), 2);
+
+        manager.rollback_migration("002").await.unwrap();
+        manager.rollback_migration("001").await.unwrap();
+        assert!(manager.get_applied_migrations().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_lists_rolled_back_migration_as_pending() {
+        let manager = manager_with(vec![
+            migration("001", "parents", &[]),
+            migration("002", "children", &["001"]),
+        ]).await;
+        manager.migrate().await.unwrap();
+
+        manager.rollback_migration("002").await.unwrap();
+
+        let status = manager.get_status().await.unwrap();
+        let applied: Vec<&str> = status.applied.iter().map(|m| m.version.as_str()).collect();
+        let pending: Vec<&str> = status.pending.iter().map(|m| m.version.as_str()).collect();
+        assert_eq!(applied, vec!["001"]);
+        assert_eq!(pending, vec!["002"]);
+        assert!(matches!(manager.rollback_migration("002").await, Err(MigrationError::MigrationNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_edited_migration_sql_is_reported() {
+        let mut manager = manager_with(vec![
+            migration("001", "parents", &[]),
+            migration("002", "children", &["001"]),
+        ]).await;
+        manager.migrate().await.unwrap();
+        assert!(manager.verify_checksums().await.unwrap().is_empty());
+
+        let mut edited = migration("002", "children", &["001"]);
+        edited.up_sql = "CREATE TABLE children (id INTEGER PRIMARY KEY, parent_id INTEGER);".to_string();
+        manager.register_migration(edited);
+
+        let mismatches = manager.verify_checksums().await.unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].version, "002");
+        assert_ne!(mismatches[0].recorded, mismatches[0].expected);
+    }
+
+    /// The `detail` lines of SQLite's query plan for `sql`
+    async fn query_plan(pool: &SqlitePool, sql: &str) -> String {
+        let rows = sqlx::query(&format!("EXPLAIN QUERY PLAN {}", sql)).fetch_all(pool).await.unwrap();
+        rows.iter().map(|row| row.get::<String, _>("detail")).collect::<Vec<_>>().join("\n")
+    }
+
+    #[tokio::test]
+    async fn test_hot_queries_use_query_path_indexes() {
+        let database = crate::database::DatabaseManager::in_memory().await.unwrap();
+        let pool = database.pool();
+
+        let hot_queries = [
+            (
+                "SELECT COUNT(*) FROM case_studies WHERE deleted_at IS NULL AND status = 'published'",
+                "idx_case_studies_status_deleted_at",
+            ),
+            (
+                "SELECT id FROM case_studies WHERE deleted_at IS NULL ORDER BY created_at DESC, id DESC LIMIT 20",
+                "idx_case_studies_deleted_at_created_at",
+            ),
+            (
+                "SELECT * FROM user_progress WHERE user_id = 1 AND case_study_id = 'case-1'",
+                "idx_user_progress_user_case_study",
+            ),
+            (
+                "SELECT * FROM assessment_questions WHERE case_study_id = 'case-1' ORDER BY order_index",
+                "idx_assessment_questions_case_study_order",
+            ),
+        ];
+        for (sql, index) in hot_queries {
+            let plan = query_plan(pool, sql).await;
+            assert!(plan.contains(index), "expected {} for {}, got:\n{}", index, sql, plan);
+        }
+        let questions = query_plan(pool, hot_queries[3].0).await;
+        assert!(!questions.contains("TEMP B-TREE"), "questions are still sorted after lookup:\n{}", questions);
+
+        // Re-running the migration is harmless, and rolling it back drops every index
+        sqlx::query(include_str!("migrations/023_query_path_indexes.sql")).execute(pool).await.unwrap();
+        let manager = MigrationManager::new(pool.clone());
+        manager.rollback_migration("023").await.unwrap();
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name IN \
+             ('idx_case_studies_status_deleted_at', 'idx_case_studies_deleted_at_created_at', \
+              'idx_user_progress_user_case_study', 'idx_assessment_questions_case_study_order')"
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        assert_eq!(remaining, 0);
+        assert_eq!(manager.migrate().await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+-- Generation Cost Tracking
+-- Records the estimated cost of each AI generation for usage reporting
+
+ALTER TABLE generation_history ADD COLUMN estimated_cost REAL;
+
+-- Index for cost aggregation by provider
+CREATE INDEX IF NOT EXISTS idx_generation_history_provider ON generation_history(ai_provider);
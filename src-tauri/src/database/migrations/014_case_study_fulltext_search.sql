@@ -0,0 +1,40 @@
+-- Case Study Full-Text Search
+-- FTS5 index over case study text, kept in sync with case_studies by triggers
+
+CREATE VIRTUAL TABLE IF NOT EXISTS case_studies_fts USING fts5(
+    case_study_id UNINDEXED,
+    title,
+    description,
+    summary,
+    content,
+    industry,
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS case_studies_fts_insert
+    AFTER INSERT ON case_studies
+    FOR EACH ROW
+BEGIN
+    INSERT INTO case_studies_fts (case_study_id, title, description, summary, content, industry)
+    VALUES (NEW.id, NEW.title, NEW.description, NEW.summary, NEW.content, NEW.industry);
+END;
+
+CREATE TRIGGER IF NOT EXISTS case_studies_fts_update
+    AFTER UPDATE OF title, description, summary, content, industry ON case_studies
+    FOR EACH ROW
+BEGIN
+    DELETE FROM case_studies_fts WHERE case_study_id = OLD.id;
+    INSERT INTO case_studies_fts (case_study_id, title, description, summary, content, industry)
+    VALUES (NEW.id, NEW.title, NEW.description, NEW.summary, NEW.content, NEW.industry);
+END;
+
+CREATE TRIGGER IF NOT EXISTS case_studies_fts_delete
+    AFTER DELETE ON case_studies
+    FOR EACH ROW
+BEGIN
+    DELETE FROM case_studies_fts WHERE case_study_id = OLD.id;
+END;
+
+-- Backfill case studies created before the index existed
+INSERT INTO case_studies_fts (case_study_id, title, description, summary, content, industry)
+SELECT id, title, description, summary, content, industry FROM case_studies;
@@ -0,0 +1,32 @@
+-- Reusable Question Bank
+-- Questions that are not tied to a single case study and can be pulled into any assessment
+
+CREATE TABLE IF NOT EXISTS question_bank (
+    id TEXT PRIMARY KEY,
+    question_text TEXT NOT NULL,
+    question_type TEXT NOT NULL, -- 'single_choice', 'multiple_choice', 'essay', etc.
+    options TEXT NOT NULL DEFAULT '[]', -- JSON array of answer options
+    correct_answer TEXT,
+    sample_answer TEXT,
+    rubric TEXT, -- JSON rubric
+    points INTEGER NOT NULL DEFAULT 1,
+    difficulty_level TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]', -- JSON array
+    source_question_id INTEGER, -- assessment_questions row this was imported from
+    created_by TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_question_bank_difficulty ON question_bank(difficulty_level);
+CREATE INDEX IF NOT EXISTS idx_question_bank_type ON question_bank(question_type);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_question_bank_source ON question_bank(source_question_id)
+    WHERE source_question_id IS NOT NULL;
+
+CREATE TRIGGER IF NOT EXISTS update_question_bank_updated_at
+    AFTER UPDATE ON question_bank
+    FOR EACH ROW
+    WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE question_bank SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,15 @@
+-- Soft Delete
+-- Deleted rows keep their status and are hidden by a deleted_at timestamp until restored or purged
+
+ALTER TABLE case_studies ADD COLUMN deleted_at DATETIME;
+ALTER TABLE assessment_workflows ADD COLUMN deleted_at DATETIME;
+ALTER TABLE configurations ADD COLUMN deleted_at DATETIME;
+
+-- Rows deleted before this migration were marked by status only
+UPDATE case_studies SET deleted_at = updated_at WHERE status = 'deleted';
+UPDATE assessment_workflows SET deleted_at = updated_at WHERE status = 'deleted';
+UPDATE configurations SET deleted_at = updated_at WHERE status = 'deleted';
+
+CREATE INDEX IF NOT EXISTS idx_case_studies_deleted_at ON case_studies(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_assessment_workflows_deleted_at ON assessment_workflows(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_configurations_deleted_at ON configurations(deleted_at);
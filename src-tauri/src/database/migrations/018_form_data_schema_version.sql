@@ -0,0 +1,6 @@
+-- Form Data Schema Version
+-- Stored submissions record the configuration schema version their data was entered against
+
+ALTER TABLE form_submissions ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_form_submissions_schema_version ON form_submissions(configuration_id, schema_version);
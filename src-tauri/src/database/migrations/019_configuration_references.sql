@@ -0,0 +1,22 @@
+-- Configuration References
+-- Categories and frameworks that configurations refer to by id, so exported bundles can carry them
+
+CREATE TABLE IF NOT EXISTS configuration_categories (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS configuration_frameworks (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Existing configurations already refer to these by value
+INSERT OR IGNORE INTO configuration_categories (id, name)
+    SELECT DISTINCT category, category FROM configurations;
+INSERT OR IGNORE INTO configuration_frameworks (id, name)
+    SELECT DISTINCT framework, framework FROM configurations WHERE framework IS NOT NULL;
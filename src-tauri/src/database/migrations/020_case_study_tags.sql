@@ -0,0 +1,4 @@
+-- Case Study Tags
+-- Normalized tags stored as a JSON array per case study
+
+ALTER TABLE case_studies ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,14 @@
+-- Case Study Embeddings
+-- One embedding vector per case study and embedding provider, for semantic search
+
+CREATE TABLE IF NOT EXISTS case_study_embeddings (
+    case_study_id TEXT NOT NULL REFERENCES case_studies(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL, -- vectors from different providers aren't comparable
+    dimensions INTEGER NOT NULL,
+    embedding BLOB NOT NULL, -- little-endian f32 values
+    source_updated_at DATETIME NOT NULL, -- case_studies.updated_at when embedded
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (case_study_id, provider)
+);
+
+CREATE INDEX IF NOT EXISTS idx_case_study_embeddings_provider ON case_study_embeddings(provider);
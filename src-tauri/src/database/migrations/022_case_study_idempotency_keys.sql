@@ -0,0 +1,10 @@
+-- Case Study Idempotency Keys
+-- Client-supplied request ids so a retried create returns the case study it already made
+
+CREATE TABLE IF NOT EXISTS case_study_idempotency_keys (
+    idempotency_key TEXT PRIMARY KEY,
+    case_study_id TEXT NOT NULL REFERENCES case_studies(id) ON DELETE CASCADE,
+    created_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_case_study_idempotency_keys_created_at ON case_study_idempotency_keys(created_at);
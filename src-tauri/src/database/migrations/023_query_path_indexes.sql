@@ -0,0 +1,15 @@
+-- Query Path Indexes
+-- Composite indexes for the repository list/count queries that otherwise scan or sort.
+-- case_studies.domain_id only exists in the pre-010 schema, where schema.sql already indexes it.
+
+-- Counts by status over live rows
+CREATE INDEX IF NOT EXISTS idx_case_studies_status_deleted_at ON case_studies(status, deleted_at);
+
+-- Live case studies, newest first (id breaks created_at ties in the page cursor)
+CREATE INDEX IF NOT EXISTS idx_case_studies_deleted_at_created_at ON case_studies(deleted_at, created_at, id);
+
+-- A user's progress on one case study
+CREATE INDEX IF NOT EXISTS idx_user_progress_user_case_study ON user_progress(user_id, case_study_id);
+
+-- A case study's questions in order
+CREATE INDEX IF NOT EXISTS idx_assessment_questions_case_study_order ON assessment_questions(case_study_id, order_index);
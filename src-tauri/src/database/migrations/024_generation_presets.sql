@@ -0,0 +1,12 @@
+-- Generation Presets
+-- Named case study generation parameters saved per user for reuse
+
+CREATE TABLE IF NOT EXISTS generation_presets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    params TEXT NOT NULL, -- JSON CaseStudyGenerationParams
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    UNIQUE(user_id, name)
+);
@@ -1,5 +1,7 @@
 pub mod migrations;
+pub mod audit;
 pub mod connection;
+pub mod commands;
 // pub mod encrypted_repositories; // Temporarily disabled for compilation
 pub mod models;
 pub mod repositories;
@@ -7,15 +9,21 @@ pub mod seeds;
 pub mod validation;
 pub mod prompt_template_repository;
 pub mod ai_config_repository;
+pub mod collection_repository;
+pub mod generation_preset_repository;
+pub mod stats_cache;
 
 #[cfg(test)]
 mod tests;
 
-pub use connection::{DatabaseManager, DatabaseStats, PoolStats};
+pub use connection::{DatabaseConfig, DatabaseManager, DatabaseStats, PoolStats};
 // pub use encrypted_repositories::*; // Temporarily disabled for compilation
 pub use migrations::{Migration, MigrationManager, MigrationError};
 pub use models::*;
 pub use repositories::*;
 pub use validation::{Validator, Validatable, ValidationError};
 pub use prompt_template_repository::PromptTemplateRepository;
-pub use ai_config_repository::AIConfigRepository;
\ No newline at end of file
+pub use ai_config_repository::AIConfigRepository;
+pub use collection_repository::{CollectionError, CollectionRepository};
+pub use generation_preset_repository::{GenerationPreset, GenerationPresetError, GenerationPresetRepository, UpdateGenerationPreset};
+pub use stats_cache::StatsCache;
\ No newline at end of file
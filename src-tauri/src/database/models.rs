@@ -57,6 +57,33 @@ pub struct NewDomain {
     pub icon: Option<String>,
 }
 
+/// Changes to an existing domain; fields left as None are unchanged
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateDomain {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// What deleting a domain does to the case studies that reference it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainDeleteMode {
+    /// Refuse to delete a domain that case studies still reference
+    #[default]
+    Restrict,
+    /// Move the referencing case studies to the trash along with deleting the domain
+    Cascade,
+}
+
+/// A domain with the number of case studies that use it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainUsage {
+    pub domain: Domain,
+    pub case_study_count: i64,
+}
+
 /// Configuration template for dynamic form generation
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ConfigurationTemplate {
@@ -182,6 +209,18 @@ pub struct NewAssessmentQuestion {
     pub is_required: Option<bool>,
 }
 
+/// Question to store against a case study identified by its text id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCaseStudyQuestion {
+    pub question_text: String,
+    pub question_type: QuestionType,
+    pub options: Option<String>, // JSON array for multiple choice
+    pub correct_answer: Option<String>,
+    pub sample_answer: Option<String>,
+    pub rubric: Option<String>, // JSON rubric
+    pub points: i64,
+}
+
 /// AI generation history and prompts
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GenerationHistory {
@@ -199,6 +238,8 @@ pub struct GenerationHistory {
     pub error_message: Option<String>,
     pub created_by: Option<i64>,
     pub created_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub estimated_cost: Option<f64>,
 }
 
 /// New generation history record
@@ -216,6 +257,45 @@ pub struct NewGenerationHistory {
     pub success: Option<bool>,
     pub error_message: Option<String>,
     pub created_by: Option<i64>,
+    pub estimated_cost: Option<f64>,
+}
+
+/// Aggregated generation cost for a single provider
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProviderCostTotal {
+    pub ai_provider: String,
+    pub generation_count: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_cost: f64,
+}
+
+/// Aggregated generation cost for a single day
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyCostTotal {
+    pub day: String,
+    pub generation_count: i64,
+    pub total_cost: f64,
+}
+
+/// Generation spend grouped by provider and by day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationCostSummary {
+    pub total_cost: f64,
+    pub total_generations: i64,
+    pub by_provider: Vec<ProviderCostTotal>,
+    pub by_day: Vec<DailyCostTotal>,
+}
+
+/// Selects generation history records for export; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    /// Inclusive lower bound on `created_at`
+    pub start_date: Option<DateTime<Utc>>,
+    /// Inclusive upper bound on `created_at`
+    pub end_date: Option<DateTime<Utc>>,
+    pub ai_provider: Option<String>,
+    pub model_name: Option<String>,
 }
 
 /// User learning analytics and progress tracking
@@ -331,6 +411,17 @@ pub struct CollectionCaseStudy {
     pub added_at: DateTime<Utc>,
 }
 
+/// A case study's place in a collection, with enough of the case study to display it
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CollectionMember {
+    pub collection_id: i64,
+    pub case_study_id: String,
+    pub title: String,
+    pub status: String,
+    pub order_index: i64,
+    pub added_at: DateTime<Utc>,
+}
+
 /// New collection-case study association
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NewCollectionCaseStudy {
@@ -583,6 +674,46 @@ pub struct NewTemplateUsage {
     pub execution_time_ms: Option<i64>,
 }
 
+/// Time window covered by template analytics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsPeriod {
+    Week,
+    Month,
+    Quarter,
+    Year,
+    AllTime,
+}
+
+/// Usage totals for one template, with averages from linked generation history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TemplateUsageRanking {
+    pub template_id: String,
+    pub name: Option<String>,
+    pub usage_count: i64,
+    pub success_count: i64,
+    pub avg_prompt_tokens: Option<f64>,
+    pub avg_completion_tokens: Option<f64>,
+    pub avg_cost: Option<f64>,
+}
+
+/// Template uses within one time bucket; `bucket` is a day, the Monday starting a week,
+/// or a month (`YYYY-MM`) depending on the period
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UsageBucket {
+    pub bucket: String,
+    pub usage_count: i64,
+}
+
+/// Aggregate template usage over a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateAnalytics {
+    pub period: AnalyticsPeriod,
+    pub total_uses: i64,
+    pub top_templates: Vec<TemplateUsageRanking>,
+    pub usage_over_time: Vec<UsageBucket>,
+}
+
 /// Template categories with metadata
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TemplateCategory {
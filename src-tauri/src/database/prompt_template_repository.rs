@@ -2,7 +2,8 @@
 
 use crate::database::models::{
     PromptTemplate, NewPromptTemplate, UpdatePromptTemplate,
-    TemplateUsage, NewTemplateUsage, TemplateCategory, NewTemplateCategory
+    TemplateUsage, NewTemplateUsage, TemplateCategory, NewTemplateCategory,
+    AnalyticsPeriod, TemplateAnalytics, TemplateUsageRanking, UsageBucket
 };
 use chrono::{DateTime, Utc};
 use sqlx::{SqlitePool, Row};
@@ -10,6 +11,32 @@ use std::collections::HashMap;
 
 pub type Result<T> = std::result::Result<T, sqlx::Error>;
 
+/// Templates listed in the analytics ranking
+const TOP_TEMPLATES_LIMIT: i64 = 10;
+
+impl AnalyticsPeriod {
+    /// `datetime('now', ...)` modifier for the start of the period, or `None` for all time
+    fn start_modifier(self) -> Option<&'static str> {
+        match self {
+            AnalyticsPeriod::Week => Some("-7 days"),
+            AnalyticsPeriod::Month => Some("-30 days"),
+            AnalyticsPeriod::Quarter => Some("-90 days"),
+            AnalyticsPeriod::Year => Some("-365 days"),
+            AnalyticsPeriod::AllTime => None,
+        }
+    }
+
+    /// SQL expression bucketing `tu.created_at`: days for short periods, weeks starting
+    /// Monday for a quarter, months beyond that
+    fn bucket_expression(self) -> &'static str {
+        match self {
+            AnalyticsPeriod::Week | AnalyticsPeriod::Month => "date(tu.created_at)",
+            AnalyticsPeriod::Quarter => "date(tu.created_at, '-6 days', 'weekday 1')",
+            AnalyticsPeriod::Year | AnalyticsPeriod::AllTime => "strftime('%Y-%m', tu.created_at)",
+        }
+    }
+}
+
 /// Repository for prompt template operations
 pub struct PromptTemplateRepository {
     pool: SqlitePool,
@@ -299,6 +326,56 @@ impl PromptTemplateRepository {
         Ok(result)
     }
 
+    /// Aggregate usage across all templates in `period`: the most used templates with
+    /// average tokens and cost of their linked generations, and uses per time bucket
+    pub async fn get_template_analytics(&self, period: AnalyticsPeriod) -> Result<TemplateAnalytics> {
+        let since = period.start_modifier();
+
+        let top_templates = sqlx::query_as::<_, TemplateUsageRanking>(
+            r#"
+            SELECT tu.template_id,
+                   pt.name,
+                   COUNT(*) AS usage_count,
+                   SUM(CASE WHEN tu.success = 1 THEN 1 ELSE 0 END) AS success_count,
+                   AVG(gh.prompt_tokens) AS avg_prompt_tokens,
+                   AVG(gh.completion_tokens) AS avg_completion_tokens,
+                   AVG(gh.estimated_cost) AS avg_cost
+            FROM template_usage tu
+            LEFT JOIN prompt_templates pt ON pt.template_id = tu.template_id
+            LEFT JOIN generation_history gh ON gh.id = tu.generation_id
+            WHERE ?1 IS NULL OR datetime(tu.created_at) >= datetime('now', ?1)
+            GROUP BY tu.template_id
+            ORDER BY usage_count DESC, tu.template_id
+            LIMIT ?2
+            "#
+        )
+        .bind(since)
+        .bind(TOP_TEMPLATES_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let usage_over_time = sqlx::query_as::<_, UsageBucket>(&format!(
+            r#"
+            SELECT {bucket} AS bucket, COUNT(*) AS usage_count
+            FROM template_usage tu
+            WHERE ?1 IS NULL OR datetime(tu.created_at) >= datetime('now', ?1)
+            GROUP BY bucket
+            ORDER BY bucket
+            "#,
+            bucket = period.bucket_expression()
+        ))
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(TemplateAnalytics {
+            period,
+            total_uses: usage_over_time.iter().map(|b| b.usage_count).sum(),
+            top_templates,
+            usage_over_time,
+        })
+    }
+
     /// Record template usage
     pub async fn record_usage(&self, usage: NewTemplateUsage) -> Result<TemplateUsage> {
         let result = sqlx::query(
@@ -481,4 +558,121 @@ mod tests {
         // This test would require running migrations first
         // For now, it's a placeholder to show the testing approach
     }
+
+    /// Schema with templates, usage and generation history, in one connection so the
+    /// in-memory database is shared
+    async fn analytics_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for sql in [
+            include_str!("../../schema.sql"),
+            include_str!("migrations/009_create_prompt_templates.sql"),
+            include_str!("migrations/013_generation_cost_tracking.sql"),
+        ] {
+            sqlx::raw_sql(sql).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    fn template(template_id: &str) -> NewPromptTemplate {
+        NewPromptTemplate {
+            template_id: template_id.to_string(),
+            name: format!("Template {}", template_id),
+            description: "Analytics test template".to_string(),
+            category: "generation".to_string(),
+            system_prompt: None,
+            user_prompt: "Write about {{topic}}".to_string(),
+            variables: "[]".to_string(),
+            example_values: None,
+            tags: None,
+            version: None,
+            is_active: Some(true),
+            is_system_template: Some(false),
+            created_by: None,
+        }
+    }
+
+    /// Record a use of `template_id` `days_ago` days back, optionally linked to a generation
+    async fn record(repo: &PromptTemplateRepository, pool: &SqlitePool, template_id: &str, days_ago: i64, generation_id: Option<i64>) {
+        let usage = repo.record_usage(NewTemplateUsage {
+            template_id: template_id.to_string(),
+            user_id: None,
+            generation_id,
+            variables_used: None,
+            success: true,
+            error_message: None,
+            execution_time_ms: Some(100),
+        })
+        .await
+        .unwrap();
+        sqlx::query("UPDATE template_usage SET created_at = datetime('now', ?) WHERE id = ?")
+            .bind(format!("-{} days", days_ago))
+            .bind(usage.id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn generation(pool: &SqlitePool, prompt_tokens: i64, completion_tokens: i64, cost: f64) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO generation_history (generation_type, prompt_tokens, completion_tokens, estimated_cost) VALUES ('case_study', ?, ?, ?) RETURNING id"
+        )
+        .bind(prompt_tokens)
+        .bind(completion_tokens)
+        .bind(cost)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    fn day(days_ago: i64) -> String {
+        (Utc::now() - chrono::Duration::days(days_ago)).format("%Y-%m-%d").to_string()
+    }
+
+    #[tokio::test]
+    async fn test_template_analytics_ranking_and_buckets() {
+        let pool = analytics_pool().await;
+        let repo = PromptTemplateRepository::new(pool.clone());
+        for id in ["alpha", "beta", "gamma"] {
+            repo.create(template(id)).await.unwrap();
+        }
+
+        let first = generation(&pool, 100, 300, 0.02).await;
+        let second = generation(&pool, 200, 500, 0.04).await;
+        record(&repo, &pool, "alpha", 1, Some(first)).await;
+        record(&repo, &pool, "alpha", 1, Some(second)).await;
+        record(&repo, &pool, "alpha", 3, None).await;
+        record(&repo, &pool, "beta", 1, None).await;
+        record(&repo, &pool, "beta", 3, None).await;
+        record(&repo, &pool, "gamma", 3, None).await;
+        // Outside the month, but still counted for the year
+        for _ in 0..4 {
+            record(&repo, &pool, "gamma", 60, None).await;
+        }
+
+        let month = repo.get_template_analytics(AnalyticsPeriod::Month).await.unwrap();
+        assert_eq!(month.total_uses, 6);
+        let ranking: Vec<(&str, i64)> = month.top_templates.iter().map(|t| (t.template_id.as_str(), t.usage_count)).collect();
+        assert_eq!(ranking, vec![("alpha", 3), ("beta", 2), ("gamma", 1)]);
+
+        let alpha = &month.top_templates[0];
+        assert_eq!(alpha.name.as_deref(), Some("Template alpha"));
+        assert_eq!(alpha.avg_prompt_tokens, Some(150.0));
+        assert_eq!(alpha.avg_completion_tokens, Some(400.0));
+        assert!((alpha.avg_cost.unwrap() - 0.03).abs() < 1e-9);
+        assert!(month.top_templates[1].avg_cost.is_none());
+
+        let buckets: Vec<(String, i64)> = month.usage_over_time.iter().map(|b| (b.bucket.clone(), b.usage_count)).collect();
+        assert_eq!(buckets, vec![(day(3), 3), (day(1), 3)]);
+
+        let year = repo.get_template_analytics(AnalyticsPeriod::Year).await.unwrap();
+        assert_eq!(year.total_uses, 10);
+        assert_eq!(year.top_templates[0].template_id, "gamma");
+        assert_eq!(year.top_templates[0].usage_count, 5);
+        assert!(year.usage_over_time.iter().all(|b| b.bucket.len() == "2024-01".len()));
+        assert_eq!(year.usage_over_time.iter().map(|b| b.usage_count).sum::<i64>(), 10);
+    }
 }
\ No newline at end of file
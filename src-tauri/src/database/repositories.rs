@@ -1,6 +1,10 @@
-use sqlx::SqlitePool;
-use anyhow::Result;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use anyhow::{Context, Result};
+use futures::TryStreamExt;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
 use super::models::*;
+use super::validation::Validator;
+use crate::case_study::repository::CaseStudyRepository;
 
 /// User repository for database operations
 pub struct UserRepository {
@@ -268,6 +272,11 @@ impl DomainRepository {
     }
 
     pub async fn create(&self, domain: NewDomain) -> Result<Domain> {
+        Validator::validate_new_domain(&domain)?;
+        if self.find_by_name(&domain.name).await?.is_some() {
+            anyhow::bail!("A domain named '{}' already exists", domain.name);
+        }
+
         let domain_result = sqlx::query_as::<_, Domain>(
             "INSERT INTO domains (name, description, color, icon) VALUES (?, ?, ?, ?) RETURNING id, name, description, color, icon, created_at"
         )
@@ -280,6 +289,129 @@ impl DomainRepository {
 
         Ok(domain_result)
     }
+
+    pub async fn update(&self, id: i64, update: UpdateDomain) -> Result<Domain> {
+        let current = self
+            .find_by_id(id)
+            .await?
+            .with_context(|| format!("Domain {} not found", id))?;
+
+        let previous_name = current.name.clone();
+        let updated = NewDomain {
+            name: update.name.unwrap_or(current.name),
+            description: update.description.or(current.description),
+            color: update.color.or(current.color),
+            icon: update.icon.or(current.icon),
+        };
+        Validator::validate_new_domain(&updated)?;
+        if self.find_by_name(&updated.name).await?.is_some_and(|other| other.id != id) {
+            anyhow::bail!("A domain named '{}' already exists", updated.name);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let domain = sqlx::query_as::<_, Domain>(
+            "UPDATE domains SET name = ?, description = ?, color = ?, icon = ? WHERE id = ? RETURNING id, name, description, color, icon, created_at"
+        )
+        .bind(&updated.name)
+        .bind(&updated.description)
+        .bind(&updated.color)
+        .bind(&updated.icon)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Case studies refer to their domain by name, so a rename carries over to them
+        if updated.name != previous_name {
+            CaseStudyRepository::rename_industry_on(&mut tx, &previous_name, &updated.name, None).await?;
+        }
+        tx.commit().await?;
+
+        Ok(domain)
+    }
+
+    /// Delete a domain. Case studies belong to a domain through their `industry` name.
+    /// With `DomainDeleteMode::Restrict` a domain that live case studies still use is
+    /// refused; with `Cascade` its live case studies are moved to the trash in the same
+    /// transaction. Templates for the domain are kept but no longer tied to it.
+    pub async fn delete(&self, id: i64, mode: DomainDeleteMode) -> Result<()> {
+        let domain = self
+            .find_by_id(id)
+            .await?
+            .with_context(|| format!("Domain {} not found", id))?;
+
+        let references = self.case_study_count(id).await?;
+        if references > 0 && mode == DomainDeleteMode::Restrict {
+            anyhow::bail!("Domain {} is used by {} case studies", id, references);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        if mode == DomainDeleteMode::Cascade {
+            let ids: Vec<String> = sqlx::query_scalar(
+                "SELECT id FROM case_studies WHERE industry = ? COLLATE NOCASE AND deleted_at IS NULL"
+            )
+            .bind(&domain.name)
+            .fetch_all(&mut *tx)
+            .await?;
+            CaseStudyRepository::bulk_delete_on(&mut tx, &ids, false, None).await?;
+        }
+        sqlx::query("UPDATE configuration_templates SET domain_id = NULL WHERE domain_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM domains WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Every domain with the number of live case studies that use it, ordered by name
+    pub async fn list_usage(&self) -> Result<Vec<DomainUsage>> {
+        let counts: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT d.id, COUNT(*)
+            FROM domains d
+            JOIN case_studies cs ON cs.industry = d.name COLLATE NOCASE
+            WHERE cs.deleted_at IS NULL
+            GROUP BY d.id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let usage = self
+            .list_all()
+            .await?
+            .into_iter()
+            .map(|domain| {
+                let case_study_count = counts
+                    .iter()
+                    .find(|(domain_id, _)| *domain_id == domain.id)
+                    .map_or(0, |(_, count)| *count);
+                DomainUsage { domain, case_study_count }
+            })
+            .collect();
+
+        Ok(usage)
+    }
+
+    /// Number of live case studies in a domain
+    pub async fn case_study_count(&self, id: i64) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM case_studies cs
+            JOIN domains d ON d.name = cs.industry COLLATE NOCASE
+            WHERE d.id = ? AND cs.deleted_at IS NULL
+            "#
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
 }
 
 /// Assessment Question repository
@@ -293,6 +425,12 @@ impl AssessmentQuestionRepository {
     }
 
     pub async fn create(&self, question: NewAssessmentQuestion) -> Result<AssessmentQuestion> {
+        Validator::validate_multiple_choice(
+            &question.question_type,
+            question.options.as_deref(),
+            question.correct_answer.as_deref(),
+        )?;
+
         let question_result = sqlx::query_as::<_, AssessmentQuestion>(
             r#"
             INSERT INTO assessment_questions (
@@ -320,6 +458,56 @@ impl AssessmentQuestionRepository {
         Ok(question_result)
     }
 
+    /// Insert questions for a case study in one transaction, ordered after its existing
+    /// questions. Returns the new ids in the order given.
+    pub async fn create_for_case_study(&self, case_study_id: &str, questions: &[NewCaseStudyQuestion]) -> Result<Vec<i64>> {
+        for question in questions {
+            Validator::validate_multiple_choice(
+                &question.question_type.to_string(),
+                question.options.as_deref(),
+                question.correct_answer.as_deref(),
+            )?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let next_index: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(order_index) + 1, 0) FROM assessment_questions WHERE case_study_id = ?"
+        )
+        .bind(case_study_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut ids = Vec::with_capacity(questions.len());
+        for (offset, question) in questions.iter().enumerate() {
+            let id: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO assessment_questions (
+                    case_study_id, question_text, question_type, options,
+                    correct_answer, sample_answer, rubric, points, order_index, is_required
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, TRUE)
+                RETURNING id
+                "#
+            )
+            .bind(case_study_id)
+            .bind(&question.question_text)
+            .bind(question.question_type.to_string())
+            .bind(&question.options)
+            .bind(&question.correct_answer)
+            .bind(&question.sample_answer)
+            .bind(&question.rubric)
+            .bind(question.points)
+            .bind(next_index + offset as i64)
+            .fetch_one(&mut *tx)
+            .await?;
+            ids.push(id);
+        }
+
+        tx.commit().await?;
+        Ok(ids)
+    }
+
     pub async fn list_by_case_study(&self, case_study_id: i64) -> Result<Vec<AssessmentQuestion>> {
         let questions = sqlx::query_as::<_, AssessmentQuestion>(
             "SELECT * FROM assessment_questions WHERE case_study_id = ? ORDER BY order_index"
@@ -331,6 +519,18 @@ impl AssessmentQuestionRepository {
         Ok(questions)
     }
 
+    /// Ids of a saved case study's questions, in order
+    pub async fn list_ids_for_case_study(&self, case_study_id: &str) -> Result<Vec<i64>> {
+        let ids = sqlx::query_scalar(
+            "SELECT id FROM assessment_questions WHERE case_study_id = ? ORDER BY order_index, id"
+        )
+        .bind(case_study_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
     pub async fn delete(&self, id: i64) -> Result<bool> {
         let result = sqlx::query("DELETE FROM assessment_questions WHERE id = ?")
             .bind(id)
@@ -491,18 +691,20 @@ impl GenerationHistoryRepository {
         Self { pool }
     }
 
+    #[tracing::instrument(name = "db.generation_history.create", skip_all)]
     pub async fn create(&self, history: NewGenerationHistory) -> Result<GenerationHistory> {
         let history_result = sqlx::query_as::<_, GenerationHistory>(
             r#"
             INSERT INTO generation_history (
                 case_study_id, generation_type, prompt_template, user_input,
                 ai_provider, model_name, prompt_tokens, completion_tokens,
-                generation_time_ms, success, error_message, created_by
+                generation_time_ms, success, error_message, created_by, estimated_cost
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             RETURNING id, case_study_id, generation_type, prompt_template, user_input,
                       ai_provider, model_name, prompt_tokens, completion_tokens,
-                      generation_time_ms, success, error_message, created_by, created_at
+                      generation_time_ms, success, error_message, created_by, created_at,
+                      estimated_cost
             "#
         )
         .bind(history.case_study_id)
@@ -517,6 +719,7 @@ impl GenerationHistoryRepository {
         .bind(history.success.unwrap_or(true))
         .bind(&history.error_message)
         .bind(history.created_by)
+        .bind(history.estimated_cost)
         .fetch_one(&self.pool)
         .await?;
 
@@ -544,4 +747,438 @@ impl GenerationHistoryRepository {
 
         Ok(history)
     }
-}
\ No newline at end of file
+
+    /// Total successful generation cost grouped by provider and by day
+    pub async fn cost_summary(&self) -> Result<GenerationCostSummary> {
+        let by_provider = sqlx::query_as::<_, ProviderCostTotal>(
+            r#"
+            SELECT COALESCE(ai_provider, 'unknown') AS ai_provider,
+                   COUNT(*) AS generation_count,
+                   COALESCE(SUM(prompt_tokens), 0) AS prompt_tokens,
+                   COALESCE(SUM(completion_tokens), 0) AS completion_tokens,
+                   COALESCE(SUM(estimated_cost), 0.0) AS total_cost
+            FROM generation_history
+            WHERE success = 1
+            GROUP BY COALESCE(ai_provider, 'unknown')
+            ORDER BY total_cost DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let by_day = sqlx::query_as::<_, DailyCostTotal>(
+            r#"
+            SELECT date(created_at) AS day,
+                   COUNT(*) AS generation_count,
+                   COALESCE(SUM(estimated_cost), 0.0) AS total_cost
+            FROM generation_history
+            WHERE success = 1
+            GROUP BY date(created_at)
+            ORDER BY day DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(GenerationCostSummary {
+            total_cost: by_provider.iter().map(|p| p.total_cost).sum(),
+            total_generations: by_provider.iter().map(|p| p.generation_count).sum(),
+            by_provider,
+            by_day,
+        })
+    }
+
+    /// Write records matching `filter` to `writer` as JSON Lines, oldest first, streaming
+    /// rows from the database one at a time. Returns the number of records written.
+    pub async fn export_jsonl<W: AsyncWrite + Unpin>(&self, filter: &HistoryFilter, writer: &mut W) -> Result<usize> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM generation_history WHERE 1 = 1");
+        // created_at is stored in SQLite's own timestamp format, so compare through datetime()
+        if let Some(start) = filter.start_date {
+            query
+                .push(" AND datetime(created_at) >= datetime(")
+                .push_bind(start.format("%Y-%m-%d %H:%M:%S").to_string())
+                .push(")");
+        }
+        if let Some(end) = filter.end_date {
+            query
+                .push(" AND datetime(created_at) <= datetime(")
+                .push_bind(end.format("%Y-%m-%d %H:%M:%S").to_string())
+                .push(")");
+        }
+        if let Some(provider) = &filter.ai_provider {
+            query.push(" AND ai_provider = ").push_bind(provider);
+        }
+        if let Some(model) = &filter.model_name {
+            query.push(" AND model_name = ").push_bind(model);
+        }
+        query.push(" ORDER BY created_at, id");
+
+        let mut rows = query.build_query_as::<GenerationHistory>().fetch(&self.pool);
+        let mut count = 0;
+        while let Some(record) = rows.try_next().await? {
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            count += 1;
+        }
+        writer.flush().await?;
+
+        Ok(count)
+    }
+
+    /// Read JSON Lines records written by `export_jsonl` and upsert them by id in one
+    /// transaction. Blank lines are skipped. Returns the number of records imported.
+    pub async fn import_jsonl<R: AsyncBufRead + Unpin>(&self, reader: R) -> Result<usize> {
+        let mut lines = reader.lines();
+        let mut tx = self.pool.begin().await?;
+        let mut count = 0;
+        let mut line_number = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: GenerationHistory = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid generation history record on line {}", line_number))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO generation_history (
+                    id, case_study_id, generation_type, prompt_template, user_input,
+                    ai_provider, model_name, prompt_tokens, completion_tokens,
+                    generation_time_ms, success, error_message, created_by, created_at,
+                    estimated_cost
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(id) DO UPDATE SET
+                    case_study_id = excluded.case_study_id,
+                    generation_type = excluded.generation_type,
+                    prompt_template = excluded.prompt_template,
+                    user_input = excluded.user_input,
+                    ai_provider = excluded.ai_provider,
+                    model_name = excluded.model_name,
+                    prompt_tokens = excluded.prompt_tokens,
+                    completion_tokens = excluded.completion_tokens,
+                    generation_time_ms = excluded.generation_time_ms,
+                    success = excluded.success,
+                    error_message = excluded.error_message,
+                    created_by = excluded.created_by,
+                    created_at = excluded.created_at,
+                    estimated_cost = excluded.estimated_cost
+                "#
+            )
+            .bind(record.id)
+            .bind(record.case_study_id)
+            .bind(&record.generation_type)
+            .bind(&record.prompt_template)
+            .bind(&record.user_input)
+            .bind(&record.ai_provider)
+            .bind(&record.model_name)
+            .bind(record.prompt_tokens)
+            .bind(record.completion_tokens)
+            .bind(record.generation_time_ms)
+            .bind(record.success)
+            .bind(&record.error_message)
+            .bind(record.created_by)
+            // Same format as CURRENT_TIMESTAMP so date filters and grouping keep working
+            .bind(record.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(record.estimated_cost)
+            .execute(&mut *tx)
+            .await?;
+            count += 1;
+        }
+
+        tx.commit().await?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_test_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::query(include_str!("../../schema.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to create schema");
+        sqlx::query(include_str!("migrations/013_generation_cost_tracking.sql"))
+            .execute(&pool)
+            .await
+            .expect("Failed to add cost tracking");
+        pool
+    }
+
+    fn generation(provider: &str, prompt: i64, completion: i64, cost: f64) -> NewGenerationHistory {
+        NewGenerationHistory {
+            case_study_id: None,
+            generation_type: "case_study".to_string(),
+            prompt_template: None,
+            user_input: None,
+            ai_provider: Some(provider.to_string()),
+            model_name: Some("test-model".to_string()),
+            prompt_tokens: Some(prompt),
+            completion_tokens: Some(completion),
+            generation_time_ms: Some(100),
+            success: Some(true),
+            error_message: None,
+            created_by: None,
+            estimated_cost: Some(cost),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generation_cost_summary() {
+        let pool = create_test_pool().await;
+        let repo = GenerationHistoryRepository::new(pool.clone());
+
+        repo.create(generation("openai", 100, 200, 0.25)).await.unwrap();
+        repo.create(generation("openai", 50, 50, 0.50)).await.unwrap();
+        repo.create(generation("anthropic", 10, 30, 1.00)).await.unwrap();
+        repo.create(generation("ollama", 400, 400, 0.0)).await.unwrap();
+
+        let mut failed = generation("openai", 999, 999, 9.99);
+        failed.success = Some(false);
+        repo.create(failed).await.unwrap();
+
+        // Move one record to an earlier day to exercise the daily grouping
+        sqlx::query("UPDATE generation_history SET created_at = datetime('now', '-1 day') WHERE ai_provider = 'anthropic'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let summary = repo.cost_summary().await.unwrap();
+
+        assert_eq!(summary.total_generations, 4);
+        assert!((summary.total_cost - 1.75).abs() < 1e-9);
+
+        let openai = summary.by_provider.iter().find(|p| p.ai_provider == "openai").unwrap();
+        assert_eq!(openai.generation_count, 2);
+        assert_eq!(openai.prompt_tokens, 150);
+        assert_eq!(openai.completion_tokens, 250);
+        assert!((openai.total_cost - 0.75).abs() < 1e-9);
+
+        assert_eq!(summary.by_day.len(), 2);
+        assert!((summary.by_day[0].total_cost - 0.75).abs() < 1e-9);
+        assert_eq!(summary.by_day[0].generation_count, 3);
+        assert!((summary.by_day[1].total_cost - 1.00).abs() < 1e-9);
+    }
+
+    async fn all_history(pool: &SqlitePool) -> serde_json::Value {
+        let records = sqlx::query_as::<_, GenerationHistory>("SELECT * FROM generation_history ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .unwrap();
+        serde_json::to_value(records).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_generation_history_jsonl_round_trip() {
+        let source = create_test_pool().await;
+        let repo = GenerationHistoryRepository::new(source.clone());
+
+        let mut detailed = generation("openai", 120, 340, 0.42);
+        detailed.prompt_template = Some("case_study_v2".to_string());
+        detailed.user_input = Some(r#"{"industry":"retail","notes":"line one\nline two"}"#.to_string());
+        repo.create(detailed).await.unwrap();
+        repo.create(generation("anthropic", 80, 200, 0.30)).await.unwrap();
+        let mut failed = generation("openai", 10, 0, 0.0);
+        failed.success = Some(false);
+        failed.error_message = Some("rate limited".to_string());
+        failed.model_name = Some("other-model".to_string());
+        repo.create(failed).await.unwrap();
+        sqlx::query("UPDATE generation_history SET created_at = datetime('now', '-3 days') WHERE ai_provider = 'anthropic'")
+            .execute(&source)
+            .await
+            .unwrap();
+
+        let mut exported = Vec::new();
+        assert_eq!(repo.export_jsonl(&HistoryFilter::default(), &mut exported).await.unwrap(), 3);
+        assert_eq!(exported.iter().filter(|b| **b == b'\n').count(), 3);
+
+        let target = create_test_pool().await;
+        let target_repo = GenerationHistoryRepository::new(target.clone());
+        assert_eq!(target_repo.import_jsonl(exported.as_slice()).await.unwrap(), 3);
+        assert_eq!(all_history(&target).await, all_history(&source).await);
+
+        // Importing again updates in place rather than duplicating
+        assert_eq!(target_repo.import_jsonl(exported.as_slice()).await.unwrap(), 3);
+        assert_eq!(all_history(&target).await, all_history(&source).await);
+
+        // Filters narrow the export
+        let mut filtered = Vec::new();
+        let filter = HistoryFilter {
+            ai_provider: Some("openai".to_string()),
+            model_name: Some("test-model".to_string()),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(repo.export_jsonl(&filter, &mut filtered).await.unwrap(), 1);
+        let recent = HistoryFilter {
+            start_date: Some(chrono::Utc::now() - chrono::Duration::days(1)),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(repo.export_jsonl(&recent, &mut Vec::new()).await.unwrap(), 2);
+    }
+
+    fn new_domain(name: &str, color: Option<&str>) -> NewDomain {
+        NewDomain {
+            name: name.to_string(),
+            description: None,
+            color: color.map(str::to_string),
+            icon: None,
+        }
+    }
+
+    /// Migrated database, where case studies name their domain in `industry`
+    async fn migrated_pool() -> SqlitePool {
+        crate::database::DatabaseManager::in_memory().await.unwrap().pool().clone()
+    }
+
+    /// Insert a case study in the `industry` domain with progress and history rows pointing at it
+    async fn insert_case_study(pool: &SqlitePool, id: &str, industry: &str) {
+        sqlx::query("INSERT OR IGNORE INTO users (username) VALUES ('author')")
+            .execute(pool)
+            .await
+            .unwrap();
+        let user_id: i64 = sqlx::query_scalar("SELECT id FROM users WHERE username = 'author'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO case_studies (id, title, content, industry, difficulty_level, duration_minutes, learning_objectives) \
+             VALUES (?, 'Market entry', 'Content', ?, 'beginner', 30, '[]')",
+        )
+        .bind(id)
+        .bind(industry)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO user_progress (user_id, case_study_id) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO generation_history (case_study_id, generation_type) VALUES (?, 'case_study')")
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_domain_create_and_update() {
+        let repo = DomainRepository::new(migrated_pool().await);
+
+        let domain = repo.create(new_domain("Education", Some("#FF9800"))).await.unwrap();
+        assert_eq!(domain.name, "Education");
+        assert_eq!(domain.color.as_deref(), Some("#FF9800"));
+
+        assert!(repo.create(new_domain("Education", None)).await.is_err());
+        assert!(repo.create(new_domain("Law", Some("orange"))).await.is_err());
+
+        let updated = repo
+            .update(domain.id, UpdateDomain { color: Some("#fff".to_string()), ..UpdateDomain::default() })
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "Education");
+        assert_eq!(updated.color.as_deref(), Some("#fff"));
+
+        // Renaming onto another domain's name is rejected, keeping its own name is not
+        let rename = |name: &str| UpdateDomain { name: Some(name.to_string()), ..UpdateDomain::default() };
+        assert!(repo.update(domain.id, rename("Business")).await.is_err());
+        assert!(repo.update(domain.id, rename("Education")).await.is_ok());
+        assert!(repo.update(9999, rename("Missing")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_domain_rename_carries_to_case_studies() {
+        let pool = migrated_pool().await;
+        let repo = DomainRepository::new(pool.clone());
+        let business = repo.find_by_name("Business").await.unwrap().unwrap();
+        insert_case_study(&pool, "a", "Business").await;
+        insert_case_study(&pool, "b", "business").await;
+        insert_case_study(&pool, "c", "Science").await;
+
+        let rename = UpdateDomain { name: Some("Commerce".to_string()), ..UpdateDomain::default() };
+        repo.update(business.id, rename).await.unwrap();
+
+        let industries: Vec<(String, String, i64)> = sqlx::query_as("SELECT id, industry, version FROM case_studies ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(industries, vec![
+            ("a".to_string(), "Commerce".to_string(), 1),
+            ("b".to_string(), "Commerce".to_string(), 1),
+            ("c".to_string(), "Science".to_string(), 1),
+        ]);
+        assert_eq!(repo.case_study_count(business.id).await.unwrap(), 2);
+
+        // Each moved case study is audited with its old industry
+        let audited: Vec<(String, String)> = sqlx::query_as(
+            "SELECT entity_id, diff_json FROM audit_log WHERE entity_type = 'case_study' AND action = 'update' ORDER BY entity_id"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(audited.len(), 2);
+        let diff: serde_json::Value = serde_json::from_str(&audited[1].1).unwrap();
+        assert_eq!(diff, serde_json::json!({ "industry": { "old": "business", "new": "Commerce" } }));
+    }
+
+    #[tokio::test]
+    async fn test_domain_delete_with_references() {
+        let pool = migrated_pool().await;
+        let repo = DomainRepository::new(pool.clone());
+        let business = repo.find_by_name("Business").await.unwrap().unwrap();
+        insert_case_study(&pool, "a", "Business").await;
+        insert_case_study(&pool, "b", "business").await;
+        insert_case_study(&pool, "trashed", "Business").await;
+        sqlx::query("UPDATE case_studies SET deleted_at = CURRENT_TIMESTAMP WHERE id = 'trashed'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Matching is by name, ignoring case; trashed case studies don't count
+        let usage = repo.list_usage().await.unwrap();
+        let count = |name: &str| usage.iter().find(|u| u.domain.name == name).unwrap().case_study_count;
+        assert_eq!(count("Business"), 2);
+        assert_eq!(count("Science"), 0);
+
+        // Restrict refuses while case studies reference the domain
+        assert!(repo.delete(business.id, DomainDeleteMode::Restrict).await.is_err());
+        assert!(repo.find_by_id(business.id).await.unwrap().is_some());
+
+        // Cascade moves the live case studies to the trash, where they keep their progress
+        repo.delete(business.id, DomainDeleteMode::Cascade).await.unwrap();
+        assert!(repo.find_by_id(business.id).await.unwrap().is_none());
+        let trashed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM case_studies WHERE deleted_at IS NOT NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(trashed, 3);
+        let progress: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_progress")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(progress, 3);
+        let deletes: Vec<String> = sqlx::query_scalar(
+            "SELECT entity_id FROM audit_log WHERE entity_type = 'case_study' AND action = 'delete' ORDER BY entity_id"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(deletes, vec!["a".to_string(), "b".to_string()]);
+
+        // An unused domain deletes under either mode
+        let science = repo.find_by_name("Science").await.unwrap().unwrap();
+        repo.delete(science.id, DomainDeleteMode::Restrict).await.unwrap();
+        assert!(repo.delete(science.id, DomainDeleteMode::Restrict).await.is_err());
+    }
+}
@@ -1,22 +1,48 @@
 // Tauri commands for database seeding
 
-use super::{DatabaseSeeder, SeedConfig, SeedStats};
+use super::{sample_data, DatabaseSeeder, SeedConfig, SeedStats};
 use crate::database::DatabaseManager;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 type DatabaseManagerState = Arc<DatabaseManager>;
 
-/// Run database seeding with configuration
+/// Cancellation token of the seed currently running, if any
+pub type SeedCancellationState = Arc<Mutex<Option<CancellationToken>>>;
+
+/// Run database seeding with configuration, emitting progress as each stage completes
 #[tauri::command]
 pub async fn seed_database(
     config: SeedConfig,
+    app_handle: AppHandle,
     database_manager_state: State<'_, DatabaseManagerState>,
+    cancellation_state: State<'_, SeedCancellationState>,
 ) -> std::result::Result<SeedStats, String> {
+    let token = CancellationToken::new();
+    *cancellation_state.lock().await = Some(token.clone());
+
     let seeder = DatabaseSeeder::new(database_manager_state.inner().clone());
-    seeder.seed(config).await.map_err(|e| e.to_string())
+    let result = seeder.seed_with_progress(config, &app_handle, &token).await;
+
+    *cancellation_state.lock().await = None;
+    result.map_err(|e| e.to_string())
+}
+
+/// Cancel the running seed; its inserted rows are removed. Returns false if no seed is running.
+#[tauri::command]
+pub async fn cancel_seeding(
+    cancellation_state: State<'_, SeedCancellationState>,
+) -> std::result::Result<bool, String> {
+    match cancellation_state.lock().await.as_ref() {
+        Some(token) => {
+            token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
 }
 
 /// Get default seeding configuration
@@ -170,6 +196,8 @@ pub async fn get_sample_data_info() -> std::result::Result<HashMap<String, serde
     info.insert("default_case_studies_per_domain".to_string(), serde_json::json!(5));
     info.insert("default_questions_per_case_study".to_string(), serde_json::json!(3));
     info.insert("default_max_users".to_string(), serde_json::json!(10));
+    info.insert("available_locales".to_string(), serde_json::json!(sample_data::SUPPORTED_LOCALES));
+    info.insert("default_locale".to_string(), serde_json::json!(sample_data::DEFAULT_LOCALE));
 
     // Data relationships
     info.insert("dependencies".to_string(), serde_json::json!({
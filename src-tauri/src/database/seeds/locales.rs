@@ -0,0 +1,214 @@
+// Locale-specific sample data packs for en-GB and es-ES; en-US lives in sample_data
+
+use crate::database::models::*;
+
+const SAMPLE_PASSWORD_HASH: &str = "$2b$12$LQv3c1yqBWVHxkd0LHAkCOYz6TtxMQJqhN8/LewdBPj4vYA5K8r0m";
+
+fn user(username: &str, email: Option<&str>, full_name: &str, role: &str, preferences: &str) -> NewUser {
+    NewUser {
+        username: username.to_string(),
+        email: email.map(|e| e.to_string()),
+        full_name: Some(full_name.to_string()),
+        password_hash: email.map(|_| SAMPLE_PASSWORD_HASH.to_string()),
+        role: Some(role.to_string()),
+        preferences: Some(preferences.to_string()),
+    }
+}
+
+fn domain(name: &str, description: &str, color: &str, icon: &str) -> NewDomain {
+    NewDomain {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        color: Some(color.to_string()),
+        icon: Some(icon.to_string()),
+    }
+}
+
+/// British English sample users
+pub fn en_gb_users() -> Vec<NewUser> {
+    vec![
+        user("admin", Some("admin@casecrafter.dev"), "System Administrator", "admin",
+            r#"{"theme": "dark", "notifications": true, "language": "en-GB"}"#),
+        user("instructor_patel", Some("priya.patel@university.ac.uk"), "Dr Priya Patel", "instructor",
+            r#"{"theme": "light", "notifications": true, "email_digest": "weekly"}"#),
+        user("instructor_hughes", Some("gareth.hughes@business.ac.uk"), "Prof Gareth Hughes", "instructor",
+            r#"{"theme": "light", "notifications": false, "auto_save": true}"#),
+        user("student_olivia", Some("olivia.taylor@student.ac.uk"), "Olivia Taylor", "user",
+            r#"{"theme": "dark", "notifications": true, "study_reminders": true}"#),
+        user("student_harry", Some("harry.evans@student.ac.uk"), "Harry Evans", "user",
+            r#"{"theme": "light", "notifications": true, "difficulty_preference": "intermediate"}"#),
+        user("student_amelia", Some("amelia.wright@student.ac.uk"), "Amelia Wright", "user",
+            r#"{"theme": "dark", "notifications": false, "progress_tracking": true}"#),
+        user("demo_user", Some("demo@casecrafter.dev"), "Demo User", "user",
+            r#"{"theme": "light", "notifications": true, "tutorial_completed": false}"#),
+        user("researcher_campbell", Some("dr.campbell@research.org.uk"), "Dr Fiona Campbell", "instructor",
+            r#"{"theme": "dark", "notifications": true, "research_mode": true}"#),
+        user("guest_user", None, "Guest User", "user",
+            r#"{"theme": "light", "notifications": false, "guest_mode": true}"#),
+        user("test_instructor", Some("test@casecrafter.dev"), "Test Instructor", "instructor",
+            r#"{"theme": "light", "notifications": true, "testing_mode": true}"#),
+    ]
+}
+
+/// British English sample domains
+pub fn en_gb_domains() -> Vec<NewDomain> {
+    vec![
+        domain("Business Strategy", "Strategic planning, competitive analysis, and business model development", "#1976d2", "strategy"),
+        domain("Marketing", "Digital marketing, brand management, customer acquisition, and market research", "#388e3c", "marketing"),
+        domain("Finance", "Financial analysis, investment decisions, budgeting, and risk management", "#f57c00", "finance"),
+        domain("Operations", "Supply chain management, process optimisation, and operational efficiency", "#7b1fa2", "operations"),
+        domain("Human Resources", "Talent management, organisational behaviour, and workplace culture", "#c62828", "hr"),
+        domain("Technology", "Digital transformation, IT strategy, and technology implementation", "#00796b", "technology"),
+        domain("Healthcare", "NHS and private healthcare management, patient care, and clinical administration", "#455a64", "healthcare"),
+    ]
+}
+
+/// US to British spelling and context substitutions applied to the en-US case studies
+const EN_GB_SUBSTITUTIONS: [(&str, &str); 14] = [
+    ("across the United States", "across the United Kingdom"),
+    ("Chapter 11 Bankruptcy", "Administration"),
+    ("Court-supervised reorganization", "Court-supervised administration"),
+    ("organization", "organisation"),
+    ("optimization", "optimisation"),
+    ("optimize", "optimise"),
+    ("localization", "localisation"),
+    ("digitization", "digitisation"),
+    ("analyze", "analyse"),
+    ("Analyze", "Analyse"),
+    ("behavior", "behaviour"),
+    ("favorable", "favourable"),
+    ("program", "programme"),
+    ("$", "£"),
+];
+
+fn to_british(text: &str) -> String {
+    EN_GB_SUBSTITUTIONS
+        .iter()
+        .fold(text.to_string(), |text, (us, gb)| text.replace(us, gb))
+}
+
+/// British English case studies, adapted from the en-US templates
+pub fn en_gb_case_studies(en_us: Vec<NewCaseStudy>) -> Vec<NewCaseStudy> {
+    let localise = |text: Option<String>| text.map(|t| to_british(&t));
+
+    en_us
+        .into_iter()
+        .map(|case_study| NewCaseStudy {
+            title: to_british(&case_study.title),
+            description: localise(case_study.description),
+            domain_id: case_study.domain_id,
+            template_id: case_study.template_id,
+            difficulty_level: case_study.difficulty_level,
+            estimated_duration: case_study.estimated_duration,
+            learning_objectives: localise(case_study.learning_objectives),
+            tags: case_study.tags,
+            content: to_british(&case_study.content),
+            background_info: localise(case_study.background_info),
+            problem_statement: localise(case_study.problem_statement),
+            analysis_framework: localise(case_study.analysis_framework),
+            sample_solution: localise(case_study.sample_solution),
+            metadata: case_study.metadata,
+            status: case_study.status,
+            created_by: case_study.created_by,
+        })
+        .collect()
+}
+
+/// Spanish sample users
+pub fn es_es_users() -> Vec<NewUser> {
+    vec![
+        user("admin", Some("admin@casecrafter.dev"), "Administrador del Sistema", "admin",
+            r#"{"theme": "dark", "notifications": true, "language": "es"}"#),
+        user("profesora_garcia", Some("lucia.garcia@universidad.es"), "Dra. Lucía García", "instructor",
+            r#"{"theme": "light", "notifications": true, "email_digest": "weekly"}"#),
+        user("profesor_martinez", Some("javier.martinez@empresariales.es"), "Prof. Javier Martínez", "instructor",
+            r#"{"theme": "light", "notifications": false, "auto_save": true}"#),
+        user("alumna_sofia", Some("sofia.lopez@alumnos.es"), "Sofía López", "user",
+            r#"{"theme": "dark", "notifications": true, "study_reminders": true}"#),
+        user("alumno_pablo", Some("pablo.sanchez@alumnos.es"), "Pablo Sánchez", "user",
+            r#"{"theme": "light", "notifications": true, "difficulty_preference": "intermediate"}"#),
+        user("alumna_carmen", Some("carmen.romero@alumnos.es"), "Carmen Romero", "user",
+            r#"{"theme": "dark", "notifications": false, "progress_tracking": true}"#),
+        user("usuario_demo", Some("demo@casecrafter.dev"), "Usuario de Demostración", "user",
+            r#"{"theme": "light", "notifications": true, "tutorial_completed": false}"#),
+        user("investigador_navarro", Some("dr.navarro@investigacion.es"), "Dr. Andrés Navarro", "instructor",
+            r#"{"theme": "dark", "notifications": true, "research_mode": true}"#),
+        user("invitado", None, "Usuario Invitado", "user",
+            r#"{"theme": "light", "notifications": false, "guest_mode": true}"#),
+        user("profesor_pruebas", Some("test@casecrafter.dev"), "Profesor de Pruebas", "instructor",
+            r#"{"theme": "light", "notifications": true, "testing_mode": true}"#),
+    ]
+}
+
+/// Spanish sample domains
+pub fn es_es_domains() -> Vec<NewDomain> {
+    vec![
+        domain("Estrategia Empresarial", "Planificación estratégica, análisis competitivo y desarrollo de modelos de negocio", "#1976d2", "strategy"),
+        domain("Marketing", "Marketing digital, gestión de marca, captación de clientes e investigación de mercados", "#388e3c", "marketing"),
+        domain("Finanzas", "Análisis financiero, decisiones de inversión, presupuestos y gestión de riesgos", "#f57c00", "finance"),
+        domain("Operaciones", "Gestión de la cadena de suministro, optimización de procesos y eficiencia operativa", "#7b1fa2", "operations"),
+        domain("Recursos Humanos", "Gestión del talento, comportamiento organizacional y cultura del trabajo", "#c62828", "hr"),
+        domain("Tecnología", "Transformación digital, estrategia de TI e implantación tecnológica", "#00796b", "technology"),
+        domain("Sanidad", "Gestión sanitaria, atención al paciente y administración hospitalaria", "#455a64", "healthcare"),
+    ]
+}
+
+/// Spanish case study templates
+pub fn es_es_case_studies() -> Vec<NewCaseStudy> {
+    vec![
+        NewCaseStudy {
+            title: "Estrategia de Entrada en Nuevos Mercados".to_string(),
+            description: Some("Un caso que analiza las estrategias de internacionalización de una empresa tecnológica en crecimiento".to_string()),
+            domain_id: 1,
+            template_id: None,
+            difficulty_level: Some("intermediate".to_string()),
+            estimated_duration: Some(90),
+            learning_objectives: Some(r#"["Analizar oportunidades de mercado", "Desarrollar estrategias de entrada", "Evaluar el entorno competitivo", "Elaborar un calendario de implantación"]"#.to_string()),
+            tags: Some(r#"["estrategia", "internacionalizacion", "competencia", "planificacion"]"#.to_string()),
+            content: "# Estrategia de Entrada en Nuevos Mercados\n\n## Antecedentes\n\nFlujoTec Soluciones es una empresa de software con sede en Valencia que lleva cinco años creciendo en el mercado nacional. Desarrolla herramientas de automatización de procesos para pymes y factura 45 millones de euros al año.\n\n## El reto\n\nLa competencia nacional aumenta y la dirección estudia expandirse fuera de España. Sin embargo:\n\n1. **Experiencia internacional limitada**: el equipo directivo nunca ha gestionado una expansión\n2. **Recursos ajustados**: el presupuesto disponible es de 4 millones de euros para dos años\n3. **Incertidumbre de mercado**: se desconocen las preferencias de los clientes y la competencia local\n4. **Complejidad regulatoria**: cada mercado tiene requisitos legales distintos\n\n## Opciones analizadas\n\n### Opción A: Portugal\n- Mercado cercano y culturalmente próximo\n- Tamaño reducido pero con baja competencia\n\n### Opción B: México\n- Gran mercado hispanohablante en plena digitalización\n- Volatilidad cambiaria y mayor distancia operativa\n\n### Opción C: Alemania\n- Alto poder adquisitivo y clientes exigentes\n- Competidores consolidados y fuertes requisitos de localización\n\n## Tu tarea\n\nComo consultor estratégico de FlujoTec:\n\n1. **Evalúa cada mercado** con marcos estratégicos adecuados\n2. **Recomienda el mercado** más adecuado para empezar\n3. **Diseña la estrategia de entrada**, con calendario y recursos\n4. **Identifica los riesgos** y cómo mitigarlos\n5. **Define indicadores** para medir el avance".to_string(),
+            background_info: Some("FlujoTec fue fundada en 2018 por dos ingenieras de software y ha crecido sin financiación externa hasta alcanzar 140 empleados.".to_string()),
+            problem_statement: Some("¿Cómo debe FlujoTec abordar su expansión internacional para maximizar el crecimiento y limitar el riesgo?".to_string()),
+            analysis_framework: Some("Aplicar las cinco fuerzas de Porter, el análisis DAFO y el marco CAGE (cultural, administrativa, geográfica y económica) para comparar mercados.".to_string()),
+            sample_solution: Some("Empezar por México mediante una entrada digital y un socio local de distribución, y evaluar Portugal como segunda fase de bajo riesgo.".to_string()),
+            metadata: Some(r#"{"industry": "Tecnología", "company_size": "Mediana", "case_type": "Decisión estratégica"}"#.to_string()),
+            status: Some("published".to_string()),
+            created_by: 1,
+        },
+        NewCaseStudy {
+            title: "Crisis de Fidelización de Clientes".to_string(),
+            description: Some("Una cadena de moda pierde clientes recurrentes y necesita una estrategia de fidelización eficaz".to_string()),
+            domain_id: 2,
+            template_id: None,
+            difficulty_level: Some("beginner".to_string()),
+            estimated_duration: Some(60),
+            learning_objectives: Some(r#"["Identificar los factores de fidelización", "Analizar el comportamiento del cliente", "Diseñar programas de fidelización", "Medir su eficacia"]"#.to_string()),
+            tags: Some(r#"["fidelizacion", "clientes", "marketing", "analitica"]"#.to_string()),
+            content: "# Crisis de Fidelización en ModaViva\n\n## La empresa\n\nModaViva es una cadena de moda joven con 40 tiendas en España y una tienda online en crecimiento.\n\n## El problema\n\nEn los últimos 18 meses:\n\n- La tasa de recompra ha caído del 62 % al 44 %\n- El valor de vida del cliente ha bajado un 30 %\n- El coste de captación ha subido un 25 %\n\n## Segmentos de clientes\n\n1. **Embajadores (20 %)**: compran a menudo y recomiendan la marca\n2. **Ocasionales (50 %)**: compran dos o tres veces al año, sobre todo en rebajas\n3. **De una sola compra (30 %)**: no han vuelto tras su primera compra\n\n## Tu reto\n\nComo nueva responsable de experiencia de cliente, debes:\n\n1. **Analizar las causas** de la pérdida de clientes\n2. **Diseñar una estrategia de fidelización** para cada segmento\n3. **Proponer un calendario** y los recursos necesarios\n4. **Fijar indicadores** para medir resultados\n\nEl presupuesto es de 450.000 euros y se esperan resultados en seis meses.".to_string(),
+            background_info: Some("ModaViva ha dedicado casi todo su presupuesto de marketing a captar clientes en redes sociales y apenas dispone de analítica de clientes.".to_string()),
+            problem_statement: Some("¿Cómo puede ModaViva invertir la caída de la fidelización y convertirla en una ventaja competitiva?".to_string()),
+            analysis_framework: Some("Usar el análisis del ciclo de vida del cliente, la segmentación RFM y el Net Promoter Score.".to_string()),
+            sample_solution: Some("Lanzar un programa de fidelización por niveles con recompensas personalizadas, mejorar la atención al cliente y crear campañas de reactivación por segmento.".to_string()),
+            metadata: Some(r#"{"industry": "Comercio minorista", "company_size": "Mediana", "case_type": "Estrategia de marketing"}"#.to_string()),
+            status: Some("published".to_string()),
+            created_by: 1,
+        },
+        NewCaseStudy {
+            title: "Decisión de Reestructuración Financiera".to_string(),
+            description: Some("Una empresa industrial en dificultades debe decidir cómo reestructurar su deuda".to_string()),
+            domain_id: 3,
+            template_id: None,
+            difficulty_level: Some("advanced".to_string()),
+            estimated_duration: Some(120),
+            learning_objectives: Some(r#"["Analizar estados financieros", "Evaluar opciones de reestructuración", "Valorar el impacto en los grupos de interés", "Recomendar una estructura de capital"]"#.to_string()),
+            tags: Some(r#"["finanzas", "reestructuracion", "deuda", "estructura-de-capital"]"#.to_string()),
+            content: "# Reestructuración Financiera en Industrias Ebro\n\n## La empresa\n\nIndustrias Ebro fabrica componentes de automoción en Zaragoza, emplea a 750 personas y factura 180 millones de euros.\n\n## Situación financiera\n\n- Deuda total: 140 millones de euros\n- Tesorería: 7 millones de euros\n- Consumo mensual de caja: 2,5 millones de euros\n- Incumplimiento inminente de covenants bancarios\n\n## Opciones\n\n### Opción 1: Acuerdo extrajudicial de refinanciación\n- Quitas y esperas negociadas con los acreedores\n- Venta de activos no estratégicos\n\n### Opción 2: Concurso de acreedores\n- Reorganización bajo supervisión judicial\n- Posibilidad de reducir la deuda de forma significativa\n\n### Opción 3: Venta o fusión\n- Entrada de un fondo de capital riesgo\n- Fusión con un competidor del sector\n\n## Tu encargo\n\nComo asesor financiero del consejo de administración:\n\n1. **Analiza la situación** financiera actual\n2. **Evalúa cada opción** y su impacto en los grupos de interés\n3. **Proyecta los resultados** del escenario recomendado\n4. **Elabora un plan de implantación** y de comunicación\n5. **Identifica los riesgos** y los planes de contingencia".to_string(),
+            background_info: Some("Industrias Ebro es una empresa familiar que se endeudó en 2019 para ampliar sus plantas justo antes de la caída del mercado.".to_string()),
+            problem_statement: Some("¿Qué estrategia de reestructuración maximiza el valor para los grupos de interés y garantiza la continuidad del negocio?".to_string()),
+            analysis_framework: Some("Aplicar análisis de ratios, descuento de flujos de caja, la Z de Altman y el análisis de grupos de interés.".to_string()),
+            sample_solution: Some("Recomendar un acuerdo extrajudicial con capitalización parcial de deuda, venta de activos no estratégicos y un plan de mejora operativa.".to_string()),
+            metadata: Some(r#"{"industry": "Industria", "company_size": "Grande", "case_type": "Análisis financiero"}"#.to_string()),
+            status: Some("published".to_string()),
+            created_by: 1,
+        },
+    ]
+}
@@ -1,13 +1,22 @@
 // Database seeding system for sample data
 
-use crate::database::{DatabaseManager, models::*};
+use crate::database::{DatabaseManager, Validator, models::*};
 use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use sqlx::query_builder::Separated;
+use sqlx::{Connection, QueryBuilder, Sqlite, SqliteConnection};
 use std::collections::HashMap;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 
 pub mod sample_data;
+pub mod locales;
 pub mod commands;
+pub mod progress;
+
+pub use progress::{NoopSeedProgress, SeedProgress, SeedProgressSink, SeedStage, SEED_PROGRESS_EVENT};
 
 #[derive(Error, Debug)]
 pub enum SeedError {
@@ -19,10 +28,18 @@ pub enum SeedError {
     Seeding(String),
     #[error("Validation error: {0}")]
     Validation(String),
+    #[error("Seeding cancelled")]
+    Cancelled,
 }
 
 pub type Result<T> = std::result::Result<T, SeedError>;
 
+/// Tables kept by a reset: applied migrations are not re-run and the audit trail is append-only
+const PRESERVED_TABLES: &[&str] = &["schema_migrations", "audit_log"];
+
+/// Rows per multi-row `INSERT`, keeping bound parameters well under SQLite's limit
+const BATCH_ROWS: usize = 500;
+
 /// Configuration for database seeding
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SeedConfig {
@@ -37,6 +54,19 @@ pub struct SeedConfig {
     pub case_studies_per_domain: usize,
     pub questions_per_case_study: usize,
     pub max_users: usize,
+    /// Seed for generated variation; the same seed reproduces the same data
+    #[serde(default)]
+    pub random_seed: Option<u64>,
+    /// Locale of the sample data pack, e.g. "en-GB"; unknown locales use en-US
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Plan the seed and report what it would create without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_locale() -> String {
+    sample_data::DEFAULT_LOCALE.to_string()
 }
 
 impl Default for SeedConfig {
@@ -53,6 +83,9 @@ impl Default for SeedConfig {
             case_studies_per_domain: 5,
             questions_per_case_study: 3,
             max_users: 10,
+            random_seed: None,
+            locale: default_locale(),
+            dry_run: false,
         }
     }
 }
@@ -69,20 +102,50 @@ pub struct SeedStats {
     pub collections_created: usize,
     pub total_records_created: usize,
     pub duration_ms: u64,
+    /// Tables written, in order, each in its own transaction
+    #[serde(default)]
+    pub table_transactions: Vec<String>,
+    /// Counts are planned rather than created
+    #[serde(default)]
+    pub dry_run: bool,
+    /// The database was (or, in a dry run, would be) reset first
+    #[serde(default)]
+    pub database_reset: bool,
 }
 
 /// Database seeder for creating sample data
 pub struct DatabaseSeeder {
     database_manager: std::sync::Arc<DatabaseManager>,
+    table_transactions: std::sync::Mutex<Vec<String>>,
+    /// Row ids written by the current run per table, so a cancelled run can be undone
+    inserted_rows: std::sync::Mutex<Vec<(String, Vec<i64>)>>,
+    dry_run: std::sync::atomic::AtomicBool,
 }
 
 impl DatabaseSeeder {
     pub fn new(database_manager: std::sync::Arc<DatabaseManager>) -> Self {
-        Self { database_manager }
+        Self {
+            database_manager,
+            table_transactions: std::sync::Mutex::new(Vec::new()),
+            inserted_rows: std::sync::Mutex::new(Vec::new()),
+            dry_run: std::sync::atomic::AtomicBool::new(false),
+        }
     }
 
     /// Run the complete seeding process
     pub async fn seed(&self, config: SeedConfig) -> Result<SeedStats> {
+        self.seed_with_progress(config, &NoopSeedProgress, &CancellationToken::new()).await
+    }
+
+    /// Run the seeding process, reporting each completed stage to `progress`. Cancelling
+    /// `cancel` stops seeding before the next stage and removes every row this run
+    /// inserted, then returns `SeedError::Cancelled`. A reset that already ran is not undone.
+    pub async fn seed_with_progress(
+        &self,
+        config: SeedConfig,
+        progress: &dyn SeedProgressSink,
+        cancel: &CancellationToken,
+    ) -> Result<SeedStats> {
         let start_time = std::time::Instant::now();
         let mut stats = SeedStats {
             users_created: 0,
@@ -94,71 +157,102 @@ impl DatabaseSeeder {
             collections_created: 0,
             total_records_created: 0,
             duration_ms: 0,
+            table_transactions: Vec::new(),
+            dry_run: config.dry_run,
+            database_reset: false,
         };
+        self.table_transactions.lock().unwrap().clear();
+        self.inserted_rows.lock().unwrap().clear();
+        self.dry_run.store(config.dry_run, std::sync::atomic::Ordering::SeqCst);
 
-        println!("Starting database seeding with config: {:?}", config);
+        tracing::info!("Starting database seeding with config: {:?}", config);
+
+        if cancel.is_cancelled() {
+            return Err(SeedError::Cancelled);
+        }
 
         // Reset database if requested
         if config.reset_database {
-            self.reset_database().await?;
-            println!("Database reset completed");
+            if !config.dry_run {
+                self.reset_database().await?;
+                tracing::info!("Database reset completed");
+            }
+            stats.database_reset = true;
+            self.finish_stage(SeedStage::Reset, 0, 0, progress, cancel).await?;
         }
 
         // Check if data already exists
         let existing_data = self.check_existing_data().await?;
         if existing_data && !config.reset_database {
-            println!("Sample data already exists. Use reset_database=true to recreate.");
+            tracing::info!("Sample data already exists. Use reset_database=true to recreate.");
             return Ok(stats);
         }
 
+        let mut rng = config.random_seed.map(StdRng::seed_from_u64);
+
         // Seed in dependency order
         let mut user_ids = Vec::new();
         let mut domain_ids = Vec::new();
         let mut case_study_ids = Vec::new();
+        let mut total = 0;
 
         // 1. Seed users first (needed for created_by fields)
         if config.seed_users {
-            user_ids = self.seed_users(config.max_users).await?;
+            user_ids = self.seed_users(&config.locale, config.max_users).await?;
             stats.users_created = user_ids.len();
-            println!("Created {} users", stats.users_created);
+            tracing::info!("Created {} users", stats.users_created);
+            total += stats.users_created;
+            self.finish_stage(SeedStage::Users, stats.users_created, total, progress, cancel).await?;
         }
 
         // 2. Seed domains
         if config.seed_domains {
-            domain_ids = self.seed_domains().await?;
+            domain_ids = self.seed_domains(&config.locale).await?;
             stats.domains_created = domain_ids.len();
-            println!("Created {} domains", stats.domains_created);
+            tracing::info!("Created {} domains", stats.domains_created);
+            total += stats.domains_created;
+            self.finish_stage(SeedStage::Domains, stats.domains_created, total, progress, cancel).await?;
         }
 
         // 3. Seed app settings
         if config.seed_app_settings {
             stats.settings_created = self.seed_app_settings().await?;
-            println!("Created {} app settings", stats.settings_created);
+            tracing::info!("Created {} app settings", stats.settings_created);
+            total += stats.settings_created;
+            self.finish_stage(SeedStage::AppSettings, stats.settings_created, total, progress, cancel).await?;
         }
 
         // 4. Seed case studies (requires users and domains)
         if config.seed_case_studies && !user_ids.is_empty() && !domain_ids.is_empty() {
-            case_study_ids = self.seed_case_studies(&user_ids, &domain_ids, config.case_studies_per_domain).await?;
+            case_study_ids = self.seed_case_studies(&config.locale, &user_ids, &domain_ids, config.case_studies_per_domain).await?;
             stats.case_studies_created = case_study_ids.len();
-            println!("Created {} case studies", stats.case_studies_created);
+            tracing::info!("Created {} case studies", stats.case_studies_created);
+            total += stats.case_studies_created;
+            self.finish_stage(SeedStage::CaseStudies, stats.case_studies_created, total, progress, cancel).await?;
         }
 
         // 5. Seed assessment questions (requires case studies)
         if config.seed_assessment_questions && !case_study_ids.is_empty() {
             stats.questions_created = self.seed_assessment_questions(&case_study_ids, config.questions_per_case_study).await?;
-            println!("Created {} assessment questions", stats.questions_created);
+            tracing::info!("Created {} assessment questions", stats.questions_created);
+            total += stats.questions_created;
+            self.finish_stage(SeedStage::AssessmentQuestions, stats.questions_created, total, progress, cancel).await?;
         }
 
         // 6. Seed user progress (requires users and case studies)
         if config.seed_user_progress && !user_ids.is_empty() && !case_study_ids.is_empty() {
-            stats.progress_records_created = self.seed_user_progress(&user_ids, &case_study_ids).await?;
-            println!("Created {} user progress records", stats.progress_records_created);
+            stats.progress_records_created = self.seed_user_progress(&user_ids, &case_study_ids, rng.as_mut()).await?;
+            tracing::info!("Created {} user progress records", stats.progress_records_created);
+            total += stats.progress_records_created;
+            self.finish_stage(SeedStage::UserProgress, stats.progress_records_created, total, progress, cancel).await?;
         }
 
         // 7. Seed collections (requires users and case studies)
         if config.seed_collections && !user_ids.is_empty() && !case_study_ids.is_empty() {
             stats.collections_created = self.seed_collections(&user_ids, &case_study_ids).await?;
-            println!("Created {} collections", stats.collections_created);
+            tracing::info!("Created {} collections", stats.collections_created);
+            total += stats.collections_created;
+            self.finish_stage(SeedStage::Collections, stats.collections_created, total, progress, cancel).await?;
         }
 
         stats.total_records_created = stats.users_created + stats.domains_created + 
@@ -166,42 +260,128 @@ impl DatabaseSeeder {
             stats.progress_records_created + stats.collections_created;
 
         stats.duration_ms = start_time.elapsed().as_millis() as u64;
+        stats.table_transactions = std::mem::take(&mut *self.table_transactions.lock().unwrap());
+        self.inserted_rows.lock().unwrap().clear();
+
+        tracing::info!("Database seeding completed in {}ms", stats.duration_ms);
+        tracing::info!("Total records created: {}", stats.total_records_created);
 
-        println!("Database seeding completed in {}ms", stats.duration_ms);
-        println!("Total records created: {}", stats.total_records_created);
+        progress.report(&SeedProgress {
+            stage: SeedStage::Completed,
+            records_created: 0,
+            total_records_created: stats.total_records_created,
+        });
 
         Ok(stats)
     }
 
-    /// Reset database by clearing all tables
-    async fn reset_database(&self) -> Result<()> {
-        let tables = vec![
-            "collection_case_studies",
-            "collections", 
-            "user_progress",
-            "assessment_questions",
-            "generation_history",
-            "attachments",
-            "case_studies",
-            "configuration_templates",
-            "app_settings",
-            "domains",
-            "users"
-        ];
+    /// Report a finished stage, then stop if cancellation was requested
+    async fn finish_stage(
+        &self,
+        stage: SeedStage,
+        records_created: usize,
+        total_records_created: usize,
+        progress: &dyn SeedProgressSink,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        progress.report(&SeedProgress { stage, records_created, total_records_created });
+
+        if cancel.is_cancelled() {
+            tracing::info!("Seeding cancelled after {:?}, removing inserted rows", stage);
+            self.remove_inserted_rows().await?;
+            return Err(SeedError::Cancelled);
+        }
+        Ok(())
+    }
 
-        for table in tables {
-            let query = format!("DELETE FROM {}", table);
-            sqlx::query(&query)
-                .execute(self.database_manager.pool())
-                .await?;
+    /// Delete every row this run inserted, newest table first, in one transaction
+    async fn remove_inserted_rows(&self) -> Result<()> {
+        let inserted = std::mem::take(&mut *self.inserted_rows.lock().unwrap());
+        if inserted.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.database_manager.pool().begin().await?;
+        for (table, ids) in inserted.iter().rev() {
+            for chunk in ids.chunks(BATCH_ROWS) {
+                let mut query = QueryBuilder::<Sqlite>::new(format!("DELETE FROM {} WHERE rowid IN (", table));
+                let mut separated = query.separated(", ");
+                for id in chunk {
+                    separated.push_bind(*id);
+                }
+                separated.push_unseparated(")");
+                query.build().execute(&mut *tx).await?;
+            }
         }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Reset database by clearing all tables in one transaction, so a failure part way
+    /// through leaves every table untouched
+    async fn reset_database(&self) -> Result<()> {
+        // PRAGMA foreign_keys is a no-op inside a transaction, so it has to be toggled on the
+        // connection before the transaction starts and restored before the connection goes back
+        // to the pool.
+        let mut conn = self.database_manager.pool().acquire().await?;
+        sqlx::query("PRAGMA foreign_keys = OFF").execute(&mut *conn).await?;
+
+        let result: Result<()> = async {
+            let mut tx = conn.begin().await?;
+
+            for table in Self::deletion_order(&mut tx).await? {
+                sqlx::query(&format!("DELETE FROM \"{}\"", table))
+                    .execute(&mut *tx)
+                    .await?;
+            }
 
-        // Reset auto-increment sequences
-        sqlx::query("DELETE FROM sqlite_sequence")
-            .execute(self.database_manager.pool())
+            // Reset auto-increment sequences
+            let has_sequences: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence')"
+            )
+            .fetch_one(&mut *tx)
             .await?;
+            if has_sequences {
+                sqlx::query("DELETE FROM sqlite_sequence").execute(&mut *tx).await?;
+            }
 
-        Ok(())
+            tx.commit().await?;
+            Ok(())
+        }
+        .await;
+
+        sqlx::query("PRAGMA foreign_keys = ON").execute(&mut *conn).await?;
+        result
+    }
+
+    /// Every table in the schema except bookkeeping ones, ordered so tables are cleared
+    /// before the tables they reference
+    async fn deletion_order(conn: &mut SqliteConnection) -> Result<Vec<String>> {
+        let tables: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT name FROM pragma_table_list
+            WHERE schema = 'main' AND type IN ('table', 'virtual') AND name NOT LIKE 'sqlite_%'
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let references: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT m.name, fk."table"
+            FROM sqlite_master m, pragma_foreign_key_list(m.name) fk
+            WHERE m.type = 'table'
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let tables = tables
+            .into_iter()
+            .filter(|table| !PRESERVED_TABLES.contains(&table.as_str()))
+            .collect();
+        Ok(children_first(tables, &references))
     }
 
     /// Check if sample data already exists
@@ -221,195 +401,225 @@ impl DatabaseSeeder {
         Ok(user_count.0 > 0 || domain_count.0 > 0 || case_study_count.0 > 0)
     }
 
-    /// Seed sample users
-    async fn seed_users(&self, max_users: usize) -> Result<Vec<i64>> {
-        let sample_users = sample_data::get_sample_users(max_users);
-        let mut user_ids = Vec::new();
+    /// Insert rows with multi-row `INSERT` statements inside a single transaction,
+    /// returning the new row ids in insertion order. A dry run writes nothing and
+    /// returns placeholder ids so dependent tables can still be planned.
+    async fn insert_batched<'a, T>(
+        &self,
+        table: &str,
+        columns: &str,
+        rows: &'a [T],
+        mut push_row: impl FnMut(Separated<'_, 'a, Sqlite, &'static str>, &'a T),
+    ) -> Result<Vec<i64>> {
+        if self.dry_run.load(std::sync::atomic::Ordering::SeqCst) {
+            return Ok((1..=rows.len() as i64).collect());
+        }
 
-        for user_data in sample_users {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO users (username, email, full_name, password_hash, role, preferences, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-                "#
-            )
-            .bind(&user_data.username)
-            .bind(&user_data.email)
-            .bind(&user_data.full_name)
-            .bind(&user_data.password_hash)
-            .bind(user_data.role.unwrap_or_else(|| "user".to_string()))
-            .bind(&user_data.preferences)
-            .execute(self.database_manager.pool())
-            .await?;
+        let mut tx = self.database_manager.pool().begin().await?;
+        let mut ids = Vec::with_capacity(rows.len());
+
+        for chunk in rows.chunks(BATCH_ROWS) {
+            let mut query = QueryBuilder::<Sqlite>::new(format!("INSERT INTO {} ({}) ", table, columns));
+            query.push_values(chunk, |row, item| push_row(row, item));
+            query.push(" RETURNING rowid");
 
-            user_ids.push(result.last_insert_rowid());
+            // RETURNING order is unspecified, but rowids grow with insertion order
+            let mut chunk_ids: Vec<i64> = query.build_query_scalar().fetch_all(&mut *tx).await?;
+            chunk_ids.sort_unstable();
+            ids.extend(chunk_ids);
         }
 
-        Ok(user_ids)
+        tx.commit().await?;
+        self.table_transactions.lock().unwrap().push(table.to_string());
+        self.inserted_rows.lock().unwrap().push((table.to_string(), ids.clone()));
+        Ok(ids)
     }
 
-    /// Seed sample domains
-    async fn seed_domains(&self) -> Result<Vec<i64>> {
-        let sample_domains = sample_data::get_sample_domains();
-        let mut domain_ids = Vec::new();
-
-        for domain_data in sample_domains {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO domains (name, description, color, icon, created_at)
-                VALUES (?, ?, ?, ?, datetime('now'))
-                "#
-            )
-            .bind(&domain_data.name)
-            .bind(&domain_data.description)
-            .bind(&domain_data.color)
-            .bind(&domain_data.icon)
-            .execute(self.database_manager.pool())
-            .await?;
+    /// Seed sample users
+    async fn seed_users(&self, locale: &str, max_users: usize) -> Result<Vec<i64>> {
+        let sample_users = sample_data::get_sample_users(locale, max_users);
+
+        self.insert_batched(
+            "users",
+            "username, email, full_name, password_hash, role, preferences, created_at, updated_at",
+            &sample_users,
+            |mut row, user_data| {
+                row.push_bind(&user_data.username)
+                    .push_bind(&user_data.email)
+                    .push_bind(&user_data.full_name)
+                    .push_bind(&user_data.password_hash)
+                    .push_bind(user_data.role.clone().unwrap_or_else(|| "user".to_string()))
+                    .push_bind(&user_data.preferences)
+                    .push("datetime('now')")
+                    .push("datetime('now')");
+            },
+        ).await
+    }
 
-            domain_ids.push(result.last_insert_rowid());
-        }
+    /// Seed sample domains
+    async fn seed_domains(&self, locale: &str) -> Result<Vec<i64>> {
+        let sample_domains = sample_data::get_sample_domains(locale);
 
-        Ok(domain_ids)
+        self.insert_batched(
+            "domains",
+            "name, description, color, icon, created_at",
+            &sample_domains,
+            |mut row, domain_data| {
+                row.push_bind(&domain_data.name)
+                    .push_bind(&domain_data.description)
+                    .push_bind(&domain_data.color)
+                    .push_bind(&domain_data.icon)
+                    .push("datetime('now')");
+            },
+        ).await
     }
 
     /// Seed sample case studies
-    async fn seed_case_studies(&self, user_ids: &[i64], domain_ids: &[i64], per_domain: usize) -> Result<Vec<i64>> {
-        let mut case_study_ids = Vec::new();
-        let sample_case_studies = sample_data::get_sample_case_studies();
+    async fn seed_case_studies(&self, locale: &str, user_ids: &[i64], domain_ids: &[i64], per_domain: usize) -> Result<Vec<i64>> {
+        let sample_case_studies = sample_data::get_sample_case_studies(locale);
+        if sample_case_studies.is_empty() {
+            return Ok(Vec::new());
+        }
 
+        let mut rows = Vec::with_capacity(domain_ids.len() * per_domain);
         for (domain_index, &domain_id) in domain_ids.iter().enumerate() {
             for i in 0..per_domain {
-                if let Some(case_study_template) = sample_case_studies.get(i % sample_case_studies.len()) {
-                    let created_by = user_ids[i % user_ids.len()];
-                    
-                    // Customize case study for the domain
-                    let customized_case_study = sample_data::customize_case_study_for_domain(
-                        case_study_template, 
-                        domain_index, 
-                        i
-                    );
-
-                    let result = sqlx::query(
-                        r#"
-                        INSERT INTO case_studies (
-                            title, description, domain_id, difficulty_level, estimated_duration,
-                            learning_objectives, tags, content, background_info, problem_statement,
-                            analysis_framework, sample_solution, metadata, status, created_by,
-                            created_at, updated_at
-                        )
-                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-                        "#
-                    )
-                    .bind(&customized_case_study.title)
-                    .bind(&customized_case_study.description)
-                    .bind(domain_id)
-                    .bind(&customized_case_study.difficulty_level)
-                    .bind(customized_case_study.estimated_duration)
-                    .bind(&customized_case_study.learning_objectives)
-                    .bind(&customized_case_study.tags)
-                    .bind(&customized_case_study.content)
-                    .bind(&customized_case_study.background_info)
-                    .bind(&customized_case_study.problem_statement)
-                    .bind(&customized_case_study.analysis_framework)
-                    .bind(&customized_case_study.sample_solution)
-                    .bind(&customized_case_study.metadata)
-                    .bind(customized_case_study.status.unwrap_or_else(|| "published".to_string()))
-                    .bind(created_by)
-                    .execute(self.database_manager.pool())
-                    .await?;
-
-                    case_study_ids.push(result.last_insert_rowid());
-                }
+                let case_study_template = &sample_case_studies[i % sample_case_studies.len()];
+                let created_by = user_ids[i % user_ids.len()];
+
+                // Customize case study for the domain
+                let customized_case_study = sample_data::customize_case_study_for_domain(
+                    case_study_template, 
+                    domain_index, 
+                    i
+                );
+                rows.push((domain_id, created_by, customized_case_study));
             }
         }
 
-        Ok(case_study_ids)
+        self.insert_batched(
+            "case_studies",
+            "title, description, domain_id, difficulty_level, estimated_duration, \
+             learning_objectives, tags, content, background_info, problem_statement, \
+             analysis_framework, sample_solution, metadata, status, created_by, \
+             created_at, updated_at",
+            &rows,
+            |mut row, (domain_id, created_by, case_study)| {
+                row.push_bind(&case_study.title)
+                    .push_bind(&case_study.description)
+                    .push_bind(*domain_id)
+                    .push_bind(&case_study.difficulty_level)
+                    .push_bind(case_study.estimated_duration)
+                    .push_bind(&case_study.learning_objectives)
+                    .push_bind(&case_study.tags)
+                    .push_bind(&case_study.content)
+                    .push_bind(&case_study.background_info)
+                    .push_bind(&case_study.problem_statement)
+                    .push_bind(&case_study.analysis_framework)
+                    .push_bind(&case_study.sample_solution)
+                    .push_bind(&case_study.metadata)
+                    .push_bind(case_study.status.clone().unwrap_or_else(|| "published".to_string()))
+                    .push_bind(*created_by)
+                    .push("datetime('now')")
+                    .push("datetime('now')");
+            },
+        ).await
     }
 
     /// Seed sample assessment questions
     async fn seed_assessment_questions(&self, case_study_ids: &[i64], per_case_study: usize) -> Result<usize> {
-        let mut total_questions = 0;
         let sample_questions = sample_data::get_sample_assessment_questions();
+        if sample_questions.is_empty() {
+            return Ok(0);
+        }
+        for question in &sample_questions {
+            Validator::validate_new_assessment_question(question)
+                .map_err(|e| SeedError::Validation(format!("Sample question '{}': {}", question.question_text, e)))?;
+        }
 
+        let mut rows = Vec::with_capacity(case_study_ids.len() * per_case_study);
         for &case_study_id in case_study_ids {
             for i in 0..per_case_study {
-                if let Some(question_template) = sample_questions.get(i % sample_questions.len()) {
-                    let customized_question = sample_data::customize_question_for_case_study(
-                        question_template,
-                        case_study_id,
-                        i
-                    );
-
-                    sqlx::query(
-                        r#"
-                        INSERT INTO assessment_questions (
-                            case_study_id, question_text, question_type, options,
-                            correct_answer, sample_answer, rubric, points, order_index,
-                            is_required, created_at
-                        )
-                        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
-                        "#
-                    )
-                    .bind(case_study_id)
-                    .bind(&customized_question.question_text)
-                    .bind(&customized_question.question_type)
-                    .bind(&customized_question.options)
-                    .bind(&customized_question.correct_answer)
-                    .bind(&customized_question.sample_answer)
-                    .bind(&customized_question.rubric)
-                    .bind(customized_question.points.unwrap_or(10))
-                    .bind(customized_question.order_index.unwrap_or(i as i64))
-                    .bind(customized_question.is_required.unwrap_or(true))
-                    .execute(self.database_manager.pool())
-                    .await?;
-
-                    total_questions += 1;
-                }
+                let question_template = &sample_questions[i % sample_questions.len()];
+                let customized_question = sample_data::customize_question_for_case_study(
+                    question_template,
+                    case_study_id,
+                    i
+                );
+                rows.push((i, customized_question));
             }
         }
 
-        Ok(total_questions)
+        let ids = self.insert_batched(
+            "assessment_questions",
+            "case_study_id, question_text, question_type, options, correct_answer, \
+             sample_answer, rubric, points, order_index, is_required, created_at",
+            &rows,
+            |mut row, (i, question)| {
+                row.push_bind(question.case_study_id)
+                    .push_bind(&question.question_text)
+                    .push_bind(&question.question_type)
+                    .push_bind(&question.options)
+                    .push_bind(&question.correct_answer)
+                    .push_bind(&question.sample_answer)
+                    .push_bind(&question.rubric)
+                    .push_bind(question.points.unwrap_or(10))
+                    .push_bind(question.order_index.unwrap_or(*i as i64))
+                    .push_bind(question.is_required.unwrap_or(true))
+                    .push("datetime('now')");
+            },
+        ).await?;
+
+        Ok(ids.len())
     }
 
     /// Seed sample app settings
     async fn seed_app_settings(&self) -> Result<usize> {
         let sample_settings = sample_data::get_sample_app_settings();
-        let mut settings_created = 0;
-
-        for setting_data in sample_settings {
-            sqlx::query(
-                r#"
-                INSERT INTO app_settings (key, value, data_type, description, is_user_configurable, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-                "#
-            )
-            .bind(&setting_data.key)
-            .bind(&setting_data.value)
-            .bind(setting_data.data_type.unwrap_or_else(|| "string".to_string()))
-            .bind(&setting_data.description)
-            .bind(setting_data.is_user_configurable.unwrap_or(false))
-            .execute(self.database_manager.pool())
-            .await?;
-
-            settings_created += 1;
-        }
 
-        Ok(settings_created)
+        let ids = self.insert_batched(
+            "app_settings",
+            "key, value, data_type, description, is_user_configurable, created_at, updated_at",
+            &sample_settings,
+            |mut row, setting_data| {
+                row.push_bind(&setting_data.key)
+                    .push_bind(&setting_data.value)
+                    .push_bind(setting_data.data_type.clone().unwrap_or_else(|| "string".to_string()))
+                    .push_bind(&setting_data.description)
+                    .push_bind(setting_data.is_user_configurable.unwrap_or(false))
+                    .push("datetime('now')")
+                    .push("datetime('now')");
+            },
+        ).await?;
+
+        Ok(ids.len())
     }
 
     /// Seed sample user progress
-    async fn seed_user_progress(&self, user_ids: &[i64], case_study_ids: &[i64]) -> Result<usize> {
-        let mut progress_created = 0;
+    async fn seed_user_progress(&self, user_ids: &[i64], case_study_ids: &[i64], mut rng: Option<&mut StdRng>) -> Result<usize> {
+        let mut rows = Vec::new();
 
         // Create progress for some user-case study combinations
         for (user_index, &user_id) in user_ids.iter().enumerate() {
-            let case_studies_to_assign = case_study_ids.len().min(3 + user_index % 5); // Vary assignment count
+            // Vary assignment count
+            let extra = match rng.as_deref_mut() {
+                Some(rng) => rng.gen_range(0..5),
+                None => user_index % 5,
+            };
+            let case_studies_to_assign = case_study_ids.len().min(3 + extra);
             
             for i in 0..case_studies_to_assign {
                 let case_study_id = case_study_ids[i % case_study_ids.len()];
-                let progress_data = sample_data::generate_user_progress(user_id, case_study_id, i);
+                rows.push(sample_data::generate_user_progress(user_id, case_study_id, i, rng.as_deref_mut()));
+            }
+        }
 
+        let ids = self.insert_batched(
+            "user_progress",
+            "user_id, case_study_id, status, time_spent, answers, score, feedback, notes, \
+             started_at, completed_at, last_accessed, created_at",
+            &rows,
+            |mut row, progress_data| {
                 // Determine completed_at based on status
                 let completed_at = if progress_data.status.as_deref() == Some("completed") || progress_data.status.as_deref() == Some("reviewed") {
                     Some("2024-01-15T10:30:00Z")
@@ -417,78 +627,70 @@ impl DatabaseSeeder {
                     None
                 };
 
-                sqlx::query(
-                    r#"
-                    INSERT INTO user_progress (
-                        user_id, case_study_id, status, time_spent, answers, score,
-                        feedback, notes, started_at, completed_at, last_accessed, created_at
-                    )
-                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'))
-                    "#
-                )
-                .bind(progress_data.user_id)
-                .bind(progress_data.case_study_id)
-                .bind(&progress_data.status)
-                .bind(progress_data.time_spent)
-                .bind(&progress_data.answers)
-                .bind(progress_data.score)
-                .bind(&progress_data.feedback)
-                .bind(&progress_data.notes)
-                .bind(progress_data.started_at)
-                .bind(completed_at)
-                .execute(self.database_manager.pool())
-                .await?;
-
-                progress_created += 1;
-            }
-        }
-
-        Ok(progress_created)
+                row.push_bind(progress_data.user_id)
+                    .push_bind(progress_data.case_study_id)
+                    .push_bind(&progress_data.status)
+                    .push_bind(progress_data.time_spent)
+                    .push_bind(&progress_data.answers)
+                    .push_bind(progress_data.score)
+                    .push_bind(&progress_data.feedback)
+                    .push_bind(&progress_data.notes)
+                    .push_bind(progress_data.started_at)
+                    .push_bind(completed_at)
+                    .push("datetime('now')")
+                    .push("datetime('now')");
+            },
+        ).await?;
+
+        Ok(ids.len())
     }
 
     /// Seed sample collections
     async fn seed_collections(&self, user_ids: &[i64], case_study_ids: &[i64]) -> Result<usize> {
-        let sample_collections = sample_data::get_sample_collections();
-        let mut collections_created = 0;
-
-        for collection_data in sample_collections {
-            let created_by = user_ids[collections_created % user_ids.len()];
-
-            let result = sqlx::query(
-                r#"
-                INSERT INTO collections (name, description, is_public, created_by, created_at, updated_at)
-                VALUES (?, ?, ?, ?, datetime('now'), datetime('now'))
-                "#
-            )
-            .bind(&collection_data.name)
-            .bind(&collection_data.description)
-            .bind(collection_data.is_public.unwrap_or(true))
-            .bind(created_by)
-            .execute(self.database_manager.pool())
-            .await?;
-
-            let collection_id = result.last_insert_rowid();
-
-            // Add some case studies to each collection
-            let case_studies_per_collection = case_study_ids.len().min(5);
-            for (index, &case_study_id) in case_study_ids.iter().take(case_studies_per_collection).enumerate() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO collection_case_studies (collection_id, case_study_id, order_index, added_at)
-                    VALUES (?, ?, ?, datetime('now'))
-                    "#
-                )
-                .bind(collection_id)
-                .bind(case_study_id)
-                .bind(index as i64)
-                .execute(self.database_manager.pool())
-                .await?;
-            }
-
-            collections_created += 1;
-        }
-
-        Ok(collections_created)
+        let sample_collections: Vec<(i64, NewCollection)> = sample_data::get_sample_collections()
+            .into_iter()
+            .enumerate()
+            .map(|(index, collection_data)| (user_ids[index % user_ids.len()], collection_data))
+            .collect();
+
+        let collection_ids = self.insert_batched(
+            "collections",
+            "name, description, is_public, created_by, created_at, updated_at",
+            &sample_collections,
+            |mut row, (created_by, collection_data)| {
+                row.push_bind(&collection_data.name)
+                    .push_bind(&collection_data.description)
+                    .push_bind(collection_data.is_public.unwrap_or(true))
+                    .push_bind(*created_by)
+                    .push("datetime('now')")
+                    .push("datetime('now')");
+            },
+        ).await?;
+
+        // Add some case studies to each collection
+        let case_studies_per_collection = case_study_ids.len().min(5);
+        let memberships: Vec<(i64, i64, i64)> = collection_ids.iter()
+            .flat_map(|&collection_id| {
+                case_study_ids.iter()
+                    .take(case_studies_per_collection)
+                    .enumerate()
+                    .map(move |(index, &case_study_id)| (collection_id, case_study_id, index as i64))
+            })
+            .collect();
+
+        self.insert_batched(
+            "collection_case_studies",
+            "collection_id, case_study_id, order_index, added_at",
+            &memberships,
+            |mut row, &(collection_id, case_study_id, order_index)| {
+                row.push_bind(collection_id)
+                    .push_bind(case_study_id)
+                    .push_bind(order_index)
+                    .push("datetime('now')");
+            },
+        ).await?;
+
+        Ok(collection_ids.len())
     }
 
     /// Get seeding statistics
@@ -512,6 +714,27 @@ impl DatabaseSeeder {
     }
 }
 
+/// Order tables so each comes before any table it references (child, parent pairs);
+/// tables in a reference cycle keep their original order at the end
+fn children_first(mut remaining: Vec<String>, references: &[(String, String)]) -> Vec<String> {
+    let mut ordered = Vec::with_capacity(remaining.len());
+
+    loop {
+        let next = remaining.iter().position(|table| {
+            !references.iter().any(|(child, parent)| {
+                parent == table && child != table && remaining.contains(child)
+            })
+        });
+        match next {
+            Some(index) => ordered.push(remaining.remove(index)),
+            None => break,
+        }
+    }
+
+    ordered.extend(remaining);
+    ordered
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -537,6 +760,9 @@ mod tests {
             collections_created: 3,
             total_records_created: 188,
             duration_ms: 1500,
+            table_transactions: Vec::new(),
+            dry_run: false,
+            database_reset: true,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -545,4 +771,270 @@ mod tests {
         assert_eq!(stats.users_created, deserialized.users_created);
         assert_eq!(stats.total_records_created, deserialized.total_records_created);
     }
-}
\ No newline at end of file
+
+    /// An in-memory database with the original schema the sample data is written for
+    async fn sample_database() -> std::sync::Arc<DatabaseManager> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(include_str!("../../../schema.sql")).execute(&pool).await.unwrap();
+        std::sync::Arc::new(DatabaseManager::from_pool(pool))
+    }
+
+    async fn progress_rows(seeder: &DatabaseSeeder) -> Vec<(i64, i64, String, i64, Option<f64>, Option<String>)> {
+        sqlx::query_as("SELECT user_id, case_study_id, status, time_spent, score, notes FROM user_progress ORDER BY id")
+            .fetch_all(seeder.database_manager.pool())
+            .await
+            .unwrap()
+    }
+
+    fn record_counts(stats: &SeedStats) -> [usize; 8] {
+        [
+            stats.users_created,
+            stats.domains_created,
+            stats.case_studies_created,
+            stats.questions_created,
+            stats.settings_created,
+            stats.progress_records_created,
+            stats.collections_created,
+            stats.total_records_created,
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_reproduces_sample_data() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        let config = SeedConfig {
+            reset_database: true,
+            random_seed: Some(42),
+            ..SeedConfig::default()
+        };
+
+        let first = seeder.seed(config.clone()).await.unwrap();
+        let first_rows = progress_rows(&seeder).await;
+        let second = seeder.seed(config).await.unwrap();
+        let second_rows = progress_rows(&seeder).await;
+
+        assert!(first.progress_records_created > 0);
+        assert_eq!(record_counts(&first), record_counts(&second));
+        assert_eq!(first_rows, second_rows);
+    }
+
+    #[tokio::test]
+    async fn test_large_seed_batches_one_transaction_per_table() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        let config = SeedConfig {
+            reset_database: true,
+            case_studies_per_domain: 150,
+            questions_per_case_study: 4,
+            ..SeedConfig::default()
+        };
+
+        let stats = seeder.seed(config).await.unwrap();
+
+        assert_eq!(stats.table_transactions, vec![
+            "users", "domains", "app_settings", "case_studies", "assessment_questions",
+            "user_progress", "collections", "collection_case_studies",
+        ]);
+        assert_eq!(stats.case_studies_created, stats.domains_created * 150);
+        assert_eq!(stats.questions_created, stats.case_studies_created * 4);
+
+        let counts = seeder.get_current_stats().await.unwrap();
+        assert_eq!(counts["case_studies"], stats.case_studies_created as i64);
+        assert_eq!(counts["assessment_questions"], stats.questions_created as i64);
+
+        // Every domain got its own case studies and every question points at a real case study
+        let per_domain: Vec<(i64,)> = sqlx::query_as("SELECT COUNT(*) FROM case_studies GROUP BY domain_id")
+            .fetch_all(seeder.database_manager.pool())
+            .await
+            .unwrap();
+        assert_eq!(per_domain.len(), stats.domains_created);
+        assert!(per_domain.iter().all(|(count,)| *count == 150));
+
+        let orphaned: (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM assessment_questions q LEFT JOIN case_studies c ON c.id = q.case_study_id WHERE c.id IS NULL"
+        )
+        .fetch_one(seeder.database_manager.pool())
+        .await
+        .unwrap();
+        assert_eq!(orphaned.0, 0);
+    }
+
+    async fn domain_names(seeder: &DatabaseSeeder) -> Vec<String> {
+        sqlx::query_scalar("SELECT name FROM domains ORDER BY id")
+            .fetch_all(seeder.database_manager.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_seed_uses_locale_pack() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+
+        let spanish = SeedConfig {
+            reset_database: true,
+            locale: "es-ES".to_string(),
+            ..SeedConfig::default()
+        };
+        seeder.seed(spanish).await.unwrap();
+        let names = domain_names(&seeder).await;
+        assert_eq!(names[0], "Estrategia Empresarial");
+        assert!(names.contains(&"Recursos Humanos".to_string()));
+        let admin: String = sqlx::query_scalar("SELECT full_name FROM users WHERE username = 'admin'")
+            .fetch_one(seeder.database_manager.pool())
+            .await
+            .unwrap();
+        assert_eq!(admin, "Administrador del Sistema");
+
+        seeder.seed(SeedConfig { reset_database: true, ..SeedConfig::default() }).await.unwrap();
+        assert_eq!(domain_names(&seeder).await, vec![
+            "Business Strategy", "Marketing", "Finance", "Operations",
+            "Human Resources", "Technology", "Healthcare",
+        ]);
+    }
+
+    #[test]
+    fn test_unknown_locale_falls_back_to_en_us() {
+        let names = |locale: &str| {
+            sample_data::get_sample_domains(locale).into_iter().map(|d| d.name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("fr-FR"), names(sample_data::DEFAULT_LOCALE));
+        assert_eq!(names("es_es"), names("es-ES"));
+        assert!(sample_data::get_sample_case_studies("en-GB")[0].content.contains('£'));
+    }
+
+    async fn table_counts(seeder: &DatabaseSeeder) -> Vec<(String, i64)> {
+        let mut counts: Vec<(String, i64)> = seeder.get_current_stats().await.unwrap().into_iter().collect();
+        counts.sort();
+        counts
+    }
+
+    #[tokio::test]
+    async fn test_reset_clears_every_table() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        seeder.seed(SeedConfig { reset_database: true, ..SeedConfig::default() }).await.unwrap();
+        // A table the seeder knows nothing about is still cleared
+        sqlx::raw_sql("CREATE TABLE reviewer_notes (id INTEGER PRIMARY KEY, case_study_id INTEGER REFERENCES case_studies(id)); INSERT INTO reviewer_notes (case_study_id) VALUES (1);")
+            .execute(seeder.database_manager.pool())
+            .await
+            .unwrap();
+
+        seeder.reset_database().await.unwrap();
+
+        assert!(table_counts(&seeder).await.iter().all(|(_, count)| *count == 0));
+        let notes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reviewer_notes")
+            .fetch_one(seeder.database_manager.pool())
+            .await
+            .unwrap();
+        assert_eq!(notes, 0);
+        let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(seeder.database_manager.pool())
+            .await
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_reset_leaves_tables_untouched() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        seeder.seed(SeedConfig { reset_database: true, ..SeedConfig::default() }).await.unwrap();
+        let before = table_counts(&seeder).await;
+
+        sqlx::query("CREATE TRIGGER fail_reset BEFORE DELETE ON collections BEGIN SELECT RAISE(ABORT, 'injected failure'); END")
+            .execute(seeder.database_manager.pool())
+            .await
+            .unwrap();
+
+        assert!(seeder.reset_database().await.is_err());
+        assert_eq!(table_counts(&seeder).await, before);
+    }
+
+    #[test]
+    fn test_children_cleared_before_parents() {
+        let tables = ["case_studies", "domains", "user_progress", "users"].map(String::from).to_vec();
+        let references = [
+            ("case_studies", "domains"),
+            ("case_studies", "users"),
+            ("user_progress", "case_studies"),
+            ("user_progress", "users"),
+        ]
+        .map(|(child, parent)| (child.to_string(), parent.to_string()));
+
+        assert_eq!(children_first(tables, &references), vec!["user_progress", "case_studies", "domains", "users"]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_plans_without_writing() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        seeder.reset_database().await.unwrap();
+
+        let stats = seeder.seed(SeedConfig { dry_run: true, ..SeedConfig::default() }).await.unwrap();
+
+        assert!(stats.dry_run);
+        assert!(!stats.database_reset);
+        assert!(record_counts(&stats).iter().all(|count| *count > 0));
+        assert!(stats.table_transactions.is_empty());
+        assert!(table_counts(&seeder).await.iter().all(|(_, count)| *count == 0));
+
+        // Planned counts match what a real run then creates
+        let created = seeder.seed(SeedConfig::default()).await.unwrap();
+        assert_eq!(record_counts(&stats), record_counts(&created));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_respects_existing_data() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        seeder.seed(SeedConfig { reset_database: true, ..SeedConfig::default() }).await.unwrap();
+        let before = table_counts(&seeder).await;
+
+        let skipped = seeder.seed(SeedConfig { dry_run: true, ..SeedConfig::default() }).await.unwrap();
+        assert_eq!(skipped.total_records_created, 0);
+
+        let replanned = seeder.seed(SeedConfig { dry_run: true, reset_database: true, ..SeedConfig::default() }).await.unwrap();
+        assert!(replanned.database_reset);
+        assert!(replanned.total_records_created > 0);
+        assert_eq!(table_counts(&seeder).await, before);
+    }
+
+    /// Records every stage reported and trips the token once `cancel_after` completes
+    struct CancellingSink {
+        cancel_after: SeedStage,
+        token: CancellationToken,
+        stages: std::sync::Mutex<Vec<SeedStage>>,
+    }
+
+    impl SeedProgressSink for CancellingSink {
+        fn report(&self, progress: &SeedProgress) {
+            self.stages.lock().unwrap().push(progress.stage);
+            if progress.stage == self.cancel_after {
+                self.token.cancel();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_after_users_rolls_back() {
+        let seeder = DatabaseSeeder::new(sample_database().await);
+        let sink = CancellingSink {
+            cancel_after: SeedStage::Users,
+            token: CancellationToken::new(),
+            stages: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let result = seeder.seed_with_progress(SeedConfig::default(), &sink, &sink.token).await;
+
+        assert!(matches!(result, Err(SeedError::Cancelled)));
+        assert_eq!(*sink.stages.lock().unwrap(), vec![SeedStage::Users]);
+        let counts = seeder.get_current_stats().await.unwrap();
+        assert_eq!(counts["users"], 0);
+        assert_eq!(counts["domains"], 0);
+        assert_eq!(counts["case_studies"], 0);
+
+        // The database is left ready for a fresh seed
+        let stats = seeder.seed(SeedConfig::default()).await.unwrap();
+        assert!(stats.users_created > 0);
+    }
+}
@@ -0,0 +1,52 @@
+// Progress reporting for database seeding
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted to the frontend as each seeding stage completes
+pub const SEED_PROGRESS_EVENT: &str = "seed://progress";
+
+/// Step of the seeding process, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedStage {
+    Reset,
+    Users,
+    Domains,
+    AppSettings,
+    CaseStudies,
+    AssessmentQuestions,
+    UserProgress,
+    Collections,
+    Completed,
+}
+
+/// Progress payload sent after each stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedProgress {
+    pub stage: SeedStage,
+    /// Records written by this stage
+    pub records_created: usize,
+    /// Records written so far across every stage
+    pub total_records_created: usize,
+}
+
+/// Receiver for seeding progress updates
+pub trait SeedProgressSink: Send + Sync {
+    fn report(&self, progress: &SeedProgress);
+}
+
+impl SeedProgressSink for AppHandle {
+    fn report(&self, progress: &SeedProgress) {
+        if let Err(e) = self.emit(SEED_PROGRESS_EVENT, progress) {
+            tracing::warn!("Failed to emit seed progress: {}", e);
+        }
+    }
+}
+
+/// Sink that discards seeding progress updates
+pub struct NoopSeedProgress;
+
+impl SeedProgressSink for NoopSeedProgress {
+    fn report(&self, _progress: &SeedProgress) {}
+}
@@ -1,11 +1,67 @@
 // Sample data templates for database seeding
 
+use super::locales;
 use crate::database::models::*;
 use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::ops::Range;
 
-/// Generate sample users
-pub fn get_sample_users(max_users: usize) -> Vec<NewUser> {
-    let users = vec![
+/// Locale used when none is requested or the requested one has no sample data
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Locales with their own sample data packs
+pub const SUPPORTED_LOCALES: [&str; 3] = ["en-US", "en-GB", "es-ES"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SampleLocale {
+    EnUs,
+    EnGb,
+    EsEs,
+}
+
+impl SampleLocale {
+    /// Match a locale tag case-insensitively, accepting `_` as well as `-`
+    fn resolve(locale: &str) -> Self {
+        match locale.trim().replace('_', "-").to_lowercase().as_str() {
+            "en-gb" => Self::EnGb,
+            "es-es" => Self::EsEs,
+            _ => Self::EnUs,
+        }
+    }
+}
+
+/// Generate sample users for a locale
+pub fn get_sample_users(locale: &str, max_users: usize) -> Vec<NewUser> {
+    let users = match SampleLocale::resolve(locale) {
+        SampleLocale::EnUs => en_us_users(),
+        SampleLocale::EnGb => locales::en_gb_users(),
+        SampleLocale::EsEs => locales::es_es_users(),
+    };
+
+    users.into_iter().take(max_users).collect()
+}
+
+/// Generate sample domains for a locale
+pub fn get_sample_domains(locale: &str) -> Vec<NewDomain> {
+    match SampleLocale::resolve(locale) {
+        SampleLocale::EnUs => en_us_domains(),
+        SampleLocale::EnGb => locales::en_gb_domains(),
+        SampleLocale::EsEs => locales::es_es_domains(),
+    }
+}
+
+/// Generate sample case study templates for a locale
+pub fn get_sample_case_studies(locale: &str) -> Vec<NewCaseStudy> {
+    match SampleLocale::resolve(locale) {
+        SampleLocale::EnUs => en_us_case_studies(),
+        SampleLocale::EnGb => locales::en_gb_case_studies(en_us_case_studies()),
+        SampleLocale::EsEs => locales::es_es_case_studies(),
+    }
+}
+
+fn en_us_users() -> Vec<NewUser> {
+    vec![
         NewUser {
             username: "admin".to_string(),
             email: Some("admin@casecrafter.dev".to_string()),
@@ -86,13 +142,10 @@ pub fn get_sample_users(max_users: usize) -> Vec<NewUser> {
             role: Some("instructor".to_string()),
             preferences: Some(r#"{"theme": "light", "notifications": true, "testing_mode": true}"#.to_string()),
         },
-    ];
-
-    users.into_iter().take(max_users).collect()
+    ]
 }
 
-/// Generate sample domains
-pub fn get_sample_domains() -> Vec<NewDomain> {
+fn en_us_domains() -> Vec<NewDomain> {
     vec![
         NewDomain {
             name: "Business Strategy".to_string(),
@@ -139,8 +192,7 @@ pub fn get_sample_domains() -> Vec<NewDomain> {
     ]
 }
 
-/// Generate sample case study templates
-pub fn get_sample_case_studies() -> Vec<NewCaseStudy> {
+fn en_us_case_studies() -> Vec<NewCaseStudy> {
     vec![
         NewCaseStudy {
             title: "Market Entry Strategy Challenge".to_string(),
@@ -364,16 +416,22 @@ pub fn get_sample_app_settings() -> Vec<NewAppSetting> {
 }
 
 /// Generate user progress data
-pub fn generate_user_progress(user_id: i64, case_study_id: i64, index: usize) -> NewUserProgress {
+pub fn generate_user_progress(user_id: i64, case_study_id: i64, index: usize, mut rng: Option<&mut StdRng>) -> NewUserProgress {
+    // Draw from the seeded generator when there is one, otherwise vary by index
+    let mut pick = |range: Range<usize>, fallback: usize| match rng.as_deref_mut() {
+        Some(rng) => rng.gen_range(range),
+        None => fallback,
+    };
+
     let statuses = ["not_started", "in_progress", "completed", "reviewed"];
-    let status = statuses[index % statuses.len()];
+    let status = statuses[pick(0..statuses.len(), index % statuses.len())];
     
-    let (time_spent, score, completed_at) = match status {
-        "not_started" => (Some(0), None, None),
-        "in_progress" => (Some(1800 + (index * 600) as i64), None, None), // 30-90 minutes
-        "completed" => (Some(3600 + (index * 300) as i64), Some(75.0 + (index as f64 * 5.0) % 25.0), Some("2024-01-15T10:30:00Z".parse::<DateTime<Utc>>().ok())),
-        "reviewed" => (Some(4200 + (index * 450) as i64), Some(80.0 + (index as f64 * 3.0) % 20.0), Some("2024-01-10T14:20:00Z".parse::<DateTime<Utc>>().ok())),
-        _ => (Some(0), None, None),
+    let (time_spent, score) = match status {
+        "not_started" => (Some(0), None),
+        "in_progress" => (Some(1800 + (pick(0..7, index) * 600) as i64), None), // 30-90 minutes
+        "completed" => (Some(3600 + (pick(0..13, index) * 300) as i64), Some(75.0 + pick(0..25, (index * 5) % 25) as f64)),
+        "reviewed" => (Some(4200 + (pick(0..9, index) * 450) as i64), Some(80.0 + pick(0..20, (index * 3) % 20) as f64)),
+        _ => (Some(0), None),
     };
 
     let answers = if status == "completed" || status == "reviewed" {
@@ -382,7 +440,7 @@ pub fn generate_user_progress(user_id: i64, case_study_id: i64, index: usize) ->
         None
     };
 
-    let notes = if index % 3 == 0 {
+    let notes = if pick(0..3, index % 3) == 0 {
         Some("Student showed good analytical thinking but needs to improve framework application.".to_string())
     } else {
         None
@@ -0,0 +1,125 @@
+// Short-lived cache for dashboard statistics
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long computed statistics are served before being recomputed
+pub const STATISTICS_TTL: Duration = Duration::from_secs(60);
+
+struct CachedValue<T> {
+    value: T,
+    computed_at: Instant,
+}
+
+struct CacheState<T> {
+    entry: Option<CachedValue<T>>,
+    /// Bumped on every invalidation so a computation that raced with a write is not stored
+    generation: u64,
+}
+
+/// Caches a single computed value for a TTL, dropping it whenever the owner invalidates
+pub struct StatsCache<T> {
+    ttl: Duration,
+    state: Mutex<CacheState<T>>,
+}
+
+impl<T: Clone> StatsCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: Mutex::new(CacheState { entry: None, generation: 0 }),
+        }
+    }
+
+    /// Return the cached value if it's still fresh, otherwise run `compute` and cache its result
+    pub async fn get_or_compute<F, Fut, E>(&self, compute: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let generation = {
+            let state = self.state.lock().unwrap();
+            if let Some(entry) = state.entry.as_ref().filter(|e| e.computed_at.elapsed() < self.ttl) {
+                return Ok(entry.value.clone());
+            }
+            state.generation
+        };
+
+        let value = compute().await?;
+
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.entry = Some(CachedValue { value: value.clone(), computed_at: Instant::now() });
+        }
+        Ok(value)
+    }
+
+    /// Drop the cached value so the next read recomputes it
+    pub fn invalidate(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.entry = None;
+        state.generation += 1;
+    }
+}
+
+impl<T: Clone> Default for StatsCache<T> {
+    fn default() -> Self {
+        Self::new(STATISTICS_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn compute(counter: &AtomicU32) -> Result<u32, ()> {
+        Ok(counter.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    #[tokio::test]
+    async fn test_serves_cached_value_until_invalidated() {
+        let cache = StatsCache::new(Duration::from_secs(60));
+        let counter = AtomicU32::new(0);
+
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(1));
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(1));
+
+        cache.invalidate();
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(2));
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expired_value_is_recomputed() {
+        let cache = StatsCache::new(Duration::from_millis(20));
+        let counter = AtomicU32::new(0);
+
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(1));
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_result_computed_across_an_invalidation_is_not_cached() {
+        let cache = StatsCache::new(Duration::from_secs(60));
+        let counter = AtomicU32::new(0);
+
+        let stale = cache
+            .get_or_compute(|| async {
+                cache.invalidate();
+                compute(&counter).await
+            })
+            .await;
+        assert_eq!(stale, Ok(1));
+        assert_eq!(cache.get_or_compute(|| compute(&counter)).await, Ok(2));
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let cache: StatsCache<u32> = StatsCache::new(Duration::from_secs(60));
+        assert_eq!(cache.get_or_compute(|| async { Err("offline") }).await, Err("offline"));
+        assert_eq!(cache.get_or_compute(|| async { Ok::<_, &str>(7) }).await, Ok(7));
+    }
+}
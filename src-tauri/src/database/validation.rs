@@ -38,6 +38,15 @@ pub enum ValidationError {
     
     #[error("Points must be positive")]
     InvalidPoints,
+
+    #[error("Multiple choice questions need at least one option")]
+    MissingOptions,
+
+    #[error("Correct answer '{answer}' is not one of the options")]
+    CorrectAnswerNotInOptions { answer: String },
+
+    #[error("Invalid color '{color}' (expected a hex code such as #2196F3)")]
+    InvalidColor { color: String },
 }
 
 pub type ValidationResult<T> = Result<T, ValidationError>;
@@ -134,6 +143,56 @@ impl Validator {
         }
     }
 
+    /// For multiple choice questions, check that `options` is a non-empty JSON array and
+    /// that `correct_answer` matches one of its entries. Entries may be plain strings or
+    /// objects with `id` and `text`, as stored by the question generator.
+    pub fn validate_multiple_choice(question_type: &str, options: Option<&str>, correct_answer: Option<&str>) -> ValidationResult<()> {
+        if question_type != "multiple_choice" {
+            return Ok(());
+        }
+
+        let options: Vec<serde_json::Value> = match options.map(str::trim).filter(|o| !o.is_empty()) {
+            Some(json) => serde_json::from_str(json).map_err(|_| ValidationError::InvalidJson {
+                field: "options".to_string()
+            })?,
+            None => Vec::new(),
+        };
+        let labels: Vec<&str> = options
+            .iter()
+            .flat_map(|option| match option {
+                serde_json::Value::String(text) => vec![text.as_str()],
+                serde_json::Value::Object(fields) => ["id", "text"]
+                    .iter()
+                    .filter_map(|key| fields.get(*key).and_then(|v| v.as_str()))
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        Self::validate_choice_answer(&labels, correct_answer)
+    }
+
+    /// Check that `options` is non-empty and `correct_answer` is one of them, ignoring case
+    /// and surrounding whitespace
+    pub fn validate_choice_answer<S: AsRef<str>>(options: &[S], correct_answer: Option<&str>) -> ValidationResult<()> {
+        if options.is_empty() {
+            return Err(ValidationError::MissingOptions);
+        }
+
+        let answer = correct_answer
+            .map(str::trim)
+            .filter(|answer| !answer.is_empty())
+            .ok_or_else(|| ValidationError::RequiredField {
+                field: "correct_answer".to_string()
+            })?;
+        if !options.iter().any(|option| option.as_ref().trim().eq_ignore_ascii_case(answer)) {
+            return Err(ValidationError::CorrectAnswerNotInOptions {
+                answer: answer.to_string()
+            });
+        }
+
+        Ok(())
+    }
+
     /// Validate progress status
     pub fn validate_progress_status(status: &str) -> ValidationResult<()> {
         match status {
@@ -298,6 +357,11 @@ impl Validator {
         if let Some(options) = &question.options {
             Self::validate_json(options, "options")?;
         }
+        Self::validate_multiple_choice(
+            &question.question_type,
+            question.options.as_deref(),
+            question.correct_answer.as_deref(),
+        )?;
         
         if let Some(rubric) = &question.rubric {
             Self::validate_json(rubric, "rubric")?;
@@ -335,9 +399,25 @@ impl Validator {
         Ok(())
     }
 
+    /// Validate a hex color code in `#RGB` or `#RRGGBB` form
+    pub fn validate_hex_color(color: &str) -> ValidationResult<()> {
+        let valid = color
+            .strip_prefix('#')
+            .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        if !valid {
+            return Err(ValidationError::InvalidColor {
+                color: color.to_string(),
+            });
+        }
+        Ok(())
+    }
+
     /// Validate new domain data
     pub fn validate_new_domain(domain: &NewDomain) -> ValidationResult<()> {
         Self::validate_domain_name(&domain.name)?;
+        if let Some(color) = &domain.color {
+            Self::validate_hex_color(color)?;
+        }
         Ok(())
     }
 }
@@ -369,4 +449,78 @@ impl Validatable for NewDomain {
     fn validate(&self) -> ValidationResult<()> {
         Validator::validate_new_domain(self)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multiple_choice_question(options: Option<&str>, correct_answer: Option<&str>) -> NewAssessmentQuestion {
+        NewAssessmentQuestion {
+            case_study_id: 1,
+            question_text: "Which market entry mode fits best?".to_string(),
+            question_type: "multiple_choice".to_string(),
+            options: options.map(str::to_string),
+            correct_answer: correct_answer.map(str::to_string),
+            sample_answer: None,
+            rubric: None,
+            points: Some(5),
+            order_index: None,
+            is_required: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_multiple_choice() {
+        let question = multiple_choice_question(Some(r#"["Licensing", "Joint Venture"]"#), Some("Joint Venture"));
+        assert!(question.validate().is_ok());
+
+        // Generated questions store option objects and may answer with an option id
+        let generated = multiple_choice_question(
+            Some(r#"[{"id": "a", "text": "Licensing"}, {"id": "b", "text": "Joint Venture"}]"#),
+            Some("b"),
+        );
+        assert!(generated.validate().is_ok());
+
+        for question in crate::database::seeds::sample_data::get_sample_assessment_questions() {
+            assert!(question.validate().is_ok(), "{}", question.question_text);
+        }
+    }
+
+    #[test]
+    fn test_correct_answer_not_in_options() {
+        let question = multiple_choice_question(Some(r#"["Licensing", "Joint Venture"]"#), Some("Franchising"));
+        assert!(matches!(
+            question.validate(),
+            Err(ValidationError::CorrectAnswerNotInOptions { answer }) if answer == "Franchising"
+        ));
+
+        let unanswered = multiple_choice_question(Some(r#"["Licensing", "Joint Venture"]"#), None);
+        assert!(matches!(unanswered.validate(), Err(ValidationError::RequiredField { .. })));
+    }
+
+    #[test]
+    fn test_multiple_choice_without_options() {
+        for options in [Some("[]"), Some(""), None] {
+            let question = multiple_choice_question(options, Some("Joint Venture"));
+            assert!(matches!(question.validate(), Err(ValidationError::MissingOptions)));
+        }
+
+        // Other question types don't need options
+        let mut essay = multiple_choice_question(None, None);
+        essay.question_type = "essay".to_string();
+        assert!(essay.validate().is_ok());
+    }
+
+    #[test]
+    fn test_hex_color_validation() {
+        for color in ["#2196F3", "#fff", "#a1B2c3"] {
+            assert!(Validator::validate_hex_color(color).is_ok(), "{}", color);
+        }
+        for color in ["2196F3", "#2196F", "#GGGGGG", "#", "blue"] {
+            assert!(matches!(
+                Validator::validate_hex_color(color),
+                Err(ValidationError::InvalidColor { .. })
+            ), "{}", color);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// Timing key derivation to pick Argon2 parameters that suit this machine
+
+use super::{derive_key, Argon2Params, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Target derivation time used when the caller doesn't give one
+pub const DEFAULT_KDF_TARGET: Duration = Duration::from_millis(500);
+
+/// Candidate parameters, weakest first. The weakest matches the legacy parameters so
+/// tuning never recommends less than existing vaults already use.
+pub const KDF_CANDIDATES: [Argon2Params; 5] = [
+    Argon2Params { memory_kib: 19 * 1024, iterations: 2, parallelism: 1 },
+    Argon2Params { memory_kib: 32 * 1024, iterations: 3, parallelism: 1 },
+    Argon2Params { memory_kib: 64 * 1024, iterations: 3, parallelism: 1 },
+    Argon2Params { memory_kib: 128 * 1024, iterations: 3, parallelism: 1 },
+    Argon2Params { memory_kib: 256 * 1024, iterations: 4, parallelism: 1 },
+];
+
+/// Derivations timed per candidate; the fastest is kept to filter out scheduling noise
+const SAMPLES: usize = 2;
+
+/// How long one candidate took to derive a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfMeasurement {
+    pub params: Argon2Params,
+    pub duration_ms: u64,
+}
+
+/// Outcome of a key derivation benchmark
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfBenchmark {
+    pub target_ms: u64,
+    /// Strongest candidate that finished within the target, or the weakest if none did
+    pub recommended: Argon2Params,
+    /// Candidates timed, weakest first. Timing stops at the first one over the target.
+    pub measurements: Vec<KdfMeasurement>,
+}
+
+/// Time `derive_key` with each of `candidates`, which must be ordered weakest first,
+/// and recommend the strongest that completes within `target`
+pub fn benchmark_kdf(candidates: &[Argon2Params], target: Duration) -> Result<KdfBenchmark> {
+    let salt = [7u8; 32];
+    let mut measurements = Vec::new();
+    let mut recommended = None;
+
+    for params in candidates {
+        let mut fastest = Duration::MAX;
+        for _ in 0..SAMPLES {
+            let started = Instant::now();
+            derive_key("kdf benchmark password", &salt, params)?;
+            fastest = fastest.min(started.elapsed());
+        }
+        measurements.push(KdfMeasurement {
+            params: *params,
+            duration_ms: fastest.as_millis() as u64,
+        });
+
+        if fastest > target {
+            break;
+        }
+        recommended = Some(*params);
+    }
+
+    Ok(KdfBenchmark {
+        target_ms: target.as_millis() as u64,
+        recommended: recommended.or_else(|| candidates.first().copied()).unwrap_or_default(),
+        measurements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_CANDIDATES: [Argon2Params; 3] = [
+        Argon2Params { memory_kib: 1024, iterations: 1, parallelism: 1 },
+        Argon2Params { memory_kib: 4 * 1024, iterations: 1, parallelism: 1 },
+        Argon2Params { memory_kib: 8 * 1024, iterations: 2, parallelism: 1 },
+    ];
+
+    fn measured(benchmark: &KdfBenchmark, params: &Argon2Params) -> u64 {
+        benchmark
+            .measurements
+            .iter()
+            .find(|m| m.params == *params)
+            .map(|m| m.duration_ms)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_recommendation_fits_target_and_is_stable() {
+        let target = Duration::from_millis(250);
+        let first = benchmark_kdf(&SMALL_CANDIDATES, target).unwrap();
+        assert_eq!(first.target_ms, 250);
+        assert!(measured(&first, &first.recommended) <= first.target_ms);
+
+        let second = benchmark_kdf(&SMALL_CANDIDATES, target).unwrap();
+        if first.recommended != second.recommended {
+            // Only a candidate timed close to the target may land on either side of it
+            let borderline = first
+                .measurements
+                .iter()
+                .chain(&second.measurements)
+                .any(|m| m.duration_ms.abs_diff(first.target_ms) <= first.target_ms / 2);
+            assert!(borderline, "{:?} vs {:?}", first, second);
+        }
+    }
+
+    #[test]
+    fn test_weakest_candidate_when_none_fit() {
+        let benchmark = benchmark_kdf(&SMALL_CANDIDATES[1..], Duration::ZERO).unwrap();
+        assert_eq!(benchmark.recommended, SMALL_CANDIDATES[1]);
+        // Stronger candidates aren't timed once one is over the target
+        assert_eq!(benchmark.measurements.len(), 1);
+    }
+}
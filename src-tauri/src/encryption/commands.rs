@@ -1,27 +1,41 @@
 // Tauri commands for encryption management
 
-use crate::encryption::{EncryptionManager, EncryptedData};
+use crate::ai::AIManager;
+use crate::database::DatabaseManager;
+use crate::encryption::benchmark::{self, KdfBenchmark, DEFAULT_KDF_TARGET, KDF_CANDIDATES};
+use crate::encryption::{EncryptionConfigExport, EncryptionManager, EncryptedData};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, State};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 // Type alias for the encryption manager state
-type EncryptionState = Arc<Mutex<EncryptionManager>>;
+pub type EncryptionState = Arc<Mutex<EncryptionManager>>;
 
-/// Initialize encryption with a password
+type AIManagerState = Arc<RwLock<Option<AIManager>>>;
+
+/// Initialize encryption with a password, then reload the AI configuration so API keys
+/// that were stored encrypted become usable
 #[tauri::command]
 pub async fn initialize_encryption(
     password: String,
     encryption_state: State<'_, EncryptionState>,
+    ai_manager_state: State<'_, AIManagerState>,
 ) -> std::result::Result<bool, String> {
-    let mut manager = encryption_state.lock().await;
-    manager
-        .initialize(&password)
-        .await
-        .map(|_| true)
-        .map_err(|e| e.to_string())
+    {
+        let mut manager = encryption_state.lock().await;
+        manager.initialize(&password).await.map_err(|e| e.to_string())?;
+    }
+
+    // The config repository takes the encryption lock itself to decrypt the keys
+    if let Some(ai_manager) = ai_manager_state.read().await.as_ref() {
+        if let Err(e) = ai_manager.reload_config().await {
+            tracing::warn!("Could not reload AI configuration after unlocking encryption: {}", e);
+        }
+    }
+    Ok(true)
 }
 
 /// Check if encryption is initialized
@@ -53,6 +67,27 @@ pub async fn decrypt_value(
     manager.decrypt(&encrypted_data).map_err(|e| e.to_string())
 }
 
+/// Encrypt several values in one call
+#[tauri::command]
+pub async fn encrypt_values(
+    values: Vec<String>,
+    encryption_state: State<'_, EncryptionState>,
+) -> std::result::Result<Vec<EncryptedData>, String> {
+    let manager = encryption_state.lock().await;
+    let values: Vec<&str> = values.iter().map(String::as_str).collect();
+    manager.encrypt_batch(&values).map_err(|e| e.to_string())
+}
+
+/// Decrypt several values in one call
+#[tauri::command]
+pub async fn decrypt_values(
+    encrypted_values: Vec<EncryptedData>,
+    encryption_state: State<'_, EncryptionState>,
+) -> std::result::Result<Vec<String>, String> {
+    let manager = encryption_state.lock().await;
+    manager.decrypt_batch(&encrypted_values).map_err(|e| e.to_string())
+}
+
 /// Encrypt user preferences
 #[tauri::command]
 pub async fn encrypt_user_preferences(
@@ -97,56 +132,77 @@ pub async fn decrypt_map(
     manager.decrypt_map(&encrypted_data).map_err(|e| e.to_string())
 }
 
-/// Change encryption password
+/// Change encryption password and re-encrypt stored data under the new key
 #[tauri::command]
 pub async fn change_encryption_password(
     old_password: String,
     new_password: String,
     encryption_state: State<'_, EncryptionState>,
+    database_manager: State<'_, Arc<DatabaseManager>>,
 ) -> std::result::Result<bool, String> {
     let mut manager = encryption_state.lock().await;
     manager
-        .change_password(&old_password, &new_password)
+        .change_password(database_manager.pool(), &old_password, &new_password)
         .await
         .map(|_| true)
         .map_err(|e| e.to_string())
 }
 
-/// Validate encryption password (check if it can decrypt existing data)
+/// Time key derivation with candidate Argon2 parameters and use the strongest that
+/// finishes within `target_ms` (500ms by default) for new vaults and password changes
+#[tauri::command]
+pub async fn benchmark_kdf(
+    target_ms: Option<u64>,
+    encryption_state: State<'_, EncryptionState>,
+) -> std::result::Result<KdfBenchmark, String> {
+    let target = target_ms.map(Duration::from_millis).unwrap_or(DEFAULT_KDF_TARGET);
+    let result = tokio::task::spawn_blocking(move || benchmark::benchmark_kdf(&KDF_CANDIDATES, target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let mut manager = encryption_state.lock().await;
+    manager
+        .save_kdf_params(result.recommended)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Validate encryption password against the stored verifier without initializing encryption
 #[tauri::command]
 pub async fn validate_encryption_password(
     password: String,
     app_handle: AppHandle,
 ) -> std::result::Result<bool, String> {
-    let mut temp_manager = EncryptionManager::new(app_handle);
-    
-    match temp_manager.initialize(&password).await {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
-    }
+    let temp_manager = EncryptionManager::new(app_handle);
+    temp_manager
+        .verify_password(&password)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Export encryption configuration (for backup purposes)
+/// Export non-secret encryption metadata for diagnostics; never includes the key or salt
 #[tauri::command]
 pub async fn export_encryption_config(
-    app_handle: AppHandle,
-) -> std::result::Result<HashMap<String, String>, String> {
-    let app_data_dir = get_app_data_dir(&app_handle)?;
-    let salt_path = app_data_dir.join("encryption.salt");
-    
-    let mut config = HashMap::new();
-    
-    if salt_path.exists() {
-        config.insert("has_salt".to_string(), "true".to_string());
-        config.insert("salt_path".to_string(), salt_path.to_string_lossy().to_string());
-    } else {
-        config.insert("has_salt".to_string(), "false".to_string());
-    }
-    
-    config.insert("encryption_algorithm".to_string(), "AES-256-GCM".to_string());
-    config.insert("key_derivation".to_string(), "Argon2".to_string());
-    
-    Ok(config)
+    encryption_state: State<'_, EncryptionState>,
+) -> std::result::Result<EncryptionConfigExport, String> {
+    let manager = encryption_state.lock().await;
+    manager.export_config().await.map_err(|e| e.to_string())
+}
+
+/// Apply the Argon2 parameters of an exported config before a vault is created
+#[tauri::command]
+pub async fn import_encryption_config(
+    config: EncryptionConfigExport,
+    encryption_state: State<'_, EncryptionState>,
+) -> std::result::Result<bool, String> {
+    let mut manager = encryption_state.lock().await;
+    manager
+        .import_config(&config)
+        .await
+        .map(|_| true)
+        .map_err(|e| e.to_string())
 }
 
 /// Test encryption functionality
@@ -200,16 +256,20 @@ pub async fn get_encryption_stats(
 }
 
 // Helper function to get app data directory
-fn get_app_data_dir(_app_handle: &AppHandle) -> std::result::Result<std::path::PathBuf, String> {
-    // Temporary workaround - in production this would use app_handle.path()
-    let home_dir = std::env::var("HOME").map_err(|_| "Could not get home directory".to_string())?;
-    let app_data_dir = std::path::PathBuf::from(home_dir).join(".local/share/case-crafter");
-    Ok(app_data_dir)
+fn get_app_data_dir(app_handle: &AppHandle) -> std::result::Result<std::path::PathBuf, String> {
+    use tauri::Manager;
+
+    app_handle.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
 }
 
 /// Setup encryption state for Tauri app
 pub fn setup_encryption_state(app_handle: AppHandle) -> EncryptionState {
-    let manager = EncryptionManager::new(app_handle);
+    let mut manager = EncryptionManager::new(app_handle);
+    if let Err(e) = manager.load_saved_kdf_params() {
+        tracing::warn!("Could not load saved key derivation parameters: {}", e);
+    }
     Arc::new(Mutex::new(manager))
 }
 
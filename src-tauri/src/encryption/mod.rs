@@ -1,19 +1,23 @@
 // AES-256 encryption module for protecting sensitive data
 // Uses AES-256-GCM for authenticated encryption
 
+pub mod benchmark;
 pub mod commands;
 
 use aes_gcm::{
     aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
 };
-use argon2::{Argon2, password_hash::{PasswordHasher, SaltString}};
+use argon2::{Algorithm, Argon2, Params, Version};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
+use sqlx::{SqliteConnection, SqlitePool};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use tauri::AppHandle;
 use thiserror::Error;
+use zeroize::{Zeroize, Zeroizing};
 
 #[derive(Error, Debug)]
 pub enum EncryptionError {
@@ -27,10 +31,68 @@ pub enum EncryptionError {
     InvalidFormat(String),
     #[error("Storage error: {0}")]
     StorageError(String),
+    #[error("Invalid encryption password")]
+    InvalidPassword,
 }
 
 pub type Result<T> = std::result::Result<T, EncryptionError>;
 
+const ENCRYPTION_ALGORITHM: &str = "AES-256-GCM";
+const ENCRYPTION_VERSION: &str = "1.0";
+const KEY_DERIVATION: &str = "Argon2id";
+/// Known plaintext encrypted with the master key so a password can be checked before use
+const VERIFICATION_SENTINEL: &str = "case-crafter-encryption-verifier";
+const SALT_FILE: &str = "encryption.salt";
+const VERIFIER_FILE: &str = "encryption.verify";
+const PARAMS_FILE: &str = "encryption.params";
+/// Parameters chosen for new vaults, e.g. by `benchmark_kdf`; never applied to an existing vault
+const TUNED_PARAMS_FILE: &str = "encryption.params.tuned";
+
+/// Argon2id cost parameters used to derive the master key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Argon2Params {
+    /// Parameters used by vaults created before they were stored (the argon2 crate defaults)
+    pub fn legacy() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+
+    fn to_argon2(self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| EncryptionError::KeyDerivationFailed(format!("Invalid Argon2 parameters: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Self::legacy()
+    }
+}
+
+/// Non-secret description of the encryption setup for diagnostics. It holds the salt's
+/// location but never the salt, the key or anything decrypted with it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionConfigExport {
+    pub encryption_algorithm: String,
+    pub key_derivation: String,
+    /// Parameters of the existing vault, or those a new vault would be created with
+    pub kdf_params: Argon2Params,
+    pub version: String,
+    pub initialized: bool,
+    pub has_salt: bool,
+    pub salt_path: String,
+}
+
 /// Encrypted data container with nonce and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptedData {
@@ -40,10 +102,15 @@ pub struct EncryptedData {
     pub version: String,   // Version for future compatibility
 }
 
-/// Key management for encryption operations
+/// Key management for encryption operations.
+///
+/// The master key is held in a `Zeroizing` buffer and wiped when the manager is dropped.
 pub struct EncryptionManager {
-    master_key: Option<[u8; 32]>,
+    master_key: Option<Zeroizing<[u8; 32]>>,
+    kdf_params: Argon2Params,
     app_handle: AppHandle,
+    /// Directory for the salt, verifier and parameter files; the app data dir when unset
+    data_dir: Option<PathBuf>,
 }
 
 impl EncryptionManager {
@@ -51,21 +118,133 @@ impl EncryptionManager {
     pub fn new(app_handle: AppHandle) -> Self {
         Self {
             master_key: None,
+            kdf_params: Argon2Params::default(),
             app_handle,
+            data_dir: None,
+        }
+    }
+
+    /// Keep the salt, verifier and parameter files in `dir` instead of the app data directory
+    pub fn set_data_dir(&mut self, dir: PathBuf) {
+        self.data_dir = Some(dir);
+    }
+
+    /// Set the Argon2 parameters used for new vaults and password changes.
+    /// Existing vaults keep deriving with the parameters stored alongside their salt.
+    pub fn set_kdf_params(&mut self, params: Argon2Params) {
+        self.kdf_params = params;
+    }
+
+    /// Get the Argon2 parameters used for new vaults and password changes
+    pub fn kdf_params(&self) -> Argon2Params {
+        self.kdf_params
+    }
+
+    /// Set the Argon2 parameters for new vaults and password changes and keep them for
+    /// later sessions. The parameters stored with an existing vault are left unchanged.
+    pub async fn save_kdf_params(&mut self, params: Argon2Params) -> Result<()> {
+        let app_data_dir = self.get_app_data_dir()?;
+        tokio::fs::create_dir_all(&app_data_dir).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to create data directory: {}", e)))?;
+        let data = serde_json::to_vec(&params)
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to serialize key derivation parameters: {}", e)))?;
+        tokio::fs::write(app_data_dir.join(TUNED_PARAMS_FILE), data).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to write key derivation parameters: {}", e)))?;
+
+        self.kdf_params = params;
+        Ok(())
+    }
+
+    /// Describe the encryption setup without any secret material
+    pub async fn export_config(&self) -> Result<EncryptionConfigExport> {
+        let salt_path = self.get_app_data_dir()?.join(SALT_FILE);
+        let has_salt = salt_path.exists();
+        let kdf_params = if has_salt { self.get_params().await? } else { self.kdf_params };
+
+        Ok(EncryptionConfigExport {
+            encryption_algorithm: ENCRYPTION_ALGORITHM.to_string(),
+            key_derivation: KEY_DERIVATION.to_string(),
+            kdf_params,
+            version: ENCRYPTION_VERSION.to_string(),
+            initialized: self.is_initialized(),
+            has_salt,
+            salt_path: salt_path.to_string_lossy().to_string(),
+        })
+    }
+
+    /// Adopt the Argon2 parameters of an exported config for a fresh vault. Fails once a
+    /// vault exists, since its stored parameters never change.
+    pub async fn import_config(&mut self, config: &EncryptionConfigExport) -> Result<()> {
+        if config.encryption_algorithm != ENCRYPTION_ALGORITHM || config.key_derivation != KEY_DERIVATION {
+            return Err(EncryptionError::InvalidFormat(format!(
+                "Unsupported encryption configuration: {} with {}",
+                config.encryption_algorithm, config.key_derivation
+            )));
+        }
+        if self.get_app_data_dir()?.join(SALT_FILE).exists() {
+            return Err(EncryptionError::StorageError(
+                "Encryption configuration can only be imported before a vault is created".to_string(),
+            ));
+        }
+        config.kdf_params.to_argon2()?;
+
+        self.save_kdf_params(config.kdf_params).await
+    }
+
+    /// Use the parameters kept by `save_kdf_params`, if any were saved
+    pub fn load_saved_kdf_params(&mut self) -> Result<()> {
+        let path = self.get_app_data_dir()?.join(TUNED_PARAMS_FILE);
+        if !path.exists() {
+            return Ok(());
         }
+
+        let data = std::fs::read(&path)
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to read key derivation parameters: {}", e)))?;
+        self.kdf_params = serde_json::from_slice(&data)
+            .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid key derivation parameters: {}", e)))?;
+        Ok(())
     }
 
-    /// Initialize encryption with a password-derived key
+    /// Initialize encryption with a password-derived key.
+    ///
+    /// The first initialization records a verifier; later ones fail with
+    /// `InvalidPassword` if the derived key cannot decrypt it.
     pub async fn initialize(&mut self, password: &str) -> Result<()> {
         let salt = self.get_or_create_salt().await?;
-        let key = self.derive_key(password, &salt)?;
+        let params = self.get_params().await?;
+        let key = self.derive_key(password, &salt, &params)?;
+
+        match self.get_verifier().await? {
+            Some(verifier) => check_verifier(&key, &verifier)?,
+            None => self.store_verifier(&create_verifier(&key)?).await?,
+        }
+
         self.master_key = Some(key);
         Ok(())
     }
 
+    /// Check a password against the stored verifier without changing any state.
+    ///
+    /// Vaults created before verifiers existed cannot be checked and are reported as valid.
+    pub async fn verify_password(&self, password: &str) -> Result<bool> {
+        let salt = self.get_salt().await?;
+        let params = self.get_params().await?;
+        let key = self.derive_key(password, &salt, &params)?;
+
+        match self.get_verifier().await? {
+            Some(verifier) => match check_verifier(&key, &verifier) {
+                Ok(()) => Ok(true),
+                Err(EncryptionError::InvalidPassword) => Ok(false),
+                Err(e) => Err(e),
+            },
+            None => Ok(true),
+        }
+    }
+
     /// Initialize encryption with a direct key (for testing)
-    pub fn initialize_with_key(&mut self, key: [u8; 32]) {
-        self.master_key = Some(key);
+    pub fn initialize_with_key(&mut self, mut key: [u8; 32]) {
+        self.master_key = Some(Zeroizing::new(key));
+        key.zeroize();
     }
 
     /// Check if encryption is initialized
@@ -75,50 +254,24 @@ impl EncryptionManager {
 
     /// Encrypt sensitive data
     pub fn encrypt(&self, plaintext: &str) -> Result<EncryptedData> {
-        let key = self.get_key()?;
-        let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from_slice(&key));
-        
-        let mut nonce_bytes = [0u8; 12];
-        rand::thread_rng().fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        let ciphertext = cipher
-            .encrypt(nonce, plaintext.as_bytes())
-            .map_err(|e| EncryptionError::EncryptionFailed(e.to_string()))?;
-
-        Ok(EncryptedData {
-            data: BASE64.encode(&ciphertext),
-            nonce: BASE64.encode(&nonce_bytes),
-            algorithm: "AES-256-GCM".to_string(),
-            version: "1.0".to_string(),
-        })
+        encrypt_with_key(self.get_key()?, plaintext)
     }
 
     /// Decrypt sensitive data
     pub fn decrypt(&self, encrypted_data: &EncryptedData) -> Result<String> {
-        let key = self.get_key()?;
-        let cipher = Aes256Gcm::new(&Key::<Aes256Gcm>::from_slice(&key));
-
-        let ciphertext = BASE64
-            .decode(&encrypted_data.data)
-            .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid data: {}", e)))?;
-
-        let nonce_bytes = BASE64
-            .decode(&encrypted_data.nonce)
-            .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid nonce: {}", e)))?;
-
-        if nonce_bytes.len() != 12 {
-            return Err(EncryptionError::InvalidFormat("Invalid nonce length".to_string()));
-        }
-
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        decrypt_with_key(self.get_key()?, encrypted_data)
+    }
 
-        let plaintext_bytes = cipher
-            .decrypt(nonce, ciphertext.as_ref())
-            .map_err(|e| EncryptionError::DecryptionFailed(e.to_string()))?;
+    /// Encrypt several values with one cipher instance; each still gets its own nonce
+    pub fn encrypt_batch(&self, plaintexts: &[&str]) -> Result<Vec<EncryptedData>> {
+        let cipher = cipher_for(self.get_key()?);
+        plaintexts.iter().map(|plaintext| encrypt_with_cipher(&cipher, plaintext)).collect()
+    }
 
-        String::from_utf8(plaintext_bytes)
-            .map_err(|e| EncryptionError::DecryptionFailed(format!("Invalid UTF-8: {}", e)))
+    /// Decrypt several values with one cipher instance, failing on the first bad item
+    pub fn decrypt_batch(&self, encrypted: &[EncryptedData]) -> Result<Vec<String>> {
+        let cipher = cipher_for(self.get_key()?);
+        encrypted.iter().map(|data| decrypt_with_cipher(&cipher, data)).collect()
     }
 
     /// Encrypt a map of key-value pairs
@@ -153,24 +306,45 @@ impl EncryptionManager {
             .map_err(|e| EncryptionError::DecryptionFailed(format!("Preferences deserialization failed: {}", e)))
     }
 
-    /// Change encryption password (re-encrypt all data)
-    pub async fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
+    /// Change encryption password, re-encrypting all stored encrypted columns under the new key.
+    ///
+    /// The data is rewritten in one transaction that only commits once the new salt, parameters
+    /// and verifier are stored, so any failure leaves the old password in effect.
+    pub async fn change_password(&mut self, pool: &SqlitePool, old_password: &str, new_password: &str) -> Result<()> {
         // Verify old password
         let old_salt = self.get_salt().await?;
-        let old_key = self.derive_key(old_password, &old_salt)?;
+        let old_params = self.get_params().await?;
+        let old_key = self.derive_key(old_password, &old_salt, &old_params)?;
         
         if let Some(current_key) = &self.master_key {
             if current_key != &old_key {
-                return Err(EncryptionError::KeyDerivationFailed("Invalid old password".to_string()));
+                return Err(EncryptionError::InvalidPassword);
             }
         }
+        let old_verifier = self.get_verifier().await?;
+        if let Some(verifier) = &old_verifier {
+            check_verifier(&old_key, verifier)?;
+        }
 
-        // Generate new salt and derive new key
+        // Generate new salt and derive new key with the configured parameters
         let new_salt = self.generate_salt();
-        let new_key = self.derive_key(new_password, &new_salt)?;
-
-        // Store new salt
-        self.store_salt(&new_salt).await?;
+        let new_params = self.kdf_params;
+        let new_key = self.derive_key(new_password, &new_salt, &new_params)?;
+
+        // Dropping the transaction on any early return rolls the data back to the old key
+        let mut tx = pool.begin().await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to start re-encryption: {}", e)))?;
+        field_encryption::reencrypt_all(&mut tx, &old_key, &new_key).await?;
+
+        // Store new salt, parameters and a verifier for the new key
+        if let Err(e) = self.store_key_files(&new_salt, &new_params, &new_key).await {
+            self.restore_key_files(&old_salt, &old_params, old_verifier.as_ref()).await;
+            return Err(e);
+        }
+        if let Err(e) = tx.commit().await {
+            self.restore_key_files(&old_salt, &old_params, old_verifier.as_ref()).await;
+            return Err(EncryptionError::StorageError(format!("Failed to commit re-encrypted data: {}", e)));
+        }
         
         // Update master key
         self.master_key = Some(new_key);
@@ -178,31 +352,39 @@ impl EncryptionManager {
         Ok(())
     }
 
+    /// Store the salt, parameters and verifier for a newly derived key
+    async fn store_key_files(&self, salt: &[u8; 32], params: &Argon2Params, key: &[u8; 32]) -> Result<()> {
+        self.store_salt(salt).await?;
+        self.store_params(params).await?;
+        self.store_verifier(&create_verifier(key)?).await
+    }
+
+    /// Best-effort restore of the previous key files after a failed password change
+    async fn restore_key_files(&self, salt: &[u8; 32], params: &Argon2Params, verifier: Option<&EncryptedData>) {
+        let _ = self.store_salt(salt).await;
+        let _ = self.store_params(params).await;
+        match verifier {
+            Some(verifier) => {
+                let _ = self.store_verifier(verifier).await;
+            },
+            None => {
+                if let Ok(dir) = self.get_app_data_dir() {
+                    let _ = tokio::fs::remove_file(dir.join(VERIFIER_FILE)).await;
+                }
+            },
+        }
+    }
+
     /// Get the current encryption key
-    fn get_key(&self) -> Result<[u8; 32]> {
+    fn get_key(&self) -> Result<&[u8; 32]> {
         self.master_key
+            .as_deref()
             .ok_or_else(|| EncryptionError::EncryptionFailed("Encryption not initialized".to_string()))
     }
 
-    /// Derive encryption key from password using Argon2
-    fn derive_key(&self, password: &str, salt: &[u8; 32]) -> Result<[u8; 32]> {
-        let argon2 = Argon2::default();
-        let salt_string = SaltString::encode_b64(salt)
-            .map_err(|e| EncryptionError::KeyDerivationFailed(format!("Salt encoding failed: {}", e)))?;
-
-        let hash = argon2
-            .hash_password(password.as_bytes(), &salt_string)
-            .map_err(|e| EncryptionError::KeyDerivationFailed(format!("Key derivation failed: {}", e)))?;
-
-        let hash_value = hash.hash.unwrap();
-        let hash_bytes = hash_value.as_bytes();
-        if hash_bytes.len() < 32 {
-            return Err(EncryptionError::KeyDerivationFailed("Derived key too short".to_string()));
-        }
-
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&hash_bytes[..32]);
-        Ok(key)
+    /// Derive encryption key from password using Argon2id with the given parameters
+    fn derive_key(&self, password: &str, salt: &[u8; 32], params: &Argon2Params) -> Result<Zeroizing<[u8; 32]>> {
+        derive_key(password, salt, params)
     }
 
     /// Generate a new random salt
@@ -219,6 +401,7 @@ impl EncryptionManager {
             Err(_) => {
                 let salt = self.generate_salt();
                 self.store_salt(&salt).await?;
+                self.store_params(&self.kdf_params).await?;
                 Ok(salt)
             }
         }
@@ -227,7 +410,7 @@ impl EncryptionManager {
     /// Get stored salt
     async fn get_salt(&self) -> Result<[u8; 32]> {
         let app_data_dir = self.get_app_data_dir()?;
-        let salt_path = app_data_dir.join("encryption.salt");
+        let salt_path = app_data_dir.join(SALT_FILE);
 
         let salt_data = tokio::fs::read(&salt_path).await
             .map_err(|e| EncryptionError::StorageError(format!("Failed to read salt: {}", e)))?;
@@ -244,7 +427,7 @@ impl EncryptionManager {
     /// Store encryption salt
     async fn store_salt(&self, salt: &[u8; 32]) -> Result<()> {
         let app_data_dir = self.get_app_data_dir()?;
-        let salt_path = app_data_dir.join("encryption.salt");
+        let salt_path = app_data_dir.join(SALT_FILE);
 
         // Ensure directory exists
         if let Some(parent) = salt_path.parent() {
@@ -258,12 +441,158 @@ impl EncryptionManager {
         Ok(())
     }
 
-    /// Get application data directory
+    /// Get the Argon2 parameters stored with the salt, falling back to the legacy defaults
+    async fn get_params(&self) -> Result<Argon2Params> {
+        let params_path = self.get_app_data_dir()?.join(PARAMS_FILE);
+        if !params_path.exists() {
+            return Ok(Argon2Params::legacy());
+        }
+
+        let data = tokio::fs::read(&params_path).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to read key derivation parameters: {}", e)))?;
+        serde_json::from_slice(&data)
+            .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid key derivation parameters: {}", e)))
+    }
+
+    /// Store the Argon2 parameters next to the salt
+    async fn store_params(&self, params: &Argon2Params) -> Result<()> {
+        let params_path = self.get_app_data_dir()?.join(PARAMS_FILE);
+        let data = serde_json::to_vec(params)
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to serialize key derivation parameters: {}", e)))?;
+
+        tokio::fs::write(&params_path, data).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to write key derivation parameters: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Get the stored password verifier, if one has been written
+    async fn get_verifier(&self) -> Result<Option<EncryptedData>> {
+        let verifier_path = self.get_app_data_dir()?.join(VERIFIER_FILE);
+        if !verifier_path.exists() {
+            return Ok(None);
+        }
+
+        let data = tokio::fs::read(&verifier_path).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to read verifier: {}", e)))?;
+        serde_json::from_slice(&data)
+            .map(Some)
+            .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid verifier: {}", e)))
+    }
+
+    /// Store the password verifier next to the salt
+    async fn store_verifier(&self, verifier: &EncryptedData) -> Result<()> {
+        let verifier_path = self.get_app_data_dir()?.join(VERIFIER_FILE);
+        let data = serde_json::to_vec(verifier)
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to serialize verifier: {}", e)))?;
+
+        tokio::fs::write(&verifier_path, data).await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to write verifier: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Get application data directory, or the directory set with `set_data_dir`
     fn get_app_data_dir(&self) -> Result<std::path::PathBuf> {
-        // Temporary workaround - in production this would use app_handle.path()
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let app_data_dir = std::path::PathBuf::from(home_dir).join(".local/share/case-crafter");
-        Ok(app_data_dir)
+        use tauri::Manager;
+
+        if let Some(dir) = &self.data_dir {
+            return Ok(dir.clone());
+        }
+        self.app_handle.path()
+            .app_data_dir()
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to get app data directory: {}", e)))
+    }
+}
+
+impl Drop for EncryptionManager {
+    fn drop(&mut self) {
+        if let Some(key) = self.master_key.as_mut() {
+            key.zeroize();
+        }
+    }
+}
+
+/// Derive a 256-bit key from a password using Argon2id with the given parameters
+pub fn derive_key(password: &str, salt: &[u8; 32], params: &Argon2Params) -> Result<Zeroizing<[u8; 32]>> {
+    let argon2 = params.to_argon2()?;
+
+    // Derive straight into the zeroized buffer so no other copy of the key is left behind
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2
+        .hash_password_into(password.as_bytes(), salt, key.as_mut())
+        .map_err(|e| EncryptionError::KeyDerivationFailed(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+fn cipher_for(key: &[u8; 32]) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+/// Encrypt with an explicit key using AES-256-GCM and a random nonce
+pub fn encrypt_with_key(key: &[u8; 32], plaintext: &str) -> Result<EncryptedData> {
+    encrypt_with_cipher(&cipher_for(key), plaintext)
+}
+
+fn encrypt_with_cipher(cipher: &Aes256Gcm, plaintext: &str) -> Result<EncryptedData> {
+    let mut nonce_bytes = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_bytes())
+        .map_err(|e| EncryptionError::EncryptionFailed(e.to_string()))?;
+
+    Ok(EncryptedData {
+        data: BASE64.encode(&ciphertext),
+        nonce: BASE64.encode(nonce_bytes),
+        algorithm: ENCRYPTION_ALGORITHM.to_string(),
+        version: ENCRYPTION_VERSION.to_string(),
+    })
+}
+
+/// Decrypt with an explicit key
+pub fn decrypt_with_key(key: &[u8; 32], encrypted_data: &EncryptedData) -> Result<String> {
+    decrypt_with_cipher(&cipher_for(key), encrypted_data)
+}
+
+fn decrypt_with_cipher(cipher: &Aes256Gcm, encrypted_data: &EncryptedData) -> Result<String> {
+    let ciphertext = BASE64
+        .decode(&encrypted_data.data)
+        .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid data: {}", e)))?;
+
+    let nonce_bytes = BASE64
+        .decode(&encrypted_data.nonce)
+        .map_err(|e| EncryptionError::InvalidFormat(format!("Invalid nonce: {}", e)))?;
+
+    if nonce_bytes.len() != 12 {
+        return Err(EncryptionError::InvalidFormat("Invalid nonce length".to_string()));
+    }
+
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let plaintext_bytes = cipher
+        .decrypt(nonce, ciphertext.as_ref())
+        .map_err(|e| EncryptionError::DecryptionFailed(e.to_string()))?;
+
+    String::from_utf8(plaintext_bytes).map_err(|e| {
+        let mut bytes = e.into_bytes();
+        bytes.zeroize();
+        EncryptionError::DecryptionFailed("Invalid UTF-8 in decrypted data".to_string())
+    })
+}
+
+/// Encrypt the verification sentinel with a key
+fn create_verifier(key: &[u8; 32]) -> Result<EncryptedData> {
+    encrypt_with_key(key, VERIFICATION_SENTINEL)
+}
+
+/// Confirm that a key decrypts the verifier back to the sentinel
+fn check_verifier(key: &[u8; 32], verifier: &EncryptedData) -> Result<()> {
+    match decrypt_with_key(key, verifier) {
+        Ok(plaintext) if plaintext == VERIFICATION_SENTINEL => Ok(()),
+        Ok(_) | Err(EncryptionError::DecryptionFailed(_)) => Err(EncryptionError::InvalidPassword),
+        Err(e) => Err(e),
     }
 }
 
@@ -301,8 +630,107 @@ pub mod field_encryption {
         )
     }
 
+    /// Columns that may hold field-encrypted values, as (table, column). Every table is keyed by `id`.
+    pub const ENCRYPTED_COLUMNS: &[(&str, &str)] = &[
+        ("users", "email"),
+        ("users", "preferences"),
+        ("user_progress", "answers"),
+        ("user_progress", "feedback"),
+        ("user_progress", "notes"),
+        ("app_settings", "value"),
+    ];
+
+    /// Decrypt every encrypted value in `ENCRYPTED_COLUMNS` with `old_key` and store it
+    /// re-encrypted with `new_key`. Returns the number of values rewritten.
+    pub async fn reencrypt_all(conn: &mut SqliteConnection, old_key: &[u8; 32], new_key: &[u8; 32]) -> Result<usize> {
+        let old_cipher = cipher_for(old_key);
+        let new_cipher = cipher_for(new_key);
+        let mut rewritten = 0;
+
+        for (table, column) in ENCRYPTED_COLUMNS {
+            let select = format!("SELECT id, {column} FROM {table} WHERE {column} LIKE '{{%'");
+            let rows: Vec<(i64, String)> = sqlx::query_as(&select)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| EncryptionError::StorageError(format!("Failed to read {}.{}: {}", table, column, e)))?;
+
+            let update = format!("UPDATE {table} SET {column} = ? WHERE id = ?");
+            for (id, value) in rows {
+                if !looks_like_encrypted_data(&value) {
+                    continue;
+                }
+                // Plain JSON that merely resembles the container is left alone
+                let encrypted: EncryptedData = match serde_json::from_str(&value) {
+                    Ok(encrypted) => encrypted,
+                    Err(_) => continue,
+                };
+
+                let plaintext = Zeroizing::new(decrypt_with_cipher(&old_cipher, &encrypted)?);
+                let reencrypted = serde_json::to_string(&encrypt_with_cipher(&new_cipher, &plaintext)?)
+                    .map_err(|e| EncryptionError::EncryptionFailed(format!("Field encryption failed: {}", e)))?;
+
+                sqlx::query(&update)
+                    .bind(reencrypted)
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| EncryptionError::StorageError(format!("Failed to update {}.{}: {}", table, column, e)))?;
+                rewritten += 1;
+            }
+        }
+
+        rewritten += reencrypt_ai_config_keys(conn, &old_cipher, &new_cipher).await?;
+        Ok(rewritten)
+    }
+
+    /// Re-encrypt the provider API keys held inside the stored AI configuration JSON
+    async fn reencrypt_ai_config_keys(conn: &mut SqliteConnection, old_cipher: &Aes256Gcm, new_cipher: &Aes256Gcm) -> Result<usize> {
+        let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, config_data FROM ai_configurations")
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| EncryptionError::StorageError(format!("Failed to read ai_configurations: {}", e)))?;
+
+        let mut rewritten = 0;
+        for (id, config_data) in rows {
+            let Ok(mut config) = serde_json::from_str::<serde_json::Value>(&config_data) else {
+                continue;
+            };
+            let mut changed = 0;
+            let providers = config.get_mut("providers").and_then(|providers| providers.as_object_mut());
+            for provider in providers.into_iter().flat_map(|providers| providers.values_mut()) {
+                let Some(api_key) = provider.get_mut("api_key") else {
+                    continue;
+                };
+                let Some(encrypted) = api_key.as_str()
+                    .filter(|value| looks_like_encrypted_data(value))
+                    .and_then(|value| serde_json::from_str::<EncryptedData>(value).ok())
+                else {
+                    continue;
+                };
+
+                let plaintext = Zeroizing::new(decrypt_with_cipher(old_cipher, &encrypted)?);
+                let reencrypted = serde_json::to_string(&encrypt_with_cipher(new_cipher, &plaintext)?)
+                    .map_err(|e| EncryptionError::EncryptionFailed(format!("Field encryption failed: {}", e)))?;
+                *api_key = serde_json::Value::String(reencrypted);
+                changed += 1;
+            }
+
+            if changed > 0 {
+                sqlx::query("UPDATE ai_configurations SET config_data = ? WHERE id = ?")
+                    .bind(config.to_string())
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| EncryptionError::StorageError(format!("Failed to update ai_configurations: {}", e)))?;
+                rewritten += changed;
+            }
+        }
+
+        Ok(rewritten)
+    }
+
     /// Check if a string looks like encrypted data
-    fn looks_like_encrypted_data(value: &str) -> bool {
+    pub fn looks_like_encrypted_data(value: &str) -> bool {
         value.starts_with('{') && value.contains("\"algorithm\"") && value.contains("\"data\"")
     }
 }
@@ -311,10 +739,24 @@ pub mod field_encryption {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_derived_key_matches_password_hash_output() {
+        use argon2::password_hash::{PasswordHasher, SaltString};
+
+        // Vaults created when keys were taken from the PHC hash must still unlock
+        let params = Argon2Params { memory_kib: 8 * 1024, iterations: 1, parallelism: 1 };
+        let salt = [7u8; 32];
+        let salt_string = SaltString::encode_b64(&salt).unwrap();
+        let hash = params.to_argon2().unwrap().hash_password(b"correct horse", &salt_string).unwrap();
+
+        let key = derive_key("correct horse", &salt, &params).unwrap();
+        assert_eq!(key.as_slice(), hash.hash.unwrap().as_bytes());
+    }
+
     #[tokio::test]
     async fn test_encryption_decryption() {
-        let app_handle = tauri::test::mock_app().handle();
-        let mut manager = EncryptionManager::new(app_handle);
+        let app = tauri::test::mock_app();
+        let mut manager = EncryptionManager::new(app.handle().clone());
         
         // Initialize with test key
         let test_key = [42u8; 32];
@@ -327,10 +769,31 @@ mod tests {
         assert_eq!(plaintext, decrypted);
     }
 
+    #[tokio::test]
+    async fn test_batch_and_single_calls_interchangeable() {
+        let app = tauri::test::mock_app();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        manager.initialize_with_key([42u8; 32]);
+
+        let plaintexts = ["{\"theme\":\"dark\"}", "{\"theme\":\"dark\"}", ""];
+        let batch = manager.encrypt_batch(&plaintexts).unwrap();
+        assert_eq!(batch.len(), 3);
+        // Identical plaintexts still get distinct nonces
+        assert_ne!(batch[0].nonce, batch[1].nonce);
+
+        for (encrypted, plaintext) in batch.iter().zip(plaintexts) {
+            assert_eq!(manager.decrypt(encrypted).unwrap(), plaintext);
+        }
+
+        let singles: Vec<EncryptedData> = plaintexts.iter().map(|p| manager.encrypt(p).unwrap()).collect();
+        assert_eq!(manager.decrypt_batch(&singles).unwrap(), plaintexts);
+        assert!(manager.decrypt_batch(&[]).unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_map_encryption() {
-        let app_handle = tauri::test::mock_app().handle();
-        let mut manager = EncryptionManager::new(app_handle);
+        let app = tauri::test::mock_app();
+        let mut manager = EncryptionManager::new(app.handle().clone());
         
         let test_key = [42u8; 32];
         manager.initialize_with_key(test_key);
@@ -345,6 +808,208 @@ mod tests {
         assert_eq!(data, decrypted);
     }
 
+    #[test]
+    fn test_correct_password_passes_verifier() {
+        let app = tauri::test::mock_app();
+        let manager = EncryptionManager::new(app.handle().clone());
+        let salt = [7u8; 32];
+
+        let key = manager.derive_key("correct horse", &salt, &Argon2Params::default()).unwrap();
+        let verifier = create_verifier(&key).unwrap();
+
+        let same_key = manager.derive_key("correct horse", &salt, &Argon2Params::default()).unwrap();
+        assert!(check_verifier(&same_key, &verifier).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_password_rejected_by_verifier() {
+        let app = tauri::test::mock_app();
+        let manager = EncryptionManager::new(app.handle().clone());
+        let salt = [7u8; 32];
+
+        let key = manager.derive_key("correct horse", &salt, &Argon2Params::default()).unwrap();
+        let verifier = create_verifier(&key).unwrap();
+
+        let wrong_key = manager.derive_key("battery staple", &salt, &Argon2Params::default()).unwrap();
+        assert!(matches!(
+            check_verifier(&wrong_key, &verifier),
+            Err(EncryptionError::InvalidPassword)
+        ));
+    }
+
+    #[test]
+    fn test_custom_argon2_params_round_trip() {
+        let app = tauri::test::mock_app();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        let salt = [3u8; 32];
+        let params = Argon2Params {
+            memory_kib: 8 * 1024,
+            iterations: 3,
+            parallelism: 2,
+        };
+
+        let key = manager.derive_key("password", &salt, &params).unwrap();
+        assert_eq!(key, manager.derive_key("password", &salt, &params).unwrap());
+        assert_ne!(key, manager.derive_key("password", &salt, &Argon2Params::legacy()).unwrap());
+
+        manager.initialize_with_key(*key);
+        let encrypted = manager.encrypt("student record").unwrap();
+        assert_eq!(manager.decrypt(&encrypted).unwrap(), "student record");
+
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(serde_json::from_str::<Argon2Params>(&json).unwrap(), params);
+    }
+
+    #[test]
+    fn test_master_key_zeroed_on_drop() {
+        let app = tauri::test::mock_app();
+        let mut manager = std::mem::ManuallyDrop::new(EncryptionManager::new(app.handle().clone()));
+        manager.initialize_with_key([42u8; 32]);
+
+        let key_ptr: *const [u8; 32] = &**manager.master_key.as_ref().unwrap();
+
+        // ManuallyDrop keeps the storage alive after the destructor runs so it can be inspected
+        unsafe { std::mem::ManuallyDrop::drop(&mut manager) };
+        let key_bytes = unsafe { std::ptr::read_volatile(key_ptr) };
+
+        assert_eq!(key_bytes, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_app_data_dir_uses_tauri_path_resolver() {
+        use tauri::Manager;
+
+        let app = tauri::test::mock_app();
+        let manager = EncryptionManager::new(app.handle().clone());
+
+        let resolved = manager.get_app_data_dir().unwrap();
+        assert_eq!(resolved, app.path().app_data_dir().unwrap());
+
+        if let Ok(home) = std::env::var("HOME") {
+            assert_ne!(resolved, std::path::PathBuf::from(home).join(".local/share/case-crafter"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_change_password_reencrypts_stored_data() {
+        let app = tauri::test::mock_app();
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let db = crate::database::DatabaseManager::in_memory().await.unwrap();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        manager.set_data_dir(data_dir.path().to_path_buf());
+        manager.set_kdf_params(Argon2Params { memory_kib: 8 * 1024, iterations: 1, parallelism: 1 });
+        manager.initialize("old password").await.unwrap();
+
+        let preferences = r#"{"theme":"dark"}"#;
+        let stored = field_encryption::encrypt_if_sensitive(&manager, "preferences", preferences).unwrap();
+        sqlx::query("INSERT INTO users (username, preferences) VALUES ('student', ?)")
+            .bind(&stored)
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        let stored_key = field_encryption::encrypt_if_sensitive(&manager, "api_key", "sk-test-secret").unwrap();
+        let config_data = serde_json::json!({ "providers": { "openai": { "api_key": stored_key } } });
+        sqlx::query("INSERT OR REPLACE INTO ai_configurations (id, config_data) VALUES (1, ?)")
+            .bind(config_data.to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        manager.change_password(db.pool(), "old password", "new password").await.unwrap();
+
+        let (rewritten,): (String,) = sqlx::query_as("SELECT preferences FROM users WHERE username = 'student'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_ne!(rewritten, stored);
+        assert_eq!(
+            field_encryption::decrypt_if_encrypted(&manager, "preferences", &rewritten).unwrap(),
+            preferences
+        );
+
+        let (config_data,): (String,) = sqlx::query_as("SELECT config_data FROM ai_configurations WHERE id = 1")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        let config: serde_json::Value = serde_json::from_str(&config_data).unwrap();
+        let rewritten_key = config["providers"]["openai"]["api_key"].as_str().unwrap();
+        assert_ne!(rewritten_key, stored_key);
+        assert_eq!(
+            field_encryption::decrypt_if_encrypted(&manager, "api_key", rewritten_key).unwrap(),
+            "sk-test-secret"
+        );
+        assert!(manager.verify_password("new password").await.unwrap());
+        assert!(!manager.verify_password("old password").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_saved_kdf_params_apply_to_later_sessions() {
+        let app = tauri::test::mock_app();
+        let data_dir = tempfile::tempdir().unwrap();
+        let session = || {
+            let mut manager = EncryptionManager::new(app.handle().clone());
+            manager.set_data_dir(data_dir.path().to_path_buf());
+            manager
+        };
+
+        let mut fresh = session();
+        fresh.load_saved_kdf_params().unwrap();
+        assert_eq!(fresh.kdf_params(), Argon2Params::default());
+
+        let tuned = Argon2Params { memory_kib: 32 * 1024, iterations: 3, parallelism: 1 };
+        let mut manager = session();
+        manager.save_kdf_params(tuned).await.unwrap();
+        assert_eq!(manager.kdf_params(), tuned);
+
+        let mut later = session();
+        later.load_saved_kdf_params().unwrap();
+        assert_eq!(later.kdf_params(), tuned);
+    }
+
+    #[tokio::test]
+    async fn test_exported_config_contains_no_secrets() {
+        let app = tauri::test::mock_app();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        manager.set_data_dir(data_dir.path().to_path_buf());
+        let key = [42u8; 32];
+        manager.initialize_with_key(key);
+        let salt = manager.get_or_create_salt().await.unwrap();
+        let encrypted = manager.encrypt("quarterly board minutes").unwrap();
+
+        let exported = manager.export_config().await.unwrap();
+        assert!(exported.initialized);
+        assert!(exported.has_salt);
+        assert!(exported.salt_path.ends_with(SALT_FILE));
+
+        let json = serde_json::to_string(&exported).unwrap();
+        for secret in [&key, &salt] {
+            let as_array = serde_json::to_string(&secret.to_vec()).unwrap();
+            let as_hex: String = secret.iter().map(|b| format!("{:02x}", b)).collect();
+            for encoding in [BASE64.encode(secret), as_hex, as_array[1..as_array.len() - 1].to_string()] {
+                assert!(!json.contains(&encoding), "export leaks {}", encoding);
+            }
+        }
+        assert!(!json.contains("quarterly board minutes"));
+        assert!(!json.contains(&encrypted.data));
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_unsupported_config() {
+        let app = tauri::test::mock_app();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut manager = EncryptionManager::new(app.handle().clone());
+        manager.set_data_dir(data_dir.path().to_path_buf());
+        let mut config = manager.export_config().await.unwrap();
+        config.encryption_algorithm = "ROT13".to_string();
+
+        let error = manager.import_config(&config).await.unwrap_err();
+        assert!(matches!(error, EncryptionError::InvalidFormat(_)));
+        assert_eq!(manager.kdf_params(), Argon2Params::default());
+    }
+
     #[test]
     fn test_sensitive_field_detection() {
         assert!(field_encryption::is_sensitive_field("password"));
@@ -1,4 +1,5 @@
 pub mod database;
 pub mod encryption;
 pub mod backup;
-pub mod ai;
\ No newline at end of file
+pub mod ai;
+pub mod clock;
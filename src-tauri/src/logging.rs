@@ -0,0 +1,89 @@
+// Structured logging to the console and to daily log files, with a level that can be changed at runtime
+
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/// Level used when neither `RUST_LOG` nor the user sets one
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Directory under the app data dir that log files are written to
+pub const LOG_DIR: &str = "logs";
+
+/// Log files are named `case-crafter.log.<date>`
+const LOG_FILE_PREFIX: &str = "case-crafter.log";
+
+/// Changes the level of the installed subscriber
+pub struct LoggingHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    level: Mutex<String>,
+    /// Flushes buffered file output when dropped, so it has to live as long as the app
+    _file_guard: WorkerGuard,
+}
+
+impl LoggingHandle {
+    /// The level or filter directives currently applied
+    pub fn level(&self) -> String {
+        self.level.lock().unwrap().clone()
+    }
+
+    /// Apply a level such as `debug`, or filter directives such as `info,case_crafter::ai=trace`
+    pub fn set_level(&self, level: &str) -> Result<(), String> {
+        let filter = parse_filter(level)?;
+        self.filter.reload(filter).map_err(|e| format!("Failed to change log level: {}", e))?;
+        *self.level.lock().unwrap() = level.trim().to_string();
+        Ok(())
+    }
+}
+
+fn parse_filter(level: &str) -> Result<EnvFilter, String> {
+    if level.trim().is_empty() {
+        return Err("Log level cannot be empty".to_string());
+    }
+    EnvFilter::try_new(level.trim()).map_err(|e| format!("Invalid log level '{}': {}", level, e))
+}
+
+/// Install the global subscriber, writing to stderr and to daily files under
+/// `<app_data_dir>/logs`. A valid `RUST_LOG` replaces the default level.
+pub fn init(app_data_dir: &Path) -> Result<LoggingHandle, String> {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|level| parse_filter(level).is_ok())
+        .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+    let (filter, filter_handle) = reload::Layer::new(parse_filter(&level)?);
+
+    let log_dir = app_data_dir.join(LOG_DIR);
+    std::fs::create_dir_all(&log_dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    let (file_writer, file_guard) = tracing_appender::non_blocking(tracing_appender::rolling::daily(&log_dir, LOG_FILE_PREFIX));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .try_init()
+        .map_err(|e| format!("Failed to install log subscriber: {}", e))?;
+
+    Ok(LoggingHandle {
+        filter: filter_handle,
+        level: Mutex::new(level),
+        _file_guard: file_guard,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_parsing() {
+        for level in ["debug", "warn", "info,case_crafter::ai=trace"] {
+            assert!(parse_filter(level).is_ok(), "{}", level);
+        }
+        for level in ["", "  ", "info,case_crafter::ai=loud"] {
+            assert!(parse_filter(level).is_err(), "{:?}", level);
+        }
+    }
+}
@@ -8,17 +8,27 @@ mod ai;
 mod case_study;
 mod assessment;
 mod config;
+mod app_state;
+mod logging;
+mod clock;
 
 use database::DatabaseManager;
 use encryption::commands as encryption_commands;
 use backup::commands as backup_commands;
+use database::commands as database_commands;
 use database::seeds::commands as seed_commands;
 use ai::commands as ai_commands;
 use case_study::commands as case_study_commands;
 use assessment::commands as assessment_commands;
 use config::commands as config_commands;
+use app_state::{AppInitState, AppInitStateHandle, AppInitStatus};
+use std::sync::Arc;
+use std::time::Duration;
 use tauri::{Manager, State};
 
+/// How long shutdown waits for a running backup before cutting it off
+const SHUTDOWN_BACKUP_TIMEOUT: Duration = Duration::from_secs(30);
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -36,61 +46,138 @@ async fn run_database_migration(db: State<'_, DatabaseManager>, command: String)
     Ok(format!("Migration command '{}' completed successfully", command))
 }
 
+/// Drop cached dashboard statistics so the next reads recompute them
+#[tauri::command]
+async fn refresh_statistics(
+    case_study_state: State<'_, case_study_commands::CaseStudyManagerState>,
+    assessment_state: State<'_, assessment_commands::AssessmentWorkflowState>,
+    config_service: State<'_, config_commands::ConfigurationService>,
+) -> Result<(), String> {
+    if let Some(manager) = case_study_state.read().await.as_ref() {
+        manager.invalidate_statistics();
+    }
+    if let Some(workflow) = assessment_state.read().await.as_ref() {
+        workflow.repository.invalidate_statistics();
+    }
+    config_service.repository.invalidate_statistics();
+    Ok(())
+}
+
+/// Open the database and manage every state that depends on it
+async fn initialize_services(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let db_manager = DatabaseManager::new(&app_handle).await.map_err(|e| e.to_string())?;
+    let db_manager = Arc::new(db_manager);
+    app_handle.manage(db_manager.clone());
+
+    // Initialize AI state with database pool
+    let ai_state = ai_commands::setup_ai_state(app_handle.clone(), db_manager.pool().clone());
+    app_handle.manage(ai_state);
+
+    // Initialize template repository state
+    let template_repo_state = ai_commands::setup_template_repository_state(db_manager.pool().clone());
+    app_handle.manage(template_repo_state);
+
+    // Initialize case study manager state
+    let case_study_state = case_study_commands::setup_case_study_manager_state(
+        (*db_manager).clone(),
+        app_handle.path().app_data_dir().ok(),
+    );
+    app_handle.manage(case_study_state);
+
+    // Initialize assessment workflow state
+    let assessment_state = assessment_commands::setup_assessment_workflow_state((*db_manager).clone());
+    app_handle.manage(assessment_state);
+
+    // Initialize configuration service state
+    let config_service = config_commands::ConfigurationService::new((*db_manager).clone());
+    app_handle.manage(config_service);
+
+    // Initialize backup system
+    let (backup_manager_state, backup_scheduler_state) = backup_commands::setup_backup_state(
+        app_handle.clone(),
+        db_manager,
+        None, // Encryption manager will be added later if needed
+    );
+
+    app_handle.manage(backup_manager_state);
+    app_handle.manage(backup_scheduler_state);
+
+    tracing::info!("Database, template repository, and backup system initialized successfully");
+    Ok(())
+}
+
+/// Whether startup initialization is still running, succeeded, or failed and why
+#[tauri::command]
+async fn get_app_init_status(init_state: State<'_, AppInitStateHandle>) -> Result<AppInitStatus, String> {
+    Ok(init_state.status().await)
+}
+
+/// Change the log level, e.g. `debug` or `info,case_crafter::ai=trace`. Returns the level
+/// now applied.
+#[tauri::command]
+fn set_log_level(level: String, logging_handle: State<'_, logging::LoggingHandle>) -> Result<String, String> {
+    logging_handle.set_level(&level)?;
+    Ok(logging_handle.level())
+}
+
+/// Re-run startup initialization after a failure. Returns immediately if it already succeeded.
+#[tauri::command]
+async fn retry_initialization(
+    app_handle: tauri::AppHandle,
+    init_state: State<'_, AppInitStateHandle>,
+) -> Result<AppInitStatus, String> {
+    Ok(init_state.initialize(|| initialize_services(app_handle)).await)
+}
+
+/// Stop the backup scheduler, letting a running backup finish, then checkpoint and close
+/// the database. Safe to run more than once.
+async fn shutdown_services(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if let Some(scheduler) = app_handle.try_state::<backup_commands::BackupSchedulerState>() {
+        if !scheduler.lock().await.shutdown(SHUTDOWN_BACKUP_TIMEOUT).await {
+            tracing::warn!("Backup still running after {:?}; stopping it", SHUTDOWN_BACKUP_TIMEOUT);
+        }
+    }
+    if let Some(db_manager) = app_handle.try_state::<Arc<DatabaseManager>>() {
+        db_manager.checkpoint_and_close().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Flush and close everything before the frontend quits the app
+#[tauri::command]
+async fn prepare_for_shutdown(app_handle: tauri::AppHandle) -> Result<(), String> {
+    shutdown_services(&app_handle).await
+}
+
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .setup(|app| {
+            // Logging comes first so the rest of startup is captured
+            match app.path().app_data_dir().map_err(|e| e.to_string()).and_then(|dir| logging::init(&dir)) {
+                Ok(logging_handle) => {
+                    app.manage(logging_handle);
+                }
+                Err(e) => eprintln!("Logging to file is unavailable: {}", e),
+            }
+
             // Initialize encryption state
             let encryption_state = encryption_commands::setup_encryption_state(app.handle().clone());
             app.manage(encryption_state);
+            app.manage(seed_commands::SeedCancellationState::default());
 
-            // Initialize AI state will be done after database is ready
+            // Database-backed state is set up in the background; its outcome is
+            // reported through get_app_init_status and can be retried
+            let init_state: AppInitStateHandle = Arc::new(AppInitState::new());
+            app.manage(init_state.clone());
 
-            // Initialize database and backup system on app startup
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                match DatabaseManager::new(&app_handle).await {
-                    Ok(db_manager) => {
-                        let db_manager = std::sync::Arc::new(db_manager);
-                        app_handle.manage(db_manager.clone());
-                        
-                        // Initialize AI state with database pool
-                        let ai_state = ai_commands::setup_ai_state(app_handle.clone(), db_manager.pool().clone());
-                        app_handle.manage(ai_state);
-                        
-                        // Initialize template repository state
-                        let template_repo_state = ai_commands::setup_template_repository_state(db_manager.pool().clone());
-                        app_handle.manage(template_repo_state);
-                        
-                        // Initialize case study manager state
-                        let case_study_state = case_study_commands::setup_case_study_manager_state((*db_manager).clone());
-                        app_handle.manage(case_study_state);
-                        
-                        // Initialize assessment workflow state
-                        let assessment_state = assessment_commands::setup_assessment_workflow_state((*db_manager).clone());
-                        app_handle.manage(assessment_state);
-                        
-                        // Initialize configuration service state
-                        let config_service = config_commands::ConfigurationService::new((*db_manager).clone());
-                        app_handle.manage(config_service);
-                        
-                        // Initialize backup system
-                        let (backup_manager_state, backup_scheduler_state) = backup_commands::setup_backup_state(
-                            app_handle.clone(),
-                            db_manager,
-                            None, // Encryption manager will be added later if needed
-                        );
-                        
-                        app_handle.manage(backup_manager_state);
-                        app_handle.manage(backup_scheduler_state);
-                        
-                        println!("Database, template repository, and backup system initialized successfully");
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to initialize database: {}", e);
-                    }
+                let status = init_state.initialize(|| initialize_services(app_handle)).await;
+                if let AppInitStatus::Failed { error } = status {
+                    tracing::error!("Failed to initialize database: {}", error);
                 }
             });
             Ok(())
@@ -99,10 +186,36 @@ fn main() {
             greet, 
             get_database_stats, 
             run_database_migration,
+            refresh_statistics,
+            get_app_init_status,
+            set_log_level,
+            retry_initialization,
+            prepare_for_shutdown,
+            database_commands::rollback_migration,
+            database_commands::get_migration_status,
+            database_commands::list_applied_migrations,
+            database_commands::verify_migration_checksums,
+            database_commands::purge_deleted_records,
+            database_commands::get_audit_log,
+            database_commands::add_to_collection,
+            database_commands::remove_from_collection,
+            database_commands::reorder_collection,
+            database_commands::list_collection_case_studies,
+            database_commands::export_generation_history,
+            database_commands::import_generation_history,
+            database_commands::get_user_activity,
+            database_commands::create_domain,
+            database_commands::update_domain,
+            database_commands::delete_domain,
+            database_commands::list_domains,
+            database_commands::get_domain_usage,
             encryption_commands::initialize_encryption,
             encryption_commands::is_encryption_initialized,
+            encryption_commands::benchmark_kdf,
             encryption_commands::encrypt_value,
             encryption_commands::decrypt_value,
+            encryption_commands::encrypt_values,
+            encryption_commands::decrypt_values,
             encryption_commands::encrypt_user_preferences,
             encryption_commands::decrypt_user_preferences,
             encryption_commands::encrypt_map,
@@ -110,6 +223,7 @@ fn main() {
             encryption_commands::change_encryption_password,
             encryption_commands::validate_encryption_password,
             encryption_commands::export_encryption_config,
+            encryption_commands::import_encryption_config,
             encryption_commands::test_encryption,
             encryption_commands::get_encryption_stats,
             backup_commands::initialize_backup,
@@ -117,6 +231,8 @@ fn main() {
             backup_commands::create_backup,
             backup_commands::list_backups,
             backup_commands::restore_backup,
+            backup_commands::restore_backup_tables,
+            backup_commands::preview_restore,
             backup_commands::delete_backup,
             backup_commands::validate_backup,
             backup_commands::get_backup_stats,
@@ -135,6 +251,7 @@ fn main() {
             backup_commands::test_backup_system,
             backup_commands::get_backup_system_info,
             seed_commands::seed_database,
+            seed_commands::cancel_seeding,
             seed_commands::get_default_seed_config,
             seed_commands::get_database_record_counts,
             seed_commands::check_sample_data_exists,
@@ -151,9 +268,19 @@ fn main() {
             ai_commands::update_ai_config,
             ai_commands::switch_ai_provider,
             ai_commands::generate_content,
+            ai_commands::generate_content_stream,
+            ai_commands::cancel_content_stream,
+            ai_commands::cancel_generation,
+            ai_commands::record_generation,
+            ai_commands::get_generation_cost_summary,
+            ai_commands::clear_ai_cache,
             ai_commands::get_available_models,
             ai_commands::validate_ai_provider,
+            ai_commands::check_all_providers,
+            ai_commands::get_provider_capabilities,
+            ai_commands::get_active_provider_capabilities,
             ai_commands::get_ai_stats,
+            ai_commands::get_ai_status,
             ai_commands::get_supported_ai_providers,
             ai_commands::is_ai_provider_supported,
             ai_commands::create_default_ai_config,
@@ -167,12 +294,26 @@ fn main() {
             ai_commands::render_prompt_template,
             ai_commands::get_prompt_categories,
             ai_commands::export_prompt_templates,
+            ai_commands::import_prompt_templates,
+            ai_commands::create_prompt_template,
+            ai_commands::update_prompt_template,
+            ai_commands::delete_prompt_template,
             ai_commands::load_default_prompt_templates,
             ai_commands::generate_case_study,
             ai_commands::generate_case_study_enhanced,
+            ai_commands::generate_case_studies_batch,
+            ai_commands::regenerate_case_study_section,
+            ai_commands::semantic_search_case_studies,
+            ai_commands::backfill_case_study_embeddings,
             ai_commands::validate_case_study_params,
+            ai_commands::save_generation_preset,
+            ai_commands::list_generation_presets,
+            ai_commands::update_generation_preset,
+            ai_commands::delete_generation_preset,
+            ai_commands::generate_case_study_from_preset,
             ai_commands::generate_assessment_questions,
             ai_commands::generate_assessment_questions_enhanced,
+            ai_commands::generate_and_save_questions,
             ai_commands::validate_question_generation_params,
             ai_commands::create_default_question_params,
             ai_commands::get_question_types,
@@ -190,6 +331,7 @@ fn main() {
             ai_commands::delete_database_template,
             ai_commands::clone_database_template,
             ai_commands::get_template_usage_stats,
+            ai_commands::get_template_analytics,
             ai_commands::record_template_usage,
             ai_commands::get_database_template_categories,
             ai_commands::create_template_category,
@@ -203,30 +345,48 @@ fn main() {
             ai_commands::validate_model_parameters,
             ai_commands::adjust_model_parameters,
             ai_commands::estimate_generation_cost,
+            ai_commands::count_tokens,
+            ai_commands::check_context_window,
             ai_commands::update_model_availability,
+            ai_commands::refresh_ollama_availability,
             ai_commands::generate_with_auto_model,
             ai_commands::create_model_selection_criteria,
             ai_commands::get_model_parameter_constraints,
+            ai_commands::list_generation_style_presets,
             // Case study management commands
             case_study_commands::create_case_study,
             case_study_commands::get_case_study,
+            case_study_commands::get_case_study_readability,
             case_study_commands::update_case_study,
             case_study_commands::delete_case_study,
+            case_study_commands::bulk_delete_case_studies,
+            case_study_commands::restore_deleted_case_study,
+            case_study_commands::purge_case_study,
+            case_study_commands::list_deleted_case_studies,
             case_study_commands::list_case_studies,
             case_study_commands::search_case_studies,
+            case_study_commands::search_case_studies_ranked,
+            case_study_commands::list_case_studies_page,
+            case_study_commands::search_case_studies_page,
+            case_study_commands::export_case_study,
             case_study_commands::publish_case_study,
             case_study_commands::archive_case_study,
             case_study_commands::restore_case_study,
+            case_study_commands::bulk_update_case_study_status,
             case_study_commands::duplicate_case_study,
             case_study_commands::get_case_study_versions,
             case_study_commands::get_case_study_version,
+            case_study_commands::diff_case_study_versions,
             case_study_commands::restore_to_version,
             case_study_commands::get_case_study_statistics,
             case_study_commands::get_recent_case_studies,
+            case_study_commands::list_case_study_tags,
+            case_study_commands::merge_case_study_tags,
             case_study_commands::get_case_studies_by_category,
             case_study_commands::get_case_studies_by_status,
             case_study_commands::count_case_studies,
             case_study_commands::case_study_exists,
+            case_study_commands::find_similar_case_studies,
             case_study_commands::create_default_case_study_metadata,
             case_study_commands::create_default_case_study_filter,
             case_study_commands::create_default_search_query,
@@ -235,9 +395,13 @@ fn main() {
             case_study_commands::validate_case_study_update_data,
             // Assessment workflow commands
             assessment_commands::create_assessment_workflow,
+            assessment_commands::create_assessment_from_collection,
             assessment_commands::get_assessment_workflow,
             assessment_commands::update_assessment_workflow,
             assessment_commands::delete_assessment_workflow,
+            assessment_commands::restore_deleted_assessment_workflow,
+            assessment_commands::purge_assessment_workflow,
+            assessment_commands::list_deleted_assessment_workflows,
             assessment_commands::list_assessment_workflows,
             assessment_commands::publish_assessment_workflow,
             assessment_commands::archive_assessment_workflow,
@@ -248,9 +412,17 @@ fn main() {
             assessment_commands::pause_assessment_session,
             assessment_commands::resume_assessment_session,
             assessment_commands::submit_assessment_for_grading,
+            assessment_commands::auto_grade_assessment_session,
+            assessment_commands::override_assessment_answer_grade,
+            assessment_commands::score_with_rubric,
             assessment_commands::get_assessment_result,
+            assessment_commands::export_assessment_results,
             assessment_commands::get_user_assessment_sessions,
+            assessment_commands::recover_assessment_session,
             assessment_commands::get_assessment_statistics,
+            assessment_commands::add_question_bank_entry,
+            assessment_commands::search_question_bank,
+            assessment_commands::import_questions_to_bank,
             assessment_commands::create_default_assessment_configuration,
             assessment_commands::create_default_assessment_metadata,
             assessment_commands::create_default_assessment_workflow_filter,
@@ -267,9 +439,13 @@ fn main() {
             // Configuration system commands
             config_commands::create_configuration,
             config_commands::get_configuration,
+            config_commands::migrate_configuration_data,
             config_commands::get_configuration_schema,
             config_commands::update_configuration,
             config_commands::delete_configuration,
+            config_commands::restore_deleted_configuration,
+            config_commands::purge_configuration,
+            config_commands::list_deleted_configurations,
             config_commands::list_configurations,
             config_commands::search_configurations,
             config_commands::get_configuration_statistics,
@@ -293,6 +469,13 @@ fn main() {
             config_commands::get_conditional_dependencies,
             config_commands::create_conditional_expression
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event {
+                if let Err(e) = tauri::async_runtime::block_on(shutdown_services(app_handle)) {
+                    tracing::warn!("Failed to shut down cleanly: {}", e);
+                }
+            }
+        });
 }
\ No newline at end of file
@@ -44,7 +44,7 @@ export const ProviderModelSelector: React.FC<ProviderModelSelectorProps> = ({
   const [error, setError] = useState<string | null>(null);
   const [lastRefresh, setLastRefresh] = useState<Date | null>(null);
 
-  const loadModels = async () => {
+  const loadModels = async (forceRefresh = false) => {
     if (!enabled) {
       setModels([]);
       return;
@@ -54,8 +54,8 @@ export const ProviderModelSelector: React.FC<ProviderModelSelectorProps> = ({
     setError(null);
 
     try {
-      // Try to get available models from the provider
-      const availableModels = await invoke<ModelInfo[]>('get_available_models');
+      // Try to get available models from the provider; the backend caches the list
+      const availableModels = await invoke<ModelInfo[]>('get_available_models', { forceRefresh });
       setModels(availableModels);
       setLastRefresh(new Date());
     } catch (err) {
@@ -101,7 +101,7 @@ export const ProviderModelSelector: React.FC<ProviderModelSelectorProps> = ({
   }, [provider, enabled]);
 
   const handleRefresh = () => {
-    loadModels();
+    loadModels(true);
   };
 
   const getProviderDisplayName = (providerType: ProviderType) => {
@@ -7,6 +7,7 @@ import {
   ModelSelectionCriteria,
   GenerationParams,
   ParameterConstraints,
+  GenerationStylePreset,
   ModelUseCase
 } from '../types/aiConfig';
 
@@ -32,6 +33,7 @@ interface UseAIConfigReturn {
   validateParameters: (modelId: string, params: GenerationParams) => Promise<boolean>;
   adjustParameters: (modelId: string, params: GenerationParams) => Promise<GenerationParams | null>;
   getParameterConstraints: (modelId: string) => Promise<ParameterConstraints | null>;
+  listGenerationStylePresets: (modelId: string) => Promise<GenerationStylePreset[] | null>;
   
   // Cost estimation
   estimateCost: (modelId: string, inputTokens: number, outputTokens: number) => Promise<number>;
@@ -169,6 +171,13 @@ export const useAIConfig = (): UseAIConfigReturn => {
     );
   }, [handleOperation]);
 
+  const listGenerationStylePresets = useCallback(async (modelId: string): Promise<GenerationStylePreset[] | null> => {
+    return await handleOperation(
+      () => invoke<GenerationStylePreset[]>('list_generation_style_presets', { modelId }),
+      `Failed to list generation styles for model: ${modelId}`
+    );
+  }, [handleOperation]);
+
   // Cost estimation
   const estimateCost = useCallback(async (modelId: string, inputTokens: number, outputTokens: number): Promise<number> => {
     const result = await handleOperation(
@@ -262,6 +271,7 @@ export const useAIConfig = (): UseAIConfigReturn => {
     validateParameters,
     adjustParameters,
     getParameterConstraints,
+    listGenerationStylePresets,
     
     // Cost estimation
     estimateCost,
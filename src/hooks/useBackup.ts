@@ -5,9 +5,11 @@ import { invoke } from '@tauri-apps/api/core';
 import { 
   BackupConfig, 
   BackupInfo, 
+  BackupOptions,
   BackupStats, 
   BackupSchedule,
   SchedulerStatus,
+  SchedulerStatusReport,
   SchedulerStats,
   BackupEvent,
   BackupProgressInfo,
@@ -23,8 +25,8 @@ export interface UseBackupReturn {
   backups: BackupInfo[];
   isLoading: boolean;
   error: string | null;
-  createBackup: (description?: string) => Promise<BackupInfo | null>;
-  restoreBackup: (backupPath: string, force?: boolean) => Promise<void>;
+  createBackup: (description?: string, options?: BackupOptions) => Promise<BackupInfo | null>;
+  restoreBackup: (backupPath: string, force?: boolean, passphrase?: string) => Promise<void>;
   deleteBackup: (backupPath: string) => Promise<void>;
   validateBackup: (backupPath: string) => Promise<boolean>;
   refreshBackups: () => Promise<void>;
@@ -108,13 +110,13 @@ export function useBackup(): UseBackupReturn {
     }
   }, []);
 
-  const createBackup = useCallback(async (description?: string): Promise<BackupInfo | null> => {
+  const createBackup = useCallback(async (description?: string, options: BackupOptions = {}): Promise<BackupInfo | null> => {
     setIsLoading(true);
     setError(null);
     setProgressInfo({ isRunning: true, currentStep: 'Initializing backup...' });
 
     try {
-      const backupInfo = await invoke('create_backup', { description }) as BackupInfo;
+      const backupInfo = await invoke('create_backup', { description, ...options }) as BackupInfo;
       
       // Refresh data after successful backup
       await Promise.all([refreshBackups(), refreshStats()]);
@@ -131,13 +133,13 @@ export function useBackup(): UseBackupReturn {
     }
   }, [refreshBackups, refreshStats]);
 
-  const restoreBackup = useCallback(async (backupPath: string, force = false): Promise<void> => {
+  const restoreBackup = useCallback(async (backupPath: string, force = false, passphrase?: string): Promise<void> => {
     setIsLoading(true);
     setError(null);
     setProgressInfo({ isRunning: true, currentStep: 'Restoring backup...' });
 
     try {
-      await invoke('restore_backup', { backupPath, force });
+      await invoke('restore_backup', { backupPath, force, passphrase });
       setProgressInfo({ isRunning: false });
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : 'Backup restoration failed';
@@ -226,7 +228,7 @@ export function useBackupScheduler(): UseBackupSchedulerReturn {
     // Set up polling for status updates when scheduler is running
     intervalRef.current = setInterval(async () => {
       try {
-        const currentStatus = await invoke('get_scheduler_status') as SchedulerStatus;
+        const { status: currentStatus } = await invoke('get_scheduler_status') as SchedulerStatusReport;
         setStatus(currentStatus);
         
         if (currentStatus === SchedulerStatus.Running) {
@@ -257,13 +259,13 @@ export function useBackupScheduler(): UseBackupSchedulerReturn {
         recentEvents
       ] = await Promise.all([
         invoke('get_backup_schedule') as Promise<BackupSchedule>,
-        invoke('get_scheduler_status') as Promise<SchedulerStatus>,
+        invoke('get_scheduler_status') as Promise<SchedulerStatusReport>,
         invoke('get_scheduler_stats') as Promise<SchedulerStats>,
         invoke('get_backup_events', { limit: 50 }) as Promise<BackupEvent[]>,
       ]);
 
       setSchedule(currentSchedule);
-      setStatus(currentStatus);
+      setStatus(currentStatus.status);
       setStats(currentStats);
       setEvents(recentEvents);
     } catch (err) {
@@ -1,8 +1,14 @@
 import { useState, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import {
+  BATCH_PROGRESS_EVENT,
+  BatchItemResult,
+  BatchProgress,
   CaseStudyGenerationParams,
   GeneratedCaseStudy,
+  GenerationPreset,
+  UpdateGenerationPreset,
   CaseStudyGenerationHelper,
 } from '../types/caseStudyGeneration';
 
@@ -15,7 +21,20 @@ interface UseCaseStudyGenerationReturn {
   
   // Actions
   generateCaseStudy: (params: CaseStudyGenerationParams) => Promise<GeneratedCaseStudy | null>;
+  generateBatch: (
+    params: CaseStudyGenerationParams[],
+    concurrency?: number,
+    onProgress?: (progress: BatchProgress) => void
+  ) => Promise<BatchItemResult[] | null>;
   validateParams: (params: CaseStudyGenerationParams) => Promise<string[]>;
+  savePreset: (userId: string, name: string, params: CaseStudyGenerationParams) => Promise<GenerationPreset | null>;
+  listPresets: (userId: string) => Promise<GenerationPreset[]>;
+  updatePreset: (presetId: number, update: UpdateGenerationPreset) => Promise<GenerationPreset | null>;
+  deletePreset: (presetId: number) => Promise<boolean>;
+  generateFromPreset: (
+    presetId: number,
+    overrides?: Partial<CaseStudyGenerationParams>
+  ) => Promise<GeneratedCaseStudy | null>;
   clearResults: () => void;
   clearError: () => void;
   
@@ -78,6 +97,26 @@ export const useCaseStudyGeneration = (): UseCaseStudyGenerationReturn => {
     return result;
   }, [handleOperation]);
 
+  const generateBatch = useCallback(async (
+    params: CaseStudyGenerationParams[],
+    concurrency?: number,
+    onProgress?: (progress: BatchProgress) => void
+  ): Promise<BatchItemResult[] | null> => {
+    setIsGenerating(true);
+    const unlisten = onProgress
+      ? await listen<BatchProgress>(BATCH_PROGRESS_EVENT, (event) => onProgress(event.payload))
+      : undefined;
+
+    const result = await handleOperation(
+      () => invoke<BatchItemResult[]>('generate_case_studies_batch', { params, concurrency }),
+      'Failed to generate case studies'
+    );
+
+    unlisten?.();
+    setIsGenerating(false);
+    return result;
+  }, [handleOperation]);
+
   const validateParams = useCallback(async (params: CaseStudyGenerationParams): Promise<string[]> => {
     const result = await handleOperation(
       () => invoke<string[]>('validate_case_study_params', { params }),
@@ -89,6 +128,60 @@ export const useCaseStudyGeneration = (): UseCaseStudyGenerationReturn => {
     return errors;
   }, [handleOperation]);
 
+  const savePreset = useCallback(async (
+    userId: string,
+    name: string,
+    params: CaseStudyGenerationParams
+  ): Promise<GenerationPreset | null> => {
+    return handleOperation(
+      () => invoke<GenerationPreset>('save_generation_preset', { userId, name, params }),
+      'Failed to save preset'
+    );
+  }, [handleOperation]);
+
+  const listPresets = useCallback(async (userId: string): Promise<GenerationPreset[]> => {
+    const result = await handleOperation(
+      () => invoke<GenerationPreset[]>('list_generation_presets', { userId }),
+      'Failed to load presets'
+    );
+    return result || [];
+  }, [handleOperation]);
+
+  const updatePreset = useCallback(async (
+    presetId: number,
+    update: UpdateGenerationPreset
+  ): Promise<GenerationPreset | null> => {
+    return handleOperation(
+      () => invoke<GenerationPreset>('update_generation_preset', { presetId, update }),
+      'Failed to update preset'
+    );
+  }, [handleOperation]);
+
+  const deletePreset = useCallback(async (presetId: number): Promise<boolean> => {
+    const result = await handleOperation(
+      () => invoke<boolean>('delete_generation_preset', { presetId }),
+      'Failed to delete preset'
+    );
+    return result || false;
+  }, [handleOperation]);
+
+  const generateFromPreset = useCallback(async (
+    presetId: number,
+    overrides?: Partial<CaseStudyGenerationParams>
+  ): Promise<GeneratedCaseStudy | null> => {
+    setIsGenerating(true);
+    const result = await handleOperation(
+      async () => {
+        const generated = await invoke<GeneratedCaseStudy>('generate_case_study_from_preset', { presetId, overrides });
+        setGeneratedCaseStudy(generated);
+        return generated;
+      },
+      'Failed to generate case study from preset'
+    );
+    setIsGenerating(false);
+    return result;
+  }, [handleOperation]);
+
   const clearResults = useCallback(() => {
     setGeneratedCaseStudy(null);
     setValidationErrors([]);
@@ -116,7 +209,13 @@ export const useCaseStudyGeneration = (): UseCaseStudyGenerationReturn => {
     
     // Actions
     generateCaseStudy,
+    generateBatch,
     validateParams,
+    savePreset,
+    listPresets,
+    updatePreset,
+    deletePreset,
+    generateFromPreset,
     clearResults,
     clearError,
     
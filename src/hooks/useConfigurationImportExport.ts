@@ -1,13 +1,13 @@
 import { useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { ConfigurationImportResult } from '../types/configuration';
+import type { ConfigurationImportResult, MergeStrategy } from '../types/configuration';
 
 export interface UseConfigurationImportExportReturn {
   isExporting: boolean;
   isImporting: boolean;
   error: string | null;
   exportTemplates: (templateIds: string[], includeMetadata?: boolean) => Promise<string | null>;
-  importTemplates: (overwriteExisting?: boolean) => Promise<ConfigurationImportResult | null>;
+  importTemplates: (overwriteExisting?: boolean, mergeStrategy?: MergeStrategy) => Promise<ConfigurationImportResult | null>;
   clearError: () => void;
 }
 
@@ -42,13 +42,17 @@ export function useConfigurationImportExport(): UseConfigurationImportExportRetu
     }
   };
 
-  const importTemplates = async (overwriteExisting = false): Promise<ConfigurationImportResult | null> => {
+  const importTemplates = async (
+    overwriteExisting = false,
+    mergeStrategy?: MergeStrategy,
+  ): Promise<ConfigurationImportResult | null> => {
     setIsImporting(true);
     setError(null);
 
     try {
       const result = await invoke<ConfigurationImportResult>('import_configuration_templates', {
         overwriteExisting,
+        mergeStrategy,
       });
       return result;
     } catch (err) {
@@ -35,6 +35,13 @@ export interface GenerationParams {
   seed?: number;
 }
 
+export type GenerationStyle = 'precise' | 'balanced' | 'creative';
+
+export interface GenerationStylePreset {
+  style: GenerationStyle;
+  params: GenerationParams; // the style's values clamped to the model's constraints
+}
+
 export interface ParameterConstraints {
   temperature?: ParameterRange<number>;
   max_tokens?: ParameterRange<number>;
@@ -63,6 +70,22 @@ export interface ModelSelectionCriteria {
 
 export type ProviderType = 'openai' | 'anthropic' | 'ollama';
 
+export interface ProviderHealth {
+  healthy: boolean;
+  latency_ms: number;
+  error?: string;
+}
+
+export interface ProviderCapabilities {
+  supports_streaming: boolean;
+  supports_functions: boolean;
+  supports_vision: boolean;
+  supports_fine_tuning: boolean;
+  max_context_length?: number;
+  supported_formats: string[];
+  rate_limits?: Record<string, number>;
+}
+
 export type ModelPerformancePriority = 'speed' | 'quality' | 'cost' | 'balanced';
 
 export type ModelUseCase = 
@@ -82,8 +105,18 @@ export interface AIConfig {
   timeout_seconds: number;
   enable_logging: boolean;
   cost_tracking_enabled: boolean;
+  sanitization?: SanitizationConfig;
 }
 
+export interface SanitizationConfig {
+  enabled: boolean;
+  allowed_tags: string[];
+  allowed_attributes: string[];
+  allowed_url_schemes: string[];
+}
+
+export type RateLimitMode = 'wait' | 'reject';
+
 export interface ProviderConfig {
   provider_type: ProviderType;
   api_key?: string;
@@ -93,6 +126,8 @@ export interface ProviderConfig {
   models: string[];
   custom_headers: Record<string, string>;
   rate_limit_per_minute?: number;
+  rate_limit_burst?: number;
+  rate_limit_mode?: RateLimitMode;
   max_concurrent_requests?: number;
   enabled: boolean;
 }
@@ -17,6 +17,12 @@ export interface PaginatedResponse<T> {
   has_more: boolean;
 }
 
+// Result of get_app_init_status / retry_initialization
+export type AppInitStatus =
+  | { status: 'initializing' }
+  | { status: 'ready' }
+  | { status: 'failed'; error: string };
+
 // ===== API REQUEST TYPES =====
 
 export interface PaginationParams {
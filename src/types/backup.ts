@@ -10,6 +10,15 @@ export interface BackupConfig {
   include_attachments: boolean;
   include_user_data: boolean;
   exclude_temporary_data: boolean;
+  max_age_days?: number | null; // prune backups older than this, even within max_backups
+  tiered_retention?: TieredRetention | null;
+  filename_template?: string; // placeholders: {timestamp}, {id}, {kind}, {app_version}
+}
+
+export interface TieredRetention {
+  daily_days: number; // keep every backup from this many days
+  weekly_weeks: number; // then the newest of each week
+  monthly_months: number; // then the newest of each month
 }
 
 export interface BackupMetadata {
@@ -24,6 +33,20 @@ export interface BackupMetadata {
   tables_included: string[];
   record_counts: Record<string, number>;
   description?: string | null;
+  key_derivation?: BackupKeyDerivation | null;
+  partial?: boolean; // Limited to a table allowlist
+}
+
+// Present on backups protected by their own passphrase; the key itself is never stored
+export interface BackupKeyDerivation {
+  salt: string;
+  params: { memory_kib: number; iterations: number; parallelism: number };
+}
+
+export interface BackupOptions {
+  destination?: string; // Absolute directory, e.g. an external drive
+  passphrase?: string;
+  tables?: string[]; // Back up only these tables
 }
 
 export interface BackupInfo {
@@ -50,6 +73,7 @@ export interface BackupSchedule {
   last_backup?: string | null; // ISO date string
   retry_attempts: number;
   retry_delay_minutes: number;
+  jitter_minutes?: number; // Random offset applied either side of each scheduled backup
 }
 
 export enum SchedulerStatus {
@@ -59,6 +83,12 @@ export enum SchedulerStatus {
   Error = 'Error',
 }
 
+export interface SchedulerStatusReport {
+  status: SchedulerStatus;
+  next_backup_time?: string | null; // ISO date string
+  last_backup_time?: string | null; // ISO date string
+}
+
 export interface SchedulerStats {
   status: SchedulerStatus;
   total_scheduled_backups: number;
@@ -92,9 +122,10 @@ export interface BackupEvent {
 export interface BackupAPI {
   initializeBackup(config: BackupConfig): Promise<boolean>;
   getBackupConfig(): Promise<BackupConfig>;
-  createBackup(description?: string): Promise<BackupInfo>;
+  createBackup(description?: string, options?: BackupOptions): Promise<BackupInfo>;
   listBackups(): Promise<BackupInfo[]>;
-  restoreBackup(backupPath: string, force: boolean): Promise<boolean>;
+  restoreBackup(backupPath: string, force: boolean, passphrase?: string): Promise<boolean>;
+  restoreBackupTables(backupPath: string, tables: string[], force: boolean, passphrase?: string): Promise<boolean>;
   deleteBackup(backupPath: string): Promise<boolean>;
   validateBackup(backupPath: string): Promise<boolean>;
   getBackupStats(): Promise<BackupStats>;
@@ -105,7 +136,7 @@ export interface BackupAPI {
   pauseBackupScheduler(): Promise<boolean>;
   resumeBackupScheduler(): Promise<boolean>;
   updateBackupSchedule(schedule: BackupSchedule): Promise<boolean>;
-  getSchedulerStatus(): Promise<SchedulerStatus>;
+  getSchedulerStatus(): Promise<SchedulerStatusReport>;
   getBackupSchedule(): Promise<BackupSchedule>;
   getSchedulerStats(): Promise<SchedulerStats>;
   getBackupEvents(limit: number): Promise<BackupEvent[]>;
@@ -130,16 +161,20 @@ export class BackupService implements BackupAPI {
     return this.invoke('get_backup_config');
   }
 
-  async createBackup(description?: string): Promise<BackupInfo> {
-    return this.invoke('create_backup', { description });
+  async createBackup(description?: string, options: BackupOptions = {}): Promise<BackupInfo> {
+    return this.invoke('create_backup', { description, ...options });
   }
 
   async listBackups(): Promise<BackupInfo[]> {
     return this.invoke('list_backups');
   }
 
-  async restoreBackup(backupPath: string, force: boolean): Promise<boolean> {
-    return this.invoke('restore_backup', { backupPath, force });
+  async restoreBackup(backupPath: string, force: boolean, passphrase?: string): Promise<boolean> {
+    return this.invoke('restore_backup', { backupPath, force, passphrase });
+  }
+
+  async restoreBackupTables(backupPath: string, tables: string[], force: boolean, passphrase?: string): Promise<boolean> {
+    return this.invoke('restore_backup_tables', { backupPath, tables, force, passphrase });
   }
 
   async deleteBackup(backupPath: string): Promise<boolean> {
@@ -174,7 +209,7 @@ export class BackupService implements BackupAPI {
     return this.invoke('update_backup_schedule', { schedule });
   }
 
-  async getSchedulerStatus(): Promise<SchedulerStatus> {
+  async getSchedulerStatus(): Promise<SchedulerStatusReport> {
     return this.invoke('get_scheduler_status');
   }
 
@@ -226,6 +261,27 @@ export interface BackupValidationResult {
   metadata?: BackupMetadata;
 }
 
+export interface TableRestorePreview {
+  table: string;
+  rows_to_restore: number; // rows the table holds after the restore
+  current_rows: number;
+  added: number;
+  removed: number;
+  changed: number;
+}
+
+export interface RestorePreview {
+  backup_id: string;
+  created_at: string; // ISO date string
+  app_version: string;
+  database_version: string;
+  schema_version: string;
+  schema_compatible: boolean; // false means the restore needs force
+  kind: 'full' | 'incremental';
+  chain_length: number;
+  tables: TableRestorePreview[];
+}
+
 export interface BackupRestoreOptions {
   force: boolean;
   backupUserData: boolean;
@@ -244,6 +300,7 @@ export const DEFAULT_BACKUP_CONFIG: BackupConfig = {
   include_attachments: true,
   include_user_data: true,
   exclude_temporary_data: true,
+  filename_template: 'case_crafter_backup_{timestamp}_{id}',
 };
 
 export const DEFAULT_BACKUP_SCHEDULE: BackupSchedule = {
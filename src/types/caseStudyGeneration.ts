@@ -1,5 +1,7 @@
 // TypeScript interfaces for case study generation
 
+import { GenerationParams, GenerationStyle } from './aiConfig';
+
 export type DifficultyLevel = 'beginner' | 'intermediate' | 'advanced';
 
 export type CompanySize = 'startup' | 'small' | 'medium' | 'large' | 'enterprise';
@@ -15,6 +17,10 @@ export interface CaseStudyGenerationParams {
   geographical_context?: string;
   time_period?: string;
   specific_focus_areas: string[];
+  enforce_length?: boolean;
+  system_prompt_override?: string; // replaces the template's author persona, max 4000 characters
+  style?: GenerationStyle; // defaults to 'balanced'
+  generation_params?: GenerationParams; // explicit values win over the style
 }
 
 export interface CaseStudyMetadata {
@@ -33,6 +39,56 @@ export interface GeneratedCaseStudy {
   key_learning_points: string[];
   suggested_analysis_framework?: string;
   metadata: CaseStudyMetadata;
+  generation_path?: GenerationPath;
+  length_check?: LengthCheck;
+}
+
+export type GenerationPath = 'structured' | 'text_parsing';
+
+export type LengthAdjustment = 'expand' | 'condense';
+
+export interface LengthCheck {
+  target_length: number;
+  word_count: number;
+  within_tolerance: boolean;
+  adjustment?: LengthAdjustment;
+}
+
+export type CaseStudySection =
+  | 'background'
+  | 'problem_statement'
+  | 'analysis_framework'
+  | 'sample_solution'
+  | 'content';
+
+export interface BatchItemResult {
+  index: number;
+  case_study?: GeneratedCaseStudy;
+  error?: string;
+}
+
+export interface BatchProgress {
+  index: number;
+  completed: number;
+  total: number;
+  succeeded: boolean;
+  error?: string;
+}
+
+export const BATCH_PROGRESS_EVENT = 'ai://case-study-batch/progress';
+
+export interface GenerationPreset {
+  id: number;
+  user_id: string;
+  name: string;
+  params: CaseStudyGenerationParams;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface UpdateGenerationPreset {
+  name?: string;
+  params?: CaseStudyGenerationParams;
 }
 
 export interface CaseStudyGenerationRequest {
@@ -15,6 +15,19 @@ export interface ConfigurationSchema {
   created_at: string;
   updated_at: string;
   created_by?: string;
+  schema_version?: number;
+  data_migrations?: DataMigration[];
+}
+
+export interface DataMigration {
+  from_version: number;
+  to_version: number;
+  transforms: FieldTransform[];
+}
+
+export interface FieldTransform {
+  type: 'RenameField' | 'AddDefault' | 'SplitField';
+  config: Record<string, any>;
 }
 
 // Type aliases for backward compatibility
@@ -57,6 +70,7 @@ export interface FieldType {
 export interface FieldOptions {
   static_options?: OptionItem[];
   dynamic_options?: DynamicOptionsConfig;
+  dynamic_source?: DynamicSource;
   allow_custom: boolean;
   custom_validation?: ValidationRule;
 }
@@ -78,6 +92,11 @@ export interface DynamicOptionsConfig {
   cache_config?: CacheConfig;
 }
 
+export interface DynamicSource {
+  name: string;
+  parameters?: Record<string, string>;
+}
+
 export interface CacheConfig {
   duration: number;
   per_user: boolean;
@@ -182,6 +201,7 @@ export interface StoredConfigurationSchema {
   created_by?: string;
   created_at: string;
   updated_at: string;
+  outdated_submissions?: number;
 }
 
 export type ConfigurationStatus = 'Draft' | 'Active' | 'Archived' | 'Deleted';
@@ -410,6 +430,16 @@ export interface ConfigurationImportResult {
   imported_ids: string[];
   skipped_templates: ImportSkippedTemplate[];
   errors: ImportError[];
+  conflicts?: ImportConflict[];
+}
+
+export type MergeStrategy = 'skip' | 'overwrite' | 'rename';
+
+export interface ImportConflict {
+  kind: 'template' | 'category' | 'framework';
+  id: string;
+  resolution: MergeStrategy;
+  new_id?: string;
 }
 
 export interface ImportSkippedTemplate {
@@ -7,11 +7,21 @@ export interface EncryptedData {
   version: string;   // Version for future compatibility
 }
 
+export interface Argon2Params {
+  memory_kib: number;
+  iterations: number;
+  parallelism: number;
+}
+
+// Non-secret metadata only: never the key, the salt contents or decrypted data
 export interface EncryptionConfig {
-  has_salt: boolean;
-  salt_path?: string;
   encryption_algorithm: string;
   key_derivation: string;
+  kdf_params: Argon2Params;
+  version: string;
+  initialized: boolean;
+  has_salt: boolean;
+  salt_path: string;
 }
 
 export interface EncryptionStats {
@@ -37,6 +47,7 @@ export interface EncryptionAPI {
   changeEncryptionPassword(oldPassword: string, newPassword: string): Promise<boolean>;
   validateEncryptionPassword(password: string): Promise<boolean>;
   exportEncryptionConfig(): Promise<EncryptionConfig>;
+  importEncryptionConfig(config: EncryptionConfig): Promise<boolean>;
   testEncryption(testData: string): Promise<boolean>;
   getEncryptionStats(): Promise<EncryptionStats>;
 }
@@ -89,6 +100,10 @@ export class EncryptionService implements EncryptionAPI {
     return this.invoke('export_encryption_config');
   }
 
+  async importEncryptionConfig(config: EncryptionConfig): Promise<boolean> {
+    return this.invoke('import_encryption_config', { config });
+  }
+
   async testEncryption(testData: string): Promise<boolean> {
     return this.invoke('test_encryption', { testData });
   }
@@ -99,6 +99,20 @@ export interface NewDomain {
   icon?: string | null;
 }
 
+export interface UpdateDomain {
+  name?: string | null;
+  description?: string | null;
+  color?: string | null;
+  icon?: string | null;
+}
+
+export type DomainDeleteMode = 'restrict' | 'cascade';
+
+export interface DomainUsage {
+  domain: Domain;
+  case_study_count: number;
+}
+
 // ===== CONFIGURATION TEMPLATE INTERFACES =====
 
 export interface ConfigurationTemplate {
@@ -186,6 +200,29 @@ export interface UpdateCaseStudy {
   status?: string | null;
 }
 
+export interface SemanticSearchHit {
+  case_study: CaseStudy;
+  score: number; // cosine similarity, higher is closer
+}
+
+export interface SimilarCaseStudy {
+  id: string;
+  title: string;
+  title_similarity: number;
+  content_similarity: number;
+  similarity: number; // weighted title and content score, 0-1
+}
+
+export interface CaseStudyPage {
+  items: CaseStudy[];
+  next_cursor?: string; // absent on the last page
+}
+
+export interface EmbeddingBackfillReport {
+  embedded: number;
+  provider: string;
+}
+
 // ===== ASSESSMENT QUESTION INTERFACES =====
 
 export interface AssessmentQuestion {
@@ -359,6 +396,15 @@ export interface NewCollectionCaseStudy {
   order_index?: number | null;
 }
 
+export interface CollectionMember {
+  collection_id: number;
+  case_study_id: string;
+  title: string;
+  status: string;
+  order_index: number;
+  added_at: string; // ISO date string
+}
+
 // ===== VIEW MODEL INTERFACES =====
 
 export interface CaseStudySummary {